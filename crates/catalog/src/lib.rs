@@ -2,4 +2,5 @@
 pub mod client;
 pub mod errors;
 pub mod mutator;
+pub mod sequences;
 pub mod session_catalog;
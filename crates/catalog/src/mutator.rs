@@ -1,40 +1,261 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use parking_lot::Mutex;
 use protogen::metastore::strategy::ResolveErrorStrategy;
-use protogen::metastore::types::catalog::CatalogState;
-use protogen::metastore::types::service::Mutation;
+use protogen::metastore::types::catalog::{CatalogEntry, CatalogState, SequenceEntry, TableEntry};
+use protogen::metastore::types::options::TableOptionsV0;
+use protogen::metastore::types::service::{Mutation, NextSequenceValues, SetSequenceValue};
 use tracing::debug;
 
 use super::client::MetastoreClientHandle;
 use crate::errors::{CatalogError, Result};
 
+/// Max number of attempts for committing mutations on the latest catalog when
+/// the commit conflicts with catalog changes from other sessions.
+const MAX_COMMIT_ATTEMPTS: usize = 16;
+
 /// Wrapper around a metastore client for mutating the catalog.
-#[derive(Clone)]
+///
+/// While a transaction is open, mutations are staged instead of being
+/// committed. Clones of the mutator share the same transaction.
+#[derive(Debug, Clone)]
 pub struct CatalogMutator {
     pub client: Option<MetastoreClientHandle>,
+    txn: Arc<Mutex<Option<CatalogTransaction>>>,
+}
+
+/// Catalog mutations staged by a transaction.
+#[derive(Debug)]
+pub struct CatalogTransaction {
+    /// Catalog state that staged mutations are applied to.
+    base: Arc<CatalogState>,
+    /// All mutations staged so far. Applied as a single batch on commit.
+    mutations: Vec<Mutation>,
+    /// Catalog state with all staged mutations applied.
+    state: Arc<CatalogState>,
+    /// Native tables created at any point during the transaction.
+    created_tables: HashMap<u32, TableEntry>,
+}
+
+impl CatalogTransaction {
+    fn new(base: Arc<CatalogState>) -> Self {
+        CatalogTransaction {
+            state: base.clone(),
+            base,
+            mutations: Vec::new(),
+            created_tables: HashMap::new(),
+        }
+    }
+
+    /// Native tables created during the transaction that still exist once
+    /// the transaction is committed.
+    ///
+    /// Storage for these tables should be deleted if the transaction doesn't
+    /// commit.
+    pub fn created_tables(&self) -> Vec<TableEntry> {
+        self.created_tables
+            .iter()
+            .filter(|(oid, _)| self.state.entries.contains_key(oid))
+            .map(|(_, table)| table.clone())
+            .collect()
+    }
+
+    /// Native tables that existed prior to the transaction that no longer
+    /// exist once the transaction is committed.
+    ///
+    /// Storage for these tables should be deleted after the transaction
+    /// commits.
+    pub fn dropped_tables(&self) -> Vec<TableEntry> {
+        native_tables(&self.base)
+            .filter(|(oid, _)| !self.state.entries.contains_key(oid))
+            .map(|(_, table)| table.clone())
+            .collect()
+    }
+
+    /// Stage mutations with the resulting state.
+    fn stage(
+        &mut self,
+        base: Arc<CatalogState>,
+        mutations: Vec<Mutation>,
+        state: Arc<CatalogState>,
+    ) -> Result<()> {
+        self.check_rebase(&base, &state)?;
+
+        for (oid, table) in native_tables(&state) {
+            if !base.entries.contains_key(oid) {
+                self.created_tables.insert(*oid, table.clone());
+            }
+        }
+
+        self.base = base;
+        self.mutations.extend(mutations);
+        self.state = state;
+        Ok(())
+    }
+
+    /// Check that staged mutations replayed on top of `base`, resulting in
+    /// `state`, line up with what was staged so far.
+    ///
+    /// If the mutations had to be replayed on a newer version of the catalog,
+    /// tables created earlier in the transaction may have been assigned
+    /// different oids, and their storage would no longer line up with the
+    /// catalog.
+    fn check_rebase(&self, base: &CatalogState, state: &CatalogState) -> Result<()> {
+        if base.version == self.base.version {
+            return Ok(());
+        }
+
+        let moved = self
+            .created_tables
+            .iter()
+            .filter(|(oid, _)| self.state.entries.contains_key(oid))
+            .any(|(oid, table)| match state.entries.get(oid) {
+                Some(CatalogEntry::Table(replayed)) => {
+                    replayed.meta.name != table.meta.name
+                        || replayed.meta.parent != table.meta.parent
+                }
+                _ => true,
+            });
+        if moved {
+            return Err(CatalogError::new(
+                "could not serialize access due to a concurrent catalog update",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Iterate over all native tables in the catalog state.
+fn native_tables(state: &CatalogState) -> impl Iterator<Item = (&u32, &TableEntry)> {
+    state.entries.iter().filter_map(|(oid, ent)| match ent {
+        CatalogEntry::Table(table) if matches!(table.options, TableOptionsV0::Internal(_)) => {
+            Some((oid, table))
+        }
+        _ => None,
+    })
 }
 
 impl CatalogMutator {
     pub fn empty() -> Self {
-        CatalogMutator { client: None }
+        CatalogMutator {
+            client: None,
+            txn: Arc::new(Mutex::new(None)),
+        }
     }
 
     pub fn is_empty(&self) -> bool {
         self.client.is_none()
     }
     pub fn new(client: Option<MetastoreClientHandle>) -> Self {
-        CatalogMutator { client }
+        CatalogMutator {
+            client,
+            txn: Arc::new(Mutex::new(None)),
+        }
     }
 
     pub fn get_metastore_client(&self) -> Option<&MetastoreClientHandle> {
         self.client.as_ref()
     }
 
+    /// Returns if there's an open transaction.
+    pub fn in_transaction(&self) -> bool {
+        self.txn.lock().is_some()
+    }
+
+    /// Returns if the native table with the given oid was created in the open
+    /// transaction.
+    pub fn created_in_transaction(&self, oid: u32) -> bool {
+        self.txn
+            .lock()
+            .as_ref()
+            .is_some_and(|txn| txn.created_tables.contains_key(&oid))
+    }
+
+    /// Get the catalog state with all mutations staged in the transaction
+    /// applied, if there's an open transaction.
+    pub fn transaction_state(&self) -> Option<Arc<CatalogState>> {
+        self.txn.lock().as_ref().map(|txn| txn.state.clone())
+    }
+
+    /// Begin a transaction.
+    ///
+    /// Until the transaction is committed, mutations will be staged and not
+    /// committed to metastore.
+    ///
+    /// Does nothing if the metastore client isn't configured.
+    pub async fn begin_transaction(&self) -> Result<()> {
+        let client = match &self.client {
+            Some(client) => client,
+            None => return Ok(()),
+        };
+
+        let base = client.get_cached_state().await?;
+        let mut txn = self.txn.lock();
+        if txn.is_some() {
+            return Err(CatalogError::new("catalog transaction already in progress"));
+        }
+        *txn = Some(CatalogTransaction::new(base));
+        Ok(())
+    }
+
+    /// Take the open transaction, if any, ending it.
+    ///
+    /// The transaction should then be committed with `commit_transaction` or
+    /// dropped to roll it back.
+    pub fn take_transaction(&self) -> Option<CatalogTransaction> {
+        self.txn.lock().take()
+    }
+
+    /// Check that a transaction's staged mutations can be applied to the
+    /// latest catalog, without committing them.
+    pub async fn prepare_transaction(&self, txn: &CatalogTransaction) -> Result<()> {
+        if txn.mutations.is_empty() {
+            return Ok(());
+        }
+
+        let client = match &self.client {
+            Some(client) => client,
+            None => return Err(CatalogError::new("metastore client not configured")),
+        };
+
+        let (base, state) =
+            Self::try_mutate_with_retry(client, txn.base.clone(), txn.mutations.clone()).await?;
+        txn.check_rebase(&base, &state)
+    }
+
+    /// Commit all staged mutations from a transaction as a single batch.
+    pub async fn commit_transaction(
+        &self,
+        txn: &CatalogTransaction,
+    ) -> Result<Option<Arc<CatalogState>>> {
+        if txn.mutations.is_empty() {
+            return Ok(None);
+        }
+
+        let client = match &self.client {
+            Some(client) => client,
+            None => return Err(CatalogError::new("metastore client not configured")),
+        };
+
+        let (base, state) =
+            Self::try_mutate_with_retry(client, txn.base.clone(), txn.mutations.clone()).await?;
+        txn.check_rebase(&base, &state)?;
+
+        let state = client
+            .commit_state(base.version, state.as_ref().clone())
+            .await?;
+        Ok(Some(state))
+    }
+
     /// Commit the catalog state.
     /// This persists the state to the metastore.
     /// The `current_catalog_version` is the version of the catalog prior to the state being committed.
     /// the 'state.version' should always be greater than 'current_catalog_version'.
     /// If not, the commit will not succeed.
+    ///
+    /// If there's an open transaction, the state is already staged and this
+    /// does nothing.
     pub async fn commit_state(
         &self,
         current_catalog_version: u64,
@@ -45,6 +266,10 @@ impl CatalogMutator {
             None => return Err(CatalogError::new("metastore client not configured")),
         };
 
+        if self.in_transaction() {
+            return Ok(Arc::new(state));
+        }
+
         client
             .commit_state(current_catalog_version, state.clone())
             .await
@@ -58,6 +283,9 @@ impl CatalogMutator {
     /// Errors if the metastore client isn't configured.
     ///
     /// This will retry mutations if we were working with an out of date catalog.
+    ///
+    /// If there's an open transaction, the mutations are staged and the
+    /// returned state includes all previously staged mutations.
     pub async fn mutate(
         &self,
         catalog_version: u64,
@@ -68,33 +296,174 @@ impl CatalogMutator {
             None => return Err(CatalogError::new("metastore client not configured")),
         };
 
-        // Note that when we have transactions, these shouldn't be sent until
-        // commit.
         let mutations: Vec<_> = mutations.into_iter().collect();
-        let state = match client.try_mutate(catalog_version, mutations.clone()).await {
-            Ok(state) => state,
+
+        let staged = self
+            .txn
+            .lock()
+            .as_ref()
+            .map(|txn| (txn.base.clone(), txn.mutations.clone()));
+
+        match staged {
+            Some((base, mut all)) => {
+                // Staged mutations are always replayed on top of the base
+                // version, which deterministically produces the same oids for
+                // objects created earlier in the transaction.
+                all.extend(mutations.iter().cloned());
+                let (base, state) = Self::try_mutate_with_retry(client, base, all).await?;
+
+                let mut txn = self.txn.lock();
+                let txn = txn
+                    .as_mut()
+                    .ok_or_else(|| CatalogError::new("catalog transaction ended during mutate"))?;
+                txn.stage(base, mutations, state.clone())?;
+
+                Ok(state)
+            }
+            None => {
+                let state = match client.try_mutate(catalog_version, mutations.clone()).await {
+                    Ok(state) => state,
+                    Err(CatalogError {
+                        msg,
+                        strategy: Some(ResolveErrorStrategy::FetchCatalogAndRetry),
+                    }) => {
+                        debug!(error_message = msg, "retrying mutations");
+                        Self::retry_on_latest(client, mutations).await?.1
+                    }
+                    Err(e) => return Err(e),
+                };
+                Ok(state)
+            }
+        }
+    }
+
+    /// Try to apply mutations to the given base state, retrying against the
+    /// latest version of the catalog if necessary.
+    ///
+    /// Returns the catalog state the mutations were applied to along with the
+    /// mutated state.
+    async fn try_mutate_with_retry(
+        client: &MetastoreClientHandle,
+        base: Arc<CatalogState>,
+        mutations: Vec<Mutation>,
+    ) -> Result<(Arc<CatalogState>, Arc<CatalogState>)> {
+        match client.try_mutate(base.version, mutations.clone()).await {
+            Ok(state) => Ok((base, state)),
             Err(CatalogError {
                 msg,
                 strategy: Some(ResolveErrorStrategy::FetchCatalogAndRetry),
             }) => {
-                // Go ahead and refetch the catalog and retry the mutation.
-                //
-                // Note that this relies on metastore _always_ being stricter
-                // when validating mutations. What this means is that retrying
-                // here should be semantically equivalent to manually refreshing
-                // the catalog and rerunning and replanning the query.
                 debug!(error_message = msg, "retrying mutations");
+                Self::retry_on_latest(client, mutations).await
+            }
+            Err(e) => Err(e),
+        }
+    }
 
-                client.refresh_cached_state().await?;
-                let state = client.get_cached_state().await?;
-                let version = state.version;
+    /// Refetch the catalog and apply mutations to the latest version.
+    ///
+    /// Note that this relies on metastore _always_ being stricter when
+    /// validating mutations. What this means is that retrying here should be
+    /// semantically equivalent to manually refreshing the catalog and
+    /// rerunning and replanning the query.
+    async fn retry_on_latest(
+        client: &MetastoreClientHandle,
+        mutations: Vec<Mutation>,
+    ) -> Result<(Arc<CatalogState>, Arc<CatalogState>)> {
+        client.refresh_cached_state().await?;
+        let base = client.get_cached_state().await?;
+        let state = client.try_mutate(base.version, mutations).await?;
+        Ok((base, state))
+    }
 
-                client.try_mutate(version, mutations).await?
-            }
-            Err(e) => return Err(e),
+    /// Hand out the next `count` values of a sequence.
+    ///
+    /// Sequences aren't transactional. The update is committed immediately,
+    /// even if there's an open transaction, so values are never handed out
+    /// twice. Updates conflicting with commits from other sessions are retried
+    /// against the latest catalog.
+    pub async fn next_sequence_values(
+        &self,
+        schema: &str,
+        name: &str,
+        count: usize,
+    ) -> Result<Vec<i64>> {
+        let mutation = Mutation::NextSequenceValues(NextSequenceValues {
+            schema: schema.to_string(),
+            name: name.to_string(),
+            count: count as u64,
+        });
+        let base = self.commit_sequence_update(mutation).await?;
+
+        // Metastore applied the update to this exact version of the catalog,
+        // so it handed out the same values.
+        find_sequence(&base, schema, name)
+            .and_then(|seq| seq.next_values(count))
+            .ok_or_else(|| CatalogError::new(format!("missing sequence: {schema}.{name}")))
+    }
+
+    /// Set the current value of a sequence.
+    ///
+    /// Like `next_sequence_values`, this is committed immediately.
+    pub async fn set_sequence_value(
+        &self,
+        schema: &str,
+        name: &str,
+        value: i64,
+        is_called: bool,
+    ) -> Result<()> {
+        let mutation = Mutation::SetSequenceValue(SetSequenceValue {
+            schema: schema.to_string(),
+            name: name.to_string(),
+            value,
+            is_called,
+        });
+        self.commit_sequence_update(mutation).await?;
+        Ok(())
+    }
+
+    /// Apply and commit a single sequence mutation, bypassing any open
+    /// transaction.
+    ///
+    /// Returns the catalog state the mutation was applied to.
+    async fn commit_sequence_update(&self, mutation: Mutation) -> Result<Arc<CatalogState>> {
+        self.commit_on_latest(vec![mutation]).await
+    }
+
+    /// Apply mutations to the latest version of the catalog and commit them,
+    /// bypassing any open transaction.
+    ///
+    /// Commits conflicting with changes from other sessions are retried on
+    /// the newer catalog, so this only fails if the mutations themselves
+    /// can't be applied. Used for catalog changes that have to follow changes
+    /// already made elsewhere.
+    ///
+    /// Returns the catalog state the mutations were applied to.
+    pub async fn commit_on_latest(&self, mutations: Vec<Mutation>) -> Result<Arc<CatalogState>> {
+        let client = match &self.client {
+            Some(client) => client,
+            None => return Err(CatalogError::new("metastore client not configured")),
         };
 
-        Ok(state)
+        let mut attempt = 1;
+        loop {
+            let base = client.get_cached_state().await?;
+            match client
+                .try_mutate_and_commit(base.version, mutations.clone())
+                .await
+            {
+                Ok(_) => return Ok(base),
+                Err(CatalogError {
+                    msg,
+                    strategy: Some(ResolveErrorStrategy::FetchCatalogAndRetry),
+                }) if attempt < MAX_COMMIT_ATTEMPTS => {
+                    debug!(error_message = msg, %attempt, "retrying commit on latest catalog");
+                    client.refresh_cached_state().await?;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
     }
 
     /// Mutate the catalog if possible and immediately commit the changes.
@@ -114,10 +483,26 @@ impl CatalogMutator {
     }
 }
 
+/// Find a sequence by schema and name in the catalog state.
+fn find_sequence<'a>(
+    state: &'a CatalogState,
+    schema: &str,
+    name: &str,
+) -> Option<&'a SequenceEntry> {
+    let schema_id = state.entries.iter().find_map(|(oid, ent)| match ent {
+        CatalogEntry::Schema(ent) if ent.meta.name == schema => Some(*oid),
+        _ => None,
+    })?;
+    state.entries.values().find_map(|ent| match ent {
+        CatalogEntry::Sequence(seq) if seq.meta.parent == schema_id && seq.meta.name == name => {
+            Some(seq)
+        }
+        _ => None,
+    })
+}
+
 impl From<MetastoreClientHandle> for CatalogMutator {
     fn from(value: MetastoreClientHandle) -> Self {
-        CatalogMutator {
-            client: Some(value),
-        }
+        CatalogMutator::new(Some(value))
     }
 }
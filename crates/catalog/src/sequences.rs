@@ -0,0 +1,124 @@
+//! Session state for sequences.
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use protogen::metastore::types::catalog::SequenceEntry;
+
+use crate::errors::{CatalogError, Result};
+use crate::mutator::CatalogMutator;
+
+/// Hands out sequence values for a session, and tracks the values last handed
+/// out for `currval`.
+///
+/// Values are fetched from the catalog `cache` values at a time, the values
+/// not used immediately are handed out by later calls in the same session
+/// without going through the catalog. Like postgres, values cached by a
+/// session are lost when the session ends, and sessions may hand out values
+/// out of order relative to each other.
+///
+/// Cheaply cloneable, clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct SessionSequences {
+    mutator: Option<CatalogMutator>,
+    /// Sequence oid to the value last returned in this session.
+    current: Arc<Mutex<HashMap<u32, i64>>>,
+    /// Sequence oid to values fetched but not yet handed out.
+    cached: Arc<Mutex<HashMap<u32, VecDeque<i64>>>>,
+}
+
+impl SessionSequences {
+    pub fn new(mutator: CatalogMutator) -> Self {
+        SessionSequences {
+            mutator: Some(mutator),
+            current: Arc::new(Mutex::new(HashMap::new())),
+            cached: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn mutator(&self) -> Result<&CatalogMutator> {
+        self.mutator
+            .as_ref()
+            .filter(|mutator| !mutator.is_empty())
+            .ok_or_else(|| CatalogError::new("sequences are not supported in this session"))
+    }
+
+    /// Get the next `count` values of the sequence.
+    pub async fn next_values(
+        &self,
+        schema: &str,
+        sequence: &SequenceEntry,
+        count: usize,
+    ) -> Result<Vec<i64>> {
+        let mut values = self.take_cached(sequence, count);
+
+        let needed = count - values.len();
+        if needed > 0 {
+            let mutator = self.mutator()?;
+            let name = &sequence.meta.name;
+            let fetch = needed.max(sequence.cache.max(1) as usize);
+            let mut fetched = match mutator.next_sequence_values(schema, name, fetch).await {
+                Ok(fetched) => fetched,
+                // The sequence may not have enough values left to fill the
+                // cache, only ask for what's needed.
+                Err(_) if fetch > needed => {
+                    mutator.next_sequence_values(schema, name, needed).await?
+                }
+                Err(e) => return Err(e),
+            };
+            let rest = fetched.split_off(needed);
+            values.extend(fetched);
+            if !rest.is_empty() {
+                self.cached
+                    .lock()
+                    .entry(sequence.meta.id)
+                    .or_default()
+                    .extend(rest);
+            }
+        }
+
+        if let Some(last) = values.last() {
+            self.current.lock().insert(sequence.meta.id, *last);
+        }
+        Ok(values)
+    }
+
+    /// Set the current value of the sequence.
+    ///
+    /// If `is_called` is false, the next call to `next_values` will return
+    /// `value`.
+    pub async fn set_value(
+        &self,
+        schema: &str,
+        sequence: &SequenceEntry,
+        value: i64,
+        is_called: bool,
+    ) -> Result<()> {
+        self.mutator()?
+            .set_sequence_value(schema, &sequence.meta.name, value, is_called)
+            .await?;
+        // Values cached before the update would continue the old series.
+        self.cached.lock().remove(&sequence.meta.id);
+        if is_called {
+            self.current.lock().insert(sequence.meta.id, value);
+        }
+        Ok(())
+    }
+
+    /// Take up to `count` values cached by the session for the sequence.
+    fn take_cached(&self, sequence: &SequenceEntry, count: usize) -> Vec<i64> {
+        let mut cached = self.cached.lock();
+        match cached.get_mut(&sequence.meta.id) {
+            Some(values) => {
+                let n = count.min(values.len());
+                values.drain(..n).collect()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Get the value last handed out for the sequence in this session.
+    pub fn current_value(&self, sequence: &SequenceEntry) -> Option<i64> {
+        self.current.lock().get(&sequence.meta.id).copied()
+    }
+}
@@ -13,7 +13,10 @@ use protogen::metastore::types::catalog::{
     EntryType,
     FunctionEntry,
     FunctionType,
+    Privilege,
+    RoleEntry,
     SchemaEntry,
+    SequenceEntry,
     SourceAccessMode,
     TableEntry,
     TunnelEntry,
@@ -23,6 +26,7 @@ use tracing::debug;
 
 use super::client::MetastoreClientHandle;
 use crate::errors::Result;
+use crate::sequences::SessionSequences;
 
 /// Configuration for letting the catalog know how to resolve certain items.
 ///
@@ -53,6 +57,8 @@ pub struct SessionCatalog {
     tunnel_names: HashMap<String, u32>,
     /// Map credentials names to their ids.
     credentials_names: HashMap<String, u32>,
+    /// Map role names to their ids.
+    role_names: HashMap<String, u32>,
     /// Map schema names to their ids.
     schema_names: HashMap<String, u32>,
     /// Map schema IDs to objects in the schema.
@@ -61,6 +67,11 @@ pub struct SessionCatalog {
     resolve_conf: ResolveConfig,
     /// Catalog for holding temporary session objects.
     temp: TempCatalog,
+    /// The user this session belongs to. Used to determine the role (if any)
+    /// restricting access to catalog objects.
+    session_user: Option<String>,
+    /// Sequence values handed out to this session.
+    sequences: SessionSequences,
 }
 
 impl SessionCatalog {
@@ -72,10 +83,13 @@ impl SessionCatalog {
             database_names: HashMap::new(),
             tunnel_names: HashMap::new(),
             credentials_names: HashMap::new(),
+            role_names: HashMap::new(),
             schema_names: HashMap::new(),
             schema_objects: HashMap::new(),
             resolve_conf,
             temp: TempCatalog::new(resolve_conf),
+            session_user: None,
+            sequences: SessionSequences::default(),
         };
         catalog.rebuild_name_maps();
         catalog
@@ -95,6 +109,30 @@ impl SessionCatalog {
         self.alias.as_deref()
     }
 
+    /// Set the user for this session.
+    ///
+    /// If a role exists with the same name as the user, access to catalog
+    /// objects will be restricted to the privileges granted to that role.
+    pub fn with_session_user(mut self, user: impl Into<String>) -> SessionCatalog {
+        let user = user.into();
+        self.session_user = if user.is_empty() { None } else { Some(user) };
+        self
+    }
+
+    pub fn session_user(&self) -> Option<&str> {
+        self.session_user.as_deref()
+    }
+
+    /// Set the sequence state for this session.
+    pub fn with_sequences(mut self, sequences: SessionSequences) -> SessionCatalog {
+        self.sequences = sequences;
+        self
+    }
+
+    pub fn sequences(&self) -> &SessionSequences {
+        &self.sequences
+    }
+
     /// Get the version of this catalog state.
     pub fn version(&self) -> u64 {
         self.state.version
@@ -127,6 +165,22 @@ impl SessionCatalog {
         }
     }
 
+    pub fn resolve_sequence(
+        &self,
+        _database: &str,
+        schema: &str,
+        name: &str,
+    ) -> Option<&SequenceEntry> {
+        let schema_id = self.schema_names.get(schema)?;
+        let obj = self.schema_objects.get(schema_id)?;
+        let obj_id = obj.objects.get(name)?;
+
+        match self.state.entries.get(obj_id)? {
+            CatalogEntry::Sequence(sequence) => Some(sequence),
+            _ => None,
+        }
+    }
+
     /// Resolve a database by name.
     pub fn resolve_database(&self, name: &str) -> Option<&DatabaseEntry> {
         // This function will panic if certain invariants aren't held:
@@ -177,6 +231,8 @@ impl SessionCatalog {
     }
 
     /// Resolve a credentials by name.
+    ///
+    /// Credentials the current role doesn't have USAGE on are never returned.
     pub fn resolve_credentials(&self, name: &str) -> Option<&CredentialsEntry> {
         // Similar invariants as `resolve_database`. If we find an entry
         // in the credentials map, it must exist in the state and must be
@@ -189,6 +245,10 @@ impl SessionCatalog {
             .get(id)
             .expect("credentials name points to invalid id");
 
+        if !self.has_privilege(ent, Privilege::Usage) {
+            return None;
+        }
+
         match ent {
             CatalogEntry::Credentials(ent) => Some(ent),
             _ => panic!(
@@ -200,6 +260,80 @@ impl SessionCatalog {
         }
     }
 
+    /// Resolve a role by name.
+    pub fn resolve_role(&self, name: &str) -> Option<&RoleEntry> {
+        // Similar invariants as `resolve_database`.
+
+        let id = self.role_names.get(name)?;
+        let ent = self
+            .state
+            .entries
+            .get(id)
+            .expect("role name points to invalid id");
+
+        match ent {
+            CatalogEntry::Role(ent) => Some(ent),
+            _ => panic!(
+                "entry type not role; name: {}, id: {}, type: {:?}",
+                name,
+                id,
+                ent.entry_type(),
+            ),
+        }
+    }
+
+    /// Get the role restricting the session user, if any.
+    ///
+    /// Users without a matching role are unrestricted.
+    pub fn current_role(&self) -> Option<&RoleEntry> {
+        self.resolve_role(self.session_user.as_deref()?)
+    }
+
+    /// Check if the session user is allowed `privilege` on the entry.
+    pub fn has_privilege(&self, ent: &CatalogEntry, privilege: Privilege) -> bool {
+        match self.current_role() {
+            Some(role) => self.role_has_privilege(role, ent, privilege),
+            None => true,
+        }
+    }
+
+    /// Check if the role was granted `privilege` on the entry.
+    ///
+    /// Builtin and temporary objects are always accessible. EXECUTE on
+    /// builtin functions and INSERT on the builtin database control access
+    /// to data outside of the catalog, and must be granted explicitly.
+    /// Tables, views and sequences additionally require USAGE on their
+    /// schema.
+    pub fn role_has_privilege(
+        &self,
+        role: &RoleEntry,
+        ent: &CatalogEntry,
+        privilege: Privilege,
+    ) -> bool {
+        let meta = ent.get_meta();
+        let needs_grant = match ent {
+            CatalogEntry::Function(_) => privilege == Privilege::Execute,
+            CatalogEntry::Database(_) => privilege == Privilege::Insert,
+            _ => false,
+        };
+        if (meta.builtin && !needs_grant) || meta.is_temp {
+            return true;
+        }
+        if !role.has_grant(meta.id, privilege) {
+            return false;
+        }
+
+        match ent {
+            CatalogEntry::Table(_) | CatalogEntry::View(_) | CatalogEntry::Sequence(_) => {
+                match self.state.entries.get(&meta.parent) {
+                    Some(schema) => self.role_has_privilege(role, schema, Privilege::Usage),
+                    None => true,
+                }
+            }
+            _ => true,
+        }
+    }
+
     /// Resolve a schema by name.
     pub fn resolve_schema(&self, name: &str) -> Option<&SchemaEntry> {
         // Similar invariants as `resolve_database`. If we find an entry in the
@@ -318,8 +452,8 @@ impl SessionCatalog {
 
         // Swap out cached catalog if a newer one was fetched.
         //
-        // Note that when we have transactions, we should move this to only
-        // swapping states between transactions.
+        // Sessions inside a transaction block swap to the transaction's staged
+        // state instead, see `LocalSessionContext::maybe_refresh_state`.
         if client.version_hint() != self.version() {
             let new_state = client.get_cached_state().await?;
             debug!(old_version = %self.version(), new_version = %new_state.version, "swapping catalog state for session");
@@ -343,13 +477,15 @@ impl SessionCatalog {
     fn as_namespaced_entry<'a>(&'a self, ent: &'a CatalogEntry) -> NamespacedCatalogEntry<'a> {
         let parent_entry = match ent {
             // Explicitly mention all the options to accidentally not leave anything here.
-            CatalogEntry::Database(_) | CatalogEntry::Tunnel(_) | CatalogEntry::Credentials(_) => {
-                None
-            }
+            CatalogEntry::Database(_)
+            | CatalogEntry::Tunnel(_)
+            | CatalogEntry::Credentials(_)
+            | CatalogEntry::Role(_) => None,
             CatalogEntry::Schema(_)
             | CatalogEntry::Table(_)
             | CatalogEntry::View(_)
-            | CatalogEntry::Function(_) => {
+            | CatalogEntry::Function(_)
+            | CatalogEntry::Sequence(_) => {
                 Some(self.state.entries.get(&ent.get_meta().parent).unwrap()) // Bug if it doesn't exist.
             }
         };
@@ -365,6 +501,7 @@ impl SessionCatalog {
         self.database_names.clear();
         self.tunnel_names.clear();
         self.credentials_names.clear();
+        self.role_names.clear();
         self.schema_names.clear();
         self.schema_objects.clear();
 
@@ -381,10 +518,16 @@ impl SessionCatalog {
                 CatalogEntry::Credentials(_) => {
                     self.credentials_names.insert(name, *id);
                 }
+                CatalogEntry::Role(_) => {
+                    self.role_names.insert(name, *id);
+                }
                 CatalogEntry::Schema(_) => {
                     self.schema_names.insert(name, *id);
                 }
-                CatalogEntry::Table(_) | CatalogEntry::View(_) | CatalogEntry::Function(_) => {
+                CatalogEntry::Table(_)
+                | CatalogEntry::View(_)
+                | CatalogEntry::Function(_)
+                | CatalogEntry::Sequence(_) => {
                     let schema_id = ent.get_meta().parent;
                     let ent = self.schema_objects.entry(schema_id).or_default();
                     ent.objects.insert(name, *id);
@@ -460,6 +603,9 @@ impl TempCatalog {
                         name,
                         nullable: f.is_nullable(),
                         arrow_type: ty.clone(),
+                        default_expr: None,
+                        generated_always: false,
+                        description: None,
                     }
                 })
                 .collect();
@@ -473,9 +619,15 @@ impl TempCatalog {
                     builtin: false,
                     external: false,
                     is_temp: true,
+                    description: None,
                 },
                 options: TableOptionsInternal {
                     columns: columns.clone(),
+                    check_constraints: Vec::new(),
+                    unique_constraints: Vec::new(),
+                    auto_compaction: None,
+                    partition_columns: Vec::new(),
+                    materialized_view: None,
                 }
                 .into(),
                 tunnel_id: None,
@@ -518,9 +670,15 @@ impl TempCatalog {
                     builtin: false,
                     external: false,
                     is_temp: true,
+                    description: None,
                 },
                 options: TableOptionsInternal {
                     columns: Vec::new(),
+                    check_constraints: Vec::new(),
+                    unique_constraints: Vec::new(),
+                    auto_compaction: None,
+                    partition_columns: Vec::new(),
+                    materialized_view: None,
                 }
                 .into(),
                 tunnel_id: None,
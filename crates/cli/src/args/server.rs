@@ -31,6 +31,14 @@ pub struct ServerArgs {
     #[arg(short, long, value_parser)]
     pub password: Option<String>,
 
+    /// Path to a file listing the users allowed to connect.
+    ///
+    /// Each line is `<user>:<password>`, where the password is either plaintext
+    /// or a SCRAM-SHA-256 or MD5 hash as stored by Postgres. Clients
+    /// authenticate using SCRAM-SHA-256, or MD5 for users with an MD5 hash.
+    #[arg(long, value_parser, conflicts_with = "password")]
+    pub users_file: Option<PathBuf>,
+
     /// Optional file path for persisting data.
     ///
     /// Catalog data and user data will be stored in this directory.
@@ -5,7 +5,12 @@ use std::sync::Arc;
 use anyhow::{anyhow, Result};
 use atty::Stream;
 use clap::Subcommand;
-use pgsrv::auth::{LocalAuthenticator, PasswordlessAuthenticator, SingleUserAuthenticator};
+use pgsrv::auth::{
+    LocalAuthenticator,
+    PasswordlessAuthenticator,
+    SingleUserAuthenticator,
+    UsersFileAuthenticator,
+};
 use slt::discovery::SltDiscovery;
 use slt::hooks::{
     AllTestsHook,
@@ -147,12 +152,13 @@ impl RunCommand for ServerArgs {
             ));
         }
 
-        let auth: Box<dyn LocalAuthenticator> = match self.password {
-            Some(password) => Box::new(SingleUserAuthenticator {
+        let auth: Box<dyn LocalAuthenticator> = match (self.users_file, self.password) {
+            (Some(path), _) => Box::new(UsersFileAuthenticator::from_file(path)?),
+            (None, Some(password)) => Box::new(SingleUserAuthenticator {
                 user: self.user,
                 password,
             }),
-            None => Box::new(PasswordlessAuthenticator {
+            (None, None) => Box::new(PasswordlessAuthenticator {
                 drop_auth_messages: self.ignore_pg_auth,
             }),
         };
@@ -228,7 +228,7 @@ impl LocalSession {
                 vec![Format::Text; num_fields],
             )?;
 
-            let stream = self.sess.execute_portal(&UNNAMED, 0).await?;
+            let stream = self.sess.execute_portal(&UNNAMED).await?;
 
             match stream {
                 ExecutionResult::Query { stream, .. } => {
@@ -241,10 +241,22 @@ impl LocalSession {
                     )
                     .await?
                 }
+                ExecutionResult::CopyIn { sink, .. } => {
+                    sink.abort().await;
+                    return Err(anyhow!(
+                        "COPY FROM STDIN is only supported over the postgres protocol"
+                    ));
+                }
+                ExecutionResult::CopyOut { .. } => {
+                    return Err(anyhow!(
+                        "COPY TO STDOUT is only supported over the postgres protocol"
+                    ));
+                }
                 res @ (ExecutionResult::CopySuccess
                 | ExecutionResult::DeleteSuccess { .. }
                 | ExecutionResult::InsertSuccess { .. }
-                | ExecutionResult::UpdateSuccess { .. }) => {
+                | ExecutionResult::UpdateSuccess { .. }
+                | ExecutionResult::MergeSuccess { .. }) => {
                     println!("{}", res);
                     print_time_elapsed(now);
                 }
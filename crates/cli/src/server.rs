@@ -489,7 +489,7 @@ fn spawn_shutdown_handler(
 mod tests {
     use std::time::Duration;
 
-    use pgsrv::auth::SingleUserAuthenticator;
+    use pgsrv::auth::{SingleUserAuthenticator, UsersFileAuthenticator};
     use tokio_postgres::{Config as ClientConfig, NoTls};
 
     use super::*;
@@ -546,4 +546,62 @@ mod tests {
             .unwrap() // Timeout error
             .unwrap(); // Query error
     }
+
+    #[tokio::test]
+    async fn users_file_authentication() {
+        let pg_listener = TcpListener::bind("localhost:0").await.unwrap();
+        let pg_addr = pg_listener.local_addr().unwrap();
+
+        // "alice" authenticates with SCRAM-SHA-256, "bob" with MD5 (hash of
+        // "hunter2bob").
+        let auth = UsersFileAuthenticator::parse(
+            "alice:hunter2\nbob:md5a2cc14bcc08bcb211f578153967abd6d\n",
+        )
+        .unwrap();
+
+        let server = ComputeServer::builder()
+            .with_authenticator(auth)
+            .with_pg_listener(pg_listener)
+            .connect()
+            .await
+            .unwrap();
+
+        tokio::spawn(server.serve());
+
+        let connect = |user: &'static str, password: &'static str| async move {
+            let (client, conn) = tokio::time::timeout(
+                Duration::from_secs(5),
+                ClientConfig::new()
+                    .user(user)
+                    .password(password)
+                    .dbname("glaredb")
+                    .host("localhost")
+                    .port(pg_addr.port())
+                    .connect(NoTls),
+            )
+            .await
+            .unwrap()?; // Timeout error
+
+            tokio::spawn(conn);
+            client.simple_query("select 1").await
+        };
+
+        connect("alice", "hunter2").await.unwrap();
+        connect("bob", "hunter2").await.unwrap();
+
+        // Failed attempts are reported as invalid passwords, including for
+        // users that don't exist.
+        for (user, password) in [
+            ("alice", "hunter3"),
+            ("bob", "hunter3"),
+            ("carol", "hunter2"),
+        ] {
+            let err = connect(user, password).await.unwrap_err();
+            assert_eq!(
+                Some(&tokio_postgres::error::SqlState::INVALID_PASSWORD),
+                err.code(),
+                "{user}: {err}"
+            );
+        }
+    }
 }
@@ -656,9 +656,11 @@ impl<'a, S: AsyncContextProvider> SqlQueryPlanner<'a, S> {
         planner_context: &mut PlannerContext,
         case_insensitive: bool,
     ) -> Result<Expr> {
-        let pattern = self
+        let mut pattern = self
             .sql_expr_to_logical_expr(pattern, schema, planner_context)
             .await?;
+        // patterns are always strings
+        rewrite_placeholder_type(&mut pattern, &DataType::Utf8);
         let pattern_type = pattern.get_type(schema)?;
         if pattern_type != DataType::Utf8 && pattern_type != DataType::Null {
             return Err(DataFusionError::Plan(
@@ -686,9 +688,11 @@ impl<'a, S: AsyncContextProvider> SqlQueryPlanner<'a, S> {
         schema: &DFSchema,
         planner_context: &mut PlannerContext,
     ) -> Result<Expr> {
-        let pattern = self
+        let mut pattern = self
             .sql_expr_to_logical_expr(pattern, schema, planner_context)
             .await?;
+        // patterns are always strings
+        rewrite_placeholder_type(&mut pattern, &DataType::Utf8);
         let pattern_type = pattern.get_type(schema)?;
         if pattern_type != DataType::Utf8 && pattern_type != DataType::Null {
             return Err(DataFusionError::Plan(
@@ -857,15 +861,78 @@ fn rewrite_placeholder(expr: &mut Expr, other: &Expr, schema: &DFSchema) -> Resu
     Ok(())
 }
 
+// like `rewrite_placeholder`, but leaves the placeholder untyped if the type of
+// other can't be determined (e.g. it's another placeholder)
+fn try_rewrite_placeholder(expr: &mut Expr, other: &Expr, schema: &DFSchema) {
+    if let Expr::Placeholder(Placeholder {
+        id: _,
+        data_type: data_type @ None,
+    }) = expr
+    {
+        if let Ok(dt) = other.get_type(schema) {
+            *data_type = Some(dt);
+        }
+    }
+}
+
+// modifies expr if it is an untyped placeholder, giving it the provided type
+fn rewrite_placeholder_type(expr: &mut Expr, typ: &DataType) {
+    if let Expr::Placeholder(Placeholder {
+        id: _,
+        data_type: data_type @ None,
+    }) = expr
+    {
+        *data_type = Some(typ.clone());
+    }
+}
+
 /// Find all [`Expr::Placeholder`] tokens in a logical plan, and try
 /// to infer their [`DataType`] from the context of their use.
 fn infer_placeholder_types(expr: Expr, schema: &DFSchema) -> Result<Expr> {
     expr.transform(&|mut expr| {
-        // Default to assuming the arguments are the same type
-        if let Expr::BinaryExpr(BinaryExpr { left, op: _, right }) = &mut expr {
-            rewrite_placeholder(left.as_mut(), right.as_ref(), schema)?;
-            rewrite_placeholder(right.as_mut(), left.as_ref(), schema)?;
-        };
+        match &mut expr {
+            // Default to assuming the arguments are the same type
+            Expr::BinaryExpr(BinaryExpr { left, op: _, right }) => {
+                rewrite_placeholder(left.as_mut(), right.as_ref(), schema)?;
+                rewrite_placeholder(right.as_mut(), left.as_ref(), schema)?;
+            }
+            // `$1::INT`
+            Expr::Cast(Cast { expr, data_type }) | Expr::TryCast(TryCast { expr, data_type }) => {
+                rewrite_placeholder_type(expr.as_mut(), data_type);
+            }
+            // `$1 IS TRUE`, `NOT $1`
+            Expr::IsTrue(expr)
+            | Expr::IsFalse(expr)
+            | Expr::IsNotTrue(expr)
+            | Expr::IsNotFalse(expr)
+            | Expr::Not(expr) => {
+                rewrite_placeholder_type(expr.as_mut(), &DataType::Boolean);
+            }
+            // `a BETWEEN $1 AND $2`
+            Expr::Between(Between {
+                expr, low, high, ..
+            }) => {
+                try_rewrite_placeholder(low.as_mut(), expr.as_ref(), schema);
+                try_rewrite_placeholder(high.as_mut(), expr.as_ref(), schema);
+                try_rewrite_placeholder(expr.as_mut(), low.as_ref(), schema);
+            }
+            // `a IN ($1, $2)`
+            Expr::InList(InList { expr, list, .. }) => {
+                for item in list.iter_mut() {
+                    try_rewrite_placeholder(item, expr.as_ref(), schema);
+                }
+                if let Some(first) = list.first() {
+                    try_rewrite_placeholder(expr.as_mut(), first, schema);
+                }
+            }
+            // `a LIKE $1`
+            Expr::Like(Like { expr, pattern, .. })
+            | Expr::SimilarTo(Like { expr, pattern, .. }) => {
+                try_rewrite_placeholder(pattern.as_mut(), expr.as_ref(), schema);
+                try_rewrite_placeholder(expr.as_mut(), pattern.as_ref(), schema);
+            }
+            _ => (),
+        }
         Ok(Transformed::Yes(expr))
     })
 }
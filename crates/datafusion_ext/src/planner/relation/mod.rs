@@ -32,6 +32,13 @@ use crate::planner::{AsyncContextProvider, SqlQueryPlanner};
 mod join;
 
 impl<'a, S: AsyncContextProvider> SqlQueryPlanner<'a, S> {
+    /// Plan a single relation outside of a query, such as the source of a
+    /// `MERGE`.
+    pub async fn relation_to_plan(&mut self, relation: ast::TableFactor) -> Result<LogicalPlan> {
+        self.create_relation(relation, &mut PlannerContext::new())
+            .await
+    }
+
     /// Create a `LogicalPlan` that scans the named relation
     #[async_recursion]
     async fn create_relation(
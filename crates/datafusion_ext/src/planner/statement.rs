@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 use datafusion::common::{
@@ -65,10 +65,16 @@ impl<'a, S: AsyncContextProvider> SqlQueryPlanner<'a, S> {
         }
     }
 
+    /// Plan the source of an insert, producing a projection matching the
+    /// columns of the target table.
+    ///
+    /// Omitted columns are filled with their value in `column_defaults`,
+    /// falling back to the default provided by the table source, or NULL.
     pub async fn insert_to_source_plan(
         &mut self,
         table_name: &OwnedTableReference,
         columns: &[String],
+        column_defaults: &HashMap<String, datafusion::logical_expr::Expr>,
         source: Box<Query>,
     ) -> Result<LogicalPlan> {
         // Do a table lookup to verify the table exists
@@ -166,8 +172,9 @@ impl<'a, S: AsyncContextProvider> SqlQueryPlanner<'a, S> {
                             .cast_to(target_field.data_type(), source.schema())?
                     }
                     // The value is not specified. Fill in the default value for the column.
-                    None => table_source
-                        .get_column_default(target_field.name())
+                    None => column_defaults
+                        .get(target_field.name())
+                        .or_else(|| table_source.get_column_default(target_field.name()))
                         .cloned()
                         .unwrap_or_else(|| {
                             // If there is no default for the column, then the default is NULL
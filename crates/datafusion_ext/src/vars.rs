@@ -98,6 +98,7 @@ impl SessionVars {
      is_cloud_instance: bool,
      dialect: Dialect,
      enable_experimental_scheduler: bool,
+     vacuum_retention_check: bool,
     }
 }
 
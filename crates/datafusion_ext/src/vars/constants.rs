@@ -222,6 +222,14 @@ pub(super) const ENABLE_EXPERIMENTAL_SCHEDULER: ServerVar<bool> = ServerVar {
     description: "If the experimental query scheduler should be enabled",
 };
 
+pub(super) const VACUUM_RETENTION_CHECK: ServerVar<bool> = ServerVar {
+    name: "vacuum_retention_check",
+    value: &true,
+    group: "glaredb",
+    user_configurable: true,
+    description: "If VACUUM should reject retention periods shorter than the table's",
+};
+
 /// Note that these are not normally shown in the search path.
 pub(super) const IMPLICIT_SCHEMAS: [&str; 2] = [
     POSTGRES_SCHEMA,
@@ -39,6 +39,7 @@ use super::constants::{
     TRANSACTION_ISOLATION,
     USER_ID,
     USER_NAME,
+    VACUUM_RETENTION_CHECK,
 };
 use super::error::VarError;
 use super::value::Value;
@@ -80,6 +81,7 @@ pub struct SessionVarsInner {
     pub is_cloud_instance: SessionVar<bool>,
     pub dialect: SessionVar<Dialect>,
     pub enable_experimental_scheduler: SessionVar<bool>,
+    pub vacuum_retention_check: SessionVar<bool>,
 }
 
 impl SessionVarsInner {
@@ -150,6 +152,8 @@ impl SessionVarsInner {
             Ok(&self.dialect)
         } else if name.eq_ignore_ascii_case(ENABLE_EXPERIMENTAL_SCHEDULER.name) {
             Ok(&self.enable_experimental_scheduler)
+        } else if name.eq_ignore_ascii_case(VACUUM_RETENTION_CHECK.name) {
+            Ok(&self.vacuum_retention_check)
         } else {
             Err(VarError::UnknownVariable(name.to_string()).into())
         }
@@ -209,6 +213,8 @@ impl SessionVarsInner {
             self.dialect.set_from_str(val, setter)
         } else if name.eq_ignore_ascii_case(ENABLE_EXPERIMENTAL_SCHEDULER.name) {
             self.enable_experimental_scheduler.set_from_str(val, setter)
+        } else if name.eq_ignore_ascii_case(VACUUM_RETENTION_CHECK.name) {
+            self.vacuum_retention_check.set_from_str(val, setter)
         } else {
             Err(VarError::UnknownVariable(name.to_string()).into())
         }
@@ -239,6 +245,7 @@ impl SessionVarsInner {
             self.max_credentials_count.config_entry(),
             self.is_cloud_instance.config_entry(),
             self.dialect.config_entry(),
+            self.vacuum_retention_check.config_entry(),
         ]
     }
 }
@@ -272,6 +279,7 @@ impl Default for SessionVarsInner {
             is_cloud_instance: SessionVar::new(&IS_CLOUD_INSTANCE),
             dialect: SessionVar::new(&DIALECT),
             enable_experimental_scheduler: SessionVar::new(&ENABLE_EXPERIMENTAL_SCHEDULER),
+            vacuum_retention_check: SessionVar::new(&VACUUM_RETENTION_CHECK),
         }
     }
 }
@@ -3,7 +3,8 @@
 use std::sync::Arc;
 
 use datafusion::arrow::datatypes::Schema;
-use datafusion::common::ToDFSchema;
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::{Column, ToDFSchema};
 use datafusion::error::Result;
 use datafusion::execution::context::SessionState;
 use datafusion::logical_expr::utils::conjunction;
@@ -30,3 +31,13 @@ pub(crate) fn exprs_to_phys_exprs(
         Ok(None)
     }
 }
+
+/// Remove table qualifiers from column references in an expression.
+pub(crate) fn unqualified(expr: Expr) -> Result<Expr> {
+    expr.transform(&|expr| {
+        Ok(match expr {
+            Expr::Column(column) => Transformed::Yes(Expr::Column(Column::from_name(column.name))),
+            expr => Transformed::No(expr),
+        })
+    })
+}
@@ -0,0 +1,76 @@
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::common::Result as DfResult;
+use datafusion::error::DataFusionError;
+use datafusion::execution::TaskContext;
+use datafusion::physical_plan::insert::DataSink;
+use datafusion::physical_plan::metrics::MetricsSet;
+use datafusion::physical_plan::{DisplayAs, DisplayFormatType, SendableRecordBatchStream};
+use object_store::ObjectStore;
+
+use crate::common::url::DatasourceUrl;
+use crate::lake::iceberg::table::IcebergTable;
+
+/// Writes a new iceberg table to object storage.
+#[derive(Debug, Clone)]
+pub struct IcebergSink {
+    url: DatasourceUrl,
+    store: Arc<dyn ObjectStore>,
+}
+
+impl fmt::Display for IcebergSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "IcebergSink({})", self.url)
+    }
+}
+
+impl DisplayAs for IcebergSink {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default => write!(f, "{self}"),
+            DisplayFormatType::Verbose => write!(f, "{self}"),
+        }
+    }
+}
+
+impl IcebergSink {
+    pub fn new(store: Arc<dyn ObjectStore>, url: DatasourceUrl) -> Self {
+        IcebergSink { url, store }
+    }
+
+    async fn stream_into_inner(&self, stream: SendableRecordBatchStream) -> DfResult<u64> {
+        // There MUST NOT be an existing table at the location, the table is
+        // created using the schema of the stream.
+        let mut table =
+            IcebergTable::create(self.url.clone(), self.store.clone(), &stream.schema())
+                .await
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+        table
+            .append(stream)
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))
+    }
+}
+
+#[async_trait]
+impl DataSink for IcebergSink {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        None
+    }
+
+    async fn write_all(
+        &self,
+        data: SendableRecordBatchStream,
+        _: &Arc<TaskContext>,
+    ) -> DfResult<u64> {
+        self.stream_into_inner(data).await
+    }
+}
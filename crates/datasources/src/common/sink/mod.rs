@@ -1,6 +1,7 @@
 pub mod bson;
 pub mod csv;
 pub mod delta;
+pub mod iceberg;
 pub mod json;
 pub mod lance;
 pub mod parquet;
@@ -11,6 +11,7 @@ use tracing::debug;
 
 use crate::lake::delta::catalog::{DataCatalog, UnityCatalog};
 use crate::lake::delta::errors::Result;
+use crate::lake::delta::version::{self as delta_version, TableCommit, TableVersion};
 
 /// Access a delta lake using a catalog.
 pub struct DeltaLakeAccessor {
@@ -68,3 +69,24 @@ pub async fn load_table_direct(location: &str, opts: StorageOptions) -> Result<D
     // during execution.
     Ok(table)
 }
+
+/// Loads a previous version of the table at the given location.
+pub async fn load_table_direct_version(
+    location: &str,
+    opts: StorageOptions,
+    version: TableVersion,
+) -> Result<DeltaTable> {
+    let mut table = load_table_direct(location, opts).await?;
+    delta_version::load_version(&mut table, version).await?;
+    Ok(table)
+}
+
+/// Lists the commits to the table at the given location, most recent first.
+pub async fn table_history_direct(
+    location: &str,
+    opts: StorageOptions,
+    limit: Option<usize>,
+) -> Result<Vec<TableCommit>> {
+    let table = load_table_direct(location, opts).await?;
+    Ok(delta_version::table_history(&table, limit).await?)
+}
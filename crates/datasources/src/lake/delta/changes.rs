@@ -0,0 +1,401 @@
+//! Reading the change data feed of delta tables.
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use datafusion::arrow::datatypes::{
+    DataType,
+    Field,
+    Schema as ArrowSchema,
+    SchemaRef as ArrowSchemaRef,
+    TimeUnit,
+};
+use datafusion::datasource::file_format::parquet::ParquetFormat;
+use datafusion::datasource::file_format::FileFormat;
+use datafusion::datasource::listing::PartitionedFile;
+use datafusion::datasource::physical_plan::FileScanConfig;
+use datafusion::datasource::TableProvider;
+use datafusion::error::Result as DataFusionResult;
+use datafusion::execution::context::SessionState;
+use datafusion::execution::object_store::ObjectStoreUrl;
+use datafusion::logical_expr::TableType;
+use datafusion::physical_expr::expressions::Column;
+use datafusion::physical_expr::PhysicalExpr;
+use datafusion::physical_plan::empty::EmptyExec;
+use datafusion::physical_plan::projection::ProjectionExec;
+use datafusion::physical_plan::union::UnionExec;
+use datafusion::physical_plan::{ExecutionPlan, Statistics};
+use datafusion::prelude::Expr;
+use datafusion::scalar::ScalarValue;
+use deltalake::delta_datafusion::DataFusionMixins;
+use deltalake::storage::ObjectStoreRef;
+use object_store::path::Path as ObjectStorePath;
+use object_store::{ObjectMeta, ObjectStore};
+use protogen::metastore::types::options::StorageOptions;
+use serde::Deserialize;
+
+use crate::lake::delta::access::load_table_direct;
+use crate::lake::delta::errors::{DeltaError, Result};
+use crate::lake::delta::partition::{parse_partition_value, partition_schema};
+
+/// Table property that enables writing the change data feed.
+const ENABLE_CHANGE_DATA_FEED: &str = "delta.enableChangeDataFeed";
+
+/// Column holding the kind of change for a row.
+pub const CHANGE_TYPE_COLUMN: &str = "_change_type";
+/// Column holding the version of the commit that made the change.
+pub const COMMIT_VERSION_COLUMN: &str = "_commit_version";
+/// Column holding the time of the commit that made the change.
+pub const COMMIT_TIMESTAMP_COLUMN: &str = "_commit_timestamp";
+
+/// Actions in a commit file that are used for the change data feed.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogAction {
+    add: Option<FileAction>,
+    remove: Option<FileAction>,
+    cdc: Option<FileAction>,
+    commit_info: Option<CommitTimestamp>,
+}
+
+/// An `add`, `remove`, or `cdc` action.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileAction {
+    path: String,
+    #[serde(default)]
+    partition_values: HashMap<String, Option<String>>,
+    size: Option<i64>,
+    #[serde(default = "default_data_change")]
+    data_change: bool,
+}
+
+fn default_data_change() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitTimestamp {
+    timestamp: Option<i64>,
+}
+
+/// Row level changes made to a delta table between two versions.
+///
+/// Commits that wrote change data files are read using those files, which
+/// already contain the change type for each row. All other commits only
+/// added or removed whole files, so rows in added files are reported as
+/// inserts, and rows in removed files as deletes.
+#[derive(Debug)]
+pub struct DeltaChangesTable {
+    store: ObjectStoreRef,
+    store_url: ObjectStoreUrl,
+    /// Columns stored in the table's data files.
+    file_schema: ArrowSchemaRef,
+    /// Partition columns of the table.
+    partition_fields: Vec<Field>,
+    /// Added and removed data files for commits without change data files.
+    data_files: Vec<PartitionedFile>,
+    /// Change data files.
+    cdc_files: Vec<PartitionedFile>,
+    schema: ArrowSchemaRef,
+}
+
+impl DeltaChangesTable {
+    /// Load the changes for the table at the given location between the start
+    /// and end versions, inclusive. Defaults to the latest version if no end
+    /// version is provided.
+    pub async fn load(
+        location: &str,
+        opts: StorageOptions,
+        start_version: i64,
+        end_version: Option<i64>,
+    ) -> Result<DeltaChangesTable> {
+        let table = load_table_direct(location, opts).await?;
+        let snapshot = table.snapshot()?;
+
+        let metadata = snapshot.metadata();
+        let enabled = metadata
+            .configuration
+            .get(ENABLE_CHANGE_DATA_FEED)
+            .cloned()
+            .flatten()
+            .is_some_and(|v| v.eq_ignore_ascii_case("true"));
+        if !enabled {
+            return Err(DeltaError::ChangeDataFeedNotEnabled);
+        }
+
+        let latest = table.version();
+        let end_version = end_version.unwrap_or(latest);
+        if start_version < 0 || start_version > end_version || end_version > latest {
+            return Err(DeltaError::InvalidVersionRange {
+                start: start_version,
+                end: end_version,
+                latest,
+            });
+        }
+
+        let partition_columns = &metadata.partition_columns;
+        let table_schema = snapshot.arrow_schema()?;
+        let file_schema = Arc::new(ArrowSchema::new(
+            table_schema
+                .fields()
+                .iter()
+                .filter(|f| !partition_columns.contains(f.name()))
+                .cloned()
+                .collect::<Vec<_>>(),
+        ));
+        let partition_fields = partition_schema(&table_schema, partition_columns)?
+            .fields()
+            .iter()
+            .map(|f| f.as_ref().clone())
+            .collect::<Vec<_>>();
+
+        let log_store = table.log_store();
+        let store = log_store.object_store();
+
+        let mut data_files = Vec::new();
+        let mut cdc_files = Vec::new();
+        for version in start_version..=end_version {
+            let path = ObjectStorePath::from(format!("_delta_log/{version:020}.json"));
+            let result = store.get(&path).await?;
+            let last_modified = result.meta.last_modified;
+            let bytes = result.bytes().await?;
+
+            let actions = bytes
+                .split(|b| *b == b'\n')
+                .filter(|line| !line.iter().all(u8::is_ascii_whitespace))
+                .map(serde_json::from_slice::<LogAction>)
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let timestamp = actions
+                .iter()
+                .find_map(|action| action.commit_info.as_ref()?.timestamp)
+                .unwrap_or_else(|| last_modified.timestamp_millis());
+            let commit_values = [
+                ScalarValue::Int64(Some(version)),
+                ScalarValue::TimestampMillisecond(Some(timestamp), Some("UTC".into())),
+            ];
+
+            let has_cdc = actions.iter().any(|action| action.cdc.is_some());
+            for action in actions {
+                if has_cdc {
+                    if let Some(cdc) = action.cdc {
+                        let file =
+                            partitioned_file(&store, cdc, &partition_fields, None, &commit_values)
+                                .await?;
+                        cdc_files.push(file);
+                    }
+                    continue;
+                }
+
+                if let Some(add) = action.add.filter(|add| add.data_change) {
+                    let file = partitioned_file(
+                        &store,
+                        add,
+                        &partition_fields,
+                        Some("insert"),
+                        &commit_values,
+                    )
+                    .await?;
+                    data_files.push(file);
+                }
+                if let Some(remove) = action.remove.filter(|remove| remove.data_change) {
+                    let file = partitioned_file(
+                        &store,
+                        remove,
+                        &partition_fields,
+                        Some("delete"),
+                        &commit_values,
+                    )
+                    .await?;
+                    data_files.push(file);
+                }
+            }
+        }
+
+        let mut fields = file_schema.fields().to_vec();
+        fields.extend(partition_fields.iter().cloned().map(Arc::new));
+        fields.extend(change_fields().into_iter().map(Arc::new));
+        let schema = Arc::new(ArrowSchema::new(fields));
+
+        Ok(DeltaChangesTable {
+            store,
+            store_url: log_store.object_store_url(),
+            file_schema,
+            partition_fields,
+            data_files,
+            cdc_files,
+            schema,
+        })
+    }
+
+    fn scan_config(
+        &self,
+        file_schema: ArrowSchemaRef,
+        table_partition_cols: Vec<Field>,
+        files: &[PartitionedFile],
+    ) -> FileScanConfig {
+        FileScanConfig {
+            object_store_url: self.store_url.clone(),
+            statistics: Statistics::new_unknown(file_schema.as_ref()),
+            file_schema,
+            projection: None,
+            file_groups: vec![files.to_vec()],
+            limit: None,
+            table_partition_cols,
+            output_ordering: Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl TableProvider for DeltaChangesTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> ArrowSchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        state
+            .runtime_env()
+            .register_object_store(self.store_url.as_ref(), self.store.clone());
+
+        let [change_type, commit_version, commit_timestamp] = change_fields();
+        let mut plans: Vec<Arc<dyn ExecutionPlan>> = Vec::new();
+
+        if !self.data_files.is_empty() {
+            let mut partition_cols = self.partition_fields.clone();
+            partition_cols.extend([
+                change_type.clone(),
+                commit_version.clone(),
+                commit_timestamp.clone(),
+            ]);
+            let conf = self.scan_config(self.file_schema.clone(), partition_cols, &self.data_files);
+            plans.push(
+                ParquetFormat::new()
+                    .create_physical_plan(state, conf, None)
+                    .await?,
+            );
+        }
+
+        if !self.cdc_files.is_empty() {
+            // Change data files store the change type alongside the data.
+            let mut fields = self.file_schema.fields().to_vec();
+            fields.push(Arc::new(change_type));
+            let file_schema = Arc::new(ArrowSchema::new(fields));
+
+            let mut partition_cols = self.partition_fields.clone();
+            partition_cols.extend([commit_version, commit_timestamp]);
+            let conf = self.scan_config(file_schema, partition_cols, &self.cdc_files);
+            let plan = ParquetFormat::new()
+                .create_physical_plan(state, conf, None)
+                .await?;
+
+            // Reorder the columns to match the output schema.
+            let indices = self
+                .schema
+                .fields()
+                .iter()
+                .map(|f| plan.schema().index_of(f.name()))
+                .collect::<Result<Vec<_>, _>>()?;
+            plans.push(project(plan, &indices)?);
+        }
+
+        let plan: Arc<dyn ExecutionPlan> = match plans.len() {
+            0 => Arc::new(EmptyExec::new(self.schema.clone())),
+            1 => plans.pop().unwrap(),
+            _ => Arc::new(UnionExec::new(plans)),
+        };
+
+        match projection {
+            Some(projection) => project(plan, projection),
+            None => Ok(plan),
+        }
+    }
+}
+
+/// Fields describing each change, in output order.
+fn change_fields() -> [Field; 3] {
+    [
+        Field::new(CHANGE_TYPE_COLUMN, DataType::Utf8, true),
+        Field::new(COMMIT_VERSION_COLUMN, DataType::Int64, true),
+        Field::new(
+            COMMIT_TIMESTAMP_COLUMN,
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            true,
+        ),
+    ]
+}
+
+/// Project the columns at the given indices from the plan.
+fn project(
+    plan: Arc<dyn ExecutionPlan>,
+    indices: &[usize],
+) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+    let schema = plan.schema();
+    let exprs = indices
+        .iter()
+        .map(|idx| {
+            let name = schema.field(*idx).name();
+            (
+                Arc::new(Column::new(name, *idx)) as Arc<dyn PhysicalExpr>,
+                name.clone(),
+            )
+        })
+        .collect();
+    Ok(Arc::new(ProjectionExec::try_new(exprs, plan)?))
+}
+
+/// Create the file to scan for an action, appending the change type (when
+/// not stored in the file) and commit columns to the partition values.
+async fn partitioned_file(
+    store: &ObjectStoreRef,
+    file: FileAction,
+    partition_fields: &[Field],
+    change_type: Option<&str>,
+    commit_values: &[ScalarValue],
+) -> Result<PartitionedFile> {
+    let location = ObjectStorePath::from_url_path(&file.path)?;
+    let size = match file.size {
+        Some(size) => size as usize,
+        None => store.head(&location).await?.size,
+    };
+
+    let mut partition_values = partition_fields
+        .iter()
+        .map(|field| {
+            let value = file.partition_values.get(field.name()).cloned().flatten();
+            parse_partition_value(value.as_deref(), field.data_type())
+        })
+        .collect::<DataFusionResult<Vec<_>>>()?;
+    partition_values.extend(change_type.map(|typ| ScalarValue::Utf8(Some(typ.to_string()))));
+    partition_values.extend(commit_values.iter().cloned());
+
+    Ok(PartitionedFile {
+        object_meta: ObjectMeta {
+            location,
+            last_modified: DateTime::<Utc>::MIN_UTC,
+            size,
+            e_tag: None,
+            version: None,
+        },
+        partition_values,
+        range: None,
+        extensions: None,
+    })
+}
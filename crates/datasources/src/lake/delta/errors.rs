@@ -18,6 +18,18 @@ pub enum DeltaError {
     #[error(transparent)]
     UrlParse(#[from] url::ParseError),
 
+    #[error(transparent)]
+    ObjectStorePath(#[from] object_store::path::Error),
+
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+
+    #[error("Change data feed is not enabled for the table, set 'delta.enableChangeDataFeed' to 'true' to enable it")]
+    ChangeDataFeedNotEnabled,
+
+    #[error("Invalid version range {start} to {end}, latest version of the table is {latest}")]
+    InvalidVersionRange { start: i64, end: i64, latest: i64 },
+
     #[error("{0}")]
     Static(&'static str),
 }
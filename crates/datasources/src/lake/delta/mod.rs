@@ -1,3 +1,6 @@
 pub mod access;
 pub mod catalog;
+pub mod changes;
 pub mod errors;
+pub mod partition;
+pub mod version;
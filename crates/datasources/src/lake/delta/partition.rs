@@ -0,0 +1,45 @@
+//! Partition values of delta tables.
+//!
+//! Partition values are recorded as strings in each file's `add` action
+//! instead of in the file itself.
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::{DataType, Field, Schema as ArrowSchema, SchemaRef};
+use datafusion::error::Result as DataFusionResult;
+use datafusion::scalar::ScalarValue;
+
+/// Parse a partition value from the table's log into a scalar of the
+/// column's type.
+pub(crate) fn parse_partition_value(
+    value: Option<&str>,
+    data_type: &DataType,
+) -> DataFusionResult<ScalarValue> {
+    let data_type = match data_type {
+        DataType::Dictionary(_, value_type) => value_type.as_ref(),
+        other => other,
+    };
+    match value {
+        Some(value) => ScalarValue::try_from_string(value.to_string(), data_type),
+        None => ScalarValue::try_from(data_type),
+    }
+}
+
+/// Get the schema of the partition columns, using the types of the columns
+/// in `schema`.
+pub(crate) fn partition_schema(
+    schema: &ArrowSchema,
+    partition_columns: &[String],
+) -> DataFusionResult<SchemaRef> {
+    let fields = partition_columns
+        .iter()
+        .map(|col| {
+            let field = schema.field_with_name(col)?;
+            let data_type = match field.data_type() {
+                DataType::Dictionary(_, value_type) => value_type.as_ref().clone(),
+                other => other.clone(),
+            };
+            Ok(Field::new(col, data_type, true))
+        })
+        .collect::<DataFusionResult<Vec<_>>>()?;
+    Ok(Arc::new(ArrowSchema::new(fields)))
+}
@@ -0,0 +1,59 @@
+//! Versions and commit history of delta tables.
+use std::fmt;
+
+use chrono::{TimeZone, Utc};
+use deltalake::kernel::CommitInfo;
+use deltalake::{DeltaResult, DeltaTable};
+
+/// A previous version of a delta table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TableVersion {
+    /// A version number, as listed by the table's history.
+    Version(i64),
+    /// The latest version committed at or before a point in time, in
+    /// nanoseconds since the Unix epoch.
+    Timestamp(i64),
+}
+
+impl fmt::Display for TableVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableVersion::Version(version) => write!(f, "version {version}"),
+            TableVersion::Timestamp(nanos) => {
+                write!(f, "timestamp {}", Utc.timestamp_nanos(*nanos))
+            }
+        }
+    }
+}
+
+/// A commit to a delta table.
+#[derive(Debug, Clone)]
+pub struct TableCommit {
+    pub version: i64,
+    pub info: CommitInfo,
+}
+
+/// Load the given version of the table.
+pub(crate) async fn load_version(table: &mut DeltaTable, version: TableVersion) -> DeltaResult<()> {
+    match version {
+        TableVersion::Version(version) => table.load_version(version).await,
+        TableVersion::Timestamp(nanos) => {
+            table.load_with_datetime(Utc.timestamp_nanos(nanos)).await
+        }
+    }
+}
+
+/// List the commits to the table, most recent first.
+pub(crate) async fn table_history(
+    table: &DeltaTable,
+    limit: Option<usize>,
+) -> DeltaResult<Vec<TableCommit>> {
+    let commits = table
+        .history(limit)
+        .await?
+        .into_iter()
+        .zip((0..=table.version()).rev())
+        .map(|(info, version)| TableCommit { version, info })
+        .collect();
+    Ok(commits)
+}
@@ -0,0 +1,439 @@
+//! Iceberg catalog implementations.
+//!
+//! Currently only catalogs implementing the REST catalog spec are supported.
+//! See <https://github.com/apache/iceberg/blob/main/open-api/rest-catalog-open-api.yaml>
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::Fields;
+use datafusion_ext::errors::ExtensionError;
+use datafusion_ext::functions::VirtualLister;
+use protogen::metastore::types::options::{IcebergCatalog, IcebergRestCatalog, StorageOptions};
+use reqwest::header;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use tracing::debug;
+use url::Url;
+
+use super::spec::TableMetadata;
+use super::table::IcebergTable;
+use crate::common::url::DatasourceUrl;
+use crate::lake::iceberg::errors::{IcebergError, Result};
+use crate::lake::storage_options_into_object_store;
+
+/// Access iceberg tables using a catalog.
+#[derive(Debug, Clone)]
+pub struct IcebergCatalogAccessor {
+    catalog: RestCatalog,
+    storage_options: StorageOptions,
+}
+
+impl IcebergCatalogAccessor {
+    /// Connect to an iceberg catalog using the provided catalog information.
+    pub async fn connect(
+        catalog: &IcebergCatalog,
+        storage_options: StorageOptions,
+    ) -> Result<IcebergCatalogAccessor> {
+        let catalog = match catalog {
+            IcebergCatalog::Rest(IcebergRestCatalog {
+                uri,
+                warehouse,
+                token,
+            }) => RestCatalog::connect(uri, warehouse.as_deref(), token.as_deref()).await?,
+        };
+
+        Ok(IcebergCatalogAccessor {
+            catalog,
+            storage_options,
+        })
+    }
+
+    /// Load a table from the catalog.
+    ///
+    /// Nested namespaces are expected to be separated by a '.'.
+    pub async fn load_table(&self, namespace: &str, table: &str) -> Result<IcebergTable> {
+        let resp = self.catalog.load_table(namespace, table).await?;
+
+        let location = DatasourceUrl::try_new(&resp.metadata.location)?;
+        debug!(%location, %namespace, %table, "iceberg table location");
+
+        let store = storage_options_into_object_store(&location, &self.storage_options)?;
+
+        Ok(IcebergTable::open_with_metadata(
+            location,
+            store,
+            resp.metadata_location.as_deref().unwrap_or_default(),
+            resp.metadata,
+        ))
+    }
+}
+
+#[async_trait]
+impl VirtualLister for IcebergCatalogAccessor {
+    async fn list_schemas(&self) -> Result<Vec<String>, ExtensionError> {
+        self.catalog
+            .list_namespaces()
+            .await
+            .map_err(ExtensionError::access)
+    }
+
+    async fn list_tables(&self, schema: &str) -> Result<Vec<String>, ExtensionError> {
+        self.catalog
+            .list_tables(schema)
+            .await
+            .map_err(ExtensionError::access)
+    }
+
+    async fn list_columns(&self, schema: &str, table: &str) -> Result<Fields, ExtensionError> {
+        let table = self
+            .load_table(schema, table)
+            .await
+            .map_err(ExtensionError::access)?;
+        let schema = table.table_arrow_schema().map_err(ExtensionError::access)?;
+        Ok(schema.fields)
+    }
+}
+
+/// Client for a catalog implementing the iceberg REST catalog spec.
+#[derive(Debug, Clone)]
+struct RestCatalog {
+    client: reqwest::Client,
+    /// Url that all catalog endpoints are relative to. Includes the api
+    /// version and the prefix provided by the catalog config.
+    base_url: Url,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConfigResponse {
+    #[serde(default)]
+    defaults: HashMap<String, String>,
+    #[serde(default)]
+    overrides: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ListNamespacesResponse {
+    namespaces: Vec<Vec<String>>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TableIdentifier {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ListTablesResponse {
+    identifiers: Vec<TableIdentifier>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct LoadTableResponse {
+    /// > May be null if the table is staged as part of a transaction
+    metadata_location: Option<String>,
+    metadata: TableMetadata,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    error: ErrorModel,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorModel {
+    message: String,
+    r#type: String,
+    code: u16,
+}
+
+impl RestCatalog {
+    async fn connect(uri: &str, warehouse: Option<&str>, token: Option<&str>) -> Result<Self> {
+        let uri = Url::parse(uri)?;
+        if uri.cannot_be_a_base() {
+            return Err(IcebergError::DataInvalid(format!(
+                "Invalid uri for iceberg REST catalog: {uri}"
+            )));
+        }
+
+        let mut headers = header::HeaderMap::new();
+        if let Some(token) = token {
+            let auth_header_val = header::HeaderValue::from_str(&format!("Bearer {token}"))
+                .map_err(|_| IcebergError::Static("Invalid token for iceberg REST catalog"))?;
+            headers.insert(header::AUTHORIZATION, auth_header_val);
+        }
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()?;
+
+        // Fetching the config also checks that we can reach the catalog.
+        let mut config_url = join_segments(&uri, ["v1", "config"]);
+        if let Some(warehouse) = warehouse {
+            config_url
+                .query_pairs_mut()
+                .append_pair("warehouse", warehouse);
+        }
+        let config: ConfigResponse = get_json(&client, config_url).await?;
+
+        // Overrides take precedence over anything provided by the client, and
+        // we don't provide anything ourselves.
+        let prefix = config
+            .overrides
+            .get("prefix")
+            .or_else(|| config.defaults.get("prefix"));
+
+        let mut base_url = join_segments(&uri, ["v1"]);
+        if let Some(prefix) = prefix {
+            base_url = join_segments(&base_url, prefix.split('/').filter(|s| !s.is_empty()));
+        }
+
+        Ok(RestCatalog { client, base_url })
+    }
+
+    /// List all top-level namespaces in the catalog.
+    async fn list_namespaces(&self) -> Result<Vec<String>> {
+        let mut namespaces = Vec::new();
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let mut url = join_segments(&self.base_url, ["namespaces"]);
+            if let Some(page_token) = &page_token {
+                url.query_pairs_mut().append_pair("pageToken", page_token);
+            }
+
+            let resp: ListNamespacesResponse = get_json(&self.client, url).await?;
+            namespaces.extend(resp.namespaces.into_iter().map(|parts| parts.join(".")));
+
+            page_token = resp.next_page_token.filter(|token| !token.is_empty());
+            if page_token.is_none() {
+                break;
+            }
+        }
+
+        Ok(namespaces)
+    }
+
+    /// List all tables in a namespace.
+    async fn list_tables(&self, namespace: &str) -> Result<Vec<String>> {
+        let namespace = encode_namespace(namespace);
+        let mut tables = Vec::new();
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let mut url =
+                join_segments(&self.base_url, ["namespaces", namespace.as_str(), "tables"]);
+            if let Some(page_token) = &page_token {
+                url.query_pairs_mut().append_pair("pageToken", page_token);
+            }
+
+            let resp: ListTablesResponse = get_json(&self.client, url).await?;
+            tables.extend(resp.identifiers.into_iter().map(|ident| ident.name));
+
+            page_token = resp.next_page_token.filter(|token| !token.is_empty());
+            if page_token.is_none() {
+                break;
+            }
+        }
+
+        Ok(tables)
+    }
+
+    async fn load_table(&self, namespace: &str, table: &str) -> Result<LoadTableResponse> {
+        let namespace = encode_namespace(namespace);
+        let url = join_segments(
+            &self.base_url,
+            ["namespaces", namespace.as_str(), "tables", table],
+        );
+        get_json(&self.client, url).await
+    }
+}
+
+/// Encode a namespace for use in a url path.
+///
+/// > If parent is a multipart namespace, the parts must be separated by the
+/// > unit separator (`0x1F`) byte.
+///
+/// The separator gets percent-encoded when added to the url.
+fn encode_namespace(namespace: &str) -> String {
+    namespace.split('.').collect::<Vec<_>>().join("\u{1F}")
+}
+
+/// Append percent-encoded path segments to a url.
+fn join_segments<'a>(url: &Url, segments: impl IntoIterator<Item = &'a str>) -> Url {
+    let mut url = url.clone();
+    url.path_segments_mut()
+        .expect("catalog uri to be a base")
+        .pop_if_empty()
+        .extend(segments);
+    url
+}
+
+async fn get_json<T: DeserializeOwned>(client: &reqwest::Client, url: Url) -> Result<T> {
+    debug!(%url, "iceberg REST catalog request");
+
+    let resp = client.get(url).send().await?;
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp.json().await?);
+    }
+
+    // Errors should be returned using the error model, fall back to the
+    // status if that's not the case.
+    match resp.json::<ErrorResponse>().await {
+        Ok(ErrorResponse { error }) => Err(IcebergError::RestCatalog {
+            code: error.code,
+            typ: error.r#type,
+            message: error.message,
+        }),
+        Err(_) => Err(IcebergError::RestCatalog {
+            code: status.as_u16(),
+            typ: status.canonical_reason().unwrap_or("Unknown").to_string(),
+            message: "Unexpected response from catalog".to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use datafusion::arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::lake::iceberg::writer::new_table_metadata;
+
+    /// A minimal http server returning canned responses keyed by request
+    /// path (including the query).
+    ///
+    /// Returns the url for the server and the list of requested paths.
+    async fn mock_catalog(
+        responses: Vec<(&'static str, u16, String)>,
+    ) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let reqs = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut conn, _) = listener.accept().await.unwrap();
+
+                let mut buf = Vec::new();
+                while !buf.ends_with(b"\r\n\r\n") {
+                    let mut b = [0; 1];
+                    if conn.read(&mut b).await.unwrap() == 0 {
+                        break;
+                    }
+                    buf.push(b[0]);
+                }
+
+                let req = String::from_utf8(buf).unwrap();
+                let path = req.split(' ').nth(1).unwrap_or_default().to_string();
+                reqs.lock().unwrap().push(path.clone());
+
+                let (code, body) = responses
+                    .iter()
+                    .find(|(p, _, _)| *p == path)
+                    .map(|(_, code, body)| (*code, body.clone()))
+                    .unwrap_or((404, "not found".to_string()));
+
+                let resp = format!(
+                    "HTTP/1.1 {code} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                conn.write_all(resp.as_bytes()).await.unwrap();
+                conn.shutdown().await.unwrap();
+            }
+        });
+
+        (format!("http://{addr}"), requests)
+    }
+
+    #[tokio::test]
+    async fn test_list_with_prefix_and_pages() {
+        let (uri, requests) = mock_catalog(vec![
+            (
+                "/v1/config?warehouse=wh",
+                200,
+                r#"{"defaults": {}, "overrides": {"prefix": "catalogs/wh"}}"#.to_string(),
+            ),
+            (
+                "/v1/catalogs/wh/namespaces",
+                200,
+                r#"{"namespaces": [["sales"]], "next-page-token": "p1"}"#.to_string(),
+            ),
+            (
+                "/v1/catalogs/wh/namespaces?pageToken=p1",
+                200,
+                r#"{"namespaces": [["web", "events"]]}"#.to_string(),
+            ),
+            (
+                "/v1/catalogs/wh/namespaces/web%1Fevents/tables",
+                200,
+                r#"{"identifiers": [{"namespace": ["web", "events"], "name": "clicks"}]}"#
+                    .to_string(),
+            ),
+        ])
+        .await;
+
+        let catalog = RestCatalog::connect(&uri, Some("wh"), None).await.unwrap();
+
+        let namespaces = catalog.list_namespaces().await.unwrap();
+        assert_eq!(vec!["sales", "web.events"], namespaces);
+
+        let tables = catalog.list_tables("web.events").await.unwrap();
+        assert_eq!(vec!["clicks"], tables);
+
+        assert_eq!(4, requests.lock().unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn test_load_table() {
+        let schema = ArrowSchema::new(vec![Field::new("a", DataType::Int64, true)]);
+        let metadata = new_table_metadata("/tmp/warehouse/db/t1".to_string(), &schema).unwrap();
+        let body = serde_json::json!({
+            "metadata-location": "/tmp/warehouse/db/t1/metadata/00001-6d1c1f5e-0b7a-4f0e-9d8d-51a4c6f1b2a3.metadata.json",
+            "metadata": metadata,
+        })
+        .to_string();
+
+        let (uri, _requests) = mock_catalog(vec![
+            ("/v1/config", 200, "{}".to_string()),
+            ("/v1/namespaces/db/tables/t1", 200, body),
+            (
+                "/v1/namespaces/db/tables/missing",
+                404,
+                r#"{"error": {"message": "Table does not exist: db.missing", "type": "NoSuchTableException", "code": 404}}"#.to_string(),
+            ),
+        ])
+        .await;
+
+        let catalog = RestCatalog::connect(&uri, None, None).await.unwrap();
+
+        let resp = catalog.load_table("db", "t1").await.unwrap();
+        assert_eq!("/tmp/warehouse/db/t1", resp.metadata.location);
+
+        let accessor = IcebergCatalogAccessor {
+            catalog,
+            storage_options: StorageOptions::default(),
+        };
+        let table = accessor.load_table("db", "t1").await.unwrap();
+        let fields = table.table_arrow_schema().unwrap().fields;
+        assert_eq!(1, fields.len());
+        assert_eq!("a", fields[0].name());
+
+        let err = accessor.load_table("db", "missing").await.unwrap_err();
+        match err {
+            IcebergError::RestCatalog { code, typ, .. } => {
+                assert_eq!(404, code);
+                assert_eq!("NoSuchTableException", typ);
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+}
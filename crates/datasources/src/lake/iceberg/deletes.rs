@@ -0,0 +1,579 @@
+//! Row-level deletes.
+//!
+//! Tables using format version 2 can delete rows without rewriting data files
+//! by writing delete files alongside them:
+//!
+//! - Position deletes: the path and row position of each deleted row.
+//! - Equality deletes: values for a set of columns, any row with the same
+//!   values for those columns is deleted.
+//!
+//! Scans need to apply the deletes when reading data files.
+//!
+//! > A position delete file must be applied to a data file when all of the
+//! > following are true:
+//! >
+//! > - The data file's file_path is equal to the delete file's file_path
+//! > - The data file's data sequence number is less than or equal to the
+//! >   delete file's data sequence number
+//! > - The data file's partition (both spec and partition values) is equal to
+//! >   the delete file's partition
+//!
+//! > An equality delete file must be applied to a data file when all of the
+//! > following are true:
+//! >
+//! > - The data file's data sequence number is strictly less than the delete's
+//! >   data sequence number
+//! > - The data file's partition (both spec id and partition values) is equal
+//! >   to the delete file's partition or the delete file's partition spec is
+//! >   unpartitioned
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use apache_avro::types::Value;
+use datafusion::arrow::array::{Array, AsArray, BooleanArray};
+use datafusion::arrow::compute::filter_record_batch;
+use datafusion::arrow::datatypes::{DataType, Field, Int64Type, Schema as ArrowSchema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::TaskContext;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayAs,
+    DisplayFormatType,
+    ExecutionPlan,
+    Partitioning,
+    SendableRecordBatchStream,
+    Statistics,
+};
+use datafusion::scalar::ScalarValue;
+use futures::StreamExt;
+
+use super::table::ScanFile;
+use crate::lake::iceberg::errors::{IcebergError, Result};
+
+/// Schema used when reading position delete files.
+pub(crate) fn position_delete_schema() -> SchemaRef {
+    Arc::new(ArrowSchema::new(vec![
+        Field::new("file_path", DataType::Utf8, true),
+        Field::new("pos", DataType::Int64, true),
+    ]))
+}
+
+/// Positions of deleted rows read from a single position delete file.
+#[derive(Debug)]
+pub(crate) struct PositionDeleteFile {
+    sequence_number: i64,
+    /// Deleted row positions keyed by data file path.
+    positions: HashMap<String, Vec<u64>>,
+}
+
+impl PositionDeleteFile {
+    /// Create from the batches read from the delete file using the position
+    /// delete schema.
+    pub(crate) fn try_new(sequence_number: i64, batches: &[RecordBatch]) -> Result<Self> {
+        let mut positions: HashMap<String, Vec<u64>> = HashMap::new();
+
+        for batch in batches {
+            let paths = batch
+                .column_by_name("file_path")
+                .and_then(|c| c.as_string_opt::<i32>())
+                .ok_or(IcebergError::Static(
+                    "Position delete file missing 'file_path' column",
+                ))?;
+            let pos = batch
+                .column_by_name("pos")
+                .and_then(|c| c.as_primitive_opt::<Int64Type>())
+                .ok_or(IcebergError::Static(
+                    "Position delete file missing 'pos' column",
+                ))?;
+
+            for row in 0..batch.num_rows() {
+                if paths.is_null(row) || pos.is_null(row) {
+                    return Err(IcebergError::DataInvalid(
+                        "Unexpected null in position delete file".to_string(),
+                    ));
+                }
+                positions
+                    .entry(paths.value(row).to_string())
+                    .or_default()
+                    .push(pos.value(row) as u64);
+            }
+        }
+
+        Ok(PositionDeleteFile {
+            sequence_number,
+            positions,
+        })
+    }
+}
+
+/// Values for deleted rows read from a single equality delete file.
+#[derive(Debug)]
+pub(crate) struct EqualityDeleteFile {
+    sequence_number: i64,
+    spec_id: i32,
+    /// If the delete file's partition spec is unpartitioned, the deletes apply
+    /// to all partitions.
+    unpartitioned: bool,
+    partition: Vec<Value>,
+    deletes: Arc<EqualityDeletes>,
+}
+
+impl EqualityDeleteFile {
+    /// Create from the batches read from the delete file. Every column in the
+    /// batches is used when comparing rows.
+    pub(crate) fn try_new(file: &ScanFile, batches: &[RecordBatch]) -> Result<Self> {
+        let columns: Vec<String> = match batches.first() {
+            Some(batch) => batch
+                .schema()
+                .fields()
+                .iter()
+                .map(|f| f.name().clone())
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let mut keys = HashSet::new();
+        for batch in batches {
+            for row in 0..batch.num_rows() {
+                let key = batch
+                    .columns()
+                    .iter()
+                    .map(|col| ScalarValue::try_from_array(col, row))
+                    .collect::<DataFusionResult<Vec<_>>>()?;
+                keys.insert(key);
+            }
+        }
+
+        Ok(EqualityDeleteFile {
+            sequence_number: file.sequence_number,
+            spec_id: file.spec_id,
+            unpartitioned: file.partition_spec.is_empty(),
+            partition: file.file.partition.clone(),
+            deletes: Arc::new(EqualityDeletes { columns, keys }),
+        })
+    }
+
+    fn applies_to(&self, file: &ScanFile) -> bool {
+        file.sequence_number < self.sequence_number
+            && (self.unpartitioned
+                || (self.spec_id == file.spec_id && self.partition == file.file.partition))
+    }
+}
+
+/// Rows deleted by an equality delete file.
+#[derive(Debug)]
+pub(crate) struct EqualityDeletes {
+    /// Columns used to compare rows.
+    columns: Vec<String>,
+    /// Values of the deleted rows, in the same order as the columns.
+    keys: HashSet<Vec<ScalarValue>>,
+}
+
+/// All delete files for a scan.
+#[derive(Debug, Default)]
+pub(crate) struct Deletes {
+    position: Vec<PositionDeleteFile>,
+    equality: Vec<EqualityDeleteFile>,
+}
+
+impl Deletes {
+    pub(crate) fn push_position(&mut self, file: PositionDeleteFile) {
+        self.position.push(file);
+    }
+
+    pub(crate) fn push_equality(&mut self, file: EqualityDeleteFile) {
+        if !file.deletes.keys.is_empty() {
+            self.equality.push(file);
+        }
+    }
+
+    /// Get the deletes that need to be applied when reading a data file.
+    ///
+    /// Returns `None` if no rows in the file are deleted.
+    pub(crate) fn for_file(&self, file: &ScanFile) -> Option<FileDeletes> {
+        let mut positions: Vec<u64> = self
+            .position
+            .iter()
+            .filter(|d| file.sequence_number <= d.sequence_number)
+            .filter_map(|d| d.positions.get(&file.file.file_path))
+            .flatten()
+            .copied()
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+
+        let equality: Vec<_> = self
+            .equality
+            .iter()
+            .filter(|d| d.applies_to(file))
+            .map(|d| d.deletes.clone())
+            .collect();
+
+        if positions.is_empty() && equality.is_empty() {
+            None
+        } else {
+            Some(FileDeletes {
+                positions,
+                equality,
+            })
+        }
+    }
+}
+
+/// Deletes for a single data file.
+#[derive(Debug)]
+pub(crate) struct FileDeletes {
+    /// Sorted positions of deleted rows.
+    positions: Vec<u64>,
+    equality: Vec<Arc<EqualityDeletes>>,
+}
+
+impl FileDeletes {
+    /// Remove deleted rows from a batch read from the data file.
+    ///
+    /// `offset` is the position in the file of the first row in the batch.
+    fn apply(&self, batch: &RecordBatch, offset: u64) -> DataFusionResult<RecordBatch> {
+        let num_rows = batch.num_rows() as u64;
+        let mut keep = vec![true; batch.num_rows()];
+        let mut deleted = false;
+
+        let start = self.positions.partition_point(|p| *p < offset);
+        let end = self.positions.partition_point(|p| *p < offset + num_rows);
+        for pos in &self.positions[start..end] {
+            keep[(pos - offset) as usize] = false;
+            deleted = true;
+        }
+
+        for deletes in &self.equality {
+            let columns = deletes
+                .columns
+                .iter()
+                .map(|name| {
+                    batch.column_by_name(name).ok_or_else(|| {
+                        DataFusionError::Execution(format!(
+                            "Missing column '{name}' for equality delete"
+                        ))
+                    })
+                })
+                .collect::<DataFusionResult<Vec<_>>>()?;
+
+            for (row, keep) in keep.iter_mut().enumerate() {
+                if !*keep {
+                    continue;
+                }
+                let key = columns
+                    .iter()
+                    .map(|col| ScalarValue::try_from_array(col, row))
+                    .collect::<DataFusionResult<Vec<_>>>()?;
+                if deletes.keys.contains(&key) {
+                    *keep = false;
+                    deleted = true;
+                }
+            }
+        }
+
+        if !deleted {
+            return Ok(batch.clone());
+        }
+
+        Ok(filter_record_batch(batch, &BooleanArray::from(keep))?)
+    }
+}
+
+/// Scan over data files with rows removed by delete files.
+///
+/// Each partition of the input reads a single data file from start to end,
+/// which lets row positions be tracked when applying position deletes. The
+/// input reads every column since equality deletes may compare columns that
+/// aren't part of the projection.
+#[derive(Debug)]
+pub struct IcebergDeleteExec {
+    input: Arc<dyn ExecutionPlan>,
+    deletes: Vec<Arc<FileDeletes>>,
+    projection: Option<Vec<usize>>,
+    schema: SchemaRef,
+}
+
+impl IcebergDeleteExec {
+    pub(crate) fn try_new(
+        input: Arc<dyn ExecutionPlan>,
+        deletes: Vec<Arc<FileDeletes>>,
+        projection: Option<Vec<usize>>,
+    ) -> DataFusionResult<Self> {
+        let schema = match &projection {
+            Some(projection) => Arc::new(input.schema().project(projection)?),
+            None => input.schema(),
+        };
+
+        Ok(IcebergDeleteExec {
+            input,
+            deletes,
+            projection,
+            schema,
+        })
+    }
+}
+
+impl ExecutionPlan for IcebergDeleteExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(self.deletes.len())
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        // Repartitioning the input would split files across partitions,
+        // losing track of row positions.
+        vec![false]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        if children.len() != 1 {
+            return Err(DataFusionError::Plan(
+                "IcebergDeleteExec expects exactly one input".to_string(),
+            ));
+        }
+        Ok(Arc::new(IcebergDeleteExec::try_new(
+            children[0].clone(),
+            self.deletes.clone(),
+            self.projection.clone(),
+        )?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        let deletes = self.deletes.get(partition).cloned().ok_or_else(|| {
+            DataFusionError::Execution(format!(
+                "Invalid partition {partition} for iceberg delete exec"
+            ))
+        })?;
+        let projection = self.projection.clone();
+        let input = self.input.execute(partition, context)?;
+
+        let mut offset = 0;
+        let stream = input.map(move |batch| {
+            let batch = batch?;
+            let num_rows = batch.num_rows() as u64;
+            let batch = deletes.apply(&batch, offset)?;
+            offset += num_rows;
+            match &projection {
+                Some(projection) => Ok(batch.project(projection)?),
+                None => Ok(batch),
+            }
+        });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            stream,
+        )))
+    }
+
+    fn statistics(&self) -> DataFusionResult<Statistics> {
+        Ok(Statistics::new_unknown(self.schema().as_ref()))
+    }
+}
+
+impl DisplayAs for IcebergDeleteExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "IcebergDeleteExec: files={}", self.deletes.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::{Int64Array, StringArray};
+
+    use super::*;
+    use crate::lake::iceberg::spec::{DataFile, PartitionField, Transform};
+
+    fn scan_file(path: &str, sequence_number: i64, partition: Vec<Value>) -> ScanFile {
+        let partition_spec = if partition.is_empty() {
+            Vec::new()
+        } else {
+            vec![PartitionField {
+                source_id: 2,
+                field_id: 1000,
+                name: "name".to_string(),
+                transform: Transform::Identity,
+            }]
+        };
+        ScanFile {
+            file: DataFile {
+                content: 0,
+                file_path: path.to_string(),
+                file_format: "PARQUET".to_string(),
+                record_count: 0,
+                file_size_in_bytes: 0,
+                column_sizes: None,
+                value_counts: None,
+                null_value_counts: None,
+                nan_value_counts: None,
+                distinct_counts: None,
+                lower_bounds: None,
+                upper_bounds: None,
+                key_metadata: None,
+                split_offsets: None,
+                equality_ids: None,
+                sort_order_id: None,
+                partition,
+            },
+            sequence_number,
+            spec_id: 0,
+            partition_spec: Arc::new(partition_spec),
+        }
+    }
+
+    fn position_batch(rows: &[(&str, i64)]) -> RecordBatch {
+        RecordBatch::try_new(
+            position_delete_schema(),
+            vec![
+                Arc::new(StringArray::from_iter_values(rows.iter().map(|r| r.0))),
+                Arc::new(Int64Array::from_iter_values(rows.iter().map(|r| r.1))),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn data_batch(ids: Vec<i64>, names: Vec<Option<&str>>) -> RecordBatch {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from(ids)),
+                Arc::new(StringArray::from(names)),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn ids(batch: &RecordBatch) -> Vec<i64> {
+        batch
+            .column(0)
+            .as_primitive::<Int64Type>()
+            .values()
+            .to_vec()
+    }
+
+    #[test]
+    fn position_deletes_across_batches() {
+        let mut deletes = Deletes::default();
+        deletes.push_position(
+            PositionDeleteFile::try_new(
+                1,
+                &[position_batch(&[
+                    ("a.parquet", 1),
+                    ("a.parquet", 4),
+                    ("b.parquet", 0),
+                ])],
+            )
+            .unwrap(),
+        );
+
+        let file_deletes = deletes
+            .for_file(&scan_file("a.parquet", 1, Vec::new()))
+            .unwrap();
+
+        let first = data_batch(vec![0, 1, 2], vec![None; 3]);
+        let second = data_batch(vec![3, 4, 5], vec![None; 3]);
+        assert_eq!(vec![0, 2], ids(&file_deletes.apply(&first, 0).unwrap()));
+        assert_eq!(vec![3, 5], ids(&file_deletes.apply(&second, 3).unwrap()));
+
+        assert!(deletes
+            .for_file(&scan_file("c.parquet", 1, Vec::new()))
+            .is_none());
+    }
+
+    #[test]
+    fn position_deletes_sequence_numbers() {
+        let mut deletes = Deletes::default();
+        deletes.push_position(
+            PositionDeleteFile::try_new(2, &[position_batch(&[("a.parquet", 0)])]).unwrap(),
+        );
+
+        // Deletes apply to data files with the same or an older sequence
+        // number.
+        assert!(deletes
+            .for_file(&scan_file("a.parquet", 1, Vec::new()))
+            .is_some());
+        assert!(deletes
+            .for_file(&scan_file("a.parquet", 2, Vec::new()))
+            .is_some());
+        assert!(deletes
+            .for_file(&scan_file("a.parquet", 3, Vec::new()))
+            .is_none());
+    }
+
+    #[test]
+    fn equality_deletes() {
+        let delete_file = scan_file("deletes.parquet", 2, Vec::new());
+        let delete_batch = data_batch(vec![2, 3], vec![Some("b"), None]);
+
+        let mut deletes = Deletes::default();
+        deletes.push_equality(EqualityDeleteFile::try_new(&delete_file, &[delete_batch]).unwrap());
+
+        // Only applies to data files with older sequence numbers.
+        assert!(deletes
+            .for_file(&scan_file("a.parquet", 2, Vec::new()))
+            .is_none());
+        let file_deletes = deletes
+            .for_file(&scan_file("a.parquet", 1, Vec::new()))
+            .unwrap();
+
+        // Nulls are equal to nulls.
+        let batch = data_batch(
+            vec![1, 2, 2, 3, 3],
+            vec![Some("a"), Some("b"), Some("c"), None, Some("d")],
+        );
+        assert_eq!(vec![1, 2, 3], ids(&file_deletes.apply(&batch, 0).unwrap()));
+    }
+
+    #[test]
+    fn equality_deletes_partitions() {
+        let delete_file = scan_file("deletes.parquet", 2, vec![Value::String("a".to_string())]);
+        let delete_batch = data_batch(vec![1], vec![Some("a")]);
+
+        let mut deletes = Deletes::default();
+        deletes.push_equality(EqualityDeleteFile::try_new(&delete_file, &[delete_batch]).unwrap());
+
+        assert!(deletes
+            .for_file(&scan_file(
+                "a.parquet",
+                1,
+                vec![Value::String("a".to_string())]
+            ))
+            .is_some());
+        assert!(deletes
+            .for_file(&scan_file(
+                "b.parquet",
+                1,
+                vec![Value::String("b".to_string())]
+            ))
+            .is_none());
+    }
+}
@@ -18,6 +18,31 @@ pub enum IcebergError {
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
 
+    #[error(transparent)]
+    Avro(#[from] apache_avro::Error),
+
+    #[error(transparent)]
+    Parquet(#[from] datafusion::parquet::errors::ParquetError),
+
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+
+    #[error(transparent)]
+    UrlParse(#[from] url::ParseError),
+
+    #[error(transparent)]
+    Common(#[from] crate::common::errors::DatasourceCommonError),
+
+    #[error(transparent)]
+    LakeStorageOptions(#[from] crate::lake::LakeStorageOptionsError),
+
+    #[error("Error from iceberg REST catalog: {typ} ({code}): {message}")]
+    RestCatalog {
+        code: u16,
+        typ: String,
+        message: String,
+    },
+
     #[error("{0}")]
     Static(&'static str),
 }
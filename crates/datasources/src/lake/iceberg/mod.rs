@@ -1,4 +1,8 @@
+pub mod catalog;
 pub mod errors;
 pub mod table;
 
+mod deletes;
+mod pruning;
 mod spec;
+mod writer;
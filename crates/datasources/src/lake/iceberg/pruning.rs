@@ -0,0 +1,686 @@
+//! Pruning manifests and data files using filters on the table.
+//!
+//! Manifest lists record the range of partition values for each manifest, and
+//! manifests record column bounds and partition values for each data file.
+//! Both are turned into column statistics for datafusion's pruning predicate,
+//! letting scans skip manifests and data files that can't contain any rows
+//! matching the filters.
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use apache_avro::types::Value;
+use chrono::NaiveDate;
+use datafusion::arrow::array::{ArrayRef, BooleanArray, UInt64Array};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::SchemaRef as ArrowSchemaRef;
+use datafusion::common::{Column, ToDFSchema};
+use datafusion::error::Result as DataFusionResult;
+use datafusion::logical_expr::utils::conjunction;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_expr::execution_props::ExecutionProps;
+use datafusion::physical_expr::{create_physical_expr, PhysicalExpr};
+use datafusion::physical_optimizer::pruning::{PruningPredicate, PruningStatistics};
+use datafusion::scalar::ScalarValue;
+
+use super::spec::{
+    decode_single_value,
+    AnyType,
+    ManifestListEntry,
+    PartitionField,
+    PartitionSpec,
+    PrimitiveType,
+    Schema,
+    Transform,
+};
+use super::table::ScanFile;
+use crate::common::unqualified;
+
+const MICROS_PER_HOUR: i64 = 60 * 60 * 1_000_000;
+const MICROS_PER_DAY: i64 = 24 * MICROS_PER_HOUR;
+
+/// Prunes manifests and data files using filters on the table.
+#[derive(Debug)]
+pub(crate) struct FilePruner {
+    predicate: PruningPredicate,
+    schema: ArrowSchemaRef,
+    /// Top-level primitive fields in the table's schema, keyed by field id.
+    fields: HashMap<i32, (String, PrimitiveType)>,
+}
+
+impl FilePruner {
+    /// Create a pruner for the provided filters.
+    ///
+    /// Returns `None` if none of the filters can be used for pruning.
+    pub(crate) fn try_new(
+        filters: &[Expr],
+        schema: &Schema,
+        arrow_schema: ArrowSchemaRef,
+    ) -> Option<FilePruner> {
+        let expr = unqualified(conjunction(filters.iter().cloned())?).ok()?;
+        let df_schema = arrow_schema.clone().to_dfschema().ok()?;
+        let expr = create_physical_expr(&expr, &df_schema, &ExecutionProps::new()).ok()?;
+        let predicate = PruningPredicate::try_new(expr, arrow_schema.clone()).ok()?;
+        if predicate.always_true() {
+            return None;
+        }
+
+        let fields = schema
+            .fields
+            .iter()
+            .filter_map(|f| match &f.r#type {
+                AnyType::Primitive(typ) => Some((f.id, (f.name.clone(), *typ))),
+                _ => None,
+            })
+            .collect();
+
+        Some(FilePruner {
+            predicate,
+            schema: arrow_schema,
+            fields,
+        })
+    }
+
+    /// Get the physical expression for the filters used for pruning.
+    pub(crate) fn filter(&self) -> &Arc<dyn PhysicalExpr> {
+        self.predicate.orig_expr()
+    }
+
+    /// Prune manifests using the partition summaries in the manifest list.
+    ///
+    /// Returns a boolean for each entry indicating if the manifest should be
+    /// read.
+    pub(crate) fn prune_manifests(
+        &self,
+        entries: &[ManifestListEntry],
+        specs: &[PartitionSpec],
+    ) -> Vec<bool> {
+        let mut stats = ContainerStatistics::new(self.schema.clone(), entries.len());
+
+        for (idx, entry) in entries.iter().enumerate() {
+            let spec = match specs.iter().find(|s| s.spec_id == entry.partition_spec_id) {
+                Some(spec) => spec,
+                None => continue,
+            };
+
+            for (field, summary) in spec.fields.iter().zip(&entry.partitions) {
+                let (name, typ) = match self.fields.get(&field.source_id) {
+                    Some(field) => field,
+                    None => continue,
+                };
+
+                // All transforms produce nulls for null inputs.
+                if !summary.contains_null {
+                    stats.set_null_count(idx, name, 0);
+                }
+
+                let decode = |bs: &Vec<u8>| {
+                    let result_type = match field.transform {
+                        Transform::Identity | Transform::Truncate(_) | Transform::Void => *typ,
+                        _ => PrimitiveType::Int,
+                    };
+                    decode_single_value(&result_type, bs).ok()
+                };
+                let lower = summary.lower_bound.as_ref().and_then(decode);
+                let upper = summary.upper_bound.as_ref().and_then(decode);
+
+                let (min, max) = source_bounds(field.transform, typ, lower, upper);
+                stats.set_bounds(idx, name, min, max);
+            }
+        }
+
+        self.prune(&stats)
+    }
+
+    /// Prune data files using the column bounds and partition values in the
+    /// manifests.
+    ///
+    /// Returns a boolean for each file indicating if the file should be
+    /// scanned.
+    pub(crate) fn prune_data_files(&self, files: &[ScanFile]) -> Vec<bool> {
+        let mut stats = ContainerStatistics::new(self.schema.clone(), files.len());
+
+        for (idx, scan_file) in files.iter().enumerate() {
+            let file = &scan_file.file;
+
+            let nan_counts: HashMap<_, _> = file
+                .nan_value_counts
+                .iter()
+                .flatten()
+                .map(|ent| (ent.key, ent.value))
+                .collect();
+
+            // Bounds for floating point columns don't include NaNs, which
+            // compare greater than all other values during execution. Only
+            // use them if we know there aren't any NaNs.
+            let field_with_bounds = |key: &i32| {
+                let (name, typ) = self.fields.get(key)?;
+                if matches!(typ, PrimitiveType::Float | PrimitiveType::Double)
+                    && nan_counts.get(key) != Some(&0)
+                {
+                    return None;
+                }
+                Some((name, typ))
+            };
+
+            for ent in file.lower_bounds.iter().flatten() {
+                if let Some((name, typ)) = field_with_bounds(&ent.key) {
+                    let min = decode_single_value(typ, &ent.value).ok();
+                    stats.set_bounds(idx, name, min, None);
+                }
+            }
+            for ent in file.upper_bounds.iter().flatten() {
+                if let Some((name, typ)) = field_with_bounds(&ent.key) {
+                    let max = decode_single_value(typ, &ent.value).ok();
+                    stats.set_bounds(idx, name, None, max);
+                }
+            }
+            for ent in file.null_value_counts.iter().flatten() {
+                if let Some((name, _)) = self.fields.get(&ent.key) {
+                    stats.set_null_count(idx, name, ent.value as u64);
+                }
+            }
+
+            // Partition values fill in anything missing from the column
+            // bounds, e.g. when the writer doesn't collect metrics.
+            for (field, value) in scan_file.partition_spec.iter().zip(&file.partition) {
+                let (name, typ) = match self.fields.get(&field.source_id) {
+                    Some(field) => field,
+                    None => continue,
+                };
+
+                if matches!(value, Value::Null) {
+                    if field.transform == Transform::Identity {
+                        stats.set_null_count(idx, name, file.record_count as u64);
+                    }
+                    continue;
+                }
+
+                let value = partition_value_to_scalar(field, typ, value);
+                let (min, max) = source_bounds(field.transform, typ, value.clone(), value);
+                stats.set_bounds(idx, name, min, max);
+            }
+        }
+
+        self.prune(&stats)
+    }
+
+    fn prune(&self, stats: &ContainerStatistics) -> Vec<bool> {
+        // Errors during pruning just mean we need to read everything.
+        self.predicate
+            .prune(stats)
+            .unwrap_or_else(|_| vec![true; stats.num_containers])
+    }
+}
+
+/// Statistics for a single column across all containers.
+#[derive(Debug, Clone)]
+struct ColumnStatistics {
+    min: Vec<Option<ScalarValue>>,
+    max: Vec<Option<ScalarValue>>,
+    null_counts: Vec<Option<u64>>,
+}
+
+/// Column statistics for a set of containers, where a container is either a
+/// manifest or a data file.
+#[derive(Debug)]
+struct ContainerStatistics {
+    schema: ArrowSchemaRef,
+    num_containers: usize,
+    columns: HashMap<String, ColumnStatistics>,
+}
+
+impl ContainerStatistics {
+    fn new(schema: ArrowSchemaRef, num_containers: usize) -> Self {
+        ContainerStatistics {
+            schema,
+            num_containers,
+            columns: HashMap::new(),
+        }
+    }
+
+    fn column_mut(&mut self, name: &str) -> &mut ColumnStatistics {
+        let n = self.num_containers;
+        self.columns
+            .entry(name.to_string())
+            .or_insert_with(|| ColumnStatistics {
+                min: vec![None; n],
+                max: vec![None; n],
+                null_counts: vec![None; n],
+            })
+    }
+
+    /// Set bounds for a column in a container. Bounds that have already been
+    /// set are kept.
+    fn set_bounds(
+        &mut self,
+        container: usize,
+        name: &str,
+        min: Option<ScalarValue>,
+        max: Option<ScalarValue>,
+    ) {
+        let col = self.column_mut(name);
+        if col.min[container].is_none() {
+            col.min[container] = min;
+        }
+        if col.max[container].is_none() {
+            col.max[container] = max;
+        }
+    }
+
+    fn set_null_count(&mut self, container: usize, name: &str, count: u64) {
+        let col = self.column_mut(name);
+        if col.null_counts[container].is_none() {
+            col.null_counts[container] = Some(count);
+        }
+    }
+
+    /// Build an array of bounds for a column using the column's type in the
+    /// table schema. Unknown bounds are null.
+    fn bounds_array(&self, values: &[Option<ScalarValue>], column: &Column) -> Option<ArrayRef> {
+        let field = self.schema.field_with_name(&column.name).ok()?;
+        let data_type = field.data_type();
+
+        let build = || -> DataFusionResult<ArrayRef> {
+            let values = values
+                .iter()
+                .map(|v| match v {
+                    Some(v) => Ok(v.clone()),
+                    None => ScalarValue::try_from(data_type),
+                })
+                .collect::<DataFusionResult<Vec<_>>>()?;
+            let arr = ScalarValue::iter_to_array(values)?;
+            if arr.data_type() == data_type {
+                Ok(arr)
+            } else {
+                Ok(cast(&arr, data_type)?)
+            }
+        };
+
+        build().ok()
+    }
+}
+
+impl PruningStatistics for ContainerStatistics {
+    fn min_values(&self, column: &Column) -> Option<ArrayRef> {
+        let stats = self.columns.get(&column.name)?;
+        self.bounds_array(&stats.min, column)
+    }
+
+    fn max_values(&self, column: &Column) -> Option<ArrayRef> {
+        let stats = self.columns.get(&column.name)?;
+        self.bounds_array(&stats.max, column)
+    }
+
+    fn num_containers(&self) -> usize {
+        self.num_containers
+    }
+
+    fn null_counts(&self, column: &Column) -> Option<ArrayRef> {
+        let stats = self.columns.get(&column.name)?;
+        Some(Arc::new(UInt64Array::from(stats.null_counts.clone())))
+    }
+
+    fn contained(&self, _column: &Column, _values: &HashSet<ScalarValue>) -> Option<BooleanArray> {
+        None
+    }
+}
+
+/// Convert a partition value from a manifest entry into a scalar.
+///
+/// Values for identity and truncate transforms use the source column's type,
+/// all other transforms produce ints.
+fn partition_value_to_scalar(
+    field: &PartitionField,
+    source_type: &PrimitiveType,
+    value: &Value,
+) -> Option<ScalarValue> {
+    if !matches!(
+        field.transform,
+        Transform::Identity | Transform::Truncate(_)
+    ) {
+        return match value {
+            Value::Int(v) | Value::Date(v) => Some(ScalarValue::Int32(Some(*v))),
+            Value::Long(v) => Some(ScalarValue::Int64(Some(*v))),
+            _ => None,
+        };
+    }
+
+    Some(match (source_type, value) {
+        (PrimitiveType::Boolean, Value::Boolean(v)) => ScalarValue::Boolean(Some(*v)),
+        (PrimitiveType::Int, Value::Int(v)) => ScalarValue::Int32(Some(*v)),
+        (PrimitiveType::Long, Value::Int(v)) => ScalarValue::Int64(Some(*v as i64)),
+        (PrimitiveType::Long, Value::Long(v)) => ScalarValue::Int64(Some(*v)),
+        (PrimitiveType::Float, Value::Float(v)) => ScalarValue::Float32(Some(*v)),
+        (PrimitiveType::Double, Value::Float(v)) => ScalarValue::Float64(Some(*v as f64)),
+        (PrimitiveType::Double, Value::Double(v)) => ScalarValue::Float64(Some(*v)),
+        (PrimitiveType::Date, Value::Date(v) | Value::Int(v)) => ScalarValue::Date32(Some(*v)),
+        (
+            PrimitiveType::Time | PrimitiveType::Timestamp | PrimitiveType::Timestamptz,
+            Value::TimeMicros(v) | Value::TimestampMicros(v) | Value::Long(v),
+        ) => ScalarValue::TimestampMicrosecond(Some(*v), None),
+        (PrimitiveType::String, Value::String(v)) => ScalarValue::Utf8(Some(v.clone())),
+        (PrimitiveType::Binary, Value::Bytes(v)) => ScalarValue::Binary(Some(v.clone())),
+        (PrimitiveType::Fixed(l), Value::Fixed(_, v)) => {
+            ScalarValue::FixedSizeBinary(*l as i32, Some(v.clone()))
+        }
+        (PrimitiveType::Decimal { .. }, Value::Bytes(v) | Value::Fixed(_, v)) => {
+            decode_single_value(source_type, v).ok()?
+        }
+        _ => return None,
+    })
+}
+
+/// Get the bounds of a source column from the bounds of a partition field.
+///
+/// Transforms are monotonic, so a range of partition values maps to a range
+/// of source values. Bounds that can't be determined are `None`.
+fn source_bounds(
+    transform: Transform,
+    source_type: &PrimitiveType,
+    lower: Option<ScalarValue>,
+    upper: Option<ScalarValue>,
+) -> (Option<ScalarValue>, Option<ScalarValue>) {
+    match transform {
+        Transform::Identity => (lower, upper),
+        Transform::Truncate(width) => {
+            // Truncated values are the lower bound for the values in the
+            // partition. Only integers have a known upper bound.
+            let width = width as i64;
+            let upper = match upper {
+                Some(ScalarValue::Int32(Some(v))) => i32::try_from(width - 1)
+                    .ok()
+                    .and_then(|w| v.checked_add(w))
+                    .map(|v| ScalarValue::Int32(Some(v))),
+                Some(ScalarValue::Int64(Some(v))) => v
+                    .checked_add(width - 1)
+                    .map(|v| ScalarValue::Int64(Some(v))),
+                _ => None,
+            };
+            (lower, upper)
+        }
+        Transform::Year | Transform::Month | Transform::Day | Transform::Hour => {
+            let range = |v: Option<ScalarValue>| {
+                let v = match v? {
+                    ScalarValue::Int32(Some(v)) => v as i64,
+                    ScalarValue::Int64(Some(v)) => v,
+                    _ => return None,
+                };
+                temporal_range(transform, source_type, v)
+            };
+            (
+                range(lower).map(|(lo, _)| lo),
+                range(upper).map(|(_, hi)| hi),
+            )
+        }
+        Transform::Bucket(_) | Transform::Void => (None, None),
+    }
+}
+
+/// Get the range of source values covered by a single value produced by a
+/// temporal transform.
+fn temporal_range(
+    transform: Transform,
+    source_type: &PrimitiveType,
+    value: i64,
+) -> Option<(ScalarValue, ScalarValue)> {
+    // Days since the epoch covered by the value, inclusive.
+    let days = match transform {
+        Transform::Year => Some((
+            months_to_days(value.checked_mul(12)?)?,
+            months_to_days(value.checked_add(1)?.checked_mul(12)?)? - 1,
+        )),
+        Transform::Month => Some((
+            months_to_days(value)?,
+            months_to_days(value.checked_add(1)?)? - 1,
+        )),
+        Transform::Day => Some((value, value)),
+        Transform::Hour => None,
+        _ => return None,
+    };
+
+    match source_type {
+        PrimitiveType::Date => {
+            let (lo, hi) = days?;
+            Some((
+                ScalarValue::Date32(Some(i32::try_from(lo).ok()?)),
+                ScalarValue::Date32(Some(i32::try_from(hi).ok()?)),
+            ))
+        }
+        PrimitiveType::Timestamp | PrimitiveType::Timestamptz => {
+            let (start, end, unit) = match days {
+                Some((lo, hi)) => (lo, hi, MICROS_PER_DAY),
+                None => (value, value, MICROS_PER_HOUR),
+            };
+            let lo = start.checked_mul(unit)?;
+            let hi = end.checked_add(1)?.checked_mul(unit)? - 1;
+            Some((
+                ScalarValue::TimestampMicrosecond(Some(lo), None),
+                ScalarValue::TimestampMicrosecond(Some(hi), None),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Get the number of days since the epoch for the first day of a month, with
+/// months counted from the epoch.
+fn months_to_days(months: i64) -> Option<i64> {
+    let year = i32::try_from(1970 + months.div_euclid(12)).ok()?;
+    let month = months.rem_euclid(12) as u32 + 1;
+    let date = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1)?;
+    Some(date.signed_duration_since(epoch).num_days())
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::logical_expr::{col, lit};
+
+    use super::*;
+    use crate::lake::iceberg::spec::{BinaryEntry, DataFile, FieldSummary, StructField};
+
+    fn test_schema() -> Schema {
+        let field = |id: i32, name: &str, typ: PrimitiveType| StructField {
+            id,
+            name: name.to_string(),
+            required: false,
+            r#type: AnyType::Primitive(typ),
+            doc: None,
+            initial_default: None,
+            write_default: None,
+        };
+        Schema {
+            schema_id: 0,
+            identifier_field_ids: None,
+            fields: vec![
+                field(1, "id", PrimitiveType::Long),
+                field(2, "mode", PrimitiveType::String),
+                field(3, "ts", PrimitiveType::Timestamp),
+            ],
+        }
+    }
+
+    fn test_pruner(filters: &[Expr]) -> FilePruner {
+        let schema = test_schema();
+        let arrow_schema = Arc::new(schema.to_arrow_schema().unwrap());
+        FilePruner::try_new(filters, &schema, arrow_schema).unwrap()
+    }
+
+    fn data_file(bounds: Option<(i64, i64)>, partition: Vec<Value>) -> ScanFile {
+        let entry = |v: i64| BinaryEntry {
+            key: 1,
+            value: v.to_le_bytes().to_vec(),
+        };
+        ScanFile {
+            file: DataFile {
+                content: 0,
+                file_path: "data.parquet".to_string(),
+                file_format: "PARQUET".to_string(),
+                record_count: 10,
+                file_size_in_bytes: 100,
+                column_sizes: None,
+                value_counts: None,
+                null_value_counts: None,
+                nan_value_counts: None,
+                distinct_counts: None,
+                lower_bounds: bounds.map(|(lo, _)| vec![entry(lo)]),
+                upper_bounds: bounds.map(|(_, hi)| vec![entry(hi)]),
+                key_metadata: None,
+                split_offsets: None,
+                equality_ids: None,
+                sort_order_id: None,
+                partition,
+            },
+            sequence_number: 0,
+            spec_id: 0,
+            partition_spec: Arc::new(vec![PartitionField {
+                source_id: 2,
+                field_id: 1000,
+                name: "mode".to_string(),
+                transform: Transform::Identity,
+            }]),
+        }
+    }
+
+    #[test]
+    fn no_pruner_for_unusable_filters() {
+        let schema = test_schema();
+        let arrow_schema = Arc::new(schema.to_arrow_schema().unwrap());
+        assert!(FilePruner::try_new(&[], &schema, arrow_schema.clone()).is_none());
+        assert!(FilePruner::try_new(&[col("missing").eq(lit(1))], &schema, arrow_schema).is_none());
+    }
+
+    #[test]
+    fn prune_data_files_by_bounds() {
+        let pruner = test_pruner(&[col("id").gt(lit(15_i64))]);
+        let files = vec![
+            data_file(Some((1, 10)), Vec::new()),
+            data_file(Some((11, 20)), Vec::new()),
+            data_file(None, Vec::new()),
+        ];
+
+        assert_eq!(vec![false, true, true], pruner.prune_data_files(&files));
+    }
+
+    #[test]
+    fn prune_data_files_by_partition() {
+        let pruner = test_pruner(&[col("mode").eq(lit("RAIL"))]);
+        let files = vec![
+            data_file(None, vec![Value::String("AIR".to_string())]),
+            data_file(None, vec![Value::String("RAIL".to_string())]),
+        ];
+
+        assert_eq!(vec![false, true], pruner.prune_data_files(&files));
+    }
+
+    #[test]
+    fn prune_manifests_by_partition_summary() {
+        let pruner = test_pruner(&[col("mode").eq(lit("RAIL"))]);
+        let spec = PartitionSpec {
+            spec_id: 0,
+            fields: vec![PartitionField {
+                source_id: 2,
+                field_id: 1000,
+                name: "mode".to_string(),
+                transform: Transform::Identity,
+            }],
+        };
+        let entry = |lower: &str, upper: &str| ManifestListEntry {
+            manifest_path: "manifest.avro".to_string(),
+            manifest_length: 100,
+            partition_spec_id: 0,
+            content: 0,
+            sequence_number: 0,
+            min_sequence_number: 0,
+            added_snapshot_id: 0,
+            added_files_count: 0,
+            existing_files_count: 0,
+            deleted_files_count: 0,
+            added_rows_count: 0,
+            existing_rows_count: 0,
+            deleted_rows_count: 0,
+            partitions: vec![FieldSummary {
+                contains_null: false,
+                contains_nan: false,
+                lower_bound: Some(lower.as_bytes().to_vec()),
+                upper_bound: Some(upper.as_bytes().to_vec()),
+            }],
+            key_metadata: None,
+        };
+        let entries = vec![entry("AIR", "MAIL"), entry("RAIL", "TRUCK")];
+
+        assert_eq!(vec![false, true], pruner.prune_manifests(&entries, &[spec]));
+    }
+
+    #[test]
+    fn temporal_transform_bounds() {
+        // 2023-01-01 is 19358 days since the epoch, 2023 is 53 years since.
+        let (lo, hi) = temporal_range(Transform::Year, &PrimitiveType::Date, 53).unwrap();
+        assert_eq!(ScalarValue::Date32(Some(19358)), lo);
+        assert_eq!(ScalarValue::Date32(Some(19358 + 364)), hi);
+
+        // Month 0 is 1970-01.
+        let (lo, hi) = temporal_range(Transform::Month, &PrimitiveType::Timestamp, 0).unwrap();
+        assert_eq!(ScalarValue::TimestampMicrosecond(Some(0), None), lo);
+        assert_eq!(
+            ScalarValue::TimestampMicrosecond(Some(31 * MICROS_PER_DAY - 1), None),
+            hi
+        );
+
+        let (lo, hi) = temporal_range(Transform::Hour, &PrimitiveType::Timestamp, 2).unwrap();
+        assert_eq!(
+            ScalarValue::TimestampMicrosecond(Some(2 * MICROS_PER_HOUR), None),
+            lo
+        );
+        assert_eq!(
+            ScalarValue::TimestampMicrosecond(Some(3 * MICROS_PER_HOUR - 1), None),
+            hi
+        );
+
+        assert!(temporal_range(Transform::Hour, &PrimitiveType::Date, 2).is_none());
+    }
+
+    #[test]
+    fn truncate_transform_bounds() {
+        let (lo, hi) = source_bounds(
+            Transform::Truncate(10),
+            &PrimitiveType::Long,
+            Some(ScalarValue::Int64(Some(20))),
+            Some(ScalarValue::Int64(Some(40))),
+        );
+        assert_eq!(Some(ScalarValue::Int64(Some(20))), lo);
+        assert_eq!(Some(ScalarValue::Int64(Some(49))), hi);
+
+        let (lo, hi) = source_bounds(
+            Transform::Truncate(2),
+            &PrimitiveType::String,
+            Some(ScalarValue::Utf8(Some("ab".to_string()))),
+            Some(ScalarValue::Utf8(Some("cd".to_string()))),
+        );
+        assert_eq!(Some(ScalarValue::Utf8(Some("ab".to_string()))), lo);
+        assert_eq!(None, hi);
+    }
+
+    #[test]
+    fn partition_values_use_source_type() {
+        let field = PartitionField {
+            source_id: 1,
+            field_id: 1000,
+            name: "id".to_string(),
+            transform: Transform::Identity,
+        };
+        assert_eq!(
+            Some(ScalarValue::Int64(Some(3))),
+            partition_value_to_scalar(&field, &PrimitiveType::Long, &Value::Int(3))
+        );
+
+        let field = PartitionField {
+            transform: Transform::Day,
+            ..field
+        };
+        assert_eq!(
+            Some(ScalarValue::Int32(Some(19358))),
+            partition_value_to_scalar(&field, &PrimitiveType::Timestamp, &Value::Date(19358))
+        );
+    }
+}
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
 
+use apache_avro::types::Value;
 use apache_avro::{from_value, Reader};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, Bytes};
@@ -14,7 +15,7 @@ use crate::lake::iceberg::spec::PartitionSpec;
 /// actual manifest.
 #[serde_as]
 #[allow(dead_code)]
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ManifestListEntry {
     pub manifest_path: String,
     pub manifest_length: i64,
@@ -35,24 +36,24 @@ pub struct ManifestListEntry {
     /// > Number of entries in the manifest that have status ADDED (1), when
     /// > null this is assumed to be non-zero
     // TODO: Remove default and deserialize into something more meaningful.
-    #[serde(default)]
+    #[serde(default, alias = "added_data_files_count")]
     pub added_files_count: i32,
     /// > Number of entries in the manifest that have status EXISTING (0), when
     /// > null this is assumed to be non-zero
-    #[serde(default)]
+    #[serde(default, alias = "existing_data_files_count")]
     pub existing_files_count: i32,
     /// > Number of entries in the manifest that have status DELETED (2), when
     /// > null this is assumed to be non-zero
-    #[serde(default)]
+    #[serde(default, alias = "deleted_data_files_count")]
     pub deleted_files_count: i32,
     /// > Number of rows in all of files in the manifest that have status ADDED,
     /// > when null this is assumed to be non-zero
     #[serde(default)]
-    pub added_rows_count: i32,
+    pub added_rows_count: i64,
     /// > Number of rows in all of files in the manifest that have status
     /// > EXISTING, when null this is assumed to be non-zero
     #[serde(default)]
-    pub existing_rows_count: i32,
+    pub existing_rows_count: i64,
     pub deleted_rows_count: i64,
     pub partitions: Vec<FieldSummary>,
     #[serde_as(as = "Option<Bytes>")]
@@ -95,7 +96,7 @@ impl ManifestList {
 
 #[serde_as]
 #[allow(dead_code)]
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FieldSummary {
     pub contains_null: bool,
     pub contains_nan: bool,
@@ -216,11 +217,12 @@ impl Manifest {
             let value = value.map_err(|e| {
                 IcebergError::DataInvalid(format!("failed to get value for manifest entry: {e}"))
             })?;
-            let entry: ManifestEntry = from_value(&value).map_err(|e| {
+            let mut entry: ManifestEntry = from_value(&value).map_err(|e| {
                 IcebergError::DataInvalid(format!(
                     "failed to deserialize value for manifest entry: {e}"
                 ))
             })?;
+            entry.data_file.partition = partition_values(&value);
             entries.push(entry);
         }
 
@@ -228,6 +230,31 @@ impl Manifest {
     }
 }
 
+/// Get the partition values from a raw manifest entry.
+///
+/// The partition struct's fields depend on the manifest's partition spec, so
+/// the values are pulled out of the raw entry instead of being deserialized
+/// along with the rest of the data file.
+fn partition_values(entry: &Value) -> Vec<Value> {
+    fn field<'a>(value: &'a Value, name: &str) -> Option<&'a Value> {
+        match value {
+            Value::Record(fields) => fields.iter().find(|(n, _)| n == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    match field(entry, "data_file").and_then(|f| field(f, "partition")) {
+        Some(Value::Record(fields)) => fields
+            .iter()
+            .map(|(_, value)| match value {
+                Value::Union(_, value) => value.as_ref().clone(),
+                value => value.clone(),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
 pub enum ManifestEntryStatus {
     #[default]
@@ -240,6 +267,10 @@ impl ManifestEntryStatus {
     pub fn is_deleted(&self) -> bool {
         matches!(self, Self::Deleted)
     }
+
+    pub fn is_added(&self) -> bool {
+        matches!(self, Self::Added)
+    }
 }
 
 impl TryFrom<i32> for ManifestEntryStatus {
@@ -292,18 +323,47 @@ pub struct DataFile {
     pub split_offsets: Option<Vec<i64>>,
     pub equality_ids: Option<Vec<i32>>,
     pub sort_order_id: Option<i32>,
+    /// Partition values for the file, in the order of the fields in the
+    /// manifest's partition spec.
+    #[serde(skip)]
+    pub partition: Vec<Value>,
+}
+
+/// Content stored in a data file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFileContent {
+    Data,
+    PositionDeletes,
+    EqualityDeletes,
+}
+
+impl TryFrom<i32> for DataFileContent {
+    type Error = IcebergError;
+
+    fn try_from(value: i32) -> Result<Self> {
+        Ok(match value {
+            0 => Self::Data,
+            1 => Self::PositionDeletes,
+            2 => Self::EqualityDeletes,
+            i => {
+                return Err(IcebergError::DataInvalid(format!(
+                    "unknown data file content: {i}"
+                )))
+            }
+        })
+    }
 }
 
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BinaryEntry {
-    key: i32,
+    pub key: i32,
     #[serde_as(as = "Bytes")]
-    value: Vec<u8>,
+    pub value: Vec<u8>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct I64Entry {
-    key: i32,
-    value: i64,
+    pub key: i32,
+    pub value: i64,
 }
@@ -1,9 +1,10 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::str::FromStr;
 
 use once_cell::sync::Lazy;
 use regex::Regex;
-use serde::{de, Deserialize, Deserializer};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 use super::Schema;
 use crate::lake::iceberg::errors::{IcebergError, Result};
@@ -11,12 +12,15 @@ use crate::lake::iceberg::errors::{IcebergError, Result};
 /// On disk table metadata.
 ///
 /// JSON serialization only.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct TableMetadata {
     pub format_version: i32,
     pub table_uuid: String,
     pub location: String,
+    /// Required in v2.
+    #[serde(default)]
+    pub last_sequence_number: i64,
     pub last_updated_ms: i64,
     pub last_column_id: i32,
     pub schemas: Vec<Schema>,
@@ -24,21 +28,111 @@ pub struct TableMetadata {
     pub partition_specs: Vec<PartitionSpec>,
     pub default_spec_id: i32,
     pub last_partition_id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub properties: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub current_snapshot_id: Option<i64>,
+    /// > A map of snapshot references. The map keys are the unique snapshot
+    /// > reference names in the table, and the map values are snapshot
+    /// > reference objects.
+    #[serde(default)]
+    pub refs: HashMap<String, SnapshotReference>,
     pub snapshots: Vec<Snapshot>,
     pub snapshot_log: Vec<SnapshotLog>,
     pub metadata_log: Vec<MetadataLog>,
     pub sort_orders: Vec<SortOrder>,
     pub default_sort_order_id: i32,
-    // TODO: Figure out what this field is for.
-    // refs: Option<HashMap<String, SnapshotReference>>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+impl TableMetadata {
+    /// Get the snapshot that's currently being pointed to by the metadata.
+    ///
+    /// Returns `None` if the table doesn't have any snapshots yet.
+    pub fn current_snapshot(&self) -> Result<Option<&Snapshot>> {
+        // Some writers use -1 to indicate there's no current snapshot.
+        let current_snapshot_id = match self.current_snapshot_id {
+            Some(id) if id != -1 => id,
+            _ => return Ok(None),
+        };
+
+        let snapshot = self
+            .snapshots
+            .iter()
+            .find(|s| s.snapshot_id == current_snapshot_id)
+            .ok_or_else(|| {
+                IcebergError::DataInvalid(format!("Missing snapshot for id: {current_snapshot_id}"))
+            })?;
+
+        Ok(Some(snapshot))
+    }
+
+    /// Get a snapshot by its id.
+    pub fn snapshot_by_id(&self, snapshot_id: i64) -> Result<&Snapshot> {
+        self.snapshots
+            .iter()
+            .find(|s| s.snapshot_id == snapshot_id)
+            .ok_or_else(|| {
+                IcebergError::DataInvalid(format!("Missing snapshot for id: {snapshot_id}"))
+            })
+    }
+
+    /// Get the snapshot that was the current snapshot of the table at the
+    /// given time (milliseconds since epoch).
+    pub fn snapshot_as_of(&self, timestamp_ms: i64) -> Result<&Snapshot> {
+        // The snapshot log records changes to the current snapshot, so it's
+        // used over the snapshot timestamps when available. Snapshots that
+        // were committed but never made current won't be in the log.
+        let snapshot_id = if self.snapshot_log.is_empty() {
+            self.snapshots
+                .iter()
+                .filter(|s| s.timestamp_ms <= timestamp_ms)
+                .max_by_key(|s| s.timestamp_ms)
+                .map(|s| s.snapshot_id)
+        } else {
+            self.snapshot_log
+                .iter()
+                .take_while(|log| log.timestamp_ms <= timestamp_ms)
+                .last()
+                .map(|log| log.snapshot_id)
+        };
+
+        let snapshot_id = snapshot_id.ok_or_else(|| {
+            IcebergError::DataInvalid(format!("No snapshot exists as of {timestamp_ms} ms"))
+        })?;
+
+        self.snapshot_by_id(snapshot_id)
+    }
+
+    /// Get the snapshot pointed to by a branch or tag.
+    pub fn snapshot_by_ref(&self, name: &str) -> Result<&Snapshot> {
+        match self.refs.get(name) {
+            Some(reference) => self.snapshot_by_id(reference.snapshot_id),
+            // > If the current-snapshot-id is set, then main must be a branch
+            // > pointing to it.
+            //
+            // Older metadata files may not include refs at all.
+            None if name == MAIN_BRANCH => self
+                .current_snapshot()?
+                .ok_or_else(|| IcebergError::DataInvalid("Table has no snapshots".to_string())),
+            None => Err(IcebergError::DataInvalid(format!(
+                "Missing snapshot reference: {name}"
+            ))),
+        }
+    }
+}
+
+/// Name of the table's main branch.
+pub const MAIN_BRANCH: &str = "main";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Snapshot {
     pub snapshot_id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_snapshot_id: Option<i64>,
+    /// Required in v2.
+    #[serde(default)]
+    pub sequence_number: i64,
     pub timestamp_ms: i64,
     #[serde(default)]
     pub summary: HashMap<String, String>,
@@ -46,28 +140,48 @@ pub struct Snapshot {
     pub schema_id: i32,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SnapshotReference {
+    pub snapshot_id: i64,
+    pub r#type: SnapshotReferenceType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_snapshots_to_keep: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_snapshot_age_ms: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_ref_age_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SnapshotReferenceType {
+    Branch,
+    Tag,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct SnapshotLog {
     pub snapshot_id: i64,
     pub timestamp_ms: i64,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct MetadataLog {
     pub metadata_file: String,
     pub timestamp_ms: i64,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct PartitionSpec {
     pub spec_id: i32,
     pub fields: Vec<PartitionField>,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub struct PartitionField {
     pub source_id: i32,
@@ -76,14 +190,14 @@ pub struct PartitionField {
     pub transform: Transform,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct SortOrder {
     pub order_id: i32,
     pub fields: Vec<SortField>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct SortField {
     pub transform: Transform,
@@ -92,14 +206,14 @@ pub struct SortField {
     pub null_order: NullOrder,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum SortDirection {
     Asc,
     Desc,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum NullOrder {
     NullsFirst,
@@ -187,6 +301,30 @@ impl FromStr for Transform {
     }
 }
 
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Transform::Identity => write!(f, "identity"),
+            Transform::Year => write!(f, "year"),
+            Transform::Month => write!(f, "month"),
+            Transform::Day => write!(f, "day"),
+            Transform::Hour => write!(f, "hour"),
+            Transform::Void => write!(f, "void"),
+            Transform::Bucket(n) => write!(f, "bucket[{n}]"),
+            Transform::Truncate(n) => write!(f, "truncate[{n}]"),
+        }
+    }
+}
+
+impl Serialize for Transform {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
 impl<'de> Deserialize<'de> for Transform {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -218,6 +356,7 @@ mod tests {
         for t in test_cases {
             let out: Transform = t.0.parse().unwrap();
             assert_eq!(t.1, out);
+            assert_eq!(t.0, out.to_string());
         }
     }
 
@@ -241,4 +380,58 @@ mod tests {
 
         assert_eq!(expected, deserialized);
     }
+
+    #[test]
+    fn test_select_snapshot() {
+        let json = r#"
+            {
+              "format-version": 2,
+              "table-uuid": "9c12d441-03fe-4693-9a96-a0705ddf69c1",
+              "location": "s3://bucket/table",
+              "last-sequence-number": 3,
+              "last-updated-ms": 3000,
+              "last-column-id": 1,
+              "schemas": [
+                {"type": "struct", "schema-id": 0, "fields": [
+                  {"id": 1, "name": "a", "required": false, "type": "long"}
+                ]}
+              ],
+              "current-schema-id": 0,
+              "partition-specs": [{"spec-id": 0, "fields": []}],
+              "default-spec-id": 0,
+              "last-partition-id": 999,
+              "current-snapshot-id": 3,
+              "refs": {
+                "main": {"snapshot-id": 3, "type": "branch"},
+                "v1": {"snapshot-id": 1, "type": "tag"}
+              },
+              "snapshots": [
+                {"snapshot-id": 1, "sequence-number": 1, "timestamp-ms": 1000, "manifest-list": "s1.avro", "schema-id": 0},
+                {"snapshot-id": 2, "parent-snapshot-id": 1, "sequence-number": 2, "timestamp-ms": 2000, "manifest-list": "s2.avro", "schema-id": 0},
+                {"snapshot-id": 3, "parent-snapshot-id": 2, "sequence-number": 3, "timestamp-ms": 3000, "manifest-list": "s3.avro", "schema-id": 0}
+              ],
+              "snapshot-log": [
+                {"snapshot-id": 1, "timestamp-ms": 1000},
+                {"snapshot-id": 2, "timestamp-ms": 2000},
+                {"snapshot-id": 3, "timestamp-ms": 3000}
+              ],
+              "metadata-log": [],
+              "sort-orders": [{"order-id": 0, "fields": []}],
+              "default-sort-order-id": 0
+            }"#;
+
+        let metadata: TableMetadata = serde_json::from_str(json).unwrap();
+
+        assert_eq!(2, metadata.snapshot_by_id(2).unwrap().snapshot_id);
+        metadata.snapshot_by_id(4).unwrap_err();
+
+        assert_eq!(1, metadata.snapshot_as_of(1000).unwrap().snapshot_id);
+        assert_eq!(2, metadata.snapshot_as_of(2999).unwrap().snapshot_id);
+        assert_eq!(3, metadata.snapshot_as_of(5000).unwrap().snapshot_id);
+        metadata.snapshot_as_of(999).unwrap_err();
+
+        assert_eq!(3, metadata.snapshot_by_ref("main").unwrap().snapshot_id);
+        assert_eq!(1, metadata.snapshot_by_ref("v1").unwrap().snapshot_id);
+        metadata.snapshot_by_ref("missing").unwrap_err();
+    }
 }
@@ -24,3 +24,6 @@ pub use metadata::*;
 
 mod manifest;
 pub use manifest::*;
+
+mod values;
+pub use values::*;
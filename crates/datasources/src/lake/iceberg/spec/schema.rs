@@ -1,3 +1,4 @@
+use std::fmt;
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -9,7 +10,8 @@ use datafusion::arrow::datatypes::{
 };
 use once_cell::sync::Lazy;
 use regex::Regex;
-use serde::{de, Deserialize, Deserializer};
+use serde::ser::SerializeStruct;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::lake::iceberg::errors::{IcebergError, Result};
 
@@ -55,6 +57,68 @@ impl TryFrom<PrimitiveType> for DataType {
     }
 }
 
+impl TryFrom<&DataType> for PrimitiveType {
+    type Error = IcebergError;
+
+    fn try_from(value: &DataType) -> Result<Self> {
+        Ok(match value {
+            DataType::Boolean => PrimitiveType::Boolean,
+            DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::UInt8
+            | DataType::UInt16 => PrimitiveType::Int,
+            DataType::Int64 | DataType::UInt32 => PrimitiveType::Long,
+            DataType::Float16 | DataType::Float32 => PrimitiveType::Float,
+            DataType::Float64 => PrimitiveType::Double,
+            DataType::Decimal128(p, s) if *p <= 38 && *s >= 0 => {
+                PrimitiveType::Decimal { p: *p, s: *s as u8 }
+            }
+            DataType::Date32 | DataType::Date64 => PrimitiveType::Date,
+            DataType::Timestamp(_, None) => PrimitiveType::Timestamp,
+            DataType::Timestamp(_, Some(_)) => PrimitiveType::Timestamptz,
+            DataType::Utf8 | DataType::LargeUtf8 => PrimitiveType::String,
+            DataType::Binary | DataType::LargeBinary => PrimitiveType::Binary,
+            DataType::FixedSizeBinary(l) => PrimitiveType::Fixed(*l as usize),
+            other => {
+                return Err(IcebergError::DataInvalid(format!(
+                    "Unsupported type for iceberg table: {other}"
+                )))
+            }
+        })
+    }
+}
+
+impl fmt::Display for PrimitiveType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrimitiveType::Boolean => write!(f, "boolean"),
+            PrimitiveType::Int => write!(f, "int"),
+            PrimitiveType::Long => write!(f, "long"),
+            PrimitiveType::Float => write!(f, "float"),
+            PrimitiveType::Double => write!(f, "double"),
+            PrimitiveType::Decimal { p, s } => write!(f, "decimal({p}, {s})"),
+            PrimitiveType::Date => write!(f, "date"),
+            PrimitiveType::Time => write!(f, "time"),
+            PrimitiveType::Timestamp => write!(f, "timestamp"),
+            PrimitiveType::Timestamptz => write!(f, "timestamptz"),
+            PrimitiveType::String => write!(f, "string"),
+            PrimitiveType::Uuid => write!(f, "uuid"),
+            PrimitiveType::Fixed(l) => write!(f, "fixed[{l}]"),
+            PrimitiveType::Binary => write!(f, "binary"),
+        }
+    }
+}
+
+impl Serialize for PrimitiveType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
 impl<'de> Deserialize<'de> for PrimitiveType {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -146,7 +210,7 @@ impl FromStr for PrimitiveType {
 }
 
 /// Union between primitive and nested types.
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum AnyType {
     Primitive(PrimitiveType),
@@ -168,7 +232,7 @@ impl TryFrom<&AnyType> for DataType {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 #[serde(tag = "type", rename = "list")]
 pub struct ListType {
@@ -190,7 +254,7 @@ impl TryFrom<&ListType> for DataType {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 #[serde(tag = "type", rename = "map")]
 pub struct MapType {
@@ -219,7 +283,7 @@ impl TryFrom<&MapType> for DataType {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 #[serde(tag = "type", rename = "struct")]
 pub struct StructType {
@@ -245,17 +309,20 @@ impl TryFrom<&StructType> for DataType {
 }
 
 /// Fields on a struct.
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub struct StructField {
     pub id: i32,
     pub name: String,
     pub required: bool,
     pub r#type: AnyType,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub doc: Option<String>,
     /// JSON serialized initial value for the field.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub initial_default: Option<String>, // TODO
     /// JSON serialized write default value for the field.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub write_default: Option<String>, // TODO
 }
 
@@ -275,6 +342,35 @@ pub struct Schema {
 }
 
 impl Schema {
+    /// Create a new schema from an arrow schema, assigning field ids starting
+    /// at 1.
+    ///
+    /// Only primitive types are supported.
+    pub fn try_from_arrow(schema_id: i32, schema: &ArrowSchema) -> Result<Schema> {
+        let fields = schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(idx, f)| {
+                Ok(StructField {
+                    id: idx as i32 + 1,
+                    name: f.name().clone(),
+                    required: !f.is_nullable(),
+                    r#type: AnyType::Primitive(f.data_type().try_into()?),
+                    doc: None,
+                    initial_default: None,
+                    write_default: None,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Schema {
+            schema_id,
+            identifier_field_ids: None,
+            fields,
+        })
+    }
+
     pub fn to_arrow_schema(&self) -> Result<ArrowSchema> {
         let fields = self
             .fields
@@ -285,6 +381,24 @@ impl Schema {
     }
 }
 
+impl Serialize for Schema {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Schemas are serialized as structs with the schema id alongside the
+        // fields.
+        let mut state = serializer.serialize_struct("Schema", 4)?;
+        state.serialize_field("type", "struct")?;
+        state.serialize_field("schema-id", &self.schema_id)?;
+        if let Some(ids) = &self.identifier_field_ids {
+            state.serialize_field("identifier-field-ids", ids)?;
+        }
+        state.serialize_field("fields", &self.fields)?;
+        state.end()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -485,4 +599,39 @@ mod tests {
         };
         assert_eq!(expected, deserialized);
     }
+
+    #[test]
+    fn test_serialize_schema_roundtrip() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("id", DataType::Int64, false),
+            ArrowField::new("name", DataType::Utf8, true),
+            ArrowField::new("amount", DataType::Decimal128(15, 2), true),
+            ArrowField::new(
+                "created",
+                DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+                true,
+            ),
+        ]);
+
+        let schema = Schema::try_from_arrow(0, &arrow_schema).unwrap();
+        let json = serde_json::to_string(&schema).unwrap();
+        assert_eq!(
+            r#"{"type":"struct","schema-id":0,"fields":[{"id":1,"name":"id","required":true,"type":"long"},{"id":2,"name":"name","required":false,"type":"string"},{"id":3,"name":"amount","required":false,"type":"decimal(15, 2)"},{"id":4,"name":"created","required":false,"type":"timestamptz"}]}"#,
+            json
+        );
+
+        let deserialized: Schema = serde_json::from_str(&json).unwrap();
+        assert_eq!(schema.fields, deserialized.fields);
+    }
+
+    #[test]
+    fn test_try_from_arrow_unsupported() {
+        let arrow_schema = ArrowSchema::new(vec![ArrowField::new(
+            "items",
+            DataType::List(Arc::new(ArrowField::new("item", DataType::Int32, true))),
+            true,
+        )]);
+
+        Schema::try_from_arrow(0, &arrow_schema).unwrap_err();
+    }
 }
@@ -0,0 +1,265 @@
+use datafusion::scalar::ScalarValue;
+
+use super::PrimitiveType;
+use crate::lake::iceberg::errors::{IcebergError, Result};
+
+/// Deserialize a value stored using the binary single-value serialization.
+///
+/// This is the format used for lower and upper bounds in manifests and
+/// manifest lists.
+///
+/// > Binary single-value serialization can be used to store the lower and upper
+/// > bounds maps of manifest files.
+///
+/// The returned scalar uses the arrow type for the primitive type.
+pub fn decode_single_value(typ: &PrimitiveType, bs: &[u8]) -> Result<ScalarValue> {
+    fn fixed<const N: usize>(typ: &PrimitiveType, bs: &[u8]) -> Result<[u8; N]> {
+        bs.try_into().map_err(|_| {
+            IcebergError::DataInvalid(format!(
+                "Invalid length {} for single value of type {typ:?}",
+                bs.len()
+            ))
+        })
+    }
+
+    Ok(match typ {
+        PrimitiveType::Boolean => ScalarValue::Boolean(Some(fixed::<1>(typ, bs)?[0] != 0)),
+        PrimitiveType::Int => ScalarValue::Int32(Some(i32::from_le_bytes(fixed(typ, bs)?))),
+        // Longs may have been promoted from ints, in which case the value is
+        // still stored using 4 bytes.
+        PrimitiveType::Long if bs.len() == 4 => {
+            ScalarValue::Int64(Some(i32::from_le_bytes(fixed(typ, bs)?) as i64))
+        }
+        PrimitiveType::Long => ScalarValue::Int64(Some(i64::from_le_bytes(fixed(typ, bs)?))),
+        PrimitiveType::Float => ScalarValue::Float32(Some(f32::from_le_bytes(fixed(typ, bs)?))),
+        // Same as above, doubles may have been promoted from floats.
+        PrimitiveType::Double if bs.len() == 4 => {
+            ScalarValue::Float64(Some(f32::from_le_bytes(fixed(typ, bs)?) as f64))
+        }
+        PrimitiveType::Double => ScalarValue::Float64(Some(f64::from_le_bytes(fixed(typ, bs)?))),
+        PrimitiveType::Decimal { p, s } => {
+            // > Stores unscaled value as two’s-complement big-endian binary,
+            // > using the minimum number of bytes for the value
+            if bs.is_empty() || bs.len() > 16 {
+                return Err(IcebergError::DataInvalid(format!(
+                    "Invalid length {} for single value of type {typ:?}",
+                    bs.len()
+                )));
+            }
+            let fill = if bs[0] & 0x80 != 0 { 0xFF } else { 0x00 };
+            let mut buf = [fill; 16];
+            buf[16 - bs.len()..].copy_from_slice(bs);
+            ScalarValue::Decimal128(Some(i128::from_be_bytes(buf)), *p, *s as i8)
+        }
+        PrimitiveType::Date => ScalarValue::Date32(Some(i32::from_le_bytes(fixed(typ, bs)?))),
+        PrimitiveType::Time | PrimitiveType::Timestamp | PrimitiveType::Timestamptz => {
+            ScalarValue::TimestampMicrosecond(Some(i64::from_le_bytes(fixed(typ, bs)?)), None)
+        }
+        PrimitiveType::String => {
+            let s = std::str::from_utf8(bs).map_err(|e| {
+                IcebergError::DataInvalid(format!("Expected utf-8 for string value: {e}"))
+            })?;
+            ScalarValue::Utf8(Some(s.to_string()))
+        }
+        PrimitiveType::Fixed(l) => {
+            if bs.len() != *l {
+                return Err(IcebergError::DataInvalid(format!(
+                    "Invalid length {} for single value of type {typ:?}",
+                    bs.len()
+                )));
+            }
+            ScalarValue::FixedSizeBinary(*l as i32, Some(bs.to_vec()))
+        }
+        PrimitiveType::Binary => ScalarValue::Binary(Some(bs.to_vec())),
+        PrimitiveType::Uuid => {
+            // Uuids are read as strings, comparing the raw bytes against
+            // those wouldn't be meaningful.
+            return Err(IcebergError::Static(
+                "Single values of type uuid are not supported",
+            ));
+        }
+    })
+}
+
+/// Serialize a value using the binary single-value serialization.
+///
+/// Returns `None` for null values since they have no serialized
+/// representation.
+pub fn encode_single_value(value: &ScalarValue) -> Result<Option<Vec<u8>>> {
+    Ok(Some(match value {
+        value if value.is_null() => return Ok(None),
+        ScalarValue::Boolean(Some(v)) => vec![*v as u8],
+        ScalarValue::Int32(Some(v)) | ScalarValue::Date32(Some(v)) => v.to_le_bytes().to_vec(),
+        ScalarValue::Int64(Some(v)) | ScalarValue::TimestampMicrosecond(Some(v), _) => {
+            v.to_le_bytes().to_vec()
+        }
+        ScalarValue::Float32(Some(v)) => v.to_le_bytes().to_vec(),
+        ScalarValue::Float64(Some(v)) => v.to_le_bytes().to_vec(),
+        ScalarValue::Decimal128(Some(v), _, _) => {
+            // Strip redundant sign bytes to get the minimum number of bytes
+            // for the value.
+            let bs = v.to_be_bytes();
+            let mut start = 0;
+            while start < bs.len() - 1 {
+                let redundant = (bs[start] == 0x00 && bs[start + 1] & 0x80 == 0)
+                    || (bs[start] == 0xFF && bs[start + 1] & 0x80 != 0);
+                if !redundant {
+                    break;
+                }
+                start += 1;
+            }
+            bs[start..].to_vec()
+        }
+        ScalarValue::Utf8(Some(v)) => v.as_bytes().to_vec(),
+        ScalarValue::Binary(Some(v)) | ScalarValue::FixedSizeBinary(_, Some(v)) => v.clone(),
+        other => {
+            return Err(IcebergError::DataInvalid(format!(
+                "Unsupported type for single value: {}",
+                other.data_type()
+            )))
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_single_value() {
+        // (type, bytes, expected)
+        let test_cases = vec![
+            (
+                PrimitiveType::Boolean,
+                vec![0x01],
+                ScalarValue::Boolean(Some(true)),
+            ),
+            (
+                PrimitiveType::Int,
+                vec![0xD2, 0x04, 0x00, 0x00],
+                ScalarValue::Int32(Some(1234)),
+            ),
+            (
+                PrimitiveType::Long,
+                (-5_i64).to_le_bytes().to_vec(),
+                ScalarValue::Int64(Some(-5)),
+            ),
+            (
+                PrimitiveType::Long,
+                7_i32.to_le_bytes().to_vec(),
+                ScalarValue::Int64(Some(7)),
+            ),
+            (
+                PrimitiveType::Double,
+                1.5_f64.to_le_bytes().to_vec(),
+                ScalarValue::Float64(Some(1.5)),
+            ),
+            (
+                PrimitiveType::Decimal { p: 15, s: 2 },
+                vec![0x30, 0x39],
+                ScalarValue::Decimal128(Some(12345), 15, 2),
+            ),
+            (
+                PrimitiveType::Decimal { p: 15, s: 2 },
+                vec![0xFF, 0x85],
+                ScalarValue::Decimal128(Some(-123), 15, 2),
+            ),
+            (
+                PrimitiveType::Date,
+                19000_i32.to_le_bytes().to_vec(),
+                ScalarValue::Date32(Some(19000)),
+            ),
+            (
+                PrimitiveType::Timestamptz,
+                1_700_000_000_000_000_i64.to_le_bytes().to_vec(),
+                ScalarValue::TimestampMicrosecond(Some(1_700_000_000_000_000), None),
+            ),
+            (
+                PrimitiveType::String,
+                b"RAIL".to_vec(),
+                ScalarValue::Utf8(Some("RAIL".to_string())),
+            ),
+        ];
+
+        for (typ, bs, expected) in test_cases {
+            let out = decode_single_value(&typ, &bs).unwrap();
+            assert_eq!(expected, out, "type: {typ:?}");
+        }
+    }
+
+    #[test]
+    fn test_decode_single_value_invalid_length() {
+        decode_single_value(&PrimitiveType::Int, &[0x01, 0x02]).unwrap_err();
+        decode_single_value(&PrimitiveType::Fixed(4), &[0x01]).unwrap_err();
+    }
+
+    #[test]
+    fn test_encode_single_value_roundtrip() {
+        // (type, value)
+        let test_cases = vec![
+            (PrimitiveType::Boolean, ScalarValue::Boolean(Some(false))),
+            (PrimitiveType::Int, ScalarValue::Int32(Some(-1234))),
+            (PrimitiveType::Long, ScalarValue::Int64(Some(1 << 40))),
+            (PrimitiveType::Float, ScalarValue::Float32(Some(2.5))),
+            (PrimitiveType::Double, ScalarValue::Float64(Some(-0.25))),
+            (
+                PrimitiveType::Decimal { p: 15, s: 2 },
+                ScalarValue::Decimal128(Some(12345), 15, 2),
+            ),
+            (
+                PrimitiveType::Decimal { p: 15, s: 2 },
+                ScalarValue::Decimal128(Some(-123), 15, 2),
+            ),
+            (
+                PrimitiveType::Decimal { p: 15, s: 2 },
+                ScalarValue::Decimal128(Some(128), 15, 2),
+            ),
+            (
+                PrimitiveType::Decimal { p: 15, s: 2 },
+                ScalarValue::Decimal128(Some(0), 15, 2),
+            ),
+            (PrimitiveType::Date, ScalarValue::Date32(Some(19000))),
+            (
+                PrimitiveType::Timestamp,
+                ScalarValue::TimestampMicrosecond(Some(1_700_000_000_000_000), None),
+            ),
+            (
+                PrimitiveType::String,
+                ScalarValue::Utf8(Some("MAIL".to_string())),
+            ),
+            (PrimitiveType::Binary, ScalarValue::Binary(Some(vec![1, 2]))),
+        ];
+
+        for (typ, value) in test_cases {
+            let bs = encode_single_value(&value).unwrap().unwrap();
+            let out = decode_single_value(&typ, &bs).unwrap();
+            assert_eq!(value, out, "type: {typ:?}");
+        }
+    }
+
+    #[test]
+    fn test_encode_single_value_decimal_min_bytes() {
+        let bs = encode_single_value(&ScalarValue::Decimal128(Some(12345), 15, 2))
+            .unwrap()
+            .unwrap();
+        assert_eq!(vec![0x30, 0x39], bs);
+
+        let bs = encode_single_value(&ScalarValue::Decimal128(Some(-123), 15, 2))
+            .unwrap()
+            .unwrap();
+        assert_eq!(vec![0xFF, 0x85], bs);
+
+        let bs = encode_single_value(&ScalarValue::Decimal128(Some(128), 15, 2))
+            .unwrap()
+            .unwrap();
+        assert_eq!(vec![0x00, 0x80], bs);
+    }
+
+    #[test]
+    fn test_encode_single_value_null() {
+        assert_eq!(
+            None,
+            encode_single_value(&ScalarValue::Int32(None)).unwrap()
+        );
+    }
+}
@@ -1,10 +1,13 @@
 use std::any::Any;
+use std::fmt;
 use std::io::Cursor;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use datafusion::arrow::datatypes::{Schema as ArrowSchema, SchemaRef as ArrowSchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::datasource::file_format::parquet::ParquetFormat;
 use datafusion::datasource::file_format::FileFormat;
 use datafusion::datasource::listing::PartitionedFile;
@@ -15,9 +18,14 @@ use datafusion::execution::context::{SessionState, TaskContext};
 use datafusion::execution::object_store::ObjectStoreUrl;
 use datafusion::logical_expr::{Expr, TableProviderFilterPushDown, TableType};
 use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::insert::{DataSink, FileSinkExec};
+use datafusion::physical_plan::metrics::MetricsSet;
+use datafusion::physical_plan::union::UnionExec;
 use datafusion::physical_plan::{
+    collect,
     DisplayAs,
     DisplayFormatType,
+    Distribution,
     ExecutionPlan,
     Partitioning,
     SendableRecordBatchStream,
@@ -26,14 +34,42 @@ use datafusion::physical_plan::{
 use futures::StreamExt;
 use object_store::path::Path as ObjectPath;
 use object_store::{ObjectMeta, ObjectStore};
-
+use rand::Rng;
+use uuid::Uuid;
+
+use super::deletes::{
+    position_delete_schema,
+    Deletes,
+    EqualityDeleteFile,
+    IcebergDeleteExec,
+    PositionDeleteFile,
+};
+use super::pruning::FilePruner;
 use super::spec::{
+    DataFile,
+    DataFileContent,
     Manifest,
-    ManifestContent,
     ManifestEntryStatus,
     ManifestList,
+    ManifestListEntry,
+    MetadataLog,
+    PartitionField,
+    Schema,
     Snapshot,
+    SnapshotLog,
+    SnapshotReference,
+    SnapshotReferenceType,
     TableMetadata,
+    MAIN_BRANCH,
+};
+use super::writer::{
+    append_summary,
+    check_appendable,
+    new_table_metadata,
+    put_new,
+    write_manifest,
+    write_manifest_list,
+    DataFileWriter,
 };
 use crate::common::url::DatasourceUrl;
 use crate::lake::iceberg::errors::{IcebergError, Result};
@@ -43,6 +79,18 @@ pub struct IcebergTable {
     state: TableState,
 }
 
+/// Selects a snapshot of a table to read instead of the current snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotSelector {
+    /// Snapshot with the given id.
+    Id(i64),
+    /// Snapshot that was current at the given time, in milliseconds since
+    /// epoch.
+    AsOf(i64),
+    /// Snapshot pointed to by the branch or tag with the given name.
+    Ref(String),
+}
+
 impl IcebergTable {
     /// Open a table at a location using the provided object store.
     pub async fn open(
@@ -54,6 +102,79 @@ impl IcebergTable {
         Ok(IcebergTable { state })
     }
 
+    /// Open a table using metadata that's already been loaded, e.g. from a
+    /// catalog.
+    ///
+    /// `metadata_location` is the path to the metadata file the metadata was
+    /// read from.
+    pub(crate) fn open_with_metadata(
+        location: DatasourceUrl,
+        store: Arc<dyn ObjectStore>,
+        metadata_location: &str,
+        metadata: TableMetadata,
+    ) -> IcebergTable {
+        let metadata_file = metadata_location
+            .rsplit_once('/')
+            .map(|(_, file)| file)
+            .unwrap_or(metadata_location)
+            .to_string();
+
+        let state = TableState {
+            resolver: PathResolver::from_metadata(&metadata),
+            version: metadata_file_version(&metadata_file),
+            snapshot_id: None,
+            location,
+            store,
+            metadata,
+            metadata_file,
+        };
+
+        IcebergTable { state }
+    }
+
+    /// Create a new empty table at a location using the provided object
+    /// store.
+    ///
+    /// Errors if a table already exists at the location.
+    pub async fn create(
+        location: DatasourceUrl,
+        store: Arc<dyn ObjectStore>,
+        schema: &ArrowSchema,
+    ) -> Result<IcebergTable> {
+        let state = TableState::create(location, store, schema).await?;
+
+        Ok(IcebergTable { state })
+    }
+
+    /// Append all batches from the stream to the table, returning the number
+    /// of rows written.
+    ///
+    /// Batch columns are matched up with the table's columns by position.
+    pub async fn append(&mut self, stream: SendableRecordBatchStream) -> Result<u64> {
+        let count = self.state.append(stream).await?;
+        if count > 0 {
+            self.state =
+                TableState::open(self.state.location.clone(), self.state.store.clone()).await?;
+        }
+
+        Ok(count)
+    }
+
+    /// Read the table at the selected snapshot instead of the current
+    /// snapshot.
+    pub fn with_snapshot(mut self, selector: &SnapshotSelector) -> Result<IcebergTable> {
+        let snapshot = match selector {
+            SnapshotSelector::Id(id) => self.state.metadata.snapshot_by_id(*id)?,
+            SnapshotSelector::AsOf(timestamp_ms) => {
+                self.state.metadata.snapshot_as_of(*timestamp_ms)?
+            }
+            SnapshotSelector::Ref(name) => self.state.metadata.snapshot_by_ref(name)?,
+        };
+
+        self.state.snapshot_id = Some(snapshot.snapshot_id);
+        Ok(self)
+    }
+
     /// Get the table metadata.
     pub fn metadata(&self) -> &TableMetadata {
         &self.state.metadata
@@ -81,6 +202,19 @@ impl IcebergTable {
     }
 }
 
+/// A data or delete file from a manifest that's part of a table scan.
+#[derive(Debug, Clone)]
+pub(crate) struct ScanFile {
+    pub file: DataFile,
+    /// Data sequence number of the file.
+    pub sequence_number: i64,
+    /// Id of the partition spec the file was written with.
+    pub spec_id: i32,
+    /// Fields of the partition spec, in the same order as the file's partition
+    /// values.
+    pub partition_spec: Arc<Vec<PartitionField>>,
+}
+
 /// Information about the state of the table at some table version.
 #[derive(Debug, Clone)]
 struct TableState {
@@ -94,6 +228,15 @@ struct TableState {
     /// metadata.
     metadata: TableMetadata,
 
+    /// File name of the loaded metadata file.
+    metadata_file: String,
+
+    /// Version of the loaded metadata file, if known.
+    version: Option<u64>,
+
+    /// Snapshot to read. The metadata's current snapshot is read if not set.
+    snapshot_id: Option<i64>,
+
     /// Resolve paths relative to the table's root.
     resolver: PathResolver,
 }
@@ -101,7 +244,12 @@ struct TableState {
 impl TableState {
     async fn open(location: DatasourceUrl, store: Arc<dyn ObjectStore>) -> Result<TableState> {
         // Read metadata.
-        let metadata = Self::get_table_metadata(&location, &store).await?;
+        let (metadata_path, version) = Self::get_table_metadata_path(&location, &store).await?;
+
+        let bs = store.get(&metadata_path).await?.bytes().await?;
+        let metadata: TableMetadata = serde_json::from_slice(&bs).map_err(|e| {
+            IcebergError::DataInvalid(format!("Failed to read table metadata: {}", e))
+        })?;
 
         let resolver = PathResolver::from_metadata(&metadata);
 
@@ -109,14 +257,49 @@ impl TableState {
             location,
             store,
             metadata,
+            metadata_file: metadata_path.filename().unwrap_or_default().to_string(),
+            version,
+            snapshot_id: None,
             resolver,
         })
     }
 
-    async fn get_table_metadata(
+    /// Create a new empty table at the given location.
+    ///
+    /// Errors if a table already exists at the location.
+    async fn create(
+        location: DatasourceUrl,
+        store: Arc<dyn ObjectStore>,
+        schema: &ArrowSchema,
+    ) -> Result<TableState> {
+        if Self::get_table_metadata_path(&location, &store)
+            .await
+            .is_ok()
+        {
+            return Err(IcebergError::DataInvalid(format!(
+                "An iceberg table already exists at '{location}'"
+            )));
+        }
+
+        let metadata = new_table_metadata(table_location(&location)?, schema)?;
+        let state = TableState {
+            resolver: PathResolver::from_metadata(&metadata),
+            location,
+            store,
+            metadata,
+            metadata_file: String::new(),
+            version: Some(0),
+            snapshot_id: None,
+        };
+        state.commit(state.metadata.clone()).await
+    }
+
+    /// Get the path to the latest metadata file for the table, and the
+    /// version of that file if it can be determined.
+    async fn get_table_metadata_path(
         location: &DatasourceUrl,
         store: &dyn ObjectStore,
-    ) -> Result<TableMetadata> {
+    ) -> Result<(ObjectPath, Option<u64>)> {
         let path = format_object_path(location, "metadata/version-hint.text")?;
 
         let version_obj = match store.get(&path).await {
@@ -134,10 +317,12 @@ impl TableState {
                     version_contents.as_str()
                 };
 
-                format_object_path(
+                let path = format_object_path(
                     location,
                     format!("metadata/v{}.metadata.json", first_line.trim()),
-                )?
+                )?;
+
+                (path, first_line.trim().parse::<u64>().ok())
             }
             Err(_e) => {
                 // List all the metadata files and try to get the one with the
@@ -146,110 +331,303 @@ impl TableState {
                 let metadata_prefix = format_object_path(location, "metadata/")?;
                 let mut metadata_objects = store.list(Some(&metadata_prefix));
 
-                let (mut latest_v, mut latest_v_obj) = (0_u32, Option::<ObjectPath>::None);
+                let (mut latest_v, mut latest_v_obj) = (0_u64, Option::<ObjectPath>::None);
 
                 while let Some(obj_meta) = metadata_objects.next().await {
                     let obj_meta = obj_meta?;
 
                     let file_name = obj_meta.location.filename().unwrap_or_default();
 
-                    if let Some(version_str) = file_name.strip_suffix(".metadata.json") {
-                        let version_num = if let Some(version_str) = version_str.strip_prefix('v') {
-                            version_str
-                        } else if let Some((version_str, _uuid)) = version_str.split_once('-') {
-                            // TODO: Maybe validate the "uuid". If invalid, continue.
-                            version_str
-                        } else {
-                            continue;
-                        };
-
-                        if let Ok(version_num) = version_num.parse::<u32>() {
-                            if version_num >= latest_v {
-                                latest_v = version_num;
-                                latest_v_obj = Some(obj_meta.location);
-                            }
+                    if let Some(version_num) = metadata_file_version(file_name) {
+                        if version_num >= latest_v {
+                            latest_v = version_num;
+                            latest_v_obj = Some(obj_meta.location);
                         }
                     }
                 }
 
-                latest_v_obj.ok_or_else(|| {
+                let path = latest_v_obj.ok_or_else(|| {
                     IcebergError::DataInvalid(
                         "no valid iceberg table exists at the given path".to_string(),
                     )
-                })?
+                })?;
+
+                (path, Some(latest_v))
             }
         };
 
-        let bs = store.get(&version_obj).await?.bytes().await?;
-        let metadata: TableMetadata = serde_json::from_slice(&bs).map_err(|e| {
-            IcebergError::DataInvalid(format!("Failed to read table metadata: {}", e))
-        })?;
-
-        Ok(metadata)
+        Ok(version_obj)
     }
 
-    /// Get the current snapshot from the table metadata
-    fn current_snapshot(&self) -> Result<&Snapshot> {
-        let current_snapshot_id = self
-            .metadata
-            .current_snapshot_id
-            .ok_or_else(|| IcebergError::DataInvalid("Missing current snapshot id".to_string()))?;
-
-        let current_snapshot = self
-            .metadata
-            .snapshots
-            .iter()
-            .find(|s| s.snapshot_id == current_snapshot_id)
-            .ok_or_else(|| {
-                IcebergError::DataInvalid(format!(
-                    "Missing snapshot for id: {}",
-                    current_snapshot_id
-                ))
-            })?;
-
-        Ok(current_snapshot)
+    fn current_schema(&self) -> Result<&Schema> {
+        self.schema_by_id(self.metadata.current_schema_id)
     }
 
-    fn table_arrow_schema(&self) -> Result<ArrowSchema> {
+    fn schema_by_id(&self, schema_id: i32) -> Result<&Schema> {
         // TODO: v1: Read `schema` (deprecated format).
 
-        let schema = self
-            .metadata
+        self.metadata
             .schemas
             .iter()
-            .find(|s| s.schema_id == self.metadata.current_schema_id)
-            .ok_or_else(|| {
-                IcebergError::DataInvalid(format!(
-                    "Missing schema for id: {}",
-                    self.metadata.current_schema_id
-                ))
-            })?;
+            .find(|s| s.schema_id == schema_id)
+            .ok_or_else(|| IcebergError::DataInvalid(format!("Missing schema for id: {schema_id}")))
+    }
 
-        schema.to_arrow_schema()
+    /// Get the snapshot being read.
+    fn snapshot(&self) -> Result<Option<&Snapshot>> {
+        match self.snapshot_id {
+            Some(id) => Ok(Some(self.metadata.snapshot_by_id(id)?)),
+            None => self.metadata.current_snapshot(),
+        }
+    }
+
+    /// Get the schema to use when reading the table.
+    ///
+    /// This is the schema the snapshot being read was written with when a
+    /// snapshot is selected, otherwise the table's current schema.
+    fn read_schema(&self) -> Result<&Schema> {
+        match self.snapshot_id {
+            Some(id) => self.schema_by_id(self.metadata.snapshot_by_id(id)?.schema_id),
+            None => self.current_schema(),
+        }
+    }
+
+    fn table_arrow_schema(&self) -> Result<ArrowSchema> {
+        self.read_schema()?.to_arrow_schema()
     }
 
     async fn read_manifests(&self) -> Result<Vec<Manifest>> {
         let list = self.read_manifest_list().await?;
 
         let mut manifests = Vec::new();
-        for ent in list.entries {
-            let manifest_path = self.resolver.relative_path(&ent.manifest_path);
+        for ent in &list.entries {
+            let manifest = self.read_manifest(ent).await?;
+            manifests.push(manifest);
+        }
+
+        Ok(manifests)
+    }
 
-            let path = format_object_path(&self.location, manifest_path)?;
-            let bs = self.store.get(&path).await?.bytes().await?;
+    async fn read_manifest(&self, ent: &ManifestListEntry) -> Result<Manifest> {
+        let manifest_path = self.resolver.relative_path(&ent.manifest_path);
 
-            let cursor = Cursor::new(bs);
+        let path = format_object_path(&self.location, manifest_path)?;
+        let bs = self.store.get(&path).await?.bytes().await?;
 
-            let manifest = Manifest::from_raw_avro(cursor)?;
-            manifests.push(manifest);
+        let cursor = Cursor::new(bs);
+
+        let mut manifest = Manifest::from_raw_avro(cursor)?;
+
+        // > When reading v2 manifests, null data sequence numbers for ADDED
+        // > entries are inherited from the manifest's sequence number.
+        //
+        // Manifest lists for v1 tables don't have sequence numbers, and the
+        // default of 0 is what all files should be read with.
+        for entry in &mut manifest.entries {
+            let status: ManifestEntryStatus = entry.status.try_into().unwrap_or_default();
+            if status.is_added() {
+                entry.sequence_number.get_or_insert(ent.sequence_number);
+                entry
+                    .file_sequence_number
+                    .get_or_insert(ent.sequence_number);
+            }
         }
 
-        Ok(manifests)
+        Ok(manifest)
+    }
+
+    /// Read the data and delete files for the current snapshot.
+    ///
+    /// If a pruner is provided, manifests and data files that can't contain
+    /// rows matching the pruner's filters are skipped.
+    async fn read_scan_files(
+        &self,
+        pruner: Option<&FilePruner>,
+    ) -> Result<(Vec<ScanFile>, Vec<ScanFile>)> {
+        let list = self.read_manifest_list().await?;
+        let keep = match pruner {
+            Some(pruner) => pruner.prune_manifests(&list.entries, &self.metadata.partition_specs),
+            None => vec![true; list.entries.len()],
+        };
+
+        let mut data_files = Vec::new();
+        let mut delete_files = Vec::new();
+        for (ent, keep) in list.entries.iter().zip(keep) {
+            if !keep {
+                continue;
+            }
+
+            let manifest = self.read_manifest(ent).await?;
+            let partition_spec = Arc::new(manifest.metadata.partition_spec);
+
+            for entry in manifest.entries {
+                let ent_status: ManifestEntryStatus = entry.status.try_into().unwrap_or_default();
+                if ent_status.is_deleted() {
+                    // Ignore deleted entries during table scans.
+                    continue;
+                }
+
+                let content = DataFileContent::try_from(entry.data_file.content)?;
+                let file = ScanFile {
+                    file: entry.data_file,
+                    sequence_number: entry.sequence_number.unwrap_or_default(),
+                    spec_id: manifest.metadata.partition_spec_id,
+                    partition_spec: partition_spec.clone(),
+                };
+
+                match content {
+                    DataFileContent::Data => data_files.push(file),
+                    DataFileContent::PositionDeletes | DataFileContent::EqualityDeletes => {
+                        delete_files.push(file)
+                    }
+                }
+            }
+        }
+
+        if let Some(pruner) = pruner {
+            let keep = pruner.prune_data_files(&data_files);
+            data_files = data_files
+                .into_iter()
+                .zip(keep)
+                .filter_map(|(file, keep)| keep.then_some(file))
+                .collect();
+        }
+
+        Ok((data_files, delete_files))
+    }
+
+    /// Get the file to pass to datafusion for reading a data or delete file.
+    fn partitioned_file(&self, file: &DataFile) -> Result<PartitionedFile> {
+        let path = self.resolver.relative_path(&file.file_path);
+        let meta = ObjectMeta {
+            location: format_object_path(&self.location, path)?,
+            last_modified: DateTime::<Utc>::MIN_UTC, // TODO: Get the actual time.
+            size: file.file_size_in_bytes as usize,
+            e_tag: None,
+            version: None,
+        };
+
+        Ok(PartitionedFile {
+            object_meta: meta,
+            partition_values: Vec::new(),
+            range: None,
+            extensions: None,
+        })
+    }
+
+    /// Get the schema to use when reading an equality delete file.
+    fn equality_delete_schema(&self, file: &DataFile) -> Result<ArrowSchemaRef> {
+        let schema = self.read_schema()?;
+        let fields = file
+            .equality_ids
+            .iter()
+            .flatten()
+            .map(|id| {
+                schema
+                    .fields
+                    .iter()
+                    .find(|f| f.id == *id)
+                    .ok_or_else(|| {
+                        IcebergError::DataInvalid(format!(
+                            "Missing field for equality delete id: {id}"
+                        ))
+                    })?
+                    .to_arrow_field()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if fields.is_empty() {
+            return Err(IcebergError::DataInvalid(format!(
+                "Missing equality ids for delete file: {}",
+                file.file_path
+            )));
+        }
+
+        Ok(Arc::new(ArrowSchema::new(fields)))
+    }
+
+    /// Read the delete files for a scan.
+    async fn read_deletes(
+        &self,
+        ctx: &SessionState,
+        object_url: &ObjectStoreUrl,
+        delete_files: Vec<ScanFile>,
+    ) -> Result<Deletes> {
+        let mut deletes = Deletes::default();
+
+        for delete_file in delete_files {
+            if !delete_file.file.file_format.eq_ignore_ascii_case("parquet") {
+                return Err(IcebergError::DataInvalid(format!(
+                    "Unsupported format for delete file: {}",
+                    delete_file.file.file_format
+                )));
+            }
+
+            match DataFileContent::try_from(delete_file.file.content)? {
+                DataFileContent::PositionDeletes => {
+                    let batches = self
+                        .read_file(ctx, object_url, position_delete_schema(), &delete_file.file)
+                        .await?;
+                    deletes.push_position(PositionDeleteFile::try_new(
+                        delete_file.sequence_number,
+                        &batches,
+                    )?);
+                }
+                DataFileContent::EqualityDeletes => {
+                    let schema = self.equality_delete_schema(&delete_file.file)?;
+                    let batches = self
+                        .read_file(ctx, object_url, schema, &delete_file.file)
+                        .await?;
+                    deletes.push_equality(EqualityDeleteFile::try_new(&delete_file, &batches)?);
+                }
+                DataFileContent::Data => {
+                    return Err(IcebergError::Static(
+                        "Unexpected data file when reading deletes",
+                    ))
+                }
+            }
+        }
+
+        Ok(deletes)
+    }
+
+    /// Read all rows from a single parquet file.
+    async fn read_file(
+        &self,
+        ctx: &SessionState,
+        object_url: &ObjectStoreUrl,
+        schema: ArrowSchemaRef,
+        file: &DataFile,
+    ) -> Result<Vec<RecordBatch>> {
+        let conf = FileScanConfig {
+            object_store_url: object_url.clone(),
+            statistics: Statistics::new_unknown(schema.as_ref()),
+            file_schema: schema,
+            projection: None,
+            file_groups: vec![vec![self.partitioned_file(file)?]],
+            limit: None,
+            table_partition_cols: Vec::new(),
+            output_ordering: Vec::new(),
+        };
+
+        let plan = ParquetFormat::new()
+            .create_physical_plan(ctx, conf, None)
+            .await?;
+
+        Ok(collect(plan, ctx.task_ctx()).await?)
     }
 
     async fn read_manifest_list(&self) -> Result<ManifestList> {
-        let current_snapshot = self.current_snapshot()?;
-        let manifest_list_path = self.resolver.relative_path(&current_snapshot.manifest_list);
+        let snapshot = match self.snapshot()? {
+            Some(snapshot) => snapshot,
+            // New tables won't have any snapshots until data is added.
+            None => {
+                return Ok(ManifestList {
+                    entries: Vec::new(),
+                })
+            }
+        };
+        let manifest_list_path = self.resolver.relative_path(&snapshot.manifest_list);
 
         let path = format_object_path(&self.location, manifest_list_path)?;
         let bs = self.store.get(&path).await?.bytes().await?;
@@ -259,6 +637,206 @@ impl TableState {
 
         Ok(list)
     }
+
+    /// Append all batches from the stream to the table as a new snapshot,
+    /// returning the number of rows written.
+    ///
+    /// The append is committed on top of the loaded metadata. If the table
+    /// was modified since the metadata was loaded, the commit fails.
+    async fn append(&self, mut stream: SendableRecordBatchStream) -> Result<u64> {
+        let spec = check_appendable(&self.metadata)?;
+        let schema = self.current_schema()?;
+
+        let location = self.location.clone();
+        let mut writer = DataFileWriter::try_new(
+            self.store.clone(),
+            self.metadata.location.clone(),
+            move |path| Ok(format_object_path(&location, path)?),
+            schema,
+        )?;
+        while let Some(batch) = stream.next().await {
+            writer.write(&batch?).await?;
+        }
+        let files = writer.finish().await?;
+
+        if files.is_empty() {
+            return Ok(0);
+        }
+
+        let parent = self.metadata.current_snapshot()?;
+        let snapshot_id = loop {
+            let id = rand::thread_rng().gen_range(1..i64::MAX);
+            if !self.metadata.snapshots.iter().any(|s| s.snapshot_id == id) {
+                break id;
+            }
+        };
+        let sequence_number = self.metadata.last_sequence_number + 1;
+        let timestamp_ms = Utc::now().timestamp_millis();
+        let commit_id = Uuid::new_v4();
+
+        let num_files = files.len();
+        let num_rows: i64 = files.iter().map(|f| f.record_count).sum();
+        let summary = append_summary(parent, &files);
+
+        // Write the manifest for the new files.
+        let manifest_name = format!("metadata/{commit_id}-m0.avro");
+        let manifest = write_manifest(schema, spec, snapshot_id, files)?;
+        let manifest_length = manifest.len() as i64;
+        self.store
+            .put(
+                &format_object_path(&self.location, &manifest_name)?,
+                manifest.into(),
+            )
+            .await?;
+
+        // Write the manifest list containing the new manifest alongside all
+        // manifests from the parent snapshot.
+        let mut manifests = vec![ManifestListEntry {
+            manifest_path: format!("{}/{manifest_name}", self.metadata.location),
+            manifest_length,
+            partition_spec_id: spec.spec_id,
+            content: 0,
+            sequence_number,
+            min_sequence_number: sequence_number,
+            added_snapshot_id: snapshot_id,
+            added_files_count: num_files as i32,
+            existing_files_count: 0,
+            deleted_files_count: 0,
+            added_rows_count: num_rows,
+            existing_rows_count: 0,
+            deleted_rows_count: 0,
+            partitions: Vec::new(),
+            key_metadata: None,
+        }];
+        manifests.extend(self.read_manifest_list().await?.entries);
+
+        let list_name = format!("metadata/snap-{snapshot_id}-1-{commit_id}.avro");
+        let snapshot = Snapshot {
+            snapshot_id,
+            parent_snapshot_id: parent.map(|s| s.snapshot_id),
+            sequence_number,
+            timestamp_ms,
+            summary,
+            manifest_list: format!("{}/{list_name}", self.metadata.location),
+            schema_id: schema.schema_id,
+        };
+        let list = write_manifest_list(&snapshot, &manifests)?;
+        self.store
+            .put(
+                &format_object_path(&self.location, &list_name)?,
+                list.into(),
+            )
+            .await?;
+
+        // Write the new metadata pointing to the new snapshot.
+        let mut metadata = self.metadata.clone();
+        metadata.last_sequence_number = sequence_number;
+        metadata.last_updated_ms = timestamp_ms;
+        metadata.current_snapshot_id = Some(snapshot_id);
+        metadata.refs.insert(
+            MAIN_BRANCH.to_string(),
+            SnapshotReference {
+                snapshot_id,
+                r#type: SnapshotReferenceType::Branch,
+                min_snapshots_to_keep: None,
+                max_snapshot_age_ms: None,
+                max_ref_age_ms: None,
+            },
+        );
+        metadata.snapshots.push(snapshot);
+        metadata.snapshot_log.push(SnapshotLog {
+            snapshot_id,
+            timestamp_ms,
+        });
+        metadata.metadata_log.push(MetadataLog {
+            metadata_file: format!("{}/metadata/{}", self.metadata.location, self.metadata_file),
+            timestamp_ms: self.metadata.last_updated_ms,
+        });
+
+        self.commit(metadata).await?;
+
+        Ok(num_rows as u64)
+    }
+
+    /// Commit new table metadata as the next version of the table.
+    async fn commit(&self, metadata: TableMetadata) -> Result<TableState> {
+        let version = self.version.ok_or_else(|| {
+            IcebergError::DataInvalid(format!(
+                "Unable to determine the version for metadata file '{}'",
+                self.metadata_file
+            ))
+        })? + 1;
+
+        let metadata_file = format!("v{version}.metadata.json");
+        let bs = serde_json::to_vec_pretty(&metadata)?;
+        put_new(
+            self.store.as_ref(),
+            &format_object_path(&self.location, format!("metadata/{metadata_file}"))?,
+            bs.into(),
+        )
+        .await?;
+
+        // The version hint is only used to find the latest metadata file, so
+        // it's updated after the metadata file is written.
+        self.store
+            .put(
+                &format_object_path(&self.location, "metadata/version-hint.text")?,
+                version.to_string().into(),
+            )
+            .await?;
+
+        Ok(TableState {
+            location: self.location.clone(),
+            store: self.store.clone(),
+            resolver: PathResolver::from_metadata(&metadata),
+            metadata,
+            metadata_file,
+            version: Some(version),
+            snapshot_id: None,
+        })
+    }
+}
+
+/// Get the version of a metadata file from its file name.
+///
+/// Metadata files are named either `v<version>.metadata.json` or
+/// `<version>-<uuid>.metadata.json`.
+fn metadata_file_version(file_name: &str) -> Option<u64> {
+    let version_str = file_name.strip_suffix(".metadata.json")?;
+    let version_num = if let Some(version_str) = version_str.strip_prefix('v') {
+        version_str
+    } else if let Some((version_str, _uuid)) = version_str.split_once('-') {
+        // TODO: Maybe validate the "uuid". If invalid, continue.
+        version_str
+    } else {
+        return None;
+    };
+
+    version_num.parse::<u64>().ok()
+}
+
+/// Get the location to write to the metadata of a new table.
+///
+/// Local paths are made absolute so that the table can be read regardless of
+/// the current working directory.
+fn table_location(location: &DatasourceUrl) -> Result<String> {
+    Ok(match location {
+        DatasourceUrl::File(path) => {
+            let path = if path.is_absolute() {
+                path.clone()
+            } else {
+                std::env::current_dir()
+                    .map_err(|e| {
+                        IcebergError::DataInvalid(format!("Failed to get current directory: {e}"))
+                    })?
+                    .join(path)
+            };
+            // Removes any "." components.
+            let path: PathBuf = path.components().collect();
+            path.to_string_lossy().trim_end_matches('/').to_string()
+        }
+        DatasourceUrl::Url(url) => url.as_str().trim_end_matches('/').to_string(),
+    })
 }
 
 /// Helper for resolving paths for files.
@@ -329,7 +907,7 @@ impl TableProvider for IcebergTableReader {
         &self,
         ctx: &SessionState,
         projection: Option<&Vec<usize>>,
-        _filters: &[Expr],
+        filters: &[Expr],
         limit: Option<usize>,
     ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
         // Create the datafusion specific url, and register the object store.
@@ -339,87 +917,189 @@ impl TableProvider for IcebergTableReader {
             .object_store_registry
             .register_store(object_url.as_ref(), self.state.store.clone());
 
-        // TODO: Properly prune based on partition values. This currently skips
-        // any partition processing, and shoves everything into a single file
-        // group when passing to the parquet exec.
-        //
-        // We also miss out on parallel reading by using a single file group.
+        let pruner = FilePruner::try_new(
+            filters,
+            self.state
+                .read_schema()
+                .map_err(|e| DataFusionError::External(Box::new(e)))?,
+            self.schema(),
+        );
+
+        let (data_files, delete_files) = self
+            .state
+            .read_scan_files(pruner.as_ref())
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
 
-        // TODO: Properly handle row-level deletes. Currently files containing
-        // delete information are ignored.
+        let deletes = if delete_files.is_empty() || data_files.is_empty() {
+            Deletes::default()
+        } else {
+            self.state
+                .read_deletes(ctx, &object_url, delete_files)
+                .await
+                .map_err(|e| DataFusionError::External(Box::new(e)))?
+        };
 
-        // TODO: Use provided filters to prune out partitions and/or data files
-        // (since the metadata will have some info about file content).
+        // Split out files that need deletes applied. Those need to be read
+        // individually from start to end.
+        let mut files = Vec::new();
+        let mut files_with_deletes = Vec::new();
+        for data_file in data_files {
+            let file = self
+                .state
+                .partitioned_file(&data_file.file)
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+            match deletes.for_file(&data_file) {
+                Some(file_deletes) => files_with_deletes.push((file, Arc::new(file_deletes))),
+                None => files.push(file),
+            }
+        }
 
         // TODO: Collect statistics and pass to exec.
 
-        let manifests = self
-            .state
-            .read_manifests()
-            .await
-            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        let mut plans = Vec::new();
 
-        // Get only data files with "data" content.
-        //
-        // TODO: Handle "delete" content and also pull out partition
-        // information.
-        let data_files: Vec<_> = manifests
-            .into_iter()
-            .filter(|m| matches!(m.metadata.content, ManifestContent::Data))
-            .flat_map(|m| {
-                m.entries.into_iter().filter_map(|ent| {
-                    let ent_status: ManifestEntryStatus = ent.status.try_into().unwrap_or_default();
-                    if ent_status.is_deleted() {
-                        // Ignore deleted entries during table scans.
-                        None
-                    } else {
-                        Some(ent.data_file)
-                    }
-                })
-            })
-            .collect();
+        if !files.is_empty() || files_with_deletes.is_empty() {
+            // Spread files across groups so they can be read in parallel.
+            let num_groups = ctx.config().target_partitions().min(files.len()).max(1);
+            let mut file_groups = vec![Vec::new(); num_groups];
+            for (idx, file) in files.into_iter().enumerate() {
+                file_groups[idx % num_groups].push(file);
+            }
 
-        let partitioned_files = data_files
-            .iter()
-            .map(|f| {
-                let path = self.state.resolver.relative_path(&f.file_path);
-                let meta = ObjectMeta {
-                    location: format_object_path(&self.state.location, path)?,
-                    last_modified: DateTime::<Utc>::MIN_UTC, // TODO: Get the actual time.
-                    size: f.file_size_in_bytes as usize,
-                    e_tag: None,
-                    version: None,
-                };
+            let conf = self.scan_config(&object_url, projection.cloned(), file_groups, limit);
+            let predicate = pruner.as_ref().map(|pruner| pruner.filter().clone());
+            let plan = ParquetFormat::new()
+                .create_physical_plan(ctx, conf, predicate.as_ref())
+                .await?;
+            plans.push(plan);
+        }
 
-                Ok(PartitionedFile {
-                    object_meta: meta,
-                    partition_values: Vec::new(),
-                    range: None,
-                    extensions: None,
-                })
-            })
-            .collect::<Result<Vec<PartitionedFile>>>()
+        if !files_with_deletes.is_empty() {
+            // Read all columns, and don't prune any row groups using the
+            // filters since that would change the positions of rows.
+            let (file_groups, deletes): (Vec<_>, Vec<_>) = files_with_deletes
+                .into_iter()
+                .map(|(file, file_deletes)| (vec![file], file_deletes))
+                .unzip();
+
+            let conf = self.scan_config(&object_url, None, file_groups, None);
+            let plan = ParquetFormat::new()
+                .create_physical_plan(ctx, conf, None)
+                .await?;
+            plans.push(Arc::new(IcebergDeleteExec::try_new(
+                plan,
+                deletes,
+                projection.cloned(),
+            )?));
+        }
+
+        let plan = if plans.len() == 1 {
+            plans.pop().unwrap()
+        } else {
+            Arc::new(UnionExec::new(plans))
+        };
+
+        Ok(Arc::new(IcebergTableScan { scan: plan }))
+    }
+
+    async fn insert_into(
+        &self,
+        _state: &SessionState,
+        input: Arc<dyn ExecutionPlan>,
+        overwrite: bool,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        if overwrite {
+            return Err(DataFusionError::NotImplemented(
+                "Overwriting iceberg tables is not supported".to_string(),
+            ));
+        }
+
+        let sink = IcebergAppendSink {
+            location: self.state.location.clone(),
+            store: self.state.store.clone(),
+        };
+
+        Ok(Arc::new(FileSinkExec::new(
+            input,
+            Arc::new(sink),
+            self.schema(),
+            None,
+        )))
+    }
+}
+
+/// Appends inserted rows to an existing table.
+#[derive(Debug)]
+struct IcebergAppendSink {
+    location: DatasourceUrl,
+    store: Arc<dyn ObjectStore>,
+}
+
+impl fmt::Display for IcebergAppendSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "IcebergAppendSink({})", self.location)
+    }
+}
+
+impl DisplayAs for IcebergAppendSink {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default => write!(f, "{self}"),
+            DisplayFormatType::Verbose => write!(f, "{self}"),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSink for IcebergAppendSink {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        None
+    }
+
+    async fn write_all(
+        &self,
+        data: SendableRecordBatchStream,
+        _context: &Arc<TaskContext>,
+    ) -> DataFusionResult<u64> {
+        // Load the latest version of the table so that the append is
+        // committed on top of it.
+        let mut table = IcebergTable::open(self.location.clone(), self.store.clone())
+            .await
             .map_err(|e| DataFusionError::External(Box::new(e)))?;
 
+        table
+            .append(data)
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))
+    }
+}
+
+impl IcebergTableReader {
+    fn scan_config(
+        &self,
+        object_url: &ObjectStoreUrl,
+        projection: Option<Vec<usize>>,
+        file_groups: Vec<Vec<PartitionedFile>>,
+        limit: Option<usize>,
+    ) -> FileScanConfig {
         let file_schema = self.schema();
         let statistics = Statistics::new_unknown(file_schema.as_ref());
 
-        let conf = FileScanConfig {
-            object_store_url: object_url,
+        FileScanConfig {
+            object_store_url: object_url.clone(),
             file_schema,
-            projection: projection.cloned(),
+            projection,
             statistics,
-            file_groups: vec![partitioned_files],
+            file_groups,
             limit,
             table_partition_cols: Vec::new(),
             output_ordering: Vec::new(),
-        };
-
-        let plan = ParquetFormat::new()
-            .create_physical_plan(ctx, conf, None)
-            .await?;
-
-        Ok(Arc::new(IcebergTableScan { parquet_scan: plan }))
+        }
     }
 }
 
@@ -440,7 +1120,7 @@ fn datasource_url_to_unique_url(url: &DatasourceUrl) -> ObjectStoreUrl {
 
 #[derive(Debug)]
 pub struct IcebergTableScan {
-    parquet_scan: Arc<dyn ExecutionPlan>,
+    scan: Arc<dyn ExecutionPlan>,
 }
 
 impl ExecutionPlan for IcebergTableScan {
@@ -449,26 +1129,38 @@ impl ExecutionPlan for IcebergTableScan {
     }
 
     fn schema(&self) -> Arc<ArrowSchema> {
-        self.parquet_scan.schema()
+        self.scan.schema()
     }
 
     fn output_partitioning(&self) -> Partitioning {
-        self.parquet_scan.output_partitioning()
+        self.scan.output_partitioning()
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        self.parquet_scan.output_ordering()
+        self.scan.output_ordering()
     }
 
     fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
-        self.parquet_scan.children()
+        self.scan.children()
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        self.scan.benefits_from_input_partitioning()
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        self.scan.required_input_distribution()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        self.scan.maintains_input_order()
     }
 
     fn with_new_children(
         self: Arc<Self>,
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
-        ExecutionPlan::with_new_children(self.parquet_scan.clone(), children)
+        ExecutionPlan::with_new_children(self.scan.clone(), children)
     }
 
     fn execute(
@@ -476,7 +1168,7 @@ impl ExecutionPlan for IcebergTableScan {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> DataFusionResult<SendableRecordBatchStream> {
-        self.parquet_scan.execute(partition, context)
+        self.scan.execute(partition, context)
     }
 
     fn statistics(&self) -> DataFusionResult<Statistics> {
@@ -487,7 +1179,7 @@ impl ExecutionPlan for IcebergTableScan {
 impl DisplayAs for IcebergTableScan {
     fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "IcebergTableScan(")?;
-        self.parquet_scan.fmt_as(t, f)?;
+        self.scan.fmt_as(t, f)?;
         write!(f, ")")
     }
 }
@@ -579,4 +1271,15 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_metadata_file_version() {
+        assert_eq!(Some(3), metadata_file_version("v3.metadata.json"));
+        assert_eq!(
+            Some(12),
+            metadata_file_version("00012-1e7a3c2b-4a8f-4b8e-9d3a-7f1c2b3a4d5e.metadata.json")
+        );
+        assert_eq!(None, metadata_file_version("version-hint.text"));
+        assert_eq!(None, metadata_file_version("metadata.json"));
+    }
 }
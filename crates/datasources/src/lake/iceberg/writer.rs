@@ -0,0 +1,771 @@
+//! Utilities for writing data files, manifests, and manifest lists for
+//! appends to Iceberg tables.
+//!
+//! Only format version 2 tables without partitioning are currently supported
+//! for writes.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use apache_avro::types::Value;
+use apache_avro::{to_value, Codec, Schema as AvroSchema, Writer};
+use bytes::Bytes;
+use chrono::Utc;
+use datafusion::arrow::array::ArrayRef;
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{
+    Field as ArrowField,
+    Schema as ArrowSchema,
+    SchemaRef as ArrowSchemaRef,
+};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::logical_expr::Accumulator;
+use datafusion::parquet::arrow::{AsyncArrowWriter, PARQUET_FIELD_ID_META_KEY};
+use datafusion::parquet::file::properties::WriterProperties;
+use datafusion::physical_plan::expressions::{MaxAccumulator, MinAccumulator};
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, PutMode};
+use tokio::io::AsyncWrite;
+use uuid::Uuid;
+
+use super::spec::{
+    encode_single_value,
+    AnyType,
+    BinaryEntry,
+    DataFile,
+    I64Entry,
+    ManifestContent,
+    ManifestEntry,
+    ManifestEntryStatus,
+    ManifestListEntry,
+    PartitionSpec,
+    PrimitiveType,
+    Schema,
+    Snapshot,
+    SortOrder,
+    TableMetadata,
+};
+use crate::lake::iceberg::errors::{IcebergError, Result};
+
+/// Max number of rows to write to a single data file before starting a new
+/// one.
+const MAX_ROWS_PER_FILE: usize = 1024 * 1024;
+
+const BUFFER_SIZE: usize = 8 * 1024 * 1024;
+
+/// Avro schema for v2 manifest files for unpartitioned tables.
+///
+/// Field ids are included since readers may use those instead of the field
+/// names.
+const MANIFEST_SCHEMA: &str = r#"{
+  "type": "record",
+  "name": "manifest_entry",
+  "fields": [
+    {"name": "status", "type": "int", "field-id": 0},
+    {"name": "snapshot_id", "type": ["null", "long"], "default": null, "field-id": 1},
+    {"name": "sequence_number", "type": ["null", "long"], "default": null, "field-id": 3},
+    {"name": "file_sequence_number", "type": ["null", "long"], "default": null, "field-id": 4},
+    {"name": "data_file", "type": {
+      "type": "record",
+      "name": "r2",
+      "fields": [
+        {"name": "content", "type": "int", "field-id": 134},
+        {"name": "file_path", "type": "string", "field-id": 100},
+        {"name": "file_format", "type": "string", "field-id": 101},
+        {"name": "partition", "type": {"type": "record", "name": "r102", "fields": []}, "field-id": 102},
+        {"name": "record_count", "type": "long", "field-id": 103},
+        {"name": "file_size_in_bytes", "type": "long", "field-id": 104},
+        {"name": "column_sizes", "type": ["null", {"type": "array", "items": {"type": "record", "name": "k117_v118", "fields": [{"name": "key", "type": "int", "field-id": 117}, {"name": "value", "type": "long", "field-id": 118}]}, "logicalType": "map"}], "default": null, "field-id": 108},
+        {"name": "value_counts", "type": ["null", {"type": "array", "items": {"type": "record", "name": "k119_v120", "fields": [{"name": "key", "type": "int", "field-id": 119}, {"name": "value", "type": "long", "field-id": 120}]}, "logicalType": "map"}], "default": null, "field-id": 109},
+        {"name": "null_value_counts", "type": ["null", {"type": "array", "items": {"type": "record", "name": "k121_v122", "fields": [{"name": "key", "type": "int", "field-id": 121}, {"name": "value", "type": "long", "field-id": 122}]}, "logicalType": "map"}], "default": null, "field-id": 110},
+        {"name": "nan_value_counts", "type": ["null", {"type": "array", "items": {"type": "record", "name": "k138_v139", "fields": [{"name": "key", "type": "int", "field-id": 138}, {"name": "value", "type": "long", "field-id": 139}]}, "logicalType": "map"}], "default": null, "field-id": 137},
+        {"name": "lower_bounds", "type": ["null", {"type": "array", "items": {"type": "record", "name": "k126_v127", "fields": [{"name": "key", "type": "int", "field-id": 126}, {"name": "value", "type": "bytes", "field-id": 127}]}, "logicalType": "map"}], "default": null, "field-id": 125},
+        {"name": "upper_bounds", "type": ["null", {"type": "array", "items": {"type": "record", "name": "k129_v130", "fields": [{"name": "key", "type": "int", "field-id": 129}, {"name": "value", "type": "bytes", "field-id": 130}]}, "logicalType": "map"}], "default": null, "field-id": 128},
+        {"name": "key_metadata", "type": ["null", "bytes"], "default": null, "field-id": 131},
+        {"name": "split_offsets", "type": ["null", {"type": "array", "items": "long", "element-id": 133}], "default": null, "field-id": 132},
+        {"name": "equality_ids", "type": ["null", {"type": "array", "items": "int", "element-id": 136}], "default": null, "field-id": 135},
+        {"name": "sort_order_id", "type": ["null", "int"], "default": null, "field-id": 140}
+      ]
+    }, "field-id": 2}
+  ]
+}"#;
+
+/// Avro schema for v2 manifest lists.
+const MANIFEST_LIST_SCHEMA: &str = r#"{
+  "type": "record",
+  "name": "manifest_file",
+  "fields": [
+    {"name": "manifest_path", "type": "string", "field-id": 500},
+    {"name": "manifest_length", "type": "long", "field-id": 501},
+    {"name": "partition_spec_id", "type": "int", "field-id": 502},
+    {"name": "content", "type": "int", "field-id": 517},
+    {"name": "sequence_number", "type": "long", "field-id": 515},
+    {"name": "min_sequence_number", "type": "long", "field-id": 516},
+    {"name": "added_snapshot_id", "type": "long", "field-id": 503},
+    {"name": "added_files_count", "type": "int", "field-id": 504},
+    {"name": "existing_files_count", "type": "int", "field-id": 505},
+    {"name": "deleted_files_count", "type": "int", "field-id": 506},
+    {"name": "added_rows_count", "type": "long", "field-id": 512},
+    {"name": "existing_rows_count", "type": "long", "field-id": 513},
+    {"name": "deleted_rows_count", "type": "long", "field-id": 514},
+    {"name": "partitions", "type": ["null", {"type": "array", "items": {
+      "type": "record",
+      "name": "r508",
+      "fields": [
+        {"name": "contains_null", "type": "boolean", "field-id": 509},
+        {"name": "contains_nan", "type": ["null", "boolean"], "default": null, "field-id": 518},
+        {"name": "lower_bound", "type": ["null", "bytes"], "default": null, "field-id": 510},
+        {"name": "upper_bound", "type": ["null", "bytes"], "default": null, "field-id": 511}
+      ]
+    }, "element-id": 508}], "default": null, "field-id": 507}
+  ]
+}"#;
+
+/// Create the metadata for a new, empty table.
+pub(crate) fn new_table_metadata(location: String, schema: &ArrowSchema) -> Result<TableMetadata> {
+    let schema = Schema::try_from_arrow(0, schema)?;
+
+    Ok(TableMetadata {
+        format_version: 2,
+        table_uuid: Uuid::new_v4().to_string(),
+        location,
+        last_sequence_number: 0,
+        last_updated_ms: Utc::now().timestamp_millis(),
+        last_column_id: schema.fields.iter().map(|f| f.id).max().unwrap_or_default(),
+        schemas: vec![schema],
+        current_schema_id: 0,
+        partition_specs: vec![PartitionSpec {
+            spec_id: 0,
+            fields: Vec::new(),
+        }],
+        default_spec_id: 0,
+        // > Partition field ids start at 1000.
+        last_partition_id: 999,
+        properties: None,
+        current_snapshot_id: None,
+        refs: HashMap::new(),
+        snapshots: Vec::new(),
+        snapshot_log: Vec::new(),
+        metadata_log: Vec::new(),
+        sort_orders: vec![SortOrder {
+            order_id: 0,
+            fields: Vec::new(),
+        }],
+        default_sort_order_id: 0,
+    })
+}
+
+/// Check that a table can be appended to, returning the partition spec new
+/// files will be written with.
+pub(crate) fn check_appendable(metadata: &TableMetadata) -> Result<&PartitionSpec> {
+    if metadata.format_version != 2 {
+        return Err(IcebergError::DataInvalid(format!(
+            "Writing to iceberg tables with format version {} is not supported",
+            metadata.format_version
+        )));
+    }
+
+    let spec = metadata
+        .partition_specs
+        .iter()
+        .find(|s| s.spec_id == metadata.default_spec_id)
+        .ok_or_else(|| {
+            IcebergError::DataInvalid(format!(
+                "Missing partition spec for id: {}",
+                metadata.default_spec_id
+            ))
+        })?;
+
+    if !spec.fields.is_empty() {
+        return Err(IcebergError::Static(
+            "Writing to partitioned iceberg tables is not supported",
+        ));
+    }
+
+    Ok(spec)
+}
+
+/// Writes record batches to one or more parquet data files.
+pub(crate) struct DataFileWriter {
+    store: Arc<dyn ObjectStore>,
+    /// Location of the table according to its metadata. Used for the paths
+    /// written to the manifest.
+    table_location: String,
+    /// Function for getting the object path for a file relative to the
+    /// table's root.
+    object_path: Box<dyn Fn(&str) -> Result<ObjectPath> + Send + Sync>,
+    /// Schema of the table, including parquet field ids.
+    schema: ArrowSchemaRef,
+    /// Types of the table's fields in the same order as the arrow schema.
+    types: Vec<(i32, PrimitiveType)>,
+    current: Option<InProgressFile>,
+    files: Vec<DataFile>,
+}
+
+impl DataFileWriter {
+    pub(crate) fn try_new(
+        store: Arc<dyn ObjectStore>,
+        table_location: String,
+        object_path: impl Fn(&str) -> Result<ObjectPath> + Send + Sync + 'static,
+        schema: &Schema,
+    ) -> Result<DataFileWriter> {
+        let mut fields = Vec::with_capacity(schema.fields.len());
+        let mut types = Vec::with_capacity(schema.fields.len());
+        for field in &schema.fields {
+            let typ = match &field.r#type {
+                AnyType::Primitive(typ) => *typ,
+                _ => {
+                    return Err(IcebergError::DataInvalid(format!(
+                        "Writing nested types is not supported, field: {}",
+                        field.name
+                    )))
+                }
+            };
+            let metadata =
+                HashMap::from([(PARQUET_FIELD_ID_META_KEY.to_string(), field.id.to_string())]);
+            fields.push(field.to_arrow_field()?.with_metadata(metadata));
+            types.push((field.id, typ));
+        }
+
+        Ok(DataFileWriter {
+            store,
+            table_location,
+            object_path: Box::new(object_path),
+            schema: Arc::new(ArrowSchema::new(fields)),
+            types,
+            current: None,
+            files: Vec::new(),
+        })
+    }
+
+    /// Write a batch, casting the columns to the table's types.
+    ///
+    /// Columns are matched up with the table's fields by position.
+    pub(crate) async fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        if batch.num_columns() != self.schema.fields().len() {
+            return Err(IcebergError::DataInvalid(format!(
+                "Expected {} columns for iceberg table, got {}",
+                self.schema.fields().len(),
+                batch.num_columns()
+            )));
+        }
+
+        let columns = batch
+            .columns()
+            .iter()
+            .zip(self.schema.fields())
+            .map(|(col, field)| cast(col, field.data_type()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let batch = RecordBatch::try_new(self.schema.clone(), columns)?;
+
+        let mut offset = 0;
+        while offset < batch.num_rows() {
+            if self.current.is_none() {
+                self.current = Some(self.new_file().await?);
+            }
+            let file = self.current.as_mut().unwrap();
+
+            let len = (MAX_ROWS_PER_FILE - file.rows).min(batch.num_rows() - offset);
+            file.write(&batch.slice(offset, len)).await?;
+            offset += len;
+
+            if file.rows >= MAX_ROWS_PER_FILE {
+                let file = self.current.take().unwrap();
+                self.files.push(file.finish(&self.store).await?);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finish writing, returning all data files that were written.
+    pub(crate) async fn finish(mut self) -> Result<Vec<DataFile>> {
+        if let Some(file) = self.current.take() {
+            self.files.push(file.finish(&self.store).await?);
+        }
+        Ok(self.files)
+    }
+
+    async fn new_file(&self) -> Result<InProgressFile> {
+        let name = format!("data/{}.parquet", Uuid::new_v4());
+        let path = (self.object_path)(&name)?;
+
+        let (_id, obj_handle) = self.store.put_multipart(&path).await?;
+
+        let props = WriterProperties::builder()
+            .set_created_by("GlareDB".to_string())
+            .build();
+        let writer =
+            AsyncArrowWriter::try_new(obj_handle, self.schema.clone(), BUFFER_SIZE, Some(props))?;
+
+        let columns = self
+            .types
+            .iter()
+            .zip(self.schema.fields())
+            .map(|((id, typ), field)| ColumnStats::new(*id, typ, field))
+            .collect();
+
+        Ok(InProgressFile {
+            path,
+            file_path: format!("{}/{name}", self.table_location),
+            writer,
+            columns,
+            rows: 0,
+        })
+    }
+}
+
+/// A data file that's currently being written to.
+struct InProgressFile {
+    path: ObjectPath,
+    /// Path of the file to write to the manifest.
+    file_path: String,
+    writer: AsyncArrowWriter<Box<dyn AsyncWrite + Unpin + Send>>,
+    columns: Vec<ColumnStats>,
+    rows: usize,
+}
+
+impl InProgressFile {
+    async fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        for (stats, col) in self.columns.iter_mut().zip(batch.columns()) {
+            stats.update(col);
+        }
+        self.rows += batch.num_rows();
+        self.writer.write(batch).await?;
+        Ok(())
+    }
+
+    async fn finish(self, store: &Arc<dyn ObjectStore>) -> Result<DataFile> {
+        self.writer.close().await?;
+        let meta = store.head(&self.path).await?;
+
+        let mut value_counts = Vec::with_capacity(self.columns.len());
+        let mut null_value_counts = Vec::with_capacity(self.columns.len());
+        let mut lower_bounds = Vec::new();
+        let mut upper_bounds = Vec::new();
+        for mut stats in self.columns {
+            value_counts.push(I64Entry {
+                key: stats.field_id,
+                value: self.rows as i64,
+            });
+            null_value_counts.push(I64Entry {
+                key: stats.field_id,
+                value: stats.nulls,
+            });
+            let (lower, upper) = stats.bounds();
+            if let Some(value) = lower {
+                lower_bounds.push(BinaryEntry {
+                    key: stats.field_id,
+                    value,
+                });
+            }
+            if let Some(value) = upper {
+                upper_bounds.push(BinaryEntry {
+                    key: stats.field_id,
+                    value,
+                });
+            }
+        }
+
+        Ok(DataFile {
+            content: 0,
+            file_path: self.file_path,
+            file_format: "PARQUET".to_string(),
+            record_count: self.rows as i64,
+            file_size_in_bytes: meta.size as i64,
+            column_sizes: None,
+            value_counts: Some(value_counts),
+            null_value_counts: Some(null_value_counts),
+            nan_value_counts: None,
+            distinct_counts: None,
+            lower_bounds: Some(lower_bounds),
+            upper_bounds: Some(upper_bounds),
+            key_metadata: None,
+            split_offsets: None,
+            equality_ids: None,
+            sort_order_id: None,
+            partition: Vec::new(),
+        })
+    }
+}
+
+/// Statistics for a single column in a data file.
+struct ColumnStats {
+    field_id: i32,
+    nulls: i64,
+    min: Option<MinAccumulator>,
+    max: Option<MaxAccumulator>,
+}
+
+impl ColumnStats {
+    fn new(field_id: i32, typ: &PrimitiveType, field: &ArrowField) -> ColumnStats {
+        // Bounds for floating point columns must not include NaNs, which the
+        // accumulators don't account for. Skip writing bounds for those.
+        let (min, max) = match typ {
+            PrimitiveType::Float | PrimitiveType::Double | PrimitiveType::Uuid => (None, None),
+            _ => (
+                MinAccumulator::try_new(field.data_type()).ok(),
+                MaxAccumulator::try_new(field.data_type()).ok(),
+            ),
+        };
+
+        ColumnStats {
+            field_id,
+            nulls: 0,
+            min,
+            max,
+        }
+    }
+
+    fn update(&mut self, array: &ArrayRef) {
+        self.nulls += array.null_count() as i64;
+
+        // Bounds are optional, stop tracking them if they can't be computed
+        // for this type.
+        let values = [array.clone()];
+        if let Some(acc) = self.min.as_mut() {
+            if acc.update_batch(&values).is_err() {
+                self.min = None;
+            }
+        }
+        if let Some(acc) = self.max.as_mut() {
+            if acc.update_batch(&values).is_err() {
+                self.max = None;
+            }
+        }
+    }
+
+    /// Get the encoded lower and upper bounds for the column.
+    fn bounds(&mut self) -> (Option<Vec<u8>>, Option<Vec<u8>>) {
+        let lower = self
+            .min
+            .as_mut()
+            .and_then(|acc| acc.evaluate().ok())
+            .and_then(|v| encode_single_value(&v).ok().flatten());
+        let upper = self
+            .max
+            .as_mut()
+            .and_then(|acc| acc.evaluate().ok())
+            .and_then(|v| encode_single_value(&v).ok().flatten());
+        (lower, upper)
+    }
+}
+
+/// Write a manifest containing newly added data files.
+pub(crate) fn write_manifest(
+    schema: &Schema,
+    spec: &PartitionSpec,
+    snapshot_id: i64,
+    files: Vec<DataFile>,
+) -> Result<Vec<u8>> {
+    let avro_schema = AvroSchema::parse_str(MANIFEST_SCHEMA)?;
+    let mut writer = Writer::with_codec(&avro_schema, Vec::new(), Codec::Deflate);
+
+    writer.add_user_metadata("schema".to_string(), serde_json::to_string(schema)?)?;
+    writer.add_user_metadata("schema-id".to_string(), schema.schema_id.to_string())?;
+    writer.add_user_metadata(
+        "partition-spec".to_string(),
+        serde_json::to_string(&spec.fields)?,
+    )?;
+    writer.add_user_metadata("partition-spec-id".to_string(), spec.spec_id.to_string())?;
+    writer.add_user_metadata("format-version".to_string(), "2")?;
+    writer.add_user_metadata("content".to_string(), ManifestContent::Data.to_string())?;
+
+    for file in files {
+        let entry = ManifestEntry {
+            status: ManifestEntryStatus::Added as i32,
+            snapshot_id: Some(snapshot_id),
+            // Sequence numbers for added files are inherited from the
+            // manifest list entry.
+            sequence_number: None,
+            file_sequence_number: None,
+            data_file: file,
+        };
+
+        let mut value = to_value(&entry)?;
+        set_empty_partition(&mut value);
+        writer.append(value.resolve(&avro_schema)?)?;
+    }
+
+    Ok(writer.into_inner()?)
+}
+
+/// Partition values aren't serialized as part of the data file, so add the
+/// (empty) partition tuple to the raw entry.
+fn set_empty_partition(entry: &mut Value) {
+    if let Value::Record(fields) = entry {
+        for (name, value) in fields.iter_mut() {
+            if name != "data_file" {
+                continue;
+            }
+            if let Value::Record(data_file) = value {
+                data_file.push(("partition".to_string(), Value::Record(Vec::new())));
+            }
+        }
+    }
+}
+
+/// Write a manifest list for a snapshot.
+pub(crate) fn write_manifest_list(
+    snapshot: &Snapshot,
+    entries: &[ManifestListEntry],
+) -> Result<Vec<u8>> {
+    let avro_schema = AvroSchema::parse_str(MANIFEST_LIST_SCHEMA)?;
+    let mut writer = Writer::with_codec(&avro_schema, Vec::new(), Codec::Deflate);
+
+    writer.add_user_metadata("snapshot-id".to_string(), snapshot.snapshot_id.to_string())?;
+    writer.add_user_metadata(
+        "parent-snapshot-id".to_string(),
+        snapshot
+            .parent_snapshot_id
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| "null".to_string()),
+    )?;
+    writer.add_user_metadata(
+        "sequence-number".to_string(),
+        snapshot.sequence_number.to_string(),
+    )?;
+    writer.add_user_metadata("format-version".to_string(), "2")?;
+
+    for entry in entries {
+        writer.append(to_value(entry)?.resolve(&avro_schema)?)?;
+    }
+
+    Ok(writer.into_inner()?)
+}
+
+/// Build the summary for a snapshot appending the given files.
+pub(crate) fn append_summary(
+    parent: Option<&Snapshot>,
+    files: &[DataFile],
+) -> HashMap<String, String> {
+    let added_files = files.len() as i64;
+    let added_records: i64 = files.iter().map(|f| f.record_count).sum();
+    let added_size: i64 = files.iter().map(|f| f.file_size_in_bytes).sum();
+
+    let mut summary = HashMap::from([
+        ("operation".to_string(), "append".to_string()),
+        ("added-data-files".to_string(), added_files.to_string()),
+        ("added-records".to_string(), added_records.to_string()),
+        ("added-files-size".to_string(), added_size.to_string()),
+    ]);
+
+    // Totals can only be computed if the parent snapshot has them as well.
+    let total = |key: &str, added: i64| -> Option<i64> {
+        match parent {
+            Some(parent) => parent
+                .summary
+                .get(key)
+                .and_then(|v| v.parse::<i64>().ok())
+                .map(|v| v + added),
+            None => Some(added),
+        }
+    };
+
+    for (key, added) in [
+        ("total-data-files", added_files),
+        ("total-records", added_records),
+        ("total-files-size", added_size),
+        ("total-delete-files", 0),
+        ("total-position-deletes", 0),
+        ("total-equality-deletes", 0),
+    ] {
+        if let Some(v) = total(key, added) {
+            summary.insert(key.to_string(), v.to_string());
+        }
+    }
+
+    summary
+}
+
+/// Put a new object, failing if the object already exists.
+///
+/// Used for committing new metadata files to detect concurrent writes to the
+/// same table.
+pub(crate) async fn put_new(store: &dyn ObjectStore, path: &ObjectPath, bs: Bytes) -> Result<()> {
+    let already_exists = || {
+        IcebergError::DataInvalid(format!(
+            "Iceberg table was concurrently modified, '{path}' already exists"
+        ))
+    };
+
+    match store
+        .put_opts(path, bs.clone(), PutMode::Create.into())
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(object_store::Error::AlreadyExists { .. }) => Err(already_exists()),
+        Err(object_store::Error::NotImplemented) => {
+            // Not all stores support conditional puts. Fall back to checking
+            // if the object exists first.
+            match store.head(path).await {
+                Ok(_) => Err(already_exists()),
+                Err(object_store::Error::NotFound { .. }) => {
+                    store.put(path, bs).await?;
+                    Ok(())
+                }
+                Err(e) => Err(e.into()),
+            }
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::lake::iceberg::spec::{Manifest, ManifestList};
+
+    fn test_data_file(path: &str, records: i64) -> DataFile {
+        DataFile {
+            content: 0,
+            file_path: path.to_string(),
+            file_format: "PARQUET".to_string(),
+            record_count: records,
+            file_size_in_bytes: 1024,
+            column_sizes: None,
+            value_counts: Some(vec![I64Entry {
+                key: 1,
+                value: records,
+            }]),
+            null_value_counts: Some(vec![I64Entry { key: 1, value: 0 }]),
+            nan_value_counts: None,
+            distinct_counts: None,
+            lower_bounds: Some(vec![BinaryEntry {
+                key: 1,
+                value: 1_i64.to_le_bytes().to_vec(),
+            }]),
+            upper_bounds: Some(vec![BinaryEntry {
+                key: 1,
+                value: records.to_le_bytes().to_vec(),
+            }]),
+            key_metadata: None,
+            split_offsets: None,
+            equality_ids: None,
+            sort_order_id: None,
+            partition: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_write_manifest_roundtrip() {
+        let schema = Schema::try_from_arrow(
+            0,
+            &ArrowSchema::new(vec![ArrowField::new(
+                "a",
+                datafusion::arrow::datatypes::DataType::Int64,
+                true,
+            )]),
+        )
+        .unwrap();
+        let spec = PartitionSpec {
+            spec_id: 0,
+            fields: Vec::new(),
+        };
+
+        let bs = write_manifest(
+            &schema,
+            &spec,
+            42,
+            vec![
+                test_data_file("tbl/data/1.parquet", 10),
+                test_data_file("tbl/data/2.parquet", 20),
+            ],
+        )
+        .unwrap();
+
+        let manifest = Manifest::from_raw_avro(Cursor::new(bs)).unwrap();
+        assert_eq!(0, manifest.metadata.partition_spec_id);
+        assert_eq!(2, manifest.metadata.format_version);
+        assert_eq!(schema.fields, manifest.metadata.schema.fields);
+        assert!(manifest.metadata.partition_spec.is_empty());
+
+        assert_eq!(2, manifest.entries.len());
+        let entry = &manifest.entries[1];
+        assert_eq!(ManifestEntryStatus::Added as i32, entry.status);
+        assert_eq!(Some(42), entry.snapshot_id);
+        assert_eq!(None, entry.sequence_number);
+        assert_eq!("tbl/data/2.parquet", entry.data_file.file_path);
+        assert_eq!(20, entry.data_file.record_count);
+        let upper = &entry.data_file.upper_bounds.as_ref().unwrap()[0];
+        assert_eq!(20_i64.to_le_bytes().to_vec(), upper.value);
+    }
+
+    #[test]
+    fn test_write_manifest_list_roundtrip() {
+        let snapshot = Snapshot {
+            snapshot_id: 42,
+            parent_snapshot_id: None,
+            sequence_number: 3,
+            timestamp_ms: 0,
+            summary: HashMap::new(),
+            manifest_list: "tbl/metadata/snap-42.avro".to_string(),
+            schema_id: 0,
+        };
+        let entry = ManifestListEntry {
+            manifest_path: "tbl/metadata/m0.avro".to_string(),
+            manifest_length: 100,
+            partition_spec_id: 0,
+            content: 0,
+            sequence_number: 3,
+            min_sequence_number: 3,
+            added_snapshot_id: 42,
+            added_files_count: 2,
+            existing_files_count: 0,
+            deleted_files_count: 0,
+            added_rows_count: 30,
+            existing_rows_count: 0,
+            deleted_rows_count: 0,
+            partitions: Vec::new(),
+            key_metadata: None,
+        };
+
+        let bs = write_manifest_list(&snapshot, &[entry]).unwrap();
+
+        let list = ManifestList::from_raw_avro(Cursor::new(bs)).unwrap();
+        assert_eq!(1, list.entries.len());
+        let entry = &list.entries[0];
+        assert_eq!("tbl/metadata/m0.avro", entry.manifest_path);
+        assert_eq!(3, entry.sequence_number);
+        assert_eq!(42, entry.added_snapshot_id);
+        assert_eq!(2, entry.added_files_count);
+        assert_eq!(30, entry.added_rows_count);
+    }
+
+    #[test]
+    fn test_append_summary() {
+        let files = vec![
+            test_data_file("tbl/data/1.parquet", 10),
+            test_data_file("tbl/data/2.parquet", 20),
+        ];
+
+        let summary = append_summary(None, &files);
+        assert_eq!("append", summary["operation"]);
+        assert_eq!("2", summary["added-data-files"]);
+        assert_eq!("30", summary["total-records"]);
+
+        let parent = Snapshot {
+            snapshot_id: 1,
+            parent_snapshot_id: None,
+            sequence_number: 1,
+            timestamp_ms: 0,
+            summary,
+            manifest_list: String::new(),
+            schema_id: 0,
+        };
+        let summary = append_summary(Some(&parent), &files[..1]);
+        assert_eq!("1", summary["added-data-files"]);
+        assert_eq!("40", summary["total-records"]);
+        assert_eq!("3", summary["total-data-files"]);
+
+        // Parent without totals.
+        let parent = Snapshot {
+            summary: HashMap::new(),
+            ..parent
+        };
+        let summary = append_summary(Some(&parent), &files);
+        assert!(!summary.contains_key("total-records"));
+    }
+}
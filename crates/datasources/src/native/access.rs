@@ -3,41 +3,75 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
 use datafusion::arrow::datatypes::{DataType, Field, Schema as ArrowSchema, TimeUnit};
-use datafusion::common::ToDFSchema;
-use datafusion::datasource::TableProvider;
+use datafusion::common::stats::Precision;
+use datafusion::common::{Column, ToDFSchema};
+use datafusion::dataframe::DataFrame;
+use datafusion::datasource::file_format::parquet::ParquetFormat;
+use datafusion::datasource::file_format::FileFormat;
+use datafusion::datasource::listing::PartitionedFile;
+use datafusion::datasource::physical_plan::FileScanConfig;
+use datafusion::datasource::{provider_as_source, TableProvider};
 use datafusion::error::Result as DataFusionResult;
 use datafusion::execution::context::SessionState;
-use datafusion::logical_expr::{ident, Cast, LogicalPlan, TableProviderFilterPushDown, TableType};
+use datafusion::logical_expr::{
+    cast,
+    col,
+    ident,
+    lit,
+    when,
+    Cast,
+    LogicalPlan,
+    LogicalPlanBuilder,
+    TableProviderFilterPushDown,
+    TableType,
+};
 use datafusion::physical_expr::create_physical_expr;
 use datafusion::physical_expr::execution_props::ExecutionProps;
 use datafusion::physical_plan::empty::EmptyExec;
 use datafusion::physical_plan::projection::ProjectionExec;
-use datafusion::physical_plan::{ExecutionPlan, Statistics};
+use datafusion::physical_plan::{execute_stream, ExecutionPlan, Statistics};
 use datafusion::prelude::Expr;
 use datafusion_ext::metrics::ReadOnlyDataSourceMetricsExecAdapter;
 use deltalake::delta_datafusion::DataFusionMixins;
-use deltalake::kernel::{ArrayType, DataType as DeltaDataType};
+use deltalake::kernel::{Add, ArrayType, DataType as DeltaDataType, StructType};
 use deltalake::logstore::{default_logstore, logstores, LogStore, LogStoreFactory};
 use deltalake::operations::create::CreateBuilder;
 use deltalake::operations::delete::DeleteBuilder;
+use deltalake::operations::merge::MergeBuilder;
+use deltalake::operations::optimize::{OptimizeBuilder, OptimizeType};
+use deltalake::operations::restore::RestoreBuilder;
 use deltalake::operations::update::UpdateBuilder;
+use deltalake::operations::vacuum::VacuumBuilder;
 pub use deltalake::protocol::SaveMode;
 use deltalake::storage::{factories, ObjectStoreFactory, ObjectStoreRef, StorageOptions};
 use deltalake::{DeltaResult, DeltaTable, DeltaTableConfig};
 use futures::StreamExt;
 use object_store::path::Path as ObjectStorePath;
 use object_store::prefix::PrefixStore;
-use object_store::ObjectStore;
+use object_store::{ObjectMeta, ObjectStore};
 use object_store_util::shared::SharedObjectStore;
 use protogen::metastore::types::catalog::TableEntry;
-use protogen::metastore::types::options::{TableOptionsInternal, TableOptionsV0};
+use protogen::metastore::types::options::{
+    AutoCompactionPolicy,
+    InternalColumnDefinition,
+    TableOptionsInternal,
+    TableOptionsV0,
+    UniqueConstraint,
+};
 use serde_json::{json, Value};
+use tracing::warn;
 use url::Url;
 use uuid::Uuid;
 
+use crate::lake::delta::partition::{parse_partition_value, partition_schema};
+use crate::lake::delta::version::{self as delta_version, TableCommit, TableVersion};
 use crate::native::errors::{NativeError, Result};
 use crate::native::insert::NativeTableInsertExec;
+use crate::native::partition::{prune_files, PartitionPruningExec};
+use crate::native::transaction::{self, StagedTable, TransactionHandle, MATCHED_COLUMN};
+use crate::native::unique::{self, UniqueWriteGuard};
 
 #[derive(Debug, Clone)]
 pub struct NativeTableStorage {
@@ -60,6 +94,44 @@ pub struct NativeTableStorage {
     ///
     /// Arcs all the way down...
     pub store: SharedObjectStore,
+
+    /// The transaction currently open for the session (if any).
+    ///
+    /// Shared by all clones of the storage, so every plan executed for a
+    /// session sees the same staged writes.
+    txn: TransactionHandle,
+}
+
+/// An action taken for rows of the source of a merge.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MergeClause {
+    /// Update table rows matching a source row.
+    MatchedUpdate {
+        predicate: Option<Expr>,
+        updates: Vec<(String, Expr)>,
+    },
+    /// Delete table rows matching a source row.
+    MatchedDelete { predicate: Option<Expr> },
+    /// Insert a row for source rows not matching any table row. `values`
+    /// holds the value for every column of the table.
+    NotMatchedInsert {
+        predicate: Option<Expr>,
+        values: Vec<(String, Expr)>,
+    },
+}
+
+impl MergeClause {
+    pub fn predicate(&self) -> Option<&Expr> {
+        match self {
+            MergeClause::MatchedUpdate { predicate, .. }
+            | MergeClause::MatchedDelete { predicate }
+            | MergeClause::NotMatchedInsert { predicate, .. } => predicate.as_ref(),
+        }
+    }
+
+    pub fn is_matched(&self) -> bool {
+        !matches!(self, MergeClause::NotMatchedInsert { .. })
+    }
 }
 
 /// Deltalake is expecting a factory that implements [`ObjectStoreFactory`] and
@@ -174,6 +246,7 @@ impl NativeTableStorage {
             db_id,
             root_url,
             store: SharedObjectStore::new(store),
+            txn: TransactionHandle::default(),
         }
     }
 
@@ -182,11 +255,103 @@ impl NativeTableStorage {
         self.db_id
     }
 
+    /// Returns the handle to the session's native table transaction.
+    pub fn transaction(&self) -> &TransactionHandle {
+        &self.txn
+    }
+
     /// Returns the location of 'native' Delta Lake tables.
+    ///
+    /// Tables created in the currently open transaction are located in the
+    /// transaction's staging location until the transaction commits.
     fn table_prefix(&self, tbl_id: u32) -> String {
+        self.txn
+            .created_table_location(tbl_id)
+            .unwrap_or_else(|| self.committed_table_prefix(tbl_id))
+    }
+
+    /// Returns the permanent location of a native table.
+    fn committed_table_prefix(&self, tbl_id: u32) -> String {
         format!("databases/{}/tables/{}", self.db_id, tbl_id)
     }
 
+    /// Stage storage for a table created in the currently open transaction.
+    ///
+    /// The table is created in a location private to the transaction, and
+    /// moved to its permanent location when the transaction commits (see
+    /// `publish_created_tables`).
+    pub fn stage_created_table(&self, table: &TableEntry) {
+        if self.txn.created_table_location(table.meta.id).is_none() {
+            let location = format!("databases/{}/staged/{}", self.db_id, Uuid::new_v4());
+            self.txn.add_created_table(table.meta.id, location);
+        }
+    }
+
+    /// Move tables created in the transaction to their permanent locations.
+    ///
+    /// `tables` are the tables created in the transaction that still exist
+    /// once it commits. Storage for any other tables created in the
+    /// transaction is deleted.
+    ///
+    /// Errors if there's already storage at the permanent location of one of
+    /// the tables, which happens if another session committed a table with the
+    /// same oid first.
+    pub async fn publish_created_tables(&self, tables: &[TableEntry]) -> Result<()> {
+        let created = self.txn.take_created_tables();
+
+        let result = async {
+            for table in tables {
+                let staged = match created.get(&table.meta.id) {
+                    Some(staged) => ObjectStorePath::from(staged.as_str()),
+                    None => continue,
+                };
+                let location = ObjectStorePath::from(self.committed_table_prefix(table.meta.id));
+                if self.store.list(Some(&location)).next().await.is_some() {
+                    return Err(NativeError::SerializationFailure(table.meta.name.clone()));
+                }
+
+                let mut objects = self.store.list(Some(&staged));
+                while let Some(meta) = objects.next().await {
+                    let meta = meta?;
+                    let to = match meta.location.prefix_match(&staged) {
+                        Some(parts) => parts.fold(location.clone(), |path, part| path.child(part)),
+                        None => continue,
+                    };
+                    self.store.copy(&meta.location, &to).await?;
+                }
+            }
+            Ok(())
+        }
+        .await;
+
+        for staged in created.values() {
+            self.delete_prefix(staged).await;
+        }
+        result
+    }
+
+    /// Delete storage for all tables created in the transaction.
+    pub async fn discard_created_tables(&self) {
+        for staged in self.txn.take_created_tables().values() {
+            self.delete_prefix(staged).await;
+        }
+    }
+
+    /// Best-effort deletion of everything under a prefix.
+    async fn delete_prefix(&self, prefix: &str) {
+        let prefix = ObjectStorePath::from(prefix);
+        let mut objects = self.store.list(Some(&prefix));
+        while let Some(meta) = objects.next().await {
+            let result = match meta {
+                Ok(meta) => self.store.delete(&meta.location).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                warn!(%e, %prefix, "failed to delete staged table storage");
+            }
+        }
+    }
+
     /// Calculates the total size of storage being used by the database in
     /// bytes.
     pub async fn calculate_db_size(&self) -> Result<usize> {
@@ -215,18 +380,23 @@ impl NativeTableStorage {
                 .with_table_name(&table.meta.name)
                 .with_log_store(delta_store);
 
+            // Columns are always nullable in the delta schema, NOT NULL
+            // constraints are enforced when writing to the table.
             for col in &opts.columns {
                 let delta_col = arrow_to_delta_safe(&col.arrow_type)?;
                 builder = builder.with_column(
                     col.name.clone(),
                     delta_col.data_type,
-                    col.nullable,
+                    true,
                     delta_col.metadata,
                 );
             }
 
+            if !opts.partition_columns.is_empty() {
+                builder = builder.with_partition_columns(opts.partition_columns.clone());
+            }
+
             let delta_table = builder.await?;
-            // TODO: Partitioning
             NativeTable::new(delta_table)
         };
 
@@ -235,16 +405,158 @@ impl NativeTableStorage {
 
     /// Load a native table.
     ///
+    /// If the table has been written to in the currently open transaction,
+    /// the returned table will include the transaction's staged writes.
+    ///
     /// Errors if the table is not the correct type.
     pub async fn load_table(&self, table: &TableEntry) -> Result<NativeTable> {
-        let _ = Self::opts_from_ent(table)?; // Check that this is the correct table type.
+        let table_id = table.meta.id;
+        let opts = Self::opts_from_ent(table)?;
+        if let Some(staged) = self
+            .txn
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|txn| txn.get_table(table_id))
+        {
+            return Ok(self
+                .staged_provider(table, staged)?
+                .with_unique_constraints(opts.unique_constraints.clone()));
+        }
 
+        let delta = self.load_delta_table(table).await?;
+        Ok(NativeTable::new(delta)
+            .with_transaction(table_id, self.txn.clone(), None)
+            .with_auto_compaction(opts.auto_compaction)
+            .with_unique_constraints(opts.unique_constraints))
+    }
+
+    /// Load the latest committed version of the table.
+    async fn load_delta_table(&self, table: &TableEntry) -> Result<DeltaTable> {
         let delta_store = self.create_delta_store_for_table(table);
         let mut table = DeltaTable::new(delta_store, DeltaTableConfig::default());
 
         table.load().await?;
 
-        Ok(NativeTable::new(table))
+        Ok(table)
+    }
+
+    /// Load a previous version of a native table.
+    ///
+    /// The returned table is read-only, and doesn't include writes staged in
+    /// the currently open transaction.
+    pub async fn load_table_version(
+        &self,
+        table: &TableEntry,
+        version: TableVersion,
+    ) -> Result<NativeTable> {
+        let _ = Self::opts_from_ent(table)?;
+
+        let delta_store = self.create_delta_store_for_table(table);
+        let mut delta = DeltaTable::new(delta_store, DeltaTableConfig::default());
+        delta_version::load_version(&mut delta, version).await?;
+
+        Ok(NativeTable::new(delta))
+    }
+
+    /// List the commits to a native table, most recent first.
+    pub async fn table_history(
+        &self,
+        table: &TableEntry,
+        limit: Option<usize>,
+    ) -> Result<Vec<TableCommit>> {
+        let _ = Self::opts_from_ent(table)?;
+
+        let delta = self.load_delta_table(table).await?;
+        Ok(delta_version::table_history(&delta, limit).await?)
+    }
+
+    /// Restore a native table to a previous version by committing a new
+    /// version with the same contents. Returns the restored version.
+    ///
+    /// Tables can only be restored to versions with the same columns as the
+    /// current version, since the table's catalog entry isn't changed.
+    pub async fn restore_table(&self, table: &TableEntry, version: TableVersion) -> Result<i64> {
+        if self.txn.lock().await.is_some() {
+            return Err(NativeError::RestoreInTransaction);
+        }
+
+        let current = self.load_delta_table(table).await?;
+        let restored = self.load_table_version(table, version).await?.delta;
+        if restored.get_schema()? != current.get_schema()? {
+            return Err(NativeError::RestoreSchemaMismatch {
+                table: table.meta.name.clone(),
+                version,
+            });
+        }
+
+        let restored_version = restored.version();
+        if restored_version == current.version() {
+            return Ok(restored_version);
+        }
+
+        RestoreBuilder::new(current.log_store(), current.state.unwrap())
+            .with_version_to_restore(restored_version)
+            .await?;
+
+        Ok(restored_version)
+    }
+
+    /// Delete files that are no longer part of the table. Returns the number
+    /// of files deleted.
+    ///
+    /// Only files removed from the table longer ago than the table's retention
+    /// period are deleted. If `retain_hours` is provided, it's used instead of
+    /// the table's retention period. A shorter period is rejected unless
+    /// `enforce_retention` is false.
+    pub async fn vacuum_table(
+        &self,
+        table: &TableEntry,
+        retain_hours: Option<u64>,
+        enforce_retention: bool,
+    ) -> Result<usize> {
+        let _ = Self::opts_from_ent(table)?;
+        if self.txn.lock().await.is_some() {
+            return Err(NativeError::MaintenanceInTransaction("VACUUM"));
+        }
+
+        let delta = self.load_delta_table(table).await?;
+        let mut builder = VacuumBuilder::new(delta.log_store(), delta.state.unwrap());
+        if let Some(hours) = retain_hours {
+            let retention = i64::try_from(hours)
+                .ok()
+                .and_then(chrono::Duration::try_hours)
+                .ok_or(NativeError::Static("Retention period is too long"))?;
+            builder = builder
+                .with_retention_period(retention)
+                .with_enforce_retention_duration(enforce_retention);
+        }
+
+        let (_, metrics) = builder.await?;
+        Ok(metrics.files_deleted.len())
+    }
+
+    /// Rewrite the table's small files into fewer larger files.
+    ///
+    /// If `zorder_by` isn't empty, all files are rewritten with their rows
+    /// ordered by the Z-order of those columns instead.
+    pub async fn optimize_table(&self, table: &TableEntry, zorder_by: Vec<String>) -> Result<()> {
+        let _ = Self::opts_from_ent(table)?;
+        if self.txn.lock().await.is_some() {
+            return Err(NativeError::MaintenanceInTransaction("OPTIMIZE"));
+        }
+
+        let delta = self.load_delta_table(table).await?;
+        let optimize_type = if zorder_by.is_empty() {
+            OptimizeType::Compact
+        } else {
+            OptimizeType::ZOrder(zorder_by)
+        };
+        OptimizeBuilder::new(delta.log_store(), delta.state.unwrap())
+            .with_type(optimize_type)
+            .await?;
+
+        Ok(())
     }
 
     pub async fn delete_table(&self, table: &TableEntry) -> Result<()> {
@@ -283,9 +595,24 @@ impl NativeTableStorage {
 
     pub async fn delete_rows_where(
         &self,
+        state: &SessionState,
         table_entry: &TableEntry,
         where_expr: Option<Expr>,
     ) -> Result<usize> {
+        if let Some(txn) = self.txn.lock().await.as_mut() {
+            let staged = self.stage_table(txn, table_entry).await?;
+            let predicate = where_expr.unwrap_or(lit(true));
+
+            let schema = staged_provider_schema(staged)?;
+            let mut exprs: Vec<_> = schema.fields().iter().map(|f| col(f.name())).collect();
+            exprs.push(matched_expr(predicate)?);
+
+            let num_deleted = self
+                .rewrite_staged(state, table_entry, staged, exprs, true)
+                .await?;
+            return Ok(num_deleted as usize);
+        }
+
         let table = self.load_table(table_entry).await?;
         if let Some(where_expr) = where_expr {
             let deleted_rows =
@@ -311,10 +638,38 @@ impl NativeTableStorage {
 
     pub async fn update_rows_where(
         &self,
+        state: &SessionState,
         table: &TableEntry,
         updates: Vec<(String, Expr)>,
         where_expr: Option<Expr>,
     ) -> Result<usize> {
+        if let Some(txn) = self.txn.lock().await.as_mut() {
+            let staged = self.stage_table(txn, table).await?;
+            let predicate = where_expr.unwrap_or(lit(true));
+
+            let updates: HashMap<_, _> = updates.into_iter().collect();
+            let schema = staged_provider_schema(staged)?;
+            let mut exprs = Vec::with_capacity(schema.fields().len() + 1);
+            for field in schema.fields() {
+                let expr = match updates.get(field.name()) {
+                    Some(update) => {
+                        let update = cast(update.clone(), field.data_type().clone());
+                        when(predicate.clone(), update)
+                            .otherwise(col(field.name()))?
+                            .alias(field.name())
+                    }
+                    None => col(field.name()),
+                };
+                exprs.push(expr);
+            }
+            exprs.push(matched_expr(predicate)?);
+
+            let num_updated = self
+                .rewrite_staged(state, table, staged, exprs, false)
+                .await?;
+            return Ok(num_updated as usize);
+        }
+
         let table = self.load_table(table).await?;
         let mut builder = UpdateBuilder::new(table.delta.log_store(), table.delta.state.unwrap());
         for update in updates.into_iter() {
@@ -326,16 +681,485 @@ impl NativeTableStorage {
         let updated_rows = builder.await?.1.num_updated_rows;
         Ok(updated_rows)
     }
+
+    /// Insert rows into a table, resolving rows that conflict with existing
+    /// rows on `conflict_columns` by applying `updates` to the existing rows.
+    /// Conflicting rows are skipped if there are no updates.
+    ///
+    /// Within `updates` and `update_predicate`, columns of existing rows are
+    /// qualified by the table's name and columns of the proposed rows by
+    /// `source_alias`. Returns the number of rows inserted or updated.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upsert_rows(
+        &self,
+        state: &SessionState,
+        table: &TableEntry,
+        source: DataFrame,
+        source_alias: &str,
+        conflict_columns: &[String],
+        updates: Vec<(String, Expr)>,
+        update_predicate: Option<Expr>,
+    ) -> Result<usize> {
+        let target_alias = table.meta.name.as_str();
+        let on = conflict_columns
+            .iter()
+            .map(|column| qualified(target_alias, column).eq(qualified(source_alias, column)))
+            .reduce(Expr::and)
+            .ok_or(NativeError::Static("missing conflict columns"))?;
+
+        let mut clauses = Vec::with_capacity(2);
+        if !updates.is_empty() {
+            clauses.push(MergeClause::MatchedUpdate {
+                predicate: update_predicate,
+                updates,
+            });
+        }
+        clauses.push(MergeClause::NotMatchedInsert {
+            predicate: None,
+            values: source
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| (field.name().clone(), qualified(source_alias, field.name())))
+                .collect(),
+        });
+
+        self.merge_rows(
+            state,
+            table,
+            source,
+            source_alias,
+            target_alias,
+            on,
+            clauses,
+        )
+        .await
+    }
+
+    /// Merge rows from `source` into a native table or an external delta
+    /// table.
+    ///
+    /// Each row of the source is joined with the rows of the table matching
+    /// `on`, and the first clause that applies to the pair is executed.
+    /// Columns of the table are qualified by `target_alias` and columns of the
+    /// source by `source_alias`. Returns the number of rows inserted, updated
+    /// or deleted.
+    ///
+    /// This is executed as a Delta merge which is committed immediately, so
+    /// can't be run inside of a transaction.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn merge_rows(
+        &self,
+        state: &SessionState,
+        table: &TableEntry,
+        source: DataFrame,
+        source_alias: &str,
+        target_alias: &str,
+        on: Expr,
+        clauses: Vec<MergeClause>,
+    ) -> Result<usize> {
+        if self.txn.lock().await.is_some() {
+            return Err(NativeError::MergeInTransaction);
+        }
+
+        let delta = match &table.options {
+            TableOptionsV0::Delta(opts) => {
+                let storage_options = HashMap::from_iter(opts.storage_options.inner.clone());
+                deltalake::open_table_with_storage_options(&opts.location, storage_options).await?
+            }
+            _ => {
+                let _ = Self::opts_from_ent(table)?;
+                self.load_delta_table(table).await?
+            }
+        };
+        let mut builder = MergeBuilder::new(delta.log_store(), delta.state.unwrap(), on, source)
+            .with_source_alias(source_alias)
+            .with_target_alias(target_alias)
+            .with_session_state(state.clone());
+
+        for clause in clauses {
+            builder = match clause {
+                MergeClause::MatchedUpdate { predicate, updates } => {
+                    builder.when_matched_update(|mut update| {
+                        if let Some(predicate) = predicate {
+                            update = update.predicate(predicate);
+                        }
+                        for (column, expr) in updates {
+                            update = update.update(column, expr);
+                        }
+                        update
+                    })?
+                }
+                MergeClause::MatchedDelete { predicate } => {
+                    builder.when_matched_delete(|mut delete| {
+                        if let Some(predicate) = predicate {
+                            delete = delete.predicate(predicate);
+                        }
+                        delete
+                    })?
+                }
+                MergeClause::NotMatchedInsert { predicate, values } => builder
+                    .when_not_matched_insert(|mut insert| {
+                        if let Some(predicate) = predicate {
+                            insert = insert.predicate(predicate);
+                        }
+                        for (column, expr) in values {
+                            insert = insert.set(column, expr);
+                        }
+                        insert
+                    })?,
+            };
+        }
+
+        let (_, metrics) = builder.await?;
+        Ok(metrics.num_target_rows_inserted
+            + metrics.num_target_rows_updated
+            + metrics.num_target_rows_deleted)
+    }
+
+    /// Stage a change to the columns of a table.
+    ///
+    /// If `rewrite_exprs` is non-empty, all data files are rewritten by
+    /// projecting the expressions over the current contents of the table.
+    /// Otherwise only the table's schema is updated.
+    ///
+    /// Nothing is visible until the returned change is committed, which lets
+    /// callers validate the matching catalog change first. Inside of a
+    /// transaction, the change is staged with the transaction's other writes
+    /// to the table instead, and is only visible to the transaction until it
+    /// commits.
+    pub async fn stage_schema_change(
+        &self,
+        state: &SessionState,
+        table: &TableEntry,
+        columns: &[InternalColumnDefinition],
+        rewrite_exprs: Vec<Expr>,
+    ) -> Result<StagedSchemaChange> {
+        let _ = Self::opts_from_ent(table)?;
+        if let Some(txn) = self.txn.lock().await.as_mut() {
+            let staged = self.stage_table(txn, table).await?;
+            self.change_schema(state, table, staged, columns, rewrite_exprs)
+                .await?;
+            return Ok(StagedSchemaChange { staged: None });
+        }
+
+        let delta = self.load_delta_table(table).await?;
+        let mut staged = StagedTable::try_new(delta)?;
+        if let Err(e) = self
+            .change_schema(state, table, &mut staged, columns, rewrite_exprs)
+            .await
+        {
+            staged.cleanup_all().await;
+            return Err(e);
+        }
+
+        Ok(StagedSchemaChange {
+            staged: Some(staged),
+        })
+    }
+
+    /// Change the columns of a staged table.
+    ///
+    /// If `rewrite_exprs` is non-empty, all files visible to the staged table
+    /// are rewritten by projecting the expressions over its current contents.
+    async fn change_schema(
+        &self,
+        state: &SessionState,
+        table: &TableEntry,
+        staged: &mut StagedTable,
+        columns: &[InternalColumnDefinition],
+        rewrite_exprs: Vec<Expr>,
+    ) -> Result<()> {
+        // Read with the columns from before the change.
+        let provider = self.staged_provider(table, staged)?;
+        staged.set_schema(columns)?;
+
+        if !rewrite_exprs.is_empty() {
+            let plan = LogicalPlanBuilder::scan(
+                table.meta.name.as_str(),
+                provider_as_source(Arc::new(provider)),
+                None,
+            )?
+            .project(rewrite_exprs)?
+            .build()?;
+
+            let plan = state.create_physical_plan(&plan).await?;
+            let stream = execute_stream(plan, state.task_ctx())?;
+            let (adds, _, _) = staged.write_stream(stream, false).await?;
+            staged.replace_files(adds);
+        }
+
+        Ok(())
+    }
+
+    /// Get a provider for a table as seen by the transaction it's staged in.
+    fn staged_provider(&self, table: &TableEntry, staged: &StagedTable) -> Result<NativeTable> {
+        Ok(NativeTable::new(staged.table().clone())
+            .with_transaction(
+                table.meta.id,
+                self.txn.clone(),
+                Some(staged.files().to_vec()),
+            )
+            .with_staged_schema(staged.schema()?))
+    }
+
+    /// Get the staged writes for a table, loading the latest version of the
+    /// table if this is the first write to it in the transaction.
+    async fn stage_table<'a>(
+        &self,
+        txn: &'a mut transaction::NativeTransaction,
+        table: &TableEntry,
+    ) -> Result<&'a mut StagedTable> {
+        let table_id = table.meta.id;
+        if txn.get_table(table_id).is_none() {
+            let opts = Self::opts_from_ent(table)?;
+            let delta = self.load_delta_table(table).await?;
+            txn.get_or_stage_table(table_id, &delta, &opts.unique_constraints)?;
+        }
+        Ok(txn
+            .get_table_mut(table_id)
+            .expect("table to have been staged"))
+    }
+
+    /// Rewrite all files visible to the transaction by projecting `exprs` over
+    /// the current contents of the table.
+    ///
+    /// The last expression must produce `MATCHED_COLUMN`, indicating if the
+    /// row was affected by the operation. Returns the number of affected rows.
+    async fn rewrite_staged(
+        &self,
+        state: &SessionState,
+        table: &TableEntry,
+        staged: &mut StagedTable,
+        exprs: Vec<Expr>,
+        skip_matched: bool,
+    ) -> Result<u64> {
+        let provider = self.staged_provider(table, staged)?;
+
+        let plan = LogicalPlanBuilder::scan(
+            table.meta.name.as_str(),
+            provider_as_source(Arc::new(provider)),
+            None,
+        )?
+        .project(exprs)?
+        .build()?;
+
+        let plan = state.create_physical_plan(&plan).await?;
+        let stream = execute_stream(plan, state.task_ctx())?;
+        let (adds, _, num_matched) = staged.write_stream(stream, skip_matched).await?;
+
+        if num_matched == 0 {
+            // Nothing changed, keep the existing files.
+            staged.discard_files(adds).await;
+        } else {
+            staged.replace_files(adds);
+        }
+
+        Ok(num_matched)
+    }
+}
+
+/// A change to the columns of a table that hasn't been committed yet.
+///
+/// The change must either be committed or aborted. Aborting deletes any data
+/// files that were rewritten for the change.
+///
+/// Changes made inside of a transaction are part of the transaction, and are
+/// committed or rolled back along with it.
+#[derive(Debug)]
+pub struct StagedSchemaChange {
+    /// The change, if it's not part of a transaction.
+    staged: Option<StagedTable>,
+}
+
+impl StagedSchemaChange {
+    /// Commit the new schema (and rewritten files) to the table.
+    pub async fn commit(self) -> Result<()> {
+        match self.staged {
+            Some(staged) => staged.commit().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Discard the change.
+    pub async fn abort(self) {
+        if let Some(staged) = self.staged {
+            staged.cleanup_all().await
+        }
+    }
+}
+
+/// Logical schema of the table as seen by the transaction.
+fn staged_provider_schema(staged: &StagedTable) -> Result<Arc<ArrowSchema>> {
+    Ok(NativeTable::new(staged.table().clone())
+        .with_staged_schema(staged.schema()?)
+        .schema())
+}
+
+/// Create the expression for `MATCHED_COLUMN`.
+fn matched_expr(predicate: Expr) -> Result<Expr> {
+    Ok(when(predicate, lit(true))
+        .otherwise(lit(false))?
+        .alias(MATCHED_COLUMN))
+}
+
+/// A column reference qualified by a table alias.
+fn qualified(alias: &str, column: &str) -> Expr {
+    Expr::Column(Column::new(Some(alias.to_string()), column))
 }
 
 #[derive(Debug)]
 pub struct NativeTable {
     delta: DeltaTable,
+    txn: Option<TableTransaction>,
+    /// Policy for compacting the table after inserts are committed.
+    auto_compaction: Option<AutoCompactionPolicy>,
+    /// PRIMARY KEY and UNIQUE constraints that writes to the table must
+    /// satisfy.
+    unique_constraints: Vec<UniqueConstraint>,
+    /// Schema of the table if its columns were changed by the transaction.
+    staged_schema: Option<StructType>,
+}
+
+/// Transaction state for a native table loaded within a session.
+#[derive(Debug, Clone)]
+struct TableTransaction {
+    table_id: u32,
+    handle: TransactionHandle,
+    /// Files visible to the transaction if the table has staged writes.
+    staged_files: Option<Vec<Add>>,
 }
 
 impl NativeTable {
     pub fn new(delta: DeltaTable) -> Self {
-        NativeTable { delta }
+        NativeTable {
+            delta,
+            txn: None,
+            auto_compaction: None,
+            unique_constraints: Vec::new(),
+            staged_schema: None,
+        }
+    }
+
+    /// Associate the table with a session's transaction.
+    ///
+    /// Writes to the table will be staged if a transaction is open during
+    /// execution. If `staged_files` is provided, scans read those files
+    /// instead of the files of the loaded table version.
+    fn with_transaction(
+        mut self,
+        table_id: u32,
+        handle: TransactionHandle,
+        staged_files: Option<Vec<Add>>,
+    ) -> Self {
+        self.txn = Some(TableTransaction {
+            table_id,
+            handle,
+            staged_files,
+        });
+        self
+    }
+
+    /// Compact the table in the background according to `policy` after
+    /// inserts are committed.
+    fn with_auto_compaction(mut self, policy: Option<AutoCompactionPolicy>) -> Self {
+        self.auto_compaction = policy;
+        self
+    }
+
+    fn with_unique_constraints(mut self, constraints: Vec<UniqueConstraint>) -> Self {
+        self.unique_constraints = constraints;
+        self
+    }
+
+    /// Read the table with the schema from a column change staged in a
+    /// transaction, instead of the schema of the loaded table version.
+    fn with_staged_schema(mut self, schema: Option<StructType>) -> Self {
+        self.staged_schema = schema;
+        self
+    }
+
+    /// PRIMARY KEY and UNIQUE constraints of the table.
+    ///
+    /// These aren't enforced by the table itself, callers writing to the
+    /// table are expected to check them.
+    pub fn unique_constraints(&self) -> &[UniqueConstraint] {
+        &self.unique_constraints
+    }
+
+    /// Wait for the table's write lock if it has unique constraints.
+    ///
+    /// Writes to tables with PRIMARY KEY or UNIQUE constraints should hold the
+    /// lock from checking their keys until they're committed, and check their
+    /// keys against the `latest` version of the table loaded after taking it.
+    ///
+    /// Returns `None` if the table has no unique constraints, or if writes
+    /// will be staged in an open transaction. Those are checked again when the
+    /// transaction commits instead.
+    pub async fn lock_unique_writes(&self) -> Option<UniqueWriteGuard> {
+        if self.unique_constraints.is_empty() {
+            return None;
+        }
+        if let Some(txn) = &self.txn {
+            if txn.handle.is_open().await {
+                return None;
+            }
+        }
+        Some(unique::lock_table(&self.delta.table_uri()).await)
+    }
+
+    /// Get the latest committed version of the table.
+    ///
+    /// Tables with writes staged in a transaction are returned as they are.
+    pub async fn latest(&self) -> Result<NativeTable> {
+        let mut delta = self.delta.clone();
+        if self.staged_files().is_none() {
+            delta.update().await?;
+        }
+        Ok(NativeTable {
+            delta,
+            txn: self.txn.clone(),
+            auto_compaction: self.auto_compaction,
+            unique_constraints: self.unique_constraints.clone(),
+            staged_schema: self.staged_schema.clone(),
+        })
+    }
+
+    /// Columns the table is partitioned by.
+    fn partition_columns(&self) -> Vec<String> {
+        self.delta
+            .snapshot()
+            .map(|snapshot| snapshot.metadata().partition_columns.clone())
+            .unwrap_or_default()
+    }
+
+    /// Schema columns are read from data files with, which has the partition
+    /// columns last.
+    fn scan_schema(&self) -> DeltaResult<Arc<ArrowSchema>> {
+        let schema = match &self.staged_schema {
+            Some(schema) => schema,
+            None => return self.delta.snapshot()?.arrow_schema(),
+        };
+
+        let partition_columns = self.partition_columns();
+        let (partitions, fields): (Vec<_>, Vec<_>) = schema
+            .fields()
+            .into_iter()
+            .partition(|f| partition_columns.contains(f.name()));
+        let fields = fields
+            .into_iter()
+            .chain(partitions)
+            .map(Field::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Arc::new(ArrowSchema::new(fields)))
+    }
+
+    /// Files visible to the current transaction, if the table has staged
+    /// writes.
+    fn staged_files(&self) -> Option<&[Add]> {
+        self.txn
+            .as_ref()
+            .and_then(|txn| txn.staged_files.as_deref())
     }
 
     pub fn storage_location(&self) -> String {
@@ -360,12 +1184,92 @@ impl NativeTable {
 
         let store = self.delta.log_store();
         let snapshot = self.delta.state.clone();
-        Arc::new(NativeTableInsertExec::new(
-            input,
-            store,
-            snapshot.unwrap(),
-            save_mode,
-        ))
+        let exec = NativeTableInsertExec::new(input, store, snapshot.unwrap(), save_mode)
+            .with_auto_compaction(self.auto_compaction);
+        let exec = match &self.txn {
+            Some(txn) => exec.with_transaction(
+                txn.table_id,
+                txn.handle.clone(),
+                self.delta.clone(),
+                self.unique_constraints.clone(),
+            ),
+            None => exec,
+        };
+        Arc::new(exec)
+    }
+
+    /// Create a parquet scan over the files visible to the transaction.
+    pub(crate) async fn scan_staged_files(
+        &self,
+        session: &SessionState,
+        files: &[Add],
+        projection: Option<&Vec<usize>>,
+        limit: Option<usize>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        let log_store = self.delta.log_store();
+        let object_store_url = log_store.object_store_url();
+        session
+            .runtime_env()
+            .register_object_store(object_store_url.as_ref(), log_store.object_store());
+
+        let partition_columns = self.partition_columns();
+        let scan_schema = self.scan_schema()?;
+        let file_schema = Arc::new(ArrowSchema::new(
+            scan_schema
+                .fields()
+                .iter()
+                .filter(|f| !partition_columns.contains(f.name()))
+                .cloned()
+                .collect::<Vec<_>>(),
+        ));
+        let table_partition_cols = partition_schema(&scan_schema, &partition_columns)?
+            .fields()
+            .iter()
+            .map(|f| f.as_ref().clone())
+            .collect::<Vec<_>>();
+
+        let partitioned_files = files
+            .iter()
+            .map(|add| {
+                let partition_values = table_partition_cols
+                    .iter()
+                    .map(|field| {
+                        let value = add.partition_values.get(field.name()).cloned().flatten();
+                        parse_partition_value(value.as_deref(), field.data_type())
+                    })
+                    .collect::<DataFusionResult<Vec<_>>>()?;
+                Ok(PartitionedFile {
+                    object_meta: ObjectMeta {
+                        location: ObjectStorePath::from(add.path.as_str()),
+                        last_modified: Utc
+                            .timestamp_millis_opt(add.modification_time)
+                            .single()
+                            .unwrap_or(DateTime::<Utc>::MIN_UTC),
+                        size: add.size as usize,
+                        e_tag: None,
+                        version: None,
+                    },
+                    partition_values,
+                    range: None,
+                    extensions: None,
+                })
+            })
+            .collect::<DataFusionResult<Vec<_>>>()?;
+
+        let conf = FileScanConfig {
+            object_store_url,
+            statistics: Statistics::new_unknown(file_schema.as_ref()),
+            file_schema,
+            projection: projection.cloned(),
+            file_groups: vec![partitioned_files],
+            limit,
+            table_partition_cols,
+            output_ordering: Vec::new(),
+        };
+
+        ParquetFormat::new()
+            .create_physical_plan(session, conf, None)
+            .await
     }
 }
 
@@ -379,9 +1283,16 @@ impl TableProvider for NativeTable {
     /// so we need to do a projection to convert them back to the original type.
     /// Ideally we should store the original type in a more accessible way (such as using the binary type and deserializing it ourselves)
     /// but for now we just do a projection
+    ///
+    /// Columns are returned in the order they were defined in. Delta moves
+    /// partition columns to the end when reading, which is also undone by the
+    /// projection.
     fn schema(&self) -> Arc<ArrowSchema> {
         let mut fields = vec![];
-        let arrow_schema = self.delta.snapshot().unwrap().arrow_schema().unwrap();
+        let arrow_schema = match &self.staged_schema {
+            Some(schema) => ArrowSchema::try_from(schema).unwrap(),
+            None => ArrowSchema::try_from(self.delta.snapshot().unwrap().schema()).unwrap(),
+        };
 
         for col in arrow_schema.fields() {
             let mut field = col.clone();
@@ -429,24 +1340,75 @@ impl TableProvider for NativeTable {
             usize::default()
         };
 
-        if num_rows == 0 {
+        let is_empty = match self.staged_files() {
+            Some(files) => files.is_empty(),
+            None => num_rows == 0,
+        };
+
+        if is_empty {
             let schema = self.schema();
             Ok(Arc::new(EmptyExec::new(schema)))
         } else {
-            let plan = self.delta.scan(session, projection, filters, limit).await?;
-            let output_schema = plan.schema();
             let mut schema = self.schema();
+            let partition_columns = self.partition_columns();
+
+            // Files are read with partition columns last, so the projection
+            // needs to be mapped onto the order delta reads columns in.
+            let scan_projection = if partition_columns.is_empty() {
+                projection.cloned()
+            } else {
+                let scan_schema = self.scan_schema()?;
+                let indices = match projection {
+                    Some(projection) => projection.clone(),
+                    None => (0..schema.fields().len()).collect(),
+                };
+                Some(
+                    indices
+                        .iter()
+                        .map(|idx| scan_schema.index_of(schema.field(*idx).name()))
+                        .collect::<Result<Vec<_>, _>>()?,
+                )
+            };
+
+            let pruned = if partition_columns.is_empty() {
+                None
+            } else {
+                let files = match self.staged_files() {
+                    Some(files) => files.to_vec(),
+                    None => self.delta.snapshot()?.file_actions()?,
+                };
+                let partition_schema = partition_schema(&schema, &partition_columns)?;
+                Some(prune_files(&files, &partition_schema, filters)?)
+            };
+
+            let plan = match (self.staged_files(), &pruned) {
+                (Some(_), Some(pruned)) => {
+                    self.scan_staged_files(session, &pruned.files, scan_projection.as_ref(), limit)
+                        .await?
+                }
+                (Some(files), None) => {
+                    self.scan_staged_files(session, files, scan_projection.as_ref(), limit)
+                        .await?
+                }
+                // Delta prunes the files using the filters itself.
+                (None, _) => {
+                    self.delta
+                        .scan(session, scan_projection.as_ref(), filters, limit)
+                        .await?
+                }
+            };
+            let output_schema = plan.schema();
             if let Some(projection) = projection {
                 schema = Arc::new(schema.project(projection)?);
             }
             let df_schema = output_schema.clone().to_dfschema_ref()?;
 
             let plan = if output_schema != schema {
-                let exprs = output_schema
+                let exprs = schema
                     .fields()
-                    .into_iter()
-                    .zip(schema.fields())
-                    .map(|(f1, f2)| {
+                    .iter()
+                    .map(|f2| {
+                        let f1 = output_schema.field_with_name(f2.name())?;
                         let expr = if f1.data_type() == f2.data_type() {
                             ident(f1.name())
                         } else {
@@ -455,18 +1417,25 @@ impl TableProvider for NativeTable {
                             Expr::Cast(cast_expr)
                         };
                         let execution_props = ExecutionProps::new();
-                        (
-                            create_physical_expr(&expr, &df_schema, &execution_props).unwrap(),
+                        Ok((
+                            create_physical_expr(&expr, &df_schema, &execution_props)?,
                             f1.name().clone(),
-                        )
+                        ))
                     })
-                    .collect::<Vec<_>>();
+                    .collect::<DataFusionResult<Vec<_>>>()?;
                 let prj = ProjectionExec::try_new(exprs, plan)?;
                 // we need to do a projection to match the schema
                 Arc::new(prj)
             } else {
                 plan
             };
+
+            let plan = match pruned {
+                Some(pruned) => {
+                    Arc::new(PartitionPruningExec::new(plan, partition_columns, &pruned))
+                }
+                None => plan,
+            };
             Ok(Arc::new(ReadOnlyDataSourceMetricsExecAdapter::new(plan)))
         }
     }
@@ -480,7 +1449,29 @@ impl TableProvider for NativeTable {
     }
 
     fn statistics(&self) -> Option<Statistics> {
-        self.delta.statistics()
+        match self.staged_files() {
+            Some(files) => {
+                let schema = self.schema();
+                let num_rows = match transaction::num_records(files) {
+                    Some(num_rows) => Precision::Exact(num_rows),
+                    None => Precision::Absent,
+                };
+                Some(Statistics {
+                    num_rows,
+                    total_byte_size: Precision::Absent,
+                    column_statistics: Statistics::unknown_column(&schema),
+                })
+            }
+            // Column statistics from delta are in the order it reads columns
+            // in, which differs from the table's for partitioned tables.
+            None if !self.partition_columns().is_empty() => {
+                self.delta.statistics().map(|stats| Statistics {
+                    column_statistics: Statistics::unknown_column(&self.schema()),
+                    ..stats
+                })
+            }
+            None => self.delta.statistics(),
+        }
     }
 
     async fn insert_into(
@@ -495,16 +1486,29 @@ impl TableProvider for NativeTable {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::Int32Array;
     use datafusion::arrow::datatypes::DataType;
+    use datafusion::arrow::record_batch::RecordBatch;
+    use datafusion::datasource::TableProvider;
+    use datafusion::physical_plan::collect;
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::prelude::SessionContext;
     use deltalake::protocol::SaveMode;
     use object_store_util::conf::StorageConfig;
     use protogen::metastore::types::catalog::{EntryMeta, EntryType, SourceAccessMode, TableEntry};
-    use protogen::metastore::types::options::{InternalColumnDefinition, TableOptionsInternal};
+    use protogen::metastore::types::options::{
+        InternalColumnDefinition,
+        TableOptionsInternal,
+        UniqueConstraint,
+    };
     use tempfile::tempdir;
     use url::Url;
     use uuid::Uuid;
 
     use crate::native::access::NativeTableStorage;
+    use crate::native::errors::NativeError;
 
     #[tokio::test]
     async fn test_delete_table() {
@@ -529,13 +1533,22 @@ mod tests {
                 builtin: false,
                 external: false,
                 is_temp: false,
+                description: None,
             },
             options: TableOptionsInternal {
                 columns: vec![InternalColumnDefinition {
                     name: "id".to_string(),
                     nullable: true,
                     arrow_type: DataType::Int32,
+                    default_expr: None,
+                    generated_always: false,
+                    description: None,
                 }],
+                check_constraints: Vec::new(),
+                unique_constraints: Vec::new(),
+                auto_compaction: None,
+                partition_columns: Vec::new(),
+                materialized_view: None,
             }
             .into(),
             tunnel_id: None,
@@ -558,4 +1571,93 @@ mod tests {
             .unwrap_err();
         assert_eq!(err, "Error loading table");
     }
+
+    async fn insert_id(storage: &NativeTableStorage, entry: &TableEntry, id: i32) {
+        let table = storage.load_table(entry).await.unwrap();
+        let schema = table.schema();
+        let batch =
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(vec![id]))])
+                .unwrap();
+        let input = Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap());
+        let exec = table.insert_exec(input, false);
+        collect(exec, SessionContext::new().task_ctx())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_commit_checks_keys_committed_concurrently() {
+        let db_id = Uuid::new_v4();
+        let dir = tempdir().unwrap();
+        let new_storage = || {
+            let conf = StorageConfig::Local {
+                path: dir.path().to_path_buf(),
+            };
+            NativeTableStorage::new(
+                db_id,
+                Url::from_file_path(dir.path()).unwrap(),
+                conf.new_object_store().unwrap(),
+            )
+        };
+        // Two sessions writing to the same table.
+        let storage = new_storage();
+        let other = new_storage();
+
+        let entry = TableEntry {
+            meta: EntryMeta {
+                entry_type: EntryType::Table,
+                id: 12345,
+                parent: 54321,
+                name: "table_1".to_string(),
+                builtin: false,
+                external: false,
+                is_temp: false,
+                description: None,
+            },
+            options: TableOptionsInternal {
+                columns: vec![InternalColumnDefinition {
+                    name: "id".to_string(),
+                    nullable: true,
+                    arrow_type: DataType::Int32,
+                    default_expr: None,
+                    generated_always: false,
+                    description: None,
+                }],
+                check_constraints: Vec::new(),
+                unique_constraints: vec![UniqueConstraint {
+                    name: "table_1_pkey".to_string(),
+                    columns: vec!["id".to_string()],
+                    primary_key: true,
+                }],
+                auto_compaction: None,
+                partition_columns: Vec::new(),
+                materialized_view: None,
+            }
+            .into(),
+            tunnel_id: None,
+            access_mode: SourceAccessMode::ReadWrite,
+            columns: None,
+        };
+
+        storage
+            .create_table(&entry, SaveMode::ErrorIfExists)
+            .await
+            .unwrap();
+
+        // Different keys committed in the meantime don't conflict.
+        storage.transaction().begin().await.unwrap();
+        insert_id(&storage, &entry, 1).await;
+        insert_id(&other, &entry, 2).await;
+        storage.transaction().commit().await.unwrap();
+
+        // The same key committed in the meantime does.
+        storage.transaction().begin().await.unwrap();
+        insert_id(&storage, &entry, 3).await;
+        insert_id(&other, &entry, 3).await;
+        let err = storage.transaction().commit().await.unwrap_err();
+        assert!(
+            matches!(&err, NativeError::UniqueViolation { constraint } if constraint == "table_1_pkey"),
+            "unexpected error: {err}"
+        );
+    }
 }
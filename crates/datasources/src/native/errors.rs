@@ -15,12 +15,48 @@ pub enum NativeError {
     #[error(transparent)]
     Arrow(#[from] datafusion::arrow::error::ArrowError),
 
+    #[error(transparent)]
+    Parquet(#[from] datafusion::parquet::errors::ParquetError),
+
     #[error(transparent)]
     UrlParse(#[from] url::ParseError),
 
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+
     #[error("Table entry not a native table: {0}")]
     NotNative(protogen::metastore::types::catalog::TableEntry),
 
+    #[error("There is already a transaction in progress")]
+    TransactionInProgress,
+
+    #[error("There is no transaction in progress")]
+    NoTransaction,
+
+    #[error("Could not serialize access due to a concurrent update to table {0}")]
+    SerializationFailure(String),
+
+    #[error("Transaction was only partially committed, writes to some tables were committed before failing: {0}")]
+    PartialCommit(Box<NativeError>),
+
+    #[error("duplicate key value violates unique constraint \"{constraint}\"")]
+    UniqueViolation { constraint: String },
+
+    #[error("MERGE and INSERT ... ON CONFLICT are not supported inside a transaction")]
+    MergeInTransaction,
+
+    #[error("RESTORE TABLE is not supported inside a transaction")]
+    RestoreInTransaction,
+
+    #[error("{0} is not supported inside a transaction")]
+    MaintenanceInTransaction(&'static str),
+
+    #[error("Cannot restore table '{table}' to {version} since its columns have changed")]
+    RestoreSchemaMismatch {
+        table: String,
+        version: crate::lake::delta::version::TableVersion,
+    },
+
     #[error("{0}")]
     Static(&'static str),
 }
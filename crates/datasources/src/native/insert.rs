@@ -1,4 +1,5 @@
 use std::any::Any;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use datafusion::arrow::datatypes::{DataType, SchemaRef};
@@ -11,6 +12,7 @@ use datafusion::physical_expr::{create_physical_expr, PhysicalSortExpr};
 use datafusion::physical_plan::projection::ProjectionExec;
 use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion::physical_plan::{
+    execute_stream,
     DisplayAs,
     DisplayFormatType,
     Distribution,
@@ -21,12 +23,20 @@ use datafusion::physical_plan::{
 };
 use deltalake::kernel::StructField;
 use deltalake::logstore::LogStore;
+use deltalake::operations::optimize::OptimizeBuilder;
 use deltalake::operations::write::WriteBuilder;
 use deltalake::protocol::SaveMode;
 use deltalake::table::state::DeltaTableState;
+use deltalake::DeltaTable;
 use futures::StreamExt;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use protogen::metastore::types::options::{AutoCompactionPolicy, UniqueConstraint};
+use tracing::{debug, warn};
 
 use crate::common::util::{create_count_record_batch, COUNT_SCHEMA};
+use crate::native::errors::Result;
+use crate::native::transaction::TransactionHandle;
 
 /// An execution plan for inserting data into a delta table.
 #[derive(Debug)]
@@ -35,6 +45,17 @@ pub struct NativeTableInsertExec {
     store: Arc<dyn LogStore>,
     snapshot: DeltaTableState,
     save_mode: SaveMode,
+    txn: Option<InsertTransaction>,
+    auto_compaction: Option<AutoCompactionPolicy>,
+}
+
+/// The transaction to stage writes in if it's open during execution.
+#[derive(Debug, Clone)]
+struct InsertTransaction {
+    table_id: u32,
+    handle: TransactionHandle,
+    table: DeltaTable,
+    unique_constraints: Vec<UniqueConstraint>,
 }
 
 impl NativeTableInsertExec {
@@ -49,7 +70,69 @@ impl NativeTableInsertExec {
             store,
             snapshot,
             save_mode,
+            txn: None,
+            auto_compaction: None,
+        }
+    }
+
+    /// Compact the table in the background after committing the insert if
+    /// the table has enough small files according to `policy`.
+    pub fn with_auto_compaction(mut self, policy: Option<AutoCompactionPolicy>) -> Self {
+        self.auto_compaction = policy;
+        self
+    }
+
+    /// Stage the inserted data in the session's transaction instead of
+    /// committing it, if a transaction is open when the plan is executed.
+    ///
+    /// Staged rows are checked against `unique_constraints` again when the
+    /// transaction commits.
+    pub fn with_transaction(
+        mut self,
+        table_id: u32,
+        handle: TransactionHandle,
+        table: DeltaTable,
+        unique_constraints: Vec<UniqueConstraint>,
+    ) -> Self {
+        self.txn = Some(InsertTransaction {
+            table_id,
+            handle,
+            table,
+            unique_constraints,
+        });
+        self
+    }
+}
+
+impl InsertTransaction {
+    /// Write the input to the transaction if it's open.
+    ///
+    /// Returns `None` if there's no open transaction, otherwise returns the
+    /// number of rows written.
+    async fn try_stage(
+        &self,
+        input: Arc<dyn ExecutionPlan>,
+        context: Arc<TaskContext>,
+        save_mode: SaveMode,
+    ) -> Result<Option<u64>> {
+        let mut txn = self.handle.lock().await;
+        let txn = match txn.as_mut() {
+            Some(txn) => txn,
+            None => return Ok(None),
+        };
+
+        let staged =
+            txn.get_or_stage_table(self.table_id, &self.table, &self.unique_constraints)?;
+        let stream = execute_stream(input, context)?;
+        let (adds, num_written, _) = staged.write_stream(stream, false).await?;
+
+        if matches!(save_mode, SaveMode::Overwrite) {
+            staged.replace_files(adds);
+        } else {
+            staged.add_files(adds);
         }
+
+        Ok(Some(num_written))
     }
 }
 
@@ -91,6 +174,8 @@ impl ExecutionPlan for NativeTableInsertExec {
             store: self.store.clone(),
             snapshot: self.snapshot.clone(),
             save_mode: self.save_mode,
+            txn: self.txn.clone(),
+            auto_compaction: self.auto_compaction,
         }))
     }
 
@@ -165,11 +250,29 @@ impl ExecutionPlan for NativeTableInsertExec {
             .with_save_mode(self.save_mode)
             .with_input_execution_plan(input.clone());
 
+        let txn = self.txn.clone();
+        let save_mode = self.save_mode;
+        let auto_compaction = self.auto_compaction;
+
         let output = futures::stream::once(async move {
-            let _ = builder
+            if let Some(txn) = txn {
+                let staged = txn
+                    .try_stage(input.clone(), context, save_mode)
+                    .await
+                    .map_err(|e| DataFusionError::External(Box::new(e)))?;
+                if let Some(count) = staged {
+                    return Ok(create_count_record_batch(count));
+                }
+            }
+
+            let table = builder
                 .await
                 .map_err(|e| DataFusionError::External(Box::new(e)))?;
 
+            if let Some(policy) = auto_compaction {
+                spawn_auto_compaction(table, policy);
+            }
+
             let count = input
                 .metrics()
                 .map(|metrics| metrics.output_rows().unwrap_or_default())
@@ -190,6 +293,79 @@ impl ExecutionPlan for NativeTableInsertExec {
     }
 }
 
+/// Compact the table in a background task if it has at least as many small
+/// files as required by the policy.
+///
+/// Files are small if they're smaller than the table's target file size.
+/// Failing to compact the table doesn't affect the insert, and the table will
+/// be compacted again after the next insert.
+fn spawn_auto_compaction(table: DeltaTable, policy: AutoCompactionPolicy) {
+    let snapshot = match table.snapshot() {
+        Ok(snapshot) => snapshot.clone(),
+        Err(e) => {
+            warn!(%e, "failed to get snapshot for auto compaction");
+            return;
+        }
+    };
+
+    let target_size = snapshot.table_config().target_file_size();
+    let num_small_files = match snapshot.file_actions() {
+        Ok(files) => files.iter().filter(|add| add.size < target_size).count(),
+        Err(e) => {
+            warn!(%e, "failed to list files for auto compaction");
+            return;
+        }
+    };
+    if (num_small_files as u64) < policy.min_files {
+        return;
+    }
+
+    let table_uri = table.table_uri();
+    let guard = match CompactionGuard::try_new(&table_uri) {
+        Some(guard) => guard,
+        None => {
+            debug!(%table_uri, "auto compaction already in progress");
+            return;
+        }
+    };
+
+    tokio::spawn(async move {
+        let _guard = guard;
+        match OptimizeBuilder::new(table.log_store(), snapshot).await {
+            Ok((_, metrics)) => debug!(
+                %table_uri,
+                files_removed = metrics.num_files_removed,
+                files_added = metrics.num_files_added,
+                "compacted native table"
+            ),
+            Err(e) => warn!(%e, %table_uri, "failed to compact native table"),
+        }
+    });
+}
+
+/// Uris of tables with an auto compaction currently running.
+static COMPACTIONS_IN_FLIGHT: Lazy<Mutex<HashSet<String>>> = Lazy::new(Default::default);
+
+/// Marks a table as being compacted until dropped, so that concurrent inserts
+/// don't start overlapping compactions of the same table.
+struct CompactionGuard(String);
+
+impl CompactionGuard {
+    fn try_new(table_uri: &str) -> Option<Self> {
+        if COMPACTIONS_IN_FLIGHT.lock().insert(table_uri.to_string()) {
+            Some(Self(table_uri.to_string()))
+        } else {
+            None
+        }
+    }
+}
+
+impl Drop for CompactionGuard {
+    fn drop(&mut self) {
+        COMPACTIONS_IN_FLIGHT.lock().remove(&self.0);
+    }
+}
+
 impl DisplayAs for NativeTableInsertExec {
     fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match t {
@@ -4,3 +4,6 @@
 pub mod access;
 pub mod errors;
 pub mod insert;
+pub mod partition;
+pub mod transaction;
+pub mod unique;
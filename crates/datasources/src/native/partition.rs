@@ -0,0 +1,482 @@
+//! Partitioning for native tables.
+//!
+//! Partitioned tables store each data file under a single combination of
+//! partition column values. The values are recorded as strings in the file's
+//! `add` action instead of in the file itself, which lets scans skip files
+//! using filters on the partition columns without opening them.
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, AsArray, UInt32Array};
+use datafusion::arrow::compute::take;
+use datafusion::arrow::datatypes::{DataType, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::arrow::util::display::array_value_to_string;
+use datafusion::common::ToDFSchema;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::TaskContext;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_expr::execution_props::ExecutionProps;
+use datafusion::physical_expr::{create_physical_expr, PhysicalSortExpr};
+use datafusion::physical_plan::{
+    DisplayAs,
+    DisplayFormatType,
+    ExecutionPlan,
+    Partitioning,
+    SendableRecordBatchStream,
+    Statistics,
+};
+use datafusion::scalar::ScalarValue;
+use deltalake::kernel::Add;
+
+use crate::common::unqualified;
+use crate::lake::delta::partition::parse_partition_value;
+use crate::native::errors::Result;
+
+/// Partition values of a single file, in partition column order.
+pub(crate) type PartitionValues = Vec<Option<String>>;
+
+/// Split a batch into one batch per distinct combination of values for the
+/// partition columns at `partition_indices`.
+///
+/// Partition columns are removed from the returned batches, with `schema`
+/// being the schema of the remaining columns.
+pub(crate) fn split_by_partition(
+    batch: &RecordBatch,
+    partition_indices: &[usize],
+    schema: &SchemaRef,
+) -> Result<Vec<(PartitionValues, RecordBatch)>> {
+    let mut rows: HashMap<PartitionValues, Vec<u32>> = HashMap::new();
+    let mut order = Vec::new();
+    for row in 0..batch.num_rows() {
+        let values = partition_indices
+            .iter()
+            .map(|idx| partition_value_string(batch.column(*idx).as_ref(), row))
+            .collect::<Result<PartitionValues>>()?;
+        rows.entry(values.clone())
+            .or_insert_with(|| {
+                order.push(values);
+                Vec::new()
+            })
+            .push(row as u32);
+    }
+
+    let data_indices: Vec<_> = (0..batch.num_columns())
+        .filter(|idx| !partition_indices.contains(idx))
+        .collect();
+
+    let mut batches = Vec::with_capacity(order.len());
+    for values in order {
+        let indices = UInt32Array::from(rows.remove(&values).unwrap_or_default());
+        let columns = data_indices
+            .iter()
+            .map(|idx| take(batch.column(*idx).as_ref(), &indices, None))
+            .collect::<Result<Vec<_>, _>>()?;
+        batches.push((values, RecordBatch::try_new(schema.clone(), columns)?));
+    }
+
+    Ok(batches)
+}
+
+/// Get the string representation of a partition value as stored in the
+/// table's log. Nulls are represented as `None`.
+fn partition_value_string(array: &dyn Array, row: usize) -> Result<Option<String>> {
+    if array.is_null(row) {
+        return Ok(None);
+    }
+    Ok(Some(array_value_to_string(array, row)?))
+}
+
+/// Get the directory, relative to the table root, that files for a partition
+/// are written to.
+///
+/// Follows the Hive layout used by other Delta writers, e.g. `a=1/b=x`, with
+/// nulls and empty strings written as `__HIVE_DEFAULT_PARTITION__`.
+pub(crate) fn partition_path(partition_columns: &[String], values: &PartitionValues) -> String {
+    partition_columns
+        .iter()
+        .zip(values)
+        .map(|(col, value)| match value {
+            Some(value) if !value.is_empty() => {
+                format!("{}={}", escape_path_part(col), escape_path_part(value))
+            }
+            _ => format!("{}={HIVE_DEFAULT_PARTITION}", escape_path_part(col)),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Directory name used for null partition values.
+const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// Percent-encode characters that can't appear in a partition directory
+/// name, using the same set of characters as Hive.
+fn escape_path_part(part: &str) -> String {
+    let mut escaped = String::with_capacity(part.len());
+    for c in part.chars() {
+        match c {
+            '\u{01}'..='\u{1F}'
+            | '\u{7F}'
+            | '"'
+            | '#'
+            | '%'
+            | '\''
+            | '*'
+            | '/'
+            | ':'
+            | '='
+            | '?'
+            | '\\'
+            | '{'
+            | '['
+            | ']'
+            | '^' => escaped.push_str(&format!("%{:02X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Get the partition values of a file in partition column order.
+pub(crate) fn file_partition_values(add: &Add, partition_columns: &[String]) -> PartitionValues {
+    partition_columns
+        .iter()
+        .map(|col| add.partition_values.get(col).cloned().flatten())
+        .collect()
+}
+
+/// Result of pruning a table's files using filters on its partition columns.
+#[derive(Debug, Clone)]
+pub(crate) struct PrunedFiles {
+    /// Files in partitions matching the filters.
+    pub files: Vec<Add>,
+    /// Filters used for pruning.
+    pub filters: Vec<Expr>,
+    /// Number of distinct partitions matching the filters.
+    pub partitions_matched: usize,
+    /// Number of distinct partitions in the table.
+    pub partitions_total: usize,
+}
+
+/// Prune files using the filters that only reference partition columns.
+///
+/// `partition_schema` holds the partition columns using the types they're
+/// read as. Filters that can't be evaluated against partition values are
+/// ignored, leaving the files to be filtered during the scan.
+pub(crate) fn prune_files(
+    files: &[Add],
+    partition_schema: &SchemaRef,
+    filters: &[Expr],
+) -> DataFusionResult<PrunedFiles> {
+    let partition_columns: Vec<_> = partition_schema
+        .fields()
+        .iter()
+        .map(|f| f.name().clone())
+        .collect();
+
+    // Group files by partition.
+    let mut partitions: Vec<PartitionValues> = Vec::new();
+    let mut partition_idx: HashMap<PartitionValues, usize> = HashMap::new();
+    let file_partitions: Vec<usize> = files
+        .iter()
+        .map(|add| {
+            let values = file_partition_values(add, &partition_columns);
+            *partition_idx.entry(values.clone()).or_insert_with(|| {
+                partitions.push(values);
+                partitions.len() - 1
+            })
+        })
+        .collect();
+
+    let filters: Vec<_> = filters
+        .iter()
+        .filter(|filter| {
+            filter.to_columns().is_ok_and(|cols| {
+                !cols.is_empty() && cols.iter().all(|c| partition_columns.contains(&c.name))
+            })
+        })
+        .cloned()
+        .collect();
+
+    let mut keep = vec![true; partitions.len()];
+    if !filters.is_empty() && !partitions.is_empty() {
+        let columns = partition_schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| {
+                let values = partitions
+                    .iter()
+                    .map(|values| parse_partition_value(values[idx].as_deref(), field.data_type()))
+                    .collect::<DataFusionResult<Vec<_>>>()?;
+                ScalarValue::iter_to_array(values)
+            })
+            .collect::<DataFusionResult<Vec<_>>>()?;
+        let batch = RecordBatch::try_new(partition_schema.clone(), columns)?;
+        let df_schema = partition_schema.clone().to_dfschema()?;
+        let props = ExecutionProps::new();
+
+        for filter in &filters {
+            let result = unqualified(filter.clone())
+                .and_then(|filter| create_physical_expr(&filter, &df_schema, &props))
+                .and_then(|expr| expr.evaluate(&batch))
+                .and_then(|value| value.into_array(batch.num_rows()));
+            let result = match result {
+                Ok(result) if result.data_type() == &DataType::Boolean => result,
+                _ => continue,
+            };
+            let result = result.as_boolean();
+            for (idx, keep) in keep.iter_mut().enumerate() {
+                *keep = *keep && result.is_valid(idx) && result.value(idx);
+            }
+        }
+    }
+
+    Ok(PrunedFiles {
+        files: files
+            .iter()
+            .zip(file_partitions)
+            .filter(|(_, partition)| keep[*partition])
+            .map(|(add, _)| add.clone())
+            .collect(),
+        filters,
+        partitions_matched: keep.iter().filter(|keep| **keep).count(),
+        partitions_total: partitions.len(),
+    })
+}
+
+/// Scan over a partitioned native table.
+///
+/// Wraps the plan reading the table's files, recording the partitions
+/// pruned by the scan so that they're shown when explaining a query.
+#[derive(Debug, Clone)]
+pub struct PartitionPruningExec {
+    input: Arc<dyn ExecutionPlan>,
+    partition_columns: Vec<String>,
+    filters: Vec<Expr>,
+    partitions_matched: usize,
+    partitions_total: usize,
+}
+
+impl PartitionPruningExec {
+    pub(crate) fn new(
+        input: Arc<dyn ExecutionPlan>,
+        partition_columns: Vec<String>,
+        pruned: &PrunedFiles,
+    ) -> Self {
+        PartitionPruningExec {
+            input,
+            partition_columns,
+            filters: pruned.filters.clone(),
+            partitions_matched: pruned.partitions_matched,
+            partitions_total: pruned.partitions_total,
+        }
+    }
+}
+
+impl ExecutionPlan for PartitionPruningExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        if children.len() != 1 {
+            return Err(DataFusionError::Plan(
+                "PartitionPruningExec expects exactly one child".to_string(),
+            ));
+        }
+        Ok(Arc::new(PartitionPruningExec {
+            input: children[0].clone(),
+            ..self.as_ref().clone()
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        self.input.execute(partition, context)
+    }
+
+    fn statistics(&self) -> DataFusionResult<Statistics> {
+        self.input.statistics()
+    }
+}
+
+impl DisplayAs for PartitionPruningExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "PartitionPruningExec: partition_cols=[{}], filters=[{}], partitions={}/{}",
+            self.partition_columns.join(", "),
+            self.filters
+                .iter()
+                .map(|filter| filter.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.partitions_matched,
+            self.partitions_total,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::{Int32Array, StringArray};
+    use datafusion::arrow::datatypes::{Field, Schema as ArrowSchema};
+    use datafusion::logical_expr::{col, lit};
+
+    use super::*;
+
+    fn add(path: &str, values: &[(&str, Option<&str>)]) -> Add {
+        Add {
+            path: path.to_string(),
+            partition_values: values
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.map(|v| v.to_string())))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn split_batch_by_partition() {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", DataType::Int32, true),
+            Field::new("region", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+                Arc::new(StringArray::from(vec![
+                    Some("us"),
+                    Some("eu"),
+                    Some("us"),
+                    None,
+                ])),
+            ],
+        )
+        .unwrap();
+
+        let data_schema = Arc::new(ArrowSchema::new(vec![Field::new(
+            "id",
+            DataType::Int32,
+            true,
+        )]));
+        let split = split_by_partition(&batch, &[1], &data_schema).unwrap();
+
+        let got: Vec<_> = split
+            .iter()
+            .map(|(values, batch)| {
+                let ids: Vec<_> = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .unwrap()
+                    .values()
+                    .to_vec();
+                (values.clone(), ids)
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (vec![Some("us".to_string())], vec![1, 3]),
+                (vec![Some("eu".to_string())], vec![2]),
+                (vec![None], vec![4]),
+            ],
+            got
+        );
+    }
+
+    #[test]
+    fn partition_directories() {
+        let columns = vec!["a".to_string(), "b".to_string()];
+        assert_eq!(
+            "a=1/b=x",
+            partition_path(
+                &columns,
+                &vec![Some("1".to_string()), Some("x".to_string())]
+            )
+        );
+        assert_eq!(
+            "a=__HIVE_DEFAULT_PARTITION__/b=2024-01-01 12%3A00%3A00",
+            partition_path(
+                &columns,
+                &vec![None, Some("2024-01-01 12:00:00".to_string())]
+            )
+        );
+        assert_eq!(
+            "a=x%2Fy%3Dz%25/b=__HIVE_DEFAULT_PARTITION__",
+            partition_path(
+                &columns,
+                &vec![Some("x/y=z%".to_string()), Some(String::new())]
+            )
+        );
+    }
+
+    #[test]
+    fn prune_files_by_partition() {
+        let files = vec![
+            add("a", &[("year", Some("2023")), ("region", Some("us"))]),
+            add("b", &[("year", Some("2024")), ("region", Some("us"))]),
+            add("c", &[("year", Some("2024")), ("region", Some("eu"))]),
+            add("d", &[("year", Some("2024")), ("region", Some("eu"))]),
+            add("e", &[("year", None), ("region", Some("eu"))]),
+        ];
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("year", DataType::Int32, true),
+            Field::new("region", DataType::Utf8, true),
+        ]));
+
+        let pruned = prune_files(
+            &files,
+            &schema,
+            &[
+                col("year").gt_eq(lit(2024)),
+                // Doesn't reference partition columns, not used for pruning.
+                col("id").eq(lit(1)),
+            ],
+        )
+        .unwrap();
+        let paths: Vec<_> = pruned.files.iter().map(|add| add.path.as_str()).collect();
+        assert_eq!(vec!["b", "c", "d"], paths);
+        assert_eq!(1, pruned.filters.len());
+        assert_eq!((2, 4), (pruned.partitions_matched, pruned.partitions_total));
+
+        let pruned = prune_files(
+            &files,
+            &schema,
+            &[col("year").eq(lit(2024)), col("region").eq(lit("eu"))],
+        )
+        .unwrap();
+        let paths: Vec<_> = pruned.files.iter().map(|add| add.path.as_str()).collect();
+        assert_eq!(vec!["c", "d"], paths);
+
+        let pruned = prune_files(&files, &schema, &[]).unwrap();
+        assert_eq!(5, pruned.files.len());
+        assert_eq!((4, 4), (pruned.partitions_matched, pruned.partitions_total));
+    }
+}
@@ -0,0 +1,984 @@
+//! Session-local transactions over native tables.
+//!
+//! Writes made inside of a transaction block are staged as Delta `add` and
+//! `remove` actions instead of being committed to the table log right away.
+//! Data files are written to the table's location eagerly, but since they're
+//! not referenced by the log they're invisible to everyone else until the
+//! transaction commits. On commit, all staged actions for a table are written
+//! as a single Delta commit. On rollback, the staged actions are dropped and
+//! any files written by the transaction are removed.
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use chrono::{DateTime, NaiveDate, Utc};
+use datafusion::arrow::array::{Array, ArrayRef, AsArray};
+use datafusion::arrow::compute::{cast, filter_record_batch, not};
+use datafusion::arrow::datatypes::{DataType, Schema as ArrowSchema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::logical_expr::Accumulator;
+use datafusion::parquet::arrow::ArrowWriter;
+use datafusion::parquet::basic::Compression;
+use datafusion::parquet::file::properties::WriterProperties;
+use datafusion::physical_plan::expressions::{MaxAccumulator, MinAccumulator};
+use datafusion::scalar::ScalarValue;
+use deltalake::delta_datafusion::DataFusionMixins;
+use deltalake::kernel::{Action, Add, Metadata, MetadataValue, Remove, StructField, StructType};
+use deltalake::operations::transaction::commit;
+use deltalake::protocol::{DeltaOperation, SaveMode};
+use deltalake::DeltaTable;
+use futures::{Stream, StreamExt};
+use object_store::path::Path as ObjectStorePath;
+use protogen::metastore::types::options::{InternalColumnDefinition, UniqueConstraint};
+use serde_json::json;
+use tokio::sync::{Mutex, MutexGuard};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::native::access::arrow_to_delta_safe;
+use crate::native::errors::{NativeError, Result};
+use crate::native::partition::{partition_path, split_by_partition, PartitionValues};
+use crate::native::unique;
+
+/// Approximate size of in-memory data to buffer before flushing a new data
+/// file for a staged write.
+const TARGET_FILE_SIZE_BYTES: usize = 128 * 1024 * 1024;
+
+/// Name of the column holding the per-row predicate result when rewriting
+/// files for deletes and updates.
+pub(crate) const MATCHED_COLUMN: &str = "__glaredb_txn_matched";
+
+/// Days from the common era to the unix epoch.
+const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+/// Handle to the transaction (if any) currently open for a session.
+///
+/// Clones of the handle all refer to the same transaction.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionHandle {
+    inner: Arc<Mutex<Option<NativeTransaction>>>,
+    /// Storage locations of tables created in the open transaction, keyed by
+    /// table oid.
+    ///
+    /// Oids aren't reserved until the transaction's catalog changes are
+    /// committed, so another session may create a table with the same oid in
+    /// the meantime. Tables created in a transaction are stored in a location
+    /// private to the transaction until it commits to avoid clobbering each
+    /// other's storage.
+    ///
+    /// Kept outside of the transaction since it's needed when resolving table
+    /// locations, which happens while the transaction is locked.
+    created: Arc<parking_lot::Mutex<HashMap<u32, String>>>,
+}
+
+impl TransactionHandle {
+    /// Get the staging location of a table created in the open transaction.
+    pub(crate) fn created_table_location(&self, table_id: u32) -> Option<String> {
+        self.created.lock().get(&table_id).cloned()
+    }
+
+    /// Record the staging location of a table created in the open
+    /// transaction.
+    pub(crate) fn add_created_table(&self, table_id: u32, location: String) {
+        self.created.lock().insert(table_id, location);
+    }
+
+    /// Take the staging locations of all tables created in the transaction.
+    pub(crate) fn take_created_tables(&self) -> HashMap<u32, String> {
+        std::mem::take(&mut *self.created.lock())
+    }
+
+    /// Lock the transaction.
+    pub(crate) async fn lock(&self) -> MutexGuard<'_, Option<NativeTransaction>> {
+        self.inner.lock().await
+    }
+
+    /// Returns if there's a transaction currently open.
+    pub async fn is_open(&self) -> bool {
+        self.inner.lock().await.is_some()
+    }
+
+    /// Open a new transaction.
+    ///
+    /// Errors if a transaction is already open.
+    pub async fn begin(&self) -> Result<()> {
+        let mut txn = self.inner.lock().await;
+        if txn.is_some() {
+            return Err(NativeError::TransactionInProgress);
+        }
+        *txn = Some(NativeTransaction::default());
+        Ok(())
+    }
+
+    /// Commit the open transaction.
+    ///
+    /// Errors if no transaction is open. The transaction is closed regardless
+    /// of the commit succeeding or not.
+    pub async fn commit(&self) -> Result<()> {
+        let txn = self
+            .inner
+            .lock()
+            .await
+            .take()
+            .ok_or(NativeError::NoTransaction)?;
+        txn.commit().await
+    }
+
+    /// Rollback the open transaction if there is one.
+    pub async fn rollback(&self) -> Result<()> {
+        let txn = self.inner.lock().await.take();
+        match txn {
+            Some(txn) => txn.rollback().await,
+            None => Ok(()),
+        }
+    }
+}
+
+/// Writes staged by a single transaction.
+#[derive(Debug, Default)]
+pub struct NativeTransaction {
+    /// Staged writes keyed by table oid.
+    tables: HashMap<u32, StagedTable>,
+}
+
+impl NativeTransaction {
+    /// Get the staged writes for a table, if the table has been written to in
+    /// this transaction.
+    pub(crate) fn get_table(&self, table_id: u32) -> Option<&StagedTable> {
+        self.tables.get(&table_id)
+    }
+
+    /// Mutable variant of `get_table`.
+    pub(crate) fn get_table_mut(&mut self, table_id: u32) -> Option<&mut StagedTable> {
+        self.tables.get_mut(&table_id)
+    }
+
+    /// Get the staged writes for a table, starting to track the table if this
+    /// is the first write to it.
+    ///
+    /// `table` should be the most recently loaded version of the table, and
+    /// will be used as the base for conflict detection on commit. Rows written
+    /// to the table are checked against `unique_constraints` again on commit.
+    pub(crate) fn get_or_stage_table(
+        &mut self,
+        table_id: u32,
+        table: &DeltaTable,
+        unique_constraints: &[UniqueConstraint],
+    ) -> Result<&mut StagedTable> {
+        if !self.tables.contains_key(&table_id) {
+            let staged = StagedTable::try_new(table.clone())?
+                .with_unique_constraints(unique_constraints.to_vec());
+            self.tables.insert(table_id, staged);
+        }
+        Ok(self.tables.get_mut(&table_id).unwrap())
+    }
+
+    async fn commit(self) -> Result<()> {
+        // Delta doesn't support commits spanning multiple tables, so each table
+        // is committed individually. To avoid a conflict on one table leaving
+        // the transaction partially committed, all tables are checked for
+        // conflicts before any of them are committed.
+        //
+        // This narrows, but doesn't close, the window for a partial commit. A
+        // table commit can still fail after the check (e.g. another session
+        // commits a conflicting change in between, or writing to storage
+        // fails). Tables committed before that point stay committed.
+        //
+        // Write locks of tables with unique constraints are held until all
+        // tables are committed, so that keys checked while preparing can't be
+        // committed by another session in the meantime. Locks are taken in
+        // order to avoid deadlocking with other transactions.
+        let mut locked: Vec<_> = self
+            .tables
+            .values()
+            .filter(|staged| !staged.unique_constraints.is_empty() && staged.has_changes())
+            .map(|staged| staged.table.table_uri())
+            .collect();
+        locked.sort();
+        locked.dedup();
+        let mut guards = Vec::with_capacity(locked.len());
+        for uri in &locked {
+            guards.push(unique::lock_table(uri).await);
+        }
+
+        for (table_id, staged) in &self.tables {
+            if let Err(e) = staged.prepare().await {
+                warn!(%e, %table_id, "staged writes conflict with the latest table version");
+                for staged in self.tables.values() {
+                    staged.cleanup_all().await;
+                }
+                return Err(e);
+            }
+        }
+
+        let mut committed = 0;
+        let mut tables = self.tables.into_iter();
+        while let Some((table_id, staged)) = tables.next() {
+            if let Err(e) = staged.commit().await {
+                warn!(%e, %table_id, %committed, "failed to commit staged writes");
+                for (_, remaining) in tables {
+                    remaining.cleanup_all().await;
+                }
+                if committed > 0 {
+                    return Err(NativeError::PartialCommit(Box::new(e)));
+                }
+                return Err(e);
+            }
+            committed += 1;
+        }
+        Ok(())
+    }
+
+    async fn rollback(self) -> Result<()> {
+        for (_, staged) in self.tables {
+            staged.cleanup_all().await;
+        }
+        Ok(())
+    }
+}
+
+/// Staged writes for a single table.
+#[derive(Debug)]
+pub(crate) struct StagedTable {
+    /// The table as of the first write in the transaction.
+    table: DeltaTable,
+    /// Physical schema of the table, including partition columns.
+    physical_schema: SchemaRef,
+    /// Columns the table is partitioned by.
+    partition_columns: Vec<String>,
+    /// Schema of data files, which excludes partition columns.
+    file_schema: SchemaRef,
+    /// Files visible to the transaction. This includes previously committed
+    /// files that haven't been removed, and files written by the transaction.
+    files: Vec<Add>,
+    /// Paths of all files written by this transaction, including those that
+    /// were later rewritten.
+    written: HashSet<String>,
+    /// Previously committed files removed by this transaction.
+    removed: Vec<Remove>,
+    /// New table metadata to commit if the table's schema was changed.
+    metadata: Option<Metadata>,
+    /// PRIMARY KEY and UNIQUE constraints of the table, checked against rows
+    /// committed by others before committing.
+    unique_constraints: Vec<UniqueConstraint>,
+}
+
+impl StagedTable {
+    pub(crate) fn try_new(table: DeltaTable) -> Result<Self> {
+        let snapshot = table.snapshot()?;
+        let physical_schema = Arc::new(ArrowSchema::try_from(snapshot.schema())?);
+        let partition_columns = snapshot.metadata().partition_columns.clone();
+        let file_schema = file_schema(&physical_schema, &partition_columns);
+        let files = snapshot.file_actions()?;
+
+        Ok(StagedTable {
+            table,
+            physical_schema,
+            partition_columns,
+            file_schema,
+            files,
+            written: HashSet::new(),
+            removed: Vec::new(),
+            metadata: None,
+            unique_constraints: Vec::new(),
+        })
+    }
+
+    pub(crate) fn with_unique_constraints(mut self, constraints: Vec<UniqueConstraint>) -> Self {
+        self.unique_constraints = constraints;
+        self
+    }
+
+    /// Change the schema of the table.
+    ///
+    /// As with creating a table, all columns are nullable in the delta schema.
+    ///
+    /// Files written after this use the new schema. Existing files aren't
+    /// modified, callers should rewrite them if they can't be read with the
+    /// new schema.
+    pub(crate) fn set_schema(&mut self, columns: &[InternalColumnDefinition]) -> Result<()> {
+        let mut fields = Vec::with_capacity(columns.len());
+        for col in columns {
+            let delta_col = arrow_to_delta_safe(&col.arrow_type)?;
+            let mut field = StructField::new(col.name.clone(), delta_col.data_type, true);
+            if let Some(metadata) = delta_col.metadata {
+                field = field.with_metadata(
+                    metadata
+                        .into_iter()
+                        .map(|(k, v)| (k, MetadataValue::String(v.to_string()))),
+                );
+            }
+            fields.push(field);
+        }
+        let schema = StructType::new(fields);
+
+        let mut metadata = self.table.snapshot()?.metadata().clone();
+        metadata.schema_string = serde_json::to_string(&schema)?;
+
+        self.physical_schema = Arc::new(ArrowSchema::try_from(&schema)?);
+        self.file_schema = file_schema(&self.physical_schema, &self.partition_columns);
+        self.metadata = Some(metadata);
+        Ok(())
+    }
+
+    /// The schema of the table if it was changed by the transaction.
+    pub(crate) fn schema(&self) -> Result<Option<StructType>> {
+        Ok(self
+            .metadata
+            .as_ref()
+            .map(|metadata| serde_json::from_str(&metadata.schema_string))
+            .transpose()?)
+    }
+
+    /// The base table for this transaction.
+    pub(crate) fn table(&self) -> &DeltaTable {
+        &self.table
+    }
+
+    /// Files currently visible to the transaction.
+    pub(crate) fn files(&self) -> &[Add] {
+        &self.files
+    }
+
+    /// Add newly written files to the transaction.
+    pub(crate) fn add_files(&mut self, adds: Vec<Add>) {
+        for add in adds {
+            self.written.insert(add.path.clone());
+            self.files.push(add);
+        }
+    }
+
+    /// Replace all currently visible files with a new set of files.
+    ///
+    /// Files previously committed to the table will be removed on commit,
+    /// files written during this transaction are simply forgotten about (and
+    /// cleaned up on commit or rollback).
+    pub(crate) fn replace_files(&mut self, adds: Vec<Add>) {
+        let deletion_timestamp = chrono::Utc::now().timestamp_millis();
+        for old in std::mem::take(&mut self.files) {
+            if !self.written.contains(&old.path) {
+                self.removed.push(remove_for_add(&old, deletion_timestamp));
+            }
+        }
+        self.add_files(adds);
+    }
+
+    /// Delete newly written files that won't be part of the transaction.
+    pub(crate) async fn discard_files(&self, adds: Vec<Add>) {
+        let store = self.table.log_store().object_store();
+        for add in adds {
+            if let Err(e) = store
+                .delete(&ObjectStorePath::from(add.path.as_str()))
+                .await
+            {
+                warn!(%e, path = %add.path, "failed to clean up discarded file");
+            }
+        }
+    }
+
+    /// Write a stream of batches as new data files.
+    ///
+    /// Batches are expected to be in table column order, and will be cast to
+    /// the physical types of the table. If `MATCHED_COLUMN` is present as the
+    /// last column, it will be dropped prior to writing. Rows where the matched
+    /// column is true will be skipped if `skip_matched` is set.
+    ///
+    /// For partitioned tables, rows are written to separate files for each
+    /// partition.
+    ///
+    /// Returns the written files, the number of rows written, and the number
+    /// of rows that were matched.
+    pub(crate) async fn write_stream<S>(
+        &self,
+        stream: S,
+        skip_matched: bool,
+    ) -> Result<(Vec<Add>, u64, u64)>
+    where
+        S: Stream<Item = datafusion::error::Result<RecordBatch>> + Unpin,
+    {
+        let mut adds = Vec::new();
+        match self
+            .write_stream_inner(stream, skip_matched, &mut adds)
+            .await
+        {
+            Ok((num_written, num_matched)) => Ok((adds, num_written, num_matched)),
+            Err(e) => {
+                // Don't leave behind files for a failed write.
+                self.discard_files(adds).await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn write_stream_inner<S>(
+        &self,
+        mut stream: S,
+        skip_matched: bool,
+        adds: &mut Vec<Add>,
+    ) -> Result<(u64, u64)>
+    where
+        S: Stream<Item = datafusion::error::Result<RecordBatch>> + Unpin,
+    {
+        let partition_indices = self
+            .partition_columns
+            .iter()
+            .map(|col| self.physical_schema.index_of(col))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Batches buffered for each partition, along with their size.
+        let mut buffered: HashMap<PartitionValues, (Vec<RecordBatch>, usize)> = HashMap::new();
+        let mut num_written = 0;
+        let mut num_matched = 0;
+
+        while let Some(batch) = stream.next().await {
+            let mut batch = batch?;
+
+            let num_cols = batch.num_columns();
+            if num_cols > 0 && batch.schema().field(num_cols - 1).name() == MATCHED_COLUMN {
+                let matched = batch.column(num_cols - 1).as_boolean().clone();
+                num_matched += matched.true_count() as u64;
+                if skip_matched {
+                    batch = filter_record_batch(&batch, &not(&matched)?)?;
+                }
+                batch = batch.project(&(0..num_cols - 1).collect::<Vec<_>>())?;
+            }
+
+            if batch.num_rows() == 0 {
+                continue;
+            }
+
+            let batch = self.cast_to_physical(batch)?;
+            num_written += batch.num_rows() as u64;
+
+            let batches = if partition_indices.is_empty() {
+                vec![(Vec::new(), batch)]
+            } else {
+                split_by_partition(&batch, &partition_indices, &self.file_schema)?
+            };
+
+            for (values, batch) in batches {
+                let (batches, size) = buffered.entry(values.clone()).or_default();
+                *size += batch.get_array_memory_size();
+                batches.push(batch);
+
+                if *size >= TARGET_FILE_SIZE_BYTES {
+                    let batches = std::mem::take(batches);
+                    *size = 0;
+                    adds.push(self.write_file(&values, batches).await?);
+                }
+            }
+        }
+
+        for (values, (batches, _)) in buffered {
+            if !batches.is_empty() {
+                adds.push(self.write_file(&values, batches).await?);
+            }
+        }
+
+        Ok((num_written, num_matched))
+    }
+
+    /// Cast a batch to the physical schema for the table.
+    fn cast_to_physical(&self, batch: RecordBatch) -> Result<RecordBatch> {
+        if batch.num_columns() != self.physical_schema.fields().len() {
+            return Err(NativeError::Static(
+                "number of columns being written does not match the table",
+            ));
+        }
+
+        let columns = batch
+            .columns()
+            .iter()
+            .zip(self.physical_schema.fields())
+            .map(|(col, field)| {
+                if col.data_type() == field.data_type() {
+                    Ok(col.clone())
+                } else {
+                    cast(col.as_ref(), field.data_type())
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(RecordBatch::try_new(self.physical_schema.clone(), columns)?)
+    }
+
+    /// Write batches to a single parquet file, returning the add action for
+    /// the file.
+    ///
+    /// Batches for partitioned tables must not include the partition columns,
+    /// `partition_values` holds their values for all rows in the file. Files
+    /// for partitioned tables are written to the partition's directory.
+    async fn write_file(
+        &self,
+        partition_values: &PartitionValues,
+        batches: Vec<RecordBatch>,
+    ) -> Result<Add> {
+        let props = WriterProperties::builder()
+            .set_created_by("GlareDB".to_string())
+            .set_compression(Compression::SNAPPY)
+            .build();
+
+        let mut writer = ArrowWriter::try_new(Vec::new(), self.file_schema.clone(), Some(props))?;
+        for batch in &batches {
+            writer.write(batch)?;
+        }
+        let buf = writer.into_inner()?;
+
+        let file_name = format!("part-00000-{}-c000.snappy.parquet", Uuid::new_v4());
+        let path = if self.partition_columns.is_empty() {
+            file_name
+        } else {
+            format!(
+                "{}/{file_name}",
+                partition_path(&self.partition_columns, partition_values)
+            )
+        };
+        let size = buf.len() as i64;
+        self.table
+            .log_store()
+            .object_store()
+            .put(&ObjectStorePath::from(path.as_str()), Bytes::from(buf))
+            .await?;
+
+        let stats = file_stats(&self.file_schema, &batches);
+
+        Ok(Add {
+            path,
+            size,
+            partition_values: self
+                .partition_columns
+                .iter()
+                .cloned()
+                .zip(partition_values.iter().cloned())
+                .collect(),
+            modification_time: chrono::Utc::now().timestamp_millis(),
+            data_change: true,
+            stats: Some(stats.to_string()),
+            ..Default::default()
+        })
+    }
+
+    /// Returns if anything would be committed for this table.
+    fn has_changes(&self) -> bool {
+        self.metadata.is_some()
+            || !self.removed.is_empty()
+            || self
+                .files
+                .iter()
+                .any(|add| self.written.contains(&add.path))
+    }
+
+    /// Check that the staged actions can still be committed on top of the
+    /// latest version of the table.
+    ///
+    /// Appends can be committed on top of anything other than a schema change,
+    /// as long as the appended rows don't share a key with rows committed
+    /// since the transaction first read the table. Staged removes or schema
+    /// changes require that nothing else was committed to the table since.
+    pub(crate) async fn prepare(&self) -> Result<()> {
+        if !self.has_changes() {
+            return Ok(());
+        }
+
+        let mut latest = self.table.clone();
+        latest.update().await?;
+        if latest.version() == self.table.version() {
+            return Ok(());
+        }
+
+        let base = self.table.snapshot()?.metadata();
+        let current = latest.snapshot()?.metadata();
+        let schema_changed = base.schema_string != current.schema_string
+            || base.partition_columns != current.partition_columns;
+        let append_only = self.metadata.is_none() && self.removed.is_empty();
+        if append_only && !schema_changed {
+            return self.check_concurrent_keys(&latest).await;
+        }
+
+        Err(NativeError::SerializationFailure(self.table.table_uri()))
+    }
+
+    /// Check that rows written by the transaction don't share a key with rows
+    /// committed to `latest` since the transaction first read the table.
+    ///
+    /// Rows written by the transaction were already checked against the rows
+    /// it read, so only the files committed since need to be checked.
+    async fn check_concurrent_keys(&self, latest: &DeltaTable) -> Result<()> {
+        if self.unique_constraints.is_empty() {
+            return Ok(());
+        }
+
+        let base: HashSet<_> = self
+            .table
+            .snapshot()?
+            .file_actions()?
+            .into_iter()
+            .map(|add| add.path)
+            .collect();
+        let concurrent: Vec<_> = latest
+            .snapshot()?
+            .file_actions()?
+            .into_iter()
+            .filter(|add| !base.contains(&add.path))
+            .collect();
+        let written: Vec<_> = self
+            .files
+            .iter()
+            .filter(|add| self.written.contains(&add.path))
+            .cloned()
+            .collect();
+
+        unique::check_concurrent_keys(latest, &self.unique_constraints, &written, &concurrent).await
+    }
+
+    /// Commit the staged actions for this table.
+    pub(crate) async fn commit(self) -> Result<()> {
+        let visible: HashSet<_> = self.files.iter().map(|add| add.path.clone()).collect();
+
+        let mut actions: Vec<Action> = self
+            .metadata
+            .iter()
+            .cloned()
+            .map(Action::Metadata)
+            .collect();
+        actions.extend(self.removed.iter().cloned().map(Action::Remove));
+        actions.extend(
+            self.files
+                .iter()
+                .filter(|add| self.written.contains(&add.path))
+                .cloned()
+                .map(Action::Add),
+        );
+
+        if !actions.is_empty() {
+            let partition_by = if self.partition_columns.is_empty() {
+                None
+            } else {
+                Some(self.partition_columns.clone())
+            };
+            let rewritten =
+                !self.removed.is_empty() || self.written.iter().any(|path| visible.contains(path));
+            let operation = if self.metadata.is_some() && rewritten {
+                // Schema changes that rewrite the table replace all of its
+                // data.
+                DeltaOperation::Write {
+                    mode: SaveMode::Overwrite,
+                    partition_by,
+                    predicate: None,
+                }
+            } else if self.metadata.is_some() {
+                // Schema changes that don't touch any data. Delta doesn't have
+                // a dedicated operation for column changes, so record this as
+                // an update that doesn't add or remove any files.
+                DeltaOperation::Update { predicate: None }
+            } else if self.removed.is_empty() {
+                DeltaOperation::Write {
+                    mode: SaveMode::Append,
+                    partition_by,
+                    predicate: None,
+                }
+            } else {
+                DeltaOperation::Update { predicate: None }
+            };
+
+            let snapshot = self.table.snapshot()?;
+            let result = commit(
+                self.table.log_store().as_ref(),
+                &actions,
+                operation,
+                Some(snapshot),
+                None,
+            )
+            .await;
+
+            if let Err(e) = result {
+                self.cleanup_all().await;
+                return Err(e.into());
+            }
+            debug!(uri = %self.table.table_uri(), num_actions = %actions.len(), "committed staged writes");
+        }
+
+        // Files written and then rewritten within the transaction were never
+        // committed, remove them.
+        self.cleanup(|path| !visible.contains(path)).await;
+
+        Ok(())
+    }
+
+    /// Delete all files written by this transaction.
+    pub(crate) async fn cleanup_all(&self) {
+        self.cleanup(|_| true).await
+    }
+
+    /// Delete files written by this transaction that match the filter.
+    async fn cleanup(&self, filter: impl Fn(&String) -> bool) {
+        let store = self.table.log_store().object_store();
+        for path in self.written.iter().filter(|path| filter(path)) {
+            if let Err(e) = store.delete(&ObjectStorePath::from(path.as_str())).await {
+                // Not fatal, the file isn't referenced by the table.
+                warn!(%e, %path, "failed to clean up staged file");
+            }
+        }
+    }
+}
+
+/// Get the schema of data files for a table, excluding the partition columns.
+fn file_schema(physical_schema: &ArrowSchema, partition_columns: &[String]) -> SchemaRef {
+    let fields: Vec<_> = physical_schema
+        .fields()
+        .iter()
+        .filter(|f| !partition_columns.contains(f.name()))
+        .cloned()
+        .collect();
+    Arc::new(ArrowSchema::new(fields))
+}
+
+/// Sum the number of records for files using their statistics.
+///
+/// Returns `None` if any file is missing statistics.
+pub(crate) fn num_records(files: &[Add]) -> Option<usize> {
+    let mut total = 0;
+    for add in files {
+        let stats: serde_json::Value = serde_json::from_str(add.stats.as_ref()?).ok()?;
+        total += stats.get("numRecords")?.as_u64()? as usize;
+    }
+    Some(total)
+}
+
+fn remove_for_add(add: &Add, deletion_timestamp: i64) -> Remove {
+    Remove {
+        path: add.path.clone(),
+        data_change: true,
+        deletion_timestamp: Some(deletion_timestamp),
+        extended_file_metadata: Some(true),
+        partition_values: Some(add.partition_values.clone()),
+        size: Some(add.size),
+        tags: add.tags.clone(),
+        ..Default::default()
+    }
+}
+
+/// Compute the stats recorded in the add action for a data file.
+///
+/// Null counts are recorded for all non-nested columns. Min and max values are
+/// only recorded for columns where they can be computed and represented in the
+/// log.
+fn file_stats(schema: &ArrowSchema, batches: &[RecordBatch]) -> serde_json::Value {
+    let mut num_records = 0;
+    let mut columns: Vec<_> = schema
+        .fields()
+        .iter()
+        .map(|field| ColumnStats::new(field.data_type()))
+        .collect();
+    for batch in batches {
+        num_records += batch.num_rows();
+        for (stats, array) in columns.iter_mut().zip(batch.columns()) {
+            if let Some(stats) = stats {
+                stats.update(array);
+            }
+        }
+    }
+
+    let mut min_values = serde_json::Map::new();
+    let mut max_values = serde_json::Map::new();
+    let mut null_count = serde_json::Map::new();
+    for (field, stats) in schema.fields().iter().zip(columns) {
+        let Some(mut stats) = stats else {
+            continue;
+        };
+        null_count.insert(field.name().clone(), json!(stats.nulls));
+        let (min, max) = stats.bounds();
+        if let Some(min) = min {
+            min_values.insert(field.name().clone(), min);
+        }
+        if let Some(max) = max {
+            max_values.insert(field.name().clone(), max);
+        }
+    }
+
+    json!({
+        "numRecords": num_records,
+        "minValues": min_values,
+        "maxValues": max_values,
+        "nullCount": null_count,
+    })
+}
+
+/// Statistics for a single column in a data file.
+struct ColumnStats {
+    nulls: usize,
+    min: Option<MinAccumulator>,
+    max: Option<MaxAccumulator>,
+}
+
+impl ColumnStats {
+    /// Create stats for a column of the given type, returns `None` for nested
+    /// types which aren't tracked.
+    fn new(data_type: &DataType) -> Option<ColumnStats> {
+        let (min, max) = match data_type {
+            DataType::Struct(_)
+            | DataType::List(_)
+            | DataType::LargeList(_)
+            | DataType::FixedSizeList(_, _)
+            | DataType::Map(_, _) => return None,
+            // Bounds for floating point columns must not include NaNs, which
+            // the accumulators don't account for. Skip writing bounds for
+            // those.
+            DataType::Float16 | DataType::Float32 | DataType::Float64 => (None, None),
+            _ => (
+                MinAccumulator::try_new(data_type).ok(),
+                MaxAccumulator::try_new(data_type).ok(),
+            ),
+        };
+
+        Some(ColumnStats { nulls: 0, min, max })
+    }
+
+    fn update(&mut self, array: &ArrayRef) {
+        self.nulls += array.null_count();
+
+        // Bounds are optional, stop tracking them if they can't be computed
+        // for this type.
+        let values = [array.clone()];
+        if let Some(acc) = self.min.as_mut() {
+            if acc.update_batch(&values).is_err() {
+                self.min = None;
+            }
+        }
+        if let Some(acc) = self.max.as_mut() {
+            if acc.update_batch(&values).is_err() {
+                self.max = None;
+            }
+        }
+    }
+
+    /// Get the min and max values for the column as stored in the log.
+    fn bounds(&mut self) -> (Option<serde_json::Value>, Option<serde_json::Value>) {
+        let min = self
+            .min
+            .as_mut()
+            .and_then(|acc| acc.evaluate().ok())
+            .and_then(|v| stats_value(&v));
+        let max = self
+            .max
+            .as_mut()
+            .and_then(|acc| acc.evaluate().ok())
+            .and_then(|v| stats_value(&v));
+        (min, max)
+    }
+}
+
+/// Convert a min or max value to its representation in the log.
+///
+/// Returns `None` for nulls and values without a representation.
+fn stats_value(value: &ScalarValue) -> Option<serde_json::Value> {
+    let timestamp = |dt: Option<DateTime<Utc>>| {
+        dt.map(|dt| json!(dt.format("%Y-%m-%dT%H:%M:%S%.6fZ").to_string()))
+    };
+
+    match value {
+        ScalarValue::Int8(Some(v)) => Some(json!(v)),
+        ScalarValue::Int16(Some(v)) => Some(json!(v)),
+        ScalarValue::Int32(Some(v)) => Some(json!(v)),
+        ScalarValue::Int64(Some(v)) => Some(json!(v)),
+        ScalarValue::UInt8(Some(v)) => Some(json!(v)),
+        ScalarValue::UInt16(Some(v)) => Some(json!(v)),
+        ScalarValue::UInt32(Some(v)) => Some(json!(v)),
+        ScalarValue::UInt64(Some(v)) => Some(json!(v)),
+        ScalarValue::Utf8(Some(v)) | ScalarValue::LargeUtf8(Some(v)) => Some(json!(v)),
+        ScalarValue::Date32(Some(v)) => {
+            NaiveDate::from_num_days_from_ce_opt(v + UNIX_EPOCH_DAYS_FROM_CE)
+                .map(|date| json!(date.format("%Y-%m-%d").to_string()))
+        }
+        ScalarValue::TimestampSecond(Some(v), _) => timestamp(DateTime::from_timestamp(*v, 0)),
+        ScalarValue::TimestampMillisecond(Some(v), _) => {
+            timestamp(DateTime::from_timestamp_millis(*v))
+        }
+        ScalarValue::TimestampMicrosecond(Some(v), _) => {
+            timestamp(DateTime::from_timestamp_micros(*v))
+        }
+        ScalarValue::TimestampNanosecond(Some(v), _) => {
+            timestamp(Some(DateTime::from_timestamp_nanos(*v)))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::{
+        Float64Array,
+        Int64Array,
+        StringArray,
+        TimestampMicrosecondArray,
+    };
+    use datafusion::arrow::datatypes::{Field, TimeUnit};
+
+    use super::*;
+
+    #[test]
+    fn num_records_from_stats() {
+        let add = |stats: Option<&str>| Add {
+            path: "a.parquet".to_string(),
+            stats: stats.map(|s| s.to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(Some(0), num_records(&[]));
+        assert_eq!(
+            Some(7),
+            num_records(&[
+                add(Some(r#"{"numRecords": 3}"#)),
+                add(Some(r#"{"numRecords": 4, "nullCount": {}}"#)),
+            ])
+        );
+        assert_eq!(
+            None,
+            num_records(&[add(Some(r#"{"numRecords": 3}"#)), add(None)])
+        );
+    }
+
+    #[test]
+    fn stats_for_file() {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Utf8, true),
+            Field::new("c", DataType::Float64, true),
+            Field::new("d", DataType::Timestamp(TimeUnit::Microsecond, None), true),
+        ]));
+        let batch =
+            |a: Vec<Option<i64>>, b: Vec<Option<&str>>, c: Vec<Option<f64>>, d: Vec<i64>| {
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![
+                        Arc::new(Int64Array::from(a)),
+                        Arc::new(StringArray::from(b)),
+                        Arc::new(Float64Array::from(c)),
+                        Arc::new(TimestampMicrosecondArray::from(d)),
+                    ],
+                )
+                .unwrap()
+            };
+
+        let stats = file_stats(
+            &schema,
+            &[
+                batch(
+                    vec![Some(3), None],
+                    vec![Some("m"), Some("b")],
+                    vec![Some(1.5), None],
+                    vec![1_000_000, 2_500_000],
+                ),
+                batch(vec![Some(-2)], vec![None], vec![None], vec![0]),
+            ],
+        );
+
+        let expected = json!({
+            "numRecords": 3,
+            "minValues": {"a": -2, "b": "b", "d": "1970-01-01T00:00:00.000000Z"},
+            "maxValues": {"a": 3, "b": "m", "d": "1970-01-01T00:00:02.500000Z"},
+            "nullCount": {"a": 1, "b": 1, "c": 2, "d": 0},
+        });
+        assert_eq!(expected, stats);
+    }
+}
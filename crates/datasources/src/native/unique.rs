@@ -0,0 +1,138 @@
+//! Enforcing PRIMARY KEY and UNIQUE constraints of native tables.
+//!
+//! Writes check their keys against the rows already in the table. To keep two
+//! writes from both passing the check and then both committing the same key,
+//! writes to tables with unique constraints hold the table's write lock from
+//! checking their keys until they've committed.
+//!
+//! Writes staged in a transaction are checked when the statement runs, and
+//! checked again when the transaction commits (with the lock held) against
+//! rows committed to the table by others in the meantime.
+//!
+//! Locks are local to the process, so keys are only guaranteed to be unique
+//! for writes made through the same node.
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use datafusion::arrow::array::Array;
+use datafusion::arrow::row::{OwnedRow, RowConverter, SortField};
+use datafusion::execution::context::{SessionContext, SessionState};
+use datafusion::physical_plan::execute_stream;
+use deltalake::delta_datafusion::DataFusionMixins;
+use deltalake::kernel::Add;
+use deltalake::DeltaTable;
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use protogen::metastore::types::options::UniqueConstraint;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+use crate::native::access::NativeTable;
+use crate::native::errors::{NativeError, Result};
+
+/// Write locks for tables with unique constraints, keyed by table uri.
+static WRITE_LOCKS: Lazy<parking_lot::Mutex<HashMap<String, Arc<Mutex<()>>>>> =
+    Lazy::new(Default::default);
+
+/// Holds the write lock of a table with unique constraints until dropped.
+#[derive(Debug)]
+pub struct UniqueWriteGuard {
+    _guard: OwnedMutexGuard<()>,
+}
+
+/// Wait for the write lock of the table at `table_uri`.
+pub(crate) async fn lock_table(table_uri: &str) -> UniqueWriteGuard {
+    let lock = WRITE_LOCKS
+        .lock()
+        .entry(table_uri.to_string())
+        .or_default()
+        .clone();
+    UniqueWriteGuard {
+        _guard: lock.lock_owned().await,
+    }
+}
+
+/// Check that no row of the `written` files shares a key with a row of the
+/// `concurrent` files for any of the constraints.
+///
+/// Both sets of files must belong to `table`. Following postgres, rows with a
+/// null in any of a key's columns never conflict.
+pub(crate) async fn check_concurrent_keys(
+    table: &DeltaTable,
+    constraints: &[UniqueConstraint],
+    written: &[Add],
+    concurrent: &[Add],
+) -> Result<()> {
+    if written.is_empty() || concurrent.is_empty() {
+        return Ok(());
+    }
+
+    let state = SessionContext::new().state();
+    let provider = NativeTable::new(table.clone());
+    let scan_schema = table.snapshot()?.arrow_schema()?;
+
+    for constraint in constraints {
+        let projection = constraint
+            .columns
+            .iter()
+            .map(|column| scan_schema.index_of(column))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut keys = HashSet::new();
+        scan_keys(&provider, &state, concurrent, &projection, |key| {
+            keys.insert(key);
+            false
+        })
+        .await?;
+
+        let conflict = scan_keys(&provider, &state, written, &projection, |key| {
+            keys.contains(&key)
+        })
+        .await?;
+        if conflict {
+            return Err(NativeError::UniqueViolation {
+                constraint: constraint.name.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the keys of all rows in `files`, passing the keys without nulls to
+/// `f` until it returns true.
+///
+/// Returns if `f` returned true for any key.
+async fn scan_keys(
+    provider: &NativeTable,
+    state: &SessionState,
+    files: &[Add],
+    projection: &[usize],
+    mut f: impl FnMut(OwnedRow) -> bool,
+) -> Result<bool> {
+    let plan = provider
+        .scan_staged_files(state, files, Some(&projection.to_vec()), None)
+        .await?;
+    let converter = RowConverter::new(
+        plan.schema()
+            .fields()
+            .iter()
+            .map(|field| SortField::new(field.data_type().clone()))
+            .collect(),
+    )?;
+
+    let mut stream = execute_stream(plan, state.task_ctx())?;
+    while let Some(batch) = stream.next().await {
+        let batch = batch?;
+        let rows = converter.convert_columns(batch.columns())?;
+        for row in 0..batch.num_rows() {
+            if batch.columns().iter().any(|column| column.is_null(row)) {
+                continue;
+            }
+            if f(rows.row(row).owned()) {
+                return Ok(true);
+            }
+        }
+    }
+
+    Ok(false)
+}
@@ -5,6 +5,7 @@ use std::task::{Context, Poll, Wake};
 use tokio::select;
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error};
 
 use super::pipeline::{ErrorSink, Sink, Source};
@@ -30,6 +31,9 @@ pub struct Task {
 
     /// This index of this node in relation to the parent query node.
     pub child: usize,
+
+    /// Cancellation for the query this task is part of.
+    pub cancel: CancellationToken,
 }
 
 impl Task {
@@ -120,11 +124,22 @@ impl TaskExecutor {
     }
 
     fn execute_inner(task: Task) {
-        // TODO: Check canceled.
-
         let partition = task.partition;
         let child = task.child;
 
+        if task.cancel.is_cancelled() {
+            debug!(%partition, "task canceled");
+            Self::handle_error(
+                child,
+                partition,
+                task.output.as_ref(),
+                task.errors.as_ref(),
+                DistExecError::Canceled,
+            );
+            // No rescheduling. The query is being torn down.
+            return;
+        }
+
         let waker = Arc::new(TaskWaker { task });
         let c_waker = waker.clone().into();
         let mut cx = Context::from_waker(&c_waker);
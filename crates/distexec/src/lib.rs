@@ -11,6 +11,9 @@ pub enum DistExecError {
     #[error("{0}")]
     String(String),
 
+    #[error("canceling statement due to user request")]
+    Canceled,
+
     #[error(transparent)]
     DataFusion(#[from] datafusion::error::DataFusionError),
 }
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use datafusion::execution::TaskContext;
 use datafusion::physical_plan::ExecutionPlan;
+use tokio_util::sync::CancellationToken;
 
 use super::executor::{Task, TaskExecutor};
 use super::pipeline::{ErrorSink, PipelineBuilder, Sink};
@@ -50,11 +51,14 @@ impl Scheduler {
     }
 
     /// Schedule a plan for execution.
+    ///
+    /// Tasks for the plan stop executing once `cancel` is triggered.
     pub fn schedule(
         &self,
         plan: Arc<dyn ExecutionPlan>,
         context: Arc<TaskContext>,
         output: OutputSink,
+        cancel: CancellationToken,
     ) -> Result<()> {
         let pipeline = PipelineBuilder::new(plan, context)
             .build(output.batches.clone(), output.errors.clone())?;
@@ -73,6 +77,7 @@ impl Scheduler {
                     errors: output.errors.clone(),
                     child: stage.output.map(|o| o.child).unwrap_or(0),
                     partition,
+                    cancel: cancel.clone(),
                 };
 
                 self.schedule_task(task);
@@ -518,6 +518,13 @@ impl Operation {
             }
         };
 
+        // Data for these is exchanged with a postgres client.
+        if let sqlexec::LogicalPlan::CopyStdio(_) = plan {
+            return Err(DatabaseError::new(
+                "COPY FROM STDIN and COPY TO STDOUT are only supported over the postgres protocol",
+            ));
+        }
+
         self.plan = Some(plan.clone());
         self.schema = plan
             .output_schema()
@@ -555,7 +562,7 @@ impl Operation {
                     sqlexec::LogicalPlan::Transaction(_) => {
                         return Err(DatabaseError::UnsupportedLazyEvaluation)
                     }
-                    sqlexec::LogicalPlan::Noop => {}
+                    sqlexec::LogicalPlan::CopyStdio(_) | sqlexec::LogicalPlan::Noop => {}
                 };
             }
             OperationType::Prql => {}
@@ -712,6 +719,9 @@ impl Operation {
             ExecutionResult::UpdateSuccess { updated_rows } => {
                 Self::numeric_result("count", updated_rows as u64)
             }
+            ExecutionResult::MergeSuccess { merged_rows } => {
+                Self::numeric_result("count", merged_rows as u64)
+            }
             _ => Self::operation_result("result", res.to_string()),
         }
     }
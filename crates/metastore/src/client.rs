@@ -343,11 +343,17 @@ impl StatefulWorker {
                     catalog: Some(state.try_into().unwrap()),
                 });
 
-                let result = self.client.commit_catalog(commit_request).await.unwrap();
-                let result = result.into_inner();
-                let state: CatalogState = result.catalog.unwrap().try_into().unwrap();
-                self.set_cached_state(state);
-                let res = Ok(self.cached_state.clone());
+                // Commits may be rejected if another session committed first.
+                // The error carries the strategy for resolving the conflict.
+                let res = match self.client.commit_catalog(commit_request).await {
+                    Ok(result) => {
+                        let result = result.into_inner();
+                        let state: CatalogState = result.catalog.unwrap().try_into().unwrap();
+                        self.set_cached_state(state);
+                        Ok(self.cached_state.clone())
+                    }
+                    Err(e) => Err(CatalogError::from(e)),
+                };
 
                 if response.send(res).is_err() {
                     error!("failed to respond to commit");
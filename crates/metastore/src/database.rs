@@ -1,6 +1,5 @@
 //! Module for handling the catalog for a single database.
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use once_cell::sync::Lazy;
@@ -14,7 +13,12 @@ use protogen::metastore::types::catalog::{
     EntryMeta,
     EntryType,
     FunctionEntry,
+    Grant,
+    Privilege,
+    RoleEntry,
     SchemaEntry,
+    SequenceEntry,
+    SequenceOwner,
     SourceAccessMode,
     TableEntry,
     TunnelEntry,
@@ -22,12 +26,22 @@ use protogen::metastore::types::catalog::{
     CURRENT_CATALOG_VERSION,
 };
 use protogen::metastore::types::options::{
+    is_widening_type_change,
     DatabaseOptions,
     DatabaseOptionsInternal,
+    InternalColumnDefinition,
     TableOptionsInternal,
+    TableOptionsV0,
     TunnelOptions,
 };
-use protogen::metastore::types::service::{AlterDatabaseOperation, AlterTableOperation, Mutation};
+use protogen::metastore::types::service::{
+    AlterDatabaseOperation,
+    AlterTableOperation,
+    CommentOn,
+    CreateSequence,
+    Mutation,
+    PrivilegeObject,
+};
 use protogen::metastore::types::storage::{ExtraState, PersistedCatalog};
 use sqlbuiltins::builtins::{
     BuiltinDatabase,
@@ -80,12 +94,10 @@ pub struct DatabaseCatalog {
     storage: Arc<Storage>,
 
     /// A cached catalog state for a single database.
+    ///
+    /// Only updated when loading the latest catalog from object storage, or
+    /// after successfully committing a new state.
     cached: Mutex<State>,
-
-    /// Whether or not to force a full reload of the catalog from object
-    /// storage.
-    // TODO: Remove when `State` has transactional semantics.
-    require_full_load: AtomicBool,
 }
 
 impl DatabaseCatalog {
@@ -101,7 +113,6 @@ impl DatabaseCatalog {
             db_id,
             storage,
             cached: Mutex::new(state),
-            require_full_load: AtomicBool::new(false),
         })
     }
 
@@ -119,27 +130,48 @@ impl DatabaseCatalog {
         version: u64,
         state_to_commit: CatalogState,
     ) -> Result<CatalogState> {
-        let state = self.cached.lock().await;
-        let state = State::from_catalog_state_and_counter(state_to_commit, state.oid_counter)?;
+        let mut cached = self.cached.lock().await;
+
+        // Mutations aren't applied to the cached state, so the cached oid
+        // counter may be behind the oids handed out for the state being
+        // committed.
+        let next_oid = state_to_commit
+            .entries
+            .keys()
+            .max()
+            .map(|oid| oid + 1)
+            .unwrap_or_default();
+        let counter = cached.oid_counter.max(next_oid);
+
+        let state = State::from_catalog_state_and_counter(state_to_commit, counter)?;
         let persist = state.to_persisted();
 
         self.storage
             .write_catalog(self.db_id, version, persist)
             .await?;
-        self.require_full_load.store(true, Ordering::Relaxed);
 
-        Ok(self.serializable_state(&state))
+        // Writes are only accepted for the latest version, so what we just
+        // wrote is now the latest state.
+        let committed = self.serializable_state(&state);
+        *cached = state;
+
+        Ok(committed)
     }
     /// Try to mutate the catalog without committing the changes.
     /// This is useful when you need to potentially do some extra checks before commiting.
     /// The returned `UncommitedCatalog` can be committed to persist the changes.
+    ///
+    /// Mutations are applied to a copy of the cached state, so the cached state
+    /// is left untouched if any mutation fails or if the returned state is
+    /// never committed. Applying the same mutations to the same version will
+    /// always produce the same state.
     pub async fn try_mutate(&self, version: u64, mutations: Vec<Mutation>) -> Result<CatalogState> {
         debug!(db_id = %self.db_id, %version, ?mutations, "mutating catalog");
 
         // TODO: Reduce locking.
         self.load_latest().await?;
 
-        let mut state = self.cached.lock().await;
+        let mut state = self.cached.lock().await.clone();
         if state.version != version {
             return Err(MetastoreError::VersionMismatch {
                 have: version,
@@ -148,16 +180,8 @@ impl DatabaseCatalog {
         }
 
         // TODO: Validate mutations.
-        // TODO: Rollback on failed mutate.
-        //
-        // Currently don't have guarantees about what the state looks like on
-        // failed mutates. Force a reload.
-        //
-        // Fixed with <https://github.com/GlareDB/glaredb/issues/547>.
-        if let Err(e) = state.mutate(mutations) {
-            self.require_full_load.store(true, Ordering::Relaxed);
-            return Err(e);
-        }
+        state.mutate(mutations)?;
+
         let state = self.serializable_state(&state);
         Ok(state)
     }
@@ -181,8 +205,7 @@ impl DatabaseCatalog {
 
         let latest_version = self.storage.latest_version(&self.db_id).await?;
 
-        // We must have the latest version and _not_ have a full load required.
-        if current_version == latest_version && !self.require_full_load.load(Ordering::Relaxed) {
+        if current_version == latest_version {
             return Ok(());
         }
         debug!(db_id = %self.db_id, %current_version, %latest_version, "loading latest catalog for database");
@@ -201,9 +224,6 @@ impl DatabaseCatalog {
         }
         *cached = state;
 
-        // Reset full load flag.
-        self.require_full_load.store(false, Ordering::Relaxed);
-
         Ok(())
     }
 }
@@ -319,7 +339,7 @@ impl CreatePolicy {
 }
 
 /// Inner state of the catalog.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct State {
     /// Version incremented on every update.
     version: u64,
@@ -335,6 +355,8 @@ struct State {
     tunnel_names: HashMap<String, u32>,
     /// Map credentials names to their ids.
     credentials_names: HashMap<String, u32>,
+    /// Map role names to their ids.
+    role_names: HashMap<String, u32>,
     /// Map schema names to their ids.
     schema_names: HashMap<String, u32>,
     /// Map schema IDs to objects in the schema.
@@ -373,6 +395,7 @@ impl State {
         let mut database_names = HashMap::new();
         let mut tunnel_names = HashMap::new();
         let mut credentials_names = HashMap::new();
+        let mut role_names = HashMap::new();
         let mut schema_names = HashMap::new();
         let mut schema_objects = HashMap::new();
 
@@ -435,6 +458,17 @@ impl State {
 
                     credentials_names.insert(creds.meta.name.clone(), *oid);
                 }
+                CatalogEntry::Role(role) => {
+                    if role.meta.parent != DATABASE_PARENT_ID {
+                        return Err(MetastoreError::ObjectHasNonZeroParent {
+                            object: *oid,
+                            parent: role.meta.parent,
+                            object_type: "role",
+                        });
+                    }
+
+                    role_names.insert(role.meta.name.clone(), *oid);
+                }
                 CatalogEntry::Schema(schema) => {
                     if schema.meta.parent == DATABASE_PARENT_ID {
                         return Err(MetastoreError::ObjectHasInvalidParentId {
@@ -446,7 +480,9 @@ impl State {
 
                     schema_names.insert(schema.meta.name.clone(), *oid);
                 }
-                entry @ CatalogEntry::View(_) | entry @ CatalogEntry::Table(_) => {
+                entry @ CatalogEntry::View(_)
+                | entry @ CatalogEntry::Table(_)
+                | entry @ CatalogEntry::Sequence(_) => {
                     if entry.get_meta().parent == DATABASE_PARENT_ID {
                         return Err(MetastoreError::ObjectHasInvalidParentId {
                             object: *oid,
@@ -501,6 +537,7 @@ impl State {
             database_names,
             tunnel_names,
             credentials_names,
+            role_names,
             schema_names,
             schema_objects,
         };
@@ -572,6 +609,8 @@ impl State {
             self.mutate_one(mutation)?;
         }
 
+        self.remove_dangling_grants();
+
         Ok(())
     }
 
@@ -609,6 +648,16 @@ impl State {
 
                 self.entries.remove(&credentials_id)?.unwrap();
             }
+            Mutation::DropRole(drop_role) => {
+                let if_exists = drop_role.if_exists;
+                let role_id = match self.role_names.remove(&drop_role.name) {
+                    None if if_exists => return Ok(()),
+                    None => return Err(MetastoreError::MissingRole(drop_role.name)),
+                    Some(id) => id,
+                };
+
+                self.entries.remove(&role_id)?.unwrap();
+            }
             Mutation::DropSchema(drop_schema) => {
                 let if_exists = drop_schema.if_exists;
                 let schema_id = match self.schema_names.remove(&drop_schema.name) {
@@ -676,7 +725,26 @@ impl State {
                     Some(id) => id,
                 };
 
-                self.entries.remove(&ent_id)?.unwrap(); // Bug if doesn't exist.
+                match self.entries.remove(&ent_id)?.unwrap() {
+                    // Bug if doesn't exist.
+                    CatalogEntry::Sequence(SequenceEntry {
+                        meta,
+                        owned_by: Some(owner),
+                        ..
+                    }) => {
+                        if let Some(CatalogEntry::Table(table)) =
+                            self.entries.get(&owner.table_id)?
+                        {
+                            return Err(MetastoreError::SequenceOwnedByColumn {
+                                sequence: meta.name,
+                                table: table.meta.name.clone(),
+                                column: owner.column,
+                            });
+                        }
+                    }
+                    CatalogEntry::Table(_) => self.drop_owned_sequences(ent_id, None)?,
+                    _ => (),
+                }
             }
             Mutation::CreateExternalDatabase(create_database) => {
                 validate_object_name(&create_database.name)?;
@@ -710,6 +778,7 @@ impl State {
                         builtin: false,
                         external: true,
                         is_temp: false,
+                        description: None,
                     },
                     options: create_database.options,
                     tunnel_id,
@@ -740,6 +809,7 @@ impl State {
                         builtin: false,
                         external: false,
                         is_temp: false,
+                        description: None,
                     },
                     options: create_tunnel.options,
                 };
@@ -775,6 +845,7 @@ impl State {
                         builtin: false,
                         external: false,
                         is_temp: false,
+                        description: None,
                     },
                     options: create_credentials.options,
                     comment: create_credentials.comment,
@@ -807,6 +878,7 @@ impl State {
                         builtin: false,
                         external: false,
                         is_temp: false,
+                        description: None,
                     },
                 };
                 self.entries.insert(oid, CatalogEntry::Schema(ent))?;
@@ -830,6 +902,7 @@ impl State {
                         builtin: false,
                         external: false,
                         is_temp: false,
+                        description: None,
                     },
                     sql: create_view.sql,
                     columns: create_view.columns,
@@ -860,6 +933,7 @@ impl State {
                         builtin: false,
                         external: false,
                         is_temp: false,
+                        description: None,
                     },
                     options: create_table.options.into(),
                     tunnel_id: None,
@@ -870,6 +944,11 @@ impl State {
                 let policy =
                     CreatePolicy::new(create_table.if_not_exists, create_table.or_replace)?;
 
+                // Sequences of the replaced table go away with it.
+                if policy == CreatePolicy::CreateOrReplace {
+                    self.drop_owned_sequences(oid, None)?;
+                }
+
                 self.try_insert_table_namespace(CatalogEntry::Table(ent), schema_id, oid, policy)?;
             }
             Mutation::CreateExternalTable(create_ext) => {
@@ -901,6 +980,7 @@ impl State {
                         builtin: false,
                         external: true,
                         is_temp: false,
+                        description: None,
                     },
                     options: create_ext.options.clone(),
                     tunnel_id,
@@ -947,9 +1027,14 @@ impl State {
 
                         let mut ent = self.entries.remove(&oid)?.unwrap();
 
-                        // The entry must be a "table" or a "view".
+                        // The entry must be a "table", "view" or "sequence".
                         assert!(
-                            matches!(ent, CatalogEntry::Table(_) | CatalogEntry::View(_)),
+                            matches!(
+                                ent,
+                                CatalogEntry::Table(_)
+                                    | CatalogEntry::View(_)
+                                    | CatalogEntry::Sequence(_)
+                            ),
                             "unexpected entry type: {ent:?}"
                         );
 
@@ -975,6 +1060,105 @@ impl State {
                             other => unreachable!("unexpected entry type: {:?}", other),
                         };
                     }
+                    AlterTableOperation::SetAutoCompaction { policy } => {
+                        let oid = match objs.tables.get(&alter_table.name) {
+                            None => {
+                                return Err(MetastoreError::MissingNamedObject {
+                                    schema: alter_table.schema,
+                                    name: alter_table.name,
+                                })
+                            }
+                            Some(id) => id,
+                        };
+
+                        match self.entries.get_mut(oid)?.unwrap() {
+                            CatalogEntry::Table(TableEntry {
+                                options: TableOptionsV0::Internal(opts),
+                                ..
+                            }) => {
+                                opts.auto_compaction = policy;
+                            }
+                            _ => {
+                                return Err(MetastoreError::AutoCompactionNotSupported(
+                                    alter_table.name,
+                                ))
+                            }
+                        };
+                    }
+                    AlterTableOperation::SetMaterializedViewRefreshed { refreshed_at } => {
+                        let oid = match objs.tables.get(&alter_table.name) {
+                            None => {
+                                return Err(MetastoreError::MissingNamedObject {
+                                    schema: alter_table.schema,
+                                    name: alter_table.name,
+                                })
+                            }
+                            Some(id) => id,
+                        };
+
+                        match self.entries.get_mut(oid)?.unwrap() {
+                            CatalogEntry::Table(TableEntry {
+                                options:
+                                    TableOptionsV0::Internal(TableOptionsInternal {
+                                        materialized_view: Some(view),
+                                        ..
+                                    }),
+                                ..
+                            }) => {
+                                view.refreshed_at = Some(refreshed_at);
+                            }
+                            _ => return Err(MetastoreError::NotMaterializedView(alter_table.name)),
+                        };
+                    }
+                    operation @ (AlterTableOperation::AddColumn { .. }
+                    | AlterTableOperation::DropColumn { .. }
+                    | AlterTableOperation::RenameColumn { .. }
+                    | AlterTableOperation::AlterColumnType { .. }) => {
+                        let oid = match objs.tables.get(&alter_table.name) {
+                            None => {
+                                return Err(MetastoreError::MissingNamedObject {
+                                    schema: alter_table.schema,
+                                    name: alter_table.name,
+                                })
+                            }
+                            Some(id) => *id,
+                        };
+
+                        let columns = match self.entries.get_mut(&oid)?.unwrap() {
+                            CatalogEntry::Table(TableEntry {
+                                options: TableOptionsV0::Internal(opts),
+                                ..
+                            }) => &mut opts.columns,
+                            _ => {
+                                return Err(MetastoreError::AlterColumnsNotSupported(
+                                    alter_table.name,
+                                ))
+                            }
+                        };
+
+                        alter_columns(&alter_table.name, columns, operation.clone())?;
+
+                        // Keep sequences owned by the column in sync.
+                        match operation {
+                            AlterTableOperation::DropColumn { name, .. } => {
+                                self.drop_owned_sequences(oid, Some(&name))?
+                            }
+                            AlterTableOperation::RenameColumn { name, new_name } => {
+                                for ent in self.entries.0.values_mut() {
+                                    if let CatalogEntry::Sequence(SequenceEntry {
+                                        owned_by: Some(owner),
+                                        ..
+                                    }) = ent
+                                    {
+                                        if owner.table_id == oid && owner.column == name {
+                                            owner.column = new_name.clone();
+                                        }
+                                    }
+                                }
+                            }
+                            _ => (),
+                        }
+                    }
                 };
             }
             Mutation::AlterDatabase(alter_database) => {
@@ -1039,6 +1223,191 @@ impl State {
                     _ => unreachable!("entry should be a tunnel"),
                 };
             }
+            Mutation::CreateRole(create_role) => {
+                validate_object_name(&create_role.name)?;
+                match self.role_names.get(&create_role.name) {
+                    Some(_) if create_role.if_not_exists => return Ok(()), // Already exists, nothing to do.
+                    Some(_) => return Err(MetastoreError::DuplicateName(create_role.name)),
+                    None => (),
+                }
+
+                let oid = self.next_oid();
+                let ent = RoleEntry {
+                    meta: EntryMeta {
+                        entry_type: EntryType::Role,
+                        id: oid,
+                        // Roles, just like databases don't have any parent.
+                        parent: DATABASE_PARENT_ID,
+                        name: create_role.name.clone(),
+                        builtin: false,
+                        external: false,
+                        is_temp: false,
+                        description: None,
+                    },
+                    grants: Vec::new(),
+                };
+                self.entries.insert(oid, CatalogEntry::Role(ent))?;
+
+                self.role_names.insert(create_role.name, oid);
+            }
+            Mutation::GrantPrivileges(grant) => {
+                let object_id = self.resolve_privilege_object(&grant.object, &grant.privileges)?;
+                let role = self.get_role_mut(&grant.role)?;
+                for privilege in grant.privileges {
+                    if !role.has_grant(object_id, privilege) {
+                        role.grants.push(Grant {
+                            object_id,
+                            privilege,
+                        });
+                    }
+                }
+            }
+            Mutation::RevokePrivileges(revoke) => {
+                let object_id =
+                    self.resolve_privilege_object(&revoke.object, &revoke.privileges)?;
+                let role = self.get_role_mut(&revoke.role)?;
+                role.grants.retain(|g| {
+                    g.object_id != object_id || !revoke.privileges.contains(&g.privilege)
+                });
+            }
+            Mutation::CreateSequence(create_sequence) => {
+                validate_object_name(&create_sequence.name)?;
+                validate_sequence_options(&create_sequence)?;
+
+                let schema_id = self.get_schema_id(&create_sequence.schema)?;
+
+                let owned_by = match create_sequence.owned_by {
+                    Some(owner) => {
+                        let table_id = self
+                            .schema_objects
+                            .get(&schema_id)
+                            .and_then(|objs| objs.tables.get(&owner.table))
+                            .copied()
+                            .ok_or_else(|| MetastoreError::MissingNamedObject {
+                                schema: create_sequence.schema.clone(),
+                                name: owner.table.clone(),
+                            })?;
+                        match self.entries.get(&table_id)? {
+                            Some(CatalogEntry::Table(TableEntry {
+                                options: TableOptionsV0::Internal(opts),
+                                ..
+                            })) if !opts.columns.iter().any(|col| col.name == owner.column) => {
+                                return Err(MetastoreError::MissingColumn {
+                                    table: owner.table,
+                                    column: owner.column,
+                                })
+                            }
+                            Some(CatalogEntry::Table(_)) => (),
+                            _ => {
+                                return Err(MetastoreError::InvalidSequenceOptions {
+                                    name: create_sequence.name,
+                                    reason: format!("'{}' is not a table", owner.table),
+                                })
+                            }
+                        }
+                        Some(SequenceOwner {
+                            table_id,
+                            column: owner.column,
+                        })
+                    }
+                    None => None,
+                };
+
+                let oid = self.get_or_next_oid(schema_id, &create_sequence.name);
+
+                let ent = SequenceEntry {
+                    meta: EntryMeta {
+                        entry_type: EntryType::Sequence,
+                        id: oid,
+                        parent: schema_id,
+                        name: create_sequence.name.clone(),
+                        builtin: false,
+                        external: false,
+                        is_temp: false,
+                        description: None,
+                    },
+                    start: create_sequence.start,
+                    increment: create_sequence.increment,
+                    min_value: create_sequence.min_value,
+                    max_value: create_sequence.max_value,
+                    cycle: create_sequence.cycle,
+                    last_value: create_sequence.start,
+                    is_called: false,
+                    owned_by,
+                    cache: create_sequence.cache,
+                };
+
+                let policy = CreatePolicy::new(create_sequence.if_not_exists, false)?;
+
+                self.try_insert_table_namespace(
+                    CatalogEntry::Sequence(ent),
+                    schema_id,
+                    oid,
+                    policy,
+                )?;
+            }
+            Mutation::NextSequenceValues(next) => {
+                let sequence = self.get_sequence_mut(&next.schema, &next.name)?;
+                let values = sequence
+                    .next_values(next.count as usize)
+                    .ok_or(MetastoreError::SequenceExhausted(next.name))?;
+                if let Some(last) = values.last() {
+                    sequence.last_value = *last;
+                    sequence.is_called = true;
+                }
+            }
+            Mutation::SetSequenceValue(set) => {
+                let sequence = self.get_sequence_mut(&set.schema, &set.name)?;
+                if set.value < sequence.min_value || set.value > sequence.max_value {
+                    return Err(MetastoreError::SequenceValueOutOfBounds {
+                        name: set.name,
+                        value: set.value,
+                        min: sequence.min_value,
+                        max: sequence.max_value,
+                    });
+                }
+                sequence.last_value = set.value;
+                sequence.is_called = set.is_called;
+            }
+            Mutation::CommentOn(comment) => {
+                let object_id = self.resolve_comment_object(&comment)?;
+                let ent = self
+                    .entries
+                    .get_mut(&object_id)?
+                    .expect("entry should exist");
+                // Views are stored in the table namespace, check against the
+                // actual entry type.
+                if ent.entry_type() != comment.object_type {
+                    return Err(MetastoreError::UnexpectedObjectType {
+                        name: comment.name,
+                        object_type: comment.object_type.as_str(),
+                    });
+                }
+
+                match (comment.column, ent) {
+                    (None, ent) => ent.get_meta_mut().description = comment.comment,
+                    (
+                        Some(column),
+                        CatalogEntry::Table(TableEntry {
+                            options: TableOptionsV0::Internal(opts),
+                            ..
+                        }),
+                    ) => {
+                        let col = opts
+                            .columns
+                            .iter_mut()
+                            .find(|col| col.name == column)
+                            .ok_or_else(|| MetastoreError::MissingColumn {
+                                table: comment.name.clone(),
+                                column: column.clone(),
+                            })?;
+                        col.description = comment.comment;
+                    }
+                    (Some(_), _) => {
+                        return Err(MetastoreError::ColumnCommentsNotSupported(comment.name))
+                    }
+                }
+            }
             Mutation::UpdateDeploymentStorage(update_deployment_storage) => {
                 // Update the new storage size
                 self.deployment.storage_size = update_deployment_storage.new_storage_size;
@@ -1065,6 +1434,7 @@ impl State {
                         builtin: false,
                         external: true,
                         is_temp: false,
+                        description: None,
                     },
                     func_type: f.function_type,
                     signature: Some(f.signature),
@@ -1125,6 +1495,168 @@ impl State {
         Ok(())
     }
 
+    fn get_role_mut(&mut self, name: &str) -> Result<&mut RoleEntry> {
+        let role_id = *self
+            .role_names
+            .get(name)
+            .ok_or_else(|| MetastoreError::MissingRole(name.to_string()))?;
+        match self.entries.get_mut(&role_id)?.expect("entry should exist") {
+            CatalogEntry::Role(role) => Ok(role),
+            ent => unreachable!("entry should be a role entry but found: {ent:?}"),
+        }
+    }
+
+    /// Resolve the object privileges are being granted on (or revoked from),
+    /// checking that the privileges apply to the object.
+    fn resolve_privilege_object(
+        &self,
+        object: &PrivilegeObject,
+        privileges: &[Privilege],
+    ) -> Result<u32> {
+        let object_id = match object.object_type {
+            EntryType::Database => *self
+                .database_names
+                .get(&object.name)
+                .ok_or_else(|| MetastoreError::MissingDatabase(object.name.clone()))?,
+            EntryType::Schema => self.get_schema_id(&object.name)?,
+            EntryType::Credentials => *self
+                .credentials_names
+                .get(&object.name)
+                .ok_or_else(|| MetastoreError::MissingCredentials(object.name.clone()))?,
+            EntryType::Table | EntryType::View | EntryType::Sequence => {
+                let schema = object.schema.as_deref().unwrap_or(DEFAULT_SCHEMA);
+                let schema_id = self.get_schema_id(schema)?;
+                self.schema_objects
+                    .get(&schema_id)
+                    .and_then(|objs| objs.tables.get(&object.name))
+                    .copied()
+                    .ok_or_else(|| MetastoreError::MissingNamedObject {
+                        schema: schema.to_string(),
+                        name: object.name.clone(),
+                    })?
+            }
+            EntryType::Function => {
+                let schema = object.schema.as_deref().unwrap_or(DEFAULT_SCHEMA);
+                let schema_id = self.get_schema_id(schema)?;
+                self.schema_objects
+                    .get(&schema_id)
+                    .and_then(|objs| objs.functions.get(&object.name))
+                    .copied()
+                    .ok_or_else(|| MetastoreError::MissingNamedObject {
+                        schema: schema.to_string(),
+                        name: object.name.clone(),
+                    })?
+            }
+            other => {
+                return Err(MetastoreError::InvalidPrivilege {
+                    privilege: privileges.first().copied().unwrap_or(Privilege::Usage),
+                    object_type: other.as_str(),
+                })
+            }
+        };
+
+        // Views are stored in the table namespace, check against the actual
+        // entry type.
+        let entry_type = self
+            .entries
+            .as_ref()
+            .get(&object_id)
+            .map(|ent| ent.entry_type())
+            .ok_or(MetastoreError::MissingEntry(object_id))?;
+        let allowed = Privilege::allowed_for(entry_type);
+        if let Some(privilege) = privileges.iter().find(|p| !allowed.contains(p)) {
+            return Err(MetastoreError::InvalidPrivilege {
+                privilege: *privilege,
+                object_type: entry_type.as_str(),
+            });
+        }
+
+        Ok(object_id)
+    }
+
+    /// Get the id of the object a comment is set on.
+    fn resolve_comment_object(&self, comment: &CommentOn) -> Result<u32> {
+        match comment.object_type {
+            EntryType::Database => self
+                .database_names
+                .get(&comment.name)
+                .copied()
+                .ok_or_else(|| MetastoreError::MissingDatabase(comment.name.clone())),
+            EntryType::Schema => self.get_schema_id(&comment.name),
+            EntryType::Table | EntryType::View => {
+                let schema = comment.schema.as_deref().unwrap_or(DEFAULT_SCHEMA);
+                let schema_id = self.get_schema_id(schema)?;
+                self.schema_objects
+                    .get(&schema_id)
+                    .and_then(|objs| objs.tables.get(&comment.name))
+                    .copied()
+                    .ok_or_else(|| MetastoreError::MissingNamedObject {
+                        schema: schema.to_string(),
+                        name: comment.name.clone(),
+                    })
+            }
+            other => Err(MetastoreError::CommentNotSupported(other.as_str())),
+        }
+    }
+
+    fn get_sequence_mut(&mut self, schema: &str, name: &str) -> Result<&mut SequenceEntry> {
+        let schema_id = self.get_schema_id(schema)?;
+        let oid = self
+            .schema_objects
+            .get(&schema_id)
+            .and_then(|objs| objs.tables.get(name))
+            .copied()
+            .ok_or_else(|| MetastoreError::MissingNamedObject {
+                schema: schema.to_string(),
+                name: name.to_string(),
+            })?;
+        match self.entries.get_mut(&oid)?.expect("entry should exist") {
+            CatalogEntry::Sequence(sequence) => Ok(sequence),
+            _ => Err(MetastoreError::NotSequence(name.to_string())),
+        }
+    }
+
+    /// Drop the sequences owned by a table, or only the ones owned by a single
+    /// column of the table.
+    fn drop_owned_sequences(&mut self, table_id: u32, column: Option<&str>) -> Result<()> {
+        let owned: Vec<_> = self
+            .entries
+            .as_ref()
+            .values()
+            .filter_map(|ent| match ent {
+                CatalogEntry::Sequence(SequenceEntry {
+                    meta,
+                    owned_by: Some(owner),
+                    ..
+                }) if owner.table_id == table_id
+                    && column.map(|col| col == owner.column).unwrap_or(true) =>
+                {
+                    Some(meta.clone())
+                }
+                _ => None,
+            })
+            .collect();
+
+        for meta in owned {
+            if let Some(objs) = self.schema_objects.get_mut(&meta.parent) {
+                objs.tables.remove(&meta.name);
+            }
+            self.entries.remove(&meta.id)?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove grants on objects that no longer exist.
+    fn remove_dangling_grants(&mut self) {
+        let existing: HashSet<u32> = self.entries.as_ref().keys().copied().collect();
+        for role_id in self.role_names.values() {
+            if let Some(CatalogEntry::Role(role)) = self.entries.0.get_mut(role_id) {
+                role.grants.retain(|g| existing.contains(&g.object_id));
+            }
+        }
+    }
+
     fn get_schema_id(&self, name: &str) -> Result<u32> {
         self.schema_names
             .get(name)
@@ -1230,6 +1762,7 @@ impl BuiltinCatalog {
                         builtin: true,
                         external: false,
                         is_temp: false,
+                        description: None,
                     },
                     options: DatabaseOptions::Internal(DatabaseOptionsInternal {}),
                     tunnel_id: None,
@@ -1252,6 +1785,7 @@ impl BuiltinCatalog {
                         builtin: true,
                         external: false,
                         is_temp: false,
+                        description: None,
                     },
                 }),
             )?;
@@ -1272,9 +1806,15 @@ impl BuiltinCatalog {
                         builtin: true,
                         external: false,
                         is_temp: false,
+                        description: None,
                     },
                     options: TableOptionsInternal {
                         columns: table.columns.clone(),
+                        check_constraints: Vec::new(),
+                        unique_constraints: Vec::new(),
+                        auto_compaction: None,
+                        partition_columns: Vec::new(),
+                        materialized_view: None,
                     }
                     .into(),
                     tunnel_id: None,
@@ -1307,6 +1847,7 @@ impl BuiltinCatalog {
                         builtin: true,
                         external: false,
                         is_temp: false,
+                        description: None,
                     },
                     sql: view.sql.to_string(),
                     columns: Vec::new(),
@@ -1407,6 +1948,7 @@ impl BuiltinCatalog {
                     builtin: true,
                     external: false,
                     is_temp: false,
+                    description: None,
                 };
 
                 ents.push(FunctionEntry {
@@ -1422,6 +1964,106 @@ impl BuiltinCatalog {
     }
 }
 
+/// Apply a column operation to the columns of a native table.
+fn alter_columns(
+    table: &str,
+    columns: &mut Vec<InternalColumnDefinition>,
+    operation: AlterTableOperation,
+) -> Result<()> {
+    let position = |columns: &[InternalColumnDefinition], name: &str| {
+        columns.iter().position(|col| col.name == name)
+    };
+    let missing_column = |column: String| MetastoreError::MissingColumn {
+        table: table.to_string(),
+        column,
+    };
+
+    match operation {
+        AlterTableOperation::AddColumn {
+            column,
+            if_not_exists,
+        } => {
+            validate_object_name(&column.name)?;
+            match position(columns, &column.name) {
+                Some(_) if if_not_exists => (),
+                Some(_) => {
+                    return Err(MetastoreError::DuplicateColumn {
+                        table: table.to_string(),
+                        column: column.name,
+                    })
+                }
+                None => columns.push(column),
+            }
+        }
+        AlterTableOperation::DropColumn { name, if_exists } => match position(columns, &name) {
+            None if if_exists => (),
+            None => return Err(missing_column(name)),
+            Some(_) if columns.len() == 1 => {
+                return Err(MetastoreError::CannotDropLastColumn(table.to_string()))
+            }
+            Some(idx) => {
+                columns.remove(idx);
+            }
+        },
+        AlterTableOperation::RenameColumn { name, new_name } => {
+            validate_object_name(&new_name)?;
+            if position(columns, &new_name).is_some() {
+                return Err(MetastoreError::DuplicateColumn {
+                    table: table.to_string(),
+                    column: new_name,
+                });
+            }
+            let idx = position(columns, &name).ok_or_else(|| missing_column(name))?;
+            columns[idx].name = new_name;
+        }
+        AlterTableOperation::AlterColumnType { name, arrow_type } => {
+            let idx = position(columns, &name).ok_or_else(|| missing_column(name.clone()))?;
+            let column = &mut columns[idx];
+            if !is_widening_type_change(&column.arrow_type, &arrow_type) {
+                return Err(MetastoreError::InvalidColumnTypeChange {
+                    column: name,
+                    from: column.arrow_type.clone(),
+                    to: arrow_type,
+                });
+            }
+            column.arrow_type = arrow_type;
+        }
+        other => unreachable!("not a column operation: {other:?}"),
+    }
+
+    Ok(())
+}
+
+fn validate_sequence_options(sequence: &CreateSequence) -> Result<()> {
+    let reason = if sequence.increment == 0 {
+        "INCREMENT must not be zero".to_string()
+    } else if sequence.cache < 1 {
+        format!("CACHE ({}) must be greater than zero", sequence.cache)
+    } else if sequence.min_value >= sequence.max_value {
+        format!(
+            "MINVALUE ({}) must be less than MAXVALUE ({})",
+            sequence.min_value, sequence.max_value
+        )
+    } else if sequence.start < sequence.min_value {
+        format!(
+            "START value ({}) cannot be less than MINVALUE ({})",
+            sequence.start, sequence.min_value
+        )
+    } else if sequence.start > sequence.max_value {
+        format!(
+            "START value ({}) cannot be greater than MAXVALUE ({})",
+            sequence.start, sequence.max_value
+        )
+    } else {
+        return Ok(());
+    };
+
+    Err(MetastoreError::InvalidSequenceOptions {
+        name: sequence.name.clone(),
+        reason,
+    })
+}
+
 #[cfg(test)]
 impl DatabaseCatalog {
     /// Try to mutate the catalog and immediately commit the changes.
@@ -1444,18 +2086,27 @@ mod tests {
     use protogen::metastore::types::options::{
         DatabaseOptionsDebug,
         InternalColumnDefinition,
+        MaterializedViewOptions,
         TableOptionsDebug,
         TableOptionsInternal,
     };
     use protogen::metastore::types::service::{
         AlterDatabase,
+        AlterTable,
         CreateExternalDatabase,
         CreateExternalTable,
+        CreateRole,
         CreateSchema,
         CreateTable,
         CreateView,
         DropDatabase,
+        DropObject,
+        DropRole,
         DropSchema,
+        GrantPrivileges,
+        NextSequenceValues,
+        RevokePrivileges,
+        SequenceOwnerColumn,
     };
     use sqlbuiltins::builtins::{DEFAULT_CATALOG, INTERNAL_SCHEMA};
 
@@ -1845,49 +2496,134 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn duplicate_table_names_if_not_exists() {
+    async fn failed_mutate_leaves_state_untouched() {
         let db = new_catalog().await;
         let initial = version(&db).await;
 
-        // Add schema.
-        let state = db
-            .try_mutate_and_commit(
-                initial,
-                vec![Mutation::CreateSchema(CreateSchema {
+        // The first mutation succeeds, the second fails. Neither should be
+        // reflected in the state.
+        db.try_mutate(
+            initial,
+            vec![
+                Mutation::CreateSchema(CreateSchema {
                     name: "mushroom".to_string(),
                     if_not_exists: false,
-                })],
-            )
-            .await
-            .unwrap();
-
-        // Try to add two tables with the same name, each with "if not exists"
-        // set to true.
-        let mutation = Mutation::CreateExternalTable(CreateExternalTable {
-            schema: "mushroom".to_string(),
-            name: "bowser".to_string(),
-            options: TableOptionsDebug::default().into(),
-            if_not_exists: true,
-            or_replace: false,
-            tunnel: None,
-            columns: None,
-        });
-        let _ = db
-            .try_mutate_and_commit(state.version, vec![mutation.clone(), mutation])
-            .await
-            .unwrap();
+                }),
+                Mutation::DropSchema(DropSchema {
+                    name: "yoshi".to_string(),
+                    if_exists: false,
+                    cascade: false,
+                }),
+            ],
+        )
+        .await
+        .unwrap_err();
 
-        // Check that the duplicate connection we tried to create is not in the
-        // state.
         let state = db.get_state().await.unwrap();
-        let ents: Vec<_> = state
+        assert_eq!(initial, state.version);
+        assert!(!state
             .entries
-            .iter()
-            .filter(|(_, ent)| ent.get_meta().name == "bowser")
-            .collect();
-        assert!(
-            ents.len() == 1,
-            "found more than one 'bowser' entry (found {}): {:?}",
+            .values()
+            .any(|ent| ent.get_meta().name == "mushroom"));
+    }
+
+    #[tokio::test]
+    async fn uncommitted_mutate_discarded() {
+        let db = new_catalog().await;
+        let initial = version(&db).await;
+
+        let create_view = |name: &str| {
+            Mutation::CreateView(CreateView {
+                schema: "public".to_string(),
+                name: name.to_string(),
+                sql: "select 1".to_string(),
+                or_replace: false,
+                columns: Vec::new(),
+            })
+        };
+        let view_oid = |state: &CatalogState, name: &str| {
+            state
+                .entries
+                .iter()
+                .find(|(_, ent)| ent.get_meta().name == name)
+                .map(|(oid, _)| *oid)
+                .unwrap()
+        };
+
+        // Never committed.
+        let uncommitted = db
+            .try_mutate(initial, vec![create_view("bowser")])
+            .await
+            .unwrap();
+
+        // Same version can still be mutated, and replaying the same mutations
+        // results in the same oids.
+        let replayed = db
+            .try_mutate(initial, vec![create_view("bowser"), create_view("peach")])
+            .await
+            .unwrap();
+        assert_eq!(
+            view_oid(&uncommitted, "bowser"),
+            view_oid(&replayed, "bowser")
+        );
+
+        let committed = db.commit(initial, replayed).await.unwrap();
+        assert_eq!(committed.version, version(&db).await);
+
+        // Oids aren't reused after commit.
+        let state = db
+            .try_mutate_and_commit(committed.version, vec![create_view("luigi")])
+            .await
+            .unwrap();
+        let luigi = view_oid(&state, "luigi");
+        assert!(luigi > view_oid(&state, "bowser"));
+        assert!(luigi > view_oid(&state, "peach"));
+    }
+
+    #[tokio::test]
+    async fn duplicate_table_names_if_not_exists() {
+        let db = new_catalog().await;
+        let initial = version(&db).await;
+
+        // Add schema.
+        let state = db
+            .try_mutate_and_commit(
+                initial,
+                vec![Mutation::CreateSchema(CreateSchema {
+                    name: "mushroom".to_string(),
+                    if_not_exists: false,
+                })],
+            )
+            .await
+            .unwrap();
+
+        // Try to add two tables with the same name, each with "if not exists"
+        // set to true.
+        let mutation = Mutation::CreateExternalTable(CreateExternalTable {
+            schema: "mushroom".to_string(),
+            name: "bowser".to_string(),
+            options: TableOptionsDebug::default().into(),
+            if_not_exists: true,
+            or_replace: false,
+            tunnel: None,
+            columns: None,
+        });
+        let _ = db
+            .try_mutate_and_commit(state.version, vec![mutation.clone(), mutation])
+            .await
+            .unwrap();
+
+        // Check that the duplicate connection we tried to create is not in the
+        // state.
+        let state = db.get_state().await.unwrap();
+        let ents: Vec<_> = state
+            .entries
+            .iter()
+            .filter(|(_, ent)| ent.get_meta().name == "bowser")
+            .collect();
+        assert!(
+            ents.len() == 1,
+            "found more than one 'bowser' entry (found {}): {:?}",
             ents.len(),
             ents
         );
@@ -2051,7 +2787,15 @@ mod tests {
                         name: "luigi".to_string(),
                         nullable: true,
                         arrow_type: DataType::Utf8,
+                        default_expr: None,
+                        generated_always: false,
+                        description: None,
                     }],
+                    check_constraints: Vec::new(),
+                    unique_constraints: Vec::new(),
+                    auto_compaction: None,
+                    partition_columns: Vec::new(),
+                    materialized_view: None,
                 },
             })],
         )
@@ -2077,11 +2821,636 @@ mod tests {
                         name: "luigi".to_string(),
                         nullable: true,
                         arrow_type: DataType::Utf8,
+                        default_expr: None,
+                        generated_always: false,
+                        description: None,
                     }],
+                    check_constraints: Vec::new(),
+                    unique_constraints: Vec::new(),
+                    auto_compaction: None,
+                    partition_columns: Vec::new(),
+                    materialized_view: None,
                 },
             })],
         )
         .await
         .unwrap();
     }
+
+    fn role_grants(state: &CatalogState, name: &str) -> Vec<Grant> {
+        state
+            .entries
+            .values()
+            .find_map(|ent| match ent {
+                CatalogEntry::Role(role) if role.meta.name == name => Some(role.grants.clone()),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn grant_and_revoke_privileges() {
+        let db = new_catalog().await;
+
+        let state = db
+            .try_mutate_and_commit(
+                version(&db).await,
+                vec![
+                    Mutation::CreateRole(CreateRole {
+                        name: "analyst".to_string(),
+                        if_not_exists: false,
+                    }),
+                    Mutation::CreateView(CreateView {
+                        schema: DEFAULT_SCHEMA.to_string(),
+                        name: "goomba".to_string(),
+                        sql: "select 1".to_string(),
+                        or_replace: false,
+                        columns: Vec::new(),
+                    }),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let view = PrivilegeObject {
+            object_type: EntryType::Table,
+            schema: None,
+            name: "goomba".to_string(),
+        };
+
+        // Only SELECT is valid for views.
+        db.try_mutate_and_commit(
+            state.version,
+            vec![Mutation::GrantPrivileges(GrantPrivileges {
+                role: "analyst".to_string(),
+                privileges: vec![Privilege::Insert],
+                object: view.clone(),
+            })],
+        )
+        .await
+        .unwrap_err();
+
+        // Missing role.
+        db.try_mutate_and_commit(
+            state.version,
+            vec![Mutation::GrantPrivileges(GrantPrivileges {
+                role: "koopa".to_string(),
+                privileges: vec![Privilege::Select],
+                object: view.clone(),
+            })],
+        )
+        .await
+        .unwrap_err();
+
+        let state = db
+            .try_mutate_and_commit(
+                state.version,
+                vec![Mutation::GrantPrivileges(GrantPrivileges {
+                    role: "analyst".to_string(),
+                    privileges: vec![Privilege::Select],
+                    object: view.clone(),
+                })],
+            )
+            .await
+            .unwrap();
+        assert_eq!(1, role_grants(&state, "analyst").len());
+
+        // Granting again shouldn't duplicate the grant.
+        let state = db
+            .try_mutate_and_commit(
+                state.version,
+                vec![Mutation::GrantPrivileges(GrantPrivileges {
+                    role: "analyst".to_string(),
+                    privileges: vec![Privilege::Select],
+                    object: view.clone(),
+                })],
+            )
+            .await
+            .unwrap();
+        assert_eq!(1, role_grants(&state, "analyst").len());
+
+        let state = db
+            .try_mutate_and_commit(
+                state.version,
+                vec![Mutation::RevokePrivileges(RevokePrivileges {
+                    role: "analyst".to_string(),
+                    privileges: vec![Privilege::Select],
+                    object: view.clone(),
+                })],
+            )
+            .await
+            .unwrap();
+        assert!(role_grants(&state, "analyst").is_empty());
+
+        // Grants are removed along with the object.
+        let state = db
+            .try_mutate_and_commit(
+                state.version,
+                vec![Mutation::GrantPrivileges(GrantPrivileges {
+                    role: "analyst".to_string(),
+                    privileges: vec![Privilege::Select],
+                    object: view.clone(),
+                })],
+            )
+            .await
+            .unwrap();
+        let state = db
+            .try_mutate_and_commit(
+                state.version,
+                vec![Mutation::DropObject(DropObject {
+                    schema: DEFAULT_SCHEMA.to_string(),
+                    name: "goomba".to_string(),
+                    if_exists: false,
+                })],
+            )
+            .await
+            .unwrap();
+        assert!(role_grants(&state, "analyst").is_empty());
+
+        let state = db
+            .try_mutate_and_commit(
+                state.version,
+                vec![Mutation::DropRole(DropRole {
+                    name: "analyst".to_string(),
+                    if_exists: false,
+                })],
+            )
+            .await
+            .unwrap();
+        assert!(!state
+            .entries
+            .values()
+            .any(|ent| matches!(ent, CatalogEntry::Role(_))));
+    }
+
+    fn table_columns(state: &CatalogState, name: &str) -> Vec<InternalColumnDefinition> {
+        state
+            .entries
+            .values()
+            .find_map(|ent| match ent {
+                CatalogEntry::Table(table) if table.meta.name == name => {
+                    table.get_internal_columns()
+                }
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn alter_table_columns() {
+        let db = new_catalog().await;
+
+        let state = db
+            .try_mutate_and_commit(
+                version(&db).await,
+                vec![Mutation::CreateTable(CreateTable {
+                    schema: DEFAULT_SCHEMA.to_string(),
+                    name: "castle".to_string(),
+                    if_not_exists: false,
+                    or_replace: false,
+                    options: TableOptionsInternal {
+                        columns: InternalColumnDefinition::from_tuples([
+                            ("id", DataType::Int32, false),
+                            ("name", DataType::Utf8, true),
+                        ]),
+                        check_constraints: Vec::new(),
+                        unique_constraints: Vec::new(),
+                        auto_compaction: None,
+                        partition_columns: Vec::new(),
+                        materialized_view: None,
+                    },
+                })],
+            )
+            .await
+            .unwrap();
+
+        let alter = |operation| {
+            Mutation::AlterTable(AlterTable {
+                schema: DEFAULT_SCHEMA.to_string(),
+                name: "castle".to_string(),
+                operation,
+            })
+        };
+
+        let state = db
+            .try_mutate_and_commit(
+                state.version,
+                vec![
+                    alter(AlterTableOperation::AddColumn {
+                        column: InternalColumnDefinition {
+                            name: "stars".to_string(),
+                            nullable: true,
+                            arrow_type: DataType::Int16,
+                            default_expr: None,
+                            generated_always: false,
+                            description: None,
+                        },
+                        if_not_exists: false,
+                    }),
+                    alter(AlterTableOperation::RenameColumn {
+                        name: "name".to_string(),
+                        new_name: "title".to_string(),
+                    }),
+                    alter(AlterTableOperation::AlterColumnType {
+                        name: "id".to_string(),
+                        arrow_type: DataType::Int64,
+                    }),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            InternalColumnDefinition::from_tuples([
+                ("id", DataType::Int64, false),
+                ("title", DataType::Utf8, true),
+                ("stars", DataType::Int16, true),
+            ]),
+            table_columns(&state, "castle"),
+        );
+
+        // Duplicate column.
+        db.try_mutate_and_commit(
+            state.version,
+            vec![alter(AlterTableOperation::AddColumn {
+                column: InternalColumnDefinition {
+                    name: "title".to_string(),
+                    nullable: true,
+                    arrow_type: DataType::Utf8,
+                    default_expr: None,
+                    generated_always: false,
+                    description: None,
+                },
+                if_not_exists: false,
+            })],
+        )
+        .await
+        .unwrap_err();
+
+        // Narrowing type change.
+        db.try_mutate_and_commit(
+            state.version,
+            vec![alter(AlterTableOperation::AlterColumnType {
+                name: "id".to_string(),
+                arrow_type: DataType::Int32,
+            })],
+        )
+        .await
+        .unwrap_err();
+
+        // Missing column.
+        db.try_mutate_and_commit(
+            state.version,
+            vec![alter(AlterTableOperation::DropColumn {
+                name: "toad".to_string(),
+                if_exists: false,
+            })],
+        )
+        .await
+        .unwrap_err();
+
+        let state = db
+            .try_mutate_and_commit(
+                state.version,
+                vec![
+                    alter(AlterTableOperation::DropColumn {
+                        name: "toad".to_string(),
+                        if_exists: true,
+                    }),
+                    alter(AlterTableOperation::DropColumn {
+                        name: "stars".to_string(),
+                        if_exists: false,
+                    }),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            InternalColumnDefinition::from_tuples([
+                ("id", DataType::Int64, false),
+                ("title", DataType::Utf8, true),
+            ]),
+            table_columns(&state, "castle"),
+        );
+    }
+
+    #[tokio::test]
+    async fn set_materialized_view_refreshed() {
+        let db = new_catalog().await;
+
+        let create = |name: &str, materialized_view| {
+            Mutation::CreateTable(CreateTable {
+                schema: DEFAULT_SCHEMA.to_string(),
+                name: name.to_string(),
+                if_not_exists: false,
+                or_replace: false,
+                options: TableOptionsInternal {
+                    columns: InternalColumnDefinition::from_tuples([("id", DataType::Int32, true)]),
+                    check_constraints: Vec::new(),
+                    unique_constraints: Vec::new(),
+                    auto_compaction: None,
+                    partition_columns: Vec::new(),
+                    materialized_view,
+                },
+            })
+        };
+        let refreshed = |name: &str| {
+            Mutation::AlterTable(AlterTable {
+                schema: DEFAULT_SCHEMA.to_string(),
+                name: name.to_string(),
+                operation: AlterTableOperation::SetMaterializedViewRefreshed { refreshed_at: 42 },
+            })
+        };
+
+        let state = db
+            .try_mutate_and_commit(
+                version(&db).await,
+                vec![
+                    create(
+                        "mv",
+                        Some(MaterializedViewOptions {
+                            sql: "SELECT 1".to_string(),
+                            columns: vec!["id".to_string()],
+                            refresh_interval_secs: None,
+                            refreshed_at: None,
+                        }),
+                    ),
+                    create("plain", None),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let state = db
+            .try_mutate_and_commit(state.version, vec![refreshed("mv")])
+            .await
+            .unwrap();
+        let refreshed_at = state.entries.values().find_map(|ent| match ent {
+            CatalogEntry::Table(TableEntry {
+                meta,
+                options:
+                    TableOptionsV0::Internal(TableOptionsInternal {
+                        materialized_view: Some(view),
+                        ..
+                    }),
+                ..
+            }) if meta.name == "mv" => view.refreshed_at,
+            _ => None,
+        });
+        assert_eq!(Some(42), refreshed_at);
+
+        // Only materialized views can be refreshed.
+        db.try_mutate_and_commit(state.version, vec![refreshed("plain")])
+            .await
+            .unwrap_err();
+    }
+
+    fn sequence(state: &CatalogState, name: &str) -> Option<SequenceEntry> {
+        state.entries.values().find_map(|ent| match ent {
+            CatalogEntry::Sequence(seq) if seq.meta.name == name => Some(seq.clone()),
+            _ => None,
+        })
+    }
+
+    #[tokio::test]
+    async fn sequence_owned_by_table() {
+        let db = new_catalog().await;
+
+        let create_sequence = |name: &str, owned_by| {
+            Mutation::CreateSequence(CreateSequence {
+                schema: DEFAULT_SCHEMA.to_string(),
+                name: name.to_string(),
+                start: 1,
+                increment: 1,
+                min_value: 1,
+                max_value: 3,
+                cycle: false,
+                owned_by,
+                if_not_exists: false,
+                cache: 1,
+            })
+        };
+        let next_values = |count| {
+            Mutation::NextSequenceValues(NextSequenceValues {
+                schema: DEFAULT_SCHEMA.to_string(),
+                name: "t_id_seq".to_string(),
+                count,
+            })
+        };
+        let drop = |name: &str| {
+            Mutation::DropObject(DropObject {
+                schema: DEFAULT_SCHEMA.to_string(),
+                name: name.to_string(),
+                if_exists: false,
+            })
+        };
+
+        let state = db
+            .try_mutate_and_commit(
+                version(&db).await,
+                vec![
+                    Mutation::CreateTable(CreateTable {
+                        schema: DEFAULT_SCHEMA.to_string(),
+                        name: "t".to_string(),
+                        if_not_exists: false,
+                        or_replace: false,
+                        options: TableOptionsInternal {
+                            columns: InternalColumnDefinition::from_tuples([(
+                                "id",
+                                DataType::Int64,
+                                false,
+                            )]),
+                            check_constraints: Vec::new(),
+                            unique_constraints: Vec::new(),
+                            auto_compaction: None,
+                            partition_columns: Vec::new(),
+                            materialized_view: None,
+                        },
+                    }),
+                    create_sequence(
+                        "t_id_seq",
+                        Some(SequenceOwnerColumn {
+                            table: "t".to_string(),
+                            column: "id".to_string(),
+                        }),
+                    ),
+                ],
+            )
+            .await
+            .unwrap();
+
+        // Owning column must exist.
+        db.try_mutate_and_commit(
+            state.version,
+            vec![create_sequence(
+                "bad_seq",
+                Some(SequenceOwnerColumn {
+                    table: "t".to_string(),
+                    column: "missing".to_string(),
+                }),
+            )],
+        )
+        .await
+        .unwrap_err();
+
+        let state = db
+            .try_mutate_and_commit(state.version, vec![next_values(2)])
+            .await
+            .unwrap();
+        let seq = sequence(&state, "t_id_seq").unwrap();
+        assert_eq!((2, true), (seq.last_value, seq.is_called));
+        assert_eq!(Some(vec![3]), seq.next_values(1));
+
+        // Only one value left before hitting the max value.
+        db.try_mutate_and_commit(state.version, vec![next_values(2)])
+            .await
+            .unwrap_err();
+
+        // Sequence can't be dropped while the table exists, but is dropped
+        // along with it.
+        db.try_mutate_and_commit(state.version, vec![drop("t_id_seq")])
+            .await
+            .unwrap_err();
+        let state = db
+            .try_mutate_and_commit(state.version, vec![drop("t")])
+            .await
+            .unwrap();
+        assert_eq!(None, sequence(&state, "t_id_seq"));
+    }
+
+    #[tokio::test]
+    async fn comment_on_objects() {
+        let db = new_catalog().await;
+
+        let state = db
+            .try_mutate_and_commit(
+                version(&db).await,
+                vec![
+                    Mutation::CreateTable(CreateTable {
+                        schema: DEFAULT_SCHEMA.to_string(),
+                        name: "castle".to_string(),
+                        if_not_exists: false,
+                        or_replace: false,
+                        options: TableOptionsInternal {
+                            columns: InternalColumnDefinition::from_tuples([(
+                                "id",
+                                DataType::Int32,
+                                false,
+                            )]),
+                            check_constraints: Vec::new(),
+                            unique_constraints: Vec::new(),
+                            auto_compaction: None,
+                            partition_columns: Vec::new(),
+                            materialized_view: None,
+                        },
+                    }),
+                    Mutation::CreateView(CreateView {
+                        schema: DEFAULT_SCHEMA.to_string(),
+                        name: "tower".to_string(),
+                        sql: "select 1".to_string(),
+                        or_replace: false,
+                        columns: Vec::new(),
+                    }),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let comment = |object_type, name: &str, column: Option<&str>, text: Option<&str>| {
+            Mutation::CommentOn(CommentOn {
+                object_type,
+                schema: match object_type {
+                    EntryType::Table | EntryType::View => Some(DEFAULT_SCHEMA.to_string()),
+                    _ => None,
+                },
+                name: name.to_string(),
+                column: column.map(String::from),
+                comment: text.map(String::from),
+            })
+        };
+        let description = |state: &CatalogState, name: &str| {
+            state
+                .entries
+                .values()
+                .find(|ent| ent.get_meta().name == name)
+                .and_then(|ent| ent.get_meta().description.clone())
+        };
+
+        let state = db
+            .try_mutate_and_commit(
+                state.version,
+                vec![
+                    comment(
+                        EntryType::Table,
+                        "castle",
+                        None,
+                        Some("Where the princess is"),
+                    ),
+                    comment(
+                        EntryType::Table,
+                        "castle",
+                        Some("id"),
+                        Some("Castle number"),
+                    ),
+                    comment(EntryType::View, "tower", None, Some("Tallest tower")),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            Some("Where the princess is".to_string()),
+            description(&state, "castle")
+        );
+        assert_eq!(
+            Some("Tallest tower".to_string()),
+            description(&state, "tower")
+        );
+        assert_eq!(
+            Some("Castle number".to_string()),
+            table_columns(&state, "castle")[0].description
+        );
+
+        // Removing a comment.
+        let state = db
+            .try_mutate_and_commit(
+                state.version,
+                vec![comment(EntryType::Table, "castle", None, None)],
+            )
+            .await
+            .unwrap();
+        assert_eq!(None, description(&state, "castle"));
+
+        // Wrong object type.
+        db.try_mutate_and_commit(
+            state.version,
+            vec![comment(EntryType::Table, "tower", None, Some("view"))],
+        )
+        .await
+        .unwrap_err();
+
+        // Missing column.
+        db.try_mutate_and_commit(
+            state.version,
+            vec![comment(
+                EntryType::Table,
+                "castle",
+                Some("moat"),
+                Some("no moat"),
+            )],
+        )
+        .await
+        .unwrap_err();
+
+        // Builtin objects can't be modified.
+        db.try_mutate_and_commit(
+            state.version,
+            vec![comment(
+                EntryType::Schema,
+                INTERNAL_SCHEMA,
+                None,
+                Some("internal"),
+            )],
+        )
+        .await
+        .unwrap_err();
+    }
 }
@@ -41,6 +41,9 @@ pub enum MetastoreError {
     #[error("Missing credentials: {0}")]
     MissingCredentials(String),
 
+    #[error("Missing role: {0}")]
+    MissingRole(String),
+
     #[error("Missing schema: {0}")]
     MissingNamedSchema(String),
 
@@ -50,6 +53,75 @@ pub enum MetastoreError {
     #[error("Missing entry: {0}")]
     MissingEntry(u32),
 
+    #[error("Invalid privilege {privilege} for {object_type}")]
+    InvalidPrivilege {
+        privilege: protogen::metastore::types::catalog::Privilege,
+        object_type: &'static str,
+    },
+
+    #[error("Missing column '{column}' in table '{table}'")]
+    MissingColumn { table: String, column: String },
+
+    #[error("Column '{column}' already exists in table '{table}'")]
+    DuplicateColumn { table: String, column: String },
+
+    #[error("Cannot change type of column '{column}' from {from} to {to}, only widening type changes are supported")]
+    InvalidColumnTypeChange {
+        column: String,
+        from: datafusion::arrow::datatypes::DataType,
+        to: datafusion::arrow::datatypes::DataType,
+    },
+
+    #[error("Cannot drop the only column of table '{0}'")]
+    CannotDropLastColumn(String),
+
+    #[error("Altering columns is only supported for native tables, '{0}' is not a native table")]
+    AlterColumnsNotSupported(String),
+
+    #[error("Auto compaction is only supported for native tables, '{0}' is not a native table")]
+    AutoCompactionNotSupported(String),
+
+    #[error("'{0}' is not a materialized view")]
+    NotMaterializedView(String),
+
+    #[error("'{0}' is not a sequence")]
+    NotSequence(String),
+
+    #[error("'{name}' is not a {object_type}")]
+    UnexpectedObjectType {
+        name: String,
+        object_type: &'static str,
+    },
+
+    #[error("Comments are not supported for objects of type '{0}'")]
+    CommentNotSupported(&'static str),
+
+    #[error("Column comments are only supported for native tables, '{0}' is not a native table")]
+    ColumnCommentsNotSupported(String),
+
+    #[error("Invalid options for sequence '{name}': {reason}")]
+    InvalidSequenceOptions { name: String, reason: String },
+
+    #[error("Sequence '{0}' reached its limit")]
+    SequenceExhausted(String),
+
+    #[error("Value {value} is out of bounds for sequence '{name}' ({min}..{max})")]
+    SequenceValueOutOfBounds {
+        name: String,
+        value: i64,
+        min: i64,
+        max: i64,
+    },
+
+    #[error(
+        "Cannot drop sequence '{sequence}', column '{column}' of table '{table}' depends on it"
+    )]
+    SequenceOwnedByColumn {
+        sequence: String,
+        table: String,
+        column: String,
+    },
+
     #[error("Tunnel '{tunnel} not supported for {action}'")]
     TunnelNotSupportedForAction {
         tunnel: String,
@@ -121,6 +193,11 @@ impl From<MetastoreError> for tonic::Status {
         // error itself without the user being notified.
         let strat = match &value {
             MetastoreError::VersionMismatch { .. } => ResolveErrorStrategy::FetchCatalogAndRetry,
+            // Another commit was written after the catalog the client mutated
+            // was fetched.
+            MetastoreError::Storage(
+                crate::storage::StorageError::AttemptedOutOfDataCatalogWrite { .. },
+            ) => ResolveErrorStrategy::FetchCatalogAndRetry,
             _ => ResolveErrorStrategy::Unknown,
         };
 
@@ -9,7 +9,7 @@ pub use sqlparser;
 use sqlparser::ast::{self, ColumnDef, Ident, ObjectName};
 use sqlparser::dialect::GenericDialect;
 use sqlparser::keywords::Keyword;
-use sqlparser::parser::{Parser, ParserError, ParserOptions};
+use sqlparser::parser::{IsOptional, Parser, ParserError, ParserOptions};
 use sqlparser::tokenizer::{Token, Tokenizer, Word};
 
 use self::options::{OptionValue, StatementOptions};
@@ -168,7 +168,14 @@ impl fmt::Display for AlterDatabaseStmt {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AlterTableOperationExtension {
-    SetAccessMode { access_mode: Ident },
+    SetAccessMode {
+        access_mode: Ident,
+    },
+    /// Set the number of small files that triggers compaction of the table,
+    /// or disable auto compaction if `None`.
+    SetAutoCompact {
+        min_files: Option<u64>,
+    },
 }
 
 impl fmt::Display for AlterTableOperationExtension {
@@ -177,6 +184,10 @@ impl fmt::Display for AlterTableOperationExtension {
             Self::SetAccessMode { access_mode } => {
                 write!(f, "SET ACCESS_MODE TO {access_mode}")
             }
+            Self::SetAutoCompact {
+                min_files: Some(min_files),
+            } => write!(f, "SET AUTO_COMPACT TO {min_files}"),
+            Self::SetAutoCompact { min_files: None } => write!(f, "SET AUTO_COMPACT TO OFF"),
         }
     }
 }
@@ -366,6 +377,274 @@ impl fmt::Display for DropCredentialsStmt {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateRoleStmt {
+    pub name: Ident,
+    pub if_not_exists: bool,
+}
+
+impl fmt::Display for CreateRoleStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CREATE ROLE ")?;
+        if self.if_not_exists {
+            write!(f, "IF NOT EXISTS ")?;
+        }
+        write!(f, "{}", self.name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropRoleStmt {
+    pub names: Vec<Ident>,
+    pub if_exists: bool,
+}
+
+impl fmt::Display for DropRoleStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DROP ROLE ")?;
+        if self.if_exists {
+            write!(f, "IF EXISTS ")?;
+        }
+        let mut sep = "";
+        for name in self.names.iter() {
+            write!(f, "{sep}{name}")?;
+            sep = ", ";
+        }
+        Ok(())
+    }
+}
+
+/// The object privileges are granted on or revoked from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrantObject {
+    Table(ObjectName),
+    Schema(Ident),
+    Database(Ident),
+    Credentials(Ident),
+    Function(ObjectName),
+}
+
+impl fmt::Display for GrantObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrantObject::Table(name) => write!(f, "TABLE {name}"),
+            GrantObject::Schema(name) => write!(f, "SCHEMA {name}"),
+            GrantObject::Database(name) => write!(f, "DATABASE {name}"),
+            GrantObject::Credentials(name) => write!(f, "CREDENTIALS {name}"),
+            GrantObject::Function(name) => write!(f, "FUNCTION {name}"),
+        }
+    }
+}
+
+/// Write out the privileges for a GRANT or REVOKE statement.
+fn fmt_privileges(f: &mut fmt::Formatter<'_>, privileges: &Option<Vec<Ident>>) -> fmt::Result {
+    match privileges {
+        None => write!(f, "ALL PRIVILEGES"),
+        Some(privileges) => {
+            let mut sep = "";
+            for privilege in privileges.iter() {
+                write!(f, "{sep}{privilege}")?;
+                sep = ", ";
+            }
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrantStmt {
+    /// Privileges to grant. `None` indicates all privileges.
+    pub privileges: Option<Vec<Ident>>,
+    /// Object to grant privileges on.
+    pub object: GrantObject,
+    /// Roles receiving the privileges.
+    pub roles: Vec<Ident>,
+}
+
+impl fmt::Display for GrantStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GRANT ")?;
+        fmt_privileges(f, &self.privileges)?;
+        write!(f, " ON {} TO ", self.object)?;
+        let mut sep = "";
+        for role in self.roles.iter() {
+            write!(f, "{sep}{role}")?;
+            sep = ", ";
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevokeStmt {
+    /// Privileges to revoke. `None` indicates all privileges.
+    pub privileges: Option<Vec<Ident>>,
+    /// Object to revoke privileges from.
+    pub object: GrantObject,
+    /// Roles losing the privileges.
+    pub roles: Vec<Ident>,
+}
+
+impl fmt::Display for RevokeStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "REVOKE ")?;
+        fmt_privileges(f, &self.privileges)?;
+        write!(f, " ON {} FROM ", self.object)?;
+        let mut sep = "";
+        for role in self.roles.iter() {
+            write!(f, "{sep}{role}")?;
+            sep = ", ";
+        }
+        Ok(())
+    }
+}
+
+/// The version a table is restored to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestoreTarget {
+    Version(u64),
+    Timestamp(String),
+}
+
+impl fmt::Display for RestoreTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RestoreTarget::Version(version) => write!(f, "VERSION {version}"),
+            RestoreTarget::Timestamp(timestamp) => write!(
+                f,
+                "TIMESTAMP {}",
+                ast::Value::SingleQuotedString(timestamp.clone())
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestoreTableStmt {
+    pub name: ObjectName,
+    pub target: RestoreTarget,
+}
+
+impl fmt::Display for RestoreTableStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RESTORE TABLE {} TO {}", self.name, self.target)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VacuumStmt {
+    pub name: ObjectName,
+    /// Hours of removed files to keep, overriding the table's retention
+    /// period.
+    pub retain_hours: Option<u64>,
+}
+
+impl fmt::Display for VacuumStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "VACUUM {}", self.name)?;
+        if let Some(hours) = self.retain_hours {
+            write!(f, " RETAIN {hours} HOURS")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptimizeStmt {
+    pub name: ObjectName,
+    /// Columns to Z-order the table's files by. Files are only compacted if
+    /// empty.
+    pub zorder_by: Vec<Ident>,
+}
+
+impl fmt::Display for OptimizeStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "OPTIMIZE {}", self.name)?;
+        if !self.zorder_by.is_empty() {
+            write!(f, " ZORDER BY (")?;
+            let mut sep = "";
+            for col in self.zorder_by.iter() {
+                write!(f, "{sep}{col}")?;
+                sep = ", ";
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefreshMaterializedViewStmt {
+    pub name: ObjectName,
+}
+
+impl fmt::Display for RefreshMaterializedViewStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "REFRESH MATERIALIZED VIEW {}", self.name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropMaterializedViewStmt {
+    pub names: Vec<ObjectName>,
+    pub if_exists: bool,
+}
+
+impl fmt::Display for DropMaterializedViewStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DROP MATERIALIZED VIEW ")?;
+        if self.if_exists {
+            write!(f, "IF EXISTS ")?;
+        }
+        let mut sep = "";
+        for name in self.names.iter() {
+            write!(f, "{sep}{name}")?;
+            sep = ", ";
+        }
+        Ok(())
+    }
+}
+
+/// Object a comment is set on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommentObject {
+    Database(Ident),
+    Schema(Ident),
+    Table(ObjectName),
+    View(ObjectName),
+    /// Column of a table, `table.column`.
+    Column(ObjectName),
+}
+
+impl fmt::Display for CommentObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommentObject::Database(name) => write!(f, "DATABASE {name}"),
+            CommentObject::Schema(name) => write!(f, "SCHEMA {name}"),
+            CommentObject::Table(name) => write!(f, "TABLE {name}"),
+            CommentObject::View(name) => write!(f, "VIEW {name}"),
+            CommentObject::Column(name) => write!(f, "COLUMN {name}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommentStmt {
+    pub object: CommentObject,
+    /// The new comment, `None` removes the comment (`IS NULL`).
+    pub comment: Option<String>,
+}
+
+impl fmt::Display for CommentStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "COMMENT ON {} IS ", self.object)?;
+        match &self.comment {
+            Some(comment) => write!(f, "'{}'", comment.replace('\'', "''")),
+            None => write!(f, "NULL"),
+        }
+    }
+}
+
 /// A source for a COPY TO statement.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CopyToSource {
@@ -396,6 +675,13 @@ pub struct CopyToStmt {
     pub options: StatementOptions,
 }
 
+impl CopyToStmt {
+    /// Whether the data is copied to the client (`COPY .. TO STDOUT`).
+    pub fn is_stdout(&self) -> bool {
+        is_copy_stdio(&self.dest, "STDOUT")
+    }
+}
+
 impl fmt::Display for CopyToStmt {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "COPY {} TO {}", self.source, self.dest)?;
@@ -412,6 +698,55 @@ impl fmt::Display for CopyToStmt {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CopyFromStmt {
+    /// Table to copy the data into.
+    pub table: ObjectName,
+    /// Optional list of table columns the source columns map to.
+    pub columns: Vec<Ident>,
+    /// Source to copy the data from.
+    pub source: Ident,
+    /// Optional format (of the source data).
+    pub format: Option<Ident>,
+    /// Optional credentials (for cloud storage).
+    pub credentials: Option<Ident>,
+    /// COPY FROM specific options.
+    pub options: StatementOptions,
+}
+
+impl CopyFromStmt {
+    /// Whether the data is copied from the client (`COPY .. FROM STDIN`).
+    pub fn is_stdin(&self) -> bool {
+        is_copy_stdio(&self.source, "STDIN")
+    }
+}
+
+impl fmt::Display for CopyFromStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "COPY {}", self.table)?;
+        if !self.columns.is_empty() {
+            write!(f, " (")?;
+            let mut sep = "";
+            for col in self.columns.iter() {
+                write!(f, "{sep}{col}")?;
+                sep = ", ";
+            }
+            write!(f, ")")?;
+        }
+        write!(f, " FROM {}", self.source)?;
+        if let Some(format) = self.format.as_ref() {
+            write!(f, " FORMAT {format}")?;
+        }
+        if let Some(creds) = self.credentials.as_ref() {
+            write!(f, " CREDENTIALS {creds}")?;
+        }
+        if !self.options.is_empty() {
+            write!(f, " {}", self.options)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum StatementWithExtensions {
     /// Statement parsed by `sqlparser`.
@@ -438,6 +773,28 @@ pub enum StatementWithExtensions {
     DropCredentials(DropCredentialsStmt),
     /// Copy To extension.
     CopyTo(CopyToStmt),
+    /// Copy From extension.
+    CopyFrom(CopyFromStmt),
+    /// Create role extension.
+    CreateRole(CreateRoleStmt),
+    /// Drop role extension.
+    DropRole(DropRoleStmt),
+    /// Grant privileges extension.
+    Grant(GrantStmt),
+    /// Revoke privileges extension.
+    Revoke(RevokeStmt),
+    /// Restore table extension.
+    RestoreTable(RestoreTableStmt),
+    /// Vacuum extension.
+    Vacuum(VacuumStmt),
+    /// Optimize extension.
+    Optimize(OptimizeStmt),
+    /// Refresh materialized view extension.
+    RefreshMaterializedView(RefreshMaterializedViewStmt),
+    /// Drop materialized view extension.
+    DropMaterializedView(DropMaterializedViewStmt),
+    /// Comment extension.
+    Comment(CommentStmt),
 }
 
 impl fmt::Display for StatementWithExtensions {
@@ -455,6 +812,17 @@ impl fmt::Display for StatementWithExtensions {
             StatementWithExtensions::CreateCredentials(stmt) => write!(f, "{}", stmt),
             StatementWithExtensions::DropCredentials(stmt) => write!(f, "{}", stmt),
             StatementWithExtensions::CopyTo(stmt) => write!(f, "{}", stmt),
+            StatementWithExtensions::CopyFrom(stmt) => write!(f, "{}", stmt),
+            StatementWithExtensions::CreateRole(stmt) => write!(f, "{}", stmt),
+            StatementWithExtensions::DropRole(stmt) => write!(f, "{}", stmt),
+            StatementWithExtensions::Grant(stmt) => write!(f, "{}", stmt),
+            StatementWithExtensions::Revoke(stmt) => write!(f, "{}", stmt),
+            StatementWithExtensions::RestoreTable(stmt) => write!(f, "{}", stmt),
+            StatementWithExtensions::Vacuum(stmt) => write!(f, "{}", stmt),
+            StatementWithExtensions::Optimize(stmt) => write!(f, "{}", stmt),
+            StatementWithExtensions::RefreshMaterializedView(stmt) => write!(f, "{}", stmt),
+            StatementWithExtensions::DropMaterializedView(stmt) => write!(f, "{}", stmt),
+            StatementWithExtensions::Comment(stmt) => write!(f, "{}", stmt),
         }
     }
 }
@@ -509,6 +877,17 @@ impl<'a> GlareDbParser<'a> {
         Self::parse(sql, Dialect::Prql)
     }
 
+    /// Parse a single SQL expression, e.g. a column default stored in the
+    /// catalog.
+    pub fn parse_expr(sql: &str) -> Result<ast::Expr, ParserError> {
+        let mut parser = GlareDbParser::new(sql, Dialect::Sql)?;
+        let expr = parser.parser.parse_expr()?;
+        if parser.parser.peek_token() != Token::EOF {
+            return parser.expected("end of expression", parser.parser.peek_token().token);
+        }
+        Ok(expr)
+    }
+
     pub fn parse(
         sql: &str,
         dialect: Dialect,
@@ -557,6 +936,22 @@ impl<'a> GlareDbParser<'a> {
                     self.parser.next_token();
                     self.parse_copy()
                 }
+                Keyword::GRANT => {
+                    self.parser.next_token();
+                    self.parse_grant()
+                }
+                Keyword::REVOKE => {
+                    self.parser.next_token();
+                    self.parse_revoke()
+                }
+                Keyword::COMMENT => {
+                    self.parser.next_token();
+                    self.parse_comment()
+                }
+                _ if self.consume_token(&Token::make_keyword("RESTORE")) => self.parse_restore(),
+                _ if self.consume_token(&Token::make_keyword("VACUUM")) => self.parse_vacuum(),
+                _ if self.consume_token(&Token::make_keyword("OPTIMIZE")) => self.parse_optimize(),
+                _ if self.consume_token(&Token::make_keyword("REFRESH")) => self.parse_refresh(),
                 _ => Ok(StatementWithExtensions::Statement(
                     self.parser.parse_statement()?,
                 )),
@@ -593,6 +988,9 @@ impl<'a> GlareDbParser<'a> {
         } else if self.parser.parse_keyword(Keyword::CREDENTIALS) {
             // CREATE CREDENTIALS ...
             self.parse_create_credentials(true, or_replace)
+        } else if !or_replace && self.parser.parse_keyword(Keyword::ROLE) {
+            // CREATE ROLE ...
+            self.parse_create_role()
         } else {
             // Fall back to underlying parser.
 
@@ -638,6 +1036,24 @@ impl<'a> GlareDbParser<'a> {
             CopyToSource::Query(query)
         } else {
             let table_name = self.parser.parse_object_name(false)?;
+
+            // COPY table [(col, ..)] FROM ..
+            let columns = if self.parser.consume_token(&Token::LParen) {
+                let columns = self
+                    .parser
+                    .parse_comma_separated(|parser| parser.parse_identifier(false))?;
+                self.parser.expect_token(&Token::RParen)?;
+                columns
+            } else {
+                Vec::new()
+            };
+            if self.parser.parse_keyword(Keyword::FROM) {
+                return self.parse_copy_from(table_name, columns);
+            }
+            if !columns.is_empty() {
+                return self.expected("FROM", self.parser.peek_token().token);
+            }
+
             CopyToSource::Table(table_name)
         };
 
@@ -645,6 +1061,18 @@ impl<'a> GlareDbParser<'a> {
         self.parser.expect_keyword(Keyword::TO)?;
         let dest = self.parser.parse_identifier(false)?;
 
+        // COPY .. TO STDOUT [WITH] (..)
+        if is_copy_stdio(&dest, "STDOUT") {
+            let (format, options) = self.parse_copy_stdio_options()?;
+            return Ok(StatementWithExtensions::CopyTo(CopyToStmt {
+                source,
+                dest,
+                format,
+                credentials: None,
+                options,
+            }));
+        }
+
         // [FORMAT ..]
         let format = self.parse_data_format()?;
 
@@ -663,6 +1091,135 @@ impl<'a> GlareDbParser<'a> {
         }))
     }
 
+    /// Parse the remainder of a COPY FROM statement, after the FROM keyword.
+    fn parse_copy_from(
+        &mut self,
+        table: ObjectName,
+        columns: Vec<Ident>,
+    ) -> Result<StatementWithExtensions, ParserError> {
+        // FROM 'source'
+        let source = self.parser.parse_identifier(false)?;
+
+        // COPY .. FROM STDIN [WITH] (..)
+        if is_copy_stdio(&source, "STDIN") {
+            let (format, options) = self.parse_copy_stdio_options()?;
+            return Ok(StatementWithExtensions::CopyFrom(CopyFromStmt {
+                table,
+                columns,
+                source,
+                format,
+                credentials: None,
+                options,
+            }));
+        }
+
+        // [FORMAT ..]
+        let format = self.parse_data_format()?;
+
+        // [CREDENTIALS ..]
+        let credentials = self.parse_connection_credentials()?;
+
+        // OPTIONS (..)
+        let options = self.parse_options()?;
+
+        Ok(StatementWithExtensions::CopyFrom(CopyFromStmt {
+            table,
+            columns,
+            source,
+            format,
+            credentials,
+            options,
+        }))
+    }
+
+    /// Parse the options for COPY to or from the client.
+    ///
+    /// Along with our own `FORMAT .. OPTIONS (..)` syntax, this accepts the
+    /// postgres `[WITH] (FORMAT csv, HEADER, ..)` syntax and the older
+    /// `[WITH] CSV HEADER ..` syntax since that's what most postgres tools
+    /// will send.
+    fn parse_copy_stdio_options(
+        &mut self,
+    ) -> Result<(Option<Ident>, StatementOptions), ParserError> {
+        let peeked = self.parser.peek_token().to_string().to_uppercase();
+        if peeked == "FORMAT" || peeked == "OPTIONS" {
+            let format = self.parse_data_format()?;
+            let options = self.parse_options()?;
+            return Ok((format, options));
+        }
+
+        let _ = self.parser.parse_keyword(Keyword::WITH);
+
+        let mut format = None;
+        let mut options = BTreeMap::new();
+
+        if self.parser.consume_token(&Token::LParen) {
+            loop {
+                let key = self.parser.parse_identifier(false)?.value.to_lowercase();
+                if key == "format" {
+                    let opt = self.parser.parse_identifier(false)?;
+                    validate_ident(&opt)?;
+                    format = Some(opt);
+                } else {
+                    // Options without a value (e.g. `HEADER`) are booleans.
+                    let value = match self.parser.peek_token().token {
+                        Token::Comma | Token::RParen => OptionValue::Boolean(true),
+                        _ => self.parse_copy_stdio_option_value()?,
+                    };
+                    options.insert(key, value);
+                }
+
+                if self.parser.consume_token(&Token::RParen) {
+                    break;
+                }
+                self.parser.expect_token(&Token::Comma)?;
+            }
+        } else {
+            loop {
+                let word = self.parser.peek_token().to_string().to_uppercase();
+                match word.as_str() {
+                    "BINARY" | "CSV" => {
+                        let opt = self.parser.parse_identifier(false)?;
+                        format = Some(Ident::new(opt.value.to_lowercase()));
+                    }
+                    "HEADER" => {
+                        let _ = self.parser.next_token();
+                        options.insert("header".to_string(), OptionValue::Boolean(true));
+                    }
+                    "DELIMITER" | "NULL" | "QUOTE" | "ESCAPE" => {
+                        let _ = self.parser.next_token();
+                        let _ = self.parser.parse_keyword(Keyword::AS);
+                        let value = self.parse_copy_stdio_option_value()?;
+                        options.insert(word.to_lowercase(), value);
+                    }
+                    _ => break,
+                }
+            }
+        }
+
+        Ok((format, StatementOptions::new(options)))
+    }
+
+    /// Parse a value for a postgres style COPY option, additionally accepting
+    /// `ON`/`OFF` booleans and escaped strings (e.g. `E'\t'`).
+    fn parse_copy_stdio_option_value(&mut self) -> Result<OptionValue, ParserError> {
+        match self.parser.peek_token().token {
+            Token::EscapedStringLiteral(s) => {
+                let _ = self.parser.next_token();
+                Ok(OptionValue::QuotedLiteral(s))
+            }
+            Token::Word(Word { value, .. }) if value.eq_ignore_ascii_case("on") => {
+                let _ = self.parser.next_token();
+                Ok(OptionValue::Boolean(true))
+            }
+            Token::Word(Word { value, .. }) if value.eq_ignore_ascii_case("off") => {
+                let _ = self.parser.next_token();
+                Ok(OptionValue::Boolean(false))
+            }
+            _ => self.parse_options_value(),
+        }
+    }
+
     /// Report unexpected token.
     fn expected<T>(&self, expected: &str, found: Token) -> Result<T, ParserError> {
         Err(ParserError::ParserError(format!(
@@ -982,6 +1539,15 @@ impl<'a> GlareDbParser<'a> {
         } else if self.consume_token(&Token::make_keyword("CREDENTIALS")) {
             // DROP CREDENTIALS ...
             self.parse_drop_credentials()
+        } else if self.parser.parse_keyword(Keyword::ROLE) {
+            // DROP ROLE ...
+            self.parse_drop_role()
+        } else if self
+            .parser
+            .parse_keywords(&[Keyword::MATERIALIZED, Keyword::VIEW])
+        {
+            // DROP MATERIALIZED VIEW ...
+            self.parse_drop_materialized_view()
         } else {
             // Fall back to underlying parser.
             Ok(StatementWithExtensions::Statement(
@@ -1040,6 +1606,271 @@ impl<'a> GlareDbParser<'a> {
         ))
     }
 
+    fn parse_drop_materialized_view(&mut self) -> Result<StatementWithExtensions, ParserError> {
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+
+        let names = self
+            .parser
+            .parse_comma_separated(|parser| parser.parse_object_name(false))?;
+
+        for name in names.iter() {
+            validate_object_name(name)?;
+        }
+
+        Ok(StatementWithExtensions::DropMaterializedView(
+            DropMaterializedViewStmt { names, if_exists },
+        ))
+    }
+
+    fn parse_create_role(&mut self) -> Result<StatementWithExtensions, ParserError> {
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+
+        let name = self.parser.parse_identifier(false)?;
+        validate_ident(&name)?;
+
+        Ok(StatementWithExtensions::CreateRole(CreateRoleStmt {
+            name,
+            if_not_exists,
+        }))
+    }
+
+    fn parse_drop_role(&mut self) -> Result<StatementWithExtensions, ParserError> {
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+
+        let names = self
+            .parser
+            .parse_comma_separated(|parser| parser.parse_identifier(false))?;
+
+        for name in names.iter() {
+            validate_ident(name)?;
+        }
+
+        Ok(StatementWithExtensions::DropRole(DropRoleStmt {
+            names,
+            if_exists,
+        }))
+    }
+
+    /// Parse a GRANT statement.
+    ///
+    /// GRANT { privilege [, ...] | ALL [PRIVILEGES] } ON object TO role [, ...]
+    fn parse_grant(&mut self) -> Result<StatementWithExtensions, ParserError> {
+        let (privileges, object) = self.parse_privileges_on_object()?;
+        self.parser.expect_keyword(Keyword::TO)?;
+        let roles = self.parse_grant_roles()?;
+
+        Ok(StatementWithExtensions::Grant(GrantStmt {
+            privileges,
+            object,
+            roles,
+        }))
+    }
+
+    /// Parse a REVOKE statement.
+    ///
+    /// REVOKE { privilege [, ...] | ALL [PRIVILEGES] } ON object FROM role [, ...]
+    fn parse_revoke(&mut self) -> Result<StatementWithExtensions, ParserError> {
+        let (privileges, object) = self.parse_privileges_on_object()?;
+        self.parser.expect_keyword(Keyword::FROM)?;
+        let roles = self.parse_grant_roles()?;
+
+        Ok(StatementWithExtensions::Revoke(RevokeStmt {
+            privileges,
+            object,
+            roles,
+        }))
+    }
+
+    /// Parse a RESTORE statement.
+    ///
+    /// RESTORE TABLE name TO { VERSION version | TIMESTAMP 'timestamp' }
+    fn parse_restore(&mut self) -> Result<StatementWithExtensions, ParserError> {
+        self.parser.expect_keyword(Keyword::TABLE)?;
+        let name = self.parser.parse_object_name(false)?;
+        validate_object_name(&name)?;
+        self.parser.expect_keyword(Keyword::TO)?;
+
+        let target = if self.consume_token(&Token::make_keyword("VERSION")) {
+            RestoreTarget::Version(self.parser.parse_literal_uint()?)
+        } else if self.parser.parse_keyword(Keyword::TIMESTAMP) {
+            RestoreTarget::Timestamp(self.parser.parse_literal_string()?)
+        } else {
+            let next = self.parser.peek_token().token;
+            return self.expected("VERSION or TIMESTAMP", next);
+        };
+
+        Ok(StatementWithExtensions::RestoreTable(RestoreTableStmt {
+            name,
+            target,
+        }))
+    }
+
+    /// Parse a VACUUM statement.
+    ///
+    /// VACUUM name [ RETAIN hours HOURS ]
+    fn parse_vacuum(&mut self) -> Result<StatementWithExtensions, ParserError> {
+        let name = self.parser.parse_object_name(false)?;
+        validate_object_name(&name)?;
+
+        let retain_hours = if self.consume_token(&Token::make_keyword("RETAIN")) {
+            let hours = self.parser.parse_literal_uint()?;
+            self.expect_token(&Token::make_keyword("HOURS"))?;
+            Some(hours)
+        } else {
+            None
+        };
+
+        Ok(StatementWithExtensions::Vacuum(VacuumStmt {
+            name,
+            retain_hours,
+        }))
+    }
+
+    /// Parse an OPTIMIZE statement.
+    ///
+    /// OPTIMIZE name [ ZORDER BY ( column [, ...] ) ]
+    fn parse_optimize(&mut self) -> Result<StatementWithExtensions, ParserError> {
+        let name = self.parser.parse_object_name(false)?;
+        validate_object_name(&name)?;
+
+        let zorder_by = if self.consume_token(&Token::make_keyword("ZORDER")) {
+            self.parser.expect_keyword(Keyword::BY)?;
+            self.parser
+                .parse_parenthesized_column_list(IsOptional::Mandatory, false)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(StatementWithExtensions::Optimize(OptimizeStmt {
+            name,
+            zorder_by,
+        }))
+    }
+
+    /// Parse a REFRESH statement.
+    ///
+    /// REFRESH MATERIALIZED VIEW name
+    fn parse_refresh(&mut self) -> Result<StatementWithExtensions, ParserError> {
+        self.parser
+            .expect_keywords(&[Keyword::MATERIALIZED, Keyword::VIEW])?;
+        let name = self.parser.parse_object_name(false)?;
+        validate_object_name(&name)?;
+
+        Ok(StatementWithExtensions::RefreshMaterializedView(
+            RefreshMaterializedViewStmt { name },
+        ))
+    }
+
+    /// Parse a COMMENT statement.
+    ///
+    /// COMMENT ON { DATABASE | SCHEMA | TABLE | VIEW | COLUMN } name IS { 'text' | NULL }
+    fn parse_comment(&mut self) -> Result<StatementWithExtensions, ParserError> {
+        self.parser.expect_keyword(Keyword::ON)?;
+
+        let object = if self.parser.parse_keyword(Keyword::DATABASE) {
+            CommentObject::Database(self.parser.parse_identifier(false)?)
+        } else if self.parser.parse_keyword(Keyword::SCHEMA) {
+            CommentObject::Schema(self.parser.parse_identifier(false)?)
+        } else if self.parser.parse_keyword(Keyword::TABLE) {
+            let name = self.parser.parse_object_name(false)?;
+            validate_object_name(&name)?;
+            CommentObject::Table(name)
+        } else if self.parser.parse_keyword(Keyword::VIEW) {
+            let name = self.parser.parse_object_name(false)?;
+            validate_object_name(&name)?;
+            CommentObject::View(name)
+        } else if self.parser.parse_keyword(Keyword::COLUMN) {
+            let name = self.parser.parse_object_name(false)?;
+            if !(2..=3).contains(&name.0.len()) {
+                return Err(ParserError::ParserError(format!(
+                    "Expected a column reference in the form [schema.]table.column, found: {name}"
+                )));
+            }
+            CommentObject::Column(name)
+        } else {
+            return self.expected(
+                "DATABASE, SCHEMA, TABLE, VIEW or COLUMN",
+                self.parser.peek_token().token,
+            );
+        };
+
+        self.parser.expect_keyword(Keyword::IS)?;
+        let comment = if self.parser.parse_keyword(Keyword::NULL) {
+            None
+        } else {
+            Some(self.parser.parse_literal_string()?)
+        };
+
+        Ok(StatementWithExtensions::Comment(CommentStmt {
+            object,
+            comment,
+        }))
+    }
+
+    /// Parse the `privileges ON object` portion of GRANT and REVOKE.
+    ///
+    /// The object may be prefixed with its type (TABLE, SCHEMA, [EXTERNAL]
+    /// DATABASE, CREDENTIALS or FUNCTION), and defaults to a table otherwise.
+    fn parse_privileges_on_object(
+        &mut self,
+    ) -> Result<(Option<Vec<Ident>>, GrantObject), ParserError> {
+        let privileges = if self.parser.parse_keyword(Keyword::ALL) {
+            let _ = self.parser.parse_keyword(Keyword::PRIVILEGES);
+            None
+        } else {
+            let privileges =
+                self.parser
+                    .parse_comma_separated(|parser| match parser.next_token().token {
+                        Token::Word(w) => Ok(Ident::new(w.value.to_uppercase())),
+                        other => Err(ParserError::ParserError(format!(
+                            "Expected a privilege, found: {other}"
+                        ))),
+                    })?;
+            Some(privileges)
+        };
+
+        self.parser.expect_keyword(Keyword::ON)?;
+
+        let object = if self.parser.parse_keyword(Keyword::SCHEMA) {
+            GrantObject::Schema(self.parser.parse_identifier(false)?)
+        } else if self.parser.parse_keyword(Keyword::DATABASE)
+            || self
+                .parser
+                .parse_keywords(&[Keyword::EXTERNAL, Keyword::DATABASE])
+        {
+            GrantObject::Database(self.parser.parse_identifier(false)?)
+        } else if self.parser.parse_keyword(Keyword::CREDENTIALS)
+            || self.consume_token(&Token::make_keyword("CREDENTIAL"))
+        {
+            GrantObject::Credentials(self.parser.parse_identifier(false)?)
+        } else if self.parser.parse_keyword(Keyword::FUNCTION) {
+            let name = self.parser.parse_object_name(false)?;
+            validate_object_name(&name)?;
+            GrantObject::Function(name)
+        } else {
+            let _ = self.parser.parse_keyword(Keyword::TABLE);
+            let name = self.parser.parse_object_name(false)?;
+            validate_object_name(&name)?;
+            GrantObject::Table(name)
+        };
+
+        Ok((privileges, object))
+    }
+
+    fn parse_grant_roles(&mut self) -> Result<Vec<Ident>, ParserError> {
+        let roles = self
+            .parser
+            .parse_comma_separated(|parser| parser.parse_identifier(false))?;
+
+        for role in roles.iter() {
+            validate_ident(role)?;
+        }
+
+        Ok(roles)
+    }
+
     fn parse_alter_database(&mut self) -> Result<StatementWithExtensions, ParserError> {
         let name = self.parser.parse_identifier(false)?;
         validate_ident(&name)?;
@@ -1073,11 +1904,22 @@ impl<'a> GlareDbParser<'a> {
         let name = self.parser.parse_object_name(false)?;
 
         let operation = if self.parser.parse_keyword(Keyword::SET) {
-            self.expect_token(&Token::make_keyword("ACCESS_MODE"))?;
-            self.expect_token(&Token::make_keyword("TO"))?;
+            if self.consume_token(&Token::make_keyword("AUTO_COMPACT")) {
+                self.expect_token(&Token::make_keyword("TO"))?;
+
+                let min_files = if self.consume_token(&Token::make_keyword("OFF")) {
+                    None
+                } else {
+                    Some(self.parser.parse_literal_uint()?)
+                };
+                AlterTableOperationExtension::SetAutoCompact { min_files }
+            } else {
+                self.expect_token(&Token::make_keyword("ACCESS_MODE"))?;
+                self.expect_token(&Token::make_keyword("TO"))?;
 
-            let access_mode = self.parser.parse_identifier(false)?;
-            AlterTableOperationExtension::SetAccessMode { access_mode }
+                let access_mode = self.parser.parse_identifier(false)?;
+                AlterTableOperationExtension::SetAccessMode { access_mode }
+            }
         } else {
             let operations = self
                 .parser
@@ -1125,6 +1967,12 @@ impl<'a> GlareDbParser<'a> {
     }
 }
 
+/// Check if a COPY source or destination refers to the client (e.g. `STDIN`)
+/// instead of a location.
+fn is_copy_stdio(ident: &Ident, stdio: &str) -> bool {
+    ident.quote_style.is_none() && ident.value.eq_ignore_ascii_case(stdio)
+}
+
 pub fn validate_ident(ident: &ast::Ident) -> Result<()> {
     pg_validate_object_name(&ident.value)
 }
@@ -1429,6 +2277,215 @@ mod tests {
         }
     }
 
+    #[test]
+    fn copy_from_roundtrips() {
+        let test_cases = [
+            "COPY table FROM 's3://bucket/data.csv'",
+            "COPY table (a, b) FROM 's3://bucket/*.parquet'",
+            "COPY table FROM 'data.csv' OPTIONS (header = false, max_errors = 10)",
+            "COPY table FROM 'gs://bucket/data' FORMAT parquet",
+            "COPY table FROM 's3://bucket/data.json' FORMAT json CREDENTIALS aws_creds",
+            "COPY table FROM s3 OPTIONS (location = 'data.csv')",
+        ];
+
+        for test_case in test_cases {
+            let stmt = GlareDbParser::parse_sql(test_case)
+                .unwrap()
+                .pop_front()
+                .unwrap();
+            assert!(matches!(stmt, StatementWithExtensions::CopyFrom(_)));
+            assert_eq!(test_case, stmt.to_string().as_str());
+        }
+    }
+
+    #[test]
+    fn copy_stdio_parse() {
+        let test_cases = [
+            ("COPY t FROM STDIN", "COPY t FROM STDIN"),
+            (
+                "COPY t (a, b) FROM stdin WITH (FORMAT csv, HEADER, DELIMITER ';')",
+                "COPY t (a, b) FROM stdin FORMAT csv OPTIONS (delimiter = ';', header = TRUE)",
+            ),
+            (
+                "COPY t FROM STDIN WITH CSV HEADER NULL AS 'null'",
+                "COPY t FROM STDIN FORMAT csv OPTIONS (header = TRUE, null = 'null')",
+            ),
+            (
+                "COPY t FROM STDIN (FORMAT binary)",
+                "COPY t FROM STDIN FORMAT binary",
+            ),
+            (
+                "COPY t FROM STDIN FORMAT csv OPTIONS (header = false)",
+                "COPY t FROM STDIN FORMAT csv OPTIONS (header = FALSE)",
+            ),
+            ("COPY t TO STDOUT", "COPY t TO STDOUT"),
+            (
+                "COPY (SELECT 1) TO STDOUT (FORMAT csv, HEADER off)",
+                "COPY (SELECT 1) TO STDOUT FORMAT csv OPTIONS (header = FALSE)",
+            ),
+            ("COPY t TO STDOUT BINARY", "COPY t TO STDOUT FORMAT binary"),
+        ];
+
+        for (sql, expected) in test_cases {
+            let stmt = GlareDbParser::parse_sql(sql).unwrap().pop_front().unwrap();
+            assert_eq!(expected, stmt.to_string().as_str(), "sql: {sql}");
+        }
+    }
+
+    #[test]
+    fn grant_revoke_roundtrip() {
+        let test_cases = [
+            ("CREATE ROLE analyst", "CREATE ROLE analyst"),
+            (
+                "CREATE ROLE IF NOT EXISTS analyst",
+                "CREATE ROLE IF NOT EXISTS analyst",
+            ),
+            ("DROP ROLE IF EXISTS a, b", "DROP ROLE IF EXISTS a, b"),
+            (
+                "GRANT select, insert ON t TO analyst",
+                "GRANT SELECT, INSERT ON TABLE t TO analyst",
+            ),
+            (
+                "GRANT SELECT ON TABLE s.t TO a, b",
+                "GRANT SELECT ON TABLE s.t TO a, b",
+            ),
+            (
+                "GRANT USAGE ON SCHEMA s TO analyst",
+                "GRANT USAGE ON SCHEMA s TO analyst",
+            ),
+            (
+                "GRANT USAGE ON EXTERNAL DATABASE pg TO analyst",
+                "GRANT USAGE ON DATABASE pg TO analyst",
+            ),
+            (
+                "GRANT ALL ON CREDENTIALS creds TO analyst",
+                "GRANT ALL PRIVILEGES ON CREDENTIALS creds TO analyst",
+            ),
+            (
+                "REVOKE ALL PRIVILEGES ON t FROM analyst",
+                "REVOKE ALL PRIVILEGES ON TABLE t FROM analyst",
+            ),
+            (
+                "REVOKE usage ON DATABASE pg FROM analyst",
+                "REVOKE USAGE ON DATABASE pg FROM analyst",
+            ),
+            (
+                "GRANT execute ON FUNCTION read_parquet TO analyst",
+                "GRANT EXECUTE ON FUNCTION read_parquet TO analyst",
+            ),
+            (
+                "REVOKE EXECUTE ON FUNCTION public.read_csv FROM analyst",
+                "REVOKE EXECUTE ON FUNCTION public.read_csv FROM analyst",
+            ),
+        ];
+
+        for (sql, expected) in test_cases {
+            let stmt = GlareDbParser::parse_sql(sql).unwrap().pop_front().unwrap();
+            assert_eq!(expected, stmt.to_string().as_str(), "sql: {sql}");
+        }
+    }
+
+    #[test]
+    fn restore_table_roundtrip() {
+        let test_cases = [
+            (
+                "RESTORE TABLE t TO VERSION 3",
+                "RESTORE TABLE t TO VERSION 3",
+            ),
+            (
+                "restore table s.t to timestamp '2024-01-01 00:00:00'",
+                "RESTORE TABLE s.t TO TIMESTAMP '2024-01-01 00:00:00'",
+            ),
+        ];
+
+        for (sql, expected) in test_cases {
+            let stmt = GlareDbParser::parse_sql(sql).unwrap().pop_front().unwrap();
+            assert_eq!(expected, stmt.to_string().as_str(), "sql: {sql}");
+        }
+
+        GlareDbParser::parse_sql("RESTORE TABLE t TO 3").unwrap_err();
+    }
+
+    #[test]
+    fn comment_roundtrip() {
+        let test_cases = [
+            (
+                "COMMENT ON TABLE t IS 'my table'",
+                "COMMENT ON TABLE t IS 'my table'",
+            ),
+            (
+                "comment on column s.t.c is 'it''s a column'",
+                "COMMENT ON COLUMN s.t.c IS 'it''s a column'",
+            ),
+            ("comment on view v is null", "COMMENT ON VIEW v IS NULL"),
+            ("COMMENT ON SCHEMA s IS ''", "COMMENT ON SCHEMA s IS ''"),
+            (
+                "COMMENT ON DATABASE db IS 'external'",
+                "COMMENT ON DATABASE db IS 'external'",
+            ),
+        ];
+
+        for (sql, expected) in test_cases {
+            let stmt = GlareDbParser::parse_sql(sql).unwrap().pop_front().unwrap();
+            assert_eq!(expected, stmt.to_string().as_str(), "sql: {sql}");
+        }
+
+        GlareDbParser::parse_sql("COMMENT ON COLUMN c IS 'no table'").unwrap_err();
+        GlareDbParser::parse_sql("COMMENT ON FUNCTION f IS 'function'").unwrap_err();
+    }
+
+    #[test]
+    fn maintenance_roundtrip() {
+        let test_cases = [
+            ("VACUUM t", "VACUUM t"),
+            ("vacuum s.t retain 24 hours", "VACUUM s.t RETAIN 24 HOURS"),
+            ("OPTIMIZE t", "OPTIMIZE t"),
+            ("optimize t zorder by (a, b)", "OPTIMIZE t ZORDER BY (a, b)"),
+            (
+                "ALTER TABLE t SET AUTO_COMPACT TO 10",
+                "ALTER TABLE t SET AUTO_COMPACT TO 10",
+            ),
+            (
+                "alter table t set auto_compact to off",
+                "ALTER TABLE t SET AUTO_COMPACT TO OFF",
+            ),
+        ];
+
+        for (sql, expected) in test_cases {
+            let stmt = GlareDbParser::parse_sql(sql).unwrap().pop_front().unwrap();
+            assert_eq!(expected, stmt.to_string().as_str(), "sql: {sql}");
+        }
+
+        GlareDbParser::parse_sql("VACUUM t RETAIN 24").unwrap_err();
+        GlareDbParser::parse_sql("OPTIMIZE t ZORDER BY a").unwrap_err();
+    }
+
+    #[test]
+    fn materialized_view_roundtrip() {
+        let test_cases = [
+            (
+                "REFRESH MATERIALIZED VIEW mv",
+                "REFRESH MATERIALIZED VIEW mv",
+            ),
+            (
+                "refresh materialized view s.mv",
+                "REFRESH MATERIALIZED VIEW s.mv",
+            ),
+            ("DROP MATERIALIZED VIEW mv", "DROP MATERIALIZED VIEW mv"),
+            (
+                "drop materialized view if exists s.mv1, mv2",
+                "DROP MATERIALIZED VIEW IF EXISTS s.mv1, mv2",
+            ),
+        ];
+
+        for (sql, expected) in test_cases {
+            let stmt = GlareDbParser::parse_sql(sql).unwrap().pop_front().unwrap();
+            assert_eq!(expected, stmt.to_string().as_str(), "sql: {sql}");
+        }
+
+        GlareDbParser::parse_sql("REFRESH VIEW mv").unwrap_err();
+    }
+
     #[test]
     fn options_parse() {
         let mut options = BTreeMap::new();
@@ -1545,4 +2602,13 @@ mod tests {
             assert_eq!(opts, expected_opts);
         }
     }
+
+    #[test]
+    fn parse_expr_roundtrip() {
+        let expr = GlareDbParser::parse_expr("price > 0 AND name <> ''").unwrap();
+        assert_eq!("price > 0 AND name <> ''", expr.to_string());
+
+        GlareDbParser::parse_expr("1 + 1; DROP TABLE t").unwrap_err();
+        GlareDbParser::parse_expr("").unwrap_err();
+    }
 }
@@ -70,10 +70,38 @@ impl TryFrom<(char, Message)> for SerializedMessage {
                     tag: msg.tag()?.to_string(),
                 })?,
             ),
+            Message::CopyInResponse(msg) => (
+                "CopyInResponse",
+                serde_json::to_string(&CopyResponse {
+                    format: msg.format(),
+                    column_formats: msg.column_formats().collect()?,
+                })?,
+            ),
+            Message::CopyOutResponse(msg) => (
+                "CopyOutResponse",
+                serde_json::to_string(&CopyResponse {
+                    format: msg.format(),
+                    column_formats: msg.column_formats().collect()?,
+                })?,
+            ),
+            Message::CopyData(msg) => (
+                "CopyData",
+                serde_json::to_string(&CopyData {
+                    data: String::from_utf8_lossy(msg.data()).to_string(), // TODO: Print raw bytes instead.
+                })?,
+            ),
+            Message::ParameterDescription(msg) => (
+                "ParameterDescription",
+                serde_json::to_string(&ParameterDescription {
+                    parameters: msg.parameters().collect()?,
+                })?,
+            ),
+            Message::CopyDone => ("CopyDone", String::new()),
             Message::ParseComplete => ("ParseComplete", String::new()),
             Message::BindComplete => ("BindComplete", String::new()),
             Message::CloseComplete => ("CloseComplete", String::new()),
             Message::NoData => ("NoData", String::new()),
+            Message::PortalSuspended => ("PortalSuspended", String::new()),
             Message::EmptyQueryResponse => ("EmptyQueryResponse", String::new()),
             Message::ErrorResponse(msg) => (
                 "ErrorResponse",
@@ -113,6 +141,8 @@ pub struct Query {
 pub struct Parse {
     pub name: Option<String>,
     pub query: String,
+    /// Parameter type OIDs, zero for unspecified.
+    pub param_types: Option<Vec<u32>>,
 }
 
 #[derive(Deserialize)]
@@ -141,12 +171,23 @@ pub struct ClosePortal {
 
 #[derive(Deserialize)]
 pub struct Describe {
-    #[allow(dead_code)]
+    /// "S" to describe a statement (default), "P" for a portal.
     pub variant: Option<String>,
-    #[allow(dead_code)]
     pub name: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct CopyFail {
+    pub message: String,
+}
+
+// Frontend and backend messages.
+
+#[derive(Serialize, Deserialize)]
+pub struct CopyData {
+    pub data: String,
+}
+
 // Backend messages.
 
 #[derive(Serialize)]
@@ -188,3 +229,9 @@ pub struct ErrorResponse {
 pub struct NoticeResponse {
     pub fields: Vec<String>,
 }
+
+#[derive(Serialize)]
+pub struct CopyResponse {
+    pub format: u8,
+    pub column_formats: Vec<u16>,
+}
@@ -13,6 +13,9 @@ use crate::messages::{
     Bind,
     ClosePortal,
     CloseStatement,
+    CopyData,
+    CopyFail,
+    Describe,
     Execute,
     Parse,
     Query,
@@ -80,7 +83,22 @@ fn run_send(
             }
             "Parse" => {
                 let val: Parse = serde_json::from_str(json)?;
-                frontend::parse(&val.name.unwrap_or_default(), &val.query, Vec::new(), buf)?;
+                frontend::parse(
+                    &val.name.unwrap_or_default(),
+                    &val.query,
+                    val.param_types.unwrap_or_default(),
+                    buf,
+                )?;
+                Ok(())
+            }
+            "Describe" => {
+                let val: Describe = serde_json::from_str(json)?;
+                let variant = match val.variant.as_deref() {
+                    None | Some("S") => b'S',
+                    Some("P") => b'P',
+                    Some(other) => return Err(anyhow!("invalid describe variant: {other}")),
+                };
+                frontend::describe(variant, &val.name.unwrap_or_default(), buf)?;
                 Ok(())
             }
             "Bind" => {
@@ -126,6 +144,20 @@ fn run_send(
                 frontend::sync(buf);
                 Ok(())
             }
+            "CopyData" => {
+                let val: CopyData = serde_json::from_str(json)?;
+                frontend::copy_data(val.data.as_bytes())?.write(buf);
+                Ok(())
+            }
+            "CopyDone" => {
+                frontend::copy_done(buf);
+                Ok(())
+            }
+            "CopyFail" => {
+                let val: CopyFail = serde_json::from_str(json)?;
+                frontend::copy_fail(&val.message, buf)?;
+                Ok(())
+            }
             unknown => panic!("unknown type: {}", unknown),
         })
         .unwrap();
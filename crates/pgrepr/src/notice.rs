@@ -14,11 +14,38 @@ pub enum SqlState {
     // Class 01 — Warning
     Warning,
 
+    // Class 08 — Connection Exception
+    ProtocolViolation,
+
     // Class 0A — Feature Not Supported
     FeatureNotSupported,
 
+    // Class 21 — Cardinality Violation
+    CardinalityViolation,
+
+    // Class 22 — Data Exception
+    BadCopyFileFormat,
+    InvalidTextRepresentation,
+
+    // Class 23 — Integrity Constraint Violation
+    NotNullViolation,
+    UniqueViolation,
+    CheckViolation,
+
+    // Class 25 — Invalid Transaction State
+    ActiveSqlTransaction,
+    NoActiveSqlTransaction,
+    InFailedSqlTransaction,
+
+    // Class 28 — Invalid Authorization Specification
+    InvalidPassword,
+
     // Class 42 — Syntax Error or Access Rule Violation
     SyntaxError,
+    InsufficientPrivilege,
+
+    // Class 57 — Operator Intervention
+    QueryCanceled,
 
     // Class XX — Internal Error
     InternalError,
@@ -29,8 +56,21 @@ impl SqlState {
         match self {
             SqlState::Successful => "00000",
             SqlState::Warning => "01000",
+            SqlState::ProtocolViolation => "08P01",
             SqlState::FeatureNotSupported => "0A000",
+            SqlState::CardinalityViolation => "21000",
+            SqlState::BadCopyFileFormat => "22P04",
+            SqlState::InvalidTextRepresentation => "22P02",
+            SqlState::NotNullViolation => "23502",
+            SqlState::UniqueViolation => "23505",
+            SqlState::CheckViolation => "23514",
+            SqlState::ActiveSqlTransaction => "25001",
+            SqlState::NoActiveSqlTransaction => "25P01",
+            SqlState::InFailedSqlTransaction => "25P02",
+            SqlState::InvalidPassword => "28P01",
             SqlState::SyntaxError => "42601",
+            SqlState::InsufficientPrivilege => "42501",
+            SqlState::QueryCanceled => "57014",
             SqlState::InternalError => "XX000",
         }
     }
@@ -1,5 +1,7 @@
 use std::str::FromStr;
 
+use tokio_postgres::types::{FromSql, Type as PgType};
+
 use crate::error::{PgReprError, Result};
 
 /// Reader defines the interface for the different kinds of values that can be
@@ -59,6 +61,45 @@ impl Reader for TextReader {
     }
 }
 
+#[derive(Debug)]
+pub struct BinaryReader;
+
+impl BinaryReader {
+    fn from_sql<'a, T: FromSql<'a>>(ty: &PgType, buf: &'a [u8]) -> Result<T> {
+        T::from_sql(ty, buf).map_err(PgReprError::ParseError)
+    }
+}
+
+impl Reader for BinaryReader {
+    fn read_bool(buf: &[u8]) -> Result<bool> {
+        Self::from_sql(&PgType::BOOL, buf)
+    }
+
+    fn read_int2(buf: &[u8]) -> Result<i16> {
+        Self::from_sql(&PgType::INT2, buf)
+    }
+
+    fn read_int4(buf: &[u8]) -> Result<i32> {
+        Self::from_sql(&PgType::INT4, buf)
+    }
+
+    fn read_int8(buf: &[u8]) -> Result<i64> {
+        Self::from_sql(&PgType::INT8, buf)
+    }
+
+    fn read_float4(buf: &[u8]) -> Result<f32> {
+        Self::from_sql(&PgType::FLOAT4, buf)
+    }
+
+    fn read_float8(buf: &[u8]) -> Result<f64> {
+        Self::from_sql(&PgType::FLOAT8, buf)
+    }
+
+    fn read_text(buf: &[u8]) -> Result<String> {
+        Self::from_sql(&PgType::TEXT, buf)
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 #[error("String was not 't', 'true', 'f', or 'false'")]
 struct ParseSqlBoolError;
@@ -96,4 +137,15 @@ mod tests {
 
         let _ = TextReader::read_bool("none".as_bytes()).unwrap_err();
     }
+
+    #[test]
+    fn binary_reader() {
+        assert!(BinaryReader::read_bool(&[1]).unwrap());
+        assert_eq!(-2, BinaryReader::read_int2(&[0xff, 0xfe]).unwrap());
+        assert_eq!(258, BinaryReader::read_int4(&[0, 0, 1, 2]).unwrap());
+        assert_eq!(1.5, BinaryReader::read_float8(&1.5_f64.to_be_bytes()).unwrap());
+        assert_eq!("abc", BinaryReader::read_text(b"abc").unwrap());
+
+        let _ = BinaryReader::read_int4(&[0, 1]).unwrap_err();
+    }
 }
@@ -13,7 +13,7 @@ use tokio_postgres::types::Type as PgType;
 
 use crate::error::{PgReprError, Result};
 use crate::format::Format;
-use crate::reader::TextReader;
+use crate::reader::{BinaryReader, TextReader};
 use crate::writer::{BinaryWriter, TextWriter};
 
 static AVAILABLE_TIMEZONES: Lazy<HashMap<String, Tz>> = Lazy::new(|| {
@@ -120,7 +120,7 @@ impl Scalar {
     pub fn decode_with_format(format: Format, buf: &[u8], as_type: &PgType) -> Result<Self> {
         match format {
             Format::Text => Self::decode::<TextReader>(buf, as_type),
-            Format::Binary => Err(PgReprError::UnsupportedPgTypeForDecode(as_type.to_owned())),
+            Format::Binary => Self::decode::<BinaryReader>(buf, as_type),
         }
     }
 
@@ -1,4 +1,4 @@
-use datafusion::arrow::datatypes::DataType as ArrowType;
+use datafusion::arrow::datatypes::{DataType as ArrowType, TimeUnit};
 use tokio_postgres::types::Type as PgType;
 
 /// Returns a compatible postgres type for the arrow datatype. If the type hint
@@ -30,3 +30,51 @@ pub fn arrow_to_pg_type(df_type: &ArrowType, type_hint: Option<PgType>) -> PgTyp
         _ => return PgType::TEXT,
     })
 }
+
+/// Returns the arrow type that values of the postgres type are represented as,
+/// if there is one.
+pub fn pg_to_arrow_type(pg_type: &PgType) -> Option<ArrowType> {
+    Some(match *pg_type {
+        PgType::BOOL => ArrowType::Boolean,
+        PgType::INT2 => ArrowType::Int16,
+        PgType::INT4 => ArrowType::Int32,
+        PgType::INT8 => ArrowType::Int64,
+        PgType::FLOAT4 => ArrowType::Float32,
+        PgType::FLOAT8 => ArrowType::Float64,
+        PgType::TEXT | PgType::VARCHAR | PgType::BPCHAR | PgType::NAME => ArrowType::Utf8,
+        PgType::BYTEA => ArrowType::Binary,
+        PgType::TIMESTAMP => ArrowType::Timestamp(TimeUnit::Microsecond, None),
+        PgType::TIMESTAMPTZ => ArrowType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+        PgType::TIME => ArrowType::Time64(TimeUnit::Microsecond),
+        PgType::DATE => ArrowType::Date32,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pg_to_arrow_round_trip() {
+        for pg_type in [
+            PgType::BOOL,
+            PgType::INT2,
+            PgType::INT4,
+            PgType::INT8,
+            PgType::FLOAT4,
+            PgType::FLOAT8,
+            PgType::TEXT,
+            PgType::BYTEA,
+            PgType::TIMESTAMP,
+            PgType::TIMESTAMPTZ,
+            PgType::TIME,
+            PgType::DATE,
+        ] {
+            let arrow_type = pg_to_arrow_type(&pg_type).unwrap();
+            assert_eq!(pg_type, arrow_to_pg_type(&arrow_type, None));
+        }
+
+        assert_eq!(None, pg_to_arrow_type(&PgType::JSON));
+    }
+}
@@ -1,4 +1,12 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use md5::{Digest, Md5};
+use once_cell::sync::Lazy;
+use sha2::Sha256;
+
 use crate::errors::{PgSrvError, Result};
+pub use crate::scram::ScramSecret;
 
 #[derive(Debug, Clone, Copy)]
 pub enum PasswordMode {
@@ -7,6 +15,11 @@ pub enum PasswordMode {
     /// Should error if no password is provided.
     RequireCleartext,
 
+    /// The frontend needs to prove it knows the password using a challenge
+    /// based on the user's stored credentials. This is SCRAM-SHA-256, or MD5
+    /// for users with an MD5 hashed password.
+    RequireChallenge,
+
     /// No password is required.
     NoPassword {
         /// Drop any authentication messages as well.
@@ -19,10 +32,90 @@ pub enum PasswordMode {
     },
 }
 
+/// Stored credentials for a user.
+#[derive(Clone)]
+pub enum Credentials {
+    ScramSha256(ScramSecret),
+    /// Hex encoded MD5 hash of the password concatenated with the user name,
+    /// prefixed with "md5".
+    Md5(String),
+}
+
+impl Credentials {
+    /// Parse credentials in the formats Postgres stores passwords in.
+    ///
+    /// Anything not prefixed with "md5" or "SCRAM-SHA-256$" is treated as a
+    /// plaintext password, and stored as a SCRAM secret.
+    pub fn parse(s: &str) -> Credentials {
+        if let Some(secret) = ScramSecret::parse(s) {
+            return Credentials::ScramSha256(secret);
+        }
+        match s.strip_prefix("md5") {
+            Some(hash) if hash.len() == 32 && hash.bytes().all(|b| b.is_ascii_hexdigit()) => {
+                Credentials::Md5(s.to_ascii_lowercase())
+            }
+            _ => Credentials::ScramSha256(ScramSecret::from_password(s)),
+        }
+    }
+
+    /// Check if a plaintext password matches these credentials.
+    pub fn verify_password(&self, user: &str, password: &str) -> bool {
+        match self {
+            Credentials::ScramSha256(secret) => secret.verify_password(password),
+            Credentials::Md5(hash) => {
+                let expected = md5_hex(&[password.as_bytes(), user.as_bytes()]);
+                constant_time_eq(hash[3..].as_bytes(), expected.as_bytes())
+            }
+        }
+    }
+}
+
+/// Compute the response a client sends to an MD5 password challenge, given
+/// the stored "md5" prefixed hash and the salt sent to the client.
+pub(crate) fn md5_challenge_response(hash: &str, salt: &[u8; 4]) -> String {
+    format!("md5{}", md5_hex(&[hash[3..].as_bytes(), salt]))
+}
+
+fn md5_hex(parts: &[&[u8]]) -> String {
+    let mut md5 = Md5::new();
+    for part in parts {
+        md5.update(part);
+    }
+    md5.finalize().iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Compare two byte slices in time independent of their contents.
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Random secret used for mock credentials when the authenticator doesn't
+/// provide its own.
+static MOCK_AUTH_NONCE: Lazy<[u8; 32]> = Lazy::new(rand::random);
+
 /// Authenticate connection on the glaredb node itself.
 pub trait LocalAuthenticator: Sync + Send {
     fn password_mode(&self) -> PasswordMode;
     fn authenticate(&self, user: &str, password: &str, db_name: &str) -> Result<()>;
+
+    /// Get the stored credentials for a user, used when the password mode is
+    /// `RequireChallenge`.
+    ///
+    /// Returns `None` if the user doesn't exist.
+    fn credentials(&self, _user: &str, _db_name: &str) -> Option<Credentials> {
+        None
+    }
+
+    /// Server secret used to derive mock credentials for users that don't
+    /// exist, see [`ScramSecret::mock`].
+    ///
+    /// Defaults to a random value generated once per process.
+    fn mock_auth_nonce(&self) -> &[u8] {
+        MOCK_AUTH_NONCE.as_slice()
+    }
 }
 impl<B> LocalAuthenticator for Box<B>
 where
@@ -35,6 +128,14 @@ where
     fn authenticate(&self, user: &str, password: &str, db_name: &str) -> Result<()> {
         (**self).authenticate(user, password, db_name)
     }
+
+    fn credentials(&self, user: &str, db_name: &str) -> Option<Credentials> {
+        (**self).credentials(user, db_name)
+    }
+
+    fn mock_auth_nonce(&self) -> &[u8] {
+        (**self).mock_auth_nonce()
+    }
 }
 
 /// A simple single user authenticator.
@@ -53,14 +154,92 @@ impl LocalAuthenticator for SingleUserAuthenticator {
         if user != self.user {
             return Err(PgSrvError::InvalidUserOrPassword);
         }
-        // TODO: Constant time compare.
-        if password != self.password {
+        if !constant_time_eq(password.as_bytes(), self.password.as_bytes()) {
             return Err(PgSrvError::InvalidUserOrPassword);
         }
         Ok(())
     }
 }
 
+/// Authenticates users listed in a users file, allowing multiple users with
+/// separate credentials to share a server.
+///
+/// Each line of the file is `<user>:<password>`, where the password may be a
+/// SCRAM-SHA-256 secret or "md5" prefixed hash as stored by Postgres in
+/// `pg_authid`, or a plaintext password. Empty lines and lines starting with
+/// '#' are ignored.
+///
+/// Users with an MD5 hashed password authenticate using MD5, everyone else
+/// uses SCRAM-SHA-256.
+#[derive(Clone)]
+pub struct UsersFileAuthenticator {
+    users: HashMap<String, Credentials>,
+    /// Hash of the file's contents, used as the secret for mock credentials
+    /// so that they're stable across restarts.
+    mock_auth_nonce: [u8; 32],
+}
+
+impl UsersFileAuthenticator {
+    /// Read users from a file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Self::parse(&contents)
+    }
+
+    /// Parse the contents of a users file.
+    pub fn parse(contents: &str) -> Result<Self> {
+        let mut users = HashMap::new();
+        for (idx, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = |msg| PgSrvError::InvalidUsersFile { line: idx + 1, msg };
+            let (user, password) = line
+                .split_once(':')
+                .ok_or_else(|| invalid("expected '<user>:<password>'"))?;
+            if user.is_empty() {
+                return Err(invalid("empty user name"));
+            }
+            if password.is_empty() {
+                return Err(invalid("empty password"));
+            }
+            if users
+                .insert(user.to_string(), Credentials::parse(password))
+                .is_some()
+            {
+                return Err(invalid("duplicate user"));
+            }
+        }
+        Ok(UsersFileAuthenticator {
+            users,
+            mock_auth_nonce: Sha256::digest(contents.as_bytes()).into(),
+        })
+    }
+}
+
+impl LocalAuthenticator for UsersFileAuthenticator {
+    fn password_mode(&self) -> PasswordMode {
+        PasswordMode::RequireChallenge
+    }
+
+    fn authenticate(&self, user: &str, password: &str, _db_name: &str) -> Result<()> {
+        match self.users.get(user) {
+            Some(creds) if creds.verify_password(user, password) => Ok(()),
+            _ => Err(PgSrvError::InvalidUserOrPassword),
+        }
+    }
+
+    fn credentials(&self, user: &str, _db_name: &str) -> Option<Credentials> {
+        self.users.get(user).cloned()
+    }
+
+    fn mock_auth_nonce(&self) -> &[u8] {
+        &self.mock_auth_nonce
+    }
+}
+
 /// Require no password provided.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct PasswordlessAuthenticator {
@@ -78,3 +257,70 @@ impl LocalAuthenticator for PasswordlessAuthenticator {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn users_file() {
+        let auth = UsersFileAuthenticator::parse(
+            "
+            # Comments and empty lines are skipped.
+            alice:hunter2
+
+            bob:md5a2cc14bcc08bcb211f578153967abd6d
+            carol:with:colons
+            ",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            auth.credentials("alice", ""),
+            Some(Credentials::ScramSha256(_))
+        ));
+        assert!(matches!(
+            auth.credentials("bob", ""),
+            Some(Credentials::Md5(_))
+        ));
+        assert!(auth.credentials("dave", "").is_none());
+
+        auth.authenticate("alice", "hunter2", "").unwrap();
+        auth.authenticate("carol", "with:colons", "").unwrap();
+        auth.authenticate("alice", "hunter3", "").unwrap_err();
+        auth.authenticate("dave", "hunter2", "").unwrap_err();
+    }
+
+    #[test]
+    fn invalid_users_file() {
+        for (contents, expected_line) in [
+            ("alice", 1),
+            ("alice:a\n:b", 2),
+            ("alice:a\nbob:", 2),
+            ("alice:a\n\nalice:b", 3),
+        ] {
+            match UsersFileAuthenticator::parse(contents) {
+                Err(PgSrvError::InvalidUsersFile { line, .. }) => {
+                    assert_eq!(expected_line, line, "{contents}")
+                }
+                _ => panic!("expected error for {contents}"),
+            }
+        }
+    }
+
+    #[test]
+    fn md5_credentials() {
+        let hash = format!("md5{}", md5_hex(&[b"hunter2", b"bob"]));
+        let creds = Credentials::parse(&hash);
+        assert!(creds.verify_password("bob", "hunter2"));
+        assert!(!creds.verify_password("bob", "hunter3"));
+        assert!(!creds.verify_password("alice", "hunter2"));
+
+        // Matches what the client computes.
+        let salt = [1, 2, 3, 4];
+        assert_eq!(
+            postgres_protocol::authentication::md5_hash(b"bob", b"hunter2", salt),
+            md5_challenge_response(&hash, &salt),
+        );
+    }
+}
@@ -105,7 +105,7 @@ impl Encoder<StartupMessage> for PgClientCodec {
 
                 Ok(())
             }
-            StartupMessage::CancelRequest { version: _ } => {
+            StartupMessage::CancelRequest { .. } => {
                 todo!("encode<StartupMessage::CancelRequest>")
             }
         }
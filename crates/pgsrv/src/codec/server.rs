@@ -102,16 +102,35 @@ where
     pub fn set_encoding_state(&mut self, s: Vec<(PgType, Format)>) {
         self.conn.get_mut().codec_mut().encoding_state = s;
     }
+
+    /// Sets how subsequent password messages should be decoded.
+    pub fn set_password_message_kind(&mut self, kind: PasswordMessageKind) {
+        self.conn.get_mut().codec_mut().password_message_kind = kind;
+    }
+}
+
+/// The kind of message the frontend sends with the 'p' type byte.
+///
+/// Password, and the various SASL messages all share the same type byte, so
+/// we need to know which one is expected in order to decode it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PasswordMessageKind {
+    #[default]
+    Password,
+    SASLInitialResponse,
+    SASLResponse,
 }
 
 pub struct PgCodec {
     encoding_state: Vec<(PgType, Format)>,
+    password_message_kind: PasswordMessageKind,
 }
 
 impl PgCodec {
     fn new() -> Self {
         Self {
             encoding_state: Vec::new(),
+            password_message_kind: PasswordMessageKind::default(),
         }
     }
 
@@ -130,7 +149,15 @@ impl PgCodec {
         match version {
             VERSION_V3 => (), // Continue with normal startup flow.
             VERSION_SSL => return Ok(StartupMessage::SSLRequest { version }),
-            VERSION_CANCEL => return Ok(StartupMessage::CancelRequest { version }),
+            VERSION_CANCEL => {
+                let process_id = conn.read_i32().await?;
+                let secret_key = conn.read_i32().await?;
+                return Ok(StartupMessage::CancelRequest {
+                    version,
+                    process_id,
+                    secret_key,
+                });
+            }
             other => return Err(PgSrvError::InvalidProtocolVersion(other)),
         }
 
@@ -172,6 +199,27 @@ impl PgCodec {
         })
     }
 
+    fn decode_sasl_initial_response(buf: &mut Cursor<'_>) -> Result<FrontendMessage> {
+        let mechanism = buf.read_cstring()?.to_string();
+        // A length of -1 indicates no initial response.
+        let len = buf.get_i32();
+        let mut data = Vec::new();
+        if len >= 0 {
+            if len as usize > buf.remaining() {
+                return Err(PgSrvError::InvalidMsgLength(len));
+            }
+            data.resize(len as usize, 0);
+            buf.copy_to_slice(&mut data);
+        }
+        Ok(FrontendMessage::SASLInitialResponse { mechanism, data })
+    }
+
+    fn decode_sasl_response(buf: &mut Cursor<'_>) -> Result<FrontendMessage> {
+        let mut data = vec![0; buf.remaining()];
+        buf.copy_to_slice(&mut data);
+        Ok(FrontendMessage::SASLResponse { data })
+    }
+
     fn decode_parse(buf: &mut Cursor<'_>) -> Result<FrontendMessage> {
         let name = buf.read_cstring()?.to_string();
         let sql = buf.read_cstring()?.to_string();
@@ -254,6 +302,22 @@ impl PgCodec {
         Ok(FrontendMessage::Close { object_type, name })
     }
 
+    fn decode_copy_data(buf: &mut Cursor<'_>) -> Result<FrontendMessage> {
+        let mut data = vec![0; buf.remaining()];
+        buf.copy_to_slice(&mut data);
+        Ok(FrontendMessage::CopyData(data))
+    }
+
+    fn decode_copy_done(_buf: &mut Cursor<'_>) -> Result<FrontendMessage> {
+        Ok(FrontendMessage::CopyDone)
+    }
+
+    fn decode_copy_fail(buf: &mut Cursor<'_>) -> Result<FrontendMessage> {
+        Ok(FrontendMessage::CopyFail {
+            message: buf.read_cstring()?.to_string(),
+        })
+    }
+
     fn decode_sync(_buf: &mut Cursor<'_>) -> Result<FrontendMessage> {
         Ok(FrontendMessage::Sync)
     }
@@ -274,6 +338,10 @@ impl Encoder<BackendMessage> for PgCodec {
         let byte = match &item {
             BackendMessage::AuthenticationOk => b'R',
             BackendMessage::AuthenticationCleartextPassword => b'R',
+            BackendMessage::AuthenticationMD5Password { .. } => b'R',
+            BackendMessage::AuthenticationSASL { .. } => b'R',
+            BackendMessage::AuthenticationSASLContinue(_) => b'R',
+            BackendMessage::AuthenticationSASLFinal(_) => b'R',
             BackendMessage::EmptyQueryResponse => b'I',
             BackendMessage::ParameterStatus { .. } => b'S',
             BackendMessage::ReadyForQuery(_) => b'Z',
@@ -286,7 +354,13 @@ impl Encoder<BackendMessage> for PgCodec {
             BackendMessage::BindComplete => b'2',
             BackendMessage::CloseComplete => b'3',
             BackendMessage::NoData => b'n',
+            BackendMessage::BackendKeyData { .. } => b'K',
+            BackendMessage::PortalSuspended => b's',
             BackendMessage::ParameterDescription(_) => b't',
+            BackendMessage::CopyInResponse(_) => b'G',
+            BackendMessage::CopyOutResponse(_) => b'H',
+            BackendMessage::CopyData(_) => b'd',
+            BackendMessage::CopyDone => b'c',
         };
         dst.put_u8(byte);
 
@@ -297,15 +371,43 @@ impl Encoder<BackendMessage> for PgCodec {
         match item {
             BackendMessage::AuthenticationOk => dst.put_i32(0),
             BackendMessage::AuthenticationCleartextPassword => dst.put_i32(3),
+            BackendMessage::AuthenticationMD5Password { salt } => {
+                dst.put_i32(5);
+                dst.put_slice(&salt);
+            }
+            BackendMessage::AuthenticationSASL { mechanisms } => {
+                dst.put_i32(10);
+                for mechanism in mechanisms {
+                    dst.put_cstring(&mechanism);
+                }
+                dst.put_u8(0);
+            }
+            BackendMessage::AuthenticationSASLContinue(data) => {
+                dst.put_i32(11);
+                dst.put_slice(&data);
+            }
+            BackendMessage::AuthenticationSASLFinal(data) => {
+                dst.put_i32(12);
+                dst.put_slice(&data);
+            }
             BackendMessage::EmptyQueryResponse => (),
             BackendMessage::ParseComplete => (),
             BackendMessage::BindComplete => (),
             BackendMessage::CloseComplete => (),
             BackendMessage::NoData => (),
+            BackendMessage::PortalSuspended => (),
+            BackendMessage::CopyDone => (),
             BackendMessage::ParameterStatus { key, val } => {
                 dst.put_cstring(&key);
                 dst.put_cstring(&val);
             }
+            BackendMessage::BackendKeyData {
+                process_id,
+                secret_key,
+            } => {
+                dst.put_i32(process_id);
+                dst.put_i32(secret_key);
+            }
             BackendMessage::ReadyForQuery(status) => match status {
                 TransactionStatus::Idle => dst.put_u8(b'I'),
                 TransactionStatus::InBlock => dst.put_u8(b'T'),
@@ -387,6 +489,14 @@ impl Encoder<BackendMessage> for PgCodec {
                     dst.put_i32(desc);
                 }
             }
+            BackendMessage::CopyInResponse(resp) | BackendMessage::CopyOutResponse(resp) => {
+                dst.put_i8(i16::from(resp.format) as i8);
+                dst.put_i16(resp.column_formats.len() as i16);
+                for format in resp.column_formats {
+                    dst.put_i16(format.into());
+                }
+            }
+            BackendMessage::CopyData(data) => dst.put_slice(&data),
         }
 
         let msg_len = dst.len() - len_idx;
@@ -423,12 +533,21 @@ impl Decoder for PgCodec {
 
         let msg = match msg_type {
             b'Q' => Self::decode_query(&mut buf)?,
-            b'p' => Self::decode_password(&mut buf)?,
+            b'p' => match self.password_message_kind {
+                PasswordMessageKind::Password => Self::decode_password(&mut buf)?,
+                PasswordMessageKind::SASLInitialResponse => {
+                    Self::decode_sasl_initial_response(&mut buf)?
+                }
+                PasswordMessageKind::SASLResponse => Self::decode_sasl_response(&mut buf)?,
+            },
             b'P' => Self::decode_parse(&mut buf)?,
             b'B' => Self::decode_bind(&mut buf)?,
             b'D' => Self::decode_describe(&mut buf)?,
             b'E' => Self::decode_execute(&mut buf)?,
             b'C' => Self::decode_close(&mut buf)?,
+            b'd' => Self::decode_copy_data(&mut buf)?,
+            b'c' => Self::decode_copy_done(&mut buf)?,
+            b'f' => Self::decode_copy_fail(&mut buf)?,
             b'S' => Self::decode_sync(&mut buf)?,
             b'H' => Self::decode_flush(&mut buf)?,
             b'X' => Self::decode_terminate(&mut buf)?,
@@ -0,0 +1,836 @@
+//! Encoding and decoding of the data exchanged during `COPY .. FROM STDIN`
+//! and `COPY .. TO STDOUT`.
+//!
+//! See <https://www.postgresql.org/docs/current/sql-copy.html#id-1.9.3.55.9>
+//! for the text, CSV and binary formats.
+use std::mem::size_of;
+
+use bytes::{BufMut, BytesMut};
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::scalar::ScalarValue;
+use pgrepr::format::Format;
+use pgrepr::notice::SqlState;
+use pgrepr::reader::BinaryReader;
+use pgrepr::scalar::Scalar;
+use pgrepr::types::arrow_to_pg_type;
+use sqlexec::{CopyStdioFormat, CopyStdioOptions};
+use tokio_postgres::types::Type as PgType;
+
+use crate::messages::ErrorResponse;
+
+/// Signature at the start of binary COPY data.
+const BINARY_SIGNATURE: &[u8] = b"PGCOPY\n\xff\r\n\0";
+
+/// Number of rows to decode before producing a batch.
+const COPY_IN_BATCH_SIZE: usize = 4096;
+
+/// The wire format for a COPY to or from the client.
+pub fn copy_wire_format(options: &CopyStdioOptions) -> Format {
+    match options.format {
+        CopyStdioFormat::Binary => Format::Binary,
+        CopyStdioFormat::Text | CopyStdioFormat::Csv => Format::Text,
+    }
+}
+
+fn bad_copy_format(msg: impl Into<String>) -> ErrorResponse {
+    ErrorResponse::error(SqlState::BadCopyFileFormat, msg)
+}
+
+/// Decodes the data sent by the client during a `COPY .. FROM STDIN` into
+/// batches.
+///
+/// Text and CSV values are decoded as strings, and binary values are decoded
+/// using the types from the schema.
+#[derive(Debug)]
+pub struct CopyInDecoder {
+    schema: SchemaRef,
+    options: CopyStdioOptions,
+    /// Postgres types for decoding binary values.
+    pg_types: Vec<PgType>,
+    /// Data that hasn't been decoded yet.
+    buf: Vec<u8>,
+    /// Decoded values for each column.
+    columns: Vec<Vec<ScalarValue>>,
+    num_rows: usize,
+    /// Line number of the next row, used in errors.
+    line: usize,
+    /// If we still need to read past the header.
+    header_pending: bool,
+    /// If we've read the end of data marker.
+    done: bool,
+}
+
+impl CopyInDecoder {
+    pub fn new(schema: SchemaRef, options: CopyStdioOptions) -> Self {
+        let pg_types = schema
+            .fields()
+            .iter()
+            .map(|f| arrow_to_pg_type(f.data_type(), None))
+            .collect();
+        let columns = vec![Vec::new(); schema.fields().len()];
+        let header_pending = options.header || options.format == CopyStdioFormat::Binary;
+
+        CopyInDecoder {
+            schema,
+            options,
+            pg_types,
+            buf: Vec::new(),
+            columns,
+            num_rows: 0,
+            line: 1,
+            header_pending,
+            done: false,
+        }
+    }
+
+    /// Decode a chunk of data sent by the client.
+    ///
+    /// Rows may be split across chunks. Returns batches for any complete rows
+    /// once enough have been decoded.
+    pub fn decode(&mut self, data: &[u8]) -> Result<Vec<RecordBatch>, ErrorResponse> {
+        self.buf.extend_from_slice(data);
+        self.decode_rows(false)
+    }
+
+    /// Decode any remaining data once the client is done sending data,
+    /// returning batches for the remaining rows.
+    pub fn finish(mut self) -> Result<Vec<RecordBatch>, ErrorResponse> {
+        let mut batches = self.decode_rows(true)?;
+        if self.options.format == CopyStdioFormat::Binary && !self.done {
+            return Err(bad_copy_format("unexpected EOF in COPY data"));
+        }
+        if self.num_rows > 0 {
+            batches.push(self.build_batch()?);
+        }
+        Ok(batches)
+    }
+
+    fn decode_rows(&mut self, at_eof: bool) -> Result<Vec<RecordBatch>, ErrorResponse> {
+        let mut batches = Vec::new();
+        let mut offset = 0;
+
+        while !self.done && offset < self.buf.len() {
+            let consumed = match self.options.format {
+                CopyStdioFormat::Binary => self.decode_binary(offset)?,
+                CopyStdioFormat::Text | CopyStdioFormat::Csv => self.decode_line(offset, at_eof)?,
+            };
+            match consumed {
+                Some(n) => offset += n,
+                None => break, // Need more data.
+            }
+
+            if self.num_rows >= COPY_IN_BATCH_SIZE {
+                batches.push(self.build_batch()?);
+            }
+        }
+
+        if self.done {
+            // Anything after the end of data marker is ignored.
+            self.buf.clear();
+        } else {
+            self.buf.drain(..offset);
+        }
+
+        Ok(batches)
+    }
+
+    /// Decode a single text or CSV line starting at `offset`, returning the
+    /// number of bytes consumed.
+    fn decode_line(&mut self, offset: usize, at_eof: bool) -> Result<Option<usize>, ErrorResponse> {
+        let data = &self.buf[offset..];
+        let (fields, consumed) = match self.options.format {
+            CopyStdioFormat::Csv => match split_csv_line(data, &self.options, at_eof, self.line)? {
+                Some(v) => v,
+                None => return Ok(None),
+            },
+            _ => match split_text_line(data, &self.options, at_eof, self.line)? {
+                Some(v) => v,
+                None => return Ok(None),
+            },
+        };
+        let line = self.line;
+        self.line += 1;
+
+        match fields {
+            CopyLine::EndOfData => {
+                self.done = true;
+                return Ok(Some(consumed));
+            }
+            CopyLine::Fields(_) if self.header_pending => {
+                self.header_pending = false;
+                return Ok(Some(consumed));
+            }
+            CopyLine::Fields(fields) => {
+                if fields.len() > self.columns.len() {
+                    return Err(bad_copy_format(format!(
+                        "extra data after last expected column (line {line})"
+                    )));
+                }
+                if fields.len() < self.columns.len() {
+                    return Err(bad_copy_format(format!(
+                        "missing data for column \"{}\" (line {line})",
+                        self.schema.field(fields.len()).name()
+                    )));
+                }
+                for (column, field) in self.columns.iter_mut().zip(fields) {
+                    column.push(ScalarValue::Utf8(field));
+                }
+                self.num_rows += 1;
+            }
+        }
+
+        Ok(Some(consumed))
+    }
+
+    /// Decode the binary header or a single binary tuple starting at
+    /// `offset`, returning the number of bytes consumed.
+    fn decode_binary(&mut self, offset: usize) -> Result<Option<usize>, ErrorResponse> {
+        let data = &self.buf[offset..];
+
+        if self.header_pending {
+            let fixed_len = BINARY_SIGNATURE.len() + 2 * size_of::<i32>();
+            if data.len() < fixed_len {
+                return Ok(None);
+            }
+            if &data[..BINARY_SIGNATURE.len()] != BINARY_SIGNATURE {
+                return Err(bad_copy_format("COPY file signature not recognized"));
+            }
+            let flags = read_i32(&data[BINARY_SIGNATURE.len()..]);
+            if flags & (1 << 16) != 0 {
+                return Err(bad_copy_format("COPY file with OIDs is not supported"));
+            }
+            let ext_len = read_i32(&data[BINARY_SIGNATURE.len() + size_of::<i32>()..]);
+            if ext_len < 0 {
+                return Err(bad_copy_format("invalid COPY file header (missing length)"));
+            }
+            let header_len = fixed_len + ext_len as usize;
+            if data.len() < header_len {
+                return Ok(None);
+            }
+            self.header_pending = false;
+            return Ok(Some(header_len));
+        }
+
+        if data.len() < size_of::<i16>() {
+            return Ok(None);
+        }
+        let num_fields = i16::from_be_bytes([data[0], data[1]]);
+        if num_fields == -1 {
+            self.done = true;
+            return Ok(Some(size_of::<i16>()));
+        }
+        if num_fields as usize != self.columns.len() {
+            return Err(bad_copy_format(format!(
+                "row field count is {num_fields}, expected {}",
+                self.columns.len()
+            )));
+        }
+
+        // Make sure we have the full tuple before decoding anything.
+        let mut pos = size_of::<i16>();
+        let mut ranges = Vec::with_capacity(self.columns.len());
+        for _ in 0..self.columns.len() {
+            if data.len() < pos + size_of::<i32>() {
+                return Ok(None);
+            }
+            let len = read_i32(&data[pos..]);
+            pos += size_of::<i32>();
+            if len < 0 {
+                ranges.push(None);
+                continue;
+            }
+            let len = len as usize;
+            if data.len() < pos + len {
+                return Ok(None);
+            }
+            ranges.push(Some(pos..pos + len));
+            pos += len;
+        }
+
+        for (idx, range) in ranges.into_iter().enumerate() {
+            let field = self.schema.field(idx);
+            let value = match range {
+                None => ScalarValue::try_from(field.data_type())
+                    .map_err(|e| ErrorResponse::error_internal(e.to_string()))?,
+                Some(range) => Scalar::decode::<BinaryReader>(&data[range], &self.pg_types[idx])
+                    .and_then(|scalar| scalar.into_datafusion(field.data_type()))
+                    .map_err(|e| {
+                        bad_copy_format(format!(
+                            "invalid binary value for column \"{}\": {e}",
+                            field.name()
+                        ))
+                    })?,
+            };
+            self.columns[idx].push(value);
+        }
+        self.num_rows += 1;
+        self.line += 1;
+
+        Ok(Some(pos))
+    }
+
+    fn build_batch(&mut self) -> Result<RecordBatch, ErrorResponse> {
+        let arrays = self
+            .columns
+            .iter_mut()
+            .map(|values| ScalarValue::iter_to_array(values.drain(..)))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ErrorResponse::error_internal(e.to_string()))?;
+        self.num_rows = 0;
+
+        RecordBatch::try_new(self.schema.clone(), arrays)
+            .map_err(|e| ErrorResponse::error_internal(e.to_string()))
+    }
+}
+
+fn read_i32(buf: &[u8]) -> i32 {
+    i32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]])
+}
+
+/// A single line of text or CSV data.
+#[derive(Debug, PartialEq)]
+enum CopyLine {
+    /// The `\.` end of data marker.
+    EndOfData,
+    Fields(Vec<Option<String>>),
+}
+
+/// Find the end of the line starting at the beginning of `data`, returning
+/// the line (without the line ending) and the number of bytes consumed.
+fn next_line(data: &[u8], at_eof: bool) -> Option<(&[u8], usize)> {
+    match data.iter().position(|b| *b == b'\n') {
+        Some(idx) => {
+            let line = &data[..idx];
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            Some((line, idx + 1))
+        }
+        None if at_eof => Some((data.strip_suffix(b"\r").unwrap_or(data), data.len())),
+        None => None,
+    }
+}
+
+fn to_utf8(buf: Vec<u8>, line: usize) -> Result<String, ErrorResponse> {
+    String::from_utf8(buf).map_err(|_| {
+        bad_copy_format(format!(
+            "invalid byte sequence for encoding \"UTF8\" (line {line})"
+        ))
+    })
+}
+
+/// Split a line of text format data into its fields.
+///
+/// Returns `None` if the data doesn't contain a full line yet.
+fn split_text_line(
+    data: &[u8],
+    options: &CopyStdioOptions,
+    at_eof: bool,
+    line_num: usize,
+) -> Result<Option<(CopyLine, usize)>, ErrorResponse> {
+    let (line, consumed) = match next_line(data, at_eof) {
+        Some(v) => v,
+        None => return Ok(None),
+    };
+    if line == b"\\." {
+        return Ok(Some((CopyLine::EndOfData, consumed)));
+    }
+
+    let mut fields = Vec::new();
+    let mut start = 0;
+    let mut idx = 0;
+    while idx <= line.len() {
+        if idx == line.len() || line[idx] == options.delimiter {
+            let raw = &line[start..idx];
+            if raw == options.null.as_bytes() {
+                fields.push(None);
+            } else {
+                fields.push(Some(to_utf8(unescape_text(raw), line_num)?));
+            }
+            start = idx + 1;
+        } else if line[idx] == b'\\' {
+            // Skip the escaped character so escaped delimiters aren't treated
+            // as separators.
+            idx += 1;
+        }
+        idx += 1;
+    }
+
+    Ok(Some((CopyLine::Fields(fields), consumed)))
+}
+
+/// Process backslash escapes for a text format value.
+fn unescape_text(raw: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(raw.len());
+    let mut idx = 0;
+    while idx < raw.len() {
+        let b = raw[idx];
+        idx += 1;
+        if b != b'\\' || idx == raw.len() {
+            out.push(b);
+            continue;
+        }
+
+        let c = raw[idx];
+        idx += 1;
+        match c {
+            b'b' => out.push(0x08),
+            b'f' => out.push(0x0c),
+            b'n' => out.push(b'\n'),
+            b'r' => out.push(b'\r'),
+            b't' => out.push(b'\t'),
+            b'v' => out.push(0x0b),
+            b'0'..=b'7' => {
+                let mut val = (c - b'0') as u32;
+                for _ in 0..2 {
+                    match raw.get(idx) {
+                        Some(d @ b'0'..=b'7') => {
+                            val = val * 8 + (d - b'0') as u32;
+                            idx += 1;
+                        }
+                        _ => break,
+                    }
+                }
+                out.push(val as u8);
+            }
+            b'x' if raw.get(idx).is_some_and(|d| d.is_ascii_hexdigit()) => {
+                let mut val = 0;
+                for _ in 0..2 {
+                    match raw.get(idx).and_then(|d| (*d as char).to_digit(16)) {
+                        Some(d) => {
+                            val = val * 16 + d;
+                            idx += 1;
+                        }
+                        None => break,
+                    }
+                }
+                out.push(val as u8);
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Split a CSV record into its fields. Quoted values may span multiple lines.
+///
+/// Returns `None` if the data doesn't contain a full record yet.
+fn split_csv_line(
+    data: &[u8],
+    options: &CopyStdioOptions,
+    at_eof: bool,
+    line_num: usize,
+) -> Result<Option<(CopyLine, usize)>, ErrorResponse> {
+    if let Some((line, consumed)) = next_line(data, at_eof) {
+        if line == b"\\." {
+            return Ok(Some((CopyLine::EndOfData, consumed)));
+        }
+    }
+
+    let mut fields = Vec::new();
+    let mut idx = 0;
+    loop {
+        let mut value = Vec::new();
+        let mut quoted = false;
+        let mut in_quotes = false;
+
+        // Read a single field.
+        loop {
+            let b = match data.get(idx) {
+                Some(b) => *b,
+                None if in_quotes && at_eof => {
+                    return Err(bad_copy_format(format!(
+                        "unterminated CSV quoted field (line {line_num})"
+                    )));
+                }
+                None if at_eof => break,
+                None => return Ok(None),
+            };
+            idx += 1;
+
+            if in_quotes {
+                if b == options.escape && data.get(idx) == Some(&options.quote) {
+                    value.push(options.quote);
+                    idx += 1;
+                } else if b == options.escape
+                    && options.escape != options.quote
+                    && data.get(idx) == Some(&options.escape)
+                {
+                    value.push(options.escape);
+                    idx += 1;
+                } else if b == options.quote {
+                    in_quotes = false;
+                } else {
+                    value.push(b);
+                }
+            } else if b == options.delimiter || b == b'\n' || b == b'\r' {
+                idx -= 1;
+                break;
+            } else if b == options.quote {
+                quoted = true;
+                in_quotes = true;
+            } else {
+                value.push(b);
+            }
+        }
+
+        // Only unquoted values can match the null string.
+        if !quoted && value == options.null.as_bytes() {
+            fields.push(None);
+        } else {
+            fields.push(Some(to_utf8(value, line_num)?));
+        }
+
+        match data.get(idx) {
+            Some(b) if *b == options.delimiter => idx += 1,
+            Some(b'\r') => {
+                if idx + 1 == data.len() && !at_eof {
+                    // Might be followed by a newline in the next chunk.
+                    return Ok(None);
+                }
+                idx += 1;
+                if data.get(idx) == Some(&b'\n') {
+                    idx += 1;
+                }
+                break;
+            }
+            Some(b'\n') => {
+                idx += 1;
+                break;
+            }
+            _ => break, // End of data.
+        }
+    }
+
+    Ok(Some((CopyLine::Fields(fields), idx)))
+}
+
+/// Encodes rows for a `COPY .. TO STDOUT`.
+#[derive(Debug)]
+pub struct CopyOutEncoder {
+    options: CopyStdioOptions,
+    pg_types: Vec<PgType>,
+}
+
+impl CopyOutEncoder {
+    pub fn new(schema: &Schema, options: CopyStdioOptions) -> Self {
+        let pg_types = schema
+            .fields()
+            .iter()
+            .map(|f| arrow_to_pg_type(f.data_type(), None))
+            .collect();
+        CopyOutEncoder { options, pg_types }
+    }
+
+    /// Data to send before any rows.
+    pub fn header(&self, schema: &Schema) -> Option<Vec<u8>> {
+        match self.options.format {
+            CopyStdioFormat::Binary => {
+                let mut buf = BINARY_SIGNATURE.to_vec();
+                buf.put_i32(0); // Flags
+                buf.put_i32(0); // Header extension length
+                Some(buf)
+            }
+            _ if self.options.header => {
+                let mut buf = Vec::new();
+                for (idx, field) in schema.fields().iter().enumerate() {
+                    if idx > 0 {
+                        buf.push(self.options.delimiter);
+                    }
+                    self.put_text_value(&mut buf, field.name().as_bytes());
+                }
+                buf.push(b'\n');
+                Some(buf)
+            }
+            _ => None,
+        }
+    }
+
+    /// Data to send after all rows.
+    pub fn trailer(&self) -> Option<Vec<u8>> {
+        match self.options.format {
+            CopyStdioFormat::Binary => Some((-1_i16).to_be_bytes().to_vec()),
+            _ => None,
+        }
+    }
+
+    /// Encode a single row from the batch.
+    pub fn encode_row(&self, batch: &RecordBatch, row: usize) -> Result<Vec<u8>, ErrorResponse> {
+        let mut buf = BytesMut::new();
+        if self.options.format == CopyStdioFormat::Binary {
+            buf.put_i16(batch.num_columns() as i16);
+        }
+
+        for (idx, (col, pg_type)) in batch.columns().iter().zip(&self.pg_types).enumerate() {
+            let scalar = Scalar::try_from_array(col, row, pg_type)?;
+
+            if self.options.format == CopyStdioFormat::Binary {
+                if scalar.is_null() {
+                    buf.put_i32(-1);
+                } else {
+                    let mut val = BytesMut::new();
+                    scalar.encode_with_format(Format::Binary, &mut val)?;
+                    buf.put_i32(val.len() as i32);
+                    buf.put_slice(&val);
+                }
+                continue;
+            }
+
+            if idx > 0 {
+                buf.put_u8(self.options.delimiter);
+            }
+            if scalar.is_null() {
+                buf.put_slice(self.options.null.as_bytes());
+            } else {
+                let mut val = BytesMut::new();
+                scalar.encode_with_format(Format::Text, &mut val)?;
+                let mut out = Vec::with_capacity(val.len());
+                self.put_text_value(&mut out, &val);
+                buf.put_slice(&out);
+            }
+        }
+
+        if self.options.format != CopyStdioFormat::Binary {
+            buf.put_u8(b'\n');
+        }
+
+        Ok(buf.to_vec())
+    }
+
+    /// Write a non-null text or CSV value, escaping or quoting as needed.
+    fn put_text_value(&self, buf: &mut Vec<u8>, val: &[u8]) {
+        let opts = &self.options;
+        if opts.format == CopyStdioFormat::Csv {
+            let needs_quotes = val.is_empty() && opts.null.is_empty()
+                || val == opts.null.as_bytes()
+                || val == b"\\."
+                || val.iter().any(|b| {
+                    *b == opts.delimiter || *b == opts.quote || *b == b'\n' || *b == b'\r'
+                });
+            if !needs_quotes {
+                buf.extend_from_slice(val);
+                return;
+            }
+            buf.push(opts.quote);
+            for b in val {
+                if *b == opts.quote || (*b == opts.escape && opts.escape != opts.quote) {
+                    buf.push(opts.escape);
+                }
+                buf.push(*b);
+            }
+            buf.push(opts.quote);
+            return;
+        }
+
+        for b in val {
+            match *b {
+                b'\\' => buf.extend_from_slice(b"\\\\"),
+                b'\n' => buf.extend_from_slice(b"\\n"),
+                b'\r' => buf.extend_from_slice(b"\\r"),
+                b'\t' => buf.extend_from_slice(b"\\t"),
+                0x08 => buf.extend_from_slice(b"\\b"),
+                0x0c => buf.extend_from_slice(b"\\f"),
+                0x0b => buf.extend_from_slice(b"\\v"),
+                b if b == opts.delimiter => {
+                    buf.push(b'\\');
+                    buf.push(b);
+                }
+                b => buf.push(b),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Int32Array, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field};
+
+    use super::*;
+
+    fn text_schema(num_columns: usize) -> SchemaRef {
+        let fields = (0..num_columns)
+            .map(|idx| Field::new(format!("c{idx}"), DataType::Utf8, true))
+            .collect::<Vec<_>>();
+        Arc::new(Schema::new(fields))
+    }
+
+    fn decode_all(
+        schema: SchemaRef,
+        options: CopyStdioOptions,
+        chunks: &[&[u8]],
+    ) -> Result<Vec<Vec<Option<String>>>, ErrorResponse> {
+        let mut decoder = CopyInDecoder::new(schema, options);
+        let mut batches = Vec::new();
+        for chunk in chunks {
+            batches.extend(decoder.decode(chunk)?);
+        }
+        batches.extend(decoder.finish()?);
+
+        let mut rows = Vec::new();
+        for batch in batches {
+            for row in 0..batch.num_rows() {
+                let row = batch
+                    .columns()
+                    .iter()
+                    .map(|col| {
+                        let col = col.as_any().downcast_ref::<StringArray>().unwrap();
+                        (!col.is_null(row)).then(|| col.value(row).to_string())
+                    })
+                    .collect();
+                rows.push(row);
+            }
+        }
+        Ok(rows)
+    }
+
+    fn row(vals: &[Option<&str>]) -> Vec<Option<String>> {
+        vals.iter().map(|v| v.map(|v| v.to_string())).collect()
+    }
+
+    #[test]
+    fn decode_text() {
+        let options = CopyStdioOptions::new(CopyStdioFormat::Text);
+        let rows = decode_all(
+            text_schema(2),
+            options,
+            &[b"1\thello\n2\t\\N\n3\ta\\tb\\\\c\\", b"\\n\n4\tlast"],
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                row(&[Some("1"), Some("hello")]),
+                row(&[Some("2"), None]),
+                row(&[Some("3"), Some("a\tb\\c\\n")]),
+                row(&[Some("4"), Some("last")]),
+            ],
+            rows
+        );
+    }
+
+    #[test]
+    fn decode_text_end_marker_and_header() {
+        let mut options = CopyStdioOptions::new(CopyStdioFormat::Text);
+        options.header = true;
+        let rows = decode_all(text_schema(1), options, &[b"a\n1\n\\.\nignored\n"]).unwrap();
+        assert_eq!(vec![row(&[Some("1")])], rows);
+    }
+
+    #[test]
+    fn decode_text_wrong_column_count() {
+        let options = CopyStdioOptions::new(CopyStdioFormat::Text);
+        let err = decode_all(text_schema(2), options.clone(), &[b"1\n"]).unwrap_err();
+        assert!(err.message.contains("missing data for column \"c1\""));
+
+        let err = decode_all(text_schema(1), options, &[b"1\t2\n"]).unwrap_err();
+        assert!(err
+            .message
+            .contains("extra data after last expected column"));
+    }
+
+    #[test]
+    fn decode_csv() {
+        let mut options = CopyStdioOptions::new(CopyStdioFormat::Csv);
+        options.header = true;
+        let rows = decode_all(
+            text_schema(2),
+            options,
+            &[
+                b"a,b\r\n1,\"x,y\"\n2,\n3,\"\"\n4,\"multi\nli",
+                b"ne \"\"quoted\"\"\"\n5,end",
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                row(&[Some("1"), Some("x,y")]),
+                row(&[Some("2"), None]),
+                row(&[Some("3"), Some("")]),
+                row(&[Some("4"), Some("multi\nline \"quoted\"")]),
+                row(&[Some("5"), Some("end")]),
+            ],
+            rows
+        );
+    }
+
+    #[test]
+    fn decode_csv_unterminated_quote() {
+        let options = CopyStdioOptions::new(CopyStdioFormat::Csv);
+        let err = decode_all(text_schema(1), options, &[b"\"abc\n"]).unwrap_err();
+        assert!(err.message.contains("unterminated CSV quoted field"));
+    }
+
+    fn int_text_batch() -> RecordBatch {
+        RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("a", DataType::Int32, true),
+                Field::new("b", DataType::Utf8, true),
+            ])),
+            vec![
+                Arc::new(Int32Array::from(vec![Some(1), None])),
+                Arc::new(StringArray::from(vec![Some("x\ty,\"z\""), Some("")])),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn encode_all(options: CopyStdioOptions) -> Vec<u8> {
+        let batch = int_text_batch();
+        let encoder = CopyOutEncoder::new(&batch.schema(), options);
+        let mut out = encoder.header(&batch.schema()).unwrap_or_default();
+        for row in 0..batch.num_rows() {
+            out.extend(encoder.encode_row(&batch, row).unwrap());
+        }
+        out.extend(encoder.trailer().unwrap_or_default());
+        out
+    }
+
+    #[test]
+    fn encode_text() {
+        let out = encode_all(CopyStdioOptions::new(CopyStdioFormat::Text));
+        assert_eq!("1\tx\\ty,\"z\"\n\\N\t\n", String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn encode_csv() {
+        let mut options = CopyStdioOptions::new(CopyStdioFormat::Csv);
+        options.header = true;
+        let out = encode_all(options);
+        assert_eq!(
+            "a,b\n1,\"x\ty,\"\"z\"\"\"\n,\"\"\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn binary_roundtrip() {
+        let options = CopyStdioOptions::new(CopyStdioFormat::Binary);
+        let out = encode_all(options.clone());
+
+        let batch = int_text_batch();
+        let mut decoder = CopyInDecoder::new(batch.schema(), options);
+        // Feed the data a byte at a time to exercise partial tuples.
+        let mut batches = Vec::new();
+        for b in out.chunks(1) {
+            batches.extend(decoder.decode(b).unwrap());
+        }
+        batches.extend(decoder.finish().unwrap());
+
+        assert_eq!(vec![batch], batches);
+    }
+
+    #[test]
+    fn binary_missing_trailer() {
+        let options = CopyStdioOptions::new(CopyStdioFormat::Binary);
+        let mut out = encode_all(options.clone());
+        out.truncate(out.len() - 2);
+
+        let mut decoder = CopyInDecoder::new(int_text_batch().schema(), options);
+        decoder.decode(&out).unwrap();
+        let err = decoder.finish().unwrap_err();
+        assert!(err.message.contains("unexpected EOF"));
+    }
+}
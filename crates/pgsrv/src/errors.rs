@@ -1,5 +1,7 @@
 use std::io;
 
+use pgrepr::notice::SqlState;
+
 use crate::messages::{BackendMessage, FrontendMessage, StartupMessage};
 
 pub type Result<T, E = PgSrvError> = std::result::Result<T, E>;
@@ -42,6 +44,15 @@ pub enum PgSrvError {
     #[error("Invalid user or password")]
     InvalidUserOrPassword,
 
+    #[error("Unsupported SASL mechanism: {0}")]
+    UnsupportedSaslMechanism(String),
+
+    #[error("Malformed SCRAM message: {0}")]
+    MalformedScramMessage(&'static str),
+
+    #[error("Invalid users file, line {line}: {msg}")]
+    InvalidUsersFile { line: usize, msg: &'static str },
+
     /// A stringified error from cloud.
     #[error("cloud: {0}")]
     CloudResponse(String),
@@ -85,3 +96,13 @@ pub enum PgSrvError {
     #[error(transparent)]
     Rustls(#[from] rustls::Error),
 }
+
+impl PgSrvError {
+    pub fn sql_state(&self) -> SqlState {
+        match self {
+            // TODO: Actually set appropriate codes for the other errors.
+            PgSrvError::InvalidUserOrPassword => SqlState::InvalidPassword,
+            _ => SqlState::InternalError,
+        }
+    }
+}
@@ -2,28 +2,49 @@ use std::collections::{HashMap, VecDeque};
 use std::ops::DerefMut;
 use std::sync::Arc;
 
-use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::datatypes::{DataType, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion::physical_plan::SendableRecordBatchStream;
 use datafusion::scalar::ScalarValue;
 use datafusion::variable::VarType;
 use datafusion_ext::vars::{Dialect, SessionVars};
 use futures::StreamExt;
 use parser::StatementWithExtensions;
+use pgrepr::error::PgReprError;
 use pgrepr::format::Format;
+use pgrepr::notice::SqlState;
 use pgrepr::scalar::Scalar;
-use sqlexec::context::local::{OutputFields, Portal, PreparedStatement};
+use pgrepr::types::pg_to_arrow_type;
+use sqlexec::cancel::BackendKey;
+use sqlexec::context::local::{
+    OutputFields,
+    Portal,
+    PreparedStatement,
+    TransactionStatus as SessionTransactionStatus,
+};
 use sqlexec::engine::{Engine, SessionStorageConfig};
 use sqlexec::session::{ExecutionResult, Session};
+use sqlexec::{CopyStdinSink, CopyStdioOptions};
 use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 use tokio_postgres::types::Type as PgType;
 use tracing::{debug, debug_span, warn, Instrument};
 use uuid::Uuid;
 
-use crate::auth::{LocalAuthenticator, PasswordMode};
-use crate::codec::server::{FramedConn, PgCodec};
+use crate::auth::{
+    constant_time_eq,
+    md5_challenge_response,
+    Credentials,
+    LocalAuthenticator,
+    PasswordMode,
+    ScramSecret,
+};
+use crate::codec::server::{FramedConn, PasswordMessageKind, PgCodec};
+use crate::copy::{copy_wire_format, CopyInDecoder, CopyOutEncoder};
 use crate::errors::{PgSrvError, Result};
 use crate::messages::{
     BackendMessage,
+    CopyResponse,
     DescribeObjectType,
     ErrorResponse,
     FieldDescriptionBuilder,
@@ -41,6 +62,7 @@ use crate::proxy::{
     GLAREDB_MEMORY_LIMIT_BYTES_KEY,
     GLAREDB_USER_ID_KEY,
 };
+use crate::scram::{ScramExchange, SCRAM_SHA_256};
 use crate::ssl::{Connection, SslConfig};
 
 pub struct ProtocolHandlerConfig {
@@ -97,8 +119,15 @@ impl ProtocolHandler {
                         }
                     }
                 }
-                StartupMessage::CancelRequest { .. } => {
-                    self.cancel(conn).await?;
+                StartupMessage::CancelRequest {
+                    process_id,
+                    secret_key,
+                    ..
+                } => {
+                    self.cancel(BackendKey {
+                        process_id,
+                        secret_key,
+                    });
                     return Ok(());
                 }
             }
@@ -131,6 +160,71 @@ impl ProtocolHandler {
         }
     }
 
+    /// Authenticate using an MD5 password challenge.
+    ///
+    /// Returns `false` if the connection closed before authentication
+    /// completed.
+    async fn authenticate_md5<C>(framed: &mut FramedConn<C>, hash: &str) -> Result<bool>
+    where
+        C: AsyncRead + AsyncWrite + Unpin,
+    {
+        let salt: [u8; 4] = rand::random();
+        framed
+            .send(BackendMessage::AuthenticationMD5Password { salt })
+            .await?;
+        match framed.read().await? {
+            Some(FrontendMessage::PasswordMessage { password }) => {
+                let expected = md5_challenge_response(hash, &salt);
+                if !constant_time_eq(password.as_bytes(), expected.as_bytes()) {
+                    return Err(PgSrvError::InvalidUserOrPassword);
+                }
+                Ok(true)
+            }
+            Some(other) => Err(PgSrvError::UnexpectedFrontendMessage(Box::new(other))),
+            None => Ok(false),
+        }
+    }
+
+    /// Authenticate using SCRAM-SHA-256.
+    ///
+    /// Returns `false` if the connection closed before authentication
+    /// completed.
+    async fn authenticate_scram<C>(framed: &mut FramedConn<C>, secret: ScramSecret) -> Result<bool>
+    where
+        C: AsyncRead + AsyncWrite + Unpin,
+    {
+        framed
+            .send(BackendMessage::AuthenticationSASL {
+                mechanisms: vec![SCRAM_SHA_256.to_string()],
+            })
+            .await?;
+
+        framed.set_password_message_kind(PasswordMessageKind::SASLInitialResponse);
+        let (exchange, server_first) = match framed.read().await? {
+            Some(FrontendMessage::SASLInitialResponse { mechanism, data }) => {
+                ScramExchange::start(secret, &mechanism, &data)?
+            }
+            Some(other) => return Err(PgSrvError::UnexpectedFrontendMessage(Box::new(other))),
+            None => return Ok(false),
+        };
+        framed
+            .send(BackendMessage::AuthenticationSASLContinue(server_first))
+            .await?;
+
+        framed.set_password_message_kind(PasswordMessageKind::SASLResponse);
+        let server_final = match framed.read().await? {
+            Some(FrontendMessage::SASLResponse { data }) => exchange.finish(&data)?,
+            Some(other) => return Err(PgSrvError::UnexpectedFrontendMessage(Box::new(other))),
+            None => return Ok(false),
+        };
+        framed.set_password_message_kind(PasswordMessageKind::Password);
+
+        framed
+            .send(BackendMessage::AuthenticationSASLFinal(server_final))
+            .await?;
+        Ok(true)
+    }
+
     /// Whether the server should be configured for integration testing.
     fn is_integration_testing_enabled(&self) -> bool {
         self.conf.integration_testing
@@ -204,10 +298,10 @@ impl ProtocolHandler {
                             Err(e) => {
                                 framed
                                     .send(
-                                        ErrorResponse::fatal_internal(format!(
-                                            "Failed to authenticate: {}",
-                                            e
-                                        ))
+                                        ErrorResponse::fatal(
+                                            e.sql_state(),
+                                            format!("Failed to authenticate: {}", e),
+                                        )
                                         .into(),
                                     )
                                     .await?;
@@ -223,6 +317,45 @@ impl ProtocolHandler {
                     None => return Ok(()),
                 }
             }
+            PasswordMode::RequireChallenge => {
+                let result = match self
+                    .conf
+                    .authenticator
+                    .credentials(&user_name, &database_name)
+                {
+                    Some(Credentials::Md5(hash)) => {
+                        Self::authenticate_md5(&mut framed, &hash).await
+                    }
+                    Some(Credentials::ScramSha256(secret)) => {
+                        Self::authenticate_scram(&mut framed, secret).await
+                    }
+                    // Go through the exchange with a secret that can't match
+                    // so we don't reveal which users exist.
+                    None => {
+                        let secret = ScramSecret::mock(
+                            &user_name,
+                            self.conf.authenticator.mock_auth_nonce(),
+                        );
+                        Self::authenticate_scram(&mut framed, secret).await
+                    }
+                };
+                match result {
+                    Ok(true) => framed.send(BackendMessage::AuthenticationOk).await?,
+                    Ok(false) => return Ok(()), // Connection closed
+                    Err(e) => {
+                        framed
+                            .send(
+                                ErrorResponse::fatal(
+                                    e.sql_state(),
+                                    format!("Failed to authenticate: {}", e),
+                                )
+                                .into(),
+                            )
+                            .await?;
+                        return Err(e);
+                    }
+                }
+            }
             PasswordMode::NoPassword { drop_auth_messages } => {
                 if drop_auth_messages {
                     // Send the message to frontend to ask for an auth message.
@@ -307,20 +440,26 @@ impl ProtocolHandler {
             framed.send(msg).await?;
         }
 
+        let key = sess.backend_key();
+        framed
+            .send(BackendMessage::BackendKeyData {
+                process_id: key.process_id,
+                secret_key: key.secret_key,
+            })
+            .await?;
+
         let cs = ClientSession::new(sess, framed);
         cs.run().await
     }
 
-    /// Cancel a connection.
+    /// Cancel the query running on another connection.
     ///
-    /// Unimplemented. The protocol states that there's no guarantee that
-    /// anything is actually canceled, so no-op is fine for now.
-    async fn cancel<C>(&self, _conn: Connection<C>) -> Result<()>
-    where
-        C: AsyncRead + AsyncWrite + Unpin,
-    {
-        debug!("cancel received (local)");
-        Ok(())
+    /// Nothing is sent back on the cancel connection, even if the key doesn't
+    /// match any session. The client learns whether the cancel worked from
+    /// the original connection.
+    fn cancel(&self, key: BackendKey) {
+        let canceled = self.engine.cancel_query(key);
+        debug!(process_id = key.process_id, %canceled, "cancel received (local)");
     }
 }
 
@@ -356,6 +495,17 @@ where
     }
 
     async fn run(mut self) -> Result<()> {
+        let result = self.run_inner().await;
+
+        // Discard any writes staged by a transaction the client didn't end.
+        if let Err(e) = self.session.close_transaction().await {
+            warn!(%e, "failed to rollback open transaction");
+        }
+
+        result
+    }
+
+    async fn run_inner(&mut self) -> Result<()> {
         self.ready_for_query().await?;
         loop {
             let msg = self.conn.read().await?;
@@ -410,6 +560,14 @@ where
                 FrontendMessage::Sync => self.sync().instrument(span).await?,
                 FrontendMessage::Flush => self.flush().instrument(span).await?,
                 FrontendMessage::Terminate => return Ok(()),
+                // Copy messages can still be in flight after a COPY FROM
+                // STDIN failed. The client will follow up with a sync or a
+                // new query.
+                FrontendMessage::CopyData(_)
+                | FrontendMessage::CopyDone
+                | FrontendMessage::CopyFail { .. } => {
+                    debug!("ignoring copy message outside of COPY FROM STDIN")
+                }
                 other => {
                     warn!(?other, "unsupported frontend message");
                     self.conn
@@ -428,7 +586,10 @@ where
     }
 
     /// Send an error response to the client.
+    ///
+    /// This also marks the current transaction block (if any) as failed.
     async fn send_error(&mut self, err: ErrorResponse) -> Result<()> {
+        self.session.fail_transaction();
         self.conn.send(err.into()).await?;
         Ok(())
     }
@@ -444,9 +605,13 @@ where
                 .await?;
         }
 
-        // TODO: Proper status.
+        let status = match self.session.transaction_status() {
+            SessionTransactionStatus::Idle => TransactionStatus::Idle,
+            SessionTransactionStatus::InBlock => TransactionStatus::InBlock,
+            SessionTransactionStatus::Failed => TransactionStatus::Failed,
+        };
         self.conn
-            .send(BackendMessage::ReadyForQuery(TransactionStatus::Idle))
+            .send(BackendMessage::ReadyForQuery(status))
             .await?;
         self.flush().await
     }
@@ -472,8 +637,6 @@ where
         let num_statements = stmts.len();
 
         for stmt in stmts {
-            // TODO: Ensure in transaction.
-
             // Note everything is using unnamed portals/prepared statements.
 
             const UNNAMED: String = String::new();
@@ -505,7 +668,7 @@ where
             }
 
             // Execute...
-            let stream = match session.execute_portal(&UNNAMED, 0).await {
+            let stream = match session.execute_portal(&UNNAMED).await {
                 Ok(stream) => stream,
                 Err(e) => {
                     self.send_error(e.into()).await?;
@@ -523,12 +686,19 @@ where
                 }
             }
 
-            Self::send_result(
+            let completed = Self::send_result(
                 conn,
                 stream,
                 session_do!(self, session, get_portal, &UNNAMED, get_encoding_state),
             )
             .await?;
+
+            // An error was sent while streaming, skip the rest of the
+            // statements.
+            if !completed {
+                session.fail_transaction();
+                return self.ready_for_query().await;
+            }
         }
 
         if num_statements == 0 {
@@ -620,9 +790,12 @@ where
         match object_type {
             DescribeObjectType::Statement => match self.session.get_prepared_statement(&name) {
                 Ok(stmt) => {
-                    // TODO: We don't accept parameters yet. So just send back
-                    // an empty paramaters list.
-                    conn.send(BackendMessage::ParameterDescription(Vec::new()))
+                    let param_oids = stmt
+                        .parameters()
+                        .into_iter()
+                        .map(|(pg_type, _)| pg_type.oid() as i32)
+                        .collect();
+                    conn.send(BackendMessage::ParameterDescription(param_oids))
                         .await?;
 
                     // Send back row description.
@@ -662,11 +835,9 @@ where
     }
 
     async fn execute(&mut self, portal: String, max_rows: i32) -> Result<()> {
-        // TODO: Ensure in transaction.
-
         let conn = &mut self.conn;
         let session = &mut self.session;
-        let stream = match session.execute_portal(&portal, max_rows).await {
+        let stream = match session.execute_portal(&portal).await {
             Ok(r) => r,
             Err(e) => return self.send_error(e.into()).await,
         };
@@ -674,12 +845,47 @@ where
         // TODO: This seems to be missing sending back row description. Is it
         // needed? If not, a comment needs to go here.
 
-        Self::send_result(
-            conn,
-            stream,
-            session_do!(self, session, get_portal, &portal, get_encoding_state),
-        )
-        .await
+        let encoding_state = session_do!(self, session, get_portal, &portal, get_encoding_state);
+
+        // A non-positive max rows means no limit.
+        let completed = match stream {
+            ExecutionResult::Query { stream } if max_rows > 0 => {
+                match Self::stream_batch(conn, stream, encoding_state, max_rows as usize).await? {
+                    StreamBatchResult::Complete { num_rows } => {
+                        // Later executes of the portal shouldn't run the
+                        // query again.
+                        if let Err(e) = session.complete_portal(&portal) {
+                            return self.send_error(e.into()).await;
+                        }
+                        Self::command_complete(conn, format!("SELECT {}", num_rows)).await?;
+                        true
+                    }
+                    StreamBatchResult::Suspended { stream } => {
+                        if let Err(e) = session.suspend_portal(&portal, stream) {
+                            return self.send_error(e.into()).await;
+                        }
+                        conn.send(BackendMessage::PortalSuspended).await?;
+                        true
+                    }
+                    StreamBatchResult::Error => false,
+                }
+            }
+            stream @ ExecutionResult::Query { .. } => {
+                let completed = Self::send_result(conn, stream, encoding_state).await?;
+                if completed {
+                    if let Err(e) = session.complete_portal(&portal) {
+                        return self.send_error(e.into()).await;
+                    }
+                }
+                completed
+            }
+            stream => Self::send_result(conn, stream, encoding_state).await?,
+        };
+
+        if !completed {
+            session.fail_transaction();
+        }
+        Ok(())
     }
 
     async fn close_object(&mut self, object_type: DescribeObjectType, name: String) -> Result<()> {
@@ -699,16 +905,26 @@ where
         Ok(())
     }
 
+    /// Send the result of an execution to the client.
+    ///
+    /// Returns `false` if an error was sent to the client while streaming the
+    /// results.
     async fn send_result(
         conn: &mut FramedConn<C>,
         stream: ExecutionResult,
         encoding_state: Vec<(PgType, Format)>,
-    ) -> Result<()> {
+    ) -> Result<bool> {
         match stream {
             ExecutionResult::Error(e) => return Err(e.into()),
             ExecutionResult::Query { stream, .. } => {
-                if let Some(num_rows) = Self::stream_batch(conn, stream, encoding_state).await? {
-                    Self::command_complete(conn, format!("SELECT {}", num_rows)).await?;
+                match Self::stream_batch(conn, stream, encoding_state, 0).await? {
+                    StreamBatchResult::Complete { num_rows } => {
+                        Self::command_complete(conn, format!("SELECT {}", num_rows)).await?
+                    }
+                    StreamBatchResult::Suspended { .. } => {
+                        unreachable!("stream not suspended without a row limit")
+                    }
+                    StreamBatchResult::Error => return Ok(false),
                 }
             }
             ExecutionResult::EmptyQuery => conn.send(BackendMessage::EmptyQueryResponse).await?,
@@ -721,12 +937,23 @@ where
                 Self::command_complete(conn, format!("INSERT 0 {rows_inserted}")).await?
             }
             ExecutionResult::CopySuccess => Self::command_complete(conn, "COPY").await?,
+            ExecutionResult::CopyIn {
+                schema,
+                options,
+                sink,
+            } => return Self::copy_in(conn, schema, options, sink).await,
+            ExecutionResult::CopyOut { options, stream } => {
+                return Self::copy_out(conn, options, stream).await
+            }
             ExecutionResult::DeleteSuccess { deleted_rows } => {
                 Self::command_complete(conn, format!("DELETE {}", deleted_rows)).await?
             }
             ExecutionResult::UpdateSuccess { updated_rows } => {
                 Self::command_complete(conn, format!("UPDATE {}", updated_rows)).await?
             }
+            ExecutionResult::MergeSuccess { merged_rows } => {
+                Self::command_complete(conn, format!("MERGE {}", merged_rows)).await?
+            }
             ExecutionResult::CreateTable => Self::command_complete(conn, "CREATE TABLE").await?,
             ExecutionResult::CreateDatabase => {
                 Self::command_complete(conn, "CREATE DATABASE").await?
@@ -751,6 +978,12 @@ where
             ExecutionResult::AlterTunnelRotateKeys => {
                 Self::command_complete(conn, "ALTER TUNNEL").await?
             }
+            ExecutionResult::RestoreTable => Self::command_complete(conn, "RESTORE TABLE").await?,
+            ExecutionResult::Vacuum => Self::command_complete(conn, "VACUUM").await?,
+            ExecutionResult::Optimize => Self::command_complete(conn, "OPTIMIZE").await?,
+            ExecutionResult::RefreshMaterializedView => {
+                Self::command_complete(conn, "REFRESH MATERIALIZED VIEW").await?
+            }
             ExecutionResult::Set => Self::command_complete(conn, "SET").await?,
             ExecutionResult::DropTables => Self::command_complete(conn, "DROP TABLE").await?,
             ExecutionResult::DropViews => Self::command_complete(conn, "DROP VIEW").await?,
@@ -760,10 +993,167 @@ where
             ExecutionResult::DropCredentials => {
                 Self::command_complete(conn, "DROP CREDENTIALS").await?
             }
+            ExecutionResult::CreateRole => Self::command_complete(conn, "CREATE ROLE").await?,
+            ExecutionResult::DropRole => Self::command_complete(conn, "DROP ROLE").await?,
+            ExecutionResult::CreateSequence => {
+                Self::command_complete(conn, "CREATE SEQUENCE").await?
+            }
+            ExecutionResult::DropSequences => Self::command_complete(conn, "DROP SEQUENCE").await?,
+            ExecutionResult::Grant => Self::command_complete(conn, "GRANT").await?,
+            ExecutionResult::Revoke => Self::command_complete(conn, "REVOKE").await?,
+            ExecutionResult::Comment => Self::command_complete(conn, "COMMENT").await?,
         };
+        Ok(true)
+    }
+
+    /// Receive the data for a `COPY .. FROM STDIN` from the client, sending
+    /// the decoded rows to the sink.
+    ///
+    /// Returns `false` if an error was sent to the client.
+    async fn copy_in(
+        conn: &mut FramedConn<C>,
+        schema: SchemaRef,
+        options: CopyStdioOptions,
+        mut sink: CopyStdinSink,
+    ) -> Result<bool> {
+        let format = copy_wire_format(&options);
+        conn.send(BackendMessage::CopyInResponse(CopyResponse {
+            format,
+            column_formats: vec![format; schema.fields().len()],
+        }))
+        .await?;
+        conn.flush().await?;
+
+        let mut decoder = CopyInDecoder::new(schema, options);
+        loop {
+            let msg = match conn.read().await? {
+                Some(msg) => msg,
+                None => {
+                    sink.abort().await;
+                    return Err(PgSrvError::Internal(
+                        "connection closed during COPY FROM STDIN".to_string(),
+                    ));
+                }
+            };
+
+            let batches = match msg {
+                FrontendMessage::CopyData(data) => decoder.decode(&data),
+                FrontendMessage::CopyDone => break,
+                FrontendMessage::CopyFail { message } => {
+                    sink.abort().await;
+                    conn.send(
+                        ErrorResponse::error(
+                            SqlState::QueryCanceled,
+                            format!("COPY from stdin failed: {message}"),
+                        )
+                        .into(),
+                    )
+                    .await?;
+                    return Ok(false);
+                }
+                // Flush and sync are ignored during the copy.
+                FrontendMessage::Flush | FrontendMessage::Sync => continue,
+                other => {
+                    warn!(?other, "unexpected message during COPY FROM STDIN");
+                    sink.abort().await;
+                    conn.send(
+                        ErrorResponse::error(
+                            SqlState::ProtocolViolation,
+                            format!(
+                                "unexpected message type during COPY from stdin: {}",
+                                other.name()
+                            ),
+                        )
+                        .into(),
+                    )
+                    .await?;
+                    return Ok(false);
+                }
+            };
+
+            if let Err(e) = Self::copy_in_send(&mut sink, batches).await {
+                sink.abort().await;
+                conn.send(e.into()).await?;
+                return Ok(false);
+            }
+        }
+
+        if let Err(e) = Self::copy_in_send(&mut sink, decoder.finish()).await {
+            sink.abort().await;
+            conn.send(e.into()).await?;
+            return Ok(false);
+        }
+
+        match sink.finish().await {
+            Ok(num_rows) => Self::command_complete(conn, format!("COPY {num_rows}")).await?,
+            Err(e) => {
+                conn.send(ErrorResponse::from(e).into()).await?;
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    async fn copy_in_send(
+        sink: &mut CopyStdinSink,
+        batches: Result<Vec<RecordBatch>, ErrorResponse>,
+    ) -> Result<(), ErrorResponse> {
+        for batch in batches? {
+            sink.send(batch).await?;
+        }
         Ok(())
     }
 
+    /// Send the data for a `COPY .. TO STDOUT` to the client.
+    ///
+    /// Returns `false` if an error was sent to the client.
+    async fn copy_out(
+        conn: &mut FramedConn<C>,
+        options: CopyStdioOptions,
+        mut stream: SendableRecordBatchStream,
+    ) -> Result<bool> {
+        let schema = stream.schema();
+        let format = copy_wire_format(&options);
+        conn.send(BackendMessage::CopyOutResponse(CopyResponse {
+            format,
+            column_formats: vec![format; schema.fields().len()],
+        }))
+        .await?;
+
+        let encoder = CopyOutEncoder::new(&schema, options);
+        if let Some(header) = encoder.header(&schema) {
+            conn.send(BackendMessage::CopyData(header)).await?;
+        }
+
+        let mut num_rows = 0;
+        while let Some(result) = stream.next().await {
+            let batch = match result {
+                Ok(batch) => batch,
+                Err(e) => {
+                    conn.send(ErrorResponse::from(e).into()).await?;
+                    return Ok(false);
+                }
+            };
+            for row_idx in 0..batch.num_rows() {
+                match encoder.encode_row(&batch, row_idx) {
+                    Ok(data) => conn.send(BackendMessage::CopyData(data)).await?,
+                    Err(e) => {
+                        conn.send(e.into()).await?;
+                        return Ok(false);
+                    }
+                }
+            }
+            num_rows += batch.num_rows();
+        }
+
+        if let Some(trailer) = encoder.trailer() {
+            conn.send(BackendMessage::CopyData(trailer)).await?;
+        }
+        conn.send(BackendMessage::CopyDone).await?;
+        Self::command_complete(conn, format!("COPY {num_rows}")).await?;
+        Ok(true)
+    }
+
     /// Convert an arrow schema into a row descriptor and send it to the client.
     async fn send_row_descriptor(conn: &mut FramedConn<C>, fields: OutputFields<'_>) -> Result<()> {
         let mut row_description = Vec::with_capacity(fields.len());
@@ -779,38 +1169,54 @@ where
         Ok(())
     }
 
-    /// Streams the batch to the client, returns an optional total number of
-    /// rows sent. `None` rows sent means that an error response was sent.
+    /// Streams the batch to the client.
+    ///
+    /// Stops once `max_rows` rows have been sent if it's non-zero, returning
+    /// the stream with the rows that are left.
     async fn stream_batch(
         conn: &mut FramedConn<C>,
         mut stream: SendableRecordBatchStream,
         encoding_state: Vec<(PgType, Format)>,
-    ) -> Result<Option<usize>> {
+        max_rows: usize,
+    ) -> Result<StreamBatchResult> {
         conn.set_encoding_state(encoding_state);
         let mut num_rows = 0;
         while let Some(result) = stream.next().await {
             let batch = match result {
                 Ok(r) => r,
                 Err(e) => {
-                    conn.send(
-                        ErrorResponse::error(
-                            pgrepr::notice::SqlState::InternalError,
-                            e.to_string(),
-                        )
-                        .into(),
-                    )
-                    .await?;
-                    return Ok(None);
+                    conn.send(ErrorResponse::from(e).into()).await?;
+                    return Ok(StreamBatchResult::Error);
                 }
             };
-            num_rows += batch.num_rows();
-            for row_idx in 0..batch.num_rows() {
+
+            let mut num_to_send = batch.num_rows();
+            if max_rows > 0 {
+                num_to_send = num_to_send.min(max_rows - num_rows);
+            }
+            for row_idx in 0..num_to_send {
                 // Clone is cheapish here, all columns behind an arc.
                 conn.send(BackendMessage::DataRow(batch.clone(), row_idx))
                     .await?;
             }
+            num_rows += num_to_send;
+
+            if max_rows > 0 && num_rows == max_rows {
+                // Put back what's left of the batch for the next execute.
+                let remaining = batch.slice(num_to_send, batch.num_rows() - num_to_send);
+                let schema = stream.schema();
+                let stream = if remaining.num_rows() > 0 {
+                    Box::pin(RecordBatchStreamAdapter::new(
+                        schema,
+                        futures::stream::once(async { Ok(remaining) }).chain(stream),
+                    )) as SendableRecordBatchStream
+                } else {
+                    stream
+                };
+                return Ok(StreamBatchResult::Suspended { stream });
+            }
         }
-        Ok(Some(num_rows))
+        Ok(StreamBatchResult::Complete { num_rows })
     }
 
     async fn command_complete(conn: &mut FramedConn<C>, tag: impl Into<String>) -> Result<()> {
@@ -819,6 +1225,16 @@ where
     }
 }
 
+/// Result of streaming batches to the client.
+enum StreamBatchResult {
+    /// All rows were sent.
+    Complete { num_rows: usize },
+    /// The row limit was reached. Holds the rows that have yet to be sent.
+    Suspended { stream: SendableRecordBatchStream },
+    /// An error was sent to the client.
+    Error,
+}
+
 /// Parse a sql string, returning an error response if failed to parse.
 fn parse_sql(
     session_vars: SessionVars,
@@ -864,13 +1280,10 @@ fn decode_param_scalars(
         })?;
 
         match typ {
-            Some(typ) => {
-                let scalar = {
-                    match val.as_deref() {
-                        None => ScalarValue::Null,
-                        Some(v) => Scalar::decode_with_format(format, v, &typ.0)?
-                            .into_datafusion(&typ.1)?,
-                    }
+            Some((pg_type, arrow_type)) => {
+                let scalar = match val.as_deref() {
+                    None => ScalarValue::try_from(arrow_type).unwrap_or(ScalarValue::Null),
+                    Some(v) => decode_param_scalar(format, v, pg_type, arrow_type)?,
                 };
                 scalars.push(scalar);
             }
@@ -886,6 +1299,44 @@ fn decode_param_scalars(
     Ok(scalars)
 }
 
+/// Decode a single parameter value as the given postgres type, casting it to
+/// the arrow type the statement expects.
+///
+/// Values in text format for types we can't decode directly are decoded as
+/// text and cast.
+fn decode_param_scalar(
+    format: Format,
+    buf: &[u8],
+    pg_type: &PgType,
+    arrow_type: &DataType,
+) -> Result<ScalarValue, ErrorResponse> {
+    let (scalar, decoded_type) = match Scalar::decode_with_format(format, buf, pg_type) {
+        Ok(scalar) => (scalar, pg_type),
+        Err(PgReprError::UnsupportedPgTypeForDecode(_)) if matches!(format, Format::Text) => (
+            Scalar::decode_with_format(format, buf, &PgType::TEXT)?,
+            &PgType::TEXT,
+        ),
+        Err(e) => return Err(e.into()),
+    };
+
+    let decoded_arrow_type = pg_to_arrow_type(decoded_type).unwrap_or(DataType::Utf8);
+    let scalar = scalar.into_datafusion(&decoded_arrow_type)?;
+    if &decoded_arrow_type == arrow_type {
+        return Ok(scalar);
+    }
+
+    scalar.cast_to(arrow_type).map_err(|_| {
+        ErrorResponse::error(
+            SqlState::InvalidTextRepresentation,
+            format!(
+                "invalid input syntax for type {}: \"{}\"",
+                pg_type,
+                String::from_utf8_lossy(buf)
+            ),
+        )
+    })
+}
+
 /// Returns a vector with all the formats extended to the default "text".
 fn all_text_formats(num: usize) -> Vec<Format> {
     extend_formats(Vec::new(), num).unwrap()
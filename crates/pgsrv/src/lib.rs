@@ -6,9 +6,9 @@
 //! - <https://www.postgresql.org/docs/current/protocol-flow.html>
 //! - <https://www.postgresql.org/docs/current/protocol-message-formats.html>
 //!
-//! We currently implement most of the Simple Query Flow and the Extended Query
-//! Flow. We do not implement the copy protocol (yet), or the functional call
-//! protocol (never).
+//! We currently implement most of the Simple Query Flow, the Extended Query
+//! Flow, and the copy protocol for `COPY .. FROM STDIN` and `COPY .. TO
+//! STDOUT`. We do not implement the functional call protocol (never).
 pub mod auth;
 pub mod errors;
 pub mod handler;
@@ -16,4 +16,6 @@ pub mod proxy;
 pub mod ssl;
 
 mod codec;
+mod copy;
 mod messages;
+mod scram;
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::DataFusionError;
 use pgrepr::error::PgReprError;
 use pgrepr::format::Format;
 use sqlexec::errors::ExecError;
@@ -24,6 +25,8 @@ pub enum StartupMessage {
     },
     CancelRequest {
         version: i32,
+        process_id: i32,
+        secret_key: i32,
     },
     StartupRequest {
         version: i32,
@@ -38,6 +41,15 @@ pub enum FrontendMessage {
     Query { sql: String },
     /// An encrypted or unencrypted password.
     PasswordMessage { password: String },
+    /// First message sent by the frontend during SASL authentication.
+    SASLInitialResponse {
+        /// Name of the SASL mechanism selected by the frontend.
+        mechanism: String,
+        /// Mechanism specific initial response.
+        data: Vec<u8>,
+    },
+    /// Subsequent messages sent by the frontend during SASL authentication.
+    SASLResponse { data: Vec<u8> },
     /// An extended query parse message.
     Parse {
         /// The name of the prepared statement. An empty string denotes the
@@ -98,6 +110,15 @@ pub enum FrontendMessage {
         /// Name of the object to close.
         name: String,
     },
+    /// Data sent during a `COPY .. FROM STDIN`.
+    CopyData(Vec<u8>),
+    /// The client finished sending data for a `COPY .. FROM STDIN`.
+    CopyDone,
+    /// The client aborted a `COPY .. FROM STDIN`.
+    CopyFail {
+        /// Reason for the failure.
+        message: String,
+    },
     /// Synchronize after running through the extended query protocol.
     Sync,
     /// Flush the connection.
@@ -111,11 +132,16 @@ impl FrontendMessage {
         match self {
             FrontendMessage::Query { .. } => "query",
             FrontendMessage::PasswordMessage { .. } => "password",
+            FrontendMessage::SASLInitialResponse { .. } => "sasl_initial_response",
+            FrontendMessage::SASLResponse { .. } => "sasl_response",
             FrontendMessage::Parse { .. } => "parse",
             FrontendMessage::Bind { .. } => "bind",
             FrontendMessage::Describe { .. } => "describe",
             FrontendMessage::Execute { .. } => "execute",
             FrontendMessage::Close { .. } => "close",
+            FrontendMessage::CopyData(_) => "copy_data",
+            FrontendMessage::CopyDone => "copy_done",
+            FrontendMessage::CopyFail { .. } => "copy_fail",
             FrontendMessage::Flush => "flush",
             FrontendMessage::Sync => "sync",
             FrontendMessage::Terminate => "terminate",
@@ -123,7 +149,12 @@ impl FrontendMessage {
     }
 
     pub(crate) fn is_auth_message(&self) -> bool {
-        matches!(self, FrontendMessage::PasswordMessage { .. })
+        matches!(
+            self,
+            FrontendMessage::PasswordMessage { .. }
+                | FrontendMessage::SASLInitialResponse { .. }
+                | FrontendMessage::SASLResponse { .. }
+        )
     }
 }
 
@@ -140,6 +171,17 @@ pub enum BackendMessage {
     NoticeResponse(pgrepr::notice::Notice),
     AuthenticationOk,
     AuthenticationCleartextPassword,
+    AuthenticationMD5Password {
+        salt: [u8; 4],
+    },
+    /// Start of SASL authentication, listing the supported mechanisms.
+    AuthenticationSASL {
+        mechanisms: Vec<String>,
+    },
+    /// A SASL challenge.
+    AuthenticationSASLContinue(Vec<u8>),
+    /// Additional data sent after SASL authentication completes.
+    AuthenticationSASLFinal(Vec<u8>),
     ParameterStatus { key: String, val: String },
     EmptyQueryResponse,
     ReadyForQuery(TransactionStatus),
@@ -150,7 +192,29 @@ pub enum BackendMessage {
     BindComplete,
     CloseComplete,
     NoData,
+    BackendKeyData {
+        process_id: i32,
+        secret_key: i32,
+    },
+    /// The row limit of an execute was reached before all rows were sent.
+    PortalSuspended,
     ParameterDescription(Vec<i32>),
+    /// Start of a `COPY .. FROM STDIN`.
+    CopyInResponse(CopyResponse),
+    /// Start of a `COPY .. TO STDOUT`.
+    CopyOutResponse(CopyResponse),
+    CopyData(Vec<u8>),
+    CopyDone,
+}
+
+/// Describes the data exchanged during a COPY to or from the client.
+#[derive(Debug)]
+pub struct CopyResponse {
+    /// Overall format of the data, text (including CSV) or binary.
+    pub format: Format,
+    /// Format for each column. All columns must be binary if the overall
+    /// format is binary, and text otherwise.
+    pub column_formats: Vec<Format>,
 }
 
 impl From<ErrorResponse> for BackendMessage {
@@ -207,9 +271,13 @@ impl ErrorResponse {
     }
 
     pub fn fatal_internal(msg: impl Into<String>) -> ErrorResponse {
+        Self::fatal(pgrepr::notice::SqlState::InternalError, msg)
+    }
+
+    pub fn fatal(code: pgrepr::notice::SqlState, msg: impl Into<String>) -> ErrorResponse {
         ErrorResponse {
             severity: ErrorSeverity::Fatal,
-            code: pgrepr::notice::SqlState::InternalError,
+            code,
             message: msg.into(),
         }
     }
@@ -217,15 +285,27 @@ impl ErrorResponse {
 
 impl From<ExecError> for ErrorResponse {
     fn from(e: ExecError) -> Self {
-        // TODO: Actually set appropriate codes.
-        ErrorResponse::error_internal(e.to_string())
+        ErrorResponse::error(e.sql_state(), e.to_string())
+    }
+}
+
+impl From<DataFusionError> for ErrorResponse {
+    fn from(e: DataFusionError) -> Self {
+        match e {
+            // Errors from our own execution plans are wrapped as external
+            // errors, unwrap them to get the right code.
+            DataFusionError::External(e) => match e.downcast::<ExecError>() {
+                Ok(e) => (*e).into(),
+                Err(e) => ErrorResponse::error_internal(DataFusionError::External(e).to_string()),
+            },
+            e => ErrorResponse::error_internal(e.to_string()),
+        }
     }
 }
 
 impl From<&PgSrvError> for ErrorResponse {
     fn from(e: &PgSrvError) -> Self {
-        // TODO: Actually set appropriate codes.
-        ErrorResponse::error_internal(e.to_string())
+        ErrorResponse::error(e.sql_state(), e.to_string())
     }
 }
 
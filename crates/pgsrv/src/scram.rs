@@ -0,0 +1,383 @@
+//! Server side of SCRAM-SHA-256 authentication.
+//!
+//! See <https://www.postgresql.org/docs/current/sasl-authentication.html> and
+//! RFC 5802 for details on the exchange. Channel binding is not supported.
+use base64::prelude::*;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::auth::constant_time_eq;
+use crate::errors::{PgSrvError, Result};
+
+/// Name of the only SASL mechanism we support.
+pub const SCRAM_SHA_256: &str = "SCRAM-SHA-256";
+
+/// Iteration count used when deriving secrets from plaintext passwords. Same
+/// as the Postgres default.
+const DEFAULT_ITERATIONS: u32 = 4096;
+
+/// Length in bytes of the random part of the server nonce.
+const NONCE_LEN: usize = 18;
+
+/// Length in bytes of salts generated for plaintext passwords.
+const SALT_LEN: usize = 16;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// A SCRAM-SHA-256 secret, as stored by Postgres.
+///
+/// The textual form is `SCRAM-SHA-256$<iterations>:<salt>$<StoredKey>:<ServerKey>`
+/// with the salt and keys base64 encoded.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ScramSecret {
+    iterations: u32,
+    salt: Vec<u8>,
+    stored_key: [u8; 32],
+    server_key: [u8; 32],
+}
+
+impl ScramSecret {
+    /// Parse a secret in the format Postgres stores them.
+    pub fn parse(s: &str) -> Option<ScramSecret> {
+        let s = s.strip_prefix(SCRAM_SHA_256)?.strip_prefix('$')?;
+        let (params, keys) = s.split_once('$')?;
+        let (iterations, salt) = params.split_once(':')?;
+        let (stored_key, server_key) = keys.split_once(':')?;
+
+        let iterations = iterations.parse().ok().filter(|i| *i > 0)?;
+        let salt = BASE64_STANDARD.decode(salt).ok()?;
+        let stored_key = BASE64_STANDARD.decode(stored_key).ok()?.try_into().ok()?;
+        let server_key = BASE64_STANDARD.decode(server_key).ok()?.try_into().ok()?;
+
+        Some(ScramSecret {
+            iterations,
+            salt,
+            stored_key,
+            server_key,
+        })
+    }
+
+    /// Derive a secret from a plaintext password using a random salt.
+    pub fn from_password(password: &str) -> ScramSecret {
+        let salt: [u8; SALT_LEN] = rand::random();
+        Self::from_password_with_salt(password, &salt, DEFAULT_ITERATIONS)
+    }
+
+    fn from_password_with_salt(password: &str, salt: &[u8], iterations: u32) -> ScramSecret {
+        // Note that we skip SASLprep normalization of the password. This only
+        // makes a difference for non-ASCII passwords.
+        let salted = salted_password(password.as_bytes(), salt, iterations);
+        let client_key = hmac(&salted, b"Client Key");
+        ScramSecret {
+            iterations,
+            salt: salt.to_vec(),
+            stored_key: Sha256::digest(client_key).into(),
+            server_key: hmac(&salted, b"Server Key"),
+        }
+    }
+
+    /// A secret no password matches.
+    ///
+    /// Used to go through the motions of the exchange for users that don't
+    /// exist, so that clients can't tell if a user exists or not. Like
+    /// Postgres, the salt is derived from the user name and a server secret so
+    /// that it's the same on every attempt, as it would be for a real user.
+    pub fn mock(user: &str, nonce: &[u8]) -> ScramSecret {
+        let digest = Sha256::new()
+            .chain_update(user.as_bytes())
+            .chain_update(nonce)
+            .finalize();
+        ScramSecret {
+            iterations: DEFAULT_ITERATIONS,
+            salt: digest[..SALT_LEN].to_vec(),
+            stored_key: rand::random(),
+            server_key: rand::random(),
+        }
+    }
+
+    /// Check if a plaintext password matches this secret.
+    pub fn verify_password(&self, password: &str) -> bool {
+        let other = Self::from_password_with_salt(password, &self.salt, self.iterations);
+        constant_time_eq(&self.stored_key, &other.stored_key)
+            && constant_time_eq(&self.server_key, &other.server_key)
+    }
+}
+
+impl std::fmt::Display for ScramSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{SCRAM_SHA_256}${}:{}${}:{}",
+            self.iterations,
+            BASE64_STANDARD.encode(&self.salt),
+            BASE64_STANDARD.encode(self.stored_key),
+            BASE64_STANDARD.encode(self.server_key),
+        )
+    }
+}
+
+/// State of an exchange after the server-first-message has been sent.
+pub struct ScramExchange {
+    secret: ScramSecret,
+    /// Base64 encoded GS2 header the client sent, which must be echoed back
+    /// in the final message.
+    gs2_header: String,
+    nonce: String,
+    client_first_bare: String,
+    server_first: String,
+}
+
+impl ScramExchange {
+    /// Handle the client-first-message sent with the initial SASL response,
+    /// returning the exchange along with the server-first-message to send.
+    pub fn start(
+        secret: ScramSecret,
+        mechanism: &str,
+        client_first: &[u8],
+    ) -> Result<(ScramExchange, Vec<u8>)> {
+        if mechanism != SCRAM_SHA_256 {
+            return Err(PgSrvError::UnsupportedSaslMechanism(mechanism.to_string()));
+        }
+        let client_first = std::str::from_utf8(client_first)
+            .map_err(|_| PgSrvError::MalformedScramMessage("invalid utf8"))?;
+
+        // gs2-header: channel binding flag, optional authzid.
+        let (cbind_flag, rest) = client_first
+            .split_once(',')
+            .ok_or(PgSrvError::MalformedScramMessage("missing gs2 header"))?;
+        match cbind_flag {
+            // Client doesn't support channel binding, or supports it but
+            // thinks the server doesn't.
+            "n" | "y" => (),
+            _ if cbind_flag.starts_with("p=") => {
+                return Err(PgSrvError::MalformedScramMessage(
+                    "channel binding is not supported",
+                ))
+            }
+            _ => return Err(PgSrvError::MalformedScramMessage("invalid gs2 header")),
+        }
+        let (authzid, client_first_bare) = rest
+            .split_once(',')
+            .ok_or(PgSrvError::MalformedScramMessage("missing gs2 header"))?;
+        if !authzid.is_empty() {
+            return Err(PgSrvError::MalformedScramMessage(
+                "authorization identity is not supported",
+            ));
+        }
+
+        // client-first-message-bare: username and nonce. The username is
+        // ignored, the user from the startup message is used instead.
+        let mut attrs = client_first_bare.split(',');
+        if !attrs.next().is_some_and(|a| a.starts_with("n=")) {
+            return Err(PgSrvError::MalformedScramMessage("missing username"));
+        }
+        let client_nonce = attrs
+            .next()
+            .and_then(|a| a.strip_prefix("r="))
+            .filter(|n| !n.is_empty())
+            .ok_or(PgSrvError::MalformedScramMessage("missing nonce"))?;
+
+        let server_nonce = rand::random::<[u8; NONCE_LEN]>();
+        let nonce = format!("{client_nonce}{}", BASE64_STANDARD.encode(server_nonce));
+        let server_first = format!(
+            "r={nonce},s={},i={}",
+            BASE64_STANDARD.encode(&secret.salt),
+            secret.iterations
+        );
+
+        let gs2_len = client_first.len() - client_first_bare.len();
+        let exchange = ScramExchange {
+            secret,
+            gs2_header: BASE64_STANDARD.encode(&client_first[..gs2_len]),
+            nonce,
+            client_first_bare: client_first_bare.to_string(),
+            server_first: server_first.clone(),
+        };
+
+        Ok((exchange, server_first.into_bytes()))
+    }
+
+    /// Verify the client-final-message, returning the server-final-message to
+    /// send if the client proved it knows the password.
+    pub fn finish(self, client_final: &[u8]) -> Result<Vec<u8>> {
+        let client_final = std::str::from_utf8(client_final)
+            .map_err(|_| PgSrvError::MalformedScramMessage("invalid utf8"))?;
+
+        let (without_proof, proof) = client_final
+            .rsplit_once(",p=")
+            .ok_or(PgSrvError::MalformedScramMessage("missing proof"))?;
+
+        let mut attrs = without_proof.split(',');
+        let channel_binding = attrs
+            .next()
+            .and_then(|a| a.strip_prefix("c="))
+            .ok_or(PgSrvError::MalformedScramMessage("missing channel binding"))?;
+        if channel_binding != self.gs2_header {
+            return Err(PgSrvError::MalformedScramMessage(
+                "unexpected channel binding",
+            ));
+        }
+        let nonce = attrs
+            .next()
+            .and_then(|a| a.strip_prefix("r="))
+            .ok_or(PgSrvError::MalformedScramMessage("missing nonce"))?;
+        if nonce != self.nonce {
+            return Err(PgSrvError::MalformedScramMessage("nonce mismatch"));
+        }
+
+        let proof: [u8; 32] = BASE64_STANDARD
+            .decode(proof)
+            .ok()
+            .and_then(|p| p.try_into().ok())
+            .ok_or(PgSrvError::MalformedScramMessage("invalid proof"))?;
+
+        let auth_message = format!(
+            "{},{},{}",
+            self.client_first_bare, self.server_first, without_proof
+        );
+
+        let client_signature = hmac(&self.secret.stored_key, auth_message.as_bytes());
+        let mut client_key = proof;
+        for (k, s) in client_key.iter_mut().zip(client_signature) {
+            *k ^= s;
+        }
+        let stored_key: [u8; 32] = Sha256::digest(client_key).into();
+        if !constant_time_eq(&stored_key, &self.secret.stored_key) {
+            return Err(PgSrvError::InvalidUserOrPassword);
+        }
+
+        let server_signature = hmac(&self.secret.server_key, auth_message.as_bytes());
+        Ok(format!("v={}", BASE64_STANDARD.encode(server_signature)).into_bytes())
+    }
+}
+
+fn hmac(key: &[u8], msg: &[u8]) -> [u8; 32] {
+    let mut mac = HmacSha256::new_from_slice(key).expect("hmac accepts keys of any length");
+    mac.update(msg);
+    mac.finalize().into_bytes().into()
+}
+
+/// The `Hi` function from RFC 5802, which is PBKDF2 with HMAC-SHA-256.
+fn salted_password(password: &[u8], salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mac = HmacSha256::new_from_slice(password).expect("hmac accepts keys of any length");
+
+    let mut prev = mac.clone();
+    prev.update(salt);
+    prev.update(&1_u32.to_be_bytes());
+    let mut prev: [u8; 32] = prev.finalize().into_bytes().into();
+
+    let mut result = prev;
+    for _ in 1..iterations {
+        let mut next = mac.clone();
+        next.update(&prev);
+        prev = next.finalize().into_bytes().into();
+        for (r, p) in result.iter_mut().zip(prev) {
+            *r ^= p;
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use postgres_protocol::authentication::sasl::{ChannelBinding, ScramSha256};
+
+    use super::*;
+
+    fn exchange(secret: ScramSecret, password: &str, binding: ChannelBinding) -> Result<()> {
+        let mut client = ScramSha256::new(password.as_bytes(), binding);
+
+        let (exchange, server_first) =
+            ScramExchange::start(secret, SCRAM_SHA_256, client.message())?;
+        client.update(&server_first).unwrap();
+
+        let server_final = exchange.finish(client.message())?;
+        client.finish(&server_final).unwrap();
+        Ok(())
+    }
+
+    #[test]
+    fn secret_roundtrip() {
+        let stored = postgres_protocol::password::scram_sha_256(b"hunter2");
+        let secret = ScramSecret::parse(&stored).unwrap();
+        assert_eq!(stored, secret.to_string());
+
+        assert!(ScramSecret::parse("md5c0ffee").is_none());
+        assert!(ScramSecret::parse("SCRAM-SHA-256$4096:abc").is_none());
+        assert!(ScramSecret::parse("SCRAM-SHA-256$0:c2FsdA==$c2FsdA==:c2FsdA==").is_none());
+    }
+
+    #[test]
+    fn verify_password() {
+        let secret = ScramSecret::from_password("hunter2");
+        assert!(secret.verify_password("hunter2"));
+        assert!(!secret.verify_password("hunter3"));
+        assert!(!ScramSecret::mock("alice", b"nonce").verify_password(""));
+
+        // Secrets generated the same way postgres does.
+        let stored = postgres_protocol::password::scram_sha_256(b"hunter2");
+        assert!(ScramSecret::parse(&stored)
+            .unwrap()
+            .verify_password("hunter2"));
+    }
+
+    #[test]
+    fn exchange_with_client() {
+        let secret = ScramSecret::from_password("hunter2");
+
+        exchange(secret.clone(), "hunter2", ChannelBinding::unsupported()).unwrap();
+        exchange(secret.clone(), "hunter2", ChannelBinding::unrequested()).unwrap();
+
+        let err = exchange(secret, "hunter3", ChannelBinding::unsupported()).unwrap_err();
+        assert!(matches!(err, PgSrvError::InvalidUserOrPassword));
+
+        let err = exchange(
+            ScramSecret::mock("alice", b"nonce"),
+            "",
+            ChannelBinding::unsupported(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, PgSrvError::InvalidUserOrPassword));
+    }
+
+    #[test]
+    fn mock_salt_is_stable() {
+        let salt = |user: &str, nonce: &[u8]| ScramSecret::mock(user, nonce).salt;
+
+        assert_eq!(salt("alice", b"nonce"), salt("alice", b"nonce"));
+        assert_ne!(salt("alice", b"nonce"), salt("bob", b"nonce"));
+        assert_ne!(salt("alice", b"nonce"), salt("alice", b"other"));
+    }
+
+    #[test]
+    fn malformed_messages() {
+        let secret = ScramSecret::from_password("hunter2");
+
+        let err = ScramExchange::start(secret.clone(), "SCRAM-SHA-256-PLUS", b"p=tls,,n=,r=abc");
+        assert!(matches!(err, Err(PgSrvError::UnsupportedSaslMechanism(_))));
+
+        for msg in [
+            &b"n=,r=abc"[..],
+            b"p=tls-server-end-point,,n=,r=abc",
+            b"n,a=someone,n=,r=abc",
+            b"n,,n=,r=",
+        ] {
+            let result = ScramExchange::start(secret.clone(), SCRAM_SHA_256, msg);
+            assert!(
+                matches!(result, Err(PgSrvError::MalformedScramMessage(_))),
+                "{}",
+                String::from_utf8_lossy(msg)
+            );
+        }
+
+        let (exchange, _) = ScramExchange::start(secret, SCRAM_SHA_256, b"n,,n=,r=abc").unwrap();
+        let err = exchange
+            .finish(b"c=biws,r=abcdef,p=AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=")
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            PgSrvError::MalformedScramMessage("nonce mismatch")
+        ));
+    }
+}
@@ -10,6 +10,7 @@ use super::options::{
     CredentialsOptions,
     DatabaseOptions,
     InternalColumnDefinition,
+    MaterializedViewOptions,
     TableOptionsV0,
     TunnelOptions,
 };
@@ -118,6 +119,8 @@ pub enum CatalogEntry {
     Tunnel(TunnelEntry),
     Function(FunctionEntry),
     Credentials(CredentialsEntry),
+    Role(RoleEntry),
+    Sequence(SequenceEntry),
 }
 
 impl CatalogEntry {
@@ -130,6 +133,8 @@ impl CatalogEntry {
             CatalogEntry::Tunnel(_) => EntryType::Tunnel,
             CatalogEntry::Function(_) => EntryType::Function,
             CatalogEntry::Credentials(_) => EntryType::Credentials,
+            CatalogEntry::Role(_) => EntryType::Role,
+            CatalogEntry::Sequence(_) => EntryType::Sequence,
         }
     }
 
@@ -143,6 +148,8 @@ impl CatalogEntry {
             CatalogEntry::Tunnel(tunnel) => &tunnel.meta,
             CatalogEntry::Function(func) => &func.meta,
             CatalogEntry::Credentials(creds) => &creds.meta,
+            CatalogEntry::Role(role) => &role.meta,
+            CatalogEntry::Sequence(seq) => &seq.meta,
         }
     }
 
@@ -156,6 +163,8 @@ impl CatalogEntry {
             CatalogEntry::Tunnel(tunnel) => &mut tunnel.meta,
             CatalogEntry::Function(func) => &mut func.meta,
             CatalogEntry::Credentials(creds) => &mut creds.meta,
+            CatalogEntry::Role(role) => &mut role.meta,
+            CatalogEntry::Sequence(seq) => &mut seq.meta,
         }
     }
 }
@@ -173,6 +182,8 @@ impl TryFrom<catalog::catalog_entry::Entry> for CatalogEntry {
             catalog::catalog_entry::Entry::Credentials(v) => {
                 CatalogEntry::Credentials(v.try_into()?)
             }
+            catalog::catalog_entry::Entry::Role(v) => CatalogEntry::Role(v.try_into()?),
+            catalog::catalog_entry::Entry::Sequence(v) => CatalogEntry::Sequence(v.try_into()?),
         })
     }
 }
@@ -195,6 +206,8 @@ impl TryFrom<CatalogEntry> for catalog::CatalogEntry {
             CatalogEntry::Tunnel(v) => catalog::catalog_entry::Entry::Tunnel(v.into()),
             CatalogEntry::Function(v) => catalog::catalog_entry::Entry::Function(v.into()),
             CatalogEntry::Credentials(v) => catalog::catalog_entry::Entry::Credentials(v.into()),
+            CatalogEntry::Role(v) => catalog::catalog_entry::Entry::Role(v.into()),
+            CatalogEntry::Sequence(v) => catalog::catalog_entry::Entry::Sequence(v.into()),
         };
         Ok(catalog::CatalogEntry { entry: Some(ent) })
     }
@@ -209,6 +222,8 @@ pub enum EntryType {
     Tunnel,
     Function,
     Credentials,
+    Role,
+    Sequence,
 }
 
 impl EntryType {
@@ -221,6 +236,8 @@ impl EntryType {
             EntryType::Tunnel => "tunnel",
             EntryType::Function => "function",
             EntryType::Credentials => "credentials",
+            EntryType::Role => "role",
+            EntryType::Sequence => "sequence",
         }
     }
 }
@@ -248,6 +265,8 @@ impl TryFrom<catalog::entry_meta::EntryType> for EntryType {
             catalog::entry_meta::EntryType::Tunnel => EntryType::Tunnel,
             catalog::entry_meta::EntryType::Function => EntryType::Function,
             catalog::entry_meta::EntryType::Credentials => EntryType::Credentials,
+            catalog::entry_meta::EntryType::Role => EntryType::Role,
+            catalog::entry_meta::EntryType::Sequence => EntryType::Sequence,
         })
     }
 }
@@ -262,6 +281,8 @@ impl From<EntryType> for catalog::entry_meta::EntryType {
             EntryType::Tunnel => catalog::entry_meta::EntryType::Tunnel,
             EntryType::Function => catalog::entry_meta::EntryType::Function,
             EntryType::Credentials => catalog::entry_meta::EntryType::Credentials,
+            EntryType::Role => catalog::entry_meta::EntryType::Role,
+            EntryType::Sequence => catalog::entry_meta::EntryType::Sequence,
         }
     }
 }
@@ -286,6 +307,8 @@ pub struct EntryMeta {
     pub builtin: bool,
     pub external: bool,
     pub is_temp: bool,
+    /// Comment set on the entry with `COMMENT ON`.
+    pub description: Option<String>,
 }
 
 impl From<EntryMeta> for catalog::EntryMeta {
@@ -299,6 +322,7 @@ impl From<EntryMeta> for catalog::EntryMeta {
             builtin: value.builtin,
             external: value.external,
             is_temp: value.is_temp,
+            description: value.description,
         }
     }
 }
@@ -314,6 +338,7 @@ impl TryFrom<catalog::EntryMeta> for EntryMeta {
             builtin: value.builtin,
             external: value.external,
             is_temp: value.is_temp,
+            description: value.description,
         })
     }
 }
@@ -467,6 +492,15 @@ impl TableEntry {
         }
     }
 
+    /// Get the materialized view options if this table holds the results of
+    /// a materialized view.
+    pub fn materialized_view(&self) -> Option<&MaterializedViewOptions> {
+        match self.options {
+            TableOptionsV0::Internal(ref options) => options.materialized_view.as_ref(),
+            _ => None,
+        }
+    }
+
     pub fn get_columns(&self) -> Option<Vec<FieldRef>> {
         self.get_internal_columns().map(|val| {
             val.iter()
@@ -889,6 +923,293 @@ impl From<CredentialsEntry> for catalog::CredentialsEntry {
     }
 }
 
+/// A privilege that can be granted to a role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Privilege {
+    Select,
+    Insert,
+    Update,
+    Delete,
+    Usage,
+    Execute,
+}
+
+impl Privilege {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Privilege::Select => "SELECT",
+            Privilege::Insert => "INSERT",
+            Privilege::Update => "UPDATE",
+            Privilege::Delete => "DELETE",
+            Privilege::Usage => "USAGE",
+            Privilege::Execute => "EXECUTE",
+        }
+    }
+
+    /// Privileges that can be granted on objects of the given type.
+    pub const fn allowed_for(entry_type: EntryType) -> &'static [Privilege] {
+        match entry_type {
+            EntryType::Table => &[
+                Privilege::Select,
+                Privilege::Insert,
+                Privilege::Update,
+                Privilege::Delete,
+            ],
+            EntryType::View => &[Privilege::Select],
+            EntryType::Schema | EntryType::Credentials | EntryType::Sequence => &[Privilege::Usage],
+            EntryType::Database => &[Privilege::Usage, Privilege::Insert],
+            EntryType::Function => &[Privilege::Execute],
+            _ => &[],
+        }
+    }
+}
+
+impl fmt::Display for Privilege {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Privilege {
+    type Err = ProtoConvError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_uppercase().as_str() {
+            "SELECT" => Privilege::Select,
+            "INSERT" => Privilege::Insert,
+            "UPDATE" => Privilege::Update,
+            "DELETE" => Privilege::Delete,
+            "USAGE" => Privilege::Usage,
+            "EXECUTE" => Privilege::Execute,
+            other => {
+                return Err(ProtoConvError::ParseError(format!(
+                    "invalid privilege: {other}"
+                )))
+            }
+        })
+    }
+}
+
+impl TryFrom<i32> for Privilege {
+    type Error = ProtoConvError;
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        catalog::Privilege::try_from(value)
+            .map_err(|_| ProtoConvError::UnknownEnumVariant("Privilege", value))
+            .and_then(|t| t.try_into())
+    }
+}
+
+impl TryFrom<catalog::Privilege> for Privilege {
+    type Error = ProtoConvError;
+    fn try_from(value: catalog::Privilege) -> Result<Self, Self::Error> {
+        Ok(match value {
+            catalog::Privilege::Unspecified => {
+                return Err(ProtoConvError::ZeroValueEnumVariant("Privilege"))
+            }
+            catalog::Privilege::Select => Privilege::Select,
+            catalog::Privilege::Insert => Privilege::Insert,
+            catalog::Privilege::Update => Privilege::Update,
+            catalog::Privilege::Delete => Privilege::Delete,
+            catalog::Privilege::Usage => Privilege::Usage,
+            catalog::Privilege::Execute => Privilege::Execute,
+        })
+    }
+}
+
+impl From<Privilege> for catalog::Privilege {
+    fn from(value: Privilege) -> Self {
+        match value {
+            Privilege::Select => catalog::Privilege::Select,
+            Privilege::Insert => catalog::Privilege::Insert,
+            Privilege::Update => catalog::Privilege::Update,
+            Privilege::Delete => catalog::Privilege::Delete,
+            Privilege::Usage => catalog::Privilege::Usage,
+            Privilege::Execute => catalog::Privilege::Execute,
+        }
+    }
+}
+
+impl From<Privilege> for i32 {
+    fn from(value: Privilege) -> Self {
+        let value: catalog::Privilege = value.into();
+        value as i32
+    }
+}
+
+/// A privilege granted on some catalog object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Grant {
+    pub object_id: u32,
+    pub privilege: Privilege,
+}
+
+impl TryFrom<catalog::Grant> for Grant {
+    type Error = ProtoConvError;
+    fn try_from(value: catalog::Grant) -> Result<Self, Self::Error> {
+        Ok(Grant {
+            object_id: value.object_id,
+            privilege: value.privilege.try_into()?,
+        })
+    }
+}
+
+impl From<Grant> for catalog::Grant {
+    fn from(value: Grant) -> Self {
+        catalog::Grant {
+            object_id: value.object_id,
+            privilege: value.privilege.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoleEntry {
+    pub meta: EntryMeta,
+    pub grants: Vec<Grant>,
+}
+
+impl RoleEntry {
+    /// Check if this role was granted the privilege on the object.
+    pub fn has_grant(&self, object_id: u32, privilege: Privilege) -> bool {
+        self.grants
+            .iter()
+            .any(|g| g.object_id == object_id && g.privilege == privilege)
+    }
+}
+
+impl TryFrom<catalog::RoleEntry> for RoleEntry {
+    type Error = ProtoConvError;
+    fn try_from(value: catalog::RoleEntry) -> Result<Self, Self::Error> {
+        let meta: EntryMeta = value.meta.required("meta")?;
+        Ok(RoleEntry {
+            meta,
+            grants: value
+                .grants
+                .into_iter()
+                .map(|g| g.try_into())
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<RoleEntry> for catalog::RoleEntry {
+    fn from(value: RoleEntry) -> Self {
+        catalog::RoleEntry {
+            meta: Some(value.meta.into()),
+            grants: value.grants.into_iter().map(|g| g.into()).collect(),
+        }
+    }
+}
+
+/// The table column a sequence belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequenceOwner {
+    pub table_id: u32,
+    pub column: String,
+}
+
+impl From<catalog::SequenceOwner> for SequenceOwner {
+    fn from(value: catalog::SequenceOwner) -> Self {
+        SequenceOwner {
+            table_id: value.table_id,
+            column: value.column,
+        }
+    }
+}
+
+impl From<SequenceOwner> for catalog::SequenceOwner {
+    fn from(value: SequenceOwner) -> Self {
+        catalog::SequenceOwner {
+            table_id: value.table_id,
+            column: value.column,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequenceEntry {
+    pub meta: EntryMeta,
+    pub start: i64,
+    pub increment: i64,
+    pub min_value: i64,
+    pub max_value: i64,
+    pub cycle: bool,
+    /// The last value handed out by the sequence.
+    pub last_value: i64,
+    /// If `last_value` was handed out. If not, `last_value` is the next value
+    /// of the sequence.
+    pub is_called: bool,
+    pub owned_by: Option<SequenceOwner>,
+    /// Number of values a session fetches at a time, handing out the values it
+    /// doesn't use immediately from its own cache.
+    pub cache: i64,
+}
+
+impl SequenceEntry {
+    /// Compute the next `count` values of the sequence.
+    ///
+    /// Returns `None` if the sequence would be exhausted before handing out
+    /// all values.
+    pub fn next_values(&self, count: usize) -> Option<Vec<i64>> {
+        let mut values = Vec::with_capacity(count);
+        let mut last = self.last_value;
+        let mut is_called = self.is_called;
+        for _ in 0..count {
+            if is_called {
+                last = self.step(last)?;
+            }
+            values.push(last);
+            is_called = true;
+        }
+        Some(values)
+    }
+
+    fn step(&self, value: i64) -> Option<i64> {
+        match value.checked_add(self.increment) {
+            Some(next) if next >= self.min_value && next <= self.max_value => Some(next),
+            _ if self.cycle && self.increment > 0 => Some(self.min_value),
+            _ if self.cycle => Some(self.max_value),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<catalog::SequenceEntry> for SequenceEntry {
+    type Error = ProtoConvError;
+    fn try_from(value: catalog::SequenceEntry) -> Result<Self, Self::Error> {
+        let meta: EntryMeta = value.meta.required("meta")?;
+        Ok(SequenceEntry {
+            meta,
+            start: value.start,
+            increment: value.increment,
+            min_value: value.min_value,
+            max_value: value.max_value,
+            cycle: value.cycle,
+            last_value: value.last_value,
+            is_called: value.is_called,
+            owned_by: value.owned_by.map(|o| o.into()),
+            // Sequences created before caching was supported default to zero.
+            cache: value.cache.max(1),
+        })
+    }
+}
+
+impl From<SequenceEntry> for catalog::SequenceEntry {
+    fn from(value: SequenceEntry) -> Self {
+        catalog::SequenceEntry {
+            meta: Some(value.meta.into()),
+            start: value.start,
+            increment: value.increment,
+            min_value: value.min_value,
+            max_value: value.max_value,
+            cycle: value.cycle,
+            last_value: value.last_value,
+            is_called: value.is_called,
+            owned_by: value.owned_by.map(|o| o.into()),
+            cache: value.cache,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
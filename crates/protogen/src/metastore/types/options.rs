@@ -19,6 +19,16 @@ pub struct InternalColumnDefinition {
     pub name: String,
     pub nullable: bool,
     pub arrow_type: DataType,
+    /// SQL expression for the column's default value.
+    #[serde(default)]
+    pub default_expr: Option<String>,
+    /// Values for the column are always generated, and can't be provided
+    /// explicitly.
+    #[serde(default)]
+    pub generated_always: bool,
+    /// Comment set on the column with `COMMENT ON COLUMN`.
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 impl InternalColumnDefinition {
@@ -36,6 +46,9 @@ impl InternalColumnDefinition {
                 name: name.into(),
                 nullable,
                 arrow_type,
+                default_expr: None,
+                generated_always: false,
+                description: None,
             })
             .collect()
     }
@@ -48,6 +61,9 @@ impl InternalColumnDefinition {
             name: field.name().clone(),
             nullable: field.is_nullable(),
             arrow_type: field.data_type().clone(),
+            default_expr: None,
+            generated_always: false,
+            description: None,
         })
     }
 
@@ -60,6 +76,31 @@ impl InternalColumnDefinition {
     }
 }
 
+/// Check if changing a column from `from` to `to` is a widening type change,
+/// i.e. every value of the old type can be represented by the new type.
+pub fn is_widening_type_change(from: &DataType, to: &DataType) -> bool {
+    use DataType::*;
+
+    match (from, to) {
+        (from, to) if from == to => true,
+        (Int8, Int16 | Int32 | Int64 | Float32 | Float64) => true,
+        (Int16, Int32 | Int64 | Float32 | Float64) => true,
+        (Int32, Int64 | Float64) => true,
+        (UInt8, UInt16 | UInt32 | UInt64 | Int16 | Int32 | Int64 | Float32 | Float64) => true,
+        (UInt16, UInt32 | UInt64 | Int32 | Int64 | Float32 | Float64) => true,
+        (UInt32, UInt64 | Int64 | Float64) => true,
+        (Float16, Float32 | Float64) => true,
+        (Float32, Float64) => true,
+        (Decimal128(p1, s1), Decimal128(p2, s2)) => {
+            s2 >= s1 && (*p2 as i16 - *s2 as i16) >= (*p1 as i16 - *s1 as i16)
+        }
+        (Utf8, LargeUtf8) => true,
+        (Binary, LargeBinary) => true,
+        (Date32, Timestamp(_, None)) => true,
+        _ => false,
+    }
+}
+
 impl From<InternalColumnDefinition> for Field {
     fn from(value: InternalColumnDefinition) -> Self {
         Field::new(value.name, value.arrow_type, value.nullable)
@@ -75,6 +116,9 @@ impl TryFrom<options::InternalColumnDefinition> for InternalColumnDefinition {
             name: value.name,
             nullable: value.nullable,
             arrow_type,
+            default_expr: value.default_expr,
+            generated_always: value.generated_always,
+            description: value.description,
         })
     }
 }
@@ -89,6 +133,140 @@ impl From<InternalColumnDefinition> for options::InternalColumnDefinition {
             name: value.name,
             nullable: value.nullable,
             arrow_type: Some(arrow_type),
+            default_expr: value.default_expr,
+            generated_always: value.generated_always,
+            description: value.description,
+        }
+    }
+}
+
+/// A CHECK constraint on a native table.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CheckConstraint {
+    pub name: String,
+    /// SQL expression that must not evaluate to false for any row in the
+    /// table.
+    pub expr: String,
+}
+
+impl From<options::CheckConstraint> for CheckConstraint {
+    fn from(value: options::CheckConstraint) -> Self {
+        CheckConstraint {
+            name: value.name,
+            expr: value.expr,
+        }
+    }
+}
+
+impl From<CheckConstraint> for options::CheckConstraint {
+    fn from(value: CheckConstraint) -> Self {
+        options::CheckConstraint {
+            name: value.name,
+            expr: value.expr,
+        }
+    }
+}
+
+/// A PRIMARY KEY or UNIQUE constraint on a native table.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct UniqueConstraint {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub primary_key: bool,
+}
+
+impl From<options::UniqueConstraint> for UniqueConstraint {
+    fn from(value: options::UniqueConstraint) -> Self {
+        UniqueConstraint {
+            name: value.name,
+            columns: value.columns,
+            primary_key: value.primary_key,
+        }
+    }
+}
+
+impl From<UniqueConstraint> for options::UniqueConstraint {
+    fn from(value: UniqueConstraint) -> Self {
+        options::UniqueConstraint {
+            name: value.name,
+            columns: value.columns,
+            primary_key: value.primary_key,
+        }
+    }
+}
+
+/// Policy for compacting the small files of a native table in the
+/// background after inserts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AutoCompactionPolicy {
+    /// Number of small files the table needs to have before it's compacted.
+    pub min_files: u64,
+}
+
+impl From<options::AutoCompactionPolicy> for AutoCompactionPolicy {
+    fn from(value: options::AutoCompactionPolicy) -> Self {
+        AutoCompactionPolicy {
+            min_files: value.min_files,
+        }
+    }
+}
+
+impl From<AutoCompactionPolicy> for options::AutoCompactionPolicy {
+    fn from(value: AutoCompactionPolicy) -> Self {
+        options::AutoCompactionPolicy {
+            min_files: value.min_files,
+        }
+    }
+}
+
+/// Options for a native table holding the results of a materialized view.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MaterializedViewOptions {
+    /// Query the view is populated from.
+    pub sql: String,
+    /// Column aliases for the query, if provided.
+    pub columns: Vec<String>,
+    /// Interval in seconds the view should be refreshed at, if refreshed on a
+    /// schedule.
+    pub refresh_interval_secs: Option<u64>,
+    /// Time of the last refresh, in microseconds since the epoch.
+    pub refreshed_at: Option<i64>,
+}
+
+impl MaterializedViewOptions {
+    /// Check if the view is due for a scheduled refresh at the given time.
+    pub fn needs_refresh(&self, now_micros: i64) -> bool {
+        match (self.refresh_interval_secs, self.refreshed_at) {
+            (Some(interval), Some(refreshed_at)) => {
+                let interval = i64::try_from(interval)
+                    .unwrap_or(i64::MAX)
+                    .saturating_mul(1_000_000);
+                refreshed_at.saturating_add(interval) <= now_micros
+            }
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+impl From<options::MaterializedViewOptions> for MaterializedViewOptions {
+    fn from(value: options::MaterializedViewOptions) -> Self {
+        MaterializedViewOptions {
+            sql: value.sql,
+            columns: value.columns,
+            refresh_interval_secs: value.refresh_interval_secs,
+            refreshed_at: value.refreshed_at,
+        }
+    }
+}
+
+impl From<MaterializedViewOptions> for options::MaterializedViewOptions {
+    fn from(value: MaterializedViewOptions) -> Self {
+        options::MaterializedViewOptions {
+            sql: value.sql,
+            columns: value.columns,
+            refresh_interval_secs: value.refresh_interval_secs,
+            refreshed_at: value.refreshed_at,
         }
     }
 }
@@ -109,6 +287,7 @@ pub enum DatabaseOptions {
     Clickhouse(DatabaseOptionsClickhouse),
     Cassandra(DatabaseOptionsCassandra),
     Sqlite(DatabaseOptionsSqlite),
+    Iceberg(DatabaseOptionsIceberg),
 }
 
 impl DatabaseOptions {
@@ -124,6 +303,7 @@ impl DatabaseOptions {
     pub const CLICKHOUSE: &'static str = "clickhouse";
     pub const CASSANDRA: &'static str = "cassandra";
     pub const SQLITE: &'static str = "sqlite";
+    pub const ICEBERG: &'static str = "iceberg";
 
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -139,6 +319,7 @@ impl DatabaseOptions {
             DatabaseOptions::Clickhouse(_) => Self::CLICKHOUSE,
             DatabaseOptions::Cassandra(_) => Self::CASSANDRA,
             DatabaseOptions::Sqlite(_) => Self::SQLITE,
+            DatabaseOptions::Iceberg(_) => Self::ICEBERG,
         }
     }
 }
@@ -181,6 +362,9 @@ impl TryFrom<options::database_options::Options> for DatabaseOptions {
                 DatabaseOptions::Cassandra(v.try_into()?)
             }
             options::database_options::Options::Sqlite(v) => DatabaseOptions::Sqlite(v.try_into()?),
+            options::database_options::Options::Iceberg(v) => {
+                DatabaseOptions::Iceberg(v.try_into()?)
+            }
         })
     }
 }
@@ -215,6 +399,7 @@ impl From<DatabaseOptions> for options::database_options::Options {
                 options::database_options::Options::Cassandra(v.into())
             }
             DatabaseOptions::Sqlite(v) => options::database_options::Options::Sqlite(v.into()),
+            DatabaseOptions::Iceberg(v) => options::database_options::Options::Iceberg(v.into()),
         }
     }
 }
@@ -539,6 +724,85 @@ impl From<DeltaLakeUnityCatalog> for options::DeltaLakeUnityCatalog {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DatabaseOptionsIceberg {
+    pub catalog: IcebergCatalog,
+    pub storage_options: StorageOptions,
+}
+
+impl TryFrom<options::DatabaseOptionsIceberg> for DatabaseOptionsIceberg {
+    type Error = ProtoConvError;
+    fn try_from(value: options::DatabaseOptionsIceberg) -> Result<Self, Self::Error> {
+        let catalog: IcebergCatalog = value.catalog.required("catalog")?;
+        let storage_options: StorageOptions = value.storage_options.required("storage_options")?;
+        Ok(DatabaseOptionsIceberg {
+            catalog,
+            storage_options,
+        })
+    }
+}
+
+impl From<DatabaseOptionsIceberg> for options::DatabaseOptionsIceberg {
+    fn from(value: DatabaseOptionsIceberg) -> Self {
+        options::DatabaseOptionsIceberg {
+            catalog: Some(value.catalog.into()),
+            storage_options: Some(value.storage_options.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IcebergCatalog {
+    Rest(IcebergRestCatalog),
+}
+
+impl TryFrom<options::database_options_iceberg::Catalog> for IcebergCatalog {
+    type Error = ProtoConvError;
+    fn try_from(value: options::database_options_iceberg::Catalog) -> Result<Self, Self::Error> {
+        Ok(match value {
+            options::database_options_iceberg::Catalog::Rest(v) => {
+                IcebergCatalog::Rest(v.try_into()?)
+            }
+        })
+    }
+}
+
+impl From<IcebergCatalog> for options::database_options_iceberg::Catalog {
+    fn from(value: IcebergCatalog) -> Self {
+        match value {
+            IcebergCatalog::Rest(v) => options::database_options_iceberg::Catalog::Rest(v.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct IcebergRestCatalog {
+    pub uri: String,
+    pub warehouse: Option<String>,
+    pub token: Option<String>,
+}
+
+impl TryFrom<options::IcebergRestCatalog> for IcebergRestCatalog {
+    type Error = ProtoConvError;
+    fn try_from(value: options::IcebergRestCatalog) -> Result<Self, Self::Error> {
+        Ok(IcebergRestCatalog {
+            uri: value.uri,
+            warehouse: value.warehouse,
+            token: value.token,
+        })
+    }
+}
+
+impl From<IcebergRestCatalog> for options::IcebergRestCatalog {
+    fn from(value: IcebergRestCatalog) -> Self {
+        options::IcebergRestCatalog {
+            uri: value.uri,
+            warehouse: value.warehouse,
+            token: value.token,
+        }
+    }
+}
+
 /// Options for a generic `ObjectStore`; to make them as versatile and compact
 /// as possible it's just a wrapper for a map, like in `delta-rs`, except here
 /// it's a `BTreeMap` instead of a `HashMap`, since the former is `Hash` unlike
@@ -732,7 +996,14 @@ impl TableOptionsV0 {
     pub const SQLITE: &'static str = "sqlite";
 
     pub const fn new_internal(columns: Vec<InternalColumnDefinition>) -> TableOptionsV0 {
-        TableOptionsV0::Internal(TableOptionsInternal { columns })
+        TableOptionsV0::Internal(TableOptionsInternal {
+            columns,
+            check_constraints: Vec::new(),
+            unique_constraints: Vec::new(),
+            auto_compaction: None,
+            partition_columns: Vec::new(),
+            materialized_view: None,
+        })
     }
 
     pub fn as_str(&self) -> &'static str {
@@ -1017,6 +1288,25 @@ impl From<TableOptionsDebug> for options::TableOptionsDebug {
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TableOptionsInternal {
     pub columns: Vec<InternalColumnDefinition>,
+    #[serde(default)]
+    pub check_constraints: Vec<CheckConstraint>,
+    #[serde(default)]
+    pub unique_constraints: Vec<UniqueConstraint>,
+    #[serde(default)]
+    pub auto_compaction: Option<AutoCompactionPolicy>,
+    /// Columns the table is partitioned by.
+    #[serde(default)]
+    pub partition_columns: Vec<String>,
+    /// Set if the table holds the results of a materialized view.
+    #[serde(default)]
+    pub materialized_view: Option<MaterializedViewOptions>,
+}
+
+impl TableOptionsInternal {
+    /// Get the table's primary key, if it has one.
+    pub fn primary_key(&self) -> Option<&UniqueConstraint> {
+        self.unique_constraints.iter().find(|c| c.primary_key)
+    }
 }
 
 impl From<TableOptionsInternal> for TableOptionsV0 {
@@ -1039,8 +1329,16 @@ impl From<DFSchemaRef> for TableOptionsInternal {
                     name: col.name().clone(),
                     nullable: col.is_nullable(),
                     arrow_type: col.data_type().clone(),
+                    default_expr: None,
+                    generated_always: false,
+                    description: None,
                 })
                 .collect::<Vec<_>>(),
+            check_constraints: Vec::new(),
+            unique_constraints: Vec::new(),
+            auto_compaction: None,
+            partition_columns: Vec::new(),
+            materialized_view: None,
         }
     }
 }
@@ -1055,8 +1353,16 @@ impl From<SchemaRef> for TableOptionsInternal {
                     name: col.name().clone(),
                     nullable: col.is_nullable(),
                     arrow_type: col.data_type().clone(),
+                    default_expr: None,
+                    generated_always: false,
+                    description: None,
                 })
                 .collect::<Vec<_>>(),
+            check_constraints: Vec::new(),
+            unique_constraints: Vec::new(),
+            auto_compaction: None,
+            partition_columns: Vec::new(),
+            materialized_view: None,
         }
     }
 }
@@ -1070,6 +1376,19 @@ impl TryFrom<options::TableOptionsInternal> for TableOptionsInternal {
                 .into_iter()
                 .map(|col| col.try_into())
                 .collect::<Result<_, _>>()?,
+            check_constraints: value
+                .check_constraints
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            unique_constraints: value
+                .unique_constraints
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            auto_compaction: value.auto_compaction.map(Into::into),
+            partition_columns: value.partition_columns,
+            materialized_view: value.materialized_view.map(Into::into),
         })
     }
 }
@@ -1079,6 +1398,19 @@ impl TryFrom<TableOptionsInternal> for options::TableOptionsInternal {
     fn try_from(value: TableOptionsInternal) -> Result<Self, Self::Error> {
         Ok(options::TableOptionsInternal {
             columns: value.columns.into_iter().map(Into::into).collect(),
+            check_constraints: value
+                .check_constraints
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            unique_constraints: value
+                .unique_constraints
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            auto_compaction: value.auto_compaction.map(Into::into),
+            partition_columns: value.partition_columns,
+            materialized_view: value.materialized_view.map(Into::into),
         })
     }
 }
@@ -2077,6 +2409,7 @@ pub enum CopyToFormatOptions {
     Parquet(CopyToFormatOptionsParquet),
     Lance(CopyToFormatOptionsLance),
     Delta(CopyToFormatOptionsDelta),
+    Iceberg(CopyToFormatOptionsIceberg),
     Json(CopyToFormatOptionsJson),
     Bson(CopyToFormatOptionsBson),
 }
@@ -2097,6 +2430,7 @@ impl CopyToFormatOptions {
     pub const BSON: &'static str = "bson";
     pub const LANCE: &'static str = "lance";
     pub const DELTA: &'static str = "delta";
+    pub const ICEBERG: &'static str = "iceberg";
 
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -2106,11 +2440,12 @@ impl CopyToFormatOptions {
             Self::Bson(_) => Self::BSON,
             Self::Lance(_) => Self::LANCE,
             Self::Delta(_) => Self::DELTA,
+            Self::Iceberg(_) => Self::ICEBERG,
         }
     }
 
     pub fn is_table(&self) -> bool {
-        matches!(self, Self::Delta(_) | Self::Lance(_))
+        matches!(self, Self::Delta(_) | Self::Iceberg(_) | Self::Lance(_))
     }
 }
 
@@ -2136,6 +2471,9 @@ pub struct CopyToFormatOptionsBson {}
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct CopyToFormatOptionsDelta {}
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct CopyToFormatOptionsIceberg {}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct CopyToFormatOptionsLance {
     pub max_rows_per_file: Option<usize>,
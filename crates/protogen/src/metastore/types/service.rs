@@ -1,7 +1,9 @@
+use datafusion::arrow::datatypes::DataType;
 use datafusion::logical_expr::Signature;
 
-use super::catalog::{FunctionType, SourceAccessMode};
+use super::catalog::{EntryType, FunctionType, Privilege, SourceAccessMode};
 use super::options::{
+    AutoCompactionPolicy,
     CredentialsOptions,
     DatabaseOptions,
     InternalColumnDefinition,
@@ -9,6 +11,7 @@ use super::options::{
     TableOptionsV0,
     TunnelOptions,
 };
+use crate::gen::common::arrow;
 use crate::gen::metastore::service;
 use crate::{gen, FromOptionalField, ProtoConvError};
 
@@ -32,6 +35,14 @@ pub enum Mutation {
     // Deployment metadata updates
     UpdateDeploymentStorage(UpdateDeploymentStorage),
     CreateFunction(CreateFunction),
+    CreateRole(CreateRole),
+    DropRole(DropRole),
+    GrantPrivileges(GrantPrivileges),
+    RevokePrivileges(RevokePrivileges),
+    CreateSequence(CreateSequence),
+    NextSequenceValues(NextSequenceValues),
+    SetSequenceValue(SetSequenceValue),
+    CommentOn(CommentOn),
 }
 
 impl TryFrom<service::Mutation> for Mutation {
@@ -76,6 +87,24 @@ impl TryFrom<service::mutation::Mutation> for Mutation {
             service::mutation::Mutation::CreateFunction(v) => {
                 Mutation::CreateFunction(v.try_into()?)
             }
+            service::mutation::Mutation::CreateRole(v) => Mutation::CreateRole(v.try_into()?),
+            service::mutation::Mutation::DropRole(v) => Mutation::DropRole(v.try_into()?),
+            service::mutation::Mutation::GrantPrivileges(v) => {
+                Mutation::GrantPrivileges(v.try_into()?)
+            }
+            service::mutation::Mutation::RevokePrivileges(v) => {
+                Mutation::RevokePrivileges(v.try_into()?)
+            }
+            service::mutation::Mutation::CreateSequence(v) => {
+                Mutation::CreateSequence(v.try_into()?)
+            }
+            service::mutation::Mutation::NextSequenceValues(v) => {
+                Mutation::NextSequenceValues(v.try_into()?)
+            }
+            service::mutation::Mutation::SetSequenceValue(v) => {
+                Mutation::SetSequenceValue(v.try_into()?)
+            }
+            service::mutation::Mutation::CommentOn(v) => Mutation::CommentOn(v.try_into()?),
         })
     }
 }
@@ -112,6 +141,20 @@ impl TryFrom<Mutation> for service::mutation::Mutation {
             Mutation::CreateFunction(v) => {
                 service::mutation::Mutation::CreateFunction(v.try_into()?)
             }
+            Mutation::CreateRole(v) => service::mutation::Mutation::CreateRole(v.into()),
+            Mutation::DropRole(v) => service::mutation::Mutation::DropRole(v.into()),
+            Mutation::GrantPrivileges(v) => service::mutation::Mutation::GrantPrivileges(v.into()),
+            Mutation::RevokePrivileges(v) => {
+                service::mutation::Mutation::RevokePrivileges(v.into())
+            }
+            Mutation::CreateSequence(v) => service::mutation::Mutation::CreateSequence(v.into()),
+            Mutation::NextSequenceValues(v) => {
+                service::mutation::Mutation::NextSequenceValues(v.into())
+            }
+            Mutation::SetSequenceValue(v) => {
+                service::mutation::Mutation::SetSequenceValue(v.into())
+            }
+            Mutation::CommentOn(v) => service::mutation::Mutation::CommentOn(v.into()),
         })
     }
 }
@@ -436,8 +479,36 @@ impl From<CreateExternalDatabase> for service::CreateExternalDatabase {
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum AlterTableOperation {
-    RenameTable { new_name: String },
-    SetAccessMode { access_mode: SourceAccessMode },
+    RenameTable {
+        new_name: String,
+    },
+    SetAccessMode {
+        access_mode: SourceAccessMode,
+    },
+    AddColumn {
+        column: InternalColumnDefinition,
+        if_not_exists: bool,
+    },
+    DropColumn {
+        name: String,
+        if_exists: bool,
+    },
+    RenameColumn {
+        name: String,
+        new_name: String,
+    },
+    AlterColumnType {
+        name: String,
+        arrow_type: DataType,
+    },
+    SetAutoCompaction {
+        policy: Option<AutoCompactionPolicy>,
+    },
+    /// Record the time a materialized view was last refreshed, in microseconds
+    /// since the epoch.
+    SetMaterializedViewRefreshed {
+        refreshed_at: i64,
+    },
 }
 
 impl TryFrom<service::alter_table_operation::Operation> for AlterTableOperation {
@@ -452,6 +523,35 @@ impl TryFrom<service::alter_table_operation::Operation> for AlterTableOperation
             ) => Self::SetAccessMode {
                 access_mode: access_mode.try_into()?,
             },
+            service::alter_table_operation::Operation::AlterTableOperationAddColumn(
+                service::AlterTableOperationAddColumn {
+                    column,
+                    if_not_exists,
+                },
+            ) => Self::AddColumn {
+                column: column.required("column")?,
+                if_not_exists,
+            },
+            service::alter_table_operation::Operation::AlterTableOperationDropColumn(
+                service::AlterTableOperationDropColumn { name, if_exists },
+            ) => Self::DropColumn { name, if_exists },
+            service::alter_table_operation::Operation::AlterTableOperationRenameColumn(
+                service::AlterTableOperationRenameColumn { name, new_name },
+            ) => Self::RenameColumn { name, new_name },
+            service::alter_table_operation::Operation::AlterTableOperationAlterColumnType(
+                service::AlterTableOperationAlterColumnType { name, arrow_type },
+            ) => Self::AlterColumnType {
+                name,
+                arrow_type: arrow_type.as_ref().required("arrow_type")?,
+            },
+            service::alter_table_operation::Operation::AlterTableOperationSetAutoCompaction(
+                service::AlterTableOperationSetAutoCompaction { policy },
+            ) => Self::SetAutoCompaction {
+                policy: policy.map(Into::into),
+            },
+            service::alter_table_operation::Operation::AlterTableOperationSetMaterializedViewRefreshed(
+                service::AlterTableOperationSetMaterializedViewRefreshed { refreshed_at },
+            ) => Self::SetMaterializedViewRefreshed { refreshed_at },
         })
     }
 }
@@ -471,6 +571,49 @@ impl From<AlterTableOperation> for service::alter_table_operation::Operation {
                     },
                 )
             }
+            AlterTableOperation::AddColumn {
+                column,
+                if_not_exists,
+            } => service::alter_table_operation::Operation::AlterTableOperationAddColumn(
+                service::AlterTableOperationAddColumn {
+                    column: Some(column.into()),
+                    if_not_exists,
+                },
+            ),
+            AlterTableOperation::DropColumn { name, if_exists } => {
+                service::alter_table_operation::Operation::AlterTableOperationDropColumn(
+                    service::AlterTableOperationDropColumn { name, if_exists },
+                )
+            }
+            AlterTableOperation::RenameColumn { name, new_name } => {
+                service::alter_table_operation::Operation::AlterTableOperationRenameColumn(
+                    service::AlterTableOperationRenameColumn { name, new_name },
+                )
+            }
+            AlterTableOperation::AlterColumnType { name, arrow_type } => {
+                // Column types always come from SQL types, which are
+                // serializable.
+                let arrow_type = arrow::ArrowType::try_from(&arrow_type)
+                    .expect("Arrow type must be serializable");
+                service::alter_table_operation::Operation::AlterTableOperationAlterColumnType(
+                    service::AlterTableOperationAlterColumnType {
+                        name,
+                        arrow_type: Some(arrow_type),
+                    },
+                )
+            }
+            AlterTableOperation::SetAutoCompaction { policy } => {
+                service::alter_table_operation::Operation::AlterTableOperationSetAutoCompaction(
+                    service::AlterTableOperationSetAutoCompaction {
+                        policy: policy.map(Into::into),
+                    },
+                )
+            }
+            AlterTableOperation::SetMaterializedViewRefreshed { refreshed_at } => {
+                service::alter_table_operation::Operation::AlterTableOperationSetMaterializedViewRefreshed(
+                    service::AlterTableOperationSetMaterializedViewRefreshed { refreshed_at },
+                )
+            }
         }
     }
 }
@@ -736,6 +879,323 @@ impl From<DropCredentials> for service::DropCredentials {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateRole {
+    pub name: String,
+    pub if_not_exists: bool,
+}
+
+impl TryFrom<service::CreateRole> for CreateRole {
+    type Error = ProtoConvError;
+    fn try_from(value: service::CreateRole) -> Result<Self, Self::Error> {
+        Ok(CreateRole {
+            name: value.name,
+            if_not_exists: value.if_not_exists,
+        })
+    }
+}
+
+impl From<CreateRole> for service::CreateRole {
+    fn from(value: CreateRole) -> Self {
+        service::CreateRole {
+            name: value.name,
+            if_not_exists: value.if_not_exists,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropRole {
+    pub name: String,
+    pub if_exists: bool,
+}
+
+impl TryFrom<service::DropRole> for DropRole {
+    type Error = ProtoConvError;
+    fn try_from(value: service::DropRole) -> Result<Self, Self::Error> {
+        Ok(DropRole {
+            name: value.name,
+            if_exists: value.if_exists,
+        })
+    }
+}
+
+impl From<DropRole> for service::DropRole {
+    fn from(value: DropRole) -> Self {
+        service::DropRole {
+            name: value.name,
+            if_exists: value.if_exists,
+        }
+    }
+}
+
+/// Reference to the object privileges are granted on or revoked from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrivilegeObject {
+    pub object_type: EntryType,
+    /// Schema of the object, only set for tables.
+    pub schema: Option<String>,
+    pub name: String,
+}
+
+impl TryFrom<service::PrivilegeObject> for PrivilegeObject {
+    type Error = ProtoConvError;
+    fn try_from(value: service::PrivilegeObject) -> Result<Self, Self::Error> {
+        Ok(PrivilegeObject {
+            object_type: value.object_type.try_into()?,
+            schema: value.schema,
+            name: value.name,
+        })
+    }
+}
+
+impl From<PrivilegeObject> for service::PrivilegeObject {
+    fn from(value: PrivilegeObject) -> Self {
+        let object_type: gen::metastore::catalog::entry_meta::EntryType = value.object_type.into();
+        service::PrivilegeObject {
+            object_type: object_type as i32,
+            schema: value.schema,
+            name: value.name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GrantPrivileges {
+    pub role: String,
+    pub privileges: Vec<Privilege>,
+    pub object: PrivilegeObject,
+}
+
+impl TryFrom<service::GrantPrivileges> for GrantPrivileges {
+    type Error = ProtoConvError;
+    fn try_from(value: service::GrantPrivileges) -> Result<Self, Self::Error> {
+        Ok(GrantPrivileges {
+            role: value.role,
+            privileges: value
+                .privileges
+                .into_iter()
+                .map(Privilege::try_from)
+                .collect::<Result<_, _>>()?,
+            object: value.object.required("object")?,
+        })
+    }
+}
+
+impl From<GrantPrivileges> for service::GrantPrivileges {
+    fn from(value: GrantPrivileges) -> Self {
+        service::GrantPrivileges {
+            role: value.role,
+            privileges: value.privileges.into_iter().map(i32::from).collect(),
+            object: Some(value.object.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RevokePrivileges {
+    pub role: String,
+    pub privileges: Vec<Privilege>,
+    pub object: PrivilegeObject,
+}
+
+impl TryFrom<service::RevokePrivileges> for RevokePrivileges {
+    type Error = ProtoConvError;
+    fn try_from(value: service::RevokePrivileges) -> Result<Self, Self::Error> {
+        Ok(RevokePrivileges {
+            role: value.role,
+            privileges: value
+                .privileges
+                .into_iter()
+                .map(Privilege::try_from)
+                .collect::<Result<_, _>>()?,
+            object: value.object.required("object")?,
+        })
+    }
+}
+
+impl From<RevokePrivileges> for service::RevokePrivileges {
+    fn from(value: RevokePrivileges) -> Self {
+        service::RevokePrivileges {
+            role: value.role,
+            privileges: value.privileges.into_iter().map(i32::from).collect(),
+            object: Some(value.object.into()),
+        }
+    }
+}
+
+/// Column owning a sequence, in the same schema as the sequence.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SequenceOwnerColumn {
+    pub table: String,
+    pub column: String,
+}
+
+impl From<service::SequenceOwnerColumn> for SequenceOwnerColumn {
+    fn from(value: service::SequenceOwnerColumn) -> Self {
+        SequenceOwnerColumn {
+            table: value.table,
+            column: value.column,
+        }
+    }
+}
+
+impl From<SequenceOwnerColumn> for service::SequenceOwnerColumn {
+    fn from(value: SequenceOwnerColumn) -> Self {
+        service::SequenceOwnerColumn {
+            table: value.table,
+            column: value.column,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CreateSequence {
+    pub schema: String,
+    pub name: String,
+    pub start: i64,
+    pub increment: i64,
+    pub min_value: i64,
+    pub max_value: i64,
+    pub cycle: bool,
+    pub owned_by: Option<SequenceOwnerColumn>,
+    pub if_not_exists: bool,
+    pub cache: i64,
+}
+
+impl TryFrom<service::CreateSequence> for CreateSequence {
+    type Error = ProtoConvError;
+    fn try_from(value: service::CreateSequence) -> Result<Self, Self::Error> {
+        Ok(CreateSequence {
+            schema: value.schema,
+            name: value.name,
+            start: value.start,
+            increment: value.increment,
+            min_value: value.min_value,
+            max_value: value.max_value,
+            cycle: value.cycle,
+            owned_by: value.owned_by.map(|o| o.into()),
+            if_not_exists: value.if_not_exists,
+            cache: value.cache,
+        })
+    }
+}
+
+impl From<CreateSequence> for service::CreateSequence {
+    fn from(value: CreateSequence) -> Self {
+        service::CreateSequence {
+            schema: value.schema,
+            name: value.name,
+            start: value.start,
+            increment: value.increment,
+            min_value: value.min_value,
+            max_value: value.max_value,
+            cycle: value.cycle,
+            owned_by: value.owned_by.map(|o| o.into()),
+            if_not_exists: value.if_not_exists,
+            cache: value.cache,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NextSequenceValues {
+    pub schema: String,
+    pub name: String,
+    pub count: u64,
+}
+
+impl TryFrom<service::NextSequenceValues> for NextSequenceValues {
+    type Error = ProtoConvError;
+    fn try_from(value: service::NextSequenceValues) -> Result<Self, Self::Error> {
+        Ok(NextSequenceValues {
+            schema: value.schema,
+            name: value.name,
+            count: value.count,
+        })
+    }
+}
+
+impl From<NextSequenceValues> for service::NextSequenceValues {
+    fn from(value: NextSequenceValues) -> Self {
+        service::NextSequenceValues {
+            schema: value.schema,
+            name: value.name,
+            count: value.count,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetSequenceValue {
+    pub schema: String,
+    pub name: String,
+    pub value: i64,
+    pub is_called: bool,
+}
+
+impl TryFrom<service::SetSequenceValue> for SetSequenceValue {
+    type Error = ProtoConvError;
+    fn try_from(value: service::SetSequenceValue) -> Result<Self, Self::Error> {
+        Ok(SetSequenceValue {
+            schema: value.schema,
+            name: value.name,
+            value: value.value,
+            is_called: value.is_called,
+        })
+    }
+}
+
+impl From<SetSequenceValue> for service::SetSequenceValue {
+    fn from(value: SetSequenceValue) -> Self {
+        service::SetSequenceValue {
+            schema: value.schema,
+            name: value.name,
+            value: value.value,
+            is_called: value.is_called,
+        }
+    }
+}
+
+/// Set or remove the comment on an object, or on a column of a table.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CommentOn {
+    pub object_type: EntryType,
+    /// Schema of the object, only set for tables and views.
+    pub schema: Option<String>,
+    pub name: String,
+    /// Column of the table to comment on.
+    pub column: Option<String>,
+    /// The new comment, `None` removes the comment.
+    pub comment: Option<String>,
+}
+
+impl TryFrom<service::CommentOn> for CommentOn {
+    type Error = ProtoConvError;
+    fn try_from(value: service::CommentOn) -> Result<Self, Self::Error> {
+        Ok(CommentOn {
+            object_type: value.object_type.try_into()?,
+            schema: value.schema,
+            name: value.name,
+            column: value.column,
+            comment: value.comment,
+        })
+    }
+}
+
+impl From<CommentOn> for service::CommentOn {
+    fn from(value: CommentOn) -> Self {
+        let object_type: gen::metastore::catalog::entry_meta::EntryType = value.object_type.into();
+        service::CommentOn {
+            object_type: object_type as i32,
+            schema: value.schema,
+            name: value.name,
+            column: value.column,
+            comment: value.comment,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UpdateDeploymentStorage {
     pub new_storage_size: u64,
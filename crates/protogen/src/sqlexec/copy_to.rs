@@ -64,7 +64,7 @@ pub struct CopyToDestinationOptionsAzure {
 
 #[derive(Clone, PartialEq, Message)]
 pub struct CopyToFormatOptions {
-    #[prost(oneof = "CopyToFormatOptionsEnum", tags = "1, 2, 3")]
+    #[prost(oneof = "CopyToFormatOptionsEnum", tags = "1, 2, 3, 4, 5, 6, 7")]
     pub copy_to_format_options_enum: Option<CopyToFormatOptionsEnum>,
 }
 
@@ -82,6 +82,8 @@ pub enum CopyToFormatOptionsEnum {
     Bson(CopyToFormatOptionsBson),
     #[prost(message, tag = "6")]
     Delta(CopyToFormatOptionsDelta),
+    #[prost(message, tag = "7")]
+    Iceberg(CopyToFormatOptionsIceberg),
 }
 
 #[derive(Clone, PartialEq, Message)]
@@ -122,6 +124,9 @@ pub struct CopyToFormatOptionsBson {}
 #[derive(Clone, PartialEq, Message)]
 pub struct CopyToFormatOptionsDelta {}
 
+#[derive(Clone, PartialEq, Message)]
+pub struct CopyToFormatOptionsIceberg {}
+
 impl TryFrom<crate::metastore::types::options::CopyToFormatOptions> for CopyToFormatOptions {
     type Error = crate::errors::ProtoConvError;
     fn try_from(
@@ -134,6 +139,13 @@ impl TryFrom<crate::metastore::types::options::CopyToFormatOptions> for CopyToFo
             crate::metastore::types::options::CopyToFormatOptions::Delta(_) => {
                 Ok(CopyToFormatOptions::default())
             }
+            crate::metastore::types::options::CopyToFormatOptions::Iceberg(_) => {
+                Ok(CopyToFormatOptions {
+                    copy_to_format_options_enum: Some(CopyToFormatOptionsEnum::Iceberg(
+                        CopyToFormatOptionsIceberg {},
+                    )),
+                })
+            }
             crate::metastore::types::options::CopyToFormatOptions::Lance(opts) => {
                 Ok(CopyToFormatOptions {
                     copy_to_format_options_enum: Some(CopyToFormatOptionsEnum::Lance(
@@ -202,6 +214,11 @@ impl TryFrom<CopyToFormatOptions> for crate::metastore::types::options::CopyToFo
                     crate::metastore::types::options::CopyToFormatOptionsDelta {},
                 ),
             ),
+            CopyToFormatOptionsEnum::Iceberg(_) => Ok(
+                crate::metastore::types::options::CopyToFormatOptions::Iceberg(
+                    crate::metastore::types::options::CopyToFormatOptionsIceberg {},
+                ),
+            ),
             CopyToFormatOptionsEnum::Parquet(parquet) => Ok(
                 crate::metastore::types::options::CopyToFormatOptions::Parquet(
                     crate::metastore::types::options::CopyToFormatOptionsParquet {
@@ -41,6 +41,20 @@ pub struct CreateTableExec {
     pub or_replace: bool,
     #[prost(message, tag = "5")]
     pub arrow_schema: Option<Schema>,
+    #[prost(btree_map = "string, string", tag = "6")]
+    pub column_defaults: std::collections::BTreeMap<String, String>,
+    #[prost(message, repeated, tag = "7")]
+    pub check_constraints: Vec<crate::gen::metastore::options::CheckConstraint>,
+    #[prost(message, repeated, tag = "8")]
+    pub unique_constraints: Vec<crate::gen::metastore::options::UniqueConstraint>,
+    #[prost(string, repeated, tag = "9")]
+    pub partition_columns: Vec<String>,
+    #[prost(message, optional, tag = "10")]
+    pub materialized_view: Option<crate::gen::metastore::options::MaterializedViewOptions>,
+    #[prost(message, repeated, tag = "11")]
+    pub column_sequences: Vec<crate::gen::metastore::service::CreateSequence>,
+    #[prost(string, repeated, tag = "12")]
+    pub generated_always: Vec<String>,
 }
 
 #[derive(Clone, PartialEq, Message)]
@@ -91,6 +105,12 @@ pub struct AlterTableExec {
     pub name: String,
     #[prost(message, tag = "4")]
     pub operation: Option<crate::gen::metastore::service::AlterTableOperation>,
+    #[prost(message, optional, tag = "5")]
+    pub table: Option<TableEntry>,
+    #[prost(message, repeated, tag = "6")]
+    pub columns: Vec<crate::gen::metastore::options::InternalColumnDefinition>,
+    #[prost(message, repeated, tag = "7")]
+    pub rewrite_exprs: Vec<LogicalExprNode>,
 }
 
 #[derive(Clone, PartialEq, Message)]
@@ -243,6 +263,42 @@ pub struct DropCredentialsExec {
     pub if_exists: bool,
 }
 
+#[derive(Clone, PartialEq, Message)]
+pub struct CreateRoleExec {
+    #[prost(uint64, tag = "1")]
+    pub catalog_version: u64,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(bool, tag = "3")]
+    pub if_not_exists: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct DropRoleExec {
+    #[prost(uint64, tag = "1")]
+    pub catalog_version: u64,
+    #[prost(string, repeated, tag = "2")]
+    pub names: Vec<String>,
+    #[prost(bool, tag = "3")]
+    pub if_exists: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct GrantPrivilegesExec {
+    #[prost(uint64, tag = "1")]
+    pub catalog_version: u64,
+    #[prost(message, repeated, tag = "2")]
+    pub grants: Vec<crate::gen::metastore::service::GrantPrivileges>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct RevokePrivilegesExec {
+    #[prost(uint64, tag = "1")]
+    pub catalog_version: u64,
+    #[prost(message, repeated, tag = "2")]
+    pub revokes: Vec<crate::gen::metastore::service::RevokePrivileges>,
+}
+
 #[derive(Clone, PartialEq, Message)]
 pub struct DropTablesExec {
     #[prost(uint64, tag = "1")]
@@ -285,6 +341,18 @@ pub struct UpdateExec {
     pub updates: Vec<UpdateSelector>,
     #[prost(message, optional, tag = "3")]
     pub where_expr: Option<LogicalExprNode>,
+    #[prost(message, repeated, tag = "4")]
+    pub constraints: Vec<RowConstraint>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct RowConstraint {
+    #[prost(bool, tag = "1")]
+    pub not_null: bool,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(message, tag = "3")]
+    pub expr: Option<LogicalExprNode>,
 }
 
 #[derive(Clone, PartialEq, Message)]
@@ -299,6 +367,117 @@ pub struct DeleteExec {
 pub struct InsertExec {
     #[prost(bytes, tag = "1")]
     pub provider_id: Vec<u8>, // UUID
+    #[prost(message, repeated, tag = "2")]
+    pub constraints: Vec<RowConstraint>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct MergeExec {
+    #[prost(message, tag = "1")]
+    pub table: Option<TableEntry>,
+    #[prost(string, tag = "2")]
+    pub source_alias: String,
+    #[prost(string, tag = "3")]
+    pub target_alias: String,
+    #[prost(message, tag = "4")]
+    pub on: Option<LogicalExprNode>,
+    #[prost(message, repeated, tag = "5")]
+    pub clauses: Vec<MergeClause>,
+    #[prost(message, repeated, tag = "6")]
+    pub constraints: Vec<RowConstraint>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct MergeClause {
+    #[prost(bool, tag = "1")]
+    pub matched: bool,
+    #[prost(bool, tag = "2")]
+    pub delete: bool,
+    #[prost(message, optional, tag = "3")]
+    pub predicate: Option<LogicalExprNode>,
+    /// Updated columns, or the values of an inserted row.
+    #[prost(message, repeated, tag = "4")]
+    pub assignments: Vec<UpdateSelector>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct RestoreTableExec {
+    #[prost(message, tag = "1")]
+    pub table: Option<TableEntry>,
+    /// Version number to restore, if restoring by version.
+    #[prost(int64, optional, tag = "2")]
+    pub version: Option<i64>,
+    /// Nanoseconds since the epoch, if restoring by timestamp.
+    #[prost(int64, optional, tag = "3")]
+    pub timestamp: Option<i64>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct RefreshMaterializedViewExec {
+    #[prost(uint64, tag = "1")]
+    pub catalog_version: u64,
+    #[prost(string, tag = "2")]
+    pub schema: String,
+    #[prost(message, tag = "3")]
+    pub table: Option<TableEntry>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CreateSequenceExec {
+    #[prost(uint64, tag = "1")]
+    pub catalog_version: u64,
+    #[prost(message, tag = "2")]
+    pub sequence: Option<crate::gen::metastore::service::CreateSequence>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommentOnExec {
+    #[prost(uint64, tag = "1")]
+    pub catalog_version: u64,
+    #[prost(message, tag = "2")]
+    pub comment: Option<crate::gen::metastore::service::CommentOn>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct DropSequencesExec {
+    #[prost(uint64, tag = "1")]
+    pub catalog_version: u64,
+    #[prost(message, repeated, tag = "2")]
+    pub sequence_references: Vec<FullObjectReference>,
+    #[prost(bool, tag = "3")]
+    pub if_exists: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct VacuumTableExec {
+    #[prost(message, tag = "1")]
+    pub table: Option<TableEntry>,
+    #[prost(uint64, optional, tag = "2")]
+    pub retain_hours: Option<u64>,
+    #[prost(bool, tag = "3")]
+    pub enforce_retention: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct OptimizeTableExec {
+    #[prost(message, tag = "1")]
+    pub table: Option<TableEntry>,
+    #[prost(string, repeated, tag = "2")]
+    pub zorder_by: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct UpsertExec {
+    #[prost(message, tag = "1")]
+    pub table: Option<TableEntry>,
+    #[prost(string, repeated, tag = "2")]
+    pub conflict_columns: Vec<String>,
+    #[prost(message, repeated, tag = "3")]
+    pub updates: Vec<UpdateSelector>,
+    #[prost(message, optional, tag = "4")]
+    pub update_predicate: Option<LogicalExprNode>,
+    #[prost(message, repeated, tag = "5")]
+    pub constraints: Vec<RowConstraint>,
 }
 
 #[derive(Clone, PartialEq, Message)]
@@ -343,7 +522,7 @@ pub struct AnalyzeExec {
 pub struct ExecutionPlanExtension {
     #[prost(
         oneof = "ExecutionPlanExtensionType",
-        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31"
+        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40"
     )]
     pub inner: Option<ExecutionPlanExtensionType>,
 }
@@ -417,4 +596,34 @@ pub enum ExecutionPlanExtensionType {
     DataSourceMetricsExecAdapter(DataSourceMetricsExecAdapter),
     #[prost(message, tag = "31")]
     DescribeTable(DescribeTableExec),
+    // Roles
+    #[prost(message, tag = "32")]
+    CreateRoleExec(CreateRoleExec),
+    #[prost(message, tag = "33")]
+    DropRoleExec(DropRoleExec),
+    #[prost(message, tag = "34")]
+    GrantPrivilegesExec(GrantPrivilegesExec),
+    #[prost(message, tag = "35")]
+    RevokePrivilegesExec(RevokePrivilegesExec),
+    // DML
+    #[prost(message, tag = "36")]
+    UpsertExec(UpsertExec),
+    #[prost(message, tag = "37")]
+    MergeExec(MergeExec),
+    #[prost(message, tag = "38")]
+    RestoreTableExec(RestoreTableExec),
+    #[prost(message, tag = "39")]
+    VacuumTableExec(VacuumTableExec),
+    #[prost(message, tag = "40")]
+    OptimizeTableExec(OptimizeTableExec),
+    #[prost(message, tag = "41")]
+    RefreshMaterializedViewExec(RefreshMaterializedViewExec),
+    // Sequences
+    #[prost(message, tag = "42")]
+    CreateSequenceExec(CreateSequenceExec),
+    #[prost(message, tag = "43")]
+    DropSequencesExec(DropSequencesExec),
+    // Comments
+    #[prost(message, tag = "44")]
+    CommentOnExec(CommentOnExec),
 }
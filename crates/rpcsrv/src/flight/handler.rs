@@ -93,6 +93,7 @@ impl FlightSessionHandler {
         ctx: MutexGuard<'_, Session>,
         lp: LogicalPlan,
     ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        ctx.start_statement();
         let plan = ctx
             .create_physical_plan(lp, &OperationInfo::default())
             .await
@@ -312,9 +313,11 @@ impl FlightSqlService for FlightSessionHandler {
             .await
             .map_err(RpcsrvError::from)?;
 
-        let portal = ctx.get_portal(&handle).map_err(RpcsrvError::from)?;
+        let stmt = ctx
+            .get_prepared_statement(&handle)
+            .map_err(RpcsrvError::from)?;
 
-        let output_schema = portal.output_schema().ok_or_else(|| {
+        let output_schema = stmt.output_schema().ok_or_else(|| {
             Status::internal("Expected a valid output schema, instead received: None".to_string())
         })?;
 
@@ -323,10 +326,24 @@ impl FlightSqlService for FlightSessionHandler {
             .map_err(RpcsrvError::from)?;
 
         let IpcMessage(schema_bytes) = message;
+
+        // Parameter types are only known once planned, empty if there aren't
+        // any parameters.
+        let parameter_schema = stmt.parameter_schema();
+        let parameter_schema = if parameter_schema.fields().is_empty() {
+            Default::default()
+        } else {
+            let IpcMessage(bytes) =
+                SchemaAsIpc::new(&parameter_schema, &IpcWriteOptions::default())
+                    .try_into()
+                    .map_err(RpcsrvError::from)?;
+            bytes
+        };
+
         let res = ActionCreatePreparedStatementResult {
             prepared_statement_handle: handle.into(),
             dataset_schema: schema_bytes,
-            parameter_schema: Default::default(), // TODO: parameters
+            parameter_schema,
         };
 
         Ok(res)
@@ -56,6 +56,7 @@ impl simple::simple_service_server::SimpleService for SimpleHandler {
             .await
             .map_err(RpcsrvError::from)?;
 
+        session.start_statement();
         let plan = session
             .create_logical_plan(&request.query_text)
             .await
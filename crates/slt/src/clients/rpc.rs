@@ -72,7 +72,7 @@ impl AsyncDB for RpcTestClient {
                 Vec::new(),
                 vec![Format::Text; num_fields],
             )?;
-            let stream = session.execute_portal(&UNNAMED, 0).await?;
+            let stream = session.execute_portal(&UNNAMED).await?;
 
             match stream {
                 ExecutionResult::Query { stream, .. } => {
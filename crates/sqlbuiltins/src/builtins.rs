@@ -16,7 +16,12 @@
 
 use std::sync::Arc;
 
-use datafusion::arrow::datatypes::{DataType, Field as ArrowField, Schema as ArrowSchema};
+use datafusion::arrow::datatypes::{
+    DataType,
+    Field as ArrowField,
+    Schema as ArrowSchema,
+    TimeUnit,
+};
 use once_cell::sync::Lazy;
 use pgrepr::oid::FIRST_GLAREDB_BUILTIN_ID;
 use protogen::metastore::types::options::InternalColumnDefinition;
@@ -101,6 +106,7 @@ pub static GLARE_DATABASES: Lazy<BuiltinTable> = Lazy::new(|| BuiltinTable {
         ("external", DataType::Boolean, false),
         ("datasource", DataType::Utf8, false),
         ("access_mode", DataType::Utf8, false), // `SourceAccessMode::as_str()`
+        ("description", DataType::Utf8, true),
     ]),
     oid: 16401,
 });
@@ -139,6 +145,7 @@ pub static GLARE_SCHEMAS: Lazy<BuiltinTable> = Lazy::new(|| BuiltinTable {
         ("database_name", DataType::Utf8, false),
         ("schema_name", DataType::Utf8, false),
         ("builtin", DataType::Boolean, false),
+        ("description", DataType::Utf8, true),
     ]),
     oid: 16404,
 });
@@ -156,6 +163,7 @@ pub static GLARE_TABLES: Lazy<BuiltinTable> = Lazy::new(|| BuiltinTable {
         ("external", DataType::Boolean, false),
         ("datasource", DataType::Utf8, false),
         ("access_mode", DataType::Utf8, false), // `SourceAccessMode::as_str()`
+        ("description", DataType::Utf8, true),
     ]),
     oid: 16405,
 });
@@ -171,6 +179,7 @@ pub static GLARE_VIEWS: Lazy<BuiltinTable> = Lazy::new(|| BuiltinTable {
         ("view_name", DataType::Utf8, false),
         ("builtin", DataType::Boolean, false),
         ("sql", DataType::Utf8, false),
+        ("description", DataType::Utf8, true),
     ]),
     oid: 16406,
 });
@@ -186,6 +195,7 @@ pub static GLARE_COLUMNS: Lazy<BuiltinTable> = Lazy::new(|| BuiltinTable {
         ("column_ordinal", DataType::UInt32, false),
         ("data_type", DataType::Utf8, false),
         ("is_nullable", DataType::Boolean, false),
+        ("description", DataType::Utf8, true),
     ]),
     oid: 16407,
 });
@@ -254,6 +264,48 @@ pub static GLARE_CACHED_EXTERNAL_DATABASE_TABLES: Lazy<BuiltinTable> = Lazy::new
     oid: 16411,
 });
 
+pub static GLARE_MATERIALIZED_VIEWS: Lazy<BuiltinTable> = Lazy::new(|| BuiltinTable {
+    schema: INTERNAL_SCHEMA,
+    name: "materialized_views",
+    columns: InternalColumnDefinition::from_tuples([
+        ("oid", DataType::UInt32, false),
+        ("database_oid", DataType::UInt32, false),
+        ("schema_oid", DataType::UInt32, false),
+        ("schema_name", DataType::Utf8, false),
+        ("view_name", DataType::Utf8, false),
+        ("sql", DataType::Utf8, false),
+        ("refresh_interval_secs", DataType::UInt64, true),
+        (
+            "last_refreshed",
+            DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            true,
+        ),
+    ]),
+    oid: 16412,
+});
+
+pub static GLARE_SEQUENCES: Lazy<BuiltinTable> = Lazy::new(|| BuiltinTable {
+    schema: INTERNAL_SCHEMA,
+    name: "sequences",
+    columns: InternalColumnDefinition::from_tuples([
+        ("oid", DataType::UInt32, false),
+        ("database_oid", DataType::UInt32, false),
+        ("schema_oid", DataType::UInt32, false),
+        ("schema_name", DataType::Utf8, false),
+        ("sequence_name", DataType::Utf8, false),
+        ("start_value", DataType::Int64, false),
+        ("increment_by", DataType::Int64, false),
+        ("min_value", DataType::Int64, false),
+        ("max_value", DataType::Int64, false),
+        ("cycle", DataType::Boolean, false),
+        ("cache_size", DataType::Int64, false),
+        ("last_value", DataType::Int64, true),
+        ("owned_by_table", DataType::Utf8, true),
+        ("owned_by_column", DataType::Utf8, true),
+    ]),
+    oid: 16413,
+});
+
 impl BuiltinTable {
     /// Check if this table matches the provided schema and name.
     pub fn matches(&self, schema: &str, name: &str) -> bool {
@@ -284,6 +336,8 @@ impl BuiltinTable {
             &GLARE_SSH_KEYS,
             &GLARE_DEPLOYMENT_METADATA,
             &GLARE_CACHED_EXTERNAL_DATABASE_TABLES,
+            &GLARE_MATERIALIZED_VIEWS,
+            &GLARE_SEQUENCES,
         ]
     }
 }
@@ -401,7 +455,8 @@ FROM (
         null AS user_defined_type_name,
         'NO' AS is_insertable_into,
         'NO' AS is_typed,
-        null AS commit_action
+        null AS commit_action,
+        t.description AS table_comment
     FROM glare_catalog.tables t INNER JOIN glare_catalog.databases d ON t.database_oid = d.oid
     UNION ALL
     SELECT
@@ -416,7 +471,8 @@ FROM (
         null AS user_defined_type_name,
         'NO' AS is_insertable_into,
         'NO' AS is_typed,
-        null AS commit_action
+        null AS commit_action,
+        v.description AS table_comment
     FROM glare_catalog.views v INNER JOIN glare_catalog.databases d ON v.database_oid = d.oid
 )",
 });
@@ -468,7 +524,8 @@ SELECT
     null AS identity_cycle,
     null AS is_generated,
     null AS generation_expression,
-    'NO' AS is_updatable
+    'NO' AS is_updatable,
+    c.description AS column_comment
 FROM glare_catalog.columns c
 INNER JOIN glare_catalog.schemas s ON c.schema_oid = s.oid
 INNER JOIN glare_catalog.databases d ON s.database_oid = d.oid
@@ -577,16 +634,31 @@ SELECT
 FROM glare_catalog.schemas s",
 });
 
-pub static PG_DESCRIPTION: Lazy<BuiltinView> = Lazy::new(|| BuiltinView {
+pub static PG_DESCRIPTION: Lazy<BuiltinView> = Lazy::new(|| {
+    BuiltinView {
     schema: POSTGRES_SCHEMA,
     name: "pg_description",
     sql: "
 SELECT
-    1 AS objoid,
-    2 AS classoid,
-    3 AS objsubid,
-    4 AS description
-FROM (VALUES (NULL, NULL, NULL, NULL)) WHERE false", // Create empty table for now.
+    d.objoid AS objoid,
+    c.oid AS classoid,
+    d.objsubid AS objsubid,
+    d.description AS description
+FROM (
+    SELECT t.oid AS objoid, 'pg_class' AS classname, 0::int AS objsubid, t.description AS description
+    FROM glare_catalog.tables t WHERE t.description IS NOT NULL
+    UNION ALL
+    SELECT v.oid, 'pg_class', 0::int, v.description
+    FROM glare_catalog.views v WHERE v.description IS NOT NULL
+    UNION ALL
+    SELECT col.table_oid, 'pg_class', (col.column_ordinal + 1)::int, col.description
+    FROM glare_catalog.columns col WHERE col.description IS NOT NULL
+    UNION ALL
+    SELECT s.oid, 'pg_namespace', 0::int, s.description
+    FROM glare_catalog.schemas s WHERE s.description IS NOT NULL
+) d
+INNER JOIN glare_catalog.views c ON c.schema_name = 'pg_catalog' AND c.view_name = d.classname",
+}
 });
 
 pub static PG_DATABASE: Lazy<BuiltinView> = Lazy::new(|| BuiltinView {
@@ -694,15 +766,16 @@ pub static PG_MATVIEWS: Lazy<BuiltinView> = Lazy::new(|| BuiltinView {
     schema: POSTGRES_SCHEMA,
     name: "pg_matviews",
     sql: "
-    SELECT '' as schemaname,
-    '' as matviewname,
+SELECT
+    schema_name as schemaname,
+    view_name as matviewname,
     '' as matviewowner,
     '' as tablespace,
     false as hasindexes,
-    false as ispopulated,
-    '' as definition
-    WHERE false
-    ",
+    last_refreshed IS NOT NULL as ispopulated,
+    sql as definition
+FROM glare_catalog.materialized_views;
+",
 });
 
 pub static PG_REWRITE: Lazy<BuiltinView> = Lazy::new(|| BuiltinView {
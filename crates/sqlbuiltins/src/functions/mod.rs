@@ -46,6 +46,7 @@ use self::alias_map::AliasMap;
 use crate::functions::scalars::bson2json::Json2Bson;
 use crate::functions::scalars::df_scalars::{Decode, Encode, IsNan, NullIf};
 use crate::functions::scalars::openai::OpenAIEmbed;
+use crate::functions::scalars::sequences::{CurrVal, NextVal, SetVal};
 use crate::functions::scalars::similarity::CosineSimilarity;
 
 /// `DEFAULT_BUILTIN_FUNCTIONS` provides all implementations of [`BuiltinFunction`]
@@ -241,6 +242,10 @@ impl FunctionRegistry {
             Arc::new(PgArrayToString),
             Arc::new(PgVersion),
             Arc::new(FormatType),
+            // Sequence functions
+            Arc::new(NextVal),
+            Arc::new(CurrVal),
+            Arc::new(SetVal),
             // System functions
             Arc::new(ConnectionId),
             Arc::new(Version),
@@ -5,6 +5,7 @@ pub mod jaq;
 pub mod kdl;
 pub mod openai;
 pub mod postgres;
+pub mod sequences;
 pub mod similarity;
 use std::sync::Arc;
 
@@ -1,7 +1,9 @@
 use std::sync::Arc;
 
 use catalog::session_catalog::SessionCatalog;
+use datafusion::arrow::array::{Array, BooleanArray};
 use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::common::cast::as_string_array;
 use datafusion::error::{DataFusionError, Result as DataFusionResult};
 use datafusion::logical_expr::expr::ScalarFunction;
 use datafusion::logical_expr::{
@@ -16,10 +18,11 @@ use datafusion::physical_plan::ColumnarValue;
 use datafusion::prelude::Expr;
 use datafusion::scalar::ScalarValue;
 use pgrepr::compatible::server_version_with_build_info;
-use protogen::metastore::types::catalog::FunctionType;
+use protogen::metastore::types::catalog::{CatalogEntry, FunctionType, Privilege};
 
 use super::df_scalars::array_to_string;
 use super::{get_nth_scalar_value, session_var};
+use crate::builtins::{DEFAULT_CATALOG, DEFAULT_SCHEMA};
 use crate::errors::BuiltinError;
 use crate::functions::{BuiltinScalarUDF, ConstBuiltinFunction, FunctionNamespace};
 
@@ -183,10 +186,18 @@ impl ConstBuiltinFunction for HasSchemaPrivilege {
 }
 
 impl BuiltinScalarUDF for HasSchemaPrivilege {
-    fn try_as_expr(&self, _: &SessionCatalog, args: Vec<Expr>) -> DataFusionResult<Expr> {
+    fn try_as_expr(&self, catalog: &SessionCatalog, args: Vec<Expr>) -> DataFusionResult<Expr> {
         let return_type_fn: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Boolean)));
-        let scalar_fn_impl: ScalarFunctionImplementation =
-            Arc::new(move |_input| Ok(ColumnarValue::Scalar(ScalarValue::Boolean(Some(true)))));
+        let catalog = catalog.clone();
+        let scalar_fn_impl: ScalarFunctionImplementation = Arc::new(move |input| {
+            has_privilege(&catalog, input, |catalog, name| {
+                let schema = match parse_object_name(name).as_slice() {
+                    [schema] => catalog.resolve_schema(schema)?,
+                    _ => return None,
+                };
+                catalog.get_by_oid(schema.meta.id)
+            })
+        });
         let udf = ScalarUDF::new(
             Self::NAME,
             &ConstBuiltinFunction::signature(self).unwrap(),
@@ -264,10 +275,18 @@ impl ConstBuiltinFunction for HasTablePrivilege {
 }
 
 impl BuiltinScalarUDF for HasTablePrivilege {
-    fn try_as_expr(&self, _: &SessionCatalog, args: Vec<Expr>) -> DataFusionResult<Expr> {
+    fn try_as_expr(&self, catalog: &SessionCatalog, args: Vec<Expr>) -> DataFusionResult<Expr> {
         let return_type_fn: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Boolean)));
-        let scalar_fn_impl: ScalarFunctionImplementation =
-            Arc::new(move |_| Ok(ColumnarValue::Scalar(ScalarValue::Boolean(Some(true)))));
+        let catalog = catalog.clone();
+        let scalar_fn_impl: ScalarFunctionImplementation = Arc::new(move |input| {
+            has_privilege(&catalog, input, |catalog, name| {
+                match parse_object_name(name).as_slice() {
+                    [name] => catalog.resolve_entry(DEFAULT_CATALOG, DEFAULT_SCHEMA, name),
+                    [schema, name] => catalog.resolve_entry(DEFAULT_CATALOG, schema, name),
+                    _ => None,
+                }
+            })
+        });
         let udf = ScalarUDF::new(
             Self::NAME,
             &ConstBuiltinFunction::signature(self).unwrap(),
@@ -285,6 +304,136 @@ impl BuiltinScalarUDF for HasTablePrivilege {
     }
 }
 
+/// Postgres privileges that aren't tracked by GlareDB. Roles are never granted
+/// these, while unrestricted users always have them.
+const UNTRACKED_PRIVILEGES: &[&str] = &[
+    "TRUNCATE",
+    "REFERENCES",
+    "TRIGGER",
+    "CREATE",
+    "TEMP",
+    "TEMPORARY",
+    "CONNECT",
+];
+
+/// Evaluate a `has_*_privilege` function.
+///
+/// Arguments are either `(object, privilege)` to check the session user, or
+/// `(user, object, privilege)`. The privilege may be a comma separated list,
+/// in which case the result is true if any of the privileges are held.
+fn has_privilege(
+    catalog: &SessionCatalog,
+    input: &[ColumnarValue],
+    resolve: impl for<'a> Fn(&'a SessionCatalog, &str) -> Option<&'a CatalogEntry>,
+) -> DataFusionResult<ColumnarValue> {
+    let num_rows = input
+        .iter()
+        .find_map(|v| match v {
+            ColumnarValue::Array(arr) => Some(arr.len()),
+            ColumnarValue::Scalar(_) => None,
+        })
+        .unwrap_or(1);
+    let arrays = input
+        .iter()
+        .map(|v| v.clone().into_array(num_rows))
+        .collect::<DataFusionResult<Vec<_>>>()?;
+    let arrays = arrays
+        .iter()
+        .map(|arr| as_string_array(arr.as_ref()))
+        .collect::<DataFusionResult<Vec<_>>>()?;
+
+    let (users, objects, privileges) = match arrays.as_slice() {
+        [objects, privileges] => (None, *objects, *privileges),
+        [users, objects, privileges] => (Some(*users), *objects, *privileges),
+        _ => {
+            return Err(DataFusionError::Execution(
+                "expected 2 or 3 arguments".to_string(),
+            ))
+        }
+    };
+
+    let mut results = Vec::with_capacity(num_rows);
+    for idx in 0..num_rows {
+        if objects.is_null(idx)
+            || privileges.is_null(idx)
+            || users.map(|u| u.is_null(idx)).unwrap_or(false)
+        {
+            results.push(None);
+            continue;
+        }
+
+        let role = match users {
+            Some(users) => catalog.resolve_role(users.value(idx)),
+            None => catalog.current_role(),
+        };
+
+        // Objects we can't resolve are only accessible to unrestricted users.
+        let ent = match resolve(catalog, objects.value(idx)) {
+            Some(ent) => ent,
+            None => {
+                results.push(Some(role.is_none()));
+                continue;
+            }
+        };
+
+        let mut allowed = false;
+        for privilege in privileges.value(idx).split(',') {
+            let privilege = privilege.trim().to_uppercase();
+            let privilege = privilege
+                .strip_suffix("WITH GRANT OPTION")
+                .unwrap_or(&privilege)
+                .trim();
+
+            let has = match (privilege.parse::<Privilege>(), role) {
+                (Ok(_), None) => true,
+                (Ok(privilege), Some(role)) => catalog.role_has_privilege(role, ent, privilege),
+                (Err(_), role) if UNTRACKED_PRIVILEGES.contains(&privilege) => role.is_none(),
+                (Err(_), _) => {
+                    return Err(DataFusionError::Execution(format!(
+                        "unrecognized privilege type: \"{privilege}\""
+                    )))
+                }
+            };
+            allowed |= has;
+        }
+        results.push(Some(allowed));
+    }
+
+    let results = BooleanArray::from(results);
+    Ok(
+        if input.iter().all(|v| matches!(v, ColumnarValue::Scalar(_))) {
+            ColumnarValue::Scalar(ScalarValue::try_from_array(&results, 0)?)
+        } else {
+            ColumnarValue::Array(Arc::new(results))
+        },
+    )
+}
+
+/// Split a possibly qualified object name into its parts.
+///
+/// Quoted parts are taken as-is, unquoted parts are lowercased.
+pub(super) fn parse_object_name(name: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut part = String::new();
+    let mut quoted = false;
+    let mut chars = name.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                part.push('"');
+            }
+            '"' => quoted = !quoted,
+            '.' if !quoted => parts.push(std::mem::take(&mut part)),
+            c if quoted => part.push(c),
+            c => part.extend(c.to_lowercase()),
+        }
+    }
+    parts.push(part);
+    parts
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct CurrentSchemas;
 
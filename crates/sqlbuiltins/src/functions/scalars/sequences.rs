@@ -0,0 +1,286 @@
+use std::sync::Arc;
+
+use catalog::session_catalog::SessionCatalog;
+use datafusion::arrow::array::{Array, AsArray, Int64Array};
+use datafusion::arrow::datatypes::{DataType, Int64Type};
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::logical_expr::expr::ScalarFunction;
+use datafusion::logical_expr::{
+    ReturnTypeFunction,
+    ScalarFunctionImplementation,
+    ScalarUDF,
+    Signature,
+    TypeSignature,
+    Volatility,
+};
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::prelude::Expr;
+use datafusion::scalar::ScalarValue;
+use protogen::metastore::types::catalog::{CatalogEntry, FunctionType, Privilege, SequenceEntry};
+use tokio::runtime::Handle;
+use tokio::task;
+
+use super::postgres::parse_object_name;
+use crate::builtins::{DEFAULT_CATALOG, DEFAULT_SCHEMA};
+use crate::functions::{BuiltinScalarUDF, ConstBuiltinFunction};
+
+/// Signature for functions taking only a sequence, either by name or oid.
+fn sequence_signature() -> Signature {
+    Signature::new(
+        TypeSignature::OneOf(vec![
+            TypeSignature::Exact(vec![DataType::Utf8]),
+            TypeSignature::Exact(vec![DataType::Int64]),
+        ]),
+        Volatility::Volatile,
+    )
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct NextVal;
+
+impl ConstBuiltinFunction for NextVal {
+    const NAME: &'static str = "nextval";
+    const DESCRIPTION: &'static str = "Advances the sequence and returns the new value";
+    const EXAMPLE: &'static str = "nextval('my_sequence')";
+    const FUNCTION_TYPE: FunctionType = FunctionType::Scalar;
+    fn signature(&self) -> Option<Signature> {
+        Some(sequence_signature())
+    }
+}
+
+impl BuiltinScalarUDF for NextVal {
+    fn try_as_expr(&self, catalog: &SessionCatalog, args: Vec<Expr>) -> DataFusionResult<Expr> {
+        let [arg] = args.as_slice() else {
+            return Err(DataFusionError::Plan(format!(
+                "{} expects exactly one argument",
+                Self::NAME
+            )));
+        };
+        let (schema, sequence) = resolve_sequence(catalog, arg)?;
+        let sequences = catalog.sequences().clone();
+
+        let scalar_fn_impl: ScalarFunctionImplementation = Arc::new(move |input| {
+            let num_rows = num_rows(input);
+            // No way around blocking here, expressions are not async.
+            let values = task::block_in_place(|| {
+                Handle::current().block_on(sequences.next_values(&schema, &sequence, num_rows))
+            })
+            .map_err(|e| DataFusionError::Execution(e.to_string()))?;
+            Ok(ColumnarValue::Array(Arc::new(Int64Array::from(values))))
+        });
+
+        Ok(zero_arg_udf(Self::NAME, scalar_fn_impl))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CurrVal;
+
+impl ConstBuiltinFunction for CurrVal {
+    const NAME: &'static str = "currval";
+    const DESCRIPTION: &'static str =
+        "Returns the value most recently returned by nextval for the sequence in this session";
+    const EXAMPLE: &'static str = "currval('my_sequence')";
+    const FUNCTION_TYPE: FunctionType = FunctionType::Scalar;
+    fn signature(&self) -> Option<Signature> {
+        Some(sequence_signature())
+    }
+}
+
+impl BuiltinScalarUDF for CurrVal {
+    fn try_as_expr(&self, catalog: &SessionCatalog, args: Vec<Expr>) -> DataFusionResult<Expr> {
+        let [arg] = args.as_slice() else {
+            return Err(DataFusionError::Plan(format!(
+                "{} expects exactly one argument",
+                Self::NAME
+            )));
+        };
+        let (_, sequence) = resolve_sequence(catalog, arg)?;
+        let sequences = catalog.sequences().clone();
+
+        let scalar_fn_impl: ScalarFunctionImplementation = Arc::new(move |input| {
+            let value = sequences.current_value(&sequence).ok_or_else(|| {
+                DataFusionError::Execution(format!(
+                    "currval of sequence '{}' is not yet defined in this session",
+                    sequence.meta.name
+                ))
+            })?;
+            Ok(ColumnarValue::Array(Arc::new(Int64Array::from(vec![
+                value;
+                num_rows(input)
+            ]))))
+        });
+
+        Ok(zero_arg_udf(Self::NAME, scalar_fn_impl))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SetVal;
+
+impl ConstBuiltinFunction for SetVal {
+    const NAME: &'static str = "setval";
+    const DESCRIPTION: &'static str = "Sets the current value of the sequence. If the optional third argument is false, the next call to nextval returns the value itself";
+    const EXAMPLE: &'static str = "setval('my_sequence', 42)";
+    const FUNCTION_TYPE: FunctionType = FunctionType::Scalar;
+    fn signature(&self) -> Option<Signature> {
+        Some(Signature::new(
+            TypeSignature::OneOf(vec![
+                TypeSignature::Exact(vec![DataType::Utf8, DataType::Int64]),
+                TypeSignature::Exact(vec![DataType::Int64, DataType::Int64]),
+                TypeSignature::Exact(vec![DataType::Utf8, DataType::Int64, DataType::Boolean]),
+                TypeSignature::Exact(vec![DataType::Int64, DataType::Int64, DataType::Boolean]),
+            ]),
+            Volatility::Volatile,
+        ))
+    }
+}
+
+impl BuiltinScalarUDF for SetVal {
+    fn try_as_expr(&self, catalog: &SessionCatalog, mut args: Vec<Expr>) -> DataFusionResult<Expr> {
+        if !(2..=3).contains(&args.len()) {
+            return Err(DataFusionError::Plan(format!(
+                "{} expects two or three arguments",
+                Self::NAME
+            )));
+        }
+        let (schema, sequence) = resolve_sequence(catalog, &args.remove(0))?;
+        let sequences = catalog.sequences().clone();
+
+        let return_type_fn: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Int64)));
+        let scalar_fn_impl: ScalarFunctionImplementation = Arc::new(move |input| {
+            let num_rows = num_rows(input);
+            let values = input[0].clone().into_array(num_rows)?;
+            let values = values.as_primitive::<Int64Type>();
+            let is_called = match input.get(1) {
+                Some(is_called) => Some(is_called.clone().into_array(num_rows)?),
+                None => None,
+            };
+
+            for idx in 0..num_rows {
+                if values.is_null(idx) {
+                    continue;
+                }
+                let is_called = match &is_called {
+                    Some(arr) => arr.as_boolean().is_valid(idx) && arr.as_boolean().value(idx),
+                    None => true,
+                };
+                task::block_in_place(|| {
+                    Handle::current().block_on(sequences.set_value(
+                        &schema,
+                        &sequence,
+                        values.value(idx),
+                        is_called,
+                    ))
+                })
+                .map_err(|e| DataFusionError::Execution(e.to_string()))?;
+            }
+
+            Ok(ColumnarValue::Array(Arc::new(values.clone())))
+        });
+
+        let udf = ScalarUDF::new(
+            Self::NAME,
+            &Signature::new(
+                TypeSignature::OneOf(vec![
+                    TypeSignature::Exact(vec![DataType::Int64]),
+                    TypeSignature::Exact(vec![DataType::Int64, DataType::Boolean]),
+                ]),
+                Volatility::Volatile,
+            ),
+            &return_type_fn,
+            &scalar_fn_impl,
+        );
+        Ok(Expr::ScalarFunction(ScalarFunction::new_udf(
+            Arc::new(udf),
+            args,
+        )))
+    }
+}
+
+/// Resolve the sequence referenced by a function argument, returning the name
+/// of its schema along with the sequence.
+///
+/// The argument is either the sequence's oid, or its (possibly qualified)
+/// name. Unqualified names are resolved in the default schema. Names are
+/// usually replaced with oids during planning, which uses the session's search
+/// path.
+fn resolve_sequence(
+    catalog: &SessionCatalog,
+    arg: &Expr,
+) -> DataFusionResult<(String, SequenceEntry)> {
+    let (name, sequence) = match arg {
+        Expr::Literal(ScalarValue::Utf8(Some(name))) => {
+            let sequence = match parse_object_name(name).as_slice() {
+                [seq] => catalog.resolve_sequence(DEFAULT_CATALOG, DEFAULT_SCHEMA, seq),
+                [schema, seq] => catalog.resolve_sequence(DEFAULT_CATALOG, schema, seq),
+                _ => None,
+            };
+            (name.clone(), sequence)
+        }
+        Expr::Literal(scalar) if scalar.data_type().is_integer() => {
+            let sequence = match scalar.cast_to(&DataType::UInt32)? {
+                ScalarValue::UInt32(Some(oid)) => match catalog.get_by_oid(oid) {
+                    Some(CatalogEntry::Sequence(sequence)) => Some(sequence),
+                    _ => None,
+                },
+                _ => None,
+            };
+            (scalar.to_string(), sequence)
+        }
+        other => {
+            return Err(DataFusionError::Plan(format!(
+                "Expected a sequence name or oid, got: {other}"
+            )))
+        }
+    };
+
+    let sequence = sequence
+        .ok_or_else(|| DataFusionError::Plan(format!("sequence '{name}' does not exist")))?;
+    if !catalog.has_privilege(&CatalogEntry::Sequence(sequence.clone()), Privilege::Usage) {
+        return Err(DataFusionError::Plan(format!(
+            "permission denied for sequence {}",
+            sequence.meta.name
+        )));
+    }
+    let schema = match catalog.get_by_oid(sequence.meta.parent) {
+        Some(CatalogEntry::Schema(schema)) => schema.meta.name.clone(),
+        _ => {
+            return Err(DataFusionError::Plan(format!(
+                "Missing schema for sequence '{}'",
+                sequence.meta.name
+            )))
+        }
+    };
+
+    Ok((schema, sequence.clone()))
+}
+
+/// Build a volatile function taking no arguments and returning an Int64.
+///
+/// The sequence argument is resolved when building the expression, so the
+/// function is only called to produce values for each row.
+fn zero_arg_udf(name: &str, scalar_fn_impl: ScalarFunctionImplementation) -> Expr {
+    let return_type_fn: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Int64)));
+    let udf = ScalarUDF::new(
+        name,
+        &Signature::exact(vec![], Volatility::Volatile),
+        &return_type_fn,
+        &scalar_fn_impl,
+    );
+    Expr::ScalarFunction(ScalarFunction::new_udf(Arc::new(udf), Vec::new()))
+}
+
+/// Get the number of rows to produce values for.
+///
+/// Functions without arguments are passed a null array with the length of the
+/// batch.
+fn num_rows(input: &[ColumnarValue]) -> usize {
+    input
+        .iter()
+        .find_map(|v| match v {
+            ColumnarValue::Array(arr) => Some(arr.len()),
+            ColumnarValue::Scalar(_) => None,
+        })
+        .unwrap_or(1)
+}
@@ -5,9 +5,16 @@ use async_trait::async_trait;
 use datafusion::datasource::TableProvider;
 use datafusion_ext::errors::{ExtensionError, Result};
 use datafusion_ext::functions::{FuncParamValue, TableFuncContextProvider};
-use datasources::lake::delta::access::load_table_direct;
+use datasources::lake::delta::access::{
+    load_table_direct,
+    load_table_direct_version,
+    table_history_direct,
+};
+use datasources::lake::delta::changes::DeltaChangesTable;
+use datasources::lake::delta::version::TableVersion;
 use protogen::metastore::types::catalog::{FunctionType, RuntimePreference};
 
+use super::versions::{history_table, timestamp_nanos};
 use super::{table_location_and_opts, TableFunc};
 use crate::functions::ConstBuiltinFunction;
 
@@ -19,6 +26,9 @@ use crate::functions::ConstBuiltinFunction;
 /// the store.
 ///
 /// See <https://github.com/delta-io/delta-rs/issues/1521>
+///
+/// The latest version is read by default. A previous version can be read by
+/// providing either `version` or `timestamp`.
 #[derive(Debug, Clone, Copy)]
 pub struct DeltaScan;
 
@@ -47,11 +57,141 @@ impl TableFunc for DeltaScan {
         args: Vec<FuncParamValue>,
         mut opts: HashMap<String, FuncParamValue>,
     ) -> Result<Arc<dyn TableProvider>> {
+        let version = scan_version(&mut opts)?;
         let (source_url, storage_options) = table_location_and_opts(ctx, args, &mut opts)?;
 
-        let table = load_table_direct(&source_url.to_string(), storage_options)
+        let table = match version {
+            Some(version) => {
+                load_table_direct_version(&source_url.to_string(), storage_options, version).await
+            }
+            None => load_table_direct(&source_url.to_string(), storage_options).await,
+        }
+        .map_err(|e| ExtensionError::Access(Box::new(e)))?;
+
+        Ok(Arc::new(table))
+    }
+}
+
+/// Get the version of the table to read from the function options, if one
+/// was provided.
+fn scan_version(opts: &mut HashMap<String, FuncParamValue>) -> Result<Option<TableVersion>> {
+    match (opts.remove("version"), opts.remove("timestamp")) {
+        (Some(_), Some(_)) => Err(ExtensionError::String(
+            "Only one of 'version' or 'timestamp' may be provided".to_string(),
+        )),
+        (Some(version), None) => Ok(Some(TableVersion::Version(version.try_into()?))),
+        (None, Some(timestamp)) => Ok(Some(TableVersion::Timestamp(timestamp_nanos(timestamp)?))),
+        (None, None) => Ok(None),
+    }
+}
+
+/// List the commits to a delta table, most recent first.
+///
+/// The number of commits returned can be limited with `limit`.
+#[derive(Debug, Clone, Copy)]
+pub struct DeltaHistory;
+
+impl ConstBuiltinFunction for DeltaHistory {
+    const NAME: &'static str = "delta_history";
+    const DESCRIPTION: &'static str = "Lists the commits to a delta table";
+    const EXAMPLE: &'static str = "SELECT * FROM delta_history('file:///path/to/table')";
+    const FUNCTION_TYPE: FunctionType = FunctionType::TableReturning;
+}
+
+#[async_trait]
+impl TableFunc for DeltaHistory {
+    fn detect_runtime(
+        &self,
+        _args: &[FuncParamValue],
+        _parent: RuntimePreference,
+    ) -> Result<RuntimePreference> {
+        Ok(RuntimePreference::Remote)
+    }
+
+    async fn create_provider(
+        &self,
+        ctx: &dyn TableFuncContextProvider,
+        args: Vec<FuncParamValue>,
+        mut opts: HashMap<String, FuncParamValue>,
+    ) -> Result<Arc<dyn TableProvider>> {
+        let limit: Option<usize> = opts.remove("limit").map(TryInto::try_into).transpose()?;
+        let (source_url, storage_options) = table_location_and_opts(ctx, args, &mut opts)?;
+
+        let commits = table_history_direct(&source_url.to_string(), storage_options, limit)
             .await
-            .map_err(|e| ExtensionError::Access(Box::new(e)))?;
+            .map_err(ExtensionError::access)?;
+
+        history_table(commits)
+    }
+}
+
+/// Read the row level changes made to a delta table between two versions
+/// from the table's change data feed.
+///
+/// The end version defaults to the latest version of the table.
+#[derive(Debug, Clone, Copy)]
+pub struct DeltaChanges;
+
+impl ConstBuiltinFunction for DeltaChanges {
+    const NAME: &'static str = "delta_changes";
+    const DESCRIPTION: &'static str =
+        "Reads the changes made to a delta table between two versions";
+    const EXAMPLE: &'static str = "SELECT * FROM delta_changes('file:///path/to/table', 1, 3)";
+    const FUNCTION_TYPE: FunctionType = FunctionType::TableReturning;
+}
+
+#[async_trait]
+impl TableFunc for DeltaChanges {
+    fn detect_runtime(
+        &self,
+        _args: &[FuncParamValue],
+        _parent: RuntimePreference,
+    ) -> Result<RuntimePreference> {
+        Ok(RuntimePreference::Remote)
+    }
+
+    async fn create_provider(
+        &self,
+        ctx: &dyn TableFuncContextProvider,
+        args: Vec<FuncParamValue>,
+        mut opts: HashMap<String, FuncParamValue>,
+    ) -> Result<Arc<dyn TableProvider>> {
+        // Versions follow the location, with an optional credentials object
+        // in between.
+        let mut args = args.into_iter();
+        let location = args
+            .next()
+            .ok_or_else(|| ExtensionError::ExpectedIndexedArgument {
+                index: 0,
+                what: "location for the table".to_string(),
+            })?;
+        let (creds, versions): (Vec<_>, Vec<_>) =
+            args.partition(|arg| matches!(arg, FuncParamValue::Ident(_)));
+
+        let mut versions = versions.into_iter();
+        let start_version: i64 = versions
+            .next()
+            .ok_or_else(|| ExtensionError::ExpectedIndexedArgument {
+                index: 1,
+                what: "start version".to_string(),
+            })?
+            .try_into()?;
+        let end_version: Option<i64> = versions.next().map(TryInto::try_into).transpose()?;
+        if versions.next().is_some() {
+            return Err(ExtensionError::InvalidNumArgs);
+        }
+
+        let args = std::iter::once(location).chain(creds).collect();
+        let (source_url, storage_options) = table_location_and_opts(ctx, args, &mut opts)?;
+
+        let table = DeltaChangesTable::load(
+            &source_url.to_string(),
+            storage_options,
+            start_version,
+            end_version,
+        )
+        .await
+        .map_err(ExtensionError::access)?;
 
         Ok(Arc::new(table))
     }
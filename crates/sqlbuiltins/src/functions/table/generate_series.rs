@@ -58,6 +58,10 @@ impl TableFunc for GenerateSeries {
         })
     }
 
+    fn requires_execute_grant(&self) -> bool {
+        false
+    }
+
     async fn create_provider(
         &self,
         _: &dyn TableFuncContextProvider,
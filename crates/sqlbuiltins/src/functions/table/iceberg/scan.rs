@@ -2,10 +2,12 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use datafusion::arrow::datatypes::{DataType, TimeUnit};
 use datafusion::datasource::TableProvider;
+use datafusion::scalar::ScalarValue;
 use datafusion_ext::errors::{ExtensionError, Result};
 use datafusion_ext::functions::{FuncParamValue, TableFuncContextProvider};
-use datasources::lake::iceberg::table::IcebergTable;
+use datasources::lake::iceberg::table::{IcebergTable, SnapshotSelector};
 use datasources::lake::storage_options_into_object_store;
 use protogen::metastore::types::catalog::{FunctionType, RuntimePreference};
 
@@ -13,6 +15,10 @@ use crate::functions::table::{table_location_and_opts, TableFunc};
 use crate::functions::ConstBuiltinFunction;
 
 /// Scan an iceberg table.
+///
+/// The current snapshot is read by default. A past snapshot can be read by
+/// providing one of `snapshot_id`, `as_of` (a timestamp), or `ref` (the name
+/// of a branch or tag).
 #[derive(Debug, Clone, Copy)]
 pub struct IcebergScan;
 
@@ -42,15 +48,65 @@ impl TableFunc for IcebergScan {
         mut opts: HashMap<String, FuncParamValue>,
     ) -> Result<Arc<dyn TableProvider>> {
         // TODO: Reduce duplication
+        let selector = snapshot_selector(&mut opts)?;
         let (loc, opts) = table_location_and_opts(ctx, args, &mut opts)?;
 
         let store =
             storage_options_into_object_store(&loc, &opts).map_err(ExtensionError::access)?;
-        let table = IcebergTable::open(loc.clone(), store)
+        let mut table = IcebergTable::open(loc.clone(), store)
             .await
             .map_err(ExtensionError::access)?;
+        if let Some(selector) = selector {
+            table = table
+                .with_snapshot(&selector)
+                .map_err(ExtensionError::access)?;
+        }
         let reader = table.table_reader().await.map_err(ExtensionError::access)?;
 
         Ok(reader)
     }
 }
+
+/// Get the snapshot to read from the function options, if one was provided.
+fn snapshot_selector(
+    opts: &mut HashMap<String, FuncParamValue>,
+) -> Result<Option<SnapshotSelector>> {
+    let mut selectors = Vec::new();
+
+    if let Some(id) = opts.remove("snapshot_id") {
+        selectors.push(SnapshotSelector::Id(id.try_into()?));
+    }
+    if let Some(timestamp) = opts.remove("as_of") {
+        selectors.push(SnapshotSelector::AsOf(timestamp_millis(timestamp)?));
+    }
+    if let Some(name) = opts.remove("ref") {
+        selectors.push(SnapshotSelector::Ref(name.try_into()?));
+    }
+
+    if selectors.len() > 1 {
+        return Err(ExtensionError::String(
+            "Only one of 'snapshot_id', 'as_of', or 'ref' may be provided".to_string(),
+        ));
+    }
+
+    Ok(selectors.pop())
+}
+
+/// Get milliseconds since epoch from a timestamp argument.
+fn timestamp_millis(arg: FuncParamValue) -> Result<i64> {
+    match arg {
+        FuncParamValue::Scalar(scalar) => {
+            match scalar.cast_to(&DataType::Timestamp(TimeUnit::Millisecond, None))? {
+                ScalarValue::TimestampMillisecond(Some(millis), _) => Ok(millis),
+                _ => Err(ExtensionError::InvalidParamValue {
+                    param: scalar.to_string(),
+                    expected: "timestamp",
+                }),
+            }
+        }
+        other => Err(ExtensionError::InvalidParamValue {
+            param: other.to_string(),
+            expected: "timestamp",
+        }),
+    }
+}
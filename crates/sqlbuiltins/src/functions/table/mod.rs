@@ -11,6 +11,7 @@ mod json;
 mod lance;
 mod mongodb;
 mod mysql;
+mod native;
 mod object_store;
 mod parquet_metadata;
 mod postgres;
@@ -21,6 +22,7 @@ mod snowflake;
 mod sqlite;
 mod sqlserver;
 pub mod system;
+mod versions;
 mod virtual_listing;
 
 use std::collections::HashMap;
@@ -41,7 +43,7 @@ use self::bigquery::ReadBigQuery;
 use self::bson::BsonScan;
 use self::cassandra::ReadCassandra;
 use self::clickhouse::ReadClickhouse;
-use self::delta::DeltaScan;
+use self::delta::{DeltaChanges, DeltaHistory, DeltaScan};
 use self::excel::ExcelScan;
 use self::generate_series::GenerateSeries;
 use self::iceberg::data_files::IcebergDataFiles;
@@ -51,6 +53,7 @@ use self::json::JsonScan;
 use self::lance::LanceScan;
 use self::mongodb::ReadMongoDb;
 use self::mysql::ReadMysql;
+use self::native::{ReadNativeVersion, TableHistory};
 use self::object_store::{CloudUpload, READ_CSV, READ_JSON, READ_PARQUET};
 use self::parquet_metadata::ParquetMetadataFunc;
 use self::postgres::ReadPostgres;
@@ -77,6 +80,16 @@ pub trait TableFunc: BuiltinFunction {
         _parent: RuntimePreference,
     ) -> Result<RuntimePreference>;
 
+    /// Whether the function reaches data not covered by catalog privileges,
+    /// such as external systems, the local filesystem or native table
+    /// storage.
+    ///
+    /// Sessions restricted to a role must be granted EXECUTE on these
+    /// functions.
+    fn requires_execute_grant(&self) -> bool {
+        true
+    }
+
     /// Return a table provider using the provided args.
     async fn create_provider(
         &self,
@@ -115,11 +128,16 @@ impl BuiltinTableFuncs {
             Arc::new(CloudUpload),
             // Data lakes
             Arc::new(DeltaScan),
+            Arc::new(DeltaHistory),
+            Arc::new(DeltaChanges),
             Arc::new(IcebergScan),
             Arc::new(IcebergSnapshots),
             Arc::new(IcebergDataFiles),
             Arc::new(ExcelScan),
             Arc::new(LanceScan),
+            // Native tables
+            Arc::new(TableHistory),
+            Arc::new(ReadNativeVersion),
             // Listing
             Arc::new(ListSchemas),
             Arc::new(ListTables),
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::datasource::TableProvider;
+use datafusion_ext::errors::{ExtensionError, Result};
+use datafusion_ext::functions::{FuncParamValue, IdentValue, TableFuncContextProvider};
+use datasources::native::access::NativeTableStorage;
+use protogen::metastore::types::catalog::{
+    CatalogEntry,
+    FunctionType,
+    Privilege,
+    RuntimePreference,
+    TableEntry,
+};
+use protogen::metastore::types::options::TableOptionsV0;
+
+use super::versions::{history_table, table_version};
+use super::TableFunc;
+use crate::functions::ConstBuiltinFunction;
+
+/// List the versions of a native table.
+#[derive(Debug, Clone, Copy)]
+pub struct TableHistory;
+
+impl ConstBuiltinFunction for TableHistory {
+    const NAME: &'static str = "table_history";
+    const DESCRIPTION: &'static str = "Lists the versions of a native table";
+    const EXAMPLE: &'static str = "SELECT * FROM table_history('my_table')";
+    const FUNCTION_TYPE: FunctionType = FunctionType::TableReturning;
+}
+
+#[async_trait]
+impl TableFunc for TableHistory {
+    fn detect_runtime(
+        &self,
+        _args: &[FuncParamValue],
+        _parent: RuntimePreference,
+    ) -> Result<RuntimePreference> {
+        Ok(RuntimePreference::Remote)
+    }
+
+    async fn create_provider(
+        &self,
+        ctx: &dyn TableFuncContextProvider,
+        args: Vec<FuncParamValue>,
+        _opts: HashMap<String, FuncParamValue>,
+    ) -> Result<Arc<dyn TableProvider>> {
+        let (name, limit) = match args.len() {
+            1 => (args.into_iter().next().unwrap(), None),
+            2 => {
+                let mut args = args.into_iter();
+                let name = args.next().unwrap();
+                let limit: usize = args.next().unwrap().try_into()?;
+                (name, Some(limit))
+            }
+            _ => return Err(ExtensionError::InvalidNumArgs),
+        };
+
+        let table = resolve_native_table(ctx, name)?;
+        let commits = native_storage(ctx, Self::NAME)?
+            .table_history(&table, limit)
+            .await
+            .map_err(ExtensionError::access)?;
+
+        history_table(commits)
+    }
+}
+
+/// Read a previous version of a native table.
+#[derive(Debug, Clone, Copy)]
+pub struct ReadNativeVersion;
+
+impl ConstBuiltinFunction for ReadNativeVersion {
+    const NAME: &'static str = "read_native_version";
+    const DESCRIPTION: &'static str =
+        "Reads a native table as of a version number or a point in time";
+    const EXAMPLE: &'static str = "SELECT * FROM read_native_version('my_table', 3)";
+    const FUNCTION_TYPE: FunctionType = FunctionType::TableReturning;
+}
+
+#[async_trait]
+impl TableFunc for ReadNativeVersion {
+    fn detect_runtime(
+        &self,
+        _args: &[FuncParamValue],
+        _parent: RuntimePreference,
+    ) -> Result<RuntimePreference> {
+        Ok(RuntimePreference::Remote)
+    }
+
+    async fn create_provider(
+        &self,
+        ctx: &dyn TableFuncContextProvider,
+        args: Vec<FuncParamValue>,
+        _opts: HashMap<String, FuncParamValue>,
+    ) -> Result<Arc<dyn TableProvider>> {
+        if args.len() != 2 {
+            return Err(ExtensionError::InvalidNumArgs);
+        }
+        let mut args = args.into_iter();
+        let table = resolve_native_table(ctx, args.next().unwrap())?;
+        let version = table_version(args.next().unwrap())?;
+
+        let table = native_storage(ctx, Self::NAME)?
+            .load_table_version(&table, version)
+            .await
+            .map_err(ExtensionError::access)?;
+
+        Ok(table.into_table_provider())
+    }
+}
+
+/// Get the native table storage from the session.
+fn native_storage(
+    ctx: &dyn TableFuncContextProvider,
+    func: &str,
+) -> Result<Arc<NativeTableStorage>> {
+    ctx.get_session_state()
+        .config()
+        .get_extension::<NativeTableStorage>()
+        .ok_or_else(|| ExtensionError::String(format!("{func} requires native table storage")))
+}
+
+/// Resolve the native table named by a function argument.
+///
+/// Unqualified names are resolved using the session's search path. The
+/// session user must be allowed to read the table.
+fn resolve_native_table(
+    ctx: &dyn TableFuncContextProvider,
+    arg: FuncParamValue,
+) -> Result<TableEntry> {
+    let name: IdentValue = arg.try_into()?;
+    let name = name.as_str();
+    let catalog = ctx.get_session_catalog();
+
+    let table = match name.split('.').collect::<Vec<_>>().as_slice() {
+        [table] => ctx
+            .get_session_vars()
+            .implicit_search_path_iter()
+            .find_map(|schema| catalog.resolve_table("default", &schema, table)),
+        [schema, table] | [_, schema, table] => catalog.resolve_table("default", schema, table),
+        _ => None,
+    };
+    let table = table.ok_or_else(|| ExtensionError::MissingObject {
+        obj_typ: "table",
+        name: name.to_string(),
+    })?;
+
+    if table.meta.external || !matches!(table.options, TableOptionsV0::Internal(_)) {
+        return Err(ExtensionError::String(format!(
+            "'{name}' is not a native table"
+        )));
+    }
+    if !catalog.has_privilege(&CatalogEntry::Table(table.clone()), Privilege::Select) {
+        return Err(ExtensionError::String(format!(
+            "permission denied for table {}",
+            table.meta.name
+        )));
+    }
+
+    Ok(table.clone())
+}
@@ -0,0 +1,109 @@
+//! Helpers shared by functions reading the history and previous versions of
+//! delta backed tables.
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Int64Builder, StringBuilder, TimestampMillisecondBuilder};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::{MemTable, TableProvider};
+use datafusion::scalar::ScalarValue;
+use datafusion_ext::errors::{ExtensionError, Result};
+use datafusion_ext::functions::FuncParamValue;
+use datasources::lake::delta::version::{TableCommit, TableVersion};
+
+/// Create a table listing the given commits.
+pub fn history_table(commits: Vec<TableCommit>) -> Result<Arc<dyn TableProvider>> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("version", DataType::Int64, false),
+        Field::new(
+            "timestamp",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            true,
+        ),
+        Field::new("operation", DataType::Utf8, true),
+        Field::new("operation_parameters", DataType::Utf8, true),
+        Field::new("operation_metrics", DataType::Utf8, true),
+        Field::new("user_metadata", DataType::Utf8, true),
+    ]));
+
+    let mut version = Int64Builder::new();
+    let mut timestamp = TimestampMillisecondBuilder::new().with_timezone("UTC");
+    let mut operation = StringBuilder::new();
+    let mut operation_parameters = StringBuilder::new();
+    let mut operation_metrics = StringBuilder::new();
+    let mut user_metadata = StringBuilder::new();
+
+    for commit in commits {
+        version.append_value(commit.version);
+        timestamp.append_option(commit.info.timestamp);
+        operation.append_option(commit.info.operation);
+        operation_parameters.append_option(
+            commit
+                .info
+                .operation_parameters
+                .map(|params| serde_json::to_string(&params))
+                .transpose()
+                .map_err(ExtensionError::access)?,
+        );
+        operation_metrics.append_option(
+            commit
+                .info
+                .info
+                .get("operationMetrics")
+                .map(serde_json::to_string)
+                .transpose()
+                .map_err(ExtensionError::access)?,
+        );
+        user_metadata.append_option(
+            commit
+                .info
+                .info
+                .get("userMetadata")
+                .and_then(|metadata| metadata.as_str()),
+        );
+    }
+
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(version.finish()),
+            Arc::new(timestamp.finish()),
+            Arc::new(operation.finish()),
+            Arc::new(operation_parameters.finish()),
+            Arc::new(operation_metrics.finish()),
+            Arc::new(user_metadata.finish()),
+        ],
+    )?;
+
+    Ok(Arc::new(MemTable::try_new(schema, vec![vec![batch]])?))
+}
+
+/// Get the table version from a function argument, either a version number or
+/// a timestamp.
+pub fn table_version(arg: FuncParamValue) -> Result<TableVersion> {
+    match arg {
+        FuncParamValue::Scalar(scalar) if scalar.data_type().is_integer() => Ok(
+            TableVersion::Version(FuncParamValue::Scalar(scalar).try_into()?),
+        ),
+        other => Ok(TableVersion::Timestamp(timestamp_nanos(other)?)),
+    }
+}
+
+/// Get nanoseconds since epoch from a timestamp argument.
+pub fn timestamp_nanos(arg: FuncParamValue) -> Result<i64> {
+    match arg {
+        FuncParamValue::Scalar(scalar) => {
+            match scalar.cast_to(&DataType::Timestamp(TimeUnit::Nanosecond, None))? {
+                ScalarValue::TimestampNanosecond(Some(nanos), _) => Ok(nanos),
+                _ => Err(ExtensionError::InvalidParamValue {
+                    param: scalar.to_string(),
+                    expected: "timestamp",
+                }),
+            }
+        }
+        other => Err(ExtensionError::InvalidParamValue {
+            param: other.to_string(),
+            expected: "timestamp",
+        }),
+    }
+}
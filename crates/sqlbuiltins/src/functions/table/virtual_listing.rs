@@ -18,6 +18,7 @@ use datasources::bigquery::BigQueryAccessor;
 use datasources::cassandra::CassandraAccess;
 use datasources::clickhouse::ClickhouseAccess;
 use datasources::debug::DebugVirtualLister;
+use datasources::lake::iceberg::catalog::IcebergCatalogAccessor;
 use datasources::mongodb::MongoDbAccessor;
 use datasources::mysql::MysqlAccessor;
 use datasources::postgres::PostgresAccess;
@@ -30,6 +31,7 @@ use protogen::metastore::types::options::{
     DatabaseOptionsBigQuery,
     DatabaseOptionsCassandra,
     DatabaseOptionsClickhouse,
+    DatabaseOptionsIceberg,
     DatabaseOptionsMongoDb,
     DatabaseOptionsMysql,
     DatabaseOptionsPostgres,
@@ -403,6 +405,15 @@ pub(crate) async fn get_virtual_lister_for_external_db(
                 .await?;
             Box::new(state)
         }
+        DatabaseOptions::Iceberg(DatabaseOptionsIceberg {
+            catalog,
+            storage_options,
+        }) => {
+            let accessor = IcebergCatalogAccessor::connect(catalog, storage_options.clone())
+                .await
+                .map_err(ExtensionError::access)?;
+            Box::new(accessor)
+        }
         DatabaseOptions::Delta(_) => {
             return Err(ExtensionError::Unimplemented(
                 "deltalake information listing",
@@ -86,8 +86,8 @@ pub fn validate_database_creds_support(database: &str, creds: &str) -> Result<()
         (database, creds),
         // Google cloud
         (DatabaseOptions::BIGQUERY, CredentialsOptions::GCP) |
-        // Delta
-        (DatabaseOptions::DELTA, CredentialsOptions::GCP | CredentialsOptions::AWS | CredentialsOptions::AZURE)
+        // Delta & Iceberg
+        (DatabaseOptions::DELTA | DatabaseOptions::ICEBERG, CredentialsOptions::GCP | CredentialsOptions::AWS | CredentialsOptions::AZURE)
     ) {
         Ok(())
     } else {
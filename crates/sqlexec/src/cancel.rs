@@ -0,0 +1,526 @@
+//! Cancellation of running queries.
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::TaskContext;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::{
+    DisplayAs,
+    DisplayFormatType,
+    ExecutionPlan,
+    Partitioning,
+    RecordBatchStream,
+    SendableRecordBatchStream,
+    Statistics,
+};
+use futures::{Future, Stream, StreamExt};
+use parking_lot::Mutex;
+use tokio::time::{Instant, Sleep};
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
+use uuid::Uuid;
+
+use crate::errors::ExecError;
+
+/// Key identifying a session when canceling its queries.
+///
+/// For postgres clients, this is sent on startup as `BackendKeyData` and sent
+/// back as part of a cancel request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BackendKey {
+    pub process_id: i32,
+    pub secret_key: i32,
+}
+
+/// Why a query was canceled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CancelReason {
+    UserRequest,
+    StatementTimeout,
+}
+
+#[derive(Debug)]
+struct QueryState {
+    /// Incremented every time a statement starts.
+    generation: u64,
+    token: CancellationToken,
+    /// When the current statement times out, if there's a timeout.
+    deadline: Option<Instant>,
+    /// Why the token was canceled. Only meaningful once it's been canceled.
+    reason: CancelReason,
+}
+
+impl Default for QueryState {
+    fn default() -> Self {
+        QueryState {
+            generation: 0,
+            token: CancellationToken::new(),
+            deadline: None,
+            reason: CancelReason::UserRequest,
+        }
+    }
+}
+
+/// Cancels the query currently running in a session, either on request or
+/// once it exceeds the statement timeout.
+#[derive(Debug, Clone, Default)]
+pub struct QueryCanceler {
+    state: Arc<Mutex<QueryState>>,
+}
+
+impl QueryCanceler {
+    /// Start a new statement, returning the cancellation token for it.
+    ///
+    /// A cancel that happened while nothing was running doesn't carry over to
+    /// the new statement. If a timeout is provided, the statement is canceled
+    /// once it runs for longer than that. Streams from earlier statements that
+    /// are still being read (e.g. suspended portals) are subject to the new
+    /// statement's timeout.
+    pub fn start_query(&self, timeout: Option<Duration>) -> CancellationToken {
+        let mut state = self.state.lock();
+        if state.token.is_cancelled() {
+            state.token = CancellationToken::new();
+        }
+        state.generation += 1;
+        state.deadline = timeout.map(|timeout| Instant::now() + timeout);
+        state.token.clone()
+    }
+
+    /// Get the cancellation token for the query that's currently running.
+    pub fn current(&self) -> CancellationToken {
+        self.state.lock().token.clone()
+    }
+
+    /// Cancel the currently running query.
+    pub fn cancel(&self) {
+        self.cancel_with_reason(CancelReason::UserRequest);
+    }
+
+    /// Wait for the current query to be canceled or to time out, returning the
+    /// error to report.
+    pub async fn canceled(&self) -> ExecError {
+        let mut watch = self.watch();
+        futures::future::poll_fn(|cx| self.poll_canceled(&mut watch, cx)).await
+    }
+
+    /// Check if the current query has been canceled or has run past its
+    /// timeout.
+    ///
+    /// Used for statements that can't be stopped partway through (writes,
+    /// DDL), which only check for a cancel before they start.
+    pub fn check(&self) -> Result<(), ExecError> {
+        let timed_out = {
+            let state = self.state.lock();
+            if state.token.is_cancelled() {
+                return Err(match state.reason {
+                    CancelReason::UserRequest => ExecError::QueryCanceled,
+                    CancelReason::StatementTimeout => ExecError::StatementTimeout,
+                });
+            }
+            state
+                .deadline
+                .is_some_and(|deadline| deadline <= Instant::now())
+        };
+
+        if timed_out {
+            self.cancel_with_reason(CancelReason::StatementTimeout);
+            return Err(ExecError::StatementTimeout);
+        }
+        Ok(())
+    }
+
+    fn cancel_with_reason(&self, reason: CancelReason) {
+        let mut state = self.state.lock();
+        if !state.token.is_cancelled() {
+            state.reason = reason;
+            state.token.cancel();
+        }
+    }
+
+    fn generation(&self) -> u64 {
+        self.state.lock().generation
+    }
+
+    fn watch(&self) -> CancelWatch {
+        let state = self.state.lock();
+        CancelWatch {
+            generation: state.generation,
+            canceled: Box::pin(state.token.clone().cancelled_owned()),
+            deadline: state
+                .deadline
+                .map(|deadline| Box::pin(tokio::time::sleep_until(deadline))),
+        }
+    }
+
+    fn poll_canceled(&self, watch: &mut CancelWatch, cx: &mut Context<'_>) -> Poll<ExecError> {
+        if watch.canceled.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(match self.state.lock().reason {
+                CancelReason::UserRequest => ExecError::QueryCanceled,
+                CancelReason::StatementTimeout => ExecError::StatementTimeout,
+            });
+        }
+
+        if let Some(deadline) = watch.deadline.as_mut() {
+            if deadline.as_mut().poll(cx).is_ready() {
+                // Cancel the token too so that any work scheduled outside of
+                // this stream stops.
+                if self.generation() == watch.generation {
+                    self.cancel_with_reason(CancelReason::StatementTimeout);
+                }
+                return Poll::Ready(ExecError::StatementTimeout);
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+/// Futures for waiting on the cancellation of a single statement.
+struct CancelWatch {
+    generation: u64,
+    canceled: Pin<Box<WaitForCancellationFutureOwned>>,
+    deadline: Option<Pin<Box<Sleep>>>,
+}
+
+/// Registry of sessions with queries that can be canceled.
+#[derive(Debug, Default)]
+pub struct QueryRegistry {
+    /// Secret key and canceler for each session, keyed by process id.
+    sessions: Mutex<HashMap<i32, (i32, QueryCanceler)>>,
+}
+
+impl QueryRegistry {
+    /// Register a session's canceler, returning the key to use for canceling
+    /// its queries.
+    pub fn register(&self, canceler: QueryCanceler) -> BackendKey {
+        let mut sessions = self.sessions.lock();
+        loop {
+            let bytes = Uuid::new_v4().into_bytes();
+            // Keep process ids positive, some clients don't like negative
+            // pids.
+            let process_id =
+                i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) & i32::MAX;
+            let secret_key = i32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+            if process_id == 0 || sessions.contains_key(&process_id) {
+                continue;
+            }
+
+            sessions.insert(process_id, (secret_key, canceler));
+            return BackendKey {
+                process_id,
+                secret_key,
+            };
+        }
+    }
+
+    /// Remove a session from the registry.
+    pub fn unregister(&self, key: BackendKey) {
+        let mut sessions = self.sessions.lock();
+        if matches!(sessions.get(&key.process_id), Some((secret, _)) if *secret == key.secret_key) {
+            sessions.remove(&key.process_id);
+        }
+    }
+
+    /// Cancel the query running for the session with the given key.
+    ///
+    /// Returns `false` if no session matches the key.
+    pub fn cancel(&self, key: BackendKey) -> bool {
+        match self.sessions.lock().get(&key.process_id) {
+            Some((secret, canceler)) if *secret == key.secret_key => {
+                canceler.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Stream that errors once its query gets canceled or times out.
+///
+/// The inner stream is dropped on cancel, stopping any work it's doing.
+pub struct CancelableStream {
+    schema: SchemaRef,
+    inner: Option<SendableRecordBatchStream>,
+    canceler: QueryCanceler,
+    /// Watch for the statement currently running, recreated whenever a new
+    /// statement starts.
+    watch: Option<CancelWatch>,
+}
+
+impl CancelableStream {
+    pub fn new(inner: SendableRecordBatchStream, canceler: QueryCanceler) -> Self {
+        CancelableStream {
+            schema: inner.schema(),
+            inner: Some(inner),
+            canceler,
+            watch: None,
+        }
+    }
+}
+
+impl Stream for CancelableStream {
+    type Item = DataFusionResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if this.inner.is_none() {
+            return Poll::Ready(None);
+        }
+
+        let generation = this.canceler.generation();
+        if this.watch.as_ref().map(|watch| watch.generation) != Some(generation) {
+            this.watch = Some(this.canceler.watch());
+        }
+        let watch = this.watch.as_mut().unwrap();
+
+        if let Poll::Ready(e) = this.canceler.poll_canceled(watch, cx) {
+            this.inner = None;
+            return Poll::Ready(Some(Err(e.into())));
+        }
+
+        let poll = this.inner.as_mut().unwrap().poll_next_unpin(cx);
+        if let Poll::Ready(None) = poll {
+            this.inner = None;
+        }
+        poll
+    }
+}
+
+impl RecordBatchStream for CancelableStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Execution plan whose streams error once the query gets canceled or times
+/// out.
+///
+/// Used to wrap the inputs of writes, which can't be stopped partway through
+/// themselves, but can stop reading what they're writing. A write that fails
+/// reading its input never commits.
+#[derive(Debug, Clone)]
+pub struct CancelableExec {
+    child: Arc<dyn ExecutionPlan>,
+    canceler: QueryCanceler,
+}
+
+impl CancelableExec {
+    pub fn new(child: Arc<dyn ExecutionPlan>, canceler: QueryCanceler) -> Self {
+        CancelableExec { child, canceler }
+    }
+}
+
+impl ExecutionPlan for CancelableExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.child.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.child.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.child.output_ordering()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.child.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        if children.len() != 1 {
+            return Err(DataFusionError::Plan(
+                "CancelableExec expects exactly one child".to_string(),
+            ));
+        }
+        Ok(Arc::new(CancelableExec {
+            child: children[0].clone(),
+            canceler: self.canceler.clone(),
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        let stream = self.child.execute(partition, context)?;
+        Ok(Box::pin(CancelableStream::new(
+            stream,
+            self.canceler.clone(),
+        )))
+    }
+
+    fn statistics(&self) -> DataFusionResult<Statistics> {
+        self.child.statistics()
+    }
+}
+
+impl DisplayAs for CancelableExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CancelableExec")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::datatypes::Schema;
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+
+    use super::*;
+
+    #[test]
+    fn cancel_with_key() {
+        let registry = QueryRegistry::default();
+        let canceler = QueryCanceler::default();
+        let key = registry.register(canceler.clone());
+        let token = canceler.start_query(None);
+
+        let wrong = BackendKey {
+            process_id: key.process_id,
+            secret_key: key.secret_key.wrapping_add(1),
+        };
+        assert!(!registry.cancel(wrong));
+        assert!(!token.is_cancelled());
+
+        assert!(registry.cancel(key));
+        assert!(token.is_cancelled());
+
+        // New queries aren't affected by a previous cancel.
+        assert!(!canceler.start_query(None).is_cancelled());
+
+        registry.unregister(key);
+        assert!(!registry.cancel(key));
+    }
+
+    #[tokio::test]
+    async fn cancel_pending_stream() {
+        let schema = Arc::new(Schema::empty());
+        let inner = Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::pending(),
+        ));
+
+        let canceler = QueryCanceler::default();
+        canceler.start_query(None);
+        let mut stream = CancelableStream::new(inner, canceler.clone());
+
+        let handle = tokio::spawn(async move { stream.next().await });
+        canceler.cancel();
+
+        let result = handle.await.unwrap().unwrap();
+        assert!(matches!(
+            result,
+            Err(datafusion::error::DataFusionError::External(e))
+                if e.to_string() == ExecError::QueryCanceled.to_string()
+        ));
+    }
+
+    #[tokio::test]
+    async fn statement_timeout() {
+        let schema = Arc::new(Schema::empty());
+        let inner = Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::pending(),
+        ));
+
+        let canceler = QueryCanceler::default();
+        let token = canceler.start_query(Some(Duration::from_millis(10)));
+        let mut stream = CancelableStream::new(inner, canceler.clone());
+
+        let result = stream.next().await.unwrap();
+        assert!(matches!(
+            result,
+            Err(datafusion::error::DataFusionError::External(e))
+                if e.to_string() == ExecError::StatementTimeout.to_string()
+        ));
+        assert!(token.is_cancelled());
+
+        // The next statement gets a fresh timeout.
+        let token = canceler.start_query(None);
+        assert!(!token.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn check_before_write() {
+        let canceler = QueryCanceler::default();
+        canceler.start_query(None);
+        assert!(canceler.check().is_ok());
+
+        canceler.cancel();
+        assert!(matches!(canceler.check(), Err(ExecError::QueryCanceled)));
+
+        // Timeouts are caught even if nothing was waiting on the deadline.
+        let token = canceler.start_query(Some(Duration::from_millis(10)));
+        assert!(canceler.check().is_ok());
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(matches!(canceler.check(), Err(ExecError::StatementTimeout)));
+        assert!(token.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn cancel_exec_input() {
+        let schema = Arc::new(Schema::empty());
+        let input = Arc::new(
+            MemoryExec::try_new(
+                &[vec![RecordBatch::new_empty(schema.clone())]],
+                schema,
+                None,
+            )
+            .unwrap(),
+        );
+
+        let canceler = QueryCanceler::default();
+        canceler.start_query(None);
+        let exec = CancelableExec::new(input, canceler.clone());
+
+        let mut stream = exec.execute(0, Arc::new(TaskContext::default())).unwrap();
+        assert!(stream.next().await.unwrap().is_ok());
+
+        canceler.cancel();
+        let mut stream = exec.execute(0, Arc::new(TaskContext::default())).unwrap();
+        assert!(matches!(
+            stream.next().await.unwrap(),
+            Err(datafusion::error::DataFusionError::External(e))
+                if e.to_string() == ExecError::QueryCanceled.to_string()
+        ));
+    }
+
+    #[tokio::test]
+    async fn new_statement_resets_timeout() {
+        let schema = Arc::new(Schema::empty());
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        let inner = Box::pin(RecordBatchStreamAdapter::new(schema.clone(), rx));
+
+        let canceler = QueryCanceler::default();
+        canceler.start_query(Some(Duration::from_millis(10)));
+        let mut stream = CancelableStream::new(inner, canceler.clone());
+
+        // Reading the stream as part of a later statement isn't affected by
+        // the deadline of the original statement.
+        canceler.start_query(None);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        tx.unbounded_send(Ok(RecordBatch::new_empty(schema)))
+            .unwrap();
+        assert!(stream.next().await.unwrap().is_ok());
+    }
+}
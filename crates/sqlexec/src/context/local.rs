@@ -4,6 +4,7 @@ use std::slice;
 use std::sync::Arc;
 
 use catalog::mutator::CatalogMutator;
+use catalog::sequences::SessionSequences;
 use catalog::session_catalog::SessionCatalog;
 use datafusion::arrow::datatypes::{DataType, Field as ArrowField, Schema as ArrowSchema};
 use datafusion::common::SchemaReference;
@@ -13,6 +14,7 @@ use datafusion::execution::context::{
     SessionState,
     TaskContext,
 };
+use datafusion::physical_plan::SendableRecordBatchStream;
 use datafusion::scalar::ScalarValue;
 use datafusion::sql::TableReference;
 use datafusion::variable::VarType;
@@ -24,7 +26,8 @@ use distexec::scheduler::Scheduler;
 use parser::StatementWithExtensions;
 use pgrepr::format::Format;
 use pgrepr::notice::Notice;
-use pgrepr::types::arrow_to_pg_type;
+use pgrepr::types::{arrow_to_pg_type, pg_to_arrow_type};
+use protogen::metastore::types::catalog::TableEntry;
 use protogen::metastore::types::service::{CreateFunction, Mutation};
 use protogen::rpcsrv::types::service::{
     InitializeSessionRequest,
@@ -33,6 +36,7 @@ use protogen::rpcsrv::types::service::{
 use sqlbuiltins::builtins::DEFAULT_CATALOG;
 use sqlbuiltins::functions::{BuiltinScalarUDF, FunctionRegistry};
 use tokio_postgres::types::Type as PgType;
+use tracing::warn;
 use uuid::Uuid;
 
 use super::{new_datafusion_runtime_env, new_datafusion_session_config_opts};
@@ -68,6 +72,8 @@ pub struct LocalSessionContext {
     prepared: HashMap<String, PreparedStatement>,
     /// Bound portals.
     portals: HashMap<String, Portal>,
+    /// Results left over from previous executes of portals.
+    portal_results: HashMap<String, PortalResults>,
     /// Handler to push metrics into tracker.
     metrics_handler: SessionMetricsHandler,
     /// Datafusion session context used for planning and execution.
@@ -80,6 +86,21 @@ pub struct LocalSessionContext {
     notices: Vec<Notice>,
     /// Functions that are available to the session.
     functions: FunctionRegistry,
+    /// Status of the session's transaction block.
+    txn_status: TransactionStatus,
+}
+
+/// Status of a session's transaction block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransactionStatus {
+    /// Not in a transaction block.
+    #[default]
+    Idle,
+    /// In a transaction block.
+    InBlock,
+    /// In a transaction block where a statement has failed. Statements will
+    /// be rejected until the block is ended.
+    Failed,
 }
 
 impl LocalSessionContext {
@@ -95,6 +116,9 @@ impl LocalSessionContext {
         task_scheduler: Scheduler,
     ) -> Result<LocalSessionContext> {
         let database_id = vars.database_id();
+        let catalog = catalog
+            .with_session_user(vars.user_name())
+            .with_sequences(SessionSequences::new(catalog_mutator.clone()));
         let runtime = new_datafusion_runtime_env(&vars, &catalog, spill_path)?;
         let opts = new_datafusion_session_config_opts(&vars);
 
@@ -122,12 +146,14 @@ impl LocalSessionContext {
             tables: native_tables,
             prepared: HashMap::new(),
             portals: HashMap::new(),
+            portal_results: HashMap::new(),
             metrics_handler,
             df_ctx,
             env_reader: None,
             task_scheduler,
             notices: Vec::new(),
             functions,
+            txn_status: TransactionStatus::Idle,
         })
     }
 
@@ -153,6 +179,7 @@ impl LocalSessionContext {
         let vars = self
             .get_session_vars()
             .with_database_id(client.database_id(), VarType::System);
+        let user_name = vars.user_name();
         let runtime = self.df_ctx.runtime_env();
         let opts = new_datafusion_session_config_opts(&vars);
         let mut conf: SessionConfig = opts.into();
@@ -171,7 +198,7 @@ impl LocalSessionContext {
 
         self.exec_client = Some(client.clone());
         self.df_ctx = df_ctx;
-        self.catalog = catalog;
+        self.catalog = catalog.with_session_user(user_name);
 
         Ok(())
     }
@@ -243,6 +270,165 @@ impl LocalSessionContext {
         self.task_scheduler.clone()
     }
 
+    /// Get the status of the session's transaction block.
+    pub fn transaction_status(&self) -> TransactionStatus {
+        self.txn_status
+    }
+
+    /// Start a transaction block, staging catalog mutations and writes to
+    /// native tables until the block is committed.
+    pub async fn begin_transaction(&mut self) -> Result<()> {
+        self.catalog_mutator().begin_transaction().await?;
+        if let Err(e) = self.tables.transaction().begin().await {
+            self.catalog_mutator().take_transaction();
+            return Err(e.into());
+        }
+        self.txn_status = TransactionStatus::InBlock;
+        Ok(())
+    }
+
+    /// Commit all catalog mutations and writes staged in the transaction
+    /// block.
+    ///
+    /// Catalog mutations and writes to native tables are all checked for
+    /// conflicts before anything is committed. If any check fails, nothing
+    /// from the block is committed. Writes to native tables are then committed
+    /// table by table, tables created in the block are moved from their
+    /// staging location to their permanent location, and the catalog is
+    /// committed last.
+    ///
+    /// There's no atomic commit across tables or the catalog. If a commit fails
+    /// after the checks passed (e.g. a concurrent commit landing in between),
+    /// writes already committed to other tables stay committed.
+    ///
+    /// The block is ended even if the commit fails.
+    pub async fn commit_transaction(&mut self) -> Result<()> {
+        self.txn_status = TransactionStatus::Idle;
+
+        let mutator = self.catalog_mutator();
+        let catalog_txn = mutator.take_transaction();
+
+        if let Some(catalog_txn) = &catalog_txn {
+            if let Err(e) = mutator.prepare_transaction(catalog_txn).await {
+                self.tables.transaction().rollback().await?;
+                self.tables.discard_created_tables().await;
+                self.reset_catalog_state().await?;
+                return Err(e.into());
+            }
+        }
+
+        if let Err(e) = self.tables.transaction().commit().await {
+            self.tables.discard_created_tables().await;
+            self.reset_catalog_state().await?;
+            return Err(e.into());
+        }
+
+        let catalog_txn = match catalog_txn {
+            Some(catalog_txn) => catalog_txn,
+            None => {
+                self.tables.discard_created_tables().await;
+                return Ok(());
+            }
+        };
+
+        let created = catalog_txn.created_tables();
+        let mut result: Result<()> = self
+            .tables
+            .publish_created_tables(&created)
+            .await
+            .map_err(Into::into);
+        if result.is_ok() {
+            result = match mutator.commit_transaction(&catalog_txn).await {
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    warn!(%e, "failed to commit catalog after committing native table writes");
+                    Err(e.into())
+                }
+            };
+        }
+        if let Err(e) = result {
+            self.discard_published_tables(created).await;
+            self.reset_catalog_state().await?;
+            return Err(e);
+        }
+
+        self.delete_tables_storage(catalog_txn.dropped_tables())
+            .await;
+        self.reset_catalog_state().await?;
+
+        Ok(())
+    }
+
+    /// Discard all catalog mutations and writes staged in the transaction
+    /// block.
+    pub async fn rollback_transaction(&mut self) -> Result<()> {
+        self.txn_status = TransactionStatus::Idle;
+
+        self.catalog_mutator().take_transaction();
+        self.reset_catalog_state().await?;
+
+        self.tables.transaction().rollback().await?;
+        self.tables.discard_created_tables().await;
+        Ok(())
+    }
+
+    /// Delete storage for native tables created in a transaction that were
+    /// moved to their permanent location, but whose catalog changes failed to
+    /// commit.
+    ///
+    /// Oids used by the transaction were never reserved, so tables committed
+    /// by other sessions in the meantime may share them. Storage for those is
+    /// left alone.
+    async fn discard_published_tables(&self, tables: Vec<TableEntry>) {
+        let client = match self.catalog_mutator().get_metastore_client() {
+            Some(client) => client.clone(),
+            None => return,
+        };
+        let committed = match client.refresh_cached_state().await {
+            Ok(()) => client.get_cached_state().await,
+            Err(e) => Err(e),
+        };
+        let committed = match committed {
+            Ok(state) => state,
+            Err(e) => {
+                warn!(%e, "failed to fetch catalog, leaving storage for tables created in transaction");
+                return;
+            }
+        };
+
+        let tables = tables
+            .into_iter()
+            .filter(|table| !committed.entries.contains_key(&table.meta.id))
+            .collect();
+        self.delete_tables_storage(tables).await;
+    }
+
+    /// Best-effort deletion of storage for native tables.
+    async fn delete_tables_storage(&self, tables: Vec<TableEntry>) {
+        for table in tables {
+            if let Err(e) = self.tables.delete_table(&table).await {
+                warn!(%e, table = %table.meta.name, "failed to delete native table storage");
+            }
+        }
+    }
+
+    /// Swap the session catalog to the latest committed catalog state,
+    /// discarding any state staged in a transaction.
+    async fn reset_catalog_state(&mut self) -> Result<()> {
+        if let Some(client) = self.catalog_mutator().get_metastore_client() {
+            let state = client.get_cached_state().await?;
+            self.catalog.swap_state(state);
+        }
+        Ok(())
+    }
+
+    /// Mark the current transaction block (if any) as failed.
+    pub fn fail_transaction(&mut self) {
+        if self.txn_status == TransactionStatus::InBlock {
+            self.txn_status = TransactionStatus::Failed;
+        }
+    }
+
     /// Return the DF session context.
     pub fn df_ctx(&self) -> &DfSessionContext {
         &self.df_ctx
@@ -282,6 +468,16 @@ impl LocalSessionContext {
 
     pub async fn maybe_refresh_state(&mut self) -> Result<()> {
         let mutator = self.catalog_mutator();
+
+        // Inside a transaction block the session should only see the state
+        // staged by the transaction.
+        if let Some(state) = mutator.transaction_state() {
+            if !Arc::ptr_eq(&state, self.catalog.get_state()) {
+                self.catalog.swap_state(state);
+            }
+            return Ok(());
+        }
+
         let client = mutator.get_metastore_client();
         self.catalog
             .maybe_refresh_state(client, self.get_session_vars().force_catalog_refresh())
@@ -294,7 +490,7 @@ impl LocalSessionContext {
         &mut self,
         name: String,
         stmt: Option<StatementWithExtensions>,
-        _params: Vec<i32>, // TODO: We can use these for providing types for parameters.
+        params: Vec<i32>, // OIDs, zero if unspecified.
     ) -> Result<()> {
         // Refresh the cached catalog state if necessary
         self.maybe_refresh_state().await?;
@@ -309,7 +505,7 @@ impl LocalSessionContext {
             ));
         }
 
-        let stmt = PreparedStatement::build(stmt, self).await?;
+        let stmt = PreparedStatement::build(stmt, params, self).await?;
         self.prepared.insert(name, stmt);
 
         Ok(())
@@ -355,6 +551,7 @@ impl LocalSessionContext {
             stmt,
             result_formats,
         };
+        self.portal_results.remove(&portal_name);
         self.portals.insert(portal_name, portal);
 
         Ok(())
@@ -383,9 +580,47 @@ impl LocalSessionContext {
 
     /// Remove a portal.
     pub fn remove_portal(&mut self, name: &str) {
+        self.portal_results.remove(name);
         self.portals.remove(name);
     }
 
+    /// Store the remaining results for a portal so that the next execute
+    /// continues where the previous one left off.
+    pub fn suspend_portal(&mut self, name: &str, stream: SendableRecordBatchStream) -> Result<()> {
+        if !self.portals.contains_key(name) {
+            return Err(ExecError::UnknownPortal(name.to_string()));
+        }
+        self.portal_results
+            .insert(name.to_string(), PortalResults::Suspended(stream));
+        Ok(())
+    }
+
+    /// Mark a portal as having returned all of its results, so that further
+    /// executes of the portal return no rows instead of running it again.
+    pub fn complete_portal(&mut self, name: &str) -> Result<()> {
+        if !self.portals.contains_key(name) {
+            return Err(ExecError::UnknownPortal(name.to_string()));
+        }
+        self.portal_results
+            .insert(name.to_string(), PortalResults::Exhausted);
+        Ok(())
+    }
+
+    /// Take the results left over from previous executes of a portal, if
+    /// any.
+    ///
+    /// Exhausted portals stay exhausted until the portal is bound again.
+    pub fn take_portal_results(&mut self, name: &str) -> Option<PortalResults> {
+        match self.portal_results.remove(name)? {
+            PortalResults::Exhausted => {
+                self.portal_results
+                    .insert(name.to_string(), PortalResults::Exhausted);
+                Some(PortalResults::Exhausted)
+            }
+            results => Some(results),
+        }
+    }
+
     pub(crate) fn push_notice(&mut self, notice: Notice) {
         self.notices.push(notice)
     }
@@ -478,7 +713,10 @@ pub struct PreparedStatement {
     /// The logical plan for the statement. Is `Some` if the statement is
     /// `Some`.
     pub(crate) plan: Option<LogicalPlan>,
-    /// Parameter data types.
+    /// Parameter data types, keyed by "$n".
+    ///
+    /// Contains an entry for every parameter up to the highest numbered one
+    /// used in the statement or provided by the client.
     pub(crate) parameter_types: Option<HashMap<String, Option<(PgType, DataType)>>>,
     /// The output schema of the statement if it produces an output.
     pub(crate) output_schema: Option<ArrowSchema>,
@@ -491,6 +729,7 @@ impl PreparedStatement {
     // TODO: Not sure if we want to delay the planning portion.
     async fn build(
         mut stmt: Option<StatementWithExtensions>,
+        param_oids: Vec<i32>,
         ctx: &LocalSessionContext,
     ) -> Result<Self> {
         if let Some(inner) = stmt.take() {
@@ -509,16 +748,7 @@ impl PreparedStatement {
                 None => Vec::new(),
             };
 
-            // Convert inferred arrow types for parameters into their associated
-            // pg type.
-            let parameter_types: HashMap<_, _> = plan
-                .get_parameter_types()?
-                .into_iter()
-                .map(|(id, arrow_type)| {
-                    let typ = arrow_type.map(|typ| (arrow_to_pg_type(&typ, None), typ));
-                    (id, typ)
-                })
-                .collect();
+            let parameter_types = resolve_parameter_types(plan.get_parameter_types()?, &param_oids);
 
             Ok(PreparedStatement {
                 stmt: Some(inner),
@@ -554,6 +784,85 @@ impl PreparedStatement {
     pub fn input_paramaters(&self) -> Option<&HashMap<String, Option<(PgType, DataType)>>> {
         self.parameter_types.as_ref()
     }
+
+    /// Returns the input parameters types in order, starting at "$1".
+    pub fn parameters(&self) -> Vec<(PgType, DataType)> {
+        let types = match &self.parameter_types {
+            Some(types) => types,
+            None => return Vec::new(),
+        };
+        (1..=types.len())
+            .filter_map(|idx| types.get(&format!("${idx}")).cloned().flatten())
+            .collect()
+    }
+
+    /// Returns the schema of the input parameters, with a field named "$n" for
+    /// each parameter.
+    pub fn parameter_schema(&self) -> ArrowSchema {
+        let fields: Vec<_> = self
+            .parameters()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (_, arrow_type))| {
+                ArrowField::new(format!("${}", idx + 1), arrow_type, true)
+            })
+            .collect();
+        ArrowSchema::new(fields)
+    }
+
+    /// Returns the output schema of the statement, if it produces one.
+    pub fn output_schema(&self) -> Option<&ArrowSchema> {
+        self.output_schema.as_ref()
+    }
+}
+
+/// Resolve the types of parameters for a prepared statement.
+///
+/// Types provided by the client take precedence for decoding values, with the
+/// values being cast to the inferred type if there is one. Parameters without
+/// either are treated as text, similar to how postgres resolves parameters of
+/// unknown types.
+fn resolve_parameter_types(
+    mut inferred: HashMap<String, Option<DataType>>,
+    param_oids: &[i32],
+) -> HashMap<String, Option<(PgType, DataType)>> {
+    let num_params = inferred
+        .keys()
+        .filter_map(|id| id.strip_prefix('$')?.parse::<usize>().ok())
+        .max()
+        .unwrap_or(0)
+        .max(param_oids.len());
+
+    (1..=num_params)
+        .map(|idx| {
+            let id = format!("${idx}");
+            let inferred = inferred.remove(&id).flatten();
+            let provided = param_oids
+                .get(idx - 1)
+                .filter(|oid| **oid != 0)
+                .and_then(|oid| PgType::from_oid(*oid as u32));
+
+            let typ = match (provided, inferred) {
+                (Some(pg_type), Some(arrow_type)) => (pg_type, arrow_type),
+                (Some(pg_type), None) => {
+                    let arrow_type = pg_to_arrow_type(&pg_type).unwrap_or(DataType::Utf8);
+                    (pg_type, arrow_type)
+                }
+                (None, Some(arrow_type)) => (arrow_to_pg_type(&arrow_type, None), arrow_type),
+                (None, None) => (PgType::TEXT, DataType::Utf8),
+            };
+            (id, Some(typ))
+        })
+        .collect()
+}
+
+/// Results left over from previous executes of a portal.
+pub enum PortalResults {
+    /// The remaining results of a portal that was suspended after hitting
+    /// the row limit of an execute.
+    Suspended(SendableRecordBatchStream),
+    /// All results of the portal have been returned.
+    Exhausted,
 }
 
 #[derive(Debug, Clone)]
@@ -637,3 +946,31 @@ impl<'a> Iterator for OutputFields<'a> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_parameter_types_precedence() {
+        let inferred = HashMap::from([
+            ("$1".to_string(), Some(DataType::Int64)),
+            ("$2".to_string(), None),
+            ("$4".to_string(), Some(DataType::Date32)),
+        ]);
+        let oids = vec![PgType::INT4.oid() as i32, 0, PgType::BOOL.oid() as i32];
+
+        let types = resolve_parameter_types(inferred, &oids);
+
+        let expected = HashMap::from([
+            // Provided types are used for decoding, inferred type for the value.
+            ("$1".to_string(), Some((PgType::INT4, DataType::Int64))),
+            // Unknown types are text.
+            ("$2".to_string(), Some((PgType::TEXT, DataType::Utf8))),
+            // Not used in the statement, but provided by the client.
+            ("$3".to_string(), Some((PgType::BOOL, DataType::Boolean))),
+            ("$4".to_string(), Some((PgType::DATE, DataType::Date32))),
+        ]);
+        assert_eq!(expected, types);
+    }
+}
@@ -21,6 +21,7 @@ use datasources::excel::table::ExcelTableProvider;
 use datasources::excel::ExcelTable;
 use datasources::json::table::json_streaming_table;
 use datasources::lake::delta::access::{load_table_direct, DeltaLakeAccessor};
+use datasources::lake::iceberg::catalog::IcebergCatalogAccessor;
 use datasources::lake::iceberg::table::IcebergTable;
 use datasources::lake::{storage_options_into_object_store, storage_options_into_store_access};
 use datasources::lance::LanceTable;
@@ -48,6 +49,7 @@ use protogen::metastore::types::options::{
     DatabaseOptionsClickhouse,
     DatabaseOptionsDebug,
     DatabaseOptionsDeltaLake,
+    DatabaseOptionsIceberg,
     DatabaseOptionsMongoDb,
     DatabaseOptionsMysql,
     DatabaseOptionsPostgres,
@@ -290,6 +292,16 @@ impl<'a> ExternalDispatcher<'a> {
                 let table = SqliteTableProvider::try_new(state, name).await?;
                 Ok(Arc::new(table))
             }
+            DatabaseOptions::Iceberg(DatabaseOptionsIceberg {
+                catalog,
+                storage_options,
+            }) => {
+                let accessor =
+                    IcebergCatalogAccessor::connect(catalog, storage_options.clone()).await?;
+                let table = accessor.load_table(schema, name).await?;
+                let reader = table.table_reader().await?;
+                Ok(reader)
+            }
         }
     }
 
@@ -8,12 +8,13 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use catalog::session_catalog::SessionCatalog;
 use datafusion::datasource::{TableProvider, ViewTable};
-use datafusion::logical_expr::{LogicalPlan, LogicalPlanBuilder};
+use datafusion::logical_expr::{cast, LogicalPlan, LogicalPlanBuilder};
 use datafusion::prelude::{Column, Expr, SessionContext as DfSessionContext};
 use datafusion_ext::functions::{DefaultTableContextProvider, FuncParamValue};
 use datasources::native::access::NativeTableStorage;
 use parser::GlareDbParser;
 use protogen::metastore::types::catalog::{DatabaseEntry, FunctionEntry, TableEntry, ViewEntry};
+use protogen::metastore::types::options::TableOptionsV0;
 use sqlbuiltins::functions::FunctionRegistry;
 
 use self::external::ExternalDispatcher;
@@ -133,7 +134,7 @@ impl ViewPlanner for LocalSessionContext {
             ));
         }
 
-        let planner = SessionPlanner::new(self);
+        let planner = SessionPlanner::new_for_view(self);
 
         let plan = planner.plan_ast(statements.pop_front().unwrap()).await?;
         let mut df_plan = plan.try_into_datafusion_plan()?;
@@ -275,3 +276,49 @@ impl<'a> Dispatcher<'a> {
         Ok(prov)
     }
 }
+
+/// Plan the query of a materialized view.
+///
+/// The output is cast to the types of the view's table, which were fixed when
+/// the view was created.
+pub(crate) async fn plan_materialized_view(
+    view_planner: &dyn ViewPlanner,
+    table: &TableEntry,
+) -> Result<LogicalPlan, PlanError> {
+    let (view, columns) = match &table.options {
+        TableOptionsV0::Internal(options) => match &options.materialized_view {
+            Some(view) => (view, &options.columns),
+            None => {
+                return Err(PlanError::String(format!(
+                    "'{}' is not a materialized view",
+                    table.meta.name
+                )))
+            }
+        },
+        _ => {
+            return Err(PlanError::String(format!(
+                "'{}' is not a materialized view",
+                table.meta.name
+            )))
+        }
+    };
+
+    let plan = view_planner.plan_view(&view.sql, &view.columns).await?;
+    let fields = plan.schema().fields().clone();
+    if fields.len() != columns.len() {
+        return Err(PlanError::String(format!(
+            "The query of materialized view '{}' no longer matches its columns",
+            table.meta.name
+        )));
+    }
+
+    Ok(LogicalPlanBuilder::from(plan)
+        .project(fields.iter().zip(columns).map(|(field, col)| {
+            cast(
+                Expr::Column(field.qualified_column()),
+                col.arrow_type.clone(),
+            )
+            .alias(&col.name)
+        }))?
+        .build()?)
+}
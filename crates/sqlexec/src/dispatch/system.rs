@@ -1,14 +1,28 @@
 use std::sync::Arc;
 
 use catalog::session_catalog::SessionCatalog;
-use datafusion::arrow::array::{BooleanBuilder, ListBuilder, StringBuilder, UInt32Builder};
+use datafusion::arrow::array::{
+    BooleanBuilder,
+    Int64Builder,
+    ListBuilder,
+    StringBuilder,
+    TimestampMicrosecondBuilder,
+    UInt32Builder,
+    UInt64Builder,
+};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::datasource::{MemTable, TableProvider};
 use datafusion::logical_expr::TypeSignature;
 use datasources::common::ssh::key::SshKey;
 use datasources::common::ssh::SshConnectionParameters;
 use datasources::native::access::NativeTableStorage;
-use protogen::metastore::types::catalog::{CatalogEntry, EntryType, SourceAccessMode, TableEntry};
+use protogen::metastore::types::catalog::{
+    CatalogEntry,
+    EntryType,
+    Privilege,
+    SourceAccessMode,
+    TableEntry,
+};
 use protogen::metastore::types::options::TunnelOptions;
 use sqlbuiltins::builtins::{
     BuiltinTable,
@@ -19,7 +33,9 @@ use sqlbuiltins::builtins::{
     GLARE_DATABASES,
     GLARE_DEPLOYMENT_METADATA,
     GLARE_FUNCTIONS,
+    GLARE_MATERIALIZED_VIEWS,
     GLARE_SCHEMAS,
+    GLARE_SEQUENCES,
     GLARE_SSH_KEYS,
     GLARE_TABLES,
     GLARE_TUNNELS,
@@ -69,6 +85,10 @@ impl<'a> SystemTableDispatcher<'a> {
             Arc::new(self.build_glare_columns())
         } else if GLARE_VIEWS.matches(schema, name) {
             Arc::new(self.build_glare_views())
+        } else if GLARE_MATERIALIZED_VIEWS.matches(schema, name) {
+            Arc::new(self.build_glare_materialized_views())
+        } else if GLARE_SEQUENCES.matches(schema, name) {
+            Arc::new(self.build_glare_sequences())
         } else if GLARE_SCHEMAS.matches(schema, name) {
             Arc::new(self.build_glare_schemas())
         } else if GLARE_FUNCTIONS.matches(schema, name) {
@@ -125,6 +145,7 @@ impl<'a> SystemTableDispatcher<'a> {
         let mut external = BooleanBuilder::new();
         let mut datasource = StringBuilder::new();
         let mut access_mode = StringBuilder::new();
+        let mut description = StringBuilder::new();
 
         for db in self
             .catalog
@@ -143,6 +164,7 @@ impl<'a> SystemTableDispatcher<'a> {
 
             datasource.append_value(db.options.as_str());
             access_mode.append_value(db.access_mode.as_str());
+            description.append_option(db.meta.description.as_deref());
         }
 
         let batch = RecordBatch::try_new(
@@ -154,6 +176,7 @@ impl<'a> SystemTableDispatcher<'a> {
                 Arc::new(external.finish()),
                 Arc::new(datasource.finish()),
                 Arc::new(access_mode.finish()),
+                Arc::new(description.finish()),
             ],
         )
         .unwrap();
@@ -211,6 +234,7 @@ impl<'a> SystemTableDispatcher<'a> {
             .catalog
             .iter_entries()
             .filter(|ent| ent.entry_type() == EntryType::Credentials)
+            .filter(|ent| self.catalog.has_privilege(ent.entry, Privilege::Usage))
         {
             oid.append_value(creds.oid);
             credentials_name.append_value(&creds.entry.get_meta().name);
@@ -247,6 +271,7 @@ impl<'a> SystemTableDispatcher<'a> {
         let mut database_name = StringBuilder::new();
         let mut schema_name = StringBuilder::new();
         let mut builtin = BooleanBuilder::new();
+        let mut description = StringBuilder::new();
 
         for schema in self
             .catalog
@@ -263,6 +288,7 @@ impl<'a> SystemTableDispatcher<'a> {
             );
             schema_name.append_value(&schema.entry.get_meta().name);
             builtin.append_value(schema.builtin);
+            description.append_option(schema.entry.get_meta().description.as_deref());
         }
         let batch = RecordBatch::try_new(
             arrow_schema.clone(),
@@ -272,6 +298,7 @@ impl<'a> SystemTableDispatcher<'a> {
                 Arc::new(database_name.finish()),
                 Arc::new(schema_name.finish()),
                 Arc::new(builtin.finish()),
+                Arc::new(description.finish()),
             ],
         )
         .unwrap();
@@ -290,6 +317,7 @@ impl<'a> SystemTableDispatcher<'a> {
         let mut external = BooleanBuilder::new();
         let mut datasource = StringBuilder::new();
         let mut access_mode = StringBuilder::new();
+        let mut description = StringBuilder::new();
 
         for table in self
             .catalog
@@ -321,6 +349,7 @@ impl<'a> SystemTableDispatcher<'a> {
 
             datasource.append_value(table.options.as_str());
             access_mode.append_value(table.access_mode.as_str());
+            description.append_option(table.meta.description.as_deref());
         }
 
         // Append temporary tables.
@@ -335,6 +364,7 @@ impl<'a> SystemTableDispatcher<'a> {
             external.append_value(table.meta.external);
             datasource.append_value(table.options.as_str());
             access_mode.append_value(SourceAccessMode::ReadWrite.as_str());
+            description.append_option(table.meta.description.as_deref());
         }
 
         let batch = RecordBatch::try_new(
@@ -349,6 +379,7 @@ impl<'a> SystemTableDispatcher<'a> {
                 Arc::new(external.finish()),
                 Arc::new(datasource.finish()),
                 Arc::new(access_mode.finish()),
+                Arc::new(description.finish()),
             ],
         )
         .unwrap();
@@ -366,6 +397,7 @@ impl<'a> SystemTableDispatcher<'a> {
         let mut column_ordinal = UInt32Builder::new();
         let mut data_type = StringBuilder::new();
         let mut is_nullable = BooleanBuilder::new();
+        let mut description = StringBuilder::new();
 
         for table in self
             .catalog
@@ -395,6 +427,7 @@ impl<'a> SystemTableDispatcher<'a> {
                 column_ordinal.append_value(i as u32);
                 data_type.append_value(col.arrow_type.to_string());
                 is_nullable.append_value(col.nullable);
+                description.append_option(col.description.as_deref());
             }
         }
 
@@ -408,6 +441,7 @@ impl<'a> SystemTableDispatcher<'a> {
                 Arc::new(column_ordinal.finish()),
                 Arc::new(data_type.finish()),
                 Arc::new(is_nullable.finish()),
+                Arc::new(description.finish()),
             ],
         )
         .unwrap();
@@ -425,6 +459,7 @@ impl<'a> SystemTableDispatcher<'a> {
         let mut view_name = StringBuilder::new();
         let mut builtin = BooleanBuilder::new();
         let mut sql = StringBuilder::new();
+        let mut description = StringBuilder::new();
 
         for view in self
             .catalog
@@ -451,6 +486,7 @@ impl<'a> SystemTableDispatcher<'a> {
             view_name.append_value(&view.entry.get_meta().name);
             builtin.append_value(view.builtin);
             sql.append_value(&ent.sql);
+            description.append_option(ent.meta.description.as_deref());
         }
 
         let batch = RecordBatch::try_new(
@@ -463,6 +499,153 @@ impl<'a> SystemTableDispatcher<'a> {
                 Arc::new(view_name.finish()),
                 Arc::new(builtin.finish()),
                 Arc::new(sql.finish()),
+                Arc::new(description.finish()),
+            ],
+        )
+        .unwrap();
+
+        MemTable::try_new(arrow_schema, vec![vec![batch]]).unwrap()
+    }
+
+    fn build_glare_materialized_views(&self) -> MemTable {
+        let arrow_schema = Arc::new(GLARE_MATERIALIZED_VIEWS.arrow_schema());
+
+        let mut oid = UInt32Builder::new();
+        let mut database_oid = UInt32Builder::new();
+        let mut schema_oid = UInt32Builder::new();
+        let mut schema_name = StringBuilder::new();
+        let mut view_name = StringBuilder::new();
+        let mut sql = StringBuilder::new();
+        let mut refresh_interval_secs = UInt64Builder::new();
+        let mut last_refreshed = TimestampMicrosecondBuilder::new().with_timezone("UTC");
+
+        for table in self
+            .catalog
+            .iter_entries()
+            .filter(|ent| ent.entry_type() == EntryType::Table)
+        {
+            let view = match table.entry {
+                CatalogEntry::Table(ent) => match ent.materialized_view() {
+                    Some(view) => view,
+                    None => continue,
+                },
+                other => panic!("unexpected entry type: {:?}", other), // Bug
+            };
+
+            oid.append_value(table.oid);
+            database_oid.append_value(
+                table
+                    .parent_entry
+                    .map(|schema| schema.get_meta().parent)
+                    .unwrap_or_default(),
+            );
+            schema_oid.append_value(table.entry.get_meta().parent);
+            schema_name.append_value(
+                table
+                    .parent_entry
+                    .map(|schema| schema.get_meta().name.as_str())
+                    .unwrap_or("<invalid>"),
+            );
+            view_name.append_value(&table.entry.get_meta().name);
+            sql.append_value(&view.sql);
+            refresh_interval_secs.append_option(view.refresh_interval_secs);
+            last_refreshed.append_option(view.refreshed_at);
+        }
+
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![
+                Arc::new(oid.finish()),
+                Arc::new(database_oid.finish()),
+                Arc::new(schema_oid.finish()),
+                Arc::new(schema_name.finish()),
+                Arc::new(view_name.finish()),
+                Arc::new(sql.finish()),
+                Arc::new(refresh_interval_secs.finish()),
+                Arc::new(last_refreshed.finish()),
+            ],
+        )
+        .unwrap();
+
+        MemTable::try_new(arrow_schema, vec![vec![batch]]).unwrap()
+    }
+
+    fn build_glare_sequences(&self) -> MemTable {
+        let arrow_schema = Arc::new(GLARE_SEQUENCES.arrow_schema());
+
+        let mut oid = UInt32Builder::new();
+        let mut database_oid = UInt32Builder::new();
+        let mut schema_oid = UInt32Builder::new();
+        let mut schema_name = StringBuilder::new();
+        let mut sequence_name = StringBuilder::new();
+        let mut start_value = Int64Builder::new();
+        let mut increment_by = Int64Builder::new();
+        let mut min_value = Int64Builder::new();
+        let mut max_value = Int64Builder::new();
+        let mut cycle = BooleanBuilder::new();
+        let mut cache_size = Int64Builder::new();
+        let mut last_value = Int64Builder::new();
+        let mut owned_by_table = StringBuilder::new();
+        let mut owned_by_column = StringBuilder::new();
+
+        for sequence in self
+            .catalog
+            .iter_entries()
+            .filter(|ent| ent.entry_type() == EntryType::Sequence)
+        {
+            let ent = match sequence.entry {
+                CatalogEntry::Sequence(ent) => ent,
+                other => panic!("unexpected catalog entry: {:?}", other), // Bug
+            };
+
+            oid.append_value(sequence.oid);
+            database_oid.append_value(
+                sequence
+                    .parent_entry
+                    .map(|schema| schema.get_meta().parent)
+                    .unwrap_or_default(),
+            );
+            schema_oid.append_value(ent.meta.parent);
+            schema_name.append_value(
+                sequence
+                    .parent_entry
+                    .map(|schema| schema.get_meta().name.as_str())
+                    .unwrap_or("<invalid>"),
+            );
+            sequence_name.append_value(&ent.meta.name);
+            start_value.append_value(ent.start);
+            increment_by.append_value(ent.increment);
+            min_value.append_value(ent.min_value);
+            max_value.append_value(ent.max_value);
+            cycle.append_value(ent.cycle);
+            cache_size.append_value(ent.cache);
+            last_value.append_option(ent.is_called.then_some(ent.last_value));
+
+            let owner = ent.owned_by.as_ref().and_then(|owner| {
+                let table = self.catalog.get_by_oid(owner.table_id)?;
+                Some((table.get_meta().name.as_str(), owner.column.as_str()))
+            });
+            owned_by_table.append_option(owner.map(|(table, _)| table));
+            owned_by_column.append_option(owner.map(|(_, column)| column));
+        }
+
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![
+                Arc::new(oid.finish()),
+                Arc::new(database_oid.finish()),
+                Arc::new(schema_oid.finish()),
+                Arc::new(schema_name.finish()),
+                Arc::new(sequence_name.finish()),
+                Arc::new(start_value.finish()),
+                Arc::new(increment_by.finish()),
+                Arc::new(min_value.finish()),
+                Arc::new(max_value.finish()),
+                Arc::new(cycle.finish()),
+                Arc::new(cache_size.finish()),
+                Arc::new(last_value.finish()),
+                Arc::new(owned_by_table.finish()),
+                Arc::new(owned_by_column.finish()),
             ],
         )
         .unwrap();
@@ -35,8 +35,10 @@ use tracing::{debug, info};
 use url::Url;
 use uuid::Uuid;
 
+use crate::cancel::{BackendKey, QueryRegistry};
 use crate::context::remote::RemoteSessionContext;
 use crate::errors::{ExecError, Result};
+use crate::refresh::MaterializedViewRefresher;
 use crate::session::Session;
 
 #[derive(Debug, Clone, Default)]
@@ -311,7 +313,7 @@ impl EngineStorageConfig {
 /// An engine is able to support multiple [`Session`]'s across multiple db instances
 pub struct Engine {
     /// Metastore client supervisor.
-    supervisor: MetastoreClientSupervisor,
+    supervisor: Arc<MetastoreClientSupervisor>,
     /// Telemetry.
     tracker: Arc<Tracker>,
     /// Storage configuration.
@@ -320,10 +322,14 @@ pub struct Engine {
     spill_path: Option<PathBuf>,
     /// Number of active sessions.
     session_counter: Arc<AtomicU64>,
+    /// Sessions with queries that can be canceled.
+    query_registry: Arc<QueryRegistry>,
     /// Scheduler for running tasks (physical plan).
     task_scheduler: Scheduler,
     /// Task executors.
     _task_executors: Vec<TaskExecutor>,
+    /// Background refreshes of materialized views.
+    refresher: MaterializedViewRefresher,
     tmp_dir: Option<tempfile::TempDir>,
 }
 
@@ -379,13 +385,18 @@ impl Engine {
         })?;
 
         Ok(Engine {
-            supervisor: MetastoreClientSupervisor::new(metastore, DEFAULT_METASTORE_CLIENT_CONFIG),
+            supervisor: Arc::new(MetastoreClientSupervisor::new(
+                metastore,
+                DEFAULT_METASTORE_CLIENT_CONFIG,
+            )),
             tracker,
             storage,
             spill_path,
             session_counter: Arc::new(AtomicU64::new(0)),
+            query_registry: Arc::new(QueryRegistry::default()),
             task_scheduler,
             _task_executors: task_executors,
+            refresher: MaterializedViewRefresher::default(),
             tmp_dir: None,
         })
     }
@@ -481,17 +492,30 @@ impl Engine {
         vars: SessionVars,
         storage: SessionStorageConfig,
     ) -> Result<TrackedSession> {
-        let session = self.new_untracked_session(vars, storage).await?;
+        let opener = self.session_opener(storage);
+        let session = opener.open(vars.clone()).await?;
+        self.refresher.ensure_started(opener, &vars);
 
         let prev = self.session_counter.fetch_add(1, Ordering::Relaxed);
         debug!(session_count = prev + 1, "new session opened");
 
+        let backend_key = self.query_registry.register(session.query_canceler());
+
         Ok(TrackedSession {
             inner: session,
             session_counter: self.session_counter.clone(),
+            backend_key,
+            query_registry: self.query_registry.clone(),
         })
     }
 
+    /// Cancel the query running in the session with the given key.
+    ///
+    /// Returns `false` if there's no session for the key.
+    pub fn cancel_query(&self, key: BackendKey) -> bool {
+        self.query_registry.cancel(key)
+    }
+
     pub async fn default_local_session_context(&self) -> Result<TrackedSession> {
         self.new_local_session_context(SessionVars::default(), SessionStorageConfig::default())
             .await
@@ -506,30 +530,18 @@ impl Engine {
         vars: SessionVars,
         storage: SessionStorageConfig,
     ) -> Result<Session> {
-        let database_id = vars.database_id();
-        let metastore = self.supervisor.init_client(database_id).await?;
-        let native = self
-            .storage
-            .new_native_tables_storage(database_id, &storage)?;
-        let state = metastore.get_cached_state().await?;
-        let catalog = SessionCatalog::new_with_alias(
-            state,
-            ResolveConfig {
-                default_schema_oid: SCHEMA_DEFAULT.oid,
-                session_schema_oid: SCHEMA_CURRENT_SESSION.oid,
-            },
-            vars.database_name(),
-        );
+        self.session_opener(storage).open(vars).await
+    }
 
-        Session::new(
-            vars,
-            catalog,
-            metastore.into(),
-            native,
-            self.tracker.clone(),
-            self.spill_path.clone(),
-            self.task_scheduler.clone(),
-        )
+    pub(crate) fn session_opener(&self, storage: SessionStorageConfig) -> SessionOpener {
+        SessionOpener {
+            supervisor: self.supervisor.clone(),
+            storage: self.storage.clone(),
+            session_storage: storage,
+            tracker: self.tracker.clone(),
+            spill_path: self.spill_path.clone(),
+            task_scheduler: self.task_scheduler.clone(),
+        }
     }
 
     /// Create a new remote session for plan execution.
@@ -567,6 +579,50 @@ impl Engine {
     }
 }
 
+/// Opens sessions with a fixed storage configuration.
+///
+/// Holds what's needed from the engine, so sessions can be opened by tasks
+/// running in the background of the engine.
+#[derive(Clone)]
+pub(crate) struct SessionOpener {
+    supervisor: Arc<MetastoreClientSupervisor>,
+    storage: EngineStorageConfig,
+    session_storage: SessionStorageConfig,
+    tracker: Arc<Tracker>,
+    spill_path: Option<PathBuf>,
+    task_scheduler: Scheduler,
+}
+
+impl SessionOpener {
+    /// Open a new untracked session.
+    pub(crate) async fn open(&self, vars: SessionVars) -> Result<Session> {
+        let database_id = vars.database_id();
+        let metastore = self.supervisor.init_client(database_id).await?;
+        let native = self
+            .storage
+            .new_native_tables_storage(database_id, &self.session_storage)?;
+        let state = metastore.get_cached_state().await?;
+        let catalog = SessionCatalog::new_with_alias(
+            state,
+            ResolveConfig {
+                default_schema_oid: SCHEMA_DEFAULT.oid,
+                session_schema_oid: SCHEMA_CURRENT_SESSION.oid,
+            },
+            vars.database_name(),
+        );
+
+        Session::new(
+            vars,
+            catalog,
+            metastore.into(),
+            native,
+            self.tracker.clone(),
+            self.spill_path.clone(),
+            self.task_scheduler.clone(),
+        )
+    }
+}
+
 /// A thin wrapper around a session.
 ///
 /// This is used to allow the engine to track the number of active sessions.
@@ -576,6 +632,17 @@ impl Engine {
 pub struct TrackedSession {
     inner: Session,
     session_counter: Arc<AtomicU64>,
+    /// Key for canceling queries in this session.
+    backend_key: BackendKey,
+    query_registry: Arc<QueryRegistry>,
+}
+
+impl TrackedSession {
+    /// Key to provide to `Engine::cancel_query` to cancel queries running in
+    /// this session.
+    pub fn backend_key(&self) -> BackendKey {
+        self.backend_key
+    }
 }
 
 impl Deref for TrackedSession {
@@ -593,6 +660,7 @@ impl DerefMut for TrackedSession {
 
 impl Drop for TrackedSession {
     fn drop(&mut self) {
+        self.query_registry.unregister(self.backend_key);
         let prev = self.session_counter.fetch_sub(1, Ordering::Relaxed);
         debug!(session_counter = prev - 1, "session closed");
     }
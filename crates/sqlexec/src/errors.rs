@@ -1,3 +1,5 @@
+use pgrepr::notice::SqlState;
+
 #[derive(Debug, thiserror::Error)]
 pub enum ExecError {
     #[error("SQL statement currently unsupported: {0}")]
@@ -27,6 +29,27 @@ pub enum ExecError {
     #[error("Unknown portal with name: {0}")]
     UnknownPortal(String),
 
+    #[error("Current transaction is aborted, commands ignored until end of transaction block")]
+    InFailedTransaction,
+
+    #[error("canceling statement due to user request")]
+    QueryCanceled,
+
+    #[error("canceling statement due to statement timeout")]
+    StatementTimeout,
+
+    #[error("null value in column \"{column}\" violates not-null constraint")]
+    NotNullViolation { column: String },
+
+    #[error("new row violates check constraint \"{constraint}\"")]
+    CheckViolation { constraint: String },
+
+    #[error("duplicate key value violates unique constraint \"{constraint}\"")]
+    UniqueViolation { constraint: String },
+
+    #[error("ON CONFLICT DO UPDATE command cannot affect row a second time")]
+    UpsertAffectsRowTwice,
+
     #[error("Empty search path, unable to resolve schema")]
     EmptySearchPath,
 
@@ -167,6 +190,43 @@ pub enum ExecError {
     Metastore(#[from] metastore::errors::MetastoreError),
 }
 
+impl ExecError {
+    /// Get the SQLSTATE code that should be reported for this error.
+    pub fn sql_state(&self) -> SqlState {
+        match self {
+            ExecError::InFailedTransaction => SqlState::InFailedSqlTransaction,
+            ExecError::QueryCanceled
+            | ExecError::StatementTimeout
+            | ExecError::DistExecError(distexec::DistExecError::Canceled) => {
+                SqlState::QueryCanceled
+            }
+            ExecError::NotNullViolation { .. } => SqlState::NotNullViolation,
+            ExecError::CheckViolation { .. } => SqlState::CheckViolation,
+            ExecError::UniqueViolation { .. }
+            | ExecError::DatasourceNative(
+                datasources::native::errors::NativeError::UniqueViolation { .. },
+            ) => SqlState::UniqueViolation,
+            ExecError::UpsertAffectsRowTwice => SqlState::CardinalityViolation,
+            ExecError::PlanError(e) => e.sql_state(),
+            ExecError::DataFusion(datafusion::error::DataFusionError::External(e)) => {
+                external_sql_state(e.as_ref())
+            }
+            _ => SqlState::InternalError,
+        }
+    }
+}
+
+/// Get the SQL state for an error boxed inside of a datafusion error.
+pub(crate) fn external_sql_state(e: &(dyn std::error::Error + Send + Sync)) -> SqlState {
+    if let Some(e) = e.downcast_ref::<ExecError>() {
+        e.sql_state()
+    } else if let Some(e) = e.downcast_ref::<crate::planner::errors::PlanError>() {
+        e.sql_state()
+    } else {
+        SqlState::InternalError
+    }
+}
+
 impl From<ExecError> for datafusion::error::DataFusionError {
     fn from(e: ExecError) -> Self {
         match e {
@@ -20,6 +20,8 @@ use datafusion_ext::metrics::{
 use datafusion_ext::runtime::runtime_group::RuntimeGroupExec;
 use datafusion_proto::logical_plan::from_proto::parse_expr;
 use datafusion_proto::physical_plan::PhysicalExtensionCodec;
+use datasources::lake::delta::version::TableVersion;
+use datasources::native::access::MergeClause;
 use prost::Message;
 use protogen::metastore::types::catalog::RuntimePreference;
 use uuid::Uuid;
@@ -28,11 +30,15 @@ use crate::planner::physical_plan::alter_database::AlterDatabaseExec;
 use crate::planner::physical_plan::alter_table::AlterTableExec;
 use crate::planner::physical_plan::alter_tunnel_rotate_keys::AlterTunnelRotateKeysExec;
 use crate::planner::physical_plan::client_recv::ClientExchangeRecvExec;
+use crate::planner::physical_plan::comment_on::CommentOnExec;
+use crate::planner::physical_plan::constraint_check::{RowConstraint, RowConstraintKind};
 use crate::planner::physical_plan::copy_to::CopyToExec;
 use crate::planner::physical_plan::create_credentials::CreateCredentialsExec;
 use crate::planner::physical_plan::create_external_database::CreateExternalDatabaseExec;
 use crate::planner::physical_plan::create_external_table::CreateExternalTableExec;
+use crate::planner::physical_plan::create_role::CreateRoleExec;
 use crate::planner::physical_plan::create_schema::CreateSchemaExec;
+use crate::planner::physical_plan::create_sequence::CreateSequenceExec;
 use crate::planner::physical_plan::create_table::CreateTableExec;
 use crate::planner::physical_plan::create_temp_table::CreateTempTableExec;
 use crate::planner::physical_plan::create_tunnel::CreateTunnelExec;
@@ -41,15 +47,25 @@ use crate::planner::physical_plan::delete::DeleteExec;
 use crate::planner::physical_plan::describe_table::DescribeTableExec;
 use crate::planner::physical_plan::drop_credentials::DropCredentialsExec;
 use crate::planner::physical_plan::drop_database::DropDatabaseExec;
+use crate::planner::physical_plan::drop_role::DropRoleExec;
 use crate::planner::physical_plan::drop_schemas::DropSchemasExec;
+use crate::planner::physical_plan::drop_sequences::DropSequencesExec;
 use crate::planner::physical_plan::drop_tables::DropTablesExec;
 use crate::planner::physical_plan::drop_tunnel::DropTunnelExec;
 use crate::planner::physical_plan::drop_views::DropViewsExec;
+use crate::planner::physical_plan::grant_privileges::GrantPrivilegesExec;
 use crate::planner::physical_plan::insert::InsertExec;
+use crate::planner::physical_plan::merge::MergeExec;
+use crate::planner::physical_plan::optimize_table::OptimizeTableExec;
+use crate::planner::physical_plan::refresh_materialized_view::RefreshMaterializedViewExec;
 use crate::planner::physical_plan::remote_scan::{ProviderReference, RemoteScanExec};
+use crate::planner::physical_plan::restore_table::RestoreTableExec;
+use crate::planner::physical_plan::revoke_privileges::RevokePrivilegesExec;
 use crate::planner::physical_plan::set_var::SetVarExec;
 use crate::planner::physical_plan::show_var::ShowVarExec;
 use crate::planner::physical_plan::update::UpdateExec;
+use crate::planner::physical_plan::upsert::UpsertExec;
+use crate::planner::physical_plan::vacuum_table::VacuumTableExec;
 use crate::planner::physical_plan::values::ExtValuesExec;
 use crate::remote::provider_cache::ProviderCache;
 
@@ -192,17 +208,32 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
                         .try_into()?,
                 })
             }
-            proto::ExecutionPlanExtensionType::AlterTableExec(ext) => Arc::new(AlterTableExec {
-                catalog_version: ext.catalog_version,
-                schema: ext.schema,
-                name: ext.name,
-                operation: ext
-                    .operation
-                    .ok_or_else(|| {
-                        DataFusionError::Internal("missing alter table operation".to_string())
-                    })?
-                    .try_into()?,
-            }),
+            proto::ExecutionPlanExtensionType::AlterTableExec(ext) => {
+                let columns = ext
+                    .columns
+                    .into_iter()
+                    .map(|col| col.try_into())
+                    .collect::<Result<_, protogen::ProtoConvError>>()?;
+                let rewrite_exprs = ext
+                    .rewrite_exprs
+                    .iter()
+                    .map(|expr| parse_expr(expr, registry))
+                    .collect::<Result<_, _>>()?;
+                Arc::new(AlterTableExec {
+                    catalog_version: ext.catalog_version,
+                    schema: ext.schema,
+                    name: ext.name,
+                    operation: ext
+                        .operation
+                        .ok_or_else(|| {
+                            DataFusionError::Internal("missing alter table operation".to_string())
+                        })?
+                        .try_into()?,
+                    table: ext.table.map(|table| table.try_into()).transpose()?,
+                    columns,
+                    rewrite_exprs,
+                })
+            }
             proto::ExecutionPlanExtensionType::AlterTunnelRotateKeysExec(ext) => {
                 Arc::new(AlterTunnelRotateKeysExec {
                     catalog_version: ext.catalog_version,
@@ -235,6 +266,25 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
                     if_not_exists: ext.if_not_exists,
                     or_replace: ext.or_replace,
                     arrow_schema: Arc::new(schema),
+                    column_defaults: ext.column_defaults,
+                    check_constraints: ext
+                        .check_constraints
+                        .into_iter()
+                        .map(|c| c.into())
+                        .collect(),
+                    unique_constraints: ext
+                        .unique_constraints
+                        .into_iter()
+                        .map(|c| c.into())
+                        .collect(),
+                    partition_columns: ext.partition_columns,
+                    column_sequences: ext
+                        .column_sequences
+                        .into_iter()
+                        .map(|s| s.try_into())
+                        .collect::<Result<_, _>>()?,
+                    generated_always: ext.generated_always,
+                    materialized_view: ext.materialized_view.map(Into::into),
                     source: inputs.first().cloned(),
                 })
             }
@@ -272,6 +322,33 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
                 names: ext.names,
                 if_exists: ext.if_exists,
             }),
+            proto::ExecutionPlanExtensionType::CreateSequenceExec(ext) => {
+                Arc::new(CreateSequenceExec {
+                    catalog_version: ext.catalog_version,
+                    sequence: ext
+                        .sequence
+                        .ok_or_else(|| DataFusionError::Internal("missing sequence".to_string()))?
+                        .try_into()?,
+                })
+            }
+            proto::ExecutionPlanExtensionType::CommentOnExec(ext) => Arc::new(CommentOnExec {
+                catalog_version: ext.catalog_version,
+                comment: ext
+                    .comment
+                    .ok_or_else(|| DataFusionError::Internal("missing comment".to_string()))?
+                    .try_into()?,
+            }),
+            proto::ExecutionPlanExtensionType::DropSequencesExec(ext) => {
+                Arc::new(DropSequencesExec {
+                    catalog_version: ext.catalog_version,
+                    sequence_references: ext
+                        .sequence_references
+                        .into_iter()
+                        .map(|r| r.into())
+                        .collect(),
+                    if_exists: ext.if_exists,
+                })
+            }
             proto::ExecutionPlanExtensionType::DropViewsExec(ext) => Arc::new(DropViewsExec {
                 catalog_version: ext.catalog_version,
                 view_references: ext.view_references.into_iter().map(|r| r.into()).collect(),
@@ -341,6 +418,36 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
                     if_exists: ext.if_exists,
                 })
             }
+            proto::ExecutionPlanExtensionType::CreateRoleExec(ext) => Arc::new(CreateRoleExec {
+                catalog_version: ext.catalog_version,
+                name: ext.name,
+                if_not_exists: ext.if_not_exists,
+            }),
+            proto::ExecutionPlanExtensionType::DropRoleExec(ext) => Arc::new(DropRoleExec {
+                catalog_version: ext.catalog_version,
+                names: ext.names,
+                if_exists: ext.if_exists,
+            }),
+            proto::ExecutionPlanExtensionType::GrantPrivilegesExec(ext) => {
+                Arc::new(GrantPrivilegesExec {
+                    catalog_version: ext.catalog_version,
+                    grants: ext
+                        .grants
+                        .into_iter()
+                        .map(|g| g.try_into())
+                        .collect::<Result<_, _>>()?,
+                })
+            }
+            proto::ExecutionPlanExtensionType::RevokePrivilegesExec(ext) => {
+                Arc::new(RevokePrivilegesExec {
+                    catalog_version: ext.catalog_version,
+                    revokes: ext
+                        .revokes
+                        .into_iter()
+                        .map(|r| r.try_into())
+                        .collect::<Result<_, _>>()?,
+                })
+            }
             proto::ExecutionPlanExtensionType::DropTablesExec(ext) => Arc::new(DropTablesExec {
                 catalog_version: ext.catalog_version,
                 tbl_references: ext.tbl_references.into_iter().map(|r| r.into()).collect(),
@@ -379,6 +486,135 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
                         .try_into()?,
                     updates,
                     where_expr,
+                    constraints: decode_row_constraints(ext.constraints, registry)?,
+                })
+            }
+            proto::ExecutionPlanExtensionType::UpsertExec(ext) => {
+                let mut updates = Vec::with_capacity(ext.updates.len());
+                for update in ext.updates {
+                    let expr = update.expr.ok_or_else(|| {
+                        DataFusionError::Internal("missing expression".to_string())
+                    })?;
+                    let expr = parse_expr(&expr, registry)?;
+                    updates.push((update.column.clone(), expr));
+                }
+                let update_predicate: Option<Expr> = ext
+                    .update_predicate
+                    .map(|expr| parse_expr(&expr, registry))
+                    .transpose()?;
+                Arc::new(UpsertExec {
+                    table: ext
+                        .table
+                        .ok_or_else(|| DataFusionError::Internal("missing table".to_string()))?
+                        .try_into()?,
+                    source: inputs
+                        .first()
+                        .ok_or_else(|| {
+                            DataFusionError::Internal("missing input source".to_string())
+                        })?
+                        .clone(),
+                    conflict_columns: ext.conflict_columns,
+                    updates,
+                    update_predicate,
+                    constraints: decode_row_constraints(ext.constraints, registry)?,
+                })
+            }
+            proto::ExecutionPlanExtensionType::MergeExec(ext) => {
+                let on = ext.on.ok_or_else(|| {
+                    DataFusionError::Internal("missing merge condition".to_string())
+                })?;
+                let mut clauses = Vec::with_capacity(ext.clauses.len());
+                for clause in ext.clauses {
+                    let predicate = clause
+                        .predicate
+                        .map(|expr| parse_expr(&expr, registry))
+                        .transpose()?;
+                    let mut assignments = Vec::with_capacity(clause.assignments.len());
+                    for assignment in clause.assignments {
+                        let expr = assignment.expr.ok_or_else(|| {
+                            DataFusionError::Internal("missing expression".to_string())
+                        })?;
+                        assignments.push((assignment.column, parse_expr(&expr, registry)?));
+                    }
+                    clauses.push(match (clause.matched, clause.delete) {
+                        (true, true) => MergeClause::MatchedDelete { predicate },
+                        (true, false) => MergeClause::MatchedUpdate {
+                            predicate,
+                            updates: assignments,
+                        },
+                        (false, _) => MergeClause::NotMatchedInsert {
+                            predicate,
+                            values: assignments,
+                        },
+                    });
+                }
+                Arc::new(MergeExec {
+                    table: ext
+                        .table
+                        .ok_or_else(|| DataFusionError::Internal("missing table".to_string()))?
+                        .try_into()?,
+                    source: inputs
+                        .first()
+                        .ok_or_else(|| {
+                            DataFusionError::Internal("missing input source".to_string())
+                        })?
+                        .clone(),
+                    source_alias: ext.source_alias,
+                    target_alias: ext.target_alias,
+                    on: parse_expr(&on, registry)?,
+                    clauses,
+                    constraints: decode_row_constraints(ext.constraints, registry)?,
+                })
+            }
+            proto::ExecutionPlanExtensionType::RestoreTableExec(ext) => {
+                let version = match (ext.version, ext.timestamp) {
+                    (Some(version), _) => TableVersion::Version(version),
+                    (None, Some(timestamp)) => TableVersion::Timestamp(timestamp),
+                    (None, None) => {
+                        return Err(DataFusionError::Internal(
+                            "missing version to restore".to_string(),
+                        ))
+                    }
+                };
+                Arc::new(RestoreTableExec {
+                    table: ext
+                        .table
+                        .ok_or_else(|| DataFusionError::Internal("missing table".to_string()))?
+                        .try_into()?,
+                    version,
+                })
+            }
+            proto::ExecutionPlanExtensionType::VacuumTableExec(ext) => Arc::new(VacuumTableExec {
+                table: ext
+                    .table
+                    .ok_or_else(|| DataFusionError::Internal("missing table".to_string()))?
+                    .try_into()?,
+                retain_hours: ext.retain_hours,
+                enforce_retention: ext.enforce_retention,
+            }),
+            proto::ExecutionPlanExtensionType::OptimizeTableExec(ext) => {
+                Arc::new(OptimizeTableExec {
+                    table: ext
+                        .table
+                        .ok_or_else(|| DataFusionError::Internal("missing table".to_string()))?
+                        .try_into()?,
+                    zorder_by: ext.zorder_by,
+                })
+            }
+            proto::ExecutionPlanExtensionType::RefreshMaterializedViewExec(ext) => {
+                Arc::new(RefreshMaterializedViewExec {
+                    catalog_version: ext.catalog_version,
+                    schema: ext.schema,
+                    table: ext
+                        .table
+                        .ok_or_else(|| DataFusionError::Internal("missing table".to_string()))?
+                        .try_into()?,
+                    source: inputs
+                        .first()
+                        .ok_or_else(|| {
+                            DataFusionError::Internal("missing input source".to_string())
+                        })?
+                        .clone(),
                 })
             }
             proto::ExecutionPlanExtensionType::InsertExec(ext) => {
@@ -406,6 +642,7 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
                             })?
                             .clone(),
                     )),
+                    constraints: decode_row_constraints(ext.constraints, registry)?,
                 })
             }
             proto::ExecutionPlanExtensionType::DeleteExec(ext) => {
@@ -553,6 +790,28 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
                 if_not_exists: exec.if_not_exists,
                 or_replace: exec.or_replace,
                 arrow_schema: Some(exec.arrow_schema.clone().try_into()?),
+                column_defaults: exec.column_defaults.clone(),
+                check_constraints: exec
+                    .check_constraints
+                    .iter()
+                    .cloned()
+                    .map(|c| c.into())
+                    .collect(),
+                unique_constraints: exec
+                    .unique_constraints
+                    .iter()
+                    .cloned()
+                    .map(|c| c.into())
+                    .collect(),
+                partition_columns: exec.partition_columns.clone(),
+                column_sequences: exec
+                    .column_sequences
+                    .iter()
+                    .cloned()
+                    .map(|s| s.into())
+                    .collect(),
+                generated_always: exec.generated_always.clone(),
+                materialized_view: exec.materialized_view.clone().map(Into::into),
             })
         } else if let Some(exec) = node.as_any().downcast_ref::<CreateTempTableExec>() {
             proto::ExecutionPlanExtensionType::CreateTempTableExec(proto::CreateTempTableExec {
@@ -573,6 +832,13 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
                 schema: exec.schema.to_owned(),
                 name: exec.name.to_owned(),
                 operation: Some(exec.operation.clone().into()),
+                table: exec.table.clone().map(|table| table.into()),
+                columns: exec.columns.iter().cloned().map(|col| col.into()).collect(),
+                rewrite_exprs: exec
+                    .rewrite_exprs
+                    .iter()
+                    .map(|expr| expr.try_into())
+                    .collect::<Result<_, _>>()?,
             })
         } else if let Some(exec) = node.as_any().downcast_ref::<AlterTunnelRotateKeysExec>() {
             proto::ExecutionPlanExtensionType::AlterTunnelRotateKeysExec(
@@ -607,6 +873,27 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
                 names: exec.names.clone(),
                 if_exists: exec.if_exists,
             })
+        } else if let Some(exec) = node.as_any().downcast_ref::<CreateSequenceExec>() {
+            proto::ExecutionPlanExtensionType::CreateSequenceExec(proto::CreateSequenceExec {
+                catalog_version: exec.catalog_version,
+                sequence: Some(exec.sequence.clone().into()),
+            })
+        } else if let Some(exec) = node.as_any().downcast_ref::<CommentOnExec>() {
+            proto::ExecutionPlanExtensionType::CommentOnExec(proto::CommentOnExec {
+                catalog_version: exec.catalog_version,
+                comment: Some(exec.comment.clone().into()),
+            })
+        } else if let Some(exec) = node.as_any().downcast_ref::<DropSequencesExec>() {
+            proto::ExecutionPlanExtensionType::DropSequencesExec(proto::DropSequencesExec {
+                catalog_version: exec.catalog_version,
+                sequence_references: exec
+                    .sequence_references
+                    .clone()
+                    .into_iter()
+                    .map(|r| r.into())
+                    .collect(),
+                if_exists: exec.if_exists,
+            })
         } else if let Some(exec) = node.as_any().downcast_ref::<DropViewsExec>() {
             proto::ExecutionPlanExtensionType::DropViewsExec(proto::DropViewsExec {
                 catalog_version: exec.catalog_version,
@@ -669,6 +956,28 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
                 names: exec.names.clone(),
                 if_exists: exec.if_exists,
             })
+        } else if let Some(exec) = node.as_any().downcast_ref::<CreateRoleExec>() {
+            proto::ExecutionPlanExtensionType::CreateRoleExec(proto::CreateRoleExec {
+                catalog_version: exec.catalog_version,
+                name: exec.name.clone(),
+                if_not_exists: exec.if_not_exists,
+            })
+        } else if let Some(exec) = node.as_any().downcast_ref::<DropRoleExec>() {
+            proto::ExecutionPlanExtensionType::DropRoleExec(proto::DropRoleExec {
+                catalog_version: exec.catalog_version,
+                names: exec.names.clone(),
+                if_exists: exec.if_exists,
+            })
+        } else if let Some(exec) = node.as_any().downcast_ref::<GrantPrivilegesExec>() {
+            proto::ExecutionPlanExtensionType::GrantPrivilegesExec(proto::GrantPrivilegesExec {
+                catalog_version: exec.catalog_version,
+                grants: exec.grants.iter().cloned().map(|g| g.into()).collect(),
+            })
+        } else if let Some(exec) = node.as_any().downcast_ref::<RevokePrivilegesExec>() {
+            proto::ExecutionPlanExtensionType::RevokePrivilegesExec(proto::RevokePrivilegesExec {
+                catalog_version: exec.catalog_version,
+                revokes: exec.revokes.iter().cloned().map(|r| r.into()).collect(),
+            })
         } else if let Some(exec) = node.as_any().downcast_ref::<DropTablesExec>() {
             proto::ExecutionPlanExtensionType::DropTablesExec(proto::DropTablesExec {
                 catalog_version: exec.catalog_version,
@@ -712,7 +1021,90 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
                     .as_ref()
                     .map(|expr| expr.try_into())
                     .transpose()?,
+                constraints: encode_row_constraints(&exec.constraints)?,
+            })
+        } else if let Some(exec) = node.as_any().downcast_ref::<UpsertExec>() {
+            let mut updates = Vec::with_capacity(exec.updates.len());
+            for (col, expr) in &exec.updates {
+                updates.push(proto::UpdateSelector {
+                    column: col.clone(),
+                    expr: Some(expr.try_into()?),
+                });
+            }
+
+            proto::ExecutionPlanExtensionType::UpsertExec(proto::UpsertExec {
+                table: Some(exec.table.clone().into()),
+                conflict_columns: exec.conflict_columns.clone(),
+                updates,
+                update_predicate: exec
+                    .update_predicate
+                    .as_ref()
+                    .map(|expr| expr.try_into())
+                    .transpose()?,
+                constraints: encode_row_constraints(&exec.constraints)?,
+            })
+        } else if let Some(exec) = node.as_any().downcast_ref::<MergeExec>() {
+            let mut clauses = Vec::with_capacity(exec.clauses.len());
+            for clause in &exec.clauses {
+                let assignments = match clause {
+                    MergeClause::MatchedUpdate { updates, .. } => updates.as_slice(),
+                    MergeClause::NotMatchedInsert { values, .. } => values.as_slice(),
+                    MergeClause::MatchedDelete { .. } => &[],
+                };
+                let assignments = assignments
+                    .iter()
+                    .map(|(column, expr)| {
+                        Ok(proto::UpdateSelector {
+                            column: column.clone(),
+                            expr: Some(expr.try_into()?),
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                clauses.push(proto::MergeClause {
+                    matched: clause.is_matched(),
+                    delete: matches!(clause, MergeClause::MatchedDelete { .. }),
+                    predicate: clause.predicate().map(|expr| expr.try_into()).transpose()?,
+                    assignments,
+                });
+            }
+
+            proto::ExecutionPlanExtensionType::MergeExec(proto::MergeExec {
+                table: Some(exec.table.clone().into()),
+                source_alias: exec.source_alias.clone(),
+                target_alias: exec.target_alias.clone(),
+                on: Some((&exec.on).try_into()?),
+                clauses,
+                constraints: encode_row_constraints(&exec.constraints)?,
+            })
+        } else if let Some(exec) = node.as_any().downcast_ref::<RestoreTableExec>() {
+            let (version, timestamp) = match exec.version {
+                TableVersion::Version(version) => (Some(version), None),
+                TableVersion::Timestamp(timestamp) => (None, Some(timestamp)),
+            };
+            proto::ExecutionPlanExtensionType::RestoreTableExec(proto::RestoreTableExec {
+                table: Some(exec.table.clone().into()),
+                version,
+                timestamp,
             })
+        } else if let Some(exec) = node.as_any().downcast_ref::<VacuumTableExec>() {
+            proto::ExecutionPlanExtensionType::VacuumTableExec(proto::VacuumTableExec {
+                table: Some(exec.table.clone().into()),
+                retain_hours: exec.retain_hours,
+                enforce_retention: exec.enforce_retention,
+            })
+        } else if let Some(exec) = node.as_any().downcast_ref::<OptimizeTableExec>() {
+            proto::ExecutionPlanExtensionType::OptimizeTableExec(proto::OptimizeTableExec {
+                table: Some(exec.table.clone().into()),
+                zorder_by: exec.zorder_by.clone(),
+            })
+        } else if let Some(exec) = node.as_any().downcast_ref::<RefreshMaterializedViewExec>() {
+            proto::ExecutionPlanExtensionType::RefreshMaterializedViewExec(
+                proto::RefreshMaterializedViewExec {
+                    catalog_version: exec.catalog_version,
+                    schema: exec.schema.clone(),
+                    table: Some(exec.table.clone().into()),
+                },
+            )
         } else if let Some(exec) = node.as_any().downcast_ref::<InsertExec>() {
             let id = match exec.provider {
                 ProviderReference::RemoteReference(id) => id,
@@ -725,6 +1117,7 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
 
             proto::ExecutionPlanExtensionType::InsertExec(proto::InsertExec {
                 provider_id: id.into_bytes().to_vec(),
+                constraints: encode_row_constraints(&exec.constraints)?,
             })
         } else if let Some(exec) = node.as_any().downcast_ref::<DeleteExec>() {
             proto::ExecutionPlanExtensionType::DeleteExec(proto::DeleteExec {
@@ -812,3 +1205,43 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
             .map_err(|e| DataFusionError::External(Box::new(e)))
     }
 }
+
+fn decode_row_constraints(
+    constraints: Vec<protogen::sqlexec::physical_plan::RowConstraint>,
+    registry: &dyn FunctionRegistry,
+) -> Result<Vec<RowConstraint>> {
+    constraints
+        .into_iter()
+        .map(|constraint| {
+            let expr = constraint
+                .expr
+                .ok_or_else(|| DataFusionError::Internal("missing expression".to_string()))?;
+            Ok(RowConstraint {
+                kind: if constraint.not_null {
+                    RowConstraintKind::NotNull
+                } else {
+                    RowConstraintKind::Check
+                },
+                name: constraint.name,
+                expr: parse_expr(&expr, registry)?,
+            })
+        })
+        .collect()
+}
+
+fn encode_row_constraints(
+    constraints: &[RowConstraint],
+) -> Result<Vec<protogen::sqlexec::physical_plan::RowConstraint>> {
+    use protogen::sqlexec::physical_plan as proto;
+
+    constraints
+        .iter()
+        .map(|constraint| {
+            Ok(proto::RowConstraint {
+                not_null: constraint.kind == RowConstraintKind::NotNull,
+                name: constraint.name.clone(),
+                expr: Some((&constraint.expr).try_into()?),
+            })
+        })
+        .collect()
+}
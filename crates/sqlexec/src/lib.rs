@@ -1,4 +1,5 @@
 //! SQL execution.
+pub mod cancel;
 pub mod context;
 pub mod engine;
 pub mod environment;
@@ -10,6 +11,8 @@ pub mod session;
 
 mod dispatch;
 mod planner;
+mod refresh;
 mod resolve;
 
-pub use planner::logical_plan::{LogicalPlan, OperationInfo};
+pub use planner::logical_plan::{CopyStdioFormat, CopyStdioOptions, LogicalPlan, OperationInfo};
+pub use planner::physical_plan::copy_stdin::CopyStdinSink;
@@ -3,7 +3,16 @@ use datafusion::optimizer::optimizer::Optimizer;
 use datafusion::optimizer::OptimizerRule;
 
 use crate::planner::extension::{ExtensionNode, ExtensionType};
-use crate::planner::logical_plan::{CopyTo, CreateTable, CreateTempTable, Insert};
+use crate::planner::logical_plan::{
+    CopyFrom,
+    CopyTo,
+    CreateTable,
+    CreateTempTable,
+    Insert,
+    Merge,
+    RefreshMaterializedView,
+    Upsert,
+};
 
 fn require_downcast_lp<P: 'static>(plan: &dyn UserDefinedLogicalNode) -> &P {
     match plan.as_any().downcast_ref::<P>() {
@@ -68,6 +77,15 @@ impl OptimizerRule for DdlInputOptimizationRule {
                         let lp = CreateTempTable { source, ..lp };
                         Ok(Some(DFLogicalPlan::Extension(lp.into_extension())))
                     }
+                    ExtensionType::CopyFrom => {
+                        let lp = require_downcast_lp::<CopyFrom>(node).clone();
+                        let source =
+                            self.default_optimizer
+                                .optimize(&lp.source, config, |_, _| {})?;
+
+                        let lp = CopyFrom { source, ..lp };
+                        Ok(Some(DFLogicalPlan::Extension(lp.into_extension())))
+                    }
                     ExtensionType::Insert => {
                         let lp = require_downcast_lp::<Insert>(node).clone();
                         let source =
@@ -77,6 +95,33 @@ impl OptimizerRule for DdlInputOptimizationRule {
                         let lp = Insert { source, ..lp };
                         Ok(Some(DFLogicalPlan::Extension(lp.into_extension())))
                     }
+                    ExtensionType::Upsert => {
+                        let lp = require_downcast_lp::<Upsert>(node).clone();
+                        let source =
+                            self.default_optimizer
+                                .optimize(&lp.source, config, |_, _| {})?;
+
+                        let lp = Upsert { source, ..lp };
+                        Ok(Some(DFLogicalPlan::Extension(lp.into_extension())))
+                    }
+                    ExtensionType::Merge => {
+                        let lp = require_downcast_lp::<Merge>(node).clone();
+                        let source =
+                            self.default_optimizer
+                                .optimize(&lp.source, config, |_, _| {})?;
+
+                        let lp = Merge { source, ..lp };
+                        Ok(Some(DFLogicalPlan::Extension(lp.into_extension())))
+                    }
+                    ExtensionType::RefreshMaterializedView => {
+                        let lp = require_downcast_lp::<RefreshMaterializedView>(node).clone();
+                        let source =
+                            self.default_optimizer
+                                .optimize(&lp.source, config, |_, _| {})?;
+
+                        let lp = RefreshMaterializedView { source, ..lp };
+                        Ok(Some(DFLogicalPlan::Extension(lp.into_extension())))
+                    }
                     _ => Ok(None),
                 }
             }
@@ -173,6 +218,13 @@ mod test {
                 if_not_exists: false,
                 or_replace: false,
                 schema: schema.clone(),
+                column_defaults: Default::default(),
+                check_constraints: Vec::new(),
+                unique_constraints: Vec::new(),
+                partition_columns: Vec::new(),
+                column_sequences: Vec::new(),
+                generated_always: Vec::new(),
+                materialized_view: None,
                 source: Some(plan),
             }
             .into_extension(),
@@ -286,6 +338,7 @@ mod test {
                 source: plan,
                 provider: ProviderReference::RemoteReference(Uuid::nil()),
                 runtime_preference: RuntimePreference::Unspecified,
+                constraints: Vec::new(),
             }
             .into_extension(),
         );
@@ -16,7 +16,7 @@ use datafusion_ext::functions::FuncParamValue;
 use datafusion_ext::planner::AsyncContextProvider;
 use datafusion_ext::runtime::table_provider::RuntimeAwareTableProvider;
 use datafusion_ext::vars::CredentialsVarProvider;
-use protogen::metastore::types::catalog::{CatalogEntry, RuntimePreference};
+use protogen::metastore::types::catalog::{CatalogEntry, Privilege, RuntimePreference};
 use protogen::metastore::types::options::TableOptionsV0;
 use protogen::rpcsrv::types::service::ResolvedTableReference;
 
@@ -43,6 +43,11 @@ pub struct PartialContextProvider<'a> {
     /// Entry resolver to use to resolve tables and other objects.
     resolver: EntryResolver<'a>,
     runtime_preference: RuntimePreference,
+    /// Whether to check the session user's privileges on resolved objects.
+    check_privileges: bool,
+    /// The object being written to, along with the privilege required for
+    /// the write. All other objects require SELECT.
+    write_target: Option<(OwnedTableReference, Privilege)>,
 }
 
 impl<'a> PartialContextProvider<'a> {
@@ -54,9 +59,47 @@ impl<'a> PartialContextProvider<'a> {
             ctx,
             resolver,
             runtime_preference: RuntimePreference::Unspecified,
+            check_privileges: true,
+            write_target: None,
         })
     }
 
+    /// Skip privilege checks when resolving objects.
+    ///
+    /// Used when planning the body of a view, since access is checked
+    /// against the view itself.
+    pub fn without_privilege_checks(mut self) -> Self {
+        self.check_privileges = false;
+        self
+    }
+
+    /// Set the object that will be written to, requiring `privilege` instead
+    /// of SELECT when it's resolved.
+    pub fn set_write_target(&mut self, reference: OwnedTableReference, privilege: Privilege) {
+        self.write_target = Some((reference, privilege));
+    }
+
+    /// Check that the referenced object may be written to with each of
+    /// `privileges`.
+    ///
+    /// Used for statements requiring more than one privilege on the object
+    /// being written to.
+    pub fn check_write_privileges(
+        &self,
+        reference: TableReference<'_>,
+        privileges: &[Privilege],
+    ) -> Result<(), PlanError> {
+        let ent = self.resolver.resolve_entry_from_reference(reference)?;
+        check_writable(&ent)?;
+        if !self.check_privileges {
+            return Ok(());
+        }
+        for privilege in privileges {
+            self.check_privilege(&ent, *privilege)?;
+        }
+        Ok(())
+    }
+
     fn new_dispatcher(&self) -> Dispatcher {
         Dispatcher::new(
             self.ctx.get_session_catalog(),
@@ -122,7 +165,21 @@ impl<'a> PartialContextProvider<'a> {
             }
         }
 
+        let write_privilege = match &self.write_target {
+            Some((target, privilege)) if *target == reference.to_owned_reference() => {
+                Some(*privilege)
+            }
+            _ => None,
+        };
+        let privilege = write_privilege.unwrap_or(Privilege::Select);
+
         let ent = self.resolver.resolve_entry_from_reference(reference)?;
+        if write_privilege.is_some() {
+            check_writable(&ent)?;
+        }
+        if self.check_privileges {
+            self.check_privilege(&ent, privilege)?;
+        }
 
         let client = self.ctx.exec_client();
         let provider = match ent {
@@ -265,6 +322,59 @@ impl<'a> PartialContextProvider<'a> {
 
         Ok(provider)
     }
+
+    /// Check that the session user has the given privilege on the resolved
+    /// entry.
+    ///
+    /// Objects in external databases require USAGE on the database for
+    /// reads, and INSERT for writes. Table functions reaching data outside
+    /// of the catalog require EXECUTE on the function.
+    fn check_privilege(&self, ent: &ResolvedEntry, privilege: Privilege) -> Result<(), PlanError> {
+        let catalog = self.ctx.get_session_catalog();
+        if catalog.current_role().is_none() {
+            return Ok(());
+        }
+
+        let (allowed, object_type, name) = match ent {
+            ResolvedEntry::Entry(ent @ (CatalogEntry::Table(_) | CatalogEntry::View(_))) => (
+                catalog.has_privilege(ent, privilege),
+                ent.entry_type().as_str(),
+                ent.get_meta().name.clone(),
+            ),
+            ResolvedEntry::Entry(ent @ CatalogEntry::Function(func)) => {
+                let requires_grant = self
+                    .ctx
+                    .function_registry()
+                    .get_table_func(&func.meta.name)
+                    .map(|f| f.requires_execute_grant())
+                    .unwrap_or(false);
+                (
+                    !requires_grant || catalog.has_privilege(ent, Privilege::Execute),
+                    "function",
+                    func.meta.name.clone(),
+                )
+            }
+            ResolvedEntry::NeedsExternalResolution { db_ent, .. } => {
+                let privilege = match privilege {
+                    Privilege::Select => Privilege::Usage,
+                    _ => Privilege::Insert,
+                };
+                let ent = CatalogEntry::Database((*db_ent).clone());
+                (
+                    catalog.has_privilege(&ent, privilege),
+                    "database",
+                    db_ent.meta.name.clone(),
+                )
+            }
+            _ => (true, "", String::new()),
+        };
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(PlanError::PermissionDenied { object_type, name })
+        }
+    }
 }
 
 #[async_trait]
@@ -276,8 +386,11 @@ impl<'a> AsyncContextProvider for PartialContextProvider<'a> {
         let provider = self
             .table_provider(name.to_owned_reference())
             .await
-            .map_err(|e| {
-                DataFusionError::Plan(format!("Unable to fetch table provider for '{name}': {e}"))
+            .map_err(|e| match e {
+                e @ PlanError::PermissionDenied { .. } => DataFusionError::External(Box::new(e)),
+                e => DataFusionError::Plan(format!(
+                    "Unable to fetch table provider for '{name}': {e}"
+                )),
             })?;
         Ok(Arc::new(DefaultTableSource::new(Arc::new(provider))))
     }
@@ -324,3 +437,18 @@ impl<'a> AsyncContextProvider for PartialContextProvider<'a> {
         self.state.config_options()
     }
 }
+
+/// Check that a resolved entry may be written to.
+///
+/// Materialized views may only be changed by refreshing them.
+fn check_writable(ent: &ResolvedEntry) -> Result<(), PlanError> {
+    match ent {
+        ResolvedEntry::Entry(CatalogEntry::Table(table)) if table.materialized_view().is_some() => {
+            Err(PlanError::String(format!(
+                "Cannot write to materialized view '{}'",
+                table.meta.name
+            )))
+        }
+        _ => Ok(()),
+    }
+}
@@ -71,6 +71,15 @@ pub enum PlanError {
     #[error("Not allowed to write into the object: {0}")]
     ObjectNotAllowedToWriteInto(OwnedTableReference),
 
+    #[error("permission denied for {object_type} {name}")]
+    PermissionDenied {
+        object_type: &'static str,
+        name: String,
+    },
+
+    #[error("permission denied: role '{role}' may only query and modify data")]
+    StatementNotAllowedForRole { role: String },
+
     #[error("Exec error: {0}")]
     Exec(Box<crate::errors::ExecError>), // TODO: Try to remove.
 
@@ -116,6 +125,21 @@ impl From<PlanError> for datafusion::error::DataFusionError {
 
 pub type Result<T, E = PlanError> = std::result::Result<T, E>;
 
+impl PlanError {
+    pub fn sql_state(&self) -> SqlState {
+        match self {
+            PlanError::PermissionDenied { .. } | PlanError::StatementNotAllowedForRole { .. } => {
+                SqlState::InsufficientPrivilege
+            }
+            PlanError::Exec(e) => e.sql_state(),
+            PlanError::DataFusion(datafusion::error::DataFusionError::External(e)) => {
+                crate::errors::external_sql_state(e.as_ref())
+            }
+            _ => SqlState::InternalError,
+        }
+    }
+}
+
 impl From<crate::errors::ExecError> for PlanError {
     fn from(value: crate::errors::ExecError) -> Self {
         PlanError::Exec(Box::new(value))
@@ -148,3 +172,4 @@ macro_rules! internal {
 }
 use datafusion::common::OwnedTableReference;
 pub(crate) use internal;
+use pgrepr::notice::SqlState;
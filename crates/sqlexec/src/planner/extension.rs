@@ -7,11 +7,15 @@ use super::logical_plan::{
     AlterDatabase,
     AlterTable,
     AlterTunnelRotateKeys,
+    CommentOn,
+    CopyFrom,
     CopyTo,
     CreateCredentials,
     CreateExternalDatabase,
     CreateExternalTable,
+    CreateRole,
     CreateSchema,
+    CreateSequence,
     CreateTable,
     CreateTempTable,
     CreateTunnel,
@@ -20,14 +24,24 @@ use super::logical_plan::{
     DescribeTable,
     DropCredentials,
     DropDatabase,
+    DropRole,
     DropSchemas,
+    DropSequences,
     DropTables,
     DropTunnel,
     DropViews,
+    GrantPrivileges,
     Insert,
+    Merge,
+    OptimizeTable,
+    RefreshMaterializedView,
+    RestoreTable,
+    RevokePrivileges,
     SetVariable,
     ShowVariable,
     Update,
+    Upsert,
+    VacuumTable,
 };
 use crate::errors::{internal, ExecError, Result};
 use crate::LogicalPlan;
@@ -43,6 +57,7 @@ pub enum ExtensionType {
     CreateCredentials,
     CreateExternalDatabase,
     CreateExternalTable,
+    CreateRole,
     CreateSchema,
     CreateTable,
     CreateTempTable,
@@ -52,15 +67,28 @@ pub enum ExtensionType {
     DropTables,
     DropCredentials,
     DropDatabase,
+    DropRole,
     DropSchemas,
     DropTunnel,
     DropViews,
     SetVariable,
     ShowVariable,
+    CopyFrom,
     CopyTo,
     Update,
     Insert,
     Delete,
+    GrantPrivileges,
+    RevokePrivileges,
+    Upsert,
+    Merge,
+    RestoreTable,
+    VacuumTable,
+    OptimizeTable,
+    RefreshMaterializedView,
+    CreateSequence,
+    DropSequences,
+    CommentOn,
 }
 
 impl FromStr for ExtensionType {
@@ -87,10 +115,24 @@ impl FromStr for ExtensionType {
             DropViews::EXTENSION_NAME => Self::DropViews,
             SetVariable::EXTENSION_NAME => Self::SetVariable,
             ShowVariable::EXTENSION_NAME => Self::ShowVariable,
+            CopyFrom::EXTENSION_NAME => Self::CopyFrom,
             CopyTo::EXTENSION_NAME => Self::CopyTo,
             Update::EXTENSION_NAME => Self::Update,
             Insert::EXTENSION_NAME => Self::Insert,
             Delete::EXTENSION_NAME => Self::Delete,
+            CreateRole::EXTENSION_NAME => Self::CreateRole,
+            DropRole::EXTENSION_NAME => Self::DropRole,
+            GrantPrivileges::EXTENSION_NAME => Self::GrantPrivileges,
+            RevokePrivileges::EXTENSION_NAME => Self::RevokePrivileges,
+            Upsert::EXTENSION_NAME => Self::Upsert,
+            Merge::EXTENSION_NAME => Self::Merge,
+            RestoreTable::EXTENSION_NAME => Self::RestoreTable,
+            VacuumTable::EXTENSION_NAME => Self::VacuumTable,
+            OptimizeTable::EXTENSION_NAME => Self::OptimizeTable,
+            RefreshMaterializedView::EXTENSION_NAME => Self::RefreshMaterializedView,
+            CreateSequence::EXTENSION_NAME => Self::CreateSequence,
+            DropSequences::EXTENSION_NAME => Self::DropSequences,
+            CommentOn::EXTENSION_NAME => Self::CommentOn,
             _ => return Err(internal!("unknown extension type: {}", s)),
         })
     }
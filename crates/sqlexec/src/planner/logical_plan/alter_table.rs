@@ -1,7 +1,10 @@
+use protogen::metastore::types::catalog::TableEntry;
+use protogen::metastore::types::options::InternalColumnDefinition;
 use protogen::metastore::types::service::AlterTableOperation;
 
 use super::{
     DfLogicalPlan,
+    Expr,
     ExtensionNode,
     UserDefinedLogicalNodeCore,
     GENERIC_OPERATION_LOGICAL_SCHEMA,
@@ -12,6 +15,14 @@ pub struct AlterTable {
     pub schema: String,
     pub name: String,
     pub operation: AlterTableOperation,
+    /// The native table being altered, set when the operation changes the
+    /// table's columns.
+    pub table: Option<TableEntry>,
+    /// Columns of the table after the alter.
+    pub columns: Vec<InternalColumnDefinition>,
+    /// Expressions producing the new table columns from the existing table
+    /// data. Empty if the existing data files don't need to be rewritten.
+    pub rewrite_exprs: Vec<Expr>,
 }
 
 impl UserDefinedLogicalNodeCore for AlterTable {
@@ -0,0 +1,69 @@
+use datafusion::prelude::Expr;
+use protogen::metastore::types::catalog::RuntimePreference;
+
+use super::{
+    DfLogicalPlan,
+    ExtensionNode,
+    UserDefinedLogicalNodeCore,
+    GENERIC_OPERATION_AND_COUNT_LOGICAL_SCHEMA,
+};
+use crate::planner::physical_plan::constraint_check::RowConstraint;
+use crate::planner::physical_plan::remote_scan::ProviderReference;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CopyFrom {
+    /// Scan over the source files.
+    pub source: DfLogicalPlan,
+    /// Table to copy the data into.
+    pub provider: ProviderReference,
+    pub runtime_preference: RuntimePreference,
+    /// For each column in the table, the index of the source column it's
+    /// copied from. Columns without a source column are filled with their
+    /// default, or null if they have none.
+    pub column_mapping: Vec<Option<usize>>,
+    /// For each column in the table, the default used when the column isn't
+    /// copied from the source.
+    pub column_defaults: Vec<Option<Expr>>,
+    /// Constraints checked for each row copied into the table.
+    pub constraints: Vec<RowConstraint>,
+    /// Maximum number of rows that can be skipped for failing to convert to
+    /// the table's types before erroring.
+    ///
+    /// Only conversion failures are counted. Rows the file reader can't
+    /// parse at all (e.g. malformed CSV lines) always fail the copy.
+    pub max_errors: u64,
+}
+
+impl UserDefinedLogicalNodeCore for CopyFrom {
+    fn name(&self) -> &str {
+        Self::EXTENSION_NAME
+    }
+
+    fn inputs(&self) -> Vec<&DfLogicalPlan> {
+        vec![&self.source]
+    }
+
+    fn schema(&self) -> &datafusion::common::DFSchemaRef {
+        &GENERIC_OPERATION_AND_COUNT_LOGICAL_SCHEMA
+    }
+
+    fn expressions(&self) -> Vec<datafusion::prelude::Expr> {
+        Vec::new()
+    }
+
+    fn fmt_for_explain(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", Self::EXTENSION_NAME)
+    }
+
+    fn from_template(
+        &self,
+        _exprs: &[datafusion::prelude::Expr],
+        _inputs: &[DfLogicalPlan],
+    ) -> Self {
+        self.clone()
+    }
+}
+
+impl ExtensionNode for CopyFrom {
+    const EXTENSION_NAME: &'static str = "CopyFrom";
+}
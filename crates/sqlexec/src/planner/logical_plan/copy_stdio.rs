@@ -0,0 +1,104 @@
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::prelude::Expr;
+
+use super::DfLogicalPlan;
+use crate::planner::physical_plan::constraint_check::RowConstraint;
+use crate::planner::physical_plan::remote_scan::ProviderReference;
+
+/// Plans for COPY to and from the client (`COPY .. FROM STDIN` and `COPY ..
+/// TO STDOUT`).
+///
+/// The data for these is exchanged over the client connection, so these
+/// plans need to be driven by the protocol handler instead of running to
+/// completion inside the session.
+#[derive(Clone, Debug)]
+pub enum CopyStdioPlan {
+    /// Copy rows sent by the client into a table.
+    From {
+        /// Table to copy the data into.
+        provider: ProviderReference,
+        /// Schema of the rows sent by the client.
+        schema: SchemaRef,
+        /// For each column in the table, the index of the column sent by the
+        /// client it's copied from.
+        column_mapping: Vec<Option<usize>>,
+        /// For each column in the table, the default used when the column
+        /// isn't sent by the client.
+        column_defaults: Vec<Option<Expr>>,
+        /// Constraints checked for each row copied into the table.
+        constraints: Vec<RowConstraint>,
+        /// Maximum number of rows that can be skipped for failing to convert
+        /// to the table's types before erroring. Rows that can't be parsed
+        /// always fail the copy.
+        max_errors: u64,
+        options: CopyStdioOptions,
+    },
+    /// Copy the output of a query to the client.
+    To {
+        source: DfLogicalPlan,
+        options: CopyStdioOptions,
+    },
+}
+
+impl CopyStdioPlan {
+    pub fn options(&self) -> &CopyStdioOptions {
+        match self {
+            Self::From { options, .. } | Self::To { options, .. } => options,
+        }
+    }
+}
+
+/// Format of the data exchanged with the client.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CopyStdioFormat {
+    Text,
+    Csv,
+    Binary,
+}
+
+impl CopyStdioFormat {
+    pub const TEXT: &'static str = "text";
+    pub const CSV: &'static str = "csv";
+    pub const BINARY: &'static str = "binary";
+}
+
+/// Options for COPY to and from the client, following postgres' COPY
+/// options.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CopyStdioOptions {
+    pub format: CopyStdioFormat,
+    /// Character separating columns. Unused for binary.
+    pub delimiter: u8,
+    /// String representing a null value. Unused for binary.
+    pub null: String,
+    /// Whether the data begins with a header line. Unused for binary.
+    pub header: bool,
+    /// Quoting character. Only used for CSV.
+    pub quote: u8,
+    /// Character used to escape the quote character inside quoted values.
+    /// Only used for CSV.
+    pub escape: u8,
+}
+
+impl CopyStdioOptions {
+    pub fn new(format: CopyStdioFormat) -> Self {
+        match format {
+            CopyStdioFormat::Text => CopyStdioOptions {
+                format,
+                delimiter: b'\t',
+                null: "\\N".to_string(),
+                header: false,
+                quote: b'"',
+                escape: b'"',
+            },
+            CopyStdioFormat::Csv | CopyStdioFormat::Binary => CopyStdioOptions {
+                format,
+                delimiter: b',',
+                null: String::new(),
+                header: false,
+                quote: b'"',
+                escape: b'"',
+            },
+        }
+    }
+}
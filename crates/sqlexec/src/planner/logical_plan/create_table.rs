@@ -1,3 +1,12 @@
+use std::collections::BTreeMap;
+
+use protogen::metastore::types::options::{
+    CheckConstraint,
+    MaterializedViewOptions,
+    UniqueConstraint,
+};
+use protogen::metastore::types::service;
+
 use super::{
     DFSchemaRef,
     DfLogicalPlan,
@@ -13,6 +22,18 @@ pub struct CreateTable {
     pub if_not_exists: bool,
     pub or_replace: bool,
     pub schema: DFSchemaRef,
+    /// SQL for the default values of columns, keyed by column name.
+    pub column_defaults: BTreeMap<String, String>,
+    pub check_constraints: Vec<CheckConstraint>,
+    pub unique_constraints: Vec<UniqueConstraint>,
+    /// Columns the table is partitioned by.
+    pub partition_columns: Vec<String>,
+    /// Sequences generating the values of SERIAL and identity columns.
+    pub column_sequences: Vec<service::CreateSequence>,
+    /// Identity columns that can't be written to explicitly.
+    pub generated_always: Vec<String>,
+    /// Set if the table holds the results of a materialized view.
+    pub materialized_view: Option<MaterializedViewOptions>,
     pub source: Option<DfLogicalPlan>,
 }
 
@@ -9,6 +9,7 @@ use super::{
     UserDefinedLogicalNodeCore,
     GENERIC_OPERATION_AND_COUNT_LOGICAL_SCHEMA,
 };
+use crate::planner::physical_plan::constraint_check::RowConstraint;
 use crate::planner::physical_plan::remote_scan::ProviderReference;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -16,6 +17,9 @@ pub struct Insert {
     pub source: DfLogicalPlan,
     pub provider: ProviderReference,
     pub runtime_preference: RuntimePreference,
+    /// Constraints checked for each inserted row. The source produces a
+    /// boolean column for each constraint following the table's columns.
+    pub constraints: Vec<RowConstraint>,
 }
 
 impl UserDefinedLogicalNodeCore for Insert {
@@ -42,9 +46,16 @@ impl UserDefinedLogicalNodeCore for Insert {
     fn from_template(
         &self,
         _exprs: &[datafusion::prelude::Expr],
-        _inputs: &[DfLogicalPlan],
+        inputs: &[DfLogicalPlan],
     ) -> Self {
-        self.clone()
+        // Keep the rewritten source (e.g. with placeholders replaced by bound
+        // parameters).
+        Self {
+            source: inputs[0].clone(),
+            provider: self.provider.clone(),
+            runtime_preference: self.runtime_preference,
+            constraints: self.constraints.clone(),
+        }
     }
 }
 
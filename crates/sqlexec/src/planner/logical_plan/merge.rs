@@ -0,0 +1,65 @@
+use datasources::native::access::MergeClause;
+use protogen::metastore::types::catalog::TableEntry;
+
+use super::{
+    DfLogicalPlan,
+    Expr,
+    ExtensionNode,
+    UserDefinedLogicalNodeCore,
+    GENERIC_OPERATION_AND_COUNT_LOGICAL_SCHEMA,
+};
+use crate::planner::physical_plan::constraint_check::RowConstraint;
+
+/// A `MERGE INTO` a native table or an external delta table.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Merge {
+    pub table: TableEntry,
+    pub source: DfLogicalPlan,
+    /// Qualifier for the columns of the source in the expressions of the
+    /// merge.
+    pub source_alias: String,
+    /// Qualifier for the columns of the table in the expressions of the
+    /// merge.
+    pub target_alias: String,
+    pub on: Expr,
+    pub clauses: Vec<MergeClause>,
+    /// Constraints checked for each row written by the merge.
+    pub constraints: Vec<RowConstraint>,
+}
+
+impl UserDefinedLogicalNodeCore for Merge {
+    fn name(&self) -> &str {
+        Self::EXTENSION_NAME
+    }
+
+    fn inputs(&self) -> Vec<&DfLogicalPlan> {
+        vec![&self.source]
+    }
+
+    fn schema(&self) -> &datafusion::common::DFSchemaRef {
+        &GENERIC_OPERATION_AND_COUNT_LOGICAL_SCHEMA
+    }
+
+    fn expressions(&self) -> Vec<datafusion::prelude::Expr> {
+        Vec::new()
+    }
+
+    fn fmt_for_explain(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", Self::EXTENSION_NAME)
+    }
+
+    fn from_template(
+        &self,
+        _exprs: &[datafusion::prelude::Expr],
+        inputs: &[DfLogicalPlan],
+    ) -> Self {
+        Self {
+            source: inputs[0].clone(),
+            ..self.clone()
+        }
+    }
+}
+
+impl ExtensionNode for Merge {
+    const EXTENSION_NAME: &'static str = "Merge";
+}
@@ -1,11 +1,16 @@
 mod alter_database;
 mod alter_table;
 mod alter_tunnel_rotate_keys;
+mod comment_on;
+mod copy_from;
+mod copy_stdio;
 mod copy_to;
 mod create_credentials;
 mod create_external_database;
 mod create_external_table;
+mod create_role;
 mod create_schema;
+mod create_sequence;
 mod create_table;
 mod create_temp_table;
 mod create_tunnel;
@@ -14,14 +19,24 @@ mod delete;
 mod describe_table;
 mod drop_credentials;
 mod drop_database;
+mod drop_role;
 mod drop_schemas;
+mod drop_sequences;
 mod drop_tables;
 mod drop_tunnel;
 mod drop_views;
+mod grant_privileges;
 mod insert;
+mod merge;
+mod optimize_table;
+mod refresh_materialized_view;
+mod restore_table;
+mod revoke_privileges;
 mod set_variable;
 mod show_variable;
 mod update;
+mod upsert;
+mod vacuum_table;
 
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -31,20 +46,28 @@ use std::sync::Arc;
 pub use alter_database::*;
 pub use alter_table::*;
 pub use alter_tunnel_rotate_keys::*;
+pub use comment_on::*;
+pub use copy_from::*;
+pub use copy_stdio::*;
 pub use copy_to::*;
 pub use create_credentials::*;
 pub use create_external_database::*;
 pub use create_external_table::*;
+pub use create_role::*;
 pub use create_schema::*;
+pub use create_sequence::*;
 pub use create_table::*;
 pub use create_temp_table::*;
 pub use create_tunnel::*;
 pub use create_view::*;
 use datafusion::arrow::datatypes::{DataType, Schema as ArrowSchema};
-use datafusion::common::{DFField, DFSchema, DFSchemaRef, ParamValues};
+use datafusion::common::tree_node::{Transformed, TreeNode, VisitRecursion};
+use datafusion::common::{DFField, DFSchema, DFSchemaRef, DataFusionError, ParamValues};
+use datafusion::logical_expr::expr::Placeholder;
 use datafusion::logical_expr::{
     Explain,
     Expr,
+    Extension,
     LogicalPlan as DfLogicalPlan,
     UserDefinedLogicalNodeCore,
 };
@@ -54,12 +77,17 @@ pub use delete::*;
 pub use describe_table::*;
 pub use drop_credentials::*;
 pub use drop_database::*;
+pub use drop_role::*;
 pub use drop_schemas::*;
+pub use drop_sequences::*;
 pub use drop_tables::*;
 pub use drop_tunnel::*;
 pub use drop_views::*;
+pub use grant_privileges::*;
 pub use insert::*;
+pub use merge::*;
 use once_cell::sync::Lazy;
+pub use optimize_table::*;
 use parser::sqlparser::ast;
 use protogen::metastore::types::options::{
     CopyToDestinationOptions,
@@ -68,9 +96,14 @@ use protogen::metastore::types::options::{
     DatabaseOptions,
     TunnelOptions,
 };
+pub use refresh_materialized_view::*;
+pub use restore_table::*;
+pub use revoke_privileges::*;
 pub use set_variable::*;
 pub use show_variable::*;
 pub use update::*;
+pub use upsert::*;
+pub use vacuum_table::*;
 
 use super::physical_plan::{
     GENERIC_OPERATION_AND_COUNT_PHYSICAL_SCHEMA,
@@ -126,6 +159,8 @@ pub enum LogicalPlan {
     Datafusion(DfLogicalPlan),
     /// Plans related to transaction management.
     Transaction(TransactionPlan),
+    /// Plans for COPY to and from the client.
+    CopyStdio(CopyStdioPlan),
     Noop,
 }
 
@@ -157,7 +192,17 @@ impl LogicalPlan {
     /// later.
     pub fn get_parameter_types(&self) -> Result<HashMap<String, Option<DataType>>> {
         Ok(match self {
-            LogicalPlan::Datafusion(plan) => plan.get_parameter_types()?,
+            LogicalPlan::Datafusion(plan) => {
+                let mut types = plan.get_parameter_types()?;
+                // Datafusion only looks at the expressions an extension
+                // exposes, which updates and deletes don't.
+                if let DfLogicalPlan::Extension(ext) = plan {
+                    for expr in dml_expressions(ext) {
+                        collect_placeholder_types(expr, &mut types)?;
+                    }
+                }
+                types
+            }
             _ => HashMap::new(),
         })
     }
@@ -166,6 +211,28 @@ impl LogicalPlan {
     ///
     /// Note this currently only replaces placeholders for datafusion plans.
     pub fn replace_placeholders(&mut self, scalars: Vec<ScalarValue>) -> Result<()> {
+        if let LogicalPlan::Datafusion(DfLogicalPlan::Extension(ext)) = self {
+            if let Some(update) = ext.node.as_any().downcast_ref::<Update>() {
+                let mut update = update.clone();
+                for (_, expr) in update.updates.iter_mut() {
+                    *expr = replace_placeholder_values(expr.clone(), &scalars)?;
+                }
+                if let Some(expr) = update.where_expr.take() {
+                    update.where_expr = Some(replace_placeholder_values(expr, &scalars)?);
+                }
+                *self = update.into_logical_plan();
+                return Ok(());
+            }
+            if let Some(delete) = ext.node.as_any().downcast_ref::<Delete>() {
+                let mut delete = delete.clone();
+                if let Some(expr) = delete.where_expr.take() {
+                    delete.where_expr = Some(replace_placeholder_values(expr, &scalars)?);
+                }
+                *self = delete.into_logical_plan();
+                return Ok(());
+            }
+        }
+
         let param_values = ParamValues::List(scalars);
 
         if let LogicalPlan::Datafusion(plan) = self {
@@ -195,6 +262,63 @@ impl LogicalPlan {
     }
 }
 
+/// Get the expressions of update and delete extensions.
+fn dml_expressions(ext: &Extension) -> Vec<&Expr> {
+    if let Some(update) = ext.node.as_any().downcast_ref::<Update>() {
+        update
+            .updates
+            .iter()
+            .map(|(_, expr)| expr)
+            .chain(update.where_expr.as_ref())
+            .collect()
+    } else if let Some(delete) = ext.node.as_any().downcast_ref::<Delete>() {
+        delete.where_expr.iter().collect()
+    } else {
+        Vec::new()
+    }
+}
+
+/// Collect the types of all placeholders in an expression.
+fn collect_placeholder_types(
+    expr: &Expr,
+    types: &mut HashMap<String, Option<DataType>>,
+) -> Result<()> {
+    expr.apply(&mut |expr| {
+        if let Expr::Placeholder(Placeholder { id, data_type }) = expr {
+            let typ = types.entry(id.clone()).or_insert(None);
+            if typ.is_none() {
+                *typ = data_type.clone();
+            }
+        }
+        Ok(VisitRecursion::Continue)
+    })?;
+    Ok(())
+}
+
+/// Replace all placeholders in an expression with their values.
+fn replace_placeholder_values(expr: Expr, values: &[ScalarValue]) -> Result<Expr> {
+    let expr = expr.transform(&|expr| {
+        if let Expr::Placeholder(Placeholder { id, data_type }) = &expr {
+            let value = id
+                .strip_prefix('$')
+                .and_then(|idx| idx.parse::<usize>().ok())
+                .and_then(|idx| idx.checked_sub(1))
+                .and_then(|idx| values.get(idx))
+                .ok_or_else(|| {
+                    DataFusionError::Plan(format!("No value found for placeholder with id {id}"))
+                })?;
+            let value = match data_type {
+                Some(typ) if *typ != value.data_type() => value.cast_to(typ)?,
+                _ => value.clone(),
+            };
+            return Ok(Transformed::Yes(Expr::Literal(value)));
+        }
+        Ok(Transformed::No(expr))
+    })?;
+
+    Ok(expr)
+}
+
 impl From<DfLogicalPlan> for LogicalPlan {
     fn from(plan: DfLogicalPlan) -> Self {
         LogicalPlan::Datafusion(plan)
@@ -291,3 +415,9 @@ impl From<TransactionPlan> for LogicalPlan {
         LogicalPlan::Transaction(plan)
     }
 }
+
+impl From<CopyStdioPlan> for LogicalPlan {
+    fn from(plan: CopyStdioPlan) -> Self {
+        LogicalPlan::CopyStdio(plan)
+    }
+}
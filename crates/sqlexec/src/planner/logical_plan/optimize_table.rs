@@ -0,0 +1,53 @@
+use protogen::metastore::types::catalog::TableEntry;
+
+use super::{
+    DfLogicalPlan,
+    ExtensionNode,
+    UserDefinedLogicalNodeCore,
+    GENERIC_OPERATION_LOGICAL_SCHEMA,
+};
+
+/// Compact the files of a native table, optionally Z-ordering them.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct OptimizeTable {
+    pub table: TableEntry,
+    pub zorder_by: Vec<String>,
+}
+
+impl UserDefinedLogicalNodeCore for OptimizeTable {
+    fn name(&self) -> &str {
+        Self::EXTENSION_NAME
+    }
+
+    fn inputs(&self) -> Vec<&DfLogicalPlan> {
+        vec![]
+    }
+
+    fn schema(&self) -> &datafusion::common::DFSchemaRef {
+        &GENERIC_OPERATION_LOGICAL_SCHEMA
+    }
+
+    fn expressions(&self) -> Vec<datafusion::prelude::Expr> {
+        vec![]
+    }
+
+    fn fmt_for_explain(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", Self::EXTENSION_NAME)?;
+        if !self.zorder_by.is_empty() {
+            write!(f, " zorder_by=[{}]", self.zorder_by.join(", "))?;
+        }
+        Ok(())
+    }
+
+    fn from_template(
+        &self,
+        _exprs: &[datafusion::prelude::Expr],
+        _inputs: &[DfLogicalPlan],
+    ) -> Self {
+        self.clone()
+    }
+}
+
+impl ExtensionNode for OptimizeTable {
+    const EXTENSION_NAME: &'static str = "OptimizeTable";
+}
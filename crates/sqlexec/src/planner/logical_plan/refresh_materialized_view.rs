@@ -0,0 +1,56 @@
+use protogen::metastore::types::catalog::TableEntry;
+
+use super::{
+    DfLogicalPlan,
+    ExtensionNode,
+    UserDefinedLogicalNodeCore,
+    GENERIC_OPERATION_LOGICAL_SCHEMA,
+};
+
+/// Replace the contents of a materialized view with the current results of
+/// its query.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct RefreshMaterializedView {
+    /// Schema containing the view.
+    pub schema: String,
+    pub table: TableEntry,
+    /// The view's query, producing the columns of the table.
+    pub source: DfLogicalPlan,
+}
+
+impl UserDefinedLogicalNodeCore for RefreshMaterializedView {
+    fn name(&self) -> &str {
+        Self::EXTENSION_NAME
+    }
+
+    fn inputs(&self) -> Vec<&DfLogicalPlan> {
+        vec![&self.source]
+    }
+
+    fn schema(&self) -> &datafusion::common::DFSchemaRef {
+        &GENERIC_OPERATION_LOGICAL_SCHEMA
+    }
+
+    fn expressions(&self) -> Vec<datafusion::prelude::Expr> {
+        Vec::new()
+    }
+
+    fn fmt_for_explain(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}: {}", Self::EXTENSION_NAME, self.table.meta.name)
+    }
+
+    fn from_template(
+        &self,
+        _exprs: &[datafusion::prelude::Expr],
+        inputs: &[DfLogicalPlan],
+    ) -> Self {
+        Self {
+            source: inputs[0].clone(),
+            ..self.clone()
+        }
+    }
+}
+
+impl ExtensionNode for RefreshMaterializedView {
+    const EXTENSION_NAME: &'static str = "RefreshMaterializedView";
+}
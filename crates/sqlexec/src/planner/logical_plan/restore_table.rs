@@ -0,0 +1,50 @@
+use datasources::lake::delta::version::TableVersion;
+use protogen::metastore::types::catalog::TableEntry;
+
+use super::{
+    DfLogicalPlan,
+    ExtensionNode,
+    UserDefinedLogicalNodeCore,
+    GENERIC_OPERATION_LOGICAL_SCHEMA,
+};
+
+/// Restore a native table to a previous version.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct RestoreTable {
+    pub table: TableEntry,
+    pub version: TableVersion,
+}
+
+impl UserDefinedLogicalNodeCore for RestoreTable {
+    fn name(&self) -> &str {
+        Self::EXTENSION_NAME
+    }
+
+    fn inputs(&self) -> Vec<&DfLogicalPlan> {
+        vec![]
+    }
+
+    fn schema(&self) -> &datafusion::common::DFSchemaRef {
+        &GENERIC_OPERATION_LOGICAL_SCHEMA
+    }
+
+    fn expressions(&self) -> Vec<datafusion::prelude::Expr> {
+        vec![]
+    }
+
+    fn fmt_for_explain(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} to {}", Self::EXTENSION_NAME, self.version)
+    }
+
+    fn from_template(
+        &self,
+        _exprs: &[datafusion::prelude::Expr],
+        _inputs: &[DfLogicalPlan],
+    ) -> Self {
+        self.clone()
+    }
+}
+
+impl ExtensionNode for RestoreTable {
+    const EXTENSION_NAME: &'static str = "RestoreTable";
+}
@@ -7,12 +7,15 @@ use super::{
     UserDefinedLogicalNodeCore,
     GENERIC_OPERATION_AND_COUNT_LOGICAL_SCHEMA,
 };
+use crate::planner::physical_plan::constraint_check::RowConstraint;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Update {
     pub table: TableEntry,
     pub updates: Vec<(String, Expr)>,
     pub where_expr: Option<Expr>,
+    /// Constraints the updated rows must satisfy.
+    pub constraints: Vec<RowConstraint>,
 }
 
 impl UserDefinedLogicalNodeCore for Update {
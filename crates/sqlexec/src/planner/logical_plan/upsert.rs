@@ -0,0 +1,63 @@
+use protogen::metastore::types::catalog::TableEntry;
+
+use super::{
+    DfLogicalPlan,
+    Expr,
+    ExtensionNode,
+    UserDefinedLogicalNodeCore,
+    GENERIC_OPERATION_AND_COUNT_LOGICAL_SCHEMA,
+};
+use crate::planner::physical_plan::constraint_check::RowConstraint;
+
+/// An `INSERT ... ON CONFLICT` into a native table.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Upsert {
+    pub table: TableEntry,
+    pub source: DfLogicalPlan,
+    /// Columns of the unique constraint used to detect conflicting rows.
+    pub conflict_columns: Vec<String>,
+    /// Updates applied to existing rows that conflict with a proposed row.
+    /// Empty for `DO NOTHING`.
+    pub updates: Vec<(String, Expr)>,
+    pub update_predicate: Option<Expr>,
+    /// Constraints checked for each proposed row. The source produces a
+    /// boolean column for each constraint following the table's columns.
+    pub constraints: Vec<RowConstraint>,
+}
+
+impl UserDefinedLogicalNodeCore for Upsert {
+    fn name(&self) -> &str {
+        Self::EXTENSION_NAME
+    }
+
+    fn inputs(&self) -> Vec<&DfLogicalPlan> {
+        vec![&self.source]
+    }
+
+    fn schema(&self) -> &datafusion::common::DFSchemaRef {
+        &GENERIC_OPERATION_AND_COUNT_LOGICAL_SCHEMA
+    }
+
+    fn expressions(&self) -> Vec<datafusion::prelude::Expr> {
+        Vec::new()
+    }
+
+    fn fmt_for_explain(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", Self::EXTENSION_NAME)
+    }
+
+    fn from_template(
+        &self,
+        _exprs: &[datafusion::prelude::Expr],
+        inputs: &[DfLogicalPlan],
+    ) -> Self {
+        Self {
+            source: inputs[0].clone(),
+            ..self.clone()
+        }
+    }
+}
+
+impl ExtensionNode for Upsert {
+    const EXTENSION_NAME: &'static str = "Upsert";
+}
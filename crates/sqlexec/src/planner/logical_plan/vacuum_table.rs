@@ -0,0 +1,55 @@
+use protogen::metastore::types::catalog::TableEntry;
+
+use super::{
+    DfLogicalPlan,
+    ExtensionNode,
+    UserDefinedLogicalNodeCore,
+    GENERIC_OPERATION_LOGICAL_SCHEMA,
+};
+
+/// Delete files no longer part of a native table.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct VacuumTable {
+    pub table: TableEntry,
+    pub retain_hours: Option<u64>,
+    /// Reject a retention period shorter than the table's.
+    pub enforce_retention: bool,
+}
+
+impl UserDefinedLogicalNodeCore for VacuumTable {
+    fn name(&self) -> &str {
+        Self::EXTENSION_NAME
+    }
+
+    fn inputs(&self) -> Vec<&DfLogicalPlan> {
+        vec![]
+    }
+
+    fn schema(&self) -> &datafusion::common::DFSchemaRef {
+        &GENERIC_OPERATION_LOGICAL_SCHEMA
+    }
+
+    fn expressions(&self) -> Vec<datafusion::prelude::Expr> {
+        vec![]
+    }
+
+    fn fmt_for_explain(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", Self::EXTENSION_NAME)?;
+        if let Some(hours) = self.retain_hours {
+            write!(f, " retain={hours}h")?;
+        }
+        Ok(())
+    }
+
+    fn from_template(
+        &self,
+        _exprs: &[datafusion::prelude::Expr],
+        _inputs: &[DfLogicalPlan],
+    ) -> Self {
+        self.clone()
+    }
+}
+
+impl ExtensionNode for VacuumTable {
+    const EXTENSION_NAME: &'static str = "VacuumTable";
+}
@@ -6,6 +6,7 @@ use catalog::mutator::CatalogMutator;
 use datafusion::arrow::datatypes::Schema;
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::context::SessionState;
 use datafusion::execution::TaskContext;
 use datafusion::physical_expr::PhysicalSortExpr;
 use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
@@ -17,7 +18,11 @@ use datafusion::physical_plan::{
     SendableRecordBatchStream,
     Statistics,
 };
+use datafusion::prelude::Expr;
+use datasources::native::access::NativeTableStorage;
 use futures::stream;
+use protogen::metastore::types::catalog::TableEntry;
+use protogen::metastore::types::options::InternalColumnDefinition;
 use protogen::metastore::types::service::{self, AlterTableOperation, Mutation};
 
 use super::{new_operation_batch, GENERIC_OPERATION_PHYSICAL_SCHEMA};
@@ -28,6 +33,9 @@ pub struct AlterTableExec {
     pub schema: String,
     pub name: String,
     pub operation: AlterTableOperation,
+    pub table: Option<TableEntry>,
+    pub columns: Vec<InternalColumnDefinition>,
+    pub rewrite_exprs: Vec<Expr>,
 }
 
 impl ExecutionPlan for AlterTableExec {
@@ -59,7 +67,7 @@ impl ExecutionPlan for AlterTableExec {
             Ok(self)
         } else {
             Err(DataFusionError::Plan(
-                "Cannot change children for AlterTableRenameExec".to_string(),
+                "Cannot change children for AlterTableExec".to_string(),
             ))
         }
     }
@@ -80,7 +88,17 @@ impl ExecutionPlan for AlterTableExec {
             .get_extension::<CatalogMutator>()
             .expect("context should have catalog mutator");
 
-        let stream = stream::once(alter_table_rename(mutator, self.clone()));
+        let storage = context
+            .session_config()
+            .get_extension::<NativeTableStorage>()
+            .expect("context should have native table storage");
+
+        let state = SessionState::new_with_config_rt(
+            context.session_config().clone(),
+            context.runtime_env(),
+        );
+
+        let stream = stream::once(alter_table(mutator, storage, state, self.clone()));
 
         Ok(Box::pin(RecordBatchStreamAdapter::new(
             self.schema(),
@@ -99,22 +117,69 @@ impl DisplayAs for AlterTableExec {
     }
 }
 
-async fn alter_table_rename(
+async fn alter_table(
     mutator: Arc<CatalogMutator>,
+    storage: Arc<NativeTableStorage>,
+    state: SessionState,
     plan: AlterTableExec,
 ) -> DataFusionResult<RecordBatch> {
+    // Column changes need the delta table schema (and possibly its data)
+    // updated in addition to the catalog entry.
+    let change = match &plan.table {
+        Some(table) => Some(
+            storage
+                .stage_schema_change(&state, table, &plan.columns, plan.rewrite_exprs)
+                .await
+                .map_err(|e| DataFusionError::Execution(format!("failed to alter table: {e}")))?,
+        ),
+        None => None,
+    };
+
+    let mutation = Mutation::AlterTable(service::AlterTable {
+        schema: plan.schema,
+        name: plan.name,
+        operation: plan.operation,
+    });
+
     // TODO: Error if schemas between references differ.
-    mutator
-        .mutate_and_commit(
-            plan.catalog_version,
-            [Mutation::AlterTable(service::AlterTable {
-                schema: plan.schema,
-                name: plan.name,
-                operation: plan.operation,
-            })],
-        )
+    //
+    // The catalog change is validated before committing anything to the
+    // table's storage, which can't be undone. Inside of a transaction, both
+    // are staged and committed with the rest of the transaction.
+    let mutated = match mutator
+        .mutate(plan.catalog_version, [mutation.clone()])
         .await
-        .map_err(|e| DataFusionError::Execution(format!("failed to alter table: {e}")))?;
+    {
+        Ok(state) => state,
+        Err(e) => {
+            if let Some(change) = change {
+                change.abort().await;
+            }
+            return Err(DataFusionError::Execution(format!(
+                "failed to alter table: {e}"
+            )));
+        }
+    };
+
+    let committed = match change {
+        Some(change) if !mutator.in_transaction() => {
+            change
+                .commit()
+                .await
+                .map_err(|e| DataFusionError::Execution(format!("failed to alter table: {e}")))?;
+
+            // As when committing a transaction, the catalog follows the
+            // table's storage, even if other sessions changed the catalog
+            // since the change was validated.
+            mutator.commit_on_latest(vec![mutation]).await
+        }
+        _ => {
+            mutator
+                .commit_state(plan.catalog_version, mutated.as_ref().clone())
+                .await
+        }
+    };
+    committed.map_err(|e| DataFusionError::Execution(format!("failed to alter table: {e}")))?;
 
     Ok(new_operation_batch("alter_table"))
 }
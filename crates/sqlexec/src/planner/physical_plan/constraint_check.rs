@@ -0,0 +1,225 @@
+use std::any::Any;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use datafusion::arrow::array::{Array, AsArray};
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::TaskContext;
+use datafusion::logical_expr::ident;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayAs,
+    DisplayFormatType,
+    ExecutionPlan,
+    Partitioning,
+    SendableRecordBatchStream,
+    Statistics,
+};
+use datafusion::prelude::Expr;
+use futures::StreamExt;
+
+use crate::errors::ExecError;
+
+/// Prefix for the names of columns holding the results of evaluating
+/// constraints on rows being inserted.
+pub const CONSTRAINT_COLUMN_PREFIX: &str = "__glaredb_constraint";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RowConstraintKind {
+    NotNull,
+    Check,
+}
+
+/// A constraint that every row written to a native table must satisfy.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RowConstraint {
+    pub kind: RowConstraintKind,
+    /// Name of the column for NOT NULL constraints, or the name of the
+    /// constraint for CHECK constraints.
+    pub name: String,
+    /// Boolean expression over the table's columns. A row violates the
+    /// constraint only if this evaluates to false.
+    pub expr: Expr,
+}
+
+impl RowConstraint {
+    pub fn not_null(column: impl Into<String>) -> Self {
+        let column = column.into();
+        RowConstraint {
+            kind: RowConstraintKind::NotNull,
+            expr: ident(&column).is_not_null(),
+            name: column,
+        }
+    }
+
+    pub fn check(name: impl Into<String>, expr: Expr) -> Self {
+        RowConstraint {
+            kind: RowConstraintKind::Check,
+            name: name.into(),
+            expr,
+        }
+    }
+
+    /// Name of the column holding the result of this constraint when
+    /// projected alongside the table's columns.
+    pub fn column_name(idx: usize) -> String {
+        format!("{CONSTRAINT_COLUMN_PREFIX}_{idx}")
+    }
+
+    /// The error returned for rows violating this constraint.
+    pub fn violation(&self) -> ExecError {
+        match self.kind {
+            RowConstraintKind::NotNull => ExecError::NotNullViolation {
+                column: self.name.clone(),
+            },
+            RowConstraintKind::Check => ExecError::CheckViolation {
+                constraint: self.name.clone(),
+            },
+        }
+    }
+
+    /// Check a column holding the results of evaluating this constraint.
+    pub fn check_results(&self, results: &dyn Array) -> Result<(), ExecError> {
+        let results = results.as_boolean_opt().ok_or_else(|| {
+            ExecError::Internal(format!(
+                "expected boolean results for constraint '{}', got {}",
+                self.name,
+                results.data_type()
+            ))
+        })?;
+        if results.false_count() > 0 {
+            return Err(self.violation());
+        }
+        Ok(())
+    }
+}
+
+/// Checks rows against constraints before they're written to a table.
+///
+/// The input contains the table's columns followed by one boolean column per
+/// constraint holding the result of evaluating that constraint for each row.
+/// The constraint columns are removed from the output.
+#[derive(Debug, Clone)]
+pub struct ConstraintCheckExec {
+    input: Arc<dyn ExecutionPlan>,
+    constraints: Vec<RowConstraint>,
+    schema: SchemaRef,
+    /// First violation encountered during execution.
+    ///
+    /// The error returned from the stream may be stringified by the table
+    /// being written to, so this keeps the original around.
+    violation: Arc<Mutex<Option<ExecError>>>,
+}
+
+impl ConstraintCheckExec {
+    pub fn try_new(
+        input: Arc<dyn ExecutionPlan>,
+        constraints: Vec<RowConstraint>,
+    ) -> DataFusionResult<Self> {
+        let input_schema = input.schema();
+        let num_fields = input_schema
+            .fields()
+            .len()
+            .checked_sub(constraints.len())
+            .ok_or_else(|| {
+                DataFusionError::Internal(
+                    "missing constraint columns in input to ConstraintCheckExec".to_string(),
+                )
+            })?;
+        let schema = Arc::new(input_schema.project(&(0..num_fields).collect::<Vec<_>>())?);
+
+        Ok(ConstraintCheckExec {
+            input,
+            constraints,
+            schema,
+            violation: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// Take the constraint violation encountered during execution, if any.
+    pub fn take_violation(&self) -> Option<ExecError> {
+        self.violation.lock().unwrap().take()
+    }
+
+    fn check_batch(&self, batch: RecordBatch) -> DataFusionResult<RecordBatch> {
+        let num_fields = self.schema.fields().len();
+        for (idx, constraint) in self.constraints.iter().enumerate() {
+            if let Err(e) = constraint.check_results(batch.column(num_fields + idx)) {
+                let msg = e.to_string();
+                self.violation.lock().unwrap().get_or_insert(e);
+                return Err(DataFusionError::Execution(msg));
+            }
+        }
+        Ok(batch.project(&(0..num_fields).collect::<Vec<_>>())?)
+    }
+}
+
+impl ExecutionPlan for ConstraintCheckExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        if children.len() != 1 {
+            return Err(DataFusionError::Plan(
+                "ConstraintCheckExec expects exactly one child".to_string(),
+            ));
+        }
+        Ok(Arc::new(ConstraintCheckExec {
+            input: children[0].clone(),
+            constraints: self.constraints.clone(),
+            schema: self.schema.clone(),
+            violation: self.violation.clone(),
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        let this = self.clone();
+        let stream = self
+            .input
+            .execute(partition, context)?
+            .map(move |batch| this.check_batch(batch?));
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+
+    fn statistics(&self) -> DataFusionResult<Statistics> {
+        Ok(Statistics::new_unknown(self.schema().as_ref()))
+    }
+}
+
+impl DisplayAs for ConstraintCheckExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        let names: Vec<_> = self.constraints.iter().map(|c| c.name.as_str()).collect();
+        write!(f, "ConstraintCheckExec: constraints=[{}]", names.join(", "))
+    }
+}
@@ -0,0 +1,465 @@
+use std::any::Any;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use datafusion::arrow::array::{new_null_array, Array, ArrayRef, BooleanArray};
+use datafusion::arrow::compute::{cast_with_options, filter_record_batch, CastOptions};
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::arrow::util::display::array_value_to_string;
+use datafusion::common::Column;
+use datafusion::datasource::provider_as_source;
+use datafusion::datasource::streaming::StreamingTable;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::context::SessionState;
+use datafusion::execution::TaskContext;
+use datafusion::logical_expr::{cast, LogicalPlanBuilder};
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::streaming::PartitionStream;
+use datafusion::physical_plan::{
+    DisplayAs,
+    DisplayFormatType,
+    ExecutionPlan,
+    Partitioning,
+    SendableRecordBatchStream,
+    Statistics,
+};
+use datafusion::prelude::Expr;
+use datafusion_ext::metrics::WriteOnlyDataSourceMetricsExecAdapter;
+use futures::{stream, StreamExt};
+
+use super::constraint_check::RowConstraint;
+use super::insert::InsertExec;
+use super::remote_scan::ProviderReference;
+use super::{
+    metrics_adapter,
+    new_operation_with_count_batch,
+    GENERIC_OPERATION_AND_COUNT_PHYSICAL_SCHEMA,
+};
+
+#[derive(Debug, Clone)]
+pub struct CopyFromExec {
+    pub provider: ProviderReference,
+    pub source: Arc<WriteOnlyDataSourceMetricsExecAdapter>,
+    pub column_mapping: Vec<Option<usize>>,
+    /// For each column in the table, the default for the column if it's not
+    /// copied from the source.
+    pub column_defaults: Vec<Option<Expr>>,
+    /// Constraints checked for each row copied into the table.
+    pub constraints: Vec<RowConstraint>,
+    pub max_errors: u64,
+}
+
+impl ExecutionPlan for CopyFromExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> Arc<Schema> {
+        GENERIC_OPERATION_AND_COUNT_PHYSICAL_SCHEMA.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.source.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        if children.len() != 1 {
+            return Err(DataFusionError::Plan(
+                "CopyFromExec wrong number of children".to_string(),
+            ));
+        }
+        Ok(Arc::new(CopyFromExec {
+            provider: self.provider.clone(),
+            source: metrics_adapter(children.first().unwrap().clone()),
+            column_mapping: self.column_mapping.clone(),
+            column_defaults: self.column_defaults.clone(),
+            constraints: self.constraints.clone(),
+            max_errors: self.max_errors,
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Execution(
+                "CopyFromExec only supports 1 partition".to_string(),
+            ));
+        }
+
+        let this = self.clone();
+        let stream = stream::once(async move { this.copy_from(context).await });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+
+    fn statistics(&self) -> DataFusionResult<Statistics> {
+        Ok(Statistics::new_unknown(self.schema().as_ref()))
+    }
+}
+
+impl DisplayAs for CopyFromExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CopyFromExec")
+    }
+}
+
+impl CopyFromExec {
+    async fn copy_from(&self, context: Arc<TaskContext>) -> DataFusionResult<RecordBatch> {
+        let table = match &self.provider {
+            ProviderReference::Provider(provider) => provider.clone(),
+            ProviderReference::RemoteReference(_) => {
+                return Err(DataFusionError::Internal(
+                    "required table provider, found remote reference to copy into".to_string(),
+                ))
+            }
+        };
+
+        let source = Arc::new(CastToTableExec {
+            schema: table.schema(),
+            source: self.source.clone(),
+            column_mapping: self.column_mapping.clone(),
+            max_errors: self.max_errors,
+            errors: Arc::new(AtomicU64::new(0)),
+        });
+        let source = self.plan_table_rows(source, &context).await?;
+
+        let count =
+            InsertExec::insert_checked_rows(table, source, self.constraints.clone(), context)
+                .await?;
+
+        Ok(new_operation_with_count_batch("copy", count))
+    }
+
+    /// Fill in defaults for columns not copied from the source, and project
+    /// the result of each constraint after the table's columns, the same as
+    /// the source of an INSERT.
+    async fn plan_table_rows(
+        &self,
+        source: Arc<dyn ExecutionPlan>,
+        context: &TaskContext,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        let has_defaults = self
+            .column_mapping
+            .iter()
+            .zip(&self.column_defaults)
+            .any(|(mapping, default)| mapping.is_none() && default.is_some());
+        if !has_defaults && self.constraints.is_empty() {
+            return Ok(source);
+        }
+
+        let schema = source.schema();
+        let partitions = (0..source.output_partitioning().partition_count())
+            .map(|partition| {
+                Arc::new(PlanPartition {
+                    schema: schema.clone(),
+                    plan: source.clone(),
+                    partition,
+                }) as Arc<dyn PartitionStream>
+            })
+            .collect();
+        let rows = StreamingTable::try_new(schema.clone(), partitions)?;
+
+        let columns: Vec<_> = schema
+            .fields()
+            .iter()
+            .map(|field| Expr::Column(Column::from_name(field.name())))
+            .collect();
+
+        let filled = columns
+            .iter()
+            .zip(schema.fields())
+            .zip(self.column_mapping.iter().zip(&self.column_defaults))
+            .map(|((column, field), mapping)| match mapping {
+                (None, Some(default)) => {
+                    cast(default.clone(), field.data_type().clone()).alias(field.name())
+                }
+                _ => column.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut builder =
+            LogicalPlanBuilder::scan("copy_source", provider_as_source(Arc::new(rows)), None)?
+                .project(filled)?;
+        if !self.constraints.is_empty() {
+            let mut exprs = columns;
+            for (idx, constraint) in self.constraints.iter().enumerate() {
+                exprs.push(
+                    constraint
+                        .expr
+                        .clone()
+                        .alias(RowConstraint::column_name(idx)),
+                );
+            }
+            builder = builder.project(exprs)?;
+        }
+
+        let state = SessionState::new_with_config_rt(
+            context.session_config().clone(),
+            context.runtime_env(),
+        );
+        state.create_physical_plan(&builder.build()?).await
+    }
+}
+
+/// A single partition of an execution plan, used for building a logical plan
+/// on top of rows being copied into a table.
+struct PlanPartition {
+    schema: SchemaRef,
+    plan: Arc<dyn ExecutionPlan>,
+    partition: usize,
+}
+
+impl PartitionStream for PlanPartition {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        match self.plan.execute(self.partition, ctx) {
+            Ok(stream) => stream,
+            Err(e) => Box::pin(RecordBatchStreamAdapter::new(
+                self.schema.clone(),
+                stream::once(async move { Err(e) }),
+            )),
+        }
+    }
+}
+
+/// Converts batches from the source files into the table's schema.
+///
+/// Rows containing values that can't be converted to the table's types are
+/// skipped, erroring once more than `max_errors` rows have been skipped.
+///
+/// Parse errors from the source (e.g. a CSV line with the wrong number of
+/// fields) are returned by the source plan itself and aren't counted here, so
+/// they always fail the copy.
+#[derive(Debug)]
+struct CastToTableExec {
+    schema: SchemaRef,
+    source: Arc<dyn ExecutionPlan>,
+    column_mapping: Vec<Option<usize>>,
+    max_errors: u64,
+    /// Number of rows skipped so far, across all partitions.
+    errors: Arc<AtomicU64>,
+}
+
+impl ExecutionPlan for CastToTableExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.source.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.source.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        if children.len() != 1 {
+            return Err(DataFusionError::Plan(
+                "CastToTableExec wrong number of children".to_string(),
+            ));
+        }
+        Ok(Arc::new(CastToTableExec {
+            schema: self.schema.clone(),
+            source: children.first().unwrap().clone(),
+            column_mapping: self.column_mapping.clone(),
+            max_errors: self.max_errors,
+            errors: self.errors.clone(),
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        let schema = self.schema.clone();
+        let column_mapping = self.column_mapping.clone();
+        let max_errors = self.max_errors;
+        let errors = self.errors.clone();
+
+        let stream = self.source.execute(partition, context)?.map(move |batch| {
+            let (batch, failure) = cast_batch(&batch?, &schema, &column_mapping)?;
+            if let Some(failure) = failure {
+                let total = errors.fetch_add(failure.rows, Ordering::Relaxed) + failure.rows;
+                if total > max_errors {
+                    return Err(DataFusionError::Execution(format!(
+                        "COPY FROM failed after skipping {total} rows (max_errors = {max_errors}): {}",
+                        failure.message
+                    )));
+                }
+            }
+            Ok(batch)
+        });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            stream,
+        )))
+    }
+
+    fn statistics(&self) -> DataFusionResult<Statistics> {
+        Ok(Statistics::new_unknown(self.schema().as_ref()))
+    }
+}
+
+impl DisplayAs for CastToTableExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CastToTableExec")
+    }
+}
+
+/// Rows in a batch that failed to convert.
+#[derive(Debug)]
+struct CastFailure {
+    /// Number of rows that failed.
+    rows: u64,
+    /// Description of the first failure.
+    message: String,
+}
+
+/// Cast a batch from the source files into the table's schema, filtering out
+/// rows with values that couldn't be converted.
+fn cast_batch(
+    batch: &RecordBatch,
+    schema: &SchemaRef,
+    column_mapping: &[Option<usize>],
+) -> DataFusionResult<(RecordBatch, Option<CastFailure>)> {
+    let num_rows = batch.num_rows();
+    let mut failed = vec![false; num_rows];
+    let mut message = None;
+
+    let options = CastOptions {
+        safe: true,
+        ..Default::default()
+    };
+
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
+    for (field, source_idx) in schema.fields().iter().zip(column_mapping) {
+        let source_idx = match source_idx {
+            Some(idx) => *idx,
+            None => {
+                columns.push(new_null_array(field.data_type(), num_rows));
+                continue;
+            }
+        };
+
+        let source = batch.column(source_idx);
+        if source.data_type() == field.data_type() {
+            columns.push(source.clone());
+            continue;
+        }
+
+        // Safe casting produces nulls for values that can't be converted.
+        let casted = cast_with_options(source, field.data_type(), &options)?;
+        for (row, failed) in failed.iter_mut().enumerate() {
+            if casted.is_null(row) && !source.is_null(row) {
+                *failed = true;
+                if message.is_none() {
+                    message = Some(format!(
+                        "invalid value '{}' for column '{}' of type {}",
+                        array_value_to_string(source, row)?,
+                        field.name(),
+                        field.data_type(),
+                    ));
+                }
+            }
+        }
+        columns.push(casted);
+    }
+
+    let batch = RecordBatch::try_new(schema.clone(), columns)?;
+
+    let failed_rows = failed.iter().filter(|failed| **failed).count();
+    if failed_rows == 0 {
+        return Ok((batch, None));
+    }
+
+    let keep: BooleanArray = failed.into_iter().map(|failed| Some(!failed)).collect();
+    let batch = filter_record_batch(&batch, &keep)?;
+
+    Ok((
+        batch,
+        Some(CastFailure {
+            rows: failed_rows as u64,
+            message: message.unwrap_or_default(),
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::{Int32Array, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field};
+
+    use super::*;
+
+    #[test]
+    fn cast_batch_skips_invalid_rows() {
+        let source = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new("c1", DataType::Utf8, true)])),
+            vec![Arc::new(StringArray::from(vec![
+                Some("1"),
+                Some("two"),
+                None,
+                Some("4"),
+            ]))],
+        )
+        .unwrap();
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+
+        let (batch, failure) = cast_batch(&source, &schema, &[Some(0), None]).unwrap();
+
+        let failure = failure.unwrap();
+        assert_eq!(1, failure.rows);
+        assert!(failure.message.contains("'two'"), "{}", failure.message);
+
+        let expected = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(vec![Some(1), None, Some(4)])),
+                Arc::new(StringArray::from(vec![None::<&str>, None, None])),
+            ],
+        )
+        .unwrap();
+        assert_eq!(expected, batch);
+    }
+}
@@ -0,0 +1,207 @@
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::TaskContext;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayAs,
+    DisplayFormatType,
+    ExecutionPlan,
+    Partitioning,
+    SendableRecordBatchStream,
+    Statistics,
+};
+use futures::stream;
+use parking_lot::Mutex;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::cancel::{CancelableStream, QueryCanceler};
+use crate::errors::{internal, ExecError, Result};
+
+/// Messages sent from the client side of a `COPY .. FROM STDIN`.
+///
+/// `None` indicates the client finished sending rows. The copy fails if the
+/// sending side goes away before that.
+pub type CopyStdinMessage = Option<RecordBatch>;
+
+/// Reads the rows sent by the client for a `COPY .. FROM STDIN`.
+///
+/// Reading the rows stops once the copy is canceled. Nothing has been written
+/// to the table at that point, so the copy fails without any changes.
+#[derive(Debug)]
+pub struct CopyStdinExec {
+    schema: SchemaRef,
+    receiver: Mutex<Option<mpsc::Receiver<CopyStdinMessage>>>,
+    canceler: QueryCanceler,
+}
+
+impl CopyStdinExec {
+    pub fn new(
+        schema: SchemaRef,
+        receiver: mpsc::Receiver<CopyStdinMessage>,
+        canceler: QueryCanceler,
+    ) -> Self {
+        CopyStdinExec {
+            schema,
+            receiver: Mutex::new(Some(receiver)),
+            canceler,
+        }
+    }
+}
+
+impl ExecutionPlan for CopyStdinExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        Vec::new()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        if children.is_empty() {
+            Ok(self)
+        } else {
+            Err(DataFusionError::Plan(
+                "Cannot change children for CopyStdinExec".to_string(),
+            ))
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Execution(
+                "CopyStdinExec only supports 1 partition".to_string(),
+            ));
+        }
+
+        let receiver = self.receiver.lock().take().ok_or_else(|| {
+            DataFusionError::Execution("CopyStdinExec can only be executed once".to_string())
+        })?;
+
+        let stream = stream::unfold(Some(receiver), |receiver| async move {
+            let mut receiver = receiver?;
+            match receiver.recv().await {
+                Some(Some(batch)) => Some((Ok(batch), Some(receiver))),
+                Some(None) => None,
+                None => Some((
+                    Err(DataFusionError::Execution(
+                        "client stopped sending data before COPY FROM STDIN completed".to_string(),
+                    )),
+                    None,
+                )),
+            }
+        });
+
+        let stream = Box::pin(RecordBatchStreamAdapter::new(self.schema.clone(), stream));
+        Ok(Box::pin(CancelableStream::new(
+            stream,
+            self.canceler.clone(),
+        )))
+    }
+
+    fn statistics(&self) -> DataFusionResult<Statistics> {
+        Ok(Statistics::new_unknown(self.schema().as_ref()))
+    }
+}
+
+impl DisplayAs for CopyStdinExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CopyStdinExec")
+    }
+}
+
+/// Sending half of a `COPY .. FROM STDIN`, with the copy into the table
+/// running in the background.
+#[derive(Debug)]
+pub struct CopyStdinSink {
+    sender: mpsc::Sender<CopyStdinMessage>,
+    handle: Option<JoinHandle<DataFusionResult<u64>>>,
+}
+
+impl CopyStdinSink {
+    pub fn new(
+        sender: mpsc::Sender<CopyStdinMessage>,
+        handle: JoinHandle<DataFusionResult<u64>>,
+    ) -> Self {
+        CopyStdinSink {
+            sender,
+            handle: Some(handle),
+        }
+    }
+
+    /// Send a batch of rows to copy into the table.
+    ///
+    /// Errors if the copy has failed, in which case nothing else should be
+    /// sent.
+    pub async fn send(&mut self, batch: RecordBatch) -> Result<()> {
+        if self.sender.send(Some(batch)).await.is_ok() {
+            return Ok(());
+        }
+        // The receiving side only goes away once the copy has stopped.
+        self.wait().await?;
+        Err(internal!(
+            "COPY FROM STDIN completed before all rows were sent"
+        ))
+    }
+
+    /// Signal that the client has sent all rows, returning the number of rows
+    /// copied into the table.
+    pub async fn finish(mut self) -> Result<u64> {
+        let _ = self.sender.send(None).await;
+        self.wait().await
+    }
+
+    /// Abort the copy, discarding all rows that have been sent.
+    pub async fn abort(self) {
+        let CopyStdinSink { sender, handle } = self;
+        drop(sender);
+        if let Some(handle) = handle {
+            let _ = handle.await;
+        }
+    }
+
+    async fn wait(&mut self) -> Result<u64> {
+        let handle = self
+            .handle
+            .take()
+            .ok_or_else(|| internal!("COPY FROM STDIN already completed"))?;
+        match handle.await {
+            Ok(Ok(count)) => Ok(count),
+            // Errors from our own execution plans (e.g. constraint
+            // violations) are wrapped as external errors, unwrap them to
+            // report the original error.
+            Ok(Err(DataFusionError::External(e))) => match e.downcast::<ExecError>() {
+                Ok(e) => Err(*e),
+                Err(e) => Err(DataFusionError::External(e).into()),
+            },
+            Ok(Err(e)) => Err(e.into()),
+            Err(e) => Err(internal!("COPY FROM STDIN failed: {e}")),
+        }
+    }
+}
@@ -24,9 +24,11 @@ use datafusion::physical_plan::{
 use datasources::common::sink::bson::BsonSink;
 use datasources::common::sink::csv::{CsvSink, CsvSinkOpts};
 use datasources::common::sink::delta::DeltaSink;
+use datasources::common::sink::iceberg::IcebergSink;
 use datasources::common::sink::json::{JsonSink, JsonSinkOpts};
 use datasources::common::sink::lance::{LanceSink, LanceSinkOpts, LanceWriteParams};
 use datasources::common::sink::parquet::{ParquetSink, ParquetSinkOpts};
+use datasources::common::url::DatasourceUrl;
 use datasources::object_store::azure::AzureStoreAccess;
 use datasources::object_store::gcs::GcsStoreAccess;
 use datasources::object_store::local::LocalStoreAccess;
@@ -142,42 +144,13 @@ impl DisplayAs for CopyToExec {
 
 impl CopyToExec {
     async fn get_destination(&self) -> DataFusionResult<(Arc<dyn ObjStoreAccess>, String)> {
-        Ok(match self.dest.clone() {
-            CopyToDestinationOptions::Local(local_options) => {
-                if !self.format.is_table() {
-                    // Create the path if it doesn't exist (for local).
-                    let _ = tokio::fs::File::create(&local_options.location).await?;
-                }
-                (Arc::new(LocalStoreAccess), local_options.location)
+        if let CopyToDestinationOptions::Local(local_options) = &self.dest {
+            if !self.format.is_table() {
+                // Create the path if it doesn't exist (for local).
+                let _ = tokio::fs::File::create(&local_options.location).await?;
             }
-            CopyToDestinationOptions::Gcs(gcs_options) => {
-                let access = GcsStoreAccess {
-                    bucket: gcs_options.bucket,
-                    service_account_key: gcs_options.service_account_key,
-                    opts: HashMap::new(),
-                };
-                (Arc::new(access), gcs_options.location)
-            }
-            CopyToDestinationOptions::S3(s3_options) => {
-                let access = S3StoreAccess {
-                    bucket: s3_options.bucket,
-                    region: Some(s3_options.region),
-                    access_key_id: s3_options.access_key_id,
-                    secret_access_key: s3_options.secret_access_key,
-                    opts: HashMap::new(),
-                };
-                (Arc::new(access), s3_options.location)
-            }
-            CopyToDestinationOptions::Azure(azure_options) => {
-                let access = AzureStoreAccess {
-                    container: azure_options.container,
-                    account_name: Some(azure_options.account),
-                    access_key: Some(azure_options.access_key),
-                    opts: HashMap::new(),
-                };
-                (Arc::new(access), azure_options.location)
-            }
-        })
+        }
+        Ok(object_store_access(self.dest.clone()))
     }
 
     async fn copy_to(&self, context: Arc<TaskContext>) -> DataFusionResult<RecordBatch> {
@@ -208,6 +181,45 @@ impl CopyToExec {
     }
 }
 
+/// Get the object store access and location within the store for a COPY
+/// location.
+pub fn object_store_access(
+    location: CopyToDestinationOptions,
+) -> (Arc<dyn ObjStoreAccess>, String) {
+    match location {
+        CopyToDestinationOptions::Local(local_options) => {
+            (Arc::new(LocalStoreAccess), local_options.location)
+        }
+        CopyToDestinationOptions::Gcs(gcs_options) => {
+            let access = GcsStoreAccess {
+                bucket: gcs_options.bucket,
+                service_account_key: gcs_options.service_account_key,
+                opts: HashMap::new(),
+            };
+            (Arc::new(access), gcs_options.location)
+        }
+        CopyToDestinationOptions::S3(s3_options) => {
+            let access = S3StoreAccess {
+                bucket: s3_options.bucket,
+                region: Some(s3_options.region),
+                access_key_id: s3_options.access_key_id,
+                secret_access_key: s3_options.secret_access_key,
+                opts: HashMap::new(),
+            };
+            (Arc::new(access), s3_options.location)
+        }
+        CopyToDestinationOptions::Azure(azure_options) => {
+            let access = AzureStoreAccess {
+                container: azure_options.container,
+                account_name: Some(azure_options.account),
+                access_key: Some(azure_options.access_key),
+                opts: HashMap::new(),
+            };
+            (Arc::new(access), azure_options.location)
+        }
+    }
+}
+
 /// Get a sink for writing a file to.
 fn get_sink_for_obj(
     format: CopyToFormatOptions,
@@ -272,6 +284,29 @@ fn get_sink_for_obj(
             .join(location)
             .map_err(|e| DataFusionError::External(Box::new(e)))?,
         )),
+        CopyToFormatOptions::Iceberg(_) => {
+            let base_url = url::Url::parse(
+                access
+                    .base_url()
+                    .map_err(|e| DataFusionError::External(Box::new(e)))?
+                    .as_str(),
+            )
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+            // Local paths are kept as is so that relative locations resolve
+            // against the current directory.
+            let url = if base_url.scheme() == "file" {
+                DatasourceUrl::try_new(location)
+            } else {
+                let url = base_url
+                    .join(location)
+                    .map_err(|e| DataFusionError::External(Box::new(e)))?;
+                DatasourceUrl::try_new(url)
+            }
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+            Box::new(IcebergSink::new(store, url))
+        }
         CopyToFormatOptions::Bson(_) => Box::new(BsonSink::from_obj_store(store, path)),
         CopyToFormatOptions::Json(json_opts) => Box::new(JsonSink::from_obj_store(
             store,
@@ -17,6 +17,9 @@ use datafusion::physical_plan::{
     SendableRecordBatchStream,
     Statistics,
 };
+use datasources::common::url::DatasourceUrl;
+use datasources::lake::iceberg::table::IcebergTable;
+use datasources::lake::storage_options_into_object_store;
 use futures::stream;
 use protogen::metastore::types::options::{InternalColumnDefinition, TableOptionsV0};
 use protogen::metastore::types::service::{self, Mutation};
@@ -108,6 +111,19 @@ async fn create_external_table(
     mutator: Arc<CatalogMutator>,
     plan: CreateExternalTableExec,
 ) -> DataFusionResult<RecordBatch> {
+    // Iceberg tables with columns provided don't exist yet, create the table
+    // before adding it to the catalog.
+    if let (TableOptionsV0::Iceberg(opts), Some(schema)) = (&plan.table_options, &plan.table_schema)
+    {
+        let url = DatasourceUrl::try_new(&opts.location)
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        let store = storage_options_into_object_store(&url, &opts.storage_options)
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        IcebergTable::create(url, store, schema)
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+    }
+
     // We dont want to tightly couple the metastore types with the arrow types
     // so we convert the arrow schema to metastore schema
     let columns = plan.table_schema.map(|schema| {
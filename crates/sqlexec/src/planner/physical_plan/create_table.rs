@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use catalog::mutator::CatalogMutator;
@@ -21,11 +22,18 @@ use datafusion::physical_plan::{
 };
 use datasources::native::access::{NativeTable, NativeTableStorage, SaveMode};
 use futures::{stream, StreamExt};
+use protogen::metastore::types::options::{
+    CheckConstraint,
+    MaterializedViewOptions,
+    TableOptionsInternal,
+    UniqueConstraint,
+};
 use protogen::metastore::types::service;
 use protogen::metastore::types::service::Mutation;
 use sqlbuiltins::builtins::DEFAULT_CATALOG;
 use tracing::debug;
 
+use super::refresh_materialized_view::now_micros;
 use super::GENERIC_OPERATION_PHYSICAL_SCHEMA;
 use crate::planner::logical_plan::OwnedFullObjectReference;
 use crate::planner::physical_plan::new_operation_batch;
@@ -37,6 +45,18 @@ pub struct CreateTableExec {
     pub if_not_exists: bool,
     pub or_replace: bool,
     pub arrow_schema: SchemaRef,
+    /// SQL for the default values of columns, keyed by column name.
+    pub column_defaults: BTreeMap<String, String>,
+    pub check_constraints: Vec<CheckConstraint>,
+    pub unique_constraints: Vec<UniqueConstraint>,
+    /// Columns the table is partitioned by.
+    pub partition_columns: Vec<String>,
+    /// Sequences generating the values of SERIAL and identity columns.
+    pub column_sequences: Vec<service::CreateSequence>,
+    /// Identity columns that can't be written to explicitly.
+    pub generated_always: Vec<String>,
+    /// Set if the table holds the results of a materialized view.
+    pub materialized_view: Option<MaterializedViewOptions>,
     pub source: Option<Arc<dyn ExecutionPlan>>,
 }
 
@@ -74,6 +94,13 @@ impl ExecutionPlan for CreateTableExec {
             if_not_exists: self.if_not_exists,
             or_replace: self.or_replace,
             arrow_schema: self.arrow_schema.clone(),
+            column_defaults: self.column_defaults.clone(),
+            check_constraints: self.check_constraints.clone(),
+            unique_constraints: self.unique_constraints.clone(),
+            partition_columns: self.partition_columns.clone(),
+            column_sequences: self.column_sequences.clone(),
+            generated_always: self.generated_always.clone(),
+            materialized_view: self.materialized_view.clone(),
             source: children.first().cloned(),
         }))
     }
@@ -128,17 +155,38 @@ impl CreateTableExec {
         let or_replace = self.or_replace;
         let if_not_exists = self.if_not_exists;
         let catalog_version = self.catalog_version;
+
+        let mut options: TableOptionsInternal = self.arrow_schema.into();
+        for column in options.columns.iter_mut() {
+            column.default_expr = self.column_defaults.get(&column.name).cloned();
+            column.generated_always = self.generated_always.contains(&column.name);
+        }
+        options.check_constraints = self.check_constraints;
+        options.unique_constraints = self.unique_constraints;
+        options.partition_columns = self.partition_columns;
+        // Materialized views are populated by the source as they're created.
+        options.materialized_view = self.materialized_view.map(|view| MaterializedViewOptions {
+            refreshed_at: Some(now_micros()),
+            ..view
+        });
+
+        // Sequences are created after the table since they're owned by its
+        // columns.
+        let mutations = std::iter::once(Mutation::CreateTable(service::CreateTable {
+            schema: self.tbl_reference.schema.clone().into_owned(),
+            name: self.tbl_reference.name.clone().into_owned(),
+            options,
+            if_not_exists,
+            or_replace,
+        }))
+        .chain(
+            self.column_sequences
+                .into_iter()
+                .map(Mutation::CreateSequence),
+        );
+
         let state = mutator
-            .mutate(
-                catalog_version,
-                [Mutation::CreateTable(service::CreateTable {
-                    schema: self.tbl_reference.schema.clone().into_owned(),
-                    name: self.tbl_reference.name.clone().into_owned(),
-                    options: self.arrow_schema.into(),
-                    if_not_exists,
-                    or_replace,
-                })],
-            )
+            .mutate(catalog_version, mutations)
             .await
             .map_err(|e| {
                 DataFusionError::Execution(format!("failed to create table in catalog: {e}"))
@@ -188,11 +236,27 @@ impl CreateTableExec {
             }
         };
 
+        // Tables created inside a transaction are created in a location
+        // private to the transaction, since their oid isn't reserved until
+        // the transaction commits.
+        if mutator.created_in_transaction(ent.meta.id) {
+            storage.stage_created_table(ent);
+        }
+
         let table_existed = storage
             .table_exists(ent)
             .await
             .map_err(|e| DataFusionError::External(Box::new(e)))?;
 
+        // Replacing a table overwrites its storage, which can't be undone when
+        // a transaction is rolled back.
+        if table_existed && or_replace && mutator.in_transaction() {
+            return Err(DataFusionError::Execution(
+                "CREATE OR REPLACE TABLE cannot replace an existing table inside a transaction block"
+                    .to_string(),
+            ));
+        }
+
         if !table_existed || !if_not_exists {
             let table = storage.create_table(ent, save_mode).await.map_err(|e| {
                 DataFusionError::Execution(format!("failed to create table in storage: {e}"))
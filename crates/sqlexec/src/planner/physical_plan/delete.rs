@@ -5,6 +5,7 @@ use std::sync::Arc;
 use datafusion::arrow::datatypes::Schema;
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::context::SessionState;
 use datafusion::execution::TaskContext;
 use datafusion::physical_expr::PhysicalSortExpr;
 use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
@@ -79,7 +80,12 @@ impl ExecutionPlan for DeleteExec {
             .get_extension::<NativeTableStorage>()
             .expect("context should have native table storage");
 
-        let stream = stream::once(delete(self.clone(), storage));
+        let state = SessionState::new_with_config_rt(
+            context.session_config().clone(),
+            context.runtime_env(),
+        );
+
+        let stream = stream::once(delete(self.clone(), state, storage));
 
         Ok(Box::pin(RecordBatchStreamAdapter::new(
             self.schema(),
@@ -100,12 +106,13 @@ impl DisplayAs for DeleteExec {
 
 async fn delete(
     plan: DeleteExec,
+    state: SessionState,
     storage: impl AsRef<NativeTableStorage>,
 ) -> DataFusionResult<RecordBatch> {
     let storage = storage.as_ref();
 
     let num_deleted = storage
-        .delete_rows_where(&plan.table, plan.where_expr)
+        .delete_rows_where(&state, &plan.table, plan.where_expr)
         .await
         .map_err(|e| DataFusionError::Execution(format!("failed to delete: {e}")))?;
 
@@ -121,6 +121,12 @@ async fn drop_tables(
         .await
         .map_err(|e| DataFusionError::Execution(format!("failed to drop tables: {e}")))?;
 
+    // Inside a transaction block, the delta tables are deleted once the
+    // transaction commits.
+    if mutator.in_transaction() {
+        return Ok(new_operation_batch("drop_tables"));
+    }
+
     // only after the catalog is updated, we can delete the delta tables
     // TODO: this should be done in the scheduler.
     let sys_exec =
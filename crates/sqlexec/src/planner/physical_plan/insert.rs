@@ -4,14 +4,18 @@ use std::sync::Arc;
 
 use datafusion::arrow::datatypes::{DataType, Schema};
 use datafusion::arrow::record_batch::RecordBatch;
-use datafusion::datasource::TableProvider;
+use datafusion::common::Column;
+use datafusion::datasource::{provider_as_source, MemTable, TableProvider};
 use datafusion::error::{DataFusionError, Result as DataFusionResult};
 use datafusion::execution::context::SessionState;
 use datafusion::execution::TaskContext;
+use datafusion::logical_expr::LogicalPlanBuilder;
 use datafusion::physical_expr::PhysicalSortExpr;
 use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::memory::MemoryExec;
 use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion::physical_plan::{
+    collect,
     DisplayAs,
     DisplayFormatType,
     ExecutionPlan,
@@ -19,17 +23,29 @@ use datafusion::physical_plan::{
     SendableRecordBatchStream,
     Statistics,
 };
+use datafusion::prelude::Expr;
 use datafusion::scalar::ScalarValue;
 use datafusion_ext::metrics::WriteOnlyDataSourceMetricsExecAdapter;
+use datasources::native::access::NativeTable;
 use futures::{stream, StreamExt};
+use protogen::metastore::types::options::UniqueConstraint;
 
+use super::constraint_check::{ConstraintCheckExec, RowConstraint};
 use super::remote_scan::ProviderReference;
-use super::{new_operation_with_count_batch, GENERIC_OPERATION_AND_COUNT_PHYSICAL_SCHEMA};
+use super::unique_check::UniqueKeyCheck;
+use super::{
+    metrics_adapter,
+    new_operation_with_count_batch,
+    GENERIC_OPERATION_AND_COUNT_PHYSICAL_SCHEMA,
+};
 
 #[derive(Debug, Clone)]
 pub struct InsertExec {
     pub provider: ProviderReference,
     pub source: Arc<WriteOnlyDataSourceMetricsExecAdapter>,
+    /// Constraints checked for each row. The source produces a boolean
+    /// column for each constraint following the table's columns.
+    pub constraints: Vec<RowConstraint>,
 }
 
 impl ExecutionPlan for InsertExec {
@@ -59,9 +75,8 @@ impl ExecutionPlan for InsertExec {
     ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
         Ok(Arc::new(InsertExec {
             provider: self.provider.clone(),
-            source: Arc::new(WriteOnlyDataSourceMetricsExecAdapter::new(
-                children.first().unwrap().clone(),
-            )),
+            source: metrics_adapter(children.first().unwrap().clone()),
+            constraints: self.constraints.clone(),
         }))
     }
 
@@ -84,7 +99,7 @@ impl ExecutionPlan for InsertExec {
                 )),
                 ProviderReference::Provider(provider) => {
                     // TODO: Add background job to track storage for native tables.
-                    Self::do_insert(provider, this.source, context).await
+                    Self::do_insert(provider, this.source, this.constraints, context).await
                 }
             }
         });
@@ -110,8 +125,117 @@ impl InsertExec {
     pub async fn do_insert(
         table: Arc<dyn TableProvider>,
         source: Arc<dyn ExecutionPlan>,
+        constraints: Vec<RowConstraint>,
         context: Arc<TaskContext>,
     ) -> DataFusionResult<RecordBatch> {
+        let inserted_rows = Self::insert_checked_rows(table, source, constraints, context).await?;
+        Ok(new_operation_with_count_batch("insert", inserted_rows))
+    }
+
+    /// Insert all rows from the source into the table after checking them
+    /// against the table's constraints, returning the number of rows
+    /// inserted.
+    ///
+    /// The source produces a boolean column for each constraint following the
+    /// table's columns.
+    pub async fn insert_checked_rows(
+        table: Arc<dyn TableProvider>,
+        source: Arc<dyn ExecutionPlan>,
+        constraints: Vec<RowConstraint>,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<u64> {
+        let check = if constraints.is_empty() {
+            None
+        } else {
+            Some(Arc::new(ConstraintCheckExec::try_new(
+                source.clone(),
+                constraints,
+            )?))
+        };
+        let source = match &check {
+            Some(check) => check.clone() as Arc<dyn ExecutionPlan>,
+            None => source,
+        };
+
+        let unique_constraints = table
+            .as_any()
+            .downcast_ref::<NativeTable>()
+            .map(|table| table.unique_constraints().to_vec())
+            .unwrap_or_default();
+        let result = if unique_constraints.is_empty() {
+            Self::insert_rows(table, source, context).await
+        } else {
+            Self::insert_unique_rows(table, source, unique_constraints, context).await
+        };
+
+        result.map_err(|e| match check.and_then(|check| check.take_violation()) {
+            Some(violation) => violation.into(),
+            None => e,
+        })
+    }
+
+    /// Insert rows into a table with PRIMARY KEY or UNIQUE constraints.
+    ///
+    /// Rows are collected up front so their keys can be checked against
+    /// each other and against the rows already in the table before writing.
+    /// Only existing rows with keys in the range of the inserted keys are
+    /// read, and the table's write lock is held until the rows are committed.
+    async fn insert_unique_rows(
+        table: Arc<dyn TableProvider>,
+        source: Arc<dyn ExecutionPlan>,
+        unique_constraints: Vec<UniqueConstraint>,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<u64> {
+        let state = SessionState::new_with_config_rt(
+            context.session_config().clone(),
+            context.runtime_env(),
+        );
+
+        let native = table
+            .as_any()
+            .downcast_ref::<NativeTable>()
+            .ok_or_else(|| DataFusionError::Internal("expected a native table".to_string()))?;
+
+        let schema = source.schema();
+        let batches = collect(source, context.clone()).await?;
+        let rows = provider_as_source(Arc::new(MemTable::try_new(
+            schema.clone(),
+            vec![batches.clone()],
+        )?));
+
+        let _guard = native.lock_unique_writes().await;
+        let existing = native
+            .latest()
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+        let column = |name: &str| Ok(Expr::Column(Column::from_name(name)));
+        let mut check = UniqueKeyCheck::new(unique_constraints, table.schema());
+        let key_filter = check
+            .key_filter(
+                LogicalPlanBuilder::scan("inserted", rows.clone(), None)?,
+                &state,
+            )
+            .await?;
+        check.add_rows(
+            LogicalPlanBuilder::scan("existing", provider_as_source(Arc::new(existing)), None)?
+                .filter(key_filter)?,
+            column,
+        )?;
+        check.add_rows(LogicalPlanBuilder::scan("inserted", rows, None)?, column)?;
+        check.check(&state).await?;
+
+        let source = Arc::new(MemoryExec::try_new(&[batches], schema, None)?);
+        Self::insert_rows(table, source, context).await
+    }
+
+    /// Insert all rows from the source into the table, returning the number
+    /// of rows inserted.
+    pub async fn insert_rows(
+        table: Arc<dyn TableProvider>,
+        source: Arc<dyn ExecutionPlan>,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<u64> {
         let state = SessionState::new_with_config_rt(
             context.session_config().clone(),
             context.runtime_env(),
@@ -145,6 +269,6 @@ impl InsertExec {
             }
         }
 
-        Ok(new_operation_with_count_batch("insert", inserted_rows))
+        Ok(inserted_rows)
     }
 }
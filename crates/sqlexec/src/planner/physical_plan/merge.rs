@@ -0,0 +1,400 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::Schema;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::Column;
+use datafusion::dataframe::DataFrame;
+use datafusion::datasource::{provider_as_source, MemTable, TableProvider};
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::context::SessionState;
+use datafusion::execution::TaskContext;
+use datafusion::logical_expr::{col, lit, JoinType, LogicalPlanBuilder, TableSource};
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    collect,
+    execute_stream,
+    DisplayAs,
+    DisplayFormatType,
+    ExecutionPlan,
+    Partitioning,
+    SendableRecordBatchStream,
+    Statistics,
+};
+use datafusion::prelude::Expr;
+use datasources::native::access::{MergeClause, NativeTableStorage};
+use futures::{stream, StreamExt};
+use protogen::metastore::types::catalog::TableEntry;
+use protogen::metastore::types::options::{TableOptionsV0, UniqueConstraint};
+
+use super::constraint_check::RowConstraint;
+use super::unique_check::UniqueKeyCheck;
+use super::{new_operation_with_count_batch, GENERIC_OPERATION_AND_COUNT_PHYSICAL_SCHEMA};
+
+/// Column marking rows of the table that were joined with a row of the
+/// source when checking constraints.
+const MATCHED_COLUMN: &str = "__glaredb_merge_matched";
+
+/// Column marking rows of the source when checking unique constraints, which
+/// joins the table with the source on both sides.
+const SOURCE_COLUMN: &str = "__glaredb_merge_source";
+
+#[derive(Debug, Clone)]
+pub struct MergeExec {
+    pub table: TableEntry,
+    pub source: Arc<dyn ExecutionPlan>,
+    pub source_alias: String,
+    pub target_alias: String,
+    pub on: Expr,
+    pub clauses: Vec<MergeClause>,
+    /// Constraints the rows inserted or updated by the merge must satisfy.
+    pub constraints: Vec<RowConstraint>,
+}
+
+impl ExecutionPlan for MergeExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> Arc<Schema> {
+        GENERIC_OPERATION_AND_COUNT_PHYSICAL_SCHEMA.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.source.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        if children.len() != 1 {
+            return Err(DataFusionError::Plan(
+                "MergeExec expects exactly one child".to_string(),
+            ));
+        }
+        Ok(Arc::new(MergeExec {
+            source: children[0].clone(),
+            ..self.as_ref().clone()
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Execution(
+                "MergeExec only supports 1 partition".to_string(),
+            ));
+        }
+
+        let storage = context
+            .session_config()
+            .get_extension::<NativeTableStorage>()
+            .expect("context should have native table storage");
+
+        let stream = stream::once(merge(self.clone(), context, storage));
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+
+    fn statistics(&self) -> DataFusionResult<Statistics> {
+        Ok(Statistics::new_unknown(self.schema().as_ref()))
+    }
+}
+
+impl DisplayAs for MergeExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MergeExec: on={}", self.on)
+    }
+}
+
+async fn merge(
+    plan: MergeExec,
+    context: Arc<TaskContext>,
+    storage: impl AsRef<NativeTableStorage>,
+) -> DataFusionResult<RecordBatch> {
+    let storage = storage.as_ref();
+    let state =
+        SessionState::new_with_config_rt(context.session_config().clone(), context.runtime_env());
+
+    // The source is collected up front since it's read once to check
+    // constraints and again by the merge.
+    let schema = plan.source.schema();
+    let batches = collect(plan.source.clone(), context).await?;
+    let source = provider_as_source(Arc::new(MemTable::try_new(schema, vec![batches])?));
+
+    // External delta tables don't have constraints.
+    let _guard = if matches!(plan.table.options, TableOptionsV0::Internal(_)) {
+        let table = storage
+            .load_table(&plan.table)
+            .await
+            .map_err(|e| DataFusionError::Execution(format!("failed to merge: {e}")))?;
+        // Held until the merge is committed so no other write can commit a
+        // key the merge was checked against.
+        let guard = table.lock_unique_writes().await;
+        let table = table
+            .latest()
+            .await
+            .map_err(|e| DataFusionError::Execution(format!("failed to merge: {e}")))?;
+        let unique_constraints = table.unique_constraints().to_vec();
+        let provider = table.into_table_provider();
+
+        if !plan.constraints.is_empty() {
+            check_constraints(&plan, &state, provider.clone(), source.clone()).await?;
+        }
+        if !unique_constraints.is_empty() {
+            check_unique_constraints(&plan, &state, provider, source.clone(), unique_constraints)
+                .await?;
+        }
+        guard
+    } else {
+        None
+    };
+
+    let source = LogicalPlanBuilder::scan(plan.source_alias.as_str(), source, None)?.build()?;
+    let num_rows = storage
+        .merge_rows(
+            &state,
+            &plan.table,
+            DataFrame::new(state.clone(), source),
+            &plan.source_alias,
+            &plan.target_alias,
+            plan.on,
+            plan.clauses,
+        )
+        .await
+        .map_err(|e| DataFusionError::Execution(format!("failed to merge: {e}")))?;
+
+    Ok(new_operation_with_count_batch("merge", num_rows as u64))
+}
+
+/// Check that the rows inserted or updated by the merge satisfy the table's
+/// constraints.
+///
+/// The source is joined with the table as it is by the merge, and the
+/// constraints are evaluated against the values written by each clause for
+/// the rows that clause applies to.
+async fn check_constraints(
+    plan: &MergeExec,
+    state: &SessionState,
+    provider: Arc<dyn TableProvider>,
+    source: Arc<dyn TableSource>,
+) -> DataFusionResult<()> {
+    let target = LogicalPlanBuilder::scan(
+        plan.target_alias.as_str(),
+        provider_as_source(provider),
+        None,
+    )?;
+    let mut target_exprs: Vec<_> = target
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| Expr::Column(field.qualified_column()))
+        .collect();
+    target_exprs.push(lit(true).alias(MATCHED_COLUMN));
+    let source = LogicalPlanBuilder::scan(plan.source_alias.as_str(), source, None)?.build()?;
+    let joined = target
+        .project(target_exprs)?
+        .join_on(source, JoinType::Right, [plan.on.clone()])?
+        .build()?;
+
+    let conditions = clause_conditions(
+        &plan.clauses,
+        col(MATCHED_COLUMN).is_not_null(),
+        col(MATCHED_COLUMN).is_null(),
+    );
+    for (clause, applies) in plan.clauses.iter().zip(conditions) {
+        let values: HashMap<_, _> = match clause {
+            MergeClause::MatchedUpdate { updates, .. } => updates
+                .iter()
+                .map(|(column, expr)| (column.as_str(), expr))
+                .collect(),
+            MergeClause::NotMatchedInsert { values, .. } => values
+                .iter()
+                .map(|(column, expr)| (column.as_str(), expr))
+                .collect(),
+            MergeClause::MatchedDelete { .. } => continue,
+        };
+
+        // Evaluate each constraint against the values written by the clause,
+        // falling back to the existing values of the row.
+        let exprs = plan
+            .constraints
+            .iter()
+            .enumerate()
+            .map(|(idx, constraint)| {
+                let expr = constraint.expr.clone().transform(&|expr| {
+                    Ok(match expr {
+                        Expr::Column(column) => match values.get(column.name.as_str()) {
+                            Some(value) => Transformed::Yes((*value).clone()),
+                            None => Transformed::Yes(Expr::Column(Column::new(
+                                Some(plan.target_alias.clone()),
+                                column.name,
+                            ))),
+                        },
+                        expr => Transformed::No(expr),
+                    })
+                })?;
+                Ok(expr.alias(RowConstraint::column_name(idx)))
+            })
+            .collect::<DataFusionResult<Vec<_>>>()?;
+
+        let logical = LogicalPlanBuilder::from(joined.clone())
+            .filter(applies)?
+            .project(exprs)?
+            .build()?;
+
+        let physical = state.create_physical_plan(&logical).await?;
+        let mut stream = execute_stream(physical, state.task_ctx())?;
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for (idx, constraint) in plan.constraints.iter().enumerate() {
+                constraint.check_results(batch.column(idx))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that the merge doesn't leave two rows with the same key.
+///
+/// The table is fully joined with the source, so that the table's rows
+/// after the merge are the rows no clause applies to along with the rows
+/// written by the update and insert clauses.
+pub(super) async fn check_unique_constraints(
+    plan: &MergeExec,
+    state: &SessionState,
+    provider: Arc<dyn TableProvider>,
+    source: Arc<dyn TableSource>,
+    unique_constraints: Vec<UniqueConstraint>,
+) -> DataFusionResult<()> {
+    let mut check = UniqueKeyCheck::new(unique_constraints, provider.schema());
+
+    let target = LogicalPlanBuilder::scan(
+        plan.target_alias.as_str(),
+        provider_as_source(provider),
+        None,
+    )?;
+    let mut target_exprs: Vec<_> = target
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| Expr::Column(field.qualified_column()))
+        .collect();
+    target_exprs.push(lit(true).alias(MATCHED_COLUMN));
+    let source = LogicalPlanBuilder::scan(plan.source_alias.as_str(), source, None)?;
+    let mut source_exprs: Vec<_> = source
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| Expr::Column(field.qualified_column()))
+        .collect();
+    source_exprs.push(lit(true).alias(SOURCE_COLUMN));
+    let joined = target
+        .project(target_exprs)?
+        .join_on(
+            source.project(source_exprs)?.build()?,
+            JoinType::Full,
+            [plan.on.clone()],
+        )?
+        .build()?;
+
+    let existing = |column: &str| {
+        Ok(Expr::Column(Column::new(
+            Some(plan.target_alias.clone()),
+            column,
+        )))
+    };
+
+    let matched = col(MATCHED_COLUMN)
+        .is_not_null()
+        .and(col(SOURCE_COLUMN).is_not_null());
+    let conditions = clause_conditions(&plan.clauses, matched, col(MATCHED_COLUMN).is_null());
+
+    // Rows of the table that no clause applies to are left as they are.
+    let no_clause_applies = plan
+        .clauses
+        .iter()
+        .filter(|clause| clause.is_matched())
+        .fold(lit(true), |no_clause_applies, clause| {
+            let predicate = clause.predicate().cloned().unwrap_or(lit(true));
+            no_clause_applies.and(predicate.is_not_true())
+        });
+    let unchanged = col(MATCHED_COLUMN)
+        .is_not_null()
+        .and(col(SOURCE_COLUMN).is_null().or(no_clause_applies));
+    check.add_rows(
+        LogicalPlanBuilder::from(joined.clone()).filter(unchanged)?,
+        existing,
+    )?;
+
+    for (clause, applies) in plan.clauses.iter().zip(conditions) {
+        let values: HashMap<_, _> = match clause {
+            MergeClause::MatchedUpdate { updates, .. } => updates
+                .iter()
+                .map(|(column, expr)| (column.as_str(), expr))
+                .collect(),
+            MergeClause::NotMatchedInsert { values, .. } => values
+                .iter()
+                .map(|(column, expr)| (column.as_str(), expr))
+                .collect(),
+            MergeClause::MatchedDelete { .. } => continue,
+        };
+        check.add_rows(
+            LogicalPlanBuilder::from(joined.clone()).filter(applies)?,
+            |column| match values.get(column) {
+                Some(value) => Ok((*value).clone()),
+                None => existing(column),
+            },
+        )?;
+    }
+
+    check.check(state).await
+}
+
+/// Get the condition for each clause applying to a row of the source joined
+/// with the table.
+///
+/// `matched` and `not_matched` tell if the source row was joined with a row
+/// of the table. Clauses only apply to rows that no earlier clause applied
+/// to.
+fn clause_conditions(clauses: &[MergeClause], matched: Expr, not_matched: Expr) -> Vec<Expr> {
+    let mut earlier_matched: Vec<Expr> = Vec::new();
+    let mut earlier_not_matched: Vec<Expr> = Vec::new();
+    clauses
+        .iter()
+        .map(|clause| {
+            let (earlier, matched) = if clause.is_matched() {
+                (&mut earlier_matched, matched.clone())
+            } else {
+                (&mut earlier_not_matched, not_matched.clone())
+            };
+            let predicate = clause.predicate().cloned().unwrap_or(lit(true));
+            let applies = earlier.iter().fold(
+                matched.and(predicate.clone().is_true()),
+                |applies, earlier| applies.and(earlier.clone().is_not_true()),
+            );
+            earlier.push(predicate);
+            applies
+        })
+        .collect()
+}
@@ -3,11 +3,17 @@ pub mod alter_table;
 pub mod alter_tunnel_rotate_keys;
 pub mod client_recv;
 pub mod client_send;
+pub mod comment_on;
+pub mod constraint_check;
+pub mod copy_from;
+pub mod copy_stdin;
 pub mod copy_to;
 pub mod create_credentials;
 pub mod create_external_database;
 pub mod create_external_table;
+pub mod create_role;
 pub mod create_schema;
+pub mod create_sequence;
 pub mod create_table;
 pub mod create_temp_table;
 pub mod create_tunnel;
@@ -16,18 +22,29 @@ pub mod delete;
 pub mod describe_table;
 pub mod drop_credentials;
 pub mod drop_database;
+pub mod drop_role;
 pub mod drop_schemas;
+pub mod drop_sequences;
 pub mod drop_tables;
 pub mod drop_temp_tables;
 pub mod drop_tunnel;
 pub mod drop_views;
+pub mod grant_privileges;
 pub mod insert;
+pub mod merge;
+pub mod optimize_table;
+pub mod refresh_materialized_view;
 pub mod remote_exec;
 pub mod remote_scan;
+pub mod restore_table;
+pub mod revoke_privileges;
 pub mod send_recv;
 pub mod set_var;
 pub mod show_var;
+pub mod unique_check;
 pub mod update;
+pub mod upsert;
+pub mod vacuum_table;
 pub mod values;
 
 use std::sync::Arc;
@@ -46,6 +63,7 @@ use datafusion::physical_plan::{
     Statistics,
 };
 use datafusion::scalar::ScalarValue;
+use datafusion_ext::metrics::WriteOnlyDataSourceMetricsExecAdapter;
 use futures::{stream, StreamExt};
 use once_cell::sync::Lazy;
 
@@ -98,6 +116,19 @@ pub fn get_operation_from_batch(batch: &RecordBatch) -> Option<String> {
     None
 }
 
+/// Wrap the input of a write in a metrics adapter, unless it already is one.
+pub fn metrics_adapter(
+    source: Arc<dyn ExecutionPlan>,
+) -> Arc<WriteOnlyDataSourceMetricsExecAdapter> {
+    match source
+        .as_any()
+        .downcast_ref::<WriteOnlyDataSourceMetricsExecAdapter>()
+    {
+        Some(adapter) => Arc::new(adapter.clone()),
+        None => Arc::new(WriteOnlyDataSourceMetricsExecAdapter::new(source)),
+    }
+}
+
 pub fn get_count_from_batch(batch: &RecordBatch) -> Option<u64> {
     if batch.columns().len() < 2 {
         return None;
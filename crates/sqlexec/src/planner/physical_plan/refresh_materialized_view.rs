@@ -0,0 +1,188 @@
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use catalog::mutator::CatalogMutator;
+use datafusion::arrow::datatypes::Schema;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::TaskContext;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayAs,
+    DisplayFormatType,
+    ExecutionPlan,
+    Partitioning,
+    SendableRecordBatchStream,
+    Statistics,
+};
+use datasources::native::access::NativeTableStorage;
+use futures::{stream, StreamExt};
+use protogen::metastore::types::catalog::TableEntry;
+use protogen::metastore::types::service::{self, AlterTableOperation, Mutation};
+
+use super::{new_operation_batch, GENERIC_OPERATION_PHYSICAL_SCHEMA};
+
+#[derive(Debug, Clone)]
+pub struct RefreshMaterializedViewExec {
+    pub catalog_version: u64,
+    /// Schema containing the view.
+    pub schema: String,
+    pub table: TableEntry,
+    pub source: Arc<dyn ExecutionPlan>,
+}
+
+impl ExecutionPlan for RefreshMaterializedViewExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> Arc<Schema> {
+        GENERIC_OPERATION_PHYSICAL_SCHEMA.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.source.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        if children.len() != 1 {
+            return Err(DataFusionError::Plan(
+                "RefreshMaterializedViewExec expects exactly one child".to_string(),
+            ));
+        }
+        Ok(Arc::new(RefreshMaterializedViewExec {
+            source: children[0].clone(),
+            ..self.as_ref().clone()
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Execution(
+                "RefreshMaterializedViewExec only supports 1 partition".to_string(),
+            ));
+        }
+
+        let mutator = context
+            .session_config()
+            .get_extension::<CatalogMutator>()
+            .expect("context should have catalog mutator");
+        let storage = context
+            .session_config()
+            .get_extension::<NativeTableStorage>()
+            .expect("context should have native table storage");
+
+        let stream = stream::once(refresh(mutator, storage, self.clone(), context));
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+
+    fn statistics(&self) -> DataFusionResult<Statistics> {
+        Ok(Statistics::new_unknown(self.schema().as_ref()))
+    }
+}
+
+impl DisplayAs for RefreshMaterializedViewExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RefreshMaterializedViewExec: {}", self.table.meta.name)
+    }
+}
+
+async fn refresh(
+    mutator: Arc<CatalogMutator>,
+    storage: Arc<NativeTableStorage>,
+    plan: RefreshMaterializedViewExec,
+    context: Arc<TaskContext>,
+) -> DataFusionResult<RecordBatch> {
+    refresh_materialized_view(
+        &mutator,
+        &storage,
+        plan.catalog_version,
+        &plan.schema,
+        &plan.table,
+        plan.source,
+        context,
+    )
+    .await?;
+
+    Ok(new_operation_batch("refresh_materialized_view"))
+}
+
+/// Overwrite the table of a materialized view with the output of `source`,
+/// and record the time of the refresh in the catalog.
+///
+/// The refresh time is taken before executing the source, so the contents of
+/// the view are at least as recent as the recorded time.
+pub async fn refresh_materialized_view(
+    mutator: &CatalogMutator,
+    storage: &NativeTableStorage,
+    catalog_version: u64,
+    schema: &str,
+    table: &TableEntry,
+    source: Arc<dyn ExecutionPlan>,
+    context: Arc<TaskContext>,
+) -> DataFusionResult<()> {
+    let refreshed_at = now_micros();
+
+    let source = if source.output_partitioning().partition_count() != 1 {
+        Arc::new(CoalescePartitionsExec::new(source))
+    } else {
+        source
+    };
+
+    let native = storage.load_table(table).await.map_err(|e| {
+        DataFusionError::Execution(format!("failed to load materialized view: {e}"))
+    })?;
+    let mut stream = native.insert_exec(source, true).execute(0, context)?;
+    while let Some(res) = stream.next().await {
+        // Drain stream to write everything.
+        let _ = res?;
+    }
+
+    mutator
+        .mutate_and_commit(
+            catalog_version,
+            [Mutation::AlterTable(service::AlterTable {
+                schema: schema.to_string(),
+                name: table.meta.name.clone(),
+                operation: AlterTableOperation::SetMaterializedViewRefreshed { refreshed_at },
+            })],
+        )
+        .await
+        .map_err(|e| {
+            DataFusionError::Execution(format!("failed to record materialized view refresh: {e}"))
+        })?;
+
+    Ok(())
+}
+
+/// Get the current time in microseconds since the epoch, the resolution
+/// refresh times are recorded in.
+pub fn now_micros() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros() as i64)
+        .unwrap_or_default()
+}
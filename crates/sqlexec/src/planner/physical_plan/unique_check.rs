@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::common::Column;
+use datafusion::error::Result as DataFusionResult;
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::{cast, count, lit, max, min, LogicalPlan, LogicalPlanBuilder};
+use datafusion::physical_plan::collect;
+use datafusion::prelude::Expr;
+use datafusion::scalar::ScalarValue;
+use protogen::metastore::types::options::UniqueConstraint;
+
+use crate::errors::ExecError;
+
+/// Name of the column holding the number of rows sharing a key.
+const KEY_COUNT_COLUMN: &str = "__glaredb_key_count";
+
+/// Checks that a write leaves no two rows of a table with the same key for
+/// any of the table's PRIMARY KEY and UNIQUE constraints.
+///
+/// Checks are made against the rows the table would contain after the
+/// write, added as sets of rows along with the value each row has for the
+/// key columns. Following postgres, rows with a null in any of a key's
+/// columns never conflict.
+///
+/// Keys are checked before writing. Writers should hold the table's unique
+/// write lock from the check until they've committed (see
+/// `NativeTable::lock_unique_writes`), otherwise concurrent writes to the
+/// same table may introduce duplicates.
+pub struct UniqueKeyCheck {
+    constraints: Vec<UniqueConstraint>,
+    /// Schema of the table, used to coerce key values to the column types.
+    schema: SchemaRef,
+    rows: Vec<LogicalPlan>,
+}
+
+impl UniqueKeyCheck {
+    pub fn new(constraints: Vec<UniqueConstraint>, schema: SchemaRef) -> Self {
+        UniqueKeyCheck {
+            constraints,
+            schema,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+
+    /// Columns that are part of at least one key.
+    pub fn key_columns(&self) -> Vec<&str> {
+        let mut columns: Vec<&str> = Vec::new();
+        for column in self.constraints.iter().flat_map(|c| &c.columns) {
+            if !columns.contains(&column.as_str()) {
+                columns.push(column);
+            }
+        }
+        columns
+    }
+
+    /// Add rows the table contains after the write, with `value` providing
+    /// the expression for each key column.
+    pub fn add_rows(
+        &mut self,
+        rows: LogicalPlanBuilder,
+        value: impl Fn(&str) -> DataFusionResult<Expr>,
+    ) -> DataFusionResult<()> {
+        let exprs = self
+            .key_columns()
+            .into_iter()
+            .map(|column| {
+                let data_type = self.schema.field_with_name(column)?.data_type().clone();
+                Ok(cast(value(column)?, data_type).alias(column))
+            })
+            .collect::<DataFusionResult<Vec<_>>>()?;
+        self.rows.push(rows.project(exprs)?.build()?);
+        Ok(())
+    }
+
+    /// Create a filter for the rows of the table that may share a key with
+    /// `rows`.
+    ///
+    /// Rows pass the filter if each of a key's columns is within the range of
+    /// values `rows` have for the column, which lets scans of the table skip
+    /// files using their statistics.
+    pub async fn key_filter(
+        &self,
+        rows: LogicalPlanBuilder,
+        state: &SessionState,
+    ) -> DataFusionResult<Expr> {
+        let columns = self.key_columns();
+        let mut aggregates = Vec::with_capacity(columns.len() * 2);
+        for column in &columns {
+            let data_type = self.schema.field_with_name(column)?.data_type().clone();
+            let value = cast(Expr::Column(Column::from_name(*column)), data_type);
+            aggregates.push(min(value.clone()));
+            aggregates.push(max(value));
+        }
+
+        let logical = rows.aggregate(Vec::<Expr>::new(), aggregates)?.build()?;
+        let physical = state.create_physical_plan(&logical).await?;
+        let batches = collect(physical, state.task_ctx()).await?;
+        let batch = match batches.iter().find(|batch| batch.num_rows() > 0) {
+            Some(batch) => batch,
+            None => return Ok(lit(false)),
+        };
+
+        let mut ranges = HashMap::with_capacity(columns.len());
+        for (idx, column) in columns.iter().enumerate() {
+            let min = ScalarValue::try_from_array(batch.column(idx * 2), 0)?;
+            let max = ScalarValue::try_from_array(batch.column(idx * 2 + 1), 0)?;
+            ranges.insert(*column, (min, max));
+        }
+
+        // Rows with a null in a key never conflict, so a key with only nulls
+        // for a column doesn't match any rows.
+        let filter = self
+            .constraints
+            .iter()
+            .map(|constraint| {
+                constraint
+                    .columns
+                    .iter()
+                    .map(|column| match ranges.get(column.as_str()) {
+                        Some((min, max)) if !min.is_null() && !max.is_null() => {
+                            Expr::Column(Column::from_name(column))
+                                .between(lit(min.clone()), lit(max.clone()))
+                        }
+                        _ => lit(false),
+                    })
+                    .reduce(Expr::and)
+                    .unwrap_or(lit(false))
+            })
+            .reduce(Expr::or)
+            .unwrap_or(lit(false));
+        Ok(filter)
+    }
+
+    /// Check the added rows for duplicate keys.
+    pub async fn check(self, state: &SessionState) -> DataFusionResult<()> {
+        let mut rows = self.rows.into_iter();
+        let mut builder = match rows.next() {
+            Some(first) if !self.constraints.is_empty() => LogicalPlanBuilder::from(first),
+            _ => return Ok(()),
+        };
+        for plan in rows {
+            builder = builder.union(plan)?;
+        }
+        let rows = builder.build()?;
+
+        for constraint in &self.constraints {
+            let columns: Vec<_> = constraint
+                .columns
+                .iter()
+                .map(|column| Expr::Column(Column::from_name(column)))
+                .collect();
+            let not_null = columns
+                .iter()
+                .map(|column| column.clone().is_not_null())
+                .reduce(Expr::and)
+                .unwrap_or(lit(true));
+
+            let logical = LogicalPlanBuilder::from(rows.clone())
+                .filter(not_null)?
+                .aggregate(columns, vec![count(lit(1)).alias(KEY_COUNT_COLUMN)])?
+                .filter(Expr::Column(Column::from_name(KEY_COUNT_COLUMN)).gt(lit(1_i64)))?
+                .limit(0, Some(1))?
+                .build()?;
+
+            let physical = state.create_physical_plan(&logical).await?;
+            let duplicates = collect(physical, state.task_ctx()).await?;
+            if duplicates.iter().any(|batch| batch.num_rows() > 0) {
+                return Err(ExecError::UniqueViolation {
+                    constraint: constraint.name.clone(),
+                }
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,14 +1,21 @@
 use std::any::Any;
+use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
 
 use datafusion::arrow::datatypes::Schema;
 use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::Column;
+use datafusion::datasource::{provider_as_source, TableProvider};
 use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::context::SessionState;
 use datafusion::execution::TaskContext;
+use datafusion::logical_expr::{cast, when, LogicalPlanBuilder};
 use datafusion::physical_expr::PhysicalSortExpr;
 use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion::physical_plan::{
+    execute_stream,
     DisplayAs,
     DisplayFormatType,
     ExecutionPlan,
@@ -18,9 +25,12 @@ use datafusion::physical_plan::{
 };
 use datafusion::prelude::Expr;
 use datasources::native::access::NativeTableStorage;
-use futures::stream;
+use futures::{stream, StreamExt};
 use protogen::metastore::types::catalog::TableEntry;
+use protogen::metastore::types::options::UniqueConstraint;
 
+use super::constraint_check::RowConstraint;
+use super::unique_check::UniqueKeyCheck;
 use super::{new_operation_with_count_batch, GENERIC_OPERATION_AND_COUNT_PHYSICAL_SCHEMA};
 
 #[derive(Debug, Clone)]
@@ -28,6 +38,8 @@ pub struct UpdateExec {
     pub table: TableEntry,
     pub updates: Vec<(String, Expr)>,
     pub where_expr: Option<Expr>,
+    /// Constraints the updated rows must satisfy.
+    pub constraints: Vec<RowConstraint>,
 }
 
 impl ExecutionPlan for UpdateExec {
@@ -80,7 +92,12 @@ impl ExecutionPlan for UpdateExec {
             .get_extension::<NativeTableStorage>()
             .expect("context should have native table storage");
 
-        let stream = stream::once(update(self.clone(), storage));
+        let state = SessionState::new_with_config_rt(
+            context.session_config().clone(),
+            context.runtime_env(),
+        );
+
+        let stream = stream::once(update(self.clone(), state, storage));
 
         Ok(Box::pin(RecordBatchStreamAdapter::new(
             self.schema(),
@@ -101,14 +118,139 @@ impl DisplayAs for UpdateExec {
 
 async fn update(
     plan: UpdateExec,
+    state: SessionState,
     storage: impl AsRef<NativeTableStorage>,
 ) -> DataFusionResult<RecordBatch> {
     let storage = storage.as_ref();
 
+    let table = storage
+        .load_table(&plan.table)
+        .await
+        .map_err(|e| DataFusionError::Execution(format!("failed to update: {e}")))?;
+    // Held until the update is committed so no other write can commit a key
+    // the update was checked against.
+    let _guard = table.lock_unique_writes().await;
+    let table = table
+        .latest()
+        .await
+        .map_err(|e| DataFusionError::Execution(format!("failed to update: {e}")))?;
+    let unique_constraints = table.unique_constraints().to_vec();
+    let provider = table.into_table_provider();
+
+    if !plan.constraints.is_empty() {
+        check_constraints(&plan, &state, provider.clone()).await?;
+    }
+    if !unique_constraints.is_empty() {
+        check_unique_constraints(&plan, &state, provider, unique_constraints).await?;
+    }
+
     let num_updated = storage
-        .update_rows_where(&plan.table, plan.updates, plan.where_expr)
+        .update_rows_where(&state, &plan.table, plan.updates, plan.where_expr)
         .await
         .map_err(|e| DataFusionError::Execution(format!("failed to update: {e}")))?;
 
     Ok(new_operation_with_count_batch("update", num_updated as u64))
 }
+
+/// Check that the rows matched by the update satisfy the table's constraints
+/// once the update is applied.
+async fn check_constraints(
+    plan: &UpdateExec,
+    state: &SessionState,
+    provider: Arc<dyn TableProvider>,
+) -> DataFusionResult<()> {
+    let schema = provider.schema();
+
+    let updates = plan
+        .updates
+        .iter()
+        .map(|(column, expr)| {
+            let data_type = schema.field_with_name(column)?.data_type().clone();
+            Ok((column.as_str(), cast(expr.clone(), data_type)))
+        })
+        .collect::<DataFusionResult<HashMap<_, _>>>()?;
+
+    // Evaluate each constraint against the updated values of the row.
+    let exprs = plan
+        .constraints
+        .iter()
+        .enumerate()
+        .map(|(idx, constraint)| {
+            let expr = constraint.expr.clone().transform(&|expr| {
+                Ok(match expr {
+                    Expr::Column(column) => match updates.get(column.name.as_str()) {
+                        Some(update) => Transformed::Yes(update.clone()),
+                        None => Transformed::No(Expr::Column(column)),
+                    },
+                    expr => Transformed::No(expr),
+                })
+            })?;
+            Ok(expr.alias(RowConstraint::column_name(idx)))
+        })
+        .collect::<DataFusionResult<Vec<_>>>()?;
+
+    let mut builder = LogicalPlanBuilder::scan(
+        plan.table.meta.name.as_str(),
+        provider_as_source(provider),
+        None,
+    )?;
+    if let Some(where_expr) = &plan.where_expr {
+        builder = builder.filter(where_expr.clone())?;
+    }
+    let logical = builder.project(exprs)?.build()?;
+
+    let physical = state.create_physical_plan(&logical).await?;
+    let mut stream = execute_stream(physical, state.task_ctx())?;
+    while let Some(batch) = stream.next().await {
+        let batch = batch?;
+        for (idx, constraint) in plan.constraints.iter().enumerate() {
+            constraint.check_results(batch.column(idx))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that the update doesn't leave two rows with the same key.
+///
+/// Rows matched by the update take the updated values for their key
+/// columns, all other rows keep their existing values.
+async fn check_unique_constraints(
+    plan: &UpdateExec,
+    state: &SessionState,
+    provider: Arc<dyn TableProvider>,
+    unique_constraints: Vec<UniqueConstraint>,
+) -> DataFusionResult<()> {
+    let updates: HashMap<_, _> = plan
+        .updates
+        .iter()
+        .map(|(column, expr)| (column.as_str(), expr))
+        .collect();
+
+    let mut check = UniqueKeyCheck::new(unique_constraints, provider.schema());
+    // Keys can only be duplicated by updating their columns.
+    if !check
+        .key_columns()
+        .iter()
+        .any(|column| updates.contains_key(column))
+    {
+        return Ok(());
+    }
+
+    let rows = LogicalPlanBuilder::scan(
+        plan.table.meta.name.as_str(),
+        provider_as_source(provider),
+        None,
+    )?;
+    check.add_rows(rows, |column| {
+        let existing = Expr::Column(Column::from_name(column));
+        Ok(match (updates.get(column), &plan.where_expr) {
+            (Some(update), Some(where_expr)) => {
+                when(where_expr.clone(), (*update).clone()).otherwise(existing)?
+            }
+            (Some(update), None) => (*update).clone(),
+            (None, _) => existing,
+        })
+    })?;
+    check.check(state).await
+}
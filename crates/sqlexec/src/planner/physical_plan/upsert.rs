@@ -0,0 +1,303 @@
+use std::any::Any;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, BooleanArray};
+use datafusion::arrow::compute::filter_record_batch;
+use datafusion::arrow::datatypes::Schema;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::arrow::row::{RowConverter, SortField};
+use datafusion::common::Column;
+use datafusion::dataframe::DataFrame;
+use datafusion::datasource::{provider_as_source, MemTable};
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::context::SessionState;
+use datafusion::execution::TaskContext;
+use datafusion::logical_expr::{LogicalPlanBuilder, TableSource};
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    collect,
+    DisplayAs,
+    DisplayFormatType,
+    ExecutionPlan,
+    Partitioning,
+    SendableRecordBatchStream,
+    Statistics,
+};
+use datafusion::prelude::Expr;
+use datasources::native::access::{MergeClause, NativeTable, NativeTableStorage};
+use futures::stream;
+use protogen::metastore::types::catalog::TableEntry;
+
+use super::constraint_check::{ConstraintCheckExec, RowConstraint};
+use super::merge::{self, MergeExec};
+use super::{new_operation_with_count_batch, GENERIC_OPERATION_AND_COUNT_PHYSICAL_SCHEMA};
+use crate::errors::ExecError;
+
+/// Qualifier for columns of the proposed rows in the updates of an
+/// `INSERT ... ON CONFLICT`.
+pub const EXCLUDED_ALIAS: &str = "excluded";
+
+#[derive(Debug, Clone)]
+pub struct UpsertExec {
+    pub table: TableEntry,
+    pub source: Arc<dyn ExecutionPlan>,
+    pub conflict_columns: Vec<String>,
+    pub updates: Vec<(String, Expr)>,
+    pub update_predicate: Option<Expr>,
+    pub constraints: Vec<RowConstraint>,
+}
+
+impl ExecutionPlan for UpsertExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> Arc<Schema> {
+        GENERIC_OPERATION_AND_COUNT_PHYSICAL_SCHEMA.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.source.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        if children.len() != 1 {
+            return Err(DataFusionError::Plan(
+                "UpsertExec expects exactly one child".to_string(),
+            ));
+        }
+        Ok(Arc::new(UpsertExec {
+            source: children[0].clone(),
+            ..self.as_ref().clone()
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Execution(
+                "UpsertExec only supports 1 partition".to_string(),
+            ));
+        }
+
+        let storage = context
+            .session_config()
+            .get_extension::<NativeTableStorage>()
+            .expect("context should have native table storage");
+
+        let stream = stream::once(upsert(self.clone(), context, storage));
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+
+    fn statistics(&self) -> DataFusionResult<Statistics> {
+        Ok(Statistics::new_unknown(self.schema().as_ref()))
+    }
+}
+
+impl DisplayAs for UpsertExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "UpsertExec: conflict_columns=[{}]",
+            self.conflict_columns.join(", ")
+        )
+    }
+}
+
+async fn upsert(
+    plan: UpsertExec,
+    context: Arc<TaskContext>,
+    storage: impl AsRef<NativeTableStorage>,
+) -> DataFusionResult<RecordBatch> {
+    let storage = storage.as_ref();
+    let state =
+        SessionState::new_with_config_rt(context.session_config().clone(), context.runtime_env());
+
+    // Proposed rows are collected up front since the merge needs them as a
+    // data frame.
+    let (input, check) = if plan.constraints.is_empty() {
+        (plan.source.clone(), None)
+    } else {
+        let check = Arc::new(ConstraintCheckExec::try_new(
+            plan.source.clone(),
+            plan.constraints.clone(),
+        )?);
+        (check.clone() as Arc<dyn ExecutionPlan>, Some(check))
+    };
+    let schema = input.schema();
+    let batches = collect(input, context).await.map_err(|e| {
+        match check.as_ref().and_then(|check| check.take_violation()) {
+            Some(violation) => violation.into(),
+            None => e,
+        }
+    })?;
+    let batches = dedup_proposed_rows(&plan, &schema, batches)?;
+
+    let source = provider_as_source(Arc::new(MemTable::try_new(schema.clone(), vec![batches])?));
+
+    let table = storage
+        .load_table(&plan.table)
+        .await
+        .map_err(|e| DataFusionError::Execution(format!("failed to insert: {e}")))?;
+    // Held until the rows are committed so no other write can commit a key
+    // the rows were checked against.
+    let _guard = table.lock_unique_writes().await;
+    let table = table
+        .latest()
+        .await
+        .map_err(|e| DataFusionError::Execution(format!("failed to insert: {e}")))?;
+    check_unique_constraints(&plan, &state, table, &schema, source.clone()).await?;
+    let source = LogicalPlanBuilder::scan(EXCLUDED_ALIAS, source, None)?.build()?;
+
+    let num_rows = storage
+        .upsert_rows(
+            &state,
+            &plan.table,
+            DataFrame::new(state.clone(), source),
+            EXCLUDED_ALIAS,
+            &plan.conflict_columns,
+            plan.updates,
+            plan.update_predicate,
+        )
+        .await
+        .map_err(|e| DataFusionError::Execution(format!("failed to insert: {e}")))?;
+
+    Ok(new_operation_with_count_batch("insert", num_rows as u64))
+}
+
+/// Remove proposed rows with the same key as an earlier proposed row.
+///
+/// Following postgres, these are skipped for `DO NOTHING` since they
+/// conflict with the earlier row once it's inserted, and error for `DO
+/// UPDATE` since the same row would be affected twice. Rows with a null in
+/// the key never conflict.
+fn dedup_proposed_rows(
+    plan: &UpsertExec,
+    schema: &Schema,
+    batches: Vec<RecordBatch>,
+) -> DataFusionResult<Vec<RecordBatch>> {
+    let indices = plan
+        .conflict_columns
+        .iter()
+        .map(|column| schema.index_of(column))
+        .collect::<Result<Vec<_>, _>>()?;
+    let converter = RowConverter::new(
+        indices
+            .iter()
+            .map(|idx| SortField::new(schema.field(*idx).data_type().clone()))
+            .collect(),
+    )?;
+
+    let mut seen = HashSet::new();
+    batches
+        .into_iter()
+        .map(|batch| {
+            let columns: Vec<_> = indices
+                .iter()
+                .map(|idx| batch.column(*idx).clone())
+                .collect();
+            let keys = converter.convert_columns(&columns)?;
+            let keep = (0..batch.num_rows())
+                .map(|row| {
+                    if columns.iter().any(|column| column.is_null(row))
+                        || seen.insert(keys.row(row).owned())
+                    {
+                        Ok(true)
+                    } else if plan.updates.is_empty() {
+                        Ok(false)
+                    } else {
+                        Err(ExecError::UpsertAffectsRowTwice.into())
+                    }
+                })
+                .collect::<DataFusionResult<Vec<_>>>()?;
+            Ok(filter_record_batch(&batch, &BooleanArray::from(keep))?)
+        })
+        .collect()
+}
+
+/// Check that the upsert doesn't leave two rows with the same key for any of
+/// the table's keys.
+///
+/// The upsert is checked as the merge it's executed as.
+async fn check_unique_constraints(
+    plan: &UpsertExec,
+    state: &SessionState,
+    table: NativeTable,
+    schema: &Schema,
+    source: Arc<dyn TableSource>,
+) -> DataFusionResult<()> {
+    let unique_constraints = table.unique_constraints().to_vec();
+    if unique_constraints.is_empty() {
+        return Ok(());
+    }
+
+    let target_alias = plan.table.meta.name.clone();
+    let qualified =
+        |alias: &str, column: &str| Expr::Column(Column::new(Some(alias.to_string()), column));
+    let on = plan
+        .conflict_columns
+        .iter()
+        .map(|column| qualified(&target_alias, column).eq(qualified(EXCLUDED_ALIAS, column)))
+        .reduce(Expr::and)
+        .ok_or_else(|| DataFusionError::Plan("missing conflict columns".to_string()))?;
+
+    let mut clauses = Vec::new();
+    if !plan.updates.is_empty() {
+        clauses.push(MergeClause::MatchedUpdate {
+            predicate: plan.update_predicate.clone(),
+            updates: plan.updates.clone(),
+        });
+    }
+    clauses.push(MergeClause::NotMatchedInsert {
+        predicate: None,
+        values: schema
+            .fields()
+            .iter()
+            .map(|field| {
+                (
+                    field.name().clone(),
+                    qualified(EXCLUDED_ALIAS, field.name()),
+                )
+            })
+            .collect(),
+    });
+
+    let merge = MergeExec {
+        table: plan.table.clone(),
+        source: plan.source.clone(),
+        source_alias: EXCLUDED_ALIAS.to_string(),
+        target_alias,
+        on,
+        clauses,
+        constraints: Vec::new(),
+    };
+    merge::check_unique_constraints(
+        &merge,
+        state,
+        table.into_table_provider(),
+        source,
+        unique_constraints,
+    )
+    .await
+}
@@ -30,6 +30,10 @@ where
 
 /// Replace `CAST('table_name' as [REGCLASS | OID])` expressions with the oid of the
 /// table.
+///
+/// Unqualified sequence names passed to sequence functions (e.g.
+/// `nextval('my_seq')`) are replaced with the oid of the sequence as well, so
+/// they're resolved using the session's search path.
 pub struct CastOIDReplacer<'a> {
     pub ctx: &'a LocalSessionContext,
 }
@@ -51,6 +55,38 @@ impl<'a> ast::VisitorMut for CastOIDReplacer<'a> {
         }
 
         let replace_expr = match expr {
+            ast::Expr::Function(func) if is_sequence_function(&func.name) => {
+                let Some(ast::FunctionArg::Unnamed(ast::FunctionArgExpr::Expr(arg))) =
+                    func.args.first_mut()
+                else {
+                    return ControlFlow::Continue(());
+                };
+                match arg {
+                    // Qualified names are resolved when planning the function.
+                    ast::Expr::Value(ast::Value::SingleQuotedString(name))
+                        if !name.contains('.') =>
+                    {
+                        let catalog = self.ctx.get_session_catalog();
+                        let oid = self.ctx.implicit_search_paths().iter().find_map(|schema| {
+                            catalog
+                                .resolve_sequence(DEFAULT_CATALOG, schema, name)
+                                .map(|seq| seq.meta.id)
+                        });
+                        match oid {
+                            Some(oid) => {
+                                *arg = ast::Expr::Value(ast::Value::Number(oid.to_string(), false))
+                            }
+                            None => {
+                                return ControlFlow::Break(PreprocessError::MissingRelation(
+                                    name.clone(),
+                                ))
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+                return ControlFlow::Continue(());
+            }
             ast::Expr::Cast {
                 expr: inner_expr,
                 data_type,
@@ -89,6 +125,17 @@ impl<'a> ast::VisitorMut for CastOIDReplacer<'a> {
     }
 }
 
+/// Check if the function takes a sequence as its first argument.
+fn is_sequence_function(name: &ast::ObjectName) -> bool {
+    match name.0.as_slice() {
+        [func] => matches!(
+            func.value.to_lowercase().as_str(),
+            "nextval" | "currval" | "setval"
+        ),
+        _ => false,
+    }
+}
+
 /// Replace `E'my_string'` with `"my_string"`.
 ///
 /// TODO: Datafusion should be updated to properly handle escaped strings. This
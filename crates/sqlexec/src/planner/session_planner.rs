@@ -1,19 +1,38 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
 
+use datafusion::arrow::compute::kernels::cast_utils::string_to_timestamp_nanos;
 use datafusion::arrow::datatypes::{
     DataType,
     Field,
+    IntervalMonthDayNanoType,
+    IntervalUnit,
     Schema,
     TimeUnit,
     DECIMAL128_MAX_PRECISION,
     DECIMAL_DEFAULT_SCALE,
 };
 use datafusion::common::parsers::CompressionTypeVariant;
-use datafusion::common::{OwnedSchemaReference, OwnedTableReference, ToDFSchema};
-use datafusion::logical_expr::{cast, col, LogicalPlanBuilder};
+use datafusion::common::{DFSchema, OwnedSchemaReference, OwnedTableReference, ToDFSchema};
+use datafusion::datasource::file_format::csv::CsvFormat;
+use datafusion::datasource::file_format::json::JsonFormat;
+use datafusion::datasource::file_format::parquet::ParquetFormat;
+use datafusion::datasource::file_format::FileFormat;
+use datafusion::datasource::provider_as_source;
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::expr::Placeholder;
+use datafusion::logical_expr::{
+    cast,
+    col,
+    ident,
+    Expr,
+    ExprSchemable,
+    LogicalPlan as DfLogicalPlan,
+    LogicalPlanBuilder,
+};
+use datafusion::scalar::ScalarValue;
 use datafusion::sql::planner::{object_name_to_table_reference, PlannerContext};
 use datafusion::sql::TableReference;
 use datafusion_ext::conversion::convert;
@@ -27,16 +46,24 @@ use datasources::common::ssh::{SshConnection, SshConnectionParameters};
 use datasources::common::url::{DatasourceUrl, DatasourceUrlType};
 use datasources::debug::DebugTableType;
 use datasources::lake::delta::access::{load_table_direct, DeltaLakeAccessor};
+use datasources::lake::delta::version::TableVersion;
+use datasources::lake::iceberg::catalog::IcebergCatalogAccessor;
 use datasources::lake::iceberg::table::IcebergTable;
 use datasources::lake::storage_options_into_object_store;
 use datasources::lance::LanceTable;
 use datasources::mongodb::{MongoDbAccessor, MongoDbConnection};
 use datasources::mysql::{MysqlAccessor, MysqlDbConnection, MysqlTableAccess};
+use datasources::native::access::MergeClause;
 use datasources::object_store::azure::AzureStoreAccess;
 use datasources::object_store::gcs::GcsStoreAccess;
 use datasources::object_store::local::LocalStoreAccess;
 use datasources::object_store::s3::S3StoreAccess;
-use datasources::object_store::{file_type_from_path, ObjStoreAccess, ObjStoreAccessor};
+use datasources::object_store::{
+    file_type_from_path,
+    ObjStoreAccess,
+    ObjStoreAccessor,
+    ObjStoreTableProvider,
+};
 use datasources::postgres::{PostgresAccess, PostgresDbConnection};
 use datasources::snowflake::{SnowflakeAccessor, SnowflakeDbConnection, SnowflakeTableAccess};
 use datasources::sqlserver::SqlServerAccess;
@@ -49,6 +76,7 @@ use parser::sqlparser::ast::{
     ColumnOption,
     DescribeAlias,
     FromTable,
+    GeneratedAs,
     Ident,
     ObjectName,
     ObjectType,
@@ -61,26 +89,45 @@ use parser::{
     AlterTableStmtExtension,
     AlterTunnelAction,
     AlterTunnelStmt,
+    CommentObject,
+    CommentStmt,
+    CopyFromStmt,
     CopyToSource,
     CopyToStmt,
     CreateCredentialStmt,
     CreateCredentialsStmt,
     CreateExternalDatabaseStmt,
     CreateExternalTableStmt,
+    CreateRoleStmt,
     CreateTunnelStmt,
     DropCredentialsStmt,
     DropDatabaseStmt,
+    DropMaterializedViewStmt,
+    DropRoleStmt,
     DropTunnelStmt,
+    GrantObject,
+    GrantStmt,
+    OptimizeStmt,
+    RefreshMaterializedViewStmt,
+    RestoreTableStmt,
+    RestoreTarget,
+    RevokeStmt,
     StatementWithExtensions,
+    VacuumStmt,
 };
 use protogen::metastore::types::catalog::{
     CatalogEntry,
     DatabaseEntry,
+    EntryType,
+    Privilege,
     RuntimePreference,
     SourceAccessMode,
     TableEntry,
 };
 use protogen::metastore::types::options::{
+    is_widening_type_change,
+    AutoCompactionPolicy,
+    CheckConstraint,
     CopyToDestinationOptions,
     CopyToDestinationOptionsAzure,
     CopyToDestinationOptionsGcs,
@@ -90,6 +137,7 @@ use protogen::metastore::types::options::{
     CopyToFormatOptionsBson,
     CopyToFormatOptionsCsv,
     CopyToFormatOptionsDelta,
+    CopyToFormatOptionsIceberg,
     CopyToFormatOptionsJson,
     CopyToFormatOptionsLance,
     CopyToFormatOptionsParquet,
@@ -105,6 +153,7 @@ use protogen::metastore::types::options::{
     DatabaseOptionsClickhouse,
     DatabaseOptionsDebug,
     DatabaseOptionsDeltaLake,
+    DatabaseOptionsIceberg,
     DatabaseOptionsMongoDb,
     DatabaseOptionsMysql,
     DatabaseOptionsPostgres,
@@ -113,6 +162,10 @@ use protogen::metastore::types::options::{
     DatabaseOptionsSqlite,
     DeltaLakeCatalog,
     DeltaLakeUnityCatalog,
+    IcebergCatalog,
+    IcebergRestCatalog,
+    InternalColumnDefinition,
+    MaterializedViewOptions,
     StorageOptions,
     TableOptionsBigQuery,
     TableOptionsCassandra,
@@ -120,6 +173,7 @@ use protogen::metastore::types::options::{
     TableOptionsDebug,
     TableOptionsExcel,
     TableOptionsGcs,
+    TableOptionsInternal,
     TableOptionsLocal,
     TableOptionsMongoDb,
     TableOptionsMysql,
@@ -133,8 +187,14 @@ use protogen::metastore::types::options::{
     TunnelOptionsDebug,
     TunnelOptionsInternal,
     TunnelOptionsSsh,
+    UniqueConstraint,
+};
+use protogen::metastore::types::service::{
+    self,
+    AlterDatabaseOperation,
+    AlterTableOperation,
+    PrivilegeObject,
 };
-use protogen::metastore::types::service::{AlterDatabaseOperation, AlterTableOperation};
 use sqlbuiltins::builtins::{CURRENT_SESSION_SCHEMA, DEFAULT_CATALOG};
 use sqlbuiltins::validation::{
     validate_copyto_dest_creds_support,
@@ -148,18 +208,29 @@ use tracing::debug;
 
 use super::context_builder::PartialContextProvider;
 use super::extension::ExtensionNode;
+use super::physical_plan::constraint_check::{RowConstraint, RowConstraintKind};
+use super::physical_plan::copy_to::object_store_access;
 use super::physical_plan::remote_scan::ProviderReference;
-use crate::context::local::LocalSessionContext;
+use super::physical_plan::upsert::EXCLUDED_ALIAS;
+use crate::context::local::{LocalSessionContext, TransactionStatus};
+use crate::dispatch::plan_materialized_view;
 use crate::planner::errors::{internal, PlanError, Result};
 use crate::planner::logical_plan::{
     AlterDatabase,
     AlterTable,
     AlterTunnelRotateKeys,
+    CommentOn,
+    CopyFrom,
+    CopyStdioFormat,
+    CopyStdioOptions,
+    CopyStdioPlan,
     CopyTo,
     CreateCredentials,
     CreateExternalDatabase,
     CreateExternalTable,
+    CreateRole,
     CreateSchema,
+    CreateSequence,
     CreateTable,
     CreateTempTable,
     CreateTunnel,
@@ -168,17 +239,28 @@ use crate::planner::logical_plan::{
     DescribeTable,
     DropCredentials,
     DropDatabase,
+    DropRole,
     DropSchemas,
+    DropSequences,
     DropTables,
     DropTunnel,
     DropViews,
     FullObjectReference,
+    GrantPrivileges,
     Insert,
     LogicalPlan,
+    Merge,
+    OptimizeTable,
+    OwnedFullObjectReference,
+    RefreshMaterializedView,
+    RestoreTable,
+    RevokePrivileges,
     SetVariable,
     ShowVariable,
     TransactionPlan,
     Update,
+    Upsert,
+    VacuumTable,
 };
 use crate::planner::preprocess::{preprocess, CastOIDReplacer, EscapedStringToDoubleQuoted};
 use crate::remote::table::StubRemoteTableProvider;
@@ -187,6 +269,9 @@ use crate::resolve::{EntryResolver, ResolvedEntry};
 /// Plan SQL statements for a session.
 pub struct SessionPlanner<'a> {
     ctx: &'a LocalSessionContext,
+    /// Whether to check the session user's privileges on objects referenced
+    /// in the statement.
+    check_privileges: bool,
 }
 
 struct PlanCredentialArgs {
@@ -227,7 +312,33 @@ impl From<CreateCredentialStmt> for PlanCredentialArgs {
 
 impl<'a> SessionPlanner<'a> {
     pub fn new(ctx: &'a LocalSessionContext) -> Self {
-        SessionPlanner { ctx }
+        SessionPlanner {
+            ctx,
+            check_privileges: true,
+        }
+    }
+
+    /// Create a planner for the body of a view.
+    ///
+    /// Objects referenced by the view are accessed with the privileges of
+    /// the view, so privileges are only checked on the view itself.
+    pub fn new_for_view(ctx: &'a LocalSessionContext) -> Self {
+        SessionPlanner {
+            ctx,
+            check_privileges: false,
+        }
+    }
+
+    fn new_context_provider<'b>(
+        &'b self,
+        state: &'b SessionState,
+    ) -> Result<PartialContextProvider<'b>> {
+        let provider = PartialContextProvider::new(self.ctx, state)?;
+        Ok(if self.check_privileges {
+            provider
+        } else {
+            provider.without_privilege_checks()
+        })
     }
 
     pub async fn plan_ast(&self, mut statement: StatementWithExtensions) -> Result<LogicalPlan> {
@@ -239,6 +350,14 @@ impl<'a> SessionPlanner<'a> {
             preprocess(inner, &mut EscapedStringToDoubleQuoted)?;
         }
 
+        if let Some(role) = self.ctx.get_session_catalog().current_role() {
+            if !is_allowed_for_role(&statement) {
+                return Err(PlanError::StatementNotAllowedForRole {
+                    role: role.meta.name.clone(),
+                });
+            }
+        }
+
         match statement {
             StatementWithExtensions::Statement(stmt) => self.plan_statement(stmt).await,
             StatementWithExtensions::CreateExternalTable(stmt) => {
@@ -260,6 +379,21 @@ impl<'a> SessionPlanner<'a> {
             }
             StatementWithExtensions::DropCredentials(stmt) => self.plan_drop_credentials(stmt),
             StatementWithExtensions::CopyTo(stmt) => self.plan_copy_to(stmt).await,
+            StatementWithExtensions::CopyFrom(stmt) => self.plan_copy_from(stmt).await,
+            StatementWithExtensions::CreateRole(stmt) => self.plan_create_role(stmt),
+            StatementWithExtensions::DropRole(stmt) => self.plan_drop_role(stmt),
+            StatementWithExtensions::Grant(stmt) => self.plan_grant(stmt),
+            StatementWithExtensions::Revoke(stmt) => self.plan_revoke(stmt),
+            StatementWithExtensions::RestoreTable(stmt) => self.plan_restore_table(stmt),
+            StatementWithExtensions::Vacuum(stmt) => self.plan_vacuum(stmt),
+            StatementWithExtensions::Optimize(stmt) => self.plan_optimize(stmt),
+            StatementWithExtensions::RefreshMaterializedView(stmt) => {
+                self.plan_refresh_materialized_view(stmt).await
+            }
+            StatementWithExtensions::DropMaterializedView(stmt) => {
+                self.plan_drop_materialized_view(stmt)
+            }
+            StatementWithExtensions::Comment(stmt) => self.plan_comment(stmt),
         }
     }
 
@@ -401,6 +535,33 @@ impl<'a> SessionPlanner<'a> {
                     storage_options,
                 })
             }
+            DatabaseOptions::ICEBERG => {
+                let catalog = match m.remove_required::<String>("catalog_type")?.as_str() {
+                    "rest" => IcebergCatalog::Rest(IcebergRestCatalog {
+                        uri: m.remove_required("uri")?,
+                        warehouse: m.remove_optional("warehouse")?,
+                        token: m.remove_optional("token")?,
+                    }),
+                    other => return Err(internal!("Unknown catalog type: {}", other)),
+                };
+
+                let mut storage_options = StorageOptions::try_from(m)?;
+                if let Some(creds) = creds_options {
+                    storage_options_with_credentials(&mut storage_options, creds);
+                }
+
+                // Try connecting to validate.
+                IcebergCatalogAccessor::connect(&catalog, storage_options.clone())
+                    .await
+                    .map_err(|e| PlanError::InvalidExternalDatabase {
+                        source: Box::new(e),
+                    })?;
+
+                DatabaseOptions::Iceberg(DatabaseOptionsIceberg {
+                    catalog,
+                    storage_options,
+                })
+            }
             DatabaseOptions::SQL_SERVER => {
                 let connection_string: String = m.remove_required("connection_string")?;
 
@@ -474,6 +635,7 @@ impl<'a> SessionPlanner<'a> {
         m: &mut StatementOptions,
         creds_options: Option<CredentialsOptions>,
         tunnel_options: Option<TunnelOptions>,
+        table_schema: Option<&Schema>,
     ) -> Result<TableOptionsV0> {
         Ok(match datasource {
             TableOptionsV0::DEBUG => {
@@ -873,7 +1035,11 @@ impl<'a> SessionPlanner<'a> {
                 } else {
                     let url = DatasourceUrl::try_new(&location)?;
                     let store = storage_options_into_object_store(&url, &storage_options)?;
-                    let _table = IcebergTable::open(url, store).await?;
+                    // When columns are provided, a new table is created at
+                    // the location when the plan is executed.
+                    if table_schema.is_none() {
+                        let _table = IcebergTable::open(url, store).await?;
+                    }
 
                     TableOptionsV0::Iceberg(TableOptionsObjectStore {
                         location,
@@ -1052,7 +1218,13 @@ impl<'a> SessionPlanner<'a> {
         // The mutator uses the new table options, but the catalog uses the old ones.
         // so we need to convert the old options to the new ones.
         let external_table_options = self
-            .get_tbl_opts_from_v0(datasource.as_str(), m, creds_options, tunnel_options)
+            .get_tbl_opts_from_v0(
+                datasource.as_str(),
+                m,
+                creds_options,
+                tunnel_options,
+                schema.as_ref(),
+            )
             .await?;
 
         let table_name = object_name_to_table_ref(stmt.name)?;
@@ -1161,7 +1333,7 @@ impl<'a> SessionPlanner<'a> {
 
     async fn plan_statement(&self, statement: ast::Statement) -> Result<LogicalPlan> {
         let state = self.ctx.df_ctx().state();
-        let mut context_provider = PartialContextProvider::new(self.ctx, &state)?;
+        let mut context_provider = self.new_context_provider(&state)?;
         match statement {
             ast::Statement::StartTransaction { .. } => Ok(TransactionPlan::Begin.into()),
             ast::Statement::Commit { .. } => Ok(TransactionPlan::Commit.into()),
@@ -1234,6 +1406,79 @@ impl<'a> SessionPlanner<'a> {
                 .into_logical_plan())
             }
 
+            // CREATE SEQUENCE
+            ast::Statement::CreateSequence {
+                temporary,
+                if_not_exists,
+                name,
+                data_type,
+                sequence_options,
+                owned_by,
+            } => {
+                if temporary {
+                    return Err(PlanError::UnsupportedFeature("temporary sequences"));
+                }
+                validate_object_name(&name)?;
+                let seq_ref = self
+                    .ctx
+                    .resolve_table_ref(object_name_to_table_ref(name)?)?;
+                let data_type = match data_type {
+                    Some(data_type) => convert_data_type(&data_type)?,
+                    None => DataType::Int64,
+                };
+
+                let mut sequence = plan_create_sequence(
+                    &seq_ref.schema,
+                    &seq_ref.name,
+                    &data_type,
+                    sequence_options,
+                )?;
+                sequence.if_not_exists = if_not_exists;
+                sequence.owned_by = match owned_by {
+                    None => None,
+                    Some(ObjectName(idents))
+                        if idents.len() == 1 && idents[0].value.eq_ignore_ascii_case("none") =>
+                    {
+                        None
+                    }
+                    Some(ObjectName(mut idents)) => {
+                        let (schema, table, column) = match idents.len() {
+                            2 => {
+                                let column = idents.pop().unwrap();
+                                let table = idents.pop().unwrap();
+                                (None, table, column)
+                            }
+                            3 => {
+                                let column = idents.pop().unwrap();
+                                let table = idents.pop().unwrap();
+                                let schema = idents.pop().unwrap();
+                                (Some(schema), table, column)
+                            }
+                            _ => {
+                                return Err(PlanError::String(format!(
+                                    "Invalid OWNED BY option: {}, expected table.column",
+                                    ObjectName(idents)
+                                )))
+                            }
+                        };
+                        if let Some(schema) = schema {
+                            if normalize_ident(schema) != seq_ref.schema {
+                                return Err(PlanError::String(
+                                    "Sequence must be in the same schema as the table it is owned by"
+                                        .to_string(),
+                                ));
+                            }
+                        }
+                        Some(service::SequenceOwnerColumn {
+                            table: normalize_ident(table),
+                            column: normalize_ident(column),
+                        })
+                    }
+                };
+
+                Ok(CreateSequence { sequence }.into_logical_plan())
+            }
+
             // Normal tables OR Tables generated from a source query.
             // CREATE TABLE
             // CREATE TABLE table2 AS (SELECT * FROM table1);
@@ -1244,17 +1489,49 @@ impl<'a> SessionPlanner<'a> {
                 engine: None,
                 name,
                 columns,
+                constraints,
                 query,
                 temporary,
+                partition_by,
                 ..
             } => {
                 validate_object_name(&name)?;
                 let table_name = object_name_to_table_ref(name)?;
 
-                let (source, arrow_cols) = if let Some(q) = query {
-                    let mut ctx = context_provider;
+                // Column defaults and check constraints (with their name if
+                // given, and the name to derive one from otherwise).
+                let mut defaults = Vec::new();
+                let mut checks = Vec::new();
+                // Primary keys and unique constraints, with their name if
+                // given. These aren't checked on insert, but are used as the
+                // targets of `INSERT ... ON CONFLICT`.
+                let mut uniques = Vec::new();
+                // SERIAL and identity columns, with the type and options of
+                // the sequence generating their values.
+                let mut identities = Vec::new();
+                for constraint in constraints {
+                    match constraint {
+                        ast::TableConstraint::Check { name, expr } => checks.push((
+                            name.map(normalize_ident),
+                            format!("{}_check", table_name.table()),
+                            *expr,
+                        )),
+                        ast::TableConstraint::Unique {
+                            name,
+                            columns,
+                            is_primary,
+                            ..
+                        } => uniques.push((
+                            name.map(normalize_ident),
+                            columns.into_iter().map(normalize_ident).collect(),
+                            is_primary,
+                        )),
+                        _ => (),
+                    }
+                }
 
-                    let mut planner = SqlQueryPlanner::new(&mut ctx);
+                let (source, arrow_cols) = if let Some(q) = query {
+                    let mut planner = SqlQueryPlanner::new(&mut context_provider);
 
                     let source = planner.query_to_plan(*q).await?;
                     let df_fields = source.schema().fields();
@@ -1262,7 +1539,9 @@ impl<'a> SessionPlanner<'a> {
                     let mut columns = columns.into_iter();
                     let mut fields = Vec::with_capacity(df_fields.len());
                     for df_field in df_fields {
-                        let field = df_field.field().as_ref().clone();
+                        // Nullability of the source isn't a constraint on
+                        // the table.
+                        let field = df_field.field().as_ref().clone().with_nullable(true);
                         let field = if let Some(column) = columns.next() {
                             // If we have a cast for the column, we can update the schema.
                             validate_ident(&column.name)?;
@@ -1298,14 +1577,90 @@ impl<'a> SessionPlanner<'a> {
                     for column in columns.into_iter() {
                         validate_ident(&column.name)?;
                         let name = normalize_ident(column.name);
-                        let data_type = convert_data_type(&column.data_type)?;
-                        let field = Field::new(name, data_type, /* nullable = */ true);
-                        arrow_cols.push(field);
+                        let serial = serial_data_type(&column.data_type);
+                        let data_type = match &serial {
+                            Some(data_type) => data_type.clone(),
+                            None => convert_data_type(&column.data_type)?,
+                        };
+                        // SERIAL columns are identity columns generated by
+                        // default.
+                        let mut identity = serial.map(|_| (false, Vec::new()));
+                        let mut nullable = true;
+                        for option in column.options {
+                            match option.option {
+                                ColumnOption::Null => nullable = true,
+                                ColumnOption::NotNull => nullable = false,
+                                ColumnOption::Default(expr) => defaults.push((name.clone(), expr)),
+                                ColumnOption::Check(expr) => checks.push((
+                                    option.name.map(normalize_ident),
+                                    format!("{}_{}_check", table_name.table(), name),
+                                    expr,
+                                )),
+                                ColumnOption::Unique { is_primary, .. } => uniques.push((
+                                    option.name.map(normalize_ident),
+                                    vec![name.clone()],
+                                    is_primary,
+                                )),
+                                ColumnOption::Generated {
+                                    generated_as:
+                                        generated_as @ (GeneratedAs::Always | GeneratedAs::ByDefault),
+                                    sequence_options,
+                                    generation_expr: None,
+                                    ..
+                                } => {
+                                    if identity.is_some() {
+                                        return Err(PlanError::String(format!(
+                                            "Multiple identity specifications for column '{name}'"
+                                        )));
+                                    }
+                                    identity = Some((
+                                        matches!(generated_as, GeneratedAs::Always),
+                                        sequence_options.unwrap_or_default(),
+                                    ));
+                                }
+                                ColumnOption::Generated { .. } => {
+                                    return Err(PlanError::UnsupportedFeature("generated columns"))
+                                }
+                                _ => (),
+                            }
+                        }
+                        if let Some((always, options)) = identity {
+                            if defaults.iter().any(|(column, _)| column == &name) {
+                                return Err(PlanError::String(format!(
+                                    "Both default and identity specified for column '{name}'"
+                                )));
+                            }
+                            // Identity columns are implicitly NOT NULL.
+                            nullable = false;
+                            identities.push((name.clone(), data_type.clone(), always, options));
+                        }
+                        arrow_cols.push(Field::new(name, data_type, nullable));
                     }
                     (None, arrow_cols)
                 };
 
                 if temporary {
+                    if !defaults.is_empty() || !checks.is_empty() || !uniques.is_empty() {
+                        return Err(PlanError::UnsupportedFeature(
+                            "DEFAULT, CHECK and UNIQUE constraints on temporary tables",
+                        ));
+                    }
+                    if !identities.is_empty() {
+                        return Err(PlanError::UnsupportedFeature(
+                            "SERIAL and identity columns on temporary tables",
+                        ));
+                    }
+                    if partition_by.is_some() {
+                        return Err(PlanError::UnsupportedFeature(
+                            "PARTITION BY on temporary tables",
+                        ));
+                    }
+                    // Not enforced for temporary tables.
+                    let arrow_cols: Vec<_> = arrow_cols
+                        .into_iter()
+                        .map(|field| field.with_nullable(true))
+                        .collect();
+
                     let table_name = match table_name {
                         TableReference::Bare { table } => table.into_owned(),
                         _ => return Err(internal!("cannot specify schema with temporary tables")),
@@ -1327,19 +1682,164 @@ impl<'a> SessionPlanner<'a> {
 
                     Ok(plan.into_logical_plan())
                 } else {
+                    let unique_constraints =
+                        plan_unique_constraints(table_name.table(), &arrow_cols, uniques)?;
+                    let partition_columns =
+                        plan_partition_columns(table_name.table(), &arrow_cols, partition_by)?;
+                    // Primary key columns are implicitly NOT NULL.
+                    let arrow_cols: Vec<_> = arrow_cols
+                        .into_iter()
+                        .map(|field| {
+                            let is_key = unique_constraints.iter().any(|unique| {
+                                unique.primary_key && unique.columns.contains(field.name())
+                            });
+                            if is_key {
+                                field.with_nullable(false)
+                            } else {
+                                field
+                            }
+                        })
+                        .collect();
+
+                    let (mut column_defaults, check_constraints) = plan_create_table_constraints(
+                        &mut context_provider,
+                        &arrow_cols,
+                        defaults,
+                        checks,
+                    )
+                    .await?;
+
+                    let tbl_reference = self.ctx.resolve_table_ref(table_name)?;
+                    let (column_sequences, generated_always) = self.plan_column_sequences(
+                        &tbl_reference,
+                        if_not_exists,
+                        or_replace,
+                        identities,
+                        &mut column_defaults,
+                    )?;
+
                     let df_schema = Schema::new(arrow_cols.clone());
                     let df_schema = df_schema.to_dfschema_ref()?;
                     let create_table = CreateTable {
-                        tbl_reference: self.ctx.resolve_table_ref(table_name)?,
+                        tbl_reference,
                         schema: df_schema,
                         if_not_exists,
                         or_replace,
+                        column_defaults,
+                        check_constraints,
+                        unique_constraints,
+                        partition_columns,
+                        column_sequences,
+                        generated_always,
+                        materialized_view: None,
                         source,
                     };
                     Ok(create_table.into_logical_plan())
                 }
             }
 
+            // Materialized views are native tables holding the results of
+            // the view's query.
+            ast::Statement::CreateView {
+                or_replace,
+                materialized: true,
+                name,
+                columns,
+                query,
+                options,
+                if_not_exists,
+                temporary,
+                ..
+            } => {
+                validate_object_name(&name)?;
+                let name = object_name_to_table_ref(name)?;
+
+                if temporary {
+                    return Err(PlanError::UnsupportedFeature(
+                        "temporary materialized views",
+                    ));
+                }
+
+                let refresh_interval_secs = match options {
+                    ast::CreateTableOptions::None => None,
+                    ast::CreateTableOptions::With(options) => plan_refresh_interval(options)?,
+                    ast::CreateTableOptions::Options(_) => {
+                        return Err(PlanError::UnsupportedFeature("materialized view OPTIONS"))
+                    }
+                };
+
+                if !matches!(
+                    query.body.as_ref(),
+                    ast::SetExpr::Values(_) | ast::SetExpr::Query(_) | ast::SetExpr::Select(_)
+                ) {
+                    return Err(PlanError::InvalidViewStatement {
+                        msg: "view body must either be a SELECT or VALUES statement",
+                    });
+                }
+
+                let sql = query.to_string();
+
+                let mut planner = SqlQueryPlanner::new(&mut context_provider);
+                let source = planner.query_to_plan(*query).await?;
+                let df_fields = source.schema().fields();
+
+                let columns: Vec<_> = columns
+                    .into_iter()
+                    .map(|col| normalize_ident(col.name))
+                    .collect();
+                if !columns.is_empty() && df_fields.len() != columns.len() {
+                    return Err(PlanError::InvalidNumberOfAliasesForView {
+                        sql,
+                        aliases: columns,
+                    });
+                }
+
+                let fields: Vec<_> = df_fields
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, df_field)| {
+                        let field = df_field.field().as_ref().clone().with_nullable(true);
+                        match columns.get(idx) {
+                            Some(name) => field.with_name(name),
+                            None => field,
+                        }
+                    })
+                    .collect();
+
+                let project_exprs: Vec<_> = fields
+                    .iter()
+                    .zip(df_fields.iter())
+                    .map(|(field, df_field)| {
+                        Expr::Column(df_field.qualified_column()).alias(field.name())
+                    })
+                    .collect();
+                let source = LogicalPlanBuilder::from(source)
+                    .project(project_exprs)?
+                    .build()?;
+
+                let df_schema = Schema::new(fields).to_dfschema_ref()?;
+                Ok(CreateTable {
+                    tbl_reference: self.ctx.resolve_table_ref(name)?,
+                    schema: df_schema,
+                    if_not_exists,
+                    or_replace,
+                    column_defaults: BTreeMap::new(),
+                    check_constraints: Vec::new(),
+                    unique_constraints: Vec::new(),
+                    partition_columns: Vec::new(),
+                    column_sequences: Vec::new(),
+                    generated_always: Vec::new(),
+                    materialized_view: Some(MaterializedViewOptions {
+                        sql,
+                        columns,
+                        refresh_interval_secs,
+                        refreshed_at: None,
+                    }),
+                    source: Some(source),
+                }
+                .into_logical_plan())
+            }
+
             // Views
             ast::Statement::CreateView {
                 or_replace,
@@ -1405,7 +1905,7 @@ impl<'a> SessionPlanner<'a> {
                 partitioned: None,
                 after_columns,
                 table: false,
-                on: None,
+                on,
                 returning: None,
                 ignore: _,
                 ..
@@ -1425,10 +1925,32 @@ impl<'a> SessionPlanner<'a> {
                     })
                     .collect::<Result<Vec<_>>>()?;
 
+                context_provider
+                    .set_write_target(table_name.to_owned_reference(), Privilege::Insert);
+
+                let (column_defaults, constraints) =
+                    match self.native_table_options(table_name.clone()) {
+                        Some(options) => {
+                            // Without a column list, values are provided for
+                            // every column.
+                            let written = options
+                                .columns
+                                .iter()
+                                .filter(|col| columns.is_empty() || columns.contains(&col.name));
+                            check_generated_always_writes(written)?;
+                            (
+                                plan_column_defaults(&mut context_provider, &options).await?,
+                                plan_row_constraints(&mut context_provider, &options).await?,
+                            )
+                        }
+                        None => (HashMap::new(), Vec::new()),
+                    };
+
                 let mut planner = SqlQueryPlanner::new(&mut context_provider);
                 let source = planner
-                    .insert_to_source_plan(&table_name, &columns, source)
+                    .insert_to_source_plan(&table_name, &columns, &column_defaults, source)
                     .await?;
+                let source = project_constraints(source, &constraints)?;
 
                 let access_mode = self
                     .get_access_mode(table_name.clone())?
@@ -1440,8 +1962,41 @@ impl<'a> SessionPlanner<'a> {
                     ));
                 }
 
+                match on {
+                    None => (),
+                    Some(ast::OnInsert::OnConflict(on_conflict)) => {
+                        // Executed as a merge, which is committed right away.
+                        if self.ctx.transaction_status() != TransactionStatus::Idle {
+                            return Err(PlanError::UnsupportedFeature(
+                                "INSERT ... ON CONFLICT inside a transaction",
+                            ));
+                        }
+                        let table =
+                            self.native_table(table_name)
+                                .ok_or(PlanError::UnsupportedFeature(
+                                    "ON CONFLICT with non-native tables",
+                                ))?;
+                        let (conflict_columns, updates, update_predicate) =
+                            plan_on_conflict(&mut context_provider, &table, on_conflict).await?;
+
+                        return Ok(Upsert {
+                            table,
+                            source,
+                            conflict_columns,
+                            updates,
+                            update_predicate,
+                            constraints,
+                        }
+                        .into_logical_plan());
+                    }
+                    Some(_) => {
+                        return Err(PlanError::UnsupportedFeature("ON DUPLICATE KEY UPDATE"))
+                    }
+                }
+
                 let state = self.ctx.df_ctx().state();
-                let mut ctx_provider = PartialContextProvider::new(self.ctx, &state)?;
+                let mut ctx_provider = self.new_context_provider(&state)?;
+                ctx_provider.set_write_target(table_name.to_owned_reference(), Privilege::Insert);
 
                 let provider = ctx_provider.table_provider(table_name).await?;
 
@@ -1466,6 +2021,7 @@ impl<'a> SessionPlanner<'a> {
                     source,
                     provider,
                     runtime_preference,
+                    constraints,
                 }
                 .into_logical_plan())
             }
@@ -1506,9 +2062,19 @@ impl<'a> SessionPlanner<'a> {
                             schema,
                             name,
                             operation: AlterTableOperation::RenameTable { new_name },
+                            table: None,
+                            columns: Vec::new(),
+                            rewrite_exprs: Vec::new(),
                         }
                         .into_logical_plan())
                     }
+                    operation @ (ast::AlterTableOperation::AddColumn { .. }
+                    | ast::AlterTableOperation::DropColumn { .. }
+                    | ast::AlterTableOperation::RenameColumn { .. }
+                    | ast::AlterTableOperation::AlterColumn { .. }) => {
+                        self.plan_alter_table_columns(&mut context_provider, name, operation)
+                            .await
+                    }
                     other => Err(PlanError::UnsupportedSQLStatement(other.to_string())),
                 }
             }
@@ -1523,8 +2089,27 @@ impl<'a> SessionPlanner<'a> {
                 let mut refs = Vec::with_capacity(names.len());
                 for name in names.into_iter() {
                     validate_object_name(&name)?;
-                    let r = object_name_to_table_ref(name)?;
-                    refs.push(self.ctx.resolve_table_ref(r)?);
+                    let r = self
+                        .ctx
+                        .resolve_table_ref(object_name_to_table_ref(name)?)?;
+                    if self.resolve_materialized_view(&r).is_some() {
+                        return Err(PlanError::String(format!(
+                            "'{}' is a materialized view, use DROP MATERIALIZED VIEW",
+                            r.name
+                        )));
+                    }
+                    if self
+                        .ctx
+                        .get_session_catalog()
+                        .resolve_sequence(&r.database, &r.schema, &r.name)
+                        .is_some()
+                    {
+                        return Err(PlanError::String(format!(
+                            "'{}' is a sequence, use DROP SEQUENCE",
+                            r.name
+                        )));
+                    }
+                    refs.push(r);
                 }
 
                 let plan = DropTables {
@@ -1554,6 +2139,38 @@ impl<'a> SessionPlanner<'a> {
                 .into_logical_plan())
             }
 
+            // Drop sequences
+            ast::Statement::Drop {
+                object_type: ObjectType::Sequence,
+                if_exists,
+                names,
+                ..
+            } => {
+                let catalog = self.ctx.get_session_catalog();
+                let mut refs = Vec::with_capacity(names.len());
+                for name in names.into_iter() {
+                    validate_object_name(&name)?;
+                    let r = self
+                        .ctx
+                        .resolve_table_ref(object_name_to_table_ref(name)?)?;
+                    if catalog
+                        .resolve_sequence(&r.database, &r.schema, &r.name)
+                        .is_none()
+                        && catalog
+                            .resolve_entry(&r.database, &r.schema, &r.name)
+                            .is_some()
+                    {
+                        return Err(PlanError::String(format!("'{}' is not a sequence", r.name)));
+                    }
+                    refs.push(r);
+                }
+                Ok(DropSequences {
+                    if_exists,
+                    sequence_references: refs,
+                }
+                .into_logical_plan())
+            }
+
             // Drop schemas
             ast::Statement::Drop {
                 object_type: ObjectType::Schema,
@@ -1645,6 +2262,8 @@ impl<'a> SessionPlanner<'a> {
                         validate_object_name(&table_name)?;
                         let table_name = object_name_to_table_ref(table_name)?;
 
+                        context_provider
+                            .set_write_target(table_name.to_owned_reference(), Privilege::Delete);
                         let table_source = context_provider
                             .get_table_source(table_name.clone())
                             .await?;
@@ -1700,6 +2319,8 @@ impl<'a> SessionPlanner<'a> {
                 validate_object_name(&table_name)?;
                 let table_name = object_name_to_table_ref(table_name)?;
 
+                context_provider
+                    .set_write_target(table_name.to_owned_reference(), Privilege::Update);
                 let table_source = context_provider
                     .get_table_source(table_name.clone())
                     .await?;
@@ -1711,9 +2332,20 @@ impl<'a> SessionPlanner<'a> {
                 for assignment in assignments {
                     if assignment.id.len() == 1 {
                         let column = assignment.id.last().unwrap().value.clone();
-                        let update_value = planner
+                        let mut update_value = planner
                             .sql_to_expr(assignment.value, &schema, &mut PlannerContext::new())
                             .await?;
+                        // Parameters assigned directly to a column take the
+                        // type of that column.
+                        if let Expr::Placeholder(Placeholder {
+                            data_type: data_type @ None,
+                            ..
+                        }) = &mut update_value
+                        {
+                            if let Ok(field) = schema.field_with_unqualified_name(&column) {
+                                *data_type = Some(field.data_type().clone());
+                            }
+                        }
                         updates.push((column, update_value));
                     } else {
                         return Err(PlanError::UnsupportedSQLStatement(
@@ -1741,10 +2373,275 @@ impl<'a> SessionPlanner<'a> {
                     return Err(PlanError::UnsupportedFeature("UPDATE with external tables"));
                 }
 
+                // Only constraints referencing updated columns can be
+                // violated by the update.
+                let mut constraints = Vec::new();
+                if let TableOptionsV0::Internal(options) = &ent.options {
+                    check_generated_always_writes(
+                        options
+                            .columns
+                            .iter()
+                            .filter(|col| updates.iter().any(|(name, _)| name == &col.name)),
+                    )?;
+                    for constraint in plan_row_constraints(&mut context_provider, options).await? {
+                        let columns = constraint.expr.to_columns()?;
+                        if updates
+                            .iter()
+                            .any(|(name, _)| columns.iter().any(|col| &col.name == name))
+                        {
+                            constraints.push(constraint);
+                        }
+                    }
+                }
+
                 Ok(Update {
                     table: ent,
                     updates,
                     where_expr,
+                    constraints,
+                }
+                .into_logical_plan())
+            }
+
+            // "MERGE INTO <table> USING <source> ON <expression> WHEN ..."
+            //
+            // Insert, update or delete rows of a native table based on how
+            // they join with rows of the source.
+            ast::Statement::Merge {
+                into: _,
+                table,
+                source,
+                on,
+                clauses,
+            } => {
+                let (table_name, target_alias) = match table {
+                    ast::TableFactor::Table { name, alias, .. } => {
+                        validate_object_name(&name)?;
+                        let table_name = object_name_to_table_ref(name)?;
+                        let alias = match alias {
+                            Some(alias) if !alias.columns.is_empty() => {
+                                return Err(PlanError::UnsupportedFeature(
+                                    "MERGE target alias with columns",
+                                ))
+                            }
+                            Some(alias) => normalize_ident(alias.name),
+                            None => table_name.table().to_string(),
+                        };
+                        (table_name, alias)
+                    }
+                    _ => return Err(PlanError::UnsupportedFeature("MERGE into a non-table")),
+                };
+                if clauses.is_empty() {
+                    return Err(PlanError::String(
+                        "MERGE requires at least one WHEN clause".to_string(),
+                    ));
+                }
+                let source_alias = match &source {
+                    ast::TableFactor::Table {
+                        alias: Some(alias), ..
+                    }
+                    | ast::TableFactor::Derived {
+                        alias: Some(alias), ..
+                    } => normalize_ident(alias.name.clone()),
+                    ast::TableFactor::Table { name, .. } => {
+                        object_name_to_table_ref(name.clone())?.table().to_string()
+                    }
+                    _ => {
+                        return Err(PlanError::String(
+                            "Subquery in MERGE must have an alias".to_string(),
+                        ))
+                    }
+                };
+                if source_alias == target_alias {
+                    return Err(PlanError::String(format!(
+                        "Name '{target_alias}' specified more than once in MERGE"
+                    )));
+                }
+
+                let table =
+                    self.merge_target(table_name.clone())
+                        .ok_or(PlanError::UnsupportedFeature(
+                            "MERGE into tables other than native and external delta tables",
+                        ))?;
+
+                let access_mode = self
+                    .get_access_mode(table_name.clone())?
+                    .unwrap_or(SourceAccessMode::ReadOnly);
+                if !access_mode.has_write_access() {
+                    return Err(PlanError::ObjectNotAllowedToWriteInto(
+                        table_name.to_owned_reference(),
+                    ));
+                }
+
+                let mut privileges = Vec::new();
+                for clause in &clauses {
+                    let privilege = match clause {
+                        ast::MergeClause::MatchedUpdate { .. } => Privilege::Update,
+                        ast::MergeClause::MatchedDelete(_) => Privilege::Delete,
+                        ast::MergeClause::NotMatched { .. } => Privilege::Insert,
+                    };
+                    if !privileges.contains(&privilege) {
+                        privileges.push(privilege);
+                    }
+                }
+                context_provider.check_write_privileges(table_name.clone(), &privileges)?;
+
+                // External delta tables don't have defaults or constraints,
+                // and their columns are read from the table itself.
+                let (target_schema, column_defaults, constraints) = match &table.options {
+                    TableOptionsV0::Internal(options) => {
+                        let fields = InternalColumnDefinition::to_arrow_fields(
+                            options.columns.iter().cloned(),
+                        );
+                        (
+                            Schema::new(fields),
+                            plan_column_defaults(&mut context_provider, options).await?,
+                            plan_row_constraints(&mut context_provider, options).await?,
+                        )
+                    }
+                    _ => {
+                        context_provider
+                            .set_write_target(table_name.to_owned_reference(), privileges[0]);
+                        let source = context_provider.get_table_source(table_name).await?;
+                        (source.schema().as_ref().clone(), HashMap::new(), Vec::new())
+                    }
+                };
+
+                let mut planner = SqlQueryPlanner::new(&mut context_provider);
+                let source = planner.relation_to_plan(source).await?;
+                let source = LogicalPlanBuilder::from(source)
+                    .alias(TableReference::bare(source_alias.clone()))?
+                    .build()?;
+
+                let schema = DFSchema::try_from_qualified_schema(
+                    TableReference::bare(target_alias.clone()),
+                    &target_schema,
+                )?
+                .join(source.schema())?;
+
+                let on = planner
+                    .sql_to_expr(*on, &schema, &mut PlannerContext::new())
+                    .await?;
+
+                let mut merge_clauses = Vec::with_capacity(clauses.len());
+                for mut clause in clauses {
+                    let (ast::MergeClause::MatchedUpdate { predicate, .. }
+                    | ast::MergeClause::MatchedDelete(predicate)
+                    | ast::MergeClause::NotMatched { predicate, .. }) = &mut clause;
+                    let predicate = match predicate.take() {
+                        Some(predicate) => Some(
+                            planner
+                                .sql_to_expr(predicate, &schema, &mut PlannerContext::new())
+                                .await?,
+                        ),
+                        None => None,
+                    };
+
+                    merge_clauses.push(match clause {
+                        ast::MergeClause::MatchedUpdate { assignments, .. } => {
+                            let mut updates = Vec::with_capacity(assignments.len());
+                            for assignment in assignments {
+                                if assignment.id.len() != 1 {
+                                    return Err(PlanError::UnsupportedSQLStatement(
+                                        "Update statement with table reference in column name"
+                                            .to_string(),
+                                    ));
+                                }
+                                let column =
+                                    normalize_ident(assignment.id.into_iter().next().unwrap());
+                                let field =
+                                    target_schema.field_with_name(&column).map_err(|_| {
+                                        PlanError::String(format!(
+                                            "Column '{column}' of table '{}' does not exist",
+                                            table.meta.name
+                                        ))
+                                    })?;
+                                let value = planner
+                                    .sql_to_expr(
+                                        assignment.value,
+                                        &schema,
+                                        &mut PlannerContext::new(),
+                                    )
+                                    .await?;
+                                updates.push((column, assign_to_column(value, field, &schema)?));
+                            }
+                            MergeClause::MatchedUpdate { predicate, updates }
+                        }
+                        ast::MergeClause::MatchedDelete(_) => {
+                            MergeClause::MatchedDelete { predicate }
+                        }
+                        ast::MergeClause::NotMatched {
+                            columns,
+                            mut values,
+                            ..
+                        } => {
+                            if values.rows.len() != 1 {
+                                return Err(PlanError::String(
+                                    "MERGE INSERT must insert exactly one row".to_string(),
+                                ));
+                            }
+                            let row = values.rows.pop().unwrap();
+                            let columns: Vec<_> = if columns.is_empty() {
+                                target_schema
+                                    .fields()
+                                    .iter()
+                                    .map(|field| field.name().clone())
+                                    .collect()
+                            } else {
+                                columns.into_iter().map(normalize_ident).collect()
+                            };
+                            if columns.len() != row.len() {
+                                return Err(PlanError::String(format!(
+                                    "MERGE INSERT has {} columns but {} values",
+                                    columns.len(),
+                                    row.len()
+                                )));
+                            }
+
+                            let mut assigned = HashMap::with_capacity(row.len());
+                            for (column, value) in columns.into_iter().zip(row) {
+                                let field =
+                                    target_schema.field_with_name(&column).map_err(|_| {
+                                        PlanError::String(format!(
+                                            "Column '{column}' of table '{}' does not exist",
+                                            table.meta.name
+                                        ))
+                                    })?;
+                                let value = planner
+                                    .sql_to_expr(value, &schema, &mut PlannerContext::new())
+                                    .await?;
+                                assigned.insert(column, assign_to_column(value, field, &schema)?);
+                            }
+
+                            // Omitted columns take their default, or NULL.
+                            let mut values = Vec::with_capacity(target_schema.fields().len());
+                            for field in target_schema.fields() {
+                                let value = match assigned.remove(field.name()) {
+                                    Some(value) => value,
+                                    None => match column_defaults.get(field.name()) {
+                                        Some(default) => {
+                                            default.clone().cast_to(field.data_type(), &schema)?
+                                        }
+                                        None => {
+                                            Expr::Literal(ScalarValue::try_from(field.data_type())?)
+                                        }
+                                    },
+                                };
+                                values.push((field.name().clone(), value));
+                            }
+                            MergeClause::NotMatchedInsert { predicate, values }
+                        }
+                    });
+                }
+
+                Ok(Merge {
+                    table,
+                    source,
+                    source_alias,
+                    target_alias,
+                    on,
+                    clauses: merge_clauses,
+                    constraints,
                 }
                 .into_logical_plan())
             }
@@ -1798,19 +2695,332 @@ impl<'a> SessionPlanner<'a> {
         .into_logical_plan())
     }
 
-    fn plan_alter_tunnel(&self, stmt: AlterTunnelStmt) -> Result<LogicalPlan> {
+    fn plan_create_role(&self, stmt: CreateRoleStmt) -> Result<LogicalPlan> {
         validate_ident(&stmt.name)?;
         let name = normalize_ident(stmt.name);
 
-        let plan = match stmt.action {
-            AlterTunnelAction::RotateKeys => {
-                let new_ssh_key = SshKey::generate_random()?;
-                let new_ssh_key = new_ssh_key.to_bytes()?;
-                AlterTunnelRotateKeys {
-                    name,
-                    if_exists: stmt.if_exists,
-                    new_ssh_key,
-                }
+        Ok(CreateRole {
+            name,
+            if_not_exists: stmt.if_not_exists,
+        }
+        .into_logical_plan())
+    }
+
+    fn plan_drop_role(&self, stmt: DropRoleStmt) -> Result<LogicalPlan> {
+        let mut names = Vec::with_capacity(stmt.names.len());
+        for name in stmt.names.into_iter() {
+            validate_ident(&name)?;
+            let name = normalize_ident(name);
+            names.push(name);
+        }
+
+        Ok(DropRole {
+            names,
+            if_exists: stmt.if_exists,
+        }
+        .into_logical_plan())
+    }
+
+    fn plan_grant(&self, stmt: GrantStmt) -> Result<LogicalPlan> {
+        let (privileges, object) = self.plan_privileges_on_object(stmt.privileges, stmt.object)?;
+
+        let mut grants = Vec::with_capacity(stmt.roles.len());
+        for role in stmt.roles.into_iter() {
+            validate_ident(&role)?;
+            grants.push(service::GrantPrivileges {
+                role: normalize_ident(role),
+                privileges: privileges.clone(),
+                object: object.clone(),
+            });
+        }
+
+        Ok(GrantPrivileges { grants }.into_logical_plan())
+    }
+
+    fn plan_revoke(&self, stmt: RevokeStmt) -> Result<LogicalPlan> {
+        let (privileges, object) = self.plan_privileges_on_object(stmt.privileges, stmt.object)?;
+
+        let mut revokes = Vec::with_capacity(stmt.roles.len());
+        for role in stmt.roles.into_iter() {
+            validate_ident(&role)?;
+            revokes.push(service::RevokePrivileges {
+                role: normalize_ident(role),
+                privileges: privileges.clone(),
+                object: object.clone(),
+            });
+        }
+
+        Ok(RevokePrivileges { revokes }.into_logical_plan())
+    }
+
+    fn plan_restore_table(&self, stmt: RestoreTableStmt) -> Result<LogicalPlan> {
+        validate_object_name(&stmt.name)?;
+        let table = self
+            .native_table(object_name_to_table_ref(stmt.name)?)
+            .ok_or(PlanError::UnsupportedFeature(
+                "RESTORE TABLE on non-native tables",
+            ))?;
+
+        let version = match stmt.target {
+            RestoreTarget::Version(version) => TableVersion::Version(
+                i64::try_from(version)
+                    .map_err(|_| PlanError::String(format!("Invalid table version: {version}")))?,
+            ),
+            RestoreTarget::Timestamp(timestamp) => {
+                TableVersion::Timestamp(string_to_timestamp_nanos(&timestamp).map_err(|e| {
+                    PlanError::String(format!("Invalid timestamp '{timestamp}': {e}"))
+                })?)
+            }
+        };
+
+        Ok(RestoreTable { table, version }.into_logical_plan())
+    }
+
+    fn plan_vacuum(&self, stmt: VacuumStmt) -> Result<LogicalPlan> {
+        validate_object_name(&stmt.name)?;
+        let table = self
+            .native_table(object_name_to_table_ref(stmt.name)?)
+            .ok_or(PlanError::UnsupportedFeature("VACUUM on non-native tables"))?;
+
+        Ok(VacuumTable {
+            table,
+            retain_hours: stmt.retain_hours,
+            enforce_retention: self.ctx.get_session_vars().vacuum_retention_check(),
+        }
+        .into_logical_plan())
+    }
+
+    fn plan_optimize(&self, stmt: OptimizeStmt) -> Result<LogicalPlan> {
+        validate_object_name(&stmt.name)?;
+        let table = self
+            .native_table(object_name_to_table_ref(stmt.name)?)
+            .ok_or(PlanError::UnsupportedFeature(
+                "OPTIMIZE on non-native tables",
+            ))?;
+
+        let columns = match &table.options {
+            TableOptionsV0::Internal(options) => &options.columns,
+            _ => unreachable!("native tables have internal options"),
+        };
+        let zorder_by = stmt
+            .zorder_by
+            .into_iter()
+            .map(|column| {
+                let column = normalize_ident(column);
+                if !columns.iter().any(|col| col.name == column) {
+                    return Err(PlanError::String(format!(
+                        "Column '{column}' of table '{}' does not exist",
+                        table.meta.name
+                    )));
+                }
+                Ok(column)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(OptimizeTable { table, zorder_by }.into_logical_plan())
+    }
+
+    async fn plan_refresh_materialized_view(
+        &self,
+        stmt: RefreshMaterializedViewStmt,
+    ) -> Result<LogicalPlan> {
+        validate_object_name(&stmt.name)?;
+        let name = stmt.name.to_string();
+        let table = self
+            .native_table(object_name_to_table_ref(stmt.name)?)
+            .filter(|table| table.materialized_view().is_some())
+            .ok_or_else(|| PlanError::String(format!("'{name}' is not a materialized view")))?;
+
+        let schema = match self.ctx.get_session_catalog().get_by_oid(table.meta.parent) {
+            Some(CatalogEntry::Schema(schema)) => schema.meta.name.clone(),
+            _ => return Err(internal!("missing schema for materialized view '{name}'")),
+        };
+
+        let source = plan_materialized_view(self.ctx, &table).await?;
+
+        Ok(RefreshMaterializedView {
+            schema,
+            table,
+            source,
+        }
+        .into_logical_plan())
+    }
+
+    fn plan_drop_materialized_view(&self, stmt: DropMaterializedViewStmt) -> Result<LogicalPlan> {
+        let mut refs = Vec::with_capacity(stmt.names.len());
+        for name in stmt.names {
+            validate_object_name(&name)?;
+            let r = self
+                .ctx
+                .resolve_table_ref(object_name_to_table_ref(name)?)?;
+            let exists = self
+                .ctx
+                .get_session_catalog()
+                .resolve_entry(&r.database, &r.schema, &r.name)
+                .is_some();
+            if exists && self.resolve_materialized_view(&r).is_none() {
+                return Err(PlanError::String(format!(
+                    "'{}' is not a materialized view",
+                    r.name
+                )));
+            }
+            refs.push(r);
+        }
+
+        Ok(DropTables {
+            if_exists: stmt.if_exists,
+            tbl_references: refs,
+        }
+        .into_logical_plan())
+    }
+
+    fn plan_comment(&self, stmt: CommentStmt) -> Result<LogicalPlan> {
+        let resolve = |name: ObjectName| -> Result<(String, String)> {
+            validate_object_name(&name)?;
+            let r = self
+                .ctx
+                .resolve_table_ref(object_name_to_table_ref(name)?)?;
+            Ok((r.schema.into_owned(), r.name.into_owned()))
+        };
+
+        let (object_type, schema, name, column) = match stmt.object {
+            CommentObject::Database(name) => {
+                validate_ident(&name)?;
+                (EntryType::Database, None, normalize_ident(name), None)
+            }
+            CommentObject::Schema(name) => {
+                validate_ident(&name)?;
+                (EntryType::Schema, None, normalize_ident(name), None)
+            }
+            CommentObject::Table(name) => {
+                let (schema, name) = resolve(name)?;
+                (EntryType::Table, Some(schema), name, None)
+            }
+            CommentObject::View(name) => {
+                let (schema, name) = resolve(name)?;
+                (EntryType::View, Some(schema), name, None)
+            }
+            CommentObject::Column(ObjectName(mut idents)) => {
+                let column = idents
+                    .pop()
+                    .ok_or_else(|| internal!("missing column in COMMENT ON COLUMN"))?;
+                validate_ident(&column)?;
+                let (schema, name) = resolve(ObjectName(idents))?;
+                (
+                    EntryType::Table,
+                    Some(schema),
+                    name,
+                    Some(normalize_ident(column)),
+                )
+            }
+        };
+
+        Ok(CommentOn {
+            comment: service::CommentOn {
+                object_type,
+                schema,
+                name,
+                column,
+                comment: stmt.comment,
+            },
+        }
+        .into_logical_plan())
+    }
+
+    /// Resolve the object and privileges for a GRANT or REVOKE.
+    ///
+    /// `ALL PRIVILEGES` (indicated by `None`) expands to every privilege
+    /// that applies to the object.
+    fn plan_privileges_on_object(
+        &self,
+        privileges: Option<Vec<Ident>>,
+        object: GrantObject,
+    ) -> Result<(Vec<Privilege>, PrivilegeObject)> {
+        let object = match object {
+            GrantObject::Table(name) => {
+                validate_object_name(&name)?;
+                let name = self
+                    .ctx
+                    .resolve_table_ref(object_name_to_table_ref(name)?)?;
+                let object_type = self
+                    .ctx
+                    .get_session_catalog()
+                    .resolve_entry(&name.database, &name.schema, &name.name)
+                    .map(|ent| ent.entry_type())
+                    .unwrap_or(EntryType::Table);
+                PrivilegeObject {
+                    object_type,
+                    schema: Some(name.schema.into_owned()),
+                    name: name.name.into_owned(),
+                }
+            }
+            GrantObject::Schema(name) => {
+                validate_ident(&name)?;
+                PrivilegeObject {
+                    object_type: EntryType::Schema,
+                    schema: None,
+                    name: normalize_ident(name),
+                }
+            }
+            GrantObject::Database(name) => {
+                validate_ident(&name)?;
+                PrivilegeObject {
+                    object_type: EntryType::Database,
+                    schema: None,
+                    name: normalize_ident(name),
+                }
+            }
+            GrantObject::Credentials(name) => {
+                validate_ident(&name)?;
+                PrivilegeObject {
+                    object_type: EntryType::Credentials,
+                    schema: None,
+                    name: normalize_ident(name),
+                }
+            }
+            GrantObject::Function(name) => {
+                validate_object_name(&name)?;
+                // Functions live in the default schema unless qualified.
+                let (schema, name) = match object_name_to_table_ref(name)? {
+                    OwnedTableReference::Bare { table } => (None, table),
+                    OwnedTableReference::Partial { schema, table } => (Some(schema), table),
+                    tr => return Err(internal!("invalid function name: {tr}")),
+                };
+                PrivilegeObject {
+                    object_type: EntryType::Function,
+                    schema: schema.map(|s| s.into_owned()),
+                    name: name.into_owned(),
+                }
+            }
+        };
+
+        let privileges = match privileges {
+            Some(privileges) => privileges
+                .into_iter()
+                .map(|p| {
+                    Privilege::from_str(&p.value)
+                        .map_err(|_| PlanError::String(format!("unrecognized privilege: {p}")))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => Privilege::allowed_for(object.object_type).to_vec(),
+        };
+
+        Ok((privileges, object))
+    }
+
+    fn plan_alter_tunnel(&self, stmt: AlterTunnelStmt) -> Result<LogicalPlan> {
+        validate_ident(&stmt.name)?;
+        let name = normalize_ident(stmt.name);
+
+        let plan = match stmt.action {
+            AlterTunnelAction::RotateKeys => {
+                let new_ssh_key = SshKey::generate_random()?;
+                let new_ssh_key = new_ssh_key.to_bytes()?;
+                AlterTunnelRotateKeys {
+                    name,
+                    if_exists: stmt.if_exists,
+                    new_ssh_key,
+                }
             }
         };
 
@@ -1850,17 +3060,310 @@ impl<'a> SessionPlanner<'a> {
                     .map_err(|e| PlanError::String(format!("{e}")))?;
                 AlterTableOperation::SetAccessMode { access_mode }
             }
+            parser::AlterTableOperationExtension::SetAutoCompact { min_files } => {
+                let policy = match min_files {
+                    Some(min_files) if min_files < 2 => {
+                        return Err(PlanError::String(format!(
+                            "AUTO_COMPACT requires at least 2 files, got {min_files}"
+                        )))
+                    }
+                    Some(min_files) => Some(AutoCompactionPolicy { min_files }),
+                    None => None,
+                };
+                AlterTableOperation::SetAutoCompaction { policy }
+            }
         };
 
         Ok(AlterTable {
             schema,
             name,
             operation,
+            table: None,
+            columns: Vec::new(),
+            rewrite_exprs: Vec::new(),
+        }
+        .into_logical_plan())
+    }
+
+    /// Plan an ALTER TABLE operation changing the columns of a native table.
+    ///
+    /// Along with the catalog operation, this computes the new columns of the
+    /// table and the expressions needed to rewrite existing data into them.
+    async fn plan_alter_table_columns(
+        &self,
+        context_provider: &mut PartialContextProvider<'_>,
+        name: ObjectName,
+        operation: ast::AlterTableOperation,
+    ) -> Result<LogicalPlan> {
+        validate_object_name(&name)?;
+        let table_ref = object_name_to_table_ref(name)?;
+        let resolved = self.ctx.resolve_table_ref(table_ref.clone())?;
+        let schema = resolved.schema.into_owned();
+        let name = resolved.name.into_owned();
+
+        let ent = EntryResolver::from_context(self.ctx)
+            .resolve_entry_from_reference(table_ref)?
+            .try_into_table_entry()?;
+        let existing = match ent.get_internal_columns() {
+            Some(columns) if !ent.meta.external && !ent.meta.is_temp => columns,
+            _ => {
+                return Err(PlanError::InvalidAlterStatement {
+                    msg: "columns can only be altered for native tables",
+                })
+            }
+        };
+        if ent.materialized_view().is_some() {
+            return Err(PlanError::String(format!(
+                "Cannot alter the columns of materialized view '{name}'"
+            )));
+        }
+        let position = |column: &str| existing.iter().position(|col| col.name == column);
+        let missing_column = |column: &str| {
+            PlanError::String(format!("Column '{column}' does not exist in '{name}'"))
+        };
+        let duplicate_column = |column: &str| {
+            PlanError::String(format!("Column '{column}' already exists in '{name}'"))
+        };
+
+        // Expressions producing the new columns from the existing data,
+        // starting out with every column unchanged.
+        let mut columns = existing.clone();
+        let mut rewrite_exprs: Vec<Expr> = existing.iter().map(|col| ident(&col.name)).collect();
+        let mut changed = true;
+
+        // Check constraints are stored as SQL and keys by column name, so the
+        // columns they reference can't be dropped or renamed. The same goes
+        // for partition columns, which additionally can't change type since
+        // existing partition values would need to be rewritten.
+        let (constraints, unique_constraints, partition_columns) = match &ent.options {
+            TableOptionsV0::Internal(options) => (
+                plan_row_constraints(context_provider, options).await?,
+                options.unique_constraints.clone(),
+                options.partition_columns.clone(),
+            ),
+            _ => (Vec::new(), Vec::new(), Vec::new()),
+        };
+        let check_not_partition = |column: &str| -> Result<()> {
+            if partition_columns.iter().any(|col| col == column) {
+                return Err(PlanError::String(format!(
+                    "Column '{column}' is a partition column of '{name}'"
+                )));
+            }
+            Ok(())
+        };
+        let check_unreferenced = |column: &str| -> Result<()> {
+            check_not_partition(column)?;
+            for unique in &unique_constraints {
+                if unique.columns.iter().any(|col| col == column) {
+                    return Err(PlanError::String(format!(
+                        "Column '{column}' is referenced by constraint '{}'",
+                        unique.name
+                    )));
+                }
+            }
+            for constraint in &constraints {
+                if constraint.kind == RowConstraintKind::Check
+                    && constraint
+                        .expr
+                        .to_columns()?
+                        .iter()
+                        .any(|col| col.name == column)
+                {
+                    return Err(PlanError::String(format!(
+                        "Column '{column}' is referenced by check constraint '{}'",
+                        constraint.name
+                    )));
+                }
+            }
+            Ok(())
+        };
+
+        let operation = match operation {
+            ast::AlterTableOperation::AddColumn {
+                if_not_exists,
+                column_def,
+                ..
+            } => {
+                validate_ident(&column_def.name)?;
+                let column_name = normalize_ident(column_def.name);
+                let arrow_type = convert_data_type(&column_def.data_type)?;
+
+                let mut default = None;
+                let mut nullable = true;
+                for opt in column_def.options {
+                    match opt.option {
+                        ColumnOption::Null => nullable = true,
+                        ColumnOption::NotNull => nullable = false,
+                        ColumnOption::Default(expr) => default = Some(expr),
+                        _ => {
+                            return Err(PlanError::UnsupportedFeature(
+                                "column constraints in ALTER TABLE ADD COLUMN",
+                            ))
+                        }
+                    }
+                }
+                // Existing rows are filled in with the default.
+                if !nullable && default.is_none() {
+                    return Err(PlanError::String(format!(
+                        "Column '{column_name}' must have a default to be added as NOT NULL"
+                    )));
+                }
+                let default_expr = default.as_ref().map(|expr| expr.to_string());
+
+                match position(&column_name) {
+                    Some(_) if if_not_exists => changed = false,
+                    Some(_) => return Err(duplicate_column(&column_name)),
+                    None => {
+                        match default {
+                            Some(default) => {
+                                let mut planner = SqlQueryPlanner::new(context_provider);
+                                let default = planner
+                                    .sql_to_expr(
+                                        default,
+                                        &DFSchema::empty(),
+                                        &mut PlannerContext::new(),
+                                    )
+                                    .await?;
+                                rewrite_exprs
+                                    .push(cast(default, arrow_type.clone()).alias(&column_name));
+                            }
+                            // Nothing to write, existing files will read the
+                            // new column as NULL.
+                            None => rewrite_exprs.clear(),
+                        }
+                        columns.push(InternalColumnDefinition {
+                            name: column_name.clone(),
+                            nullable,
+                            arrow_type: arrow_type.clone(),
+                            default_expr: default_expr.clone(),
+                            generated_always: false,
+                            description: None,
+                        });
+                    }
+                }
+
+                AlterTableOperation::AddColumn {
+                    column: InternalColumnDefinition {
+                        name: column_name,
+                        nullable,
+                        arrow_type,
+                        default_expr,
+                        generated_always: false,
+                        description: None,
+                    },
+                    if_not_exists,
+                }
+            }
+            ast::AlterTableOperation::DropColumn {
+                column_name,
+                if_exists,
+                ..
+            } => {
+                let column_name = normalize_ident(column_name);
+                match position(&column_name) {
+                    None if if_exists => changed = false,
+                    None => return Err(missing_column(&column_name)),
+                    Some(_) if existing.len() == 1 => {
+                        return Err(PlanError::InvalidAlterStatement {
+                            msg: "cannot drop the only column of a table",
+                        })
+                    }
+                    // Data is rewritten without the column so that a column
+                    // later added with the same name doesn't see stale data.
+                    Some(idx) => {
+                        check_unreferenced(&column_name)?;
+                        columns.remove(idx);
+                        rewrite_exprs.remove(idx);
+                        if columns
+                            .iter()
+                            .all(|col| partition_columns.contains(&col.name))
+                        {
+                            return Err(PlanError::String(format!(
+                                "Cannot drop the last column of '{name}' that isn't a partition column"
+                            )));
+                        }
+                    }
+                }
+
+                AlterTableOperation::DropColumn {
+                    name: column_name,
+                    if_exists,
+                }
+            }
+            ast::AlterTableOperation::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => {
+                validate_ident(&new_column_name)?;
+                let old_name = normalize_ident(old_column_name);
+                let new_name = normalize_ident(new_column_name);
+                if position(&new_name).is_some() {
+                    return Err(duplicate_column(&new_name));
+                }
+                let idx = position(&old_name).ok_or_else(|| missing_column(&old_name))?;
+                check_unreferenced(&old_name)?;
+                columns[idx].name = new_name.clone();
+                rewrite_exprs[idx] = ident(&old_name).alias(&new_name);
+
+                AlterTableOperation::RenameColumn {
+                    name: old_name,
+                    new_name,
+                }
+            }
+            ast::AlterTableOperation::AlterColumn {
+                column_name,
+                op: ast::AlterColumnOperation::SetDataType { data_type, using },
+            } => {
+                if using.is_some() {
+                    return Err(PlanError::UnsupportedFeature(
+                        "ALTER COLUMN TYPE with USING",
+                    ));
+                }
+                let column_name = normalize_ident(column_name);
+                let arrow_type = convert_data_type(&data_type)?;
+                let idx = position(&column_name).ok_or_else(|| missing_column(&column_name))?;
+                let current = &existing[idx].arrow_type;
+                if current == &arrow_type {
+                    changed = false;
+                } else {
+                    check_not_partition(&column_name)?;
+                    if !is_widening_type_change(current, &arrow_type) {
+                        return Err(PlanError::String(format!(
+                            "Cannot change type of column '{column_name}' from {current} to {arrow_type}, only widening type changes are supported"
+                        )));
+                    }
+                }
+                columns[idx].arrow_type = arrow_type.clone();
+                rewrite_exprs[idx] =
+                    cast(ident(&column_name), arrow_type.clone()).alias(&column_name);
+
+                AlterTableOperation::AlterColumnType {
+                    name: column_name,
+                    arrow_type,
+                }
+            }
+            other => return Err(PlanError::UnsupportedSQLStatement(other.to_string())),
+        };
+
+        let (table, rewrite_exprs) = if changed {
+            (Some(ent), rewrite_exprs)
+        } else {
+            (None, Vec::new())
+        };
+
+        Ok(AlterTable {
+            schema,
+            name,
+            operation,
+            table,
+            columns,
+            rewrite_exprs,
         }
         .into_logical_plan())
     }
 
     async fn plan_copy_to(&self, stmt: CopyToStmt) -> Result<LogicalPlan> {
+        let dest_is_stdout = stmt.is_stdout();
         let query = match stmt.source {
             CopyToSource::Table(table) => {
                 validate_object_name(&table)?;
@@ -1876,13 +3379,253 @@ impl<'a> SessionPlanner<'a> {
         };
 
         let state = self.ctx.df_ctx().state();
-        let mut context_provider = PartialContextProvider::new(self.ctx, &state)?;
+        let mut context_provider = self.new_context_provider(&state)?;
         let mut planner = SqlQueryPlanner::new(&mut context_provider);
         let source = planner.query_to_plan(query).await?;
 
-        let mut m = stmt.options;
+        let mut m = stmt.options;
+        if dest_is_stdout {
+            let options = plan_copy_stdio_options(stmt.format.as_ref(), &mut m)?;
+            return Ok(CopyStdioPlan::To { source, options }.into());
+        }
+
+        // Exporting data out of the database requires INSERT on it, the same
+        // as writing to an external database.
+        let catalog = self.ctx.get_session_catalog();
+        if catalog.current_role().is_some() {
+            let allowed = catalog
+                .resolve_database(DEFAULT_CATALOG)
+                .map(|db| {
+                    catalog.has_privilege(&CatalogEntry::Database(db.clone()), Privilege::Insert)
+                })
+                .unwrap_or(false);
+            if !allowed {
+                return Err(PlanError::PermissionDenied {
+                    object_type: "database",
+                    name: DEFAULT_CATALOG.to_string(),
+                });
+            }
+        }
+
+        let dest = self.plan_copy_location(stmt.dest, stmt.credentials, &mut m)?;
+        let format = plan_copy_format(stmt.format.as_ref(), dest.location(), &mut m)?;
+
+        validate_copyto_dest_format_support(dest.as_str(), format.as_str()).map_err(|e| {
+            PlanError::InvalidExternalTable {
+                source: Box::new(e),
+            }
+        })?;
+
+        Ok(CopyTo {
+            format,
+            dest,
+            source,
+        }
+        .into_logical_plan())
+    }
+
+    async fn plan_copy_from(&self, stmt: CopyFromStmt) -> Result<LogicalPlan> {
+        let from_stdin = stmt.is_stdin();
+        validate_object_name(&stmt.table)?;
+        let table_name = object_name_to_table_ref(stmt.table)?;
+
+        let access_mode = self
+            .get_access_mode(table_name.clone())?
+            .unwrap_or(SourceAccessMode::ReadOnly);
+
+        if !access_mode.has_write_access() {
+            return Err(PlanError::ObjectNotAllowedToWriteInto(
+                table_name.to_owned_reference(),
+            ));
+        }
+
+        let state = self.ctx.df_ctx().state();
+        let mut ctx_provider = self.new_context_provider(&state)?;
+        ctx_provider.set_write_target(table_name.to_owned_reference(), Privilege::Insert);
+        let provider = ctx_provider.table_provider(table_name.clone()).await?;
+
+        if provider
+            .provider
+            .as_any()
+            .downcast_ref::<StubRemoteTableProvider>()
+            .is_some()
+        {
+            return Err(PlanError::UnsupportedFeature(
+                "COPY FROM into remote tables",
+            ));
+        }
+        let table_schema = provider.provider.schema();
+
+        let columns = stmt
+            .columns
+            .into_iter()
+            .map(|col| {
+                validate_ident(&col)?;
+                Ok(normalize_ident(col))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Source columns are mapped by position onto either the provided
+        // columns or all of the table's columns.
+        let targets = if columns.is_empty() {
+            (0..table_schema.fields().len()).collect::<Vec<_>>()
+        } else {
+            let mut targets = Vec::with_capacity(columns.len());
+            for col in &columns {
+                let idx = table_schema.index_of(col).map_err(|_| {
+                    PlanError::String(format!(
+                        "column '{col}' of table '{table_name}' does not exist"
+                    ))
+                })?;
+                if targets.contains(&idx) {
+                    return Err(PlanError::String(format!(
+                        "column '{col}' specified more than once"
+                    )));
+                }
+                targets.push(idx);
+            }
+            targets
+        };
+
+        let mut column_mapping = vec![None; table_schema.fields().len()];
+        for (source_idx, target_idx) in targets.iter().enumerate() {
+            column_mapping[*target_idx] = Some(source_idx);
+        }
+
+        // Rows are copied into native tables the same way as they're
+        // inserted, filling in defaults and checking constraints.
+        let (column_defaults, constraints) = match self.native_table_options(table_name.clone()) {
+            Some(options) => {
+                let mut defaults = plan_column_defaults(&mut ctx_provider, &options).await?;
+                let defaults = table_schema
+                    .fields()
+                    .iter()
+                    .map(|field| defaults.remove(field.name()))
+                    .collect();
+                (
+                    defaults,
+                    plan_row_constraints(&mut ctx_provider, &options).await?,
+                )
+            }
+            None => (vec![None; table_schema.fields().len()], Vec::new()),
+        };
+
+        let mut m = stmt.options;
+        let max_errors = m.remove_optional::<usize>("max_errors")?.unwrap_or(0) as u64;
+
+        if from_stdin {
+            let options = plan_copy_stdio_options(stmt.format.as_ref(), &mut m)?;
+            // Binary data is decoded using the table's types, everything else
+            // is read as text and converted when copied into the table.
+            let fields = targets
+                .iter()
+                .map(|idx| {
+                    let field = table_schema.field(*idx);
+                    match options.format {
+                        CopyStdioFormat::Binary => field.clone().with_nullable(true),
+                        _ => Field::new(field.name(), DataType::Utf8, true),
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            return Ok(CopyStdioPlan::From {
+                provider: ProviderReference::Provider(provider.provider),
+                schema: Arc::new(Schema::new(fields)),
+                column_mapping,
+                column_defaults,
+                constraints,
+                max_errors,
+                options,
+            }
+            .into());
+        }
+
+        let location = self.plan_copy_location(stmt.source, stmt.credentials, &mut m)?;
+        let format = plan_copy_format(stmt.format.as_ref(), location.location(), &mut m)?;
+
+        let file_format: Arc<dyn FileFormat> = match &format {
+            CopyToFormatOptions::Csv(opts) => Arc::new(
+                CsvFormat::default()
+                    .with_has_header(opts.header)
+                    .with_delimiter(opts.delim)
+                    .with_schema_infer_max_rec(Some(20480)),
+            ),
+            CopyToFormatOptions::Parquet(_) => Arc::new(ParquetFormat::default()),
+            CopyToFormatOptions::Json(opts) if !opts.array => Arc::new(JsonFormat::default()),
+            other => {
+                return Err(PlanError::String(format!(
+                    "unsupported format for COPY FROM: {}",
+                    other.as_str()
+                )))
+            }
+        };
+
+        let (access, location) = object_store_access(location);
+        let accessor = ObjStoreAccessor::new(access.clone())?;
+        let objects = accessor.list_globbed(&location).await?;
+        if objects.is_empty() {
+            return Err(PlanError::String(format!(
+                "no files found matching '{location}'"
+            )));
+        }
+        let store = accessor.into_object_store();
+
+        let source_schema = access
+            .infer_schema(&store, &state, file_format.as_ref(), &objects)
+            .await?;
+        // Read CSV values as text so that values failing to convert to the
+        // table's types only skip the row instead of failing the read.
+        let source_schema = if matches!(format, CopyToFormatOptions::Csv(_)) {
+            let fields = source_schema
+                .fields()
+                .iter()
+                .map(|f| Field::new(f.name(), DataType::Utf8, true))
+                .collect::<Vec<_>>();
+            Arc::new(Schema::new(fields))
+        } else {
+            source_schema
+        };
+
+        if targets.len() != source_schema.fields().len() {
+            return Err(PlanError::String(format!(
+                "COPY FROM source has {} columns, expected {}",
+                source_schema.fields().len(),
+                targets.len()
+            )));
+        }
+
+        let source_provider = Arc::new(ObjStoreTableProvider::new(
+            store,
+            source_schema,
+            access.base_url()?,
+            objects,
+            file_format,
+        ));
+        let source =
+            LogicalPlanBuilder::scan("copy_source", provider_as_source(source_provider), None)?
+                .build()?;
+
+        Ok(CopyFrom {
+            source,
+            provider: ProviderReference::Provider(provider.provider),
+            runtime_preference: RuntimePreference::Local,
+            column_mapping,
+            column_defaults,
+            constraints,
+            max_errors,
+        }
+        .into_logical_plan())
+    }
 
-        let destination = normalize_ident(stmt.dest);
+    /// Plan the location for COPY statements, either a source (COPY FROM) or
+    /// a destination (COPY TO).
+    fn plan_copy_location(
+        &self,
+        location: Ident,
+        credentials: Option<Ident>,
+        m: &mut StatementOptions,
+    ) -> Result<CopyToDestinationOptions> {
+        let destination = normalize_ident(location);
 
         // We currently support two versions of COPY TO:
         //
@@ -1923,7 +3666,7 @@ impl<'a> SessionPlanner<'a> {
             (destination, Some(uri), location)
         };
 
-        let creds = stmt.credentials.map(normalize_ident);
+        let creds = credentials.map(normalize_ident);
         let creds_options = self.get_credentials_opts(&creds)?;
         if let Some(creds_options) = &creds_options {
             validate_copyto_dest_creds_support(destination, creds_options.as_str()).map_err(
@@ -1948,7 +3691,7 @@ impl<'a> SessionPlanner<'a> {
             Ok(bucket)
         }
 
-        let dest = match destination {
+        let opts = match destination {
             CopyToDestinationOptions::LOCAL => {
                 CopyToDestinationOptions::Local(CopyToDestinationOptionsLocal { location })
             }
@@ -1961,7 +3704,7 @@ impl<'a> SessionPlanner<'a> {
                 let service_account_key =
                     m.remove_optional_or("service_account_key", service_account_key)?;
 
-                let bucket = get_bucket(&mut m, &uri, "bucket")?;
+                let bucket = get_bucket(m, &uri, "bucket")?;
 
                 CopyToDestinationOptions::Gcs(CopyToDestinationOptionsGcs {
                     service_account_key,
@@ -1988,7 +3731,7 @@ impl<'a> SessionPlanner<'a> {
                     m.remove_optional_or("secret_access_key", secret_access_key)?;
 
                 let region = m.remove_required("region")?;
-                let bucket = get_bucket(&mut m, &uri, "bucket")?;
+                let bucket = get_bucket(m, &uri, "bucket")?;
 
                 CopyToDestinationOptions::S3(CopyToDestinationOptionsS3 {
                     access_key_id,
@@ -2020,7 +3763,7 @@ impl<'a> SessionPlanner<'a> {
                     ),
                 };
 
-                let container = get_bucket(&mut m, &uri, "container")?;
+                let container = get_bucket(m, &uri, "container")?;
 
                 CopyToDestinationOptions::Azure(CopyToDestinationOptionsAzure {
                     account,
@@ -2036,71 +3779,7 @@ impl<'a> SessionPlanner<'a> {
             }
         };
 
-        let loc = dest.location();
-        let loc = Path::new(loc);
-        let ext = loc
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| ext.to_lowercase());
-
-        let format = match stmt
-            .format
-            .as_ref()
-            .map(|f| f.value.as_str())
-            // Choose from specified format "OR" from location.
-            .or(ext.as_deref())
-        {
-            None => {
-                // TODO: Choose the default based on destination.
-                CopyToFormatOptions::default()
-            }
-            Some(CopyToFormatOptions::CSV) => {
-                let delim = m.remove_optional::<char>("delimeter")?.unwrap_or(',');
-                let header = m.remove_optional::<bool>("header")?.unwrap_or(true);
-                CopyToFormatOptions::Csv(CopyToFormatOptionsCsv {
-                    delim: delim as u8,
-                    header,
-                })
-            }
-            Some(CopyToFormatOptions::PARQUET) => {
-                let row_group_size = m
-                    .remove_optional::<usize>("row_group_size")?
-                    .unwrap_or(122880);
-                CopyToFormatOptions::Parquet(CopyToFormatOptionsParquet { row_group_size })
-            }
-            Some(CopyToFormatOptions::JSON) => {
-                let array = m.remove_optional::<bool>("array")?.unwrap_or(false);
-                CopyToFormatOptions::Json(CopyToFormatOptionsJson { array })
-            }
-            Some(CopyToFormatOptions::BSON) => {
-                CopyToFormatOptions::Bson(CopyToFormatOptionsBson {})
-            }
-            Some(CopyToFormatOptions::LANCE) => {
-                CopyToFormatOptions::Lance(CopyToFormatOptionsLance {
-                    max_rows_per_file: m.remove_optional("max_rows_per_file")?,
-                    max_rows_per_group: m.remove_optional("max_rows_per_group")?,
-                    max_bytes_per_file: m.remove_optional("max_bytes_per_file")?,
-                    input_batch_size: m.remove_optional("input_batch_size")?,
-                })
-            }
-            Some(CopyToFormatOptions::DELTA) => {
-                CopyToFormatOptions::Delta(CopyToFormatOptionsDelta {})
-            }
-            Some(other) => return Err(internal!("unsupported output format: {other}")),
-        };
-
-        validate_copyto_dest_format_support(dest.as_str(), format.as_str()).map_err(|e| {
-            PlanError::InvalidExternalTable {
-                source: Box::new(e),
-            }
-        })?;
-
-        Ok(CopyTo {
-            format,
-            dest,
-            source,
-        }
-        .into_logical_plan())
+        Ok(opts)
     }
 
     fn get_tunnel_opts(&self, tunnel: &Option<String>) -> Result<Option<TunnelOptions>> {
@@ -2160,6 +3839,740 @@ impl<'a> SessionPlanner<'a> {
             _ => None,
         })
     }
+
+    /// Get the entry for the referenced table if it's a native table.
+    fn native_table(&self, table_ref: TableReference<'a>) -> Option<TableEntry> {
+        let resolver = EntryResolver::from_context(self.ctx);
+        match resolver.resolve_entry_from_reference(table_ref).ok()? {
+            ResolvedEntry::Entry(CatalogEntry::Table(ent))
+                if !ent.meta.external
+                    && !ent.meta.is_temp
+                    && matches!(ent.options, TableOptionsV0::Internal(_)) =>
+            {
+                Some(ent)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the entry for the referenced table if it can be the target of a
+    /// MERGE, either a native table or an external delta table.
+    fn merge_target(&self, table_ref: TableReference<'a>) -> Option<TableEntry> {
+        let resolver = EntryResolver::from_context(self.ctx);
+        match resolver
+            .resolve_entry_from_reference(table_ref.clone())
+            .ok()?
+        {
+            ResolvedEntry::Entry(CatalogEntry::Table(ent))
+                if matches!(ent.options, TableOptionsV0::Delta(_)) =>
+            {
+                Some(ent)
+            }
+            _ => self.native_table(table_ref),
+        }
+    }
+
+    /// Get the entry for the referenced table if it's a materialized view.
+    fn resolve_materialized_view(
+        &self,
+        reference: &OwnedFullObjectReference,
+    ) -> Option<TableEntry> {
+        self.ctx
+            .get_session_catalog()
+            .resolve_table(&reference.database, &reference.schema, &reference.name)
+            .filter(|table| table.materialized_view().is_some())
+            .cloned()
+    }
+
+    /// Get the options of the referenced table if it's a native table.
+    fn native_table_options(&self, table_ref: TableReference<'a>) -> Option<TableOptionsInternal> {
+        match self.native_table(table_ref)?.options {
+            TableOptionsV0::Internal(options) => Some(options),
+            _ => None,
+        }
+    }
+
+    /// Plan the sequences generating values for the SERIAL and identity
+    /// columns of a new table.
+    ///
+    /// Each identity is given as its column, the column's type, whether it's
+    /// `GENERATED ALWAYS`, and the options of its sequence. The sequences
+    /// cache `IDENTITY_SEQUENCE_CACHE` values unless CACHE is given. Defaults
+    /// calling `nextval` are added for the columns. Returns the sequences to create
+    /// along with the columns defined as `GENERATED ALWAYS`.
+    fn plan_column_sequences(
+        &self,
+        table: &OwnedFullObjectReference,
+        if_not_exists: bool,
+        or_replace: bool,
+        identities: Vec<(String, DataType, bool, Vec<ast::SequenceOptions>)>,
+        column_defaults: &mut BTreeMap<String, String>,
+    ) -> Result<(Vec<service::CreateSequence>, Vec<String>)> {
+        let catalog = self.ctx.get_session_catalog();
+        let existing = catalog.resolve_entry(&table.database, &table.schema, &table.name);
+        // Nothing is created if the table already exists.
+        if if_not_exists && existing.is_some() {
+            return Ok((Vec::new(), Vec::new()));
+        }
+        // Sequences owned by a replaced table are dropped along with it, so
+        // their names can be reused.
+        let replaced = match existing {
+            Some(CatalogEntry::Table(ent)) if or_replace => Some(ent.meta.id),
+            _ => None,
+        };
+        let is_free = |name: &str| match catalog.resolve_entry(&table.database, &table.schema, name)
+        {
+            None => true,
+            Some(CatalogEntry::Sequence(seq)) => {
+                replaced.is_some() && seq.owned_by.as_ref().map(|owner| owner.table_id) == replaced
+            }
+            Some(_) => false,
+        };
+
+        let mut sequences: Vec<service::CreateSequence> = Vec::with_capacity(identities.len());
+        let mut generated_always = Vec::new();
+        for (column, data_type, always, options) in identities {
+            let base_name = format!("{}_{}_seq", table.name, column);
+            let mut name = base_name.clone();
+            let mut suffix = 1;
+            while name == table.name
+                || !is_free(&name)
+                || sequences.iter().any(|seq| seq.name == name)
+            {
+                name = format!("{base_name}{suffix}");
+                suffix += 1;
+            }
+
+            let has_cache = options
+                .iter()
+                .any(|option| matches!(option, ast::SequenceOptions::Cache(_)));
+            let mut sequence = plan_create_sequence(&table.schema, &name, &data_type, options)?;
+            if !has_cache {
+                sequence.cache = IDENTITY_SEQUENCE_CACHE;
+            }
+            sequence.owned_by = Some(service::SequenceOwnerColumn {
+                table: table.name.to_string(),
+                column: column.clone(),
+            });
+            // The sequence doesn't exist yet, so refer to it by its qualified
+            // name instead of its oid.
+            let qualified = format!("{}.{}", quote_ident(&table.schema), quote_ident(&name));
+            column_defaults.insert(
+                column.clone(),
+                format!("nextval('{}')", qualified.replace('\'', "''")),
+            );
+            if always {
+                generated_always.push(column);
+            }
+            sequences.push(sequence);
+        }
+
+        Ok((sequences, generated_always))
+    }
+}
+
+/// Check if a statement may be run by a session restricted to a role.
+///
+/// Restricted sessions may only query and modify data, and create temporary
+/// tables. COPY TO a location is further checked against the role's grants
+/// when planning.
+fn is_allowed_for_role(statement: &StatementWithExtensions) -> bool {
+    match statement {
+        StatementWithExtensions::Statement(stmt) => matches!(
+            stmt,
+            ast::Statement::Query(_)
+                | ast::Statement::Explain { .. }
+                | ast::Statement::ExplainTable { .. }
+                | ast::Statement::Insert { .. }
+                | ast::Statement::Update { .. }
+                | ast::Statement::Delete { .. }
+                | ast::Statement::Merge { .. }
+                | ast::Statement::StartTransaction { .. }
+                | ast::Statement::Commit { .. }
+                | ast::Statement::Rollback { .. }
+                | ast::Statement::SetVariable { .. }
+                | ast::Statement::ShowVariable { .. }
+                | ast::Statement::CreateTable {
+                    temporary: true,
+                    ..
+                }
+        ),
+        StatementWithExtensions::CopyTo(_) | StatementWithExtensions::CopyFrom(_) => true,
+        _ => false,
+    }
+}
+
+/// Parse and plan an expression stored in the catalog as SQL.
+async fn plan_catalog_expr(
+    context_provider: &mut PartialContextProvider<'_>,
+    sql: &str,
+    schema: &DFSchema,
+) -> Result<Expr> {
+    let expr = parser::GlareDbParser::parse_expr(sql)?;
+    let mut planner = SqlQueryPlanner::new(context_provider);
+    Ok(planner
+        .sql_to_expr(expr, schema, &mut PlannerContext::new())
+        .await?)
+}
+
+/// Plan the defaults of a native table's columns, keyed by column name.
+async fn plan_column_defaults(
+    context_provider: &mut PartialContextProvider<'_>,
+    options: &TableOptionsInternal,
+) -> Result<HashMap<String, Expr>> {
+    let mut defaults = HashMap::new();
+    for column in &options.columns {
+        if let Some(sql) = &column.default_expr {
+            let expr = plan_catalog_expr(context_provider, sql, &DFSchema::empty()).await?;
+            defaults.insert(column.name.clone(), expr);
+        }
+    }
+    Ok(defaults)
+}
+
+/// Plan the constraints that rows written to a native table must satisfy.
+async fn plan_row_constraints(
+    context_provider: &mut PartialContextProvider<'_>,
+    options: &TableOptionsInternal,
+) -> Result<Vec<RowConstraint>> {
+    let mut constraints: Vec<_> = options
+        .columns
+        .iter()
+        .filter(|column| !column.nullable)
+        .map(|column| RowConstraint::not_null(&column.name))
+        .collect();
+
+    if !options.check_constraints.is_empty() {
+        let fields = InternalColumnDefinition::to_arrow_fields(options.columns.iter().cloned());
+        let schema = Schema::new(fields).to_dfschema()?;
+        for check in &options.check_constraints {
+            let expr = plan_catalog_expr(context_provider, &check.expr, &schema).await?;
+            constraints.push(RowConstraint::check(&check.name, expr));
+        }
+    }
+
+    Ok(constraints)
+}
+
+/// Project the result of evaluating each constraint after the columns of the
+/// source plan.
+fn project_constraints(
+    source: DfLogicalPlan,
+    constraints: &[RowConstraint],
+) -> Result<DfLogicalPlan> {
+    if constraints.is_empty() {
+        return Ok(source);
+    }
+
+    let mut exprs: Vec<_> = source
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| Expr::Column(field.qualified_column()))
+        .collect();
+    for (idx, constraint) in constraints.iter().enumerate() {
+        exprs.push(
+            constraint
+                .expr
+                .clone()
+                .alias(RowConstraint::column_name(idx)),
+        );
+    }
+
+    Ok(LogicalPlanBuilder::from(source).project(exprs)?.build()?)
+}
+
+/// Plan the conflict target and the updates of an `INSERT ... ON CONFLICT`
+/// into a native table.
+///
+/// Updates may reference both the existing row, qualified by the table's
+/// name, and the proposed row, qualified by `excluded`. No updates are
+/// returned for `DO NOTHING`.
+async fn plan_on_conflict(
+    context_provider: &mut PartialContextProvider<'_>,
+    table: &TableEntry,
+    on_conflict: ast::OnConflict,
+) -> Result<(Vec<String>, Vec<(String, Expr)>, Option<Expr>)> {
+    let options = match &table.options {
+        TableOptionsV0::Internal(options) => options,
+        _ => return Err(internal!("expected native table options")),
+    };
+
+    let unique = match on_conflict.conflict_target {
+        Some(ast::ConflictTarget::Columns(columns)) => {
+            let mut columns: Vec<_> = columns.into_iter().map(normalize_ident).collect();
+            columns.sort();
+            options.unique_constraints.iter().find(|unique| {
+                let mut unique_columns = unique.columns.clone();
+                unique_columns.sort();
+                unique_columns == columns
+            })
+        }
+        Some(ast::ConflictTarget::OnConstraint(name)) => {
+            let name = name.0.into_iter().last().map(normalize_ident);
+            let unique = options
+                .unique_constraints
+                .iter()
+                .find(|unique| Some(&unique.name) == name.as_ref());
+            if unique.is_none() {
+                return Err(PlanError::String(format!(
+                    "Constraint '{}' for table '{}' does not exist",
+                    name.unwrap_or_default(),
+                    table.meta.name
+                )));
+            }
+            unique
+        }
+        None => match on_conflict.action {
+            ast::OnConflictAction::DoNothing => options.primary_key(),
+            ast::OnConflictAction::DoUpdate(_) => {
+                return Err(PlanError::String(
+                    "ON CONFLICT DO UPDATE requires a conflict target".to_string(),
+                ))
+            }
+        },
+    };
+    let conflict_columns = unique
+        .ok_or_else(|| {
+            PlanError::String(
+                "There is no unique or primary key constraint matching the ON CONFLICT specification"
+                    .to_string(),
+            )
+        })?
+        .columns
+        .clone();
+
+    let ast::DoUpdate {
+        assignments,
+        selection,
+    } = match on_conflict.action {
+        ast::OnConflictAction::DoNothing => return Ok((conflict_columns, Vec::new(), None)),
+        ast::OnConflictAction::DoUpdate(do_update) => do_update,
+    };
+
+    let fields = InternalColumnDefinition::to_arrow_fields(options.columns.iter().cloned());
+    let schema = Schema::new(fields);
+    let existing = DFSchema::try_from_qualified_schema(
+        TableReference::bare(table.meta.name.clone()),
+        &schema,
+    )?;
+    let excluded =
+        DFSchema::try_from_qualified_schema(TableReference::bare(EXCLUDED_ALIAS), &schema)?;
+    let df_schema = existing.join(&excluded)?;
+
+    let mut planner = SqlQueryPlanner::new(context_provider);
+    let mut updates = Vec::with_capacity(assignments.len());
+    for assignment in assignments {
+        if assignment.id.len() != 1 {
+            return Err(PlanError::UnsupportedSQLStatement(
+                "Update statement with table reference in column name".to_string(),
+            ));
+        }
+        let column = normalize_ident(assignment.id.into_iter().next().unwrap());
+        let field = schema.field_with_name(&column).map_err(|_| {
+            PlanError::String(format!(
+                "Column '{column}' of table '{}' does not exist",
+                table.meta.name
+            ))
+        })?;
+
+        let value = planner
+            .sql_to_expr(assignment.value, &df_schema, &mut PlannerContext::new())
+            .await?;
+        updates.push((column, assign_to_column(value, field, &df_schema)?));
+    }
+
+    let update_predicate = match selection {
+        Some(selection) => Some(
+            planner
+                .sql_to_expr(selection, &df_schema, &mut PlannerContext::new())
+                .await?,
+        ),
+        None => None,
+    };
+
+    Ok((conflict_columns, updates, update_predicate))
+}
+
+/// Cast a value assigned to a column of a native table to the column's type.
+///
+/// Parameters assigned directly to a column take the type of that column.
+fn assign_to_column(mut value: Expr, field: &Field, schema: &DFSchema) -> Result<Expr> {
+    if let Expr::Placeholder(Placeholder {
+        data_type: data_type @ None,
+        ..
+    }) = &mut value
+    {
+        *data_type = Some(field.data_type().clone());
+    }
+    Ok(value.cast_to(field.data_type(), schema)?)
+}
+
+/// Validate the column defaults and check constraints for a new native table,
+/// returning them in the form they're stored in the catalog.
+///
+/// Each check is given as its name if one was provided, the name to derive
+/// one from otherwise, and its expression.
+async fn plan_create_table_constraints(
+    context_provider: &mut PartialContextProvider<'_>,
+    fields: &[Field],
+    defaults: Vec<(String, ast::Expr)>,
+    checks: Vec<(Option<String>, String, ast::Expr)>,
+) -> Result<(BTreeMap<String, String>, Vec<CheckConstraint>)> {
+    let schema = Schema::new(fields.to_vec()).to_dfschema()?;
+    let mut planner = SqlQueryPlanner::new(context_provider);
+
+    let mut column_defaults = BTreeMap::new();
+    for (column, expr) in defaults {
+        let sql = expr.to_string();
+        let data_type = schema.field_with_unqualified_name(&column)?.data_type();
+        // Defaults can't reference other columns.
+        planner
+            .sql_to_expr(expr, &DFSchema::empty(), &mut PlannerContext::new())
+            .await?
+            .cast_to(data_type, &DFSchema::empty())?;
+        column_defaults.insert(column, sql);
+    }
+
+    let mut check_constraints: Vec<CheckConstraint> = Vec::with_capacity(checks.len());
+    for (name, base_name, expr) in checks {
+        let exists = |name: &str| check_constraints.iter().any(|check| check.name == name);
+        let name = match name {
+            Some(name) if exists(&name) => {
+                return Err(PlanError::String(format!(
+                    "Check constraint '{name}' already exists"
+                )))
+            }
+            Some(name) => name,
+            None => {
+                let mut name = base_name.clone();
+                let mut suffix = 1;
+                while exists(&name) {
+                    name = format!("{base_name}{suffix}");
+                    suffix += 1;
+                }
+                name
+            }
+        };
+
+        let sql = expr.to_string();
+        let planned = planner
+            .sql_to_expr(expr, &schema, &mut PlannerContext::new())
+            .await?;
+        if planned.get_type(&schema)? != DataType::Boolean {
+            return Err(PlanError::String(format!(
+                "Check constraint '{name}' must be a boolean expression"
+            )));
+        }
+        check_constraints.push(CheckConstraint { name, expr: sql });
+    }
+
+    Ok((column_defaults, check_constraints))
+}
+
+/// Number of values sessions fetch at a time for the sequences of SERIAL and
+/// identity columns without CACHE. Fetching values commits the catalog, which
+/// would otherwise happen on every insert into these tables.
+const IDENTITY_SEQUENCE_CACHE: i64 = 32;
+
+/// Get the integer type of a SERIAL pseudo-type.
+fn serial_data_type(sql_type: &ast::DataType) -> Option<DataType> {
+    match sql_type {
+        ast::DataType::Custom(name, modifiers) if modifiers.is_empty() => {
+            match name.to_string().to_lowercase().as_str() {
+                "smallserial" | "serial2" => Some(DataType::Int16),
+                "serial" | "serial4" => Some(DataType::Int32),
+                "bigserial" | "serial8" => Some(DataType::Int64),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Plan a new sequence producing values of the given integer type.
+///
+/// Bounds that aren't specified default to the range of the type for
+/// ascending sequences, and to the range of the type up to -1 for descending
+/// ones. The sequence starts at its lower bound when ascending, and its upper
+/// bound when descending. Sessions fetch a single value at a time unless
+/// CACHE is given.
+fn plan_create_sequence(
+    schema: &str,
+    name: &str,
+    data_type: &DataType,
+    options: Vec<ast::SequenceOptions>,
+) -> Result<service::CreateSequence> {
+    let (type_min, type_max) = match data_type {
+        DataType::Int16 => (i16::MIN as i64, i16::MAX as i64),
+        DataType::Int32 => (i32::MIN as i64, i32::MAX as i64),
+        DataType::Int64 => (i64::MIN, i64::MAX),
+        other => {
+            return Err(PlanError::String(format!(
+                "Sequences must be of type smallint, integer or bigint, got {other}"
+            )))
+        }
+    };
+
+    let mut increment = 1;
+    let mut min_value = None;
+    let mut max_value = None;
+    let mut start = None;
+    let mut cycle = false;
+    let mut cache = 1;
+    for option in options {
+        match option {
+            ast::SequenceOptions::IncrementBy(expr, _) => increment = sequence_option_value(&expr)?,
+            ast::SequenceOptions::MinValue(expr) => {
+                min_value = expr.map(|expr| sequence_option_value(&expr)).transpose()?
+            }
+            ast::SequenceOptions::MaxValue(expr) => {
+                max_value = expr.map(|expr| sequence_option_value(&expr)).transpose()?
+            }
+            ast::SequenceOptions::StartWith(expr, _) => start = Some(sequence_option_value(&expr)?),
+            ast::SequenceOptions::Cycle(no_cycle) => cycle = !no_cycle,
+            ast::SequenceOptions::Cache(expr) => cache = sequence_option_value(&expr)?,
+        }
+    }
+
+    let min_value = min_value.unwrap_or(if increment < 0 { type_min } else { 1 });
+    let max_value = max_value.unwrap_or(if increment < 0 { -1 } else { type_max });
+    for (option, value) in [("MINVALUE", min_value), ("MAXVALUE", max_value)] {
+        if value < type_min || value > type_max {
+            return Err(PlanError::String(format!(
+                "{option} ({value}) is out of range for sequence of type {data_type}"
+            )));
+        }
+    }
+    let start = start.unwrap_or(if increment < 0 { max_value } else { min_value });
+
+    Ok(service::CreateSequence {
+        schema: schema.to_string(),
+        name: name.to_string(),
+        start,
+        increment,
+        min_value,
+        max_value,
+        cycle,
+        owned_by: None,
+        if_not_exists: false,
+        cache,
+    })
+}
+
+/// Get the value of a sequence option, e.g. `INCREMENT BY -1`.
+fn sequence_option_value(expr: &ast::Expr) -> Result<i64> {
+    let value = match expr {
+        ast::Expr::Value(ast::Value::Number(n, _)) => n.parse().ok(),
+        ast::Expr::UnaryOp {
+            op: ast::UnaryOperator::Minus,
+            expr,
+        } => match expr.as_ref() {
+            ast::Expr::Value(ast::Value::Number(n, _)) => format!("-{n}").parse().ok(),
+            _ => None,
+        },
+        _ => None,
+    };
+    value.ok_or_else(|| PlanError::String(format!("Invalid sequence option value: {expr}")))
+}
+
+/// Quote an identifier if it wouldn't be parsed as-is.
+pub(crate) fn quote_ident(ident: &str) -> String {
+    let is_plain = ident
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && ident
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if is_plain {
+        ident.to_string()
+    } else {
+        format!("\"{}\"", ident.replace('"', "\"\""))
+    }
+}
+
+/// Check that none of the columns being written to are identity columns
+/// defined as `GENERATED ALWAYS`.
+fn check_generated_always_writes<'a>(
+    columns: impl IntoIterator<Item = &'a InternalColumnDefinition>,
+) -> Result<()> {
+    match columns.into_iter().find(|col| col.generated_always) {
+        Some(col) => Err(PlanError::String(format!(
+            "Cannot write to column '{}', it is an identity column defined as GENERATED ALWAYS",
+            col.name
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Validate the primary key and unique constraints for a new native table.
+///
+/// Each constraint is given as its name if one was provided, its columns,
+/// and whether it's the primary key.
+fn plan_unique_constraints(
+    table: &str,
+    fields: &[Field],
+    uniques: Vec<(Option<String>, Vec<String>, bool)>,
+) -> Result<Vec<UniqueConstraint>> {
+    let mut unique_constraints: Vec<UniqueConstraint> = Vec::with_capacity(uniques.len());
+    for (name, columns, primary_key) in uniques {
+        if primary_key && unique_constraints.iter().any(|unique| unique.primary_key) {
+            return Err(PlanError::String(format!(
+                "Multiple primary keys for table '{table}' are not allowed"
+            )));
+        }
+        for column in &columns {
+            if !fields.iter().any(|field| field.name() == column) {
+                return Err(PlanError::String(format!(
+                    "Column '{column}' named in key does not exist"
+                )));
+            }
+        }
+
+        let exists = |name: &str| unique_constraints.iter().any(|unique| unique.name == name);
+        let name = match name {
+            Some(name) if exists(&name) => {
+                return Err(PlanError::String(format!(
+                    "Constraint '{name}' already exists"
+                )))
+            }
+            Some(name) => name,
+            None => {
+                let base_name = if primary_key {
+                    format!("{table}_pkey")
+                } else {
+                    format!("{table}_{}_key", columns.join("_"))
+                };
+                let mut name = base_name.clone();
+                let mut suffix = 1;
+                while exists(&name) {
+                    name = format!("{base_name}{suffix}");
+                    suffix += 1;
+                }
+                name
+            }
+        };
+
+        unique_constraints.push(UniqueConstraint {
+            name,
+            columns,
+            primary_key,
+        });
+    }
+    Ok(unique_constraints)
+}
+
+/// Get the columns a table is partitioned by from its `PARTITION BY`
+/// expression.
+///
+/// Partition columns must be of a type with a stable string representation
+/// since partition values are stored as strings, and at least one column must
+/// not be a partition column.
+fn plan_partition_columns(
+    table: &str,
+    fields: &[Field],
+    partition_by: Option<Box<ast::Expr>>,
+) -> Result<Vec<String>> {
+    let exprs = match partition_by.map(|expr| *expr) {
+        None => return Ok(Vec::new()),
+        Some(ast::Expr::Tuple(exprs)) => exprs,
+        Some(ast::Expr::Nested(expr)) => vec![*expr],
+        Some(expr) => vec![expr],
+    };
+
+    let mut columns: Vec<String> = Vec::with_capacity(exprs.len());
+    for expr in exprs {
+        let column = match expr {
+            ast::Expr::Identifier(ident) => normalize_ident(ident),
+            other => {
+                return Err(PlanError::String(format!(
+                    "PARTITION BY expects column names, got '{other}'"
+                )))
+            }
+        };
+        let field = fields
+            .iter()
+            .find(|field| field.name() == &column)
+            .ok_or_else(|| {
+                PlanError::String(format!("Partition column '{column}' does not exist"))
+            })?;
+        if !matches!(
+            field.data_type(),
+            DataType::Boolean
+                | DataType::Int8
+                | DataType::Int16
+                | DataType::Int32
+                | DataType::Int64
+                | DataType::Utf8
+                | DataType::Date32
+        ) {
+            return Err(PlanError::String(format!(
+                "Cannot partition by column '{column}' of type {}",
+                field.data_type()
+            )));
+        }
+        if columns.contains(&column) {
+            return Err(PlanError::String(format!(
+                "Partition column '{column}' specified more than once"
+            )));
+        }
+        columns.push(column);
+    }
+
+    if columns.len() == fields.len() {
+        return Err(PlanError::String(format!(
+            "Cannot partition table '{table}' by all of its columns"
+        )));
+    }
+
+    Ok(columns)
+}
+
+/// Get the refresh interval in seconds from the `WITH` options of a
+/// materialized view.
+///
+/// The interval is given as a string, e.g. `refresh_interval = '1 hour'`.
+/// Intervals in months aren't allowed since they don't have a fixed length.
+fn plan_refresh_interval(options: Vec<ast::SqlOption>) -> Result<Option<u64>> {
+    let mut refresh_interval = None;
+    for option in options {
+        let name = normalize_ident(option.name);
+        if name != "refresh_interval" {
+            return Err(PlanError::String(format!(
+                "Unknown materialized view option '{name}'"
+            )));
+        }
+        let value = match option.value {
+            ast::Expr::Value(ast::Value::SingleQuotedString(value)) => value,
+            other => {
+                return Err(PlanError::String(format!(
+                    "Expected a string for refresh_interval, got '{other}'"
+                )))
+            }
+        };
+
+        let invalid = || PlanError::String(format!("Invalid refresh_interval '{value}'"));
+        let interval = ScalarValue::Utf8(Some(value.clone()))
+            .cast_to(&DataType::Interval(IntervalUnit::MonthDayNano))
+            .map_err(|_| invalid())?;
+        let (months, days, nanos) = match interval {
+            ScalarValue::IntervalMonthDayNano(Some(interval)) => {
+                IntervalMonthDayNanoType::to_parts(interval)
+            }
+            _ => return Err(invalid()),
+        };
+        if months != 0 || days < 0 || nanos < 0 {
+            return Err(invalid());
+        }
+        let secs = days as u64 * 24 * 60 * 60 + nanos as u64 / 1_000_000_000;
+        if secs == 0 {
+            return Err(invalid());
+        }
+        refresh_interval = Some(secs);
+    }
+
+    Ok(refresh_interval)
 }
 
 /// Get the object store bucket and location.
@@ -2258,6 +4671,148 @@ async fn validate_and_get_file_type_and_compression(
     Ok((file_type, compression))
 }
 
+/// Plan the format for COPY statements, using the extension of the location
+/// if the format isn't explicitly provided.
+fn plan_copy_format(
+    format: Option<&Ident>,
+    location: &str,
+    m: &mut StatementOptions,
+) -> Result<CopyToFormatOptions> {
+    let loc = Path::new(location);
+    let ext = loc
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+
+    let format = match format
+        .map(|f| f.value.as_str())
+        // Choose from specified format "OR" from location.
+        .or(ext.as_deref())
+    {
+        None => {
+            // TODO: Choose the default based on destination.
+            CopyToFormatOptions::default()
+        }
+        Some(CopyToFormatOptions::CSV) => {
+            let delim = m.remove_optional::<char>("delimeter")?.unwrap_or(',');
+            let header = m.remove_optional::<bool>("header")?.unwrap_or(true);
+            CopyToFormatOptions::Csv(CopyToFormatOptionsCsv {
+                delim: delim as u8,
+                header,
+            })
+        }
+        Some(CopyToFormatOptions::PARQUET) => {
+            let row_group_size = m
+                .remove_optional::<usize>("row_group_size")?
+                .unwrap_or(122880);
+            CopyToFormatOptions::Parquet(CopyToFormatOptionsParquet { row_group_size })
+        }
+        Some(CopyToFormatOptions::JSON) => {
+            let array = m.remove_optional::<bool>("array")?.unwrap_or(false);
+            CopyToFormatOptions::Json(CopyToFormatOptionsJson { array })
+        }
+        Some(CopyToFormatOptions::BSON) => CopyToFormatOptions::Bson(CopyToFormatOptionsBson {}),
+        Some(CopyToFormatOptions::LANCE) => CopyToFormatOptions::Lance(CopyToFormatOptionsLance {
+            max_rows_per_file: m.remove_optional("max_rows_per_file")?,
+            max_rows_per_group: m.remove_optional("max_rows_per_group")?,
+            max_bytes_per_file: m.remove_optional("max_bytes_per_file")?,
+            input_batch_size: m.remove_optional("input_batch_size")?,
+        }),
+        Some(CopyToFormatOptions::DELTA) => CopyToFormatOptions::Delta(CopyToFormatOptionsDelta {}),
+        Some(CopyToFormatOptions::ICEBERG) => {
+            CopyToFormatOptions::Iceberg(CopyToFormatOptionsIceberg {})
+        }
+        Some(other) => return Err(internal!("unsupported output format: {other}")),
+    };
+
+    Ok(format)
+}
+
+/// Plan the options for COPY to or from the client.
+fn plan_copy_stdio_options(
+    format: Option<&Ident>,
+    m: &mut StatementOptions,
+) -> Result<CopyStdioOptions> {
+    let format = match format.map(|f| f.value.to_lowercase()).as_deref() {
+        None | Some(CopyStdioFormat::TEXT) => CopyStdioFormat::Text,
+        Some(CopyStdioFormat::CSV) => CopyStdioFormat::Csv,
+        Some(CopyStdioFormat::BINARY) => CopyStdioFormat::Binary,
+        Some(other) => {
+            return Err(PlanError::String(format!(
+                "COPY format \"{other}\" not recognized"
+            )))
+        }
+    };
+
+    let mut options = CopyStdioOptions::new(format);
+
+    let delimiter = m.remove_optional::<String>("delimiter")?;
+    let null = m.remove_optional::<String>("null")?;
+    let header = m.remove_optional::<bool>("header")?;
+    let quote = m.remove_optional::<String>("quote")?;
+    let escape = m.remove_optional::<String>("escape")?;
+
+    if format == CopyStdioFormat::Binary
+        && (delimiter.is_some() || null.is_some() || header.is_some())
+    {
+        return Err(PlanError::String(
+            "cannot specify DELIMITER, NULL or HEADER in BINARY mode".to_string(),
+        ));
+    }
+    if format != CopyStdioFormat::Csv && (quote.is_some() || escape.is_some()) {
+        return Err(PlanError::String(
+            "COPY QUOTE and ESCAPE are only available in CSV mode".to_string(),
+        ));
+    }
+
+    fn single_byte(name: &str, value: String) -> Result<u8> {
+        match value.as_bytes() {
+            [b] if b.is_ascii() => Ok(*b),
+            _ => Err(PlanError::String(format!(
+                "COPY {name} must be a single one-byte character"
+            ))),
+        }
+    }
+
+    if let Some(delimiter) = delimiter {
+        options.delimiter = single_byte("delimiter", delimiter)?;
+        if matches!(options.delimiter, b'\r' | b'\n')
+            || (format == CopyStdioFormat::Text && options.delimiter == b'\\')
+        {
+            return Err(PlanError::String(format!(
+                "COPY delimiter cannot be {:?}",
+                options.delimiter as char
+            )));
+        }
+    }
+    if let Some(null) = null {
+        if null.contains(['\r', '\n']) {
+            return Err(PlanError::String(
+                "COPY null representation cannot use newline or carriage return".to_string(),
+            ));
+        }
+        options.null = null;
+    }
+    if let Some(header) = header {
+        options.header = header;
+    }
+    if let Some(quote) = quote {
+        options.quote = single_byte("quote", quote)?;
+        // Escape defaults to the quote character.
+        options.escape = options.quote;
+    }
+    if let Some(escape) = escape {
+        options.escape = single_byte("escape", escape)?;
+    }
+    if format == CopyStdioFormat::Csv && options.delimiter == options.quote {
+        return Err(PlanError::String(
+            "COPY delimiter and quote must be different".to_string(),
+        ));
+    }
+
+    Ok(options)
+}
+
 /// Resolves an ident (unquoted -> lowercase else case sensitive).
 fn normalize_ident(ident: Ident) -> String {
     let normalizer = IdentNormalizer::new(/* normalize = */ true);
@@ -0,0 +1,207 @@
+//! Scheduled refreshes of materialized views.
+//!
+//! Materialized views created with a `refresh_interval` are refreshed in the
+//! background by the engine, one task per database, once their interval has
+//! elapsed since their last refresh.
+use std::collections::HashMap;
+use std::time::Duration;
+
+use datafusion::variable::VarType;
+use datafusion_ext::vars::SessionVars;
+use futures::StreamExt;
+use parking_lot::Mutex;
+use protogen::metastore::types::catalog::CatalogEntry;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::engine::SessionOpener;
+use crate::errors::{ExecError, Result};
+use crate::planner::physical_plan::refresh_materialized_view::now_micros;
+use crate::planner::session_planner::quote_ident;
+
+/// How often to check for materialized views due for a refresh.
+const REFRESH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Background tasks refreshing materialized views, keyed by database id.
+///
+/// Tasks are stopped when this is dropped.
+#[derive(Debug, Default)]
+pub struct MaterializedViewRefresher {
+    tasks: Mutex<HashMap<Uuid, JoinHandle<()>>>,
+}
+
+impl MaterializedViewRefresher {
+    /// Start refreshing the materialized views of the database the session
+    /// variables are for, if not already started.
+    ///
+    /// Refreshes run in their own sessions as the user of `vars`.
+    pub fn ensure_started(&self, opener: SessionOpener, vars: &SessionVars) {
+        let database_id = vars.database_id();
+        let mut tasks = self.tasks.lock();
+        if tasks
+            .get(&database_id)
+            .is_some_and(|task| !task.is_finished())
+        {
+            return;
+        }
+
+        let vars = RefreshVars::new(vars);
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(REFRESH_CHECK_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                if let Err(e) = refresh_due_views(&opener, &vars).await {
+                    warn!(%e, %database_id, "failed to refresh materialized views");
+                }
+            }
+        });
+        tasks.insert(database_id, task);
+    }
+}
+
+impl Drop for MaterializedViewRefresher {
+    fn drop(&mut self) {
+        for task in self.tasks.get_mut().values() {
+            task.abort();
+        }
+    }
+}
+
+/// Variables of the sessions refreshes run in.
+#[derive(Debug)]
+struct RefreshVars {
+    database_id: Uuid,
+    database_name: String,
+    user_id: Uuid,
+    user_name: String,
+    is_cloud_instance: bool,
+}
+
+impl RefreshVars {
+    fn new(vars: &SessionVars) -> Self {
+        RefreshVars {
+            database_id: vars.database_id(),
+            database_name: vars.database_name(),
+            user_id: vars.user_id(),
+            user_name: vars.user_name(),
+            is_cloud_instance: vars.is_cloud_instance(),
+        }
+    }
+
+    fn session_vars(&self) -> SessionVars {
+        SessionVars::default()
+            .with_database_id(self.database_id, VarType::System)
+            .with_database_name(&self.database_name, VarType::System)
+            .with_user_id(self.user_id, VarType::System)
+            .with_user_name(&self.user_name, VarType::System)
+            .with_is_cloud_instance(self.is_cloud_instance, VarType::System)
+    }
+}
+
+/// Refresh all materialized views of the database whose refresh interval has
+/// elapsed.
+///
+/// A view failing to refresh doesn't keep the others from being refreshed,
+/// it'll be retried on the next check.
+async fn refresh_due_views(opener: &SessionOpener, vars: &RefreshVars) -> Result<()> {
+    let mut session = opener.open(vars.session_vars()).await?;
+
+    let now = now_micros();
+    let due: Vec<_> = session
+        .get_session_catalog()
+        .iter_entries()
+        .filter_map(|ent| match (ent.entry, ent.parent_entry) {
+            (CatalogEntry::Table(table), Some(CatalogEntry::Schema(schema)))
+                if table
+                    .materialized_view()
+                    .is_some_and(|view| view.needs_refresh(now)) =>
+            {
+                Some((schema.meta.name.clone(), table.meta.name.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+
+    for (schema, name) in due {
+        debug!(%schema, %name, "refreshing materialized view");
+        let query = format!(
+            "REFRESH MATERIALIZED VIEW {}.{}",
+            quote_ident(&schema),
+            quote_ident(&name)
+        );
+        let result = async {
+            let mut stream = session.execute_sql(&query).await?;
+            while let Some(batch) = stream.next().await {
+                batch?;
+            }
+            Ok::<_, ExecError>(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            warn!(%e, %schema, %name, "failed to refresh materialized view");
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::Int64Array;
+    use datafusion::arrow::record_batch::RecordBatch;
+
+    use super::*;
+    use crate::engine::{Engine, SessionStorageConfig};
+    use crate::session::Session;
+
+    async fn query(session: &mut Session, sql: &str) -> Vec<RecordBatch> {
+        let mut stream = session.execute_sql(sql).await.unwrap();
+        let mut batches = Vec::new();
+        while let Some(batch) = stream.next().await {
+            batches.push(batch.unwrap());
+        }
+        batches
+    }
+
+    async fn count_in(session: &mut Session, view: &str) -> i64 {
+        let batches = query(session, &format!("SELECT num FROM {view}")).await;
+        batches[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap()
+            .value(0)
+    }
+
+    #[tokio::test]
+    async fn refreshes_due_views() {
+        let engine = Engine::from_data_dir(None).await.unwrap();
+        let mut session = engine.default_local_session_context().await.unwrap();
+
+        query(&mut session, "CREATE TABLE t (a INT)").await;
+        query(&mut session, "INSERT INTO t VALUES (1)").await;
+        query(
+            &mut session,
+            "CREATE MATERIALIZED VIEW every_second WITH (refresh_interval = '1 second') AS \
+             SELECT count(*) AS num FROM t",
+        )
+        .await;
+        query(
+            &mut session,
+            "CREATE MATERIALIZED VIEW manual AS SELECT count(*) AS num FROM t",
+        )
+        .await;
+        query(&mut session, "INSERT INTO t VALUES (2)").await;
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        let opener = engine.session_opener(SessionStorageConfig::default());
+        let vars = RefreshVars::new(&session.get_session_vars());
+        refresh_due_views(&opener, &vars).await.unwrap();
+
+        assert_eq!(2, count_in(&mut session, "every_second").await);
+        assert_eq!(1, count_in(&mut session, "manual").await);
+    }
+}
@@ -25,11 +25,15 @@ use crate::planner::logical_plan::{
     AlterDatabase,
     AlterTable,
     AlterTunnelRotateKeys,
+    CommentOn,
+    CopyFrom,
     CopyTo,
     CreateCredentials,
     CreateExternalDatabase,
     CreateExternalTable,
+    CreateRole,
     CreateSchema,
+    CreateSequence,
     CreateTable,
     CreateTempTable,
     CreateTunnel,
@@ -38,25 +42,39 @@ use crate::planner::logical_plan::{
     DescribeTable,
     DropCredentials,
     DropDatabase,
+    DropRole,
     DropSchemas,
+    DropSequences,
     DropTables,
     DropTunnel,
     DropViews,
+    GrantPrivileges,
     Insert,
+    Merge,
+    OptimizeTable,
+    RefreshMaterializedView,
+    RestoreTable,
+    RevokePrivileges,
     SetVariable,
     ShowVariable,
     Update,
+    Upsert,
+    VacuumTable,
 };
 use crate::planner::physical_plan::alter_database::AlterDatabaseExec;
 use crate::planner::physical_plan::alter_table::AlterTableExec;
 use crate::planner::physical_plan::alter_tunnel_rotate_keys::AlterTunnelRotateKeysExec;
 use crate::planner::physical_plan::client_recv::ClientExchangeRecvExec;
 use crate::planner::physical_plan::client_send::ClientExchangeSendExec;
+use crate::planner::physical_plan::comment_on::CommentOnExec;
+use crate::planner::physical_plan::copy_from::CopyFromExec;
 use crate::planner::physical_plan::copy_to::CopyToExec;
 use crate::planner::physical_plan::create_credentials::CreateCredentialsExec;
 use crate::planner::physical_plan::create_external_database::CreateExternalDatabaseExec;
 use crate::planner::physical_plan::create_external_table::CreateExternalTableExec;
+use crate::planner::physical_plan::create_role::CreateRoleExec;
 use crate::planner::physical_plan::create_schema::CreateSchemaExec;
+use crate::planner::physical_plan::create_sequence::CreateSequenceExec;
 use crate::planner::physical_plan::create_table::CreateTableExec;
 use crate::planner::physical_plan::create_temp_table::CreateTempTableExec;
 use crate::planner::physical_plan::create_tunnel::CreateTunnelExec;
@@ -65,18 +83,28 @@ use crate::planner::physical_plan::delete::DeleteExec;
 use crate::planner::physical_plan::describe_table::DescribeTableExec;
 use crate::planner::physical_plan::drop_credentials::DropCredentialsExec;
 use crate::planner::physical_plan::drop_database::DropDatabaseExec;
+use crate::planner::physical_plan::drop_role::DropRoleExec;
 use crate::planner::physical_plan::drop_schemas::DropSchemasExec;
+use crate::planner::physical_plan::drop_sequences::DropSequencesExec;
 use crate::planner::physical_plan::drop_tables::DropTablesExec;
 use crate::planner::physical_plan::drop_temp_tables::DropTempTablesExec;
 use crate::planner::physical_plan::drop_tunnel::DropTunnelExec;
 use crate::planner::physical_plan::drop_views::DropViewsExec;
+use crate::planner::physical_plan::grant_privileges::GrantPrivilegesExec;
 use crate::planner::physical_plan::insert::InsertExec;
+use crate::planner::physical_plan::merge::MergeExec;
+use crate::planner::physical_plan::optimize_table::OptimizeTableExec;
+use crate::planner::physical_plan::refresh_materialized_view::RefreshMaterializedViewExec;
 use crate::planner::physical_plan::remote_exec::RemoteExecutionExec;
 use crate::planner::physical_plan::remote_scan::ProviderReference;
+use crate::planner::physical_plan::restore_table::RestoreTableExec;
+use crate::planner::physical_plan::revoke_privileges::RevokePrivilegesExec;
 use crate::planner::physical_plan::send_recv::SendRecvJoinExec;
 use crate::planner::physical_plan::set_var::SetVarExec;
 use crate::planner::physical_plan::show_var::ShowVarExec;
 use crate::planner::physical_plan::update::UpdateExec;
+use crate::planner::physical_plan::upsert::UpsertExec;
+use crate::planner::physical_plan::vacuum_table::VacuumTableExec;
 
 pub struct DDLExtensionPlanner {
     catalog: SessionCatalog,
@@ -116,6 +144,9 @@ impl ExtensionPlanner for DDLExtensionPlanner {
                     schema: lp.schema.to_owned(),
                     name: lp.name.to_owned(),
                     operation: lp.operation.clone(),
+                    table: lp.table.clone(),
+                    columns: lp.columns.clone(),
+                    rewrite_exprs: lp.rewrite_exprs.clone(),
                 };
                 RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
             }
@@ -181,6 +212,13 @@ impl ExtensionPlanner for DDLExtensionPlanner {
                     if_not_exists: lp.if_not_exists,
                     or_replace: lp.or_replace,
                     arrow_schema: Arc::new(lp.schema.as_ref().into()),
+                    column_defaults: lp.column_defaults.clone(),
+                    check_constraints: lp.check_constraints.clone(),
+                    unique_constraints: lp.unique_constraints.clone(),
+                    partition_columns: lp.partition_columns.clone(),
+                    column_sequences: lp.column_sequences.clone(),
+                    generated_always: lp.generated_always.clone(),
+                    materialized_view: lp.materialized_view.clone(),
                     source: physical_inputs.first().cloned(),
                 };
                 RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
@@ -291,6 +329,65 @@ impl ExtensionPlanner for DDLExtensionPlanner {
                 };
                 RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
             }
+            ExtensionType::CreateRole => {
+                let lp = require_downcast_lp::<CreateRole>(node);
+                let exec = CreateRoleExec {
+                    catalog_version: self.catalog.version(),
+                    name: lp.name.clone(),
+                    if_not_exists: lp.if_not_exists,
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
+            ExtensionType::DropRole => {
+                let lp = require_downcast_lp::<DropRole>(node);
+                let exec = DropRoleExec {
+                    catalog_version: self.catalog.version(),
+                    names: lp.names.clone(),
+                    if_exists: lp.if_exists,
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
+            ExtensionType::CreateSequence => {
+                let lp = require_downcast_lp::<CreateSequence>(node);
+                let exec = CreateSequenceExec {
+                    catalog_version: self.catalog.version(),
+                    sequence: lp.sequence.clone(),
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
+            ExtensionType::DropSequences => {
+                let lp = require_downcast_lp::<DropSequences>(node);
+                let exec = DropSequencesExec {
+                    catalog_version: self.catalog.version(),
+                    sequence_references: lp.sequence_references.clone(),
+                    if_exists: lp.if_exists,
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
+            ExtensionType::CommentOn => {
+                let lp = require_downcast_lp::<CommentOn>(node);
+                let exec = CommentOnExec {
+                    catalog_version: self.catalog.version(),
+                    comment: lp.comment.clone(),
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
+            ExtensionType::GrantPrivileges => {
+                let lp = require_downcast_lp::<GrantPrivileges>(node);
+                let exec = GrantPrivilegesExec {
+                    catalog_version: self.catalog.version(),
+                    grants: lp.grants.clone(),
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
+            ExtensionType::RevokePrivileges => {
+                let lp = require_downcast_lp::<RevokePrivileges>(node);
+                let exec = RevokePrivilegesExec {
+                    catalog_version: self.catalog.version(),
+                    revokes: lp.revokes.clone(),
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
             ExtensionType::DropDatabase => {
                 let lp = require_downcast_lp::<DropDatabase>(node);
                 let exec = DropDatabaseExec {
@@ -359,12 +456,33 @@ impl ExtensionPlanner for DDLExtensionPlanner {
                 });
                 RuntimeGroupExec::new(runtime, exec)
             }
+            ExtensionType::CopyFrom => {
+                let lp = require_downcast_lp::<CopyFrom>(node);
+                let provider = match &lp.provider {
+                    ProviderReference::RemoteReference(_) => {
+                        unreachable!("required local table, found remote reference to table")
+                    }
+                    other => other.clone(),
+                };
+                let exec = Arc::new(CopyFromExec {
+                    provider,
+                    source: Arc::new(WriteOnlyDataSourceMetricsExecAdapter::new(
+                        physical_inputs.first().unwrap().clone(),
+                    )),
+                    column_mapping: lp.column_mapping.clone(),
+                    column_defaults: lp.column_defaults.clone(),
+                    constraints: lp.constraints.clone(),
+                    max_errors: lp.max_errors,
+                });
+                RuntimeGroupExec::new(lp.runtime_preference, exec)
+            }
             ExtensionType::Update => {
                 let lp = require_downcast_lp::<Update>(node);
                 let exec = UpdateExec {
                     table: lp.table.clone(),
                     updates: lp.updates.clone(),
                     where_expr: lp.where_expr.clone(),
+                    constraints: lp.constraints.clone(),
                 };
                 RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
             }
@@ -383,9 +501,70 @@ impl ExtensionPlanner for DDLExtensionPlanner {
                     source: Arc::new(WriteOnlyDataSourceMetricsExecAdapter::new(
                         physical_inputs.first().unwrap().clone(),
                     )),
+                    constraints: lp.constraints.clone(),
                 });
                 RuntimeGroupExec::new(lp.runtime_preference, exec)
             }
+            ExtensionType::Upsert => {
+                let lp = require_downcast_lp::<Upsert>(node);
+                let exec = UpsertExec {
+                    table: lp.table.clone(),
+                    source: physical_inputs.first().unwrap().clone(),
+                    conflict_columns: lp.conflict_columns.clone(),
+                    updates: lp.updates.clone(),
+                    update_predicate: lp.update_predicate.clone(),
+                    constraints: lp.constraints.clone(),
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
+            ExtensionType::Merge => {
+                let lp = require_downcast_lp::<Merge>(node);
+                let exec = MergeExec {
+                    table: lp.table.clone(),
+                    source: physical_inputs.first().unwrap().clone(),
+                    source_alias: lp.source_alias.clone(),
+                    target_alias: lp.target_alias.clone(),
+                    on: lp.on.clone(),
+                    clauses: lp.clauses.clone(),
+                    constraints: lp.constraints.clone(),
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
+            ExtensionType::RestoreTable => {
+                let lp = require_downcast_lp::<RestoreTable>(node);
+                let exec = RestoreTableExec {
+                    table: lp.table.clone(),
+                    version: lp.version,
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
+            ExtensionType::RefreshMaterializedView => {
+                let lp = require_downcast_lp::<RefreshMaterializedView>(node);
+                let exec = RefreshMaterializedViewExec {
+                    catalog_version: self.catalog.version(),
+                    schema: lp.schema.clone(),
+                    table: lp.table.clone(),
+                    source: physical_inputs.first().unwrap().clone(),
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
+            ExtensionType::VacuumTable => {
+                let lp = require_downcast_lp::<VacuumTable>(node);
+                let exec = VacuumTableExec {
+                    table: lp.table.clone(),
+                    retain_hours: lp.retain_hours,
+                    enforce_retention: lp.enforce_retention,
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
+            ExtensionType::OptimizeTable => {
+                let lp = require_downcast_lp::<OptimizeTable>(node);
+                let exec = OptimizeTableExec {
+                    table: lp.table.clone(),
+                    zorder_by: lp.zorder_by.clone(),
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
             ExtensionType::Delete => {
                 let lp = require_downcast_lp::<Delete>(node);
                 let exec = DeleteExec {
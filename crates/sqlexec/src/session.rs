@@ -4,23 +4,25 @@ use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 use catalog::mutator::CatalogMutator;
 use catalog::session_catalog::SessionCatalog;
-use datafusion::arrow::datatypes::Schema;
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::error::{DataFusionError, Result as DataFusionResult};
 use datafusion::logical_expr::LogicalPlan as DfLogicalPlan;
 use datafusion::physical_plan::empty::EmptyExec;
 use datafusion::physical_plan::{
     execute_stream,
+    EmptyRecordBatchStream,
     ExecutionPlan,
     RecordBatchStream,
     SendableRecordBatchStream,
 };
 use datafusion::physical_planner::{DefaultPhysicalPlanner, PhysicalPlanner};
 use datafusion::scalar::ScalarValue;
-use datafusion_ext::metrics::AggregatedMetrics;
+use datafusion_ext::metrics::{AggregatedMetrics, WriteOnlyDataSourceMetricsExecAdapter};
 use datafusion_ext::session_metrics::{
     BatchStreamWithMetricSender,
     ExecutionStatus,
@@ -38,13 +40,30 @@ use pgrepr::format::Format;
 use pgrepr::notice::{Notice, NoticeSeverity, SqlState};
 use sqlbuiltins::functions::BuiltinScalarUDF;
 use telemetry::Tracker;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 use url::Url;
 use uuid::Uuid;
 
-use crate::context::local::{LocalSessionContext, Portal, PreparedStatement};
+use crate::cancel::{CancelableExec, CancelableStream, QueryCanceler};
+use crate::context::local::{
+    LocalSessionContext,
+    Portal,
+    PortalResults,
+    PreparedStatement,
+    TransactionStatus,
+};
 use crate::environment::EnvironmentReader;
 use crate::errors::{ExecError, Result};
-use crate::planner::logical_plan::{LogicalPlan, OperationInfo, TransactionPlan};
+use crate::planner::logical_plan::{
+    CopyStdioOptions,
+    CopyStdioPlan,
+    LogicalPlan,
+    OperationInfo,
+    TransactionPlan,
+};
+use crate::planner::physical_plan::copy_from::CopyFromExec;
+use crate::planner::physical_plan::copy_stdin::{CopyStdinExec, CopyStdinSink};
 use crate::planner::physical_plan::{
     get_count_from_batch,
     get_operation_from_batch,
@@ -55,6 +74,48 @@ use crate::planner::session_planner::SessionPlanner;
 use crate::remote::client::{RemoteClient, RemoteClientType};
 use crate::remote::planner::{DDLExtensionPlanner, RemotePhysicalPlanner};
 
+/// Number of decoded batches from the client that can be buffered during a
+/// `COPY .. FROM STDIN` before waiting on the copy into the table.
+const COPY_STDIN_BUFFERED_BATCHES: usize = 8;
+
+/// Check if a schema is that of an operation result, returned by plans that
+/// write or modify the catalog.
+fn is_operation_schema(schema: &SchemaRef) -> bool {
+    schema.eq(&GENERIC_OPERATION_PHYSICAL_SCHEMA)
+        || schema.eq(&GENERIC_OPERATION_AND_COUNT_PHYSICAL_SCHEMA)
+}
+
+/// Wrap the inputs of an operation plan so reading them stops once the
+/// statement is canceled.
+///
+/// Nested operations and the metrics adapters writes wrap their inputs in are
+/// descended into, so only the plans producing the rows that get written are
+/// wrapped.
+fn with_cancelable_inputs(
+    plan: Arc<dyn ExecutionPlan>,
+    canceler: &QueryCanceler,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let children = plan.children();
+    if children.is_empty() {
+        return Ok(plan);
+    }
+
+    let children = children
+        .into_iter()
+        .map(|child| {
+            if is_operation_schema(&child.schema())
+                || child.as_any().is::<WriteOnlyDataSourceMetricsExecAdapter>()
+            {
+                with_cancelable_inputs(child, canceler)
+            } else {
+                Ok(Arc::new(CancelableExec::new(child, canceler.clone())) as _)
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(plan.with_new_children(children)?)
+}
+
 static EMPTY_EXEC_PLAN: Lazy<Arc<dyn ExecutionPlan>> =
     Lazy::new(|| Arc::new(EmptyExec::new(Arc::new(Schema::empty()))));
 
@@ -81,8 +142,23 @@ pub enum ExecutionResult {
     DeleteSuccess { deleted_rows: usize },
     /// Data successfully updated.
     UpdateSuccess { updated_rows: usize },
+    /// Data successfully merged.
+    MergeSuccess { merged_rows: usize },
     /// Data successfully copied.
     CopySuccess,
+    /// Client is to send the data for a `COPY .. FROM STDIN`.
+    CopyIn {
+        /// Schema of the rows the client is sending.
+        schema: SchemaRef,
+        options: CopyStdioOptions,
+        /// Sink for the decoded rows.
+        sink: CopyStdinSink,
+    },
+    /// Data to send to the client for a `COPY .. TO STDOUT`.
+    CopyOut {
+        options: CopyStdioOptions,
+        stream: SendableRecordBatchStream,
+    },
     /// Table created.
     CreateTable,
     /// Database created.
@@ -103,6 +179,14 @@ pub enum ExecutionResult {
     AlterDatabase,
     /// A tunnel was altered.
     AlterTunnelRotateKeys,
+    /// A table was restored to a previous version.
+    RestoreTable,
+    /// A table was vacuumed.
+    Vacuum,
+    /// A table was optimized.
+    Optimize,
+    /// A materialized view was refreshed.
+    RefreshMaterializedView,
     /// A client local variable was set.
     Set,
     /// Tables dropped.
@@ -117,6 +201,20 @@ pub enum ExecutionResult {
     DropTunnel,
     /// Credentials are dropped.
     DropCredentials,
+    /// Role created.
+    CreateRole,
+    /// Roles dropped.
+    DropRole,
+    /// Sequence created.
+    CreateSequence,
+    /// Sequences dropped.
+    DropSequences,
+    /// Privileges granted.
+    Grant,
+    /// Privileges revoked.
+    Revoke,
+    /// Comment set on an object.
+    Comment,
 }
 // this just makes the `prepare_statement` method a bit more ergonomic.
 pub struct PrepareStatementArg {
@@ -219,7 +317,10 @@ impl ExecutionResult {
             ExecutionResult::InsertSuccess { .. } => "insert",
             ExecutionResult::DeleteSuccess { .. } => "delete",
             ExecutionResult::UpdateSuccess { .. } => "update",
+            ExecutionResult::MergeSuccess { .. } => "merge",
             ExecutionResult::CopySuccess => "copy",
+            ExecutionResult::CopyIn { .. } => "copy_in",
+            ExecutionResult::CopyOut { .. } => "copy_out",
             ExecutionResult::CreateTable => "create_table",
             ExecutionResult::CreateDatabase => "create_database",
             ExecutionResult::CreateTunnel => "create_tunnel",
@@ -230,6 +331,10 @@ impl ExecutionResult {
             ExecutionResult::AlterTable => "alter_table",
             ExecutionResult::AlterDatabase => "alter_database",
             ExecutionResult::AlterTunnelRotateKeys => "alter_tunnel_rotate_keys",
+            ExecutionResult::RestoreTable => "restore_table",
+            ExecutionResult::Vacuum => "vacuum",
+            ExecutionResult::Optimize => "optimize",
+            ExecutionResult::RefreshMaterializedView => "refresh_materialized_view",
             ExecutionResult::Set => "set_local",
             ExecutionResult::DropTables => "drop_tables",
             ExecutionResult::DropViews => "drop_views",
@@ -237,6 +342,13 @@ impl ExecutionResult {
             ExecutionResult::DropDatabase => "drop_database",
             ExecutionResult::DropTunnel => "drop_tunnel",
             ExecutionResult::DropCredentials => "drop_credentials",
+            ExecutionResult::CreateRole => "create_role",
+            ExecutionResult::DropRole => "drop_role",
+            ExecutionResult::CreateSequence => "create_sequence",
+            ExecutionResult::DropSequences => "drop_sequences",
+            ExecutionResult::Grant => "grant",
+            ExecutionResult::Revoke => "revoke",
+            ExecutionResult::Comment => "comment",
         }
     }
 
@@ -259,6 +371,13 @@ impl ExecutionResult {
                 | ExecutionResult::DropDatabase
                 | ExecutionResult::DropTunnel
                 | ExecutionResult::DropCredentials
+                | ExecutionResult::CreateRole
+                | ExecutionResult::DropRole
+                | ExecutionResult::CreateSequence
+                | ExecutionResult::DropSequences
+                | ExecutionResult::Grant
+                | ExecutionResult::Revoke
+                | ExecutionResult::Comment
         )
     }
 
@@ -280,6 +399,9 @@ impl ExecutionResult {
             "update" => ExecutionResult::UpdateSuccess {
                 updated_rows: count.unwrap_or_default() as usize,
             },
+            "merge" => ExecutionResult::MergeSuccess {
+                merged_rows: count.unwrap_or_default() as usize,
+            },
             "copy" => ExecutionResult::CopySuccess,
             "create_table" => ExecutionResult::CreateTable,
             "create_database" => ExecutionResult::CreateDatabase,
@@ -291,6 +413,10 @@ impl ExecutionResult {
             "alter_table" => ExecutionResult::AlterTable,
             "alter_database" => ExecutionResult::AlterDatabase,
             "alter_tunnel_rotate_keys" => ExecutionResult::AlterTunnelRotateKeys,
+            "restore_table" => ExecutionResult::RestoreTable,
+            "vacuum" => ExecutionResult::Vacuum,
+            "optimize" => ExecutionResult::Optimize,
+            "refresh_materialized_view" => ExecutionResult::RefreshMaterializedView,
             "set" => ExecutionResult::Set,
             "drop_tables" => ExecutionResult::DropTables,
             "drop_views" => ExecutionResult::DropViews,
@@ -298,6 +424,13 @@ impl ExecutionResult {
             "drop_database" => ExecutionResult::DropDatabase,
             "drop_tunnel" => ExecutionResult::DropTunnel,
             "drop_credentials" => ExecutionResult::DropCredentials,
+            "create_role" => ExecutionResult::CreateRole,
+            "drop_role" => ExecutionResult::DropRole,
+            "create_sequence" => ExecutionResult::CreateSequence,
+            "drop_sequences" => ExecutionResult::DropSequences,
+            "grant" => ExecutionResult::Grant,
+            "revoke" => ExecutionResult::Revoke,
+            "comment" => ExecutionResult::Comment,
             _ => return None,
         })
     }
@@ -337,7 +470,16 @@ impl fmt::Display for ExecutionResult {
                     write!(f, "Updated {} rows", updated_rows)
                 }
             }
+            ExecutionResult::MergeSuccess { merged_rows } => {
+                if *merged_rows == 1 {
+                    write!(f, "Merged 1 row")
+                } else {
+                    write!(f, "Merged {} rows", merged_rows)
+                }
+            }
             ExecutionResult::CopySuccess => write!(f, "Copy success"),
+            ExecutionResult::CopyIn { .. } => write!(f, "Copy from client"),
+            ExecutionResult::CopyOut { .. } => write!(f, "Copy to client"),
             ExecutionResult::CreateTable => write!(f, "Table created"),
             ExecutionResult::CreateDatabase => write!(f, "Database created"),
             ExecutionResult::CreateTunnel => write!(f, "Tunnel created"),
@@ -348,6 +490,10 @@ impl fmt::Display for ExecutionResult {
             ExecutionResult::AlterTable => write!(f, "Table altered"),
             ExecutionResult::AlterDatabase => write!(f, "Database altered"),
             ExecutionResult::AlterTunnelRotateKeys => write!(f, "Keys rotated"),
+            ExecutionResult::RestoreTable => write!(f, "Table restored"),
+            ExecutionResult::Vacuum => write!(f, "Table vacuumed"),
+            ExecutionResult::Optimize => write!(f, "Table optimized"),
+            ExecutionResult::RefreshMaterializedView => write!(f, "Materialized view refreshed"),
             ExecutionResult::Set => write!(f, "Local variable set"),
             ExecutionResult::DropTables => write!(f, "Table(s) dropped"),
             ExecutionResult::DropViews => write!(f, "View(s) dropped"),
@@ -355,6 +501,13 @@ impl fmt::Display for ExecutionResult {
             ExecutionResult::DropDatabase => write!(f, "Database(s) dropped"),
             ExecutionResult::DropTunnel => write!(f, "Tunnel(s) dropped"),
             ExecutionResult::DropCredentials => write!(f, "Credentials dropped"),
+            ExecutionResult::CreateRole => write!(f, "Role created"),
+            ExecutionResult::DropRole => write!(f, "Role(s) dropped"),
+            ExecutionResult::CreateSequence => write!(f, "Sequence created"),
+            ExecutionResult::DropSequences => write!(f, "Sequence(s) dropped"),
+            ExecutionResult::Grant => write!(f, "Privileges granted"),
+            ExecutionResult::Revoke => write!(f, "Privileges revoked"),
+            ExecutionResult::Comment => write!(f, "Comment set"),
         }
     }
 }
@@ -390,6 +543,8 @@ impl RecordBatchStream for StreamAndFirstResult {
 /// in the future (e.g. consensus).
 pub struct Session {
     pub(crate) ctx: LocalSessionContext,
+    /// Cancels the query currently running in this session.
+    canceler: QueryCanceler,
 }
 
 impl Session {
@@ -424,7 +579,26 @@ impl Session {
             task_scheduler,
         )?;
 
-        Ok(Session { ctx })
+        Ok(Session {
+            ctx,
+            canceler: QueryCanceler::default(),
+        })
+    }
+
+    /// Mark the start of a new statement.
+    ///
+    /// Resets any previous cancel and starts the statement timeout if one is
+    /// configured. Streams returned from executing physical plans error once
+    /// the statement is canceled or times out.
+    pub fn start_statement(&self) {
+        let timeout = self.get_session_vars().statement_timeout();
+        let timeout = (timeout > 0).then(|| Duration::from_millis(timeout as u64));
+        self.canceler.start_query(timeout);
+    }
+
+    /// Get the canceler for queries running in this session.
+    pub fn query_canceler(&self) -> QueryCanceler {
+        self.canceler.clone()
     }
 
     pub async fn register_function(&mut self, udf: Arc<dyn BuiltinScalarUDF>) -> Result<()> {
@@ -503,11 +677,41 @@ impl Session {
         }
     }
 
+    /// Create a physical plan, giving up as soon as the current statement is
+    /// canceled.
+    ///
+    /// Planning doesn't change anything, so it's always safe to stop.
+    async fn create_cancelable_physical_plan(
+        &self,
+        plan: DfLogicalPlan,
+        op: &OperationInfo,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let canceler = self.canceler.clone();
+        tokio::select! {
+            plan = self.create_physical_plan(plan, op) => plan,
+            e = canceler.canceled() => Err(e),
+        }
+    }
+
     /// Execute a datafusion physical plan.
+    ///
+    /// Queries are stopped as soon as they're canceled. Plans that write or
+    /// modify the catalog (anything returning an operation result) can't be
+    /// safely stopped partway through, so they're checked for a cancel before
+    /// they start, and then only stopped while reading their inputs. Once
+    /// they've read everything, they run to completion.
     pub async fn execute_physical_plan(
         &self,
         plan: Arc<dyn ExecutionPlan>,
     ) -> Result<SendableRecordBatchStream> {
+        let cancelable = !is_operation_schema(&plan.schema());
+        let plan = if cancelable {
+            plan
+        } else {
+            self.canceler.check()?;
+            with_cancelable_inputs(plan, &self.canceler)?
+        };
+
         let context = self.ctx.task_context();
         let stream = if self.ctx.get_session_vars().enable_experimental_scheduler() {
             let scheduler = self.ctx.get_task_scheduler();
@@ -520,13 +724,24 @@ impl Session {
                 errors: sink,
             };
 
-            scheduler.schedule(plan, context, output)?;
+            let cancel = if cancelable {
+                self.canceler.current()
+            } else {
+                CancellationToken::new()
+            };
+            scheduler.schedule(plan, context, output, cancel)?;
             Box::pin(stream)
         } else {
             execute_stream(plan, context)?
         };
 
-        Ok(stream)
+        if !cancelable {
+            return Ok(stream);
+        }
+        Ok(Box::pin(CancelableStream::new(
+            stream,
+            self.canceler.clone(),
+        )))
     }
 
     pub fn get_session_vars(&self) -> SessionVars {
@@ -546,10 +761,16 @@ impl Session {
     }
 
     /// Like 'prepare_statement', but for a portal.
+    ///
+    /// Statements with parameters are only prepared, the portal can't be bound
+    /// until values for the parameters are provided.
     pub async fn prepare_portal(&mut self, portal_id: &str, query: &str) -> Result<()> {
         self.prepare_statement(portal_id.to_string(), query, Vec::new())
             .await?;
         let prepared = self.get_prepared_statement(portal_id)?;
+        if !prepared.parameters().is_empty() {
+            return Ok(());
+        }
 
         let num_fields = prepared.output_fields().map(|f| f.len()).unwrap_or(0);
         self.bind_statement(
@@ -577,6 +798,17 @@ impl Session {
         self.ctx.remove_portal(name);
     }
 
+    /// Suspend a portal, storing the results that have yet to be sent for the
+    /// next execute of the portal.
+    pub fn suspend_portal(&mut self, name: &str, stream: SendableRecordBatchStream) -> Result<()> {
+        self.ctx.suspend_portal(name, stream)
+    }
+
+    /// Mark a portal as having sent all of its results.
+    pub fn complete_portal(&mut self, name: &str) -> Result<()> {
+        self.ctx.complete_portal(name)
+    }
+
     pub fn take_notices(&mut self) -> Vec<Notice> {
         self.ctx.take_notices()
     }
@@ -585,6 +817,80 @@ impl Session {
         self.ctx.push_notice(notice)
     }
 
+    /// Get the status of the session's transaction block.
+    pub fn transaction_status(&self) -> TransactionStatus {
+        self.ctx.transaction_status()
+    }
+
+    /// Mark the current transaction block (if any) as failed.
+    ///
+    /// Should be called whenever an error is returned to the client.
+    pub fn fail_transaction(&mut self) {
+        self.ctx.fail_transaction()
+    }
+
+    /// Rollback the current transaction block if there is one.
+    ///
+    /// Should be called when the client disconnects to clean up any staged
+    /// writes.
+    pub async fn close_transaction(&mut self) -> Result<()> {
+        if self.ctx.transaction_status() != TransactionStatus::Idle {
+            self.ctx.rollback_transaction().await?;
+        }
+        Ok(())
+    }
+
+    /// Execute a transaction control statement.
+    async fn execute_transaction_plan(&mut self, plan: TransactionPlan) -> Result<ExecutionResult> {
+        let status = self.ctx.transaction_status();
+        let result = match plan {
+            TransactionPlan::Begin => {
+                if status == TransactionStatus::Idle {
+                    self.ctx.begin_transaction().await?;
+                } else {
+                    self.ctx.push_notice(Notice {
+                        severity: NoticeSeverity::Warning,
+                        code: SqlState::ActiveSqlTransaction,
+                        message: "there is already a transaction in progress".to_string(),
+                    });
+                }
+                ExecutionResult::Begin
+            }
+            TransactionPlan::Commit => match status {
+                TransactionStatus::InBlock => {
+                    self.ctx.commit_transaction().await?;
+                    ExecutionResult::Commit
+                }
+                TransactionStatus::Failed => {
+                    // Committing a failed transaction rolls it back instead.
+                    self.ctx.rollback_transaction().await?;
+                    ExecutionResult::Rollback
+                }
+                TransactionStatus::Idle => {
+                    self.push_no_transaction_notice();
+                    ExecutionResult::Commit
+                }
+            },
+            TransactionPlan::Abort => {
+                if status == TransactionStatus::Idle {
+                    self.push_no_transaction_notice();
+                } else {
+                    self.ctx.rollback_transaction().await?;
+                }
+                ExecutionResult::Rollback
+            }
+        };
+        Ok(result)
+    }
+
+    fn push_no_transaction_notice(&mut self) {
+        self.ctx.push_notice(Notice {
+            severity: NoticeSeverity::Warning,
+            code: SqlState::NoActiveSqlTransaction,
+            message: "there is no transaction in progress".to_string(),
+        });
+    }
+
     /// Bind the parameters of a prepared statement to the given values.
     ///
     /// If successful, the bound statement will create a portal which can be
@@ -606,14 +912,29 @@ impl Session {
         plan: LogicalPlan,
         op: &OperationInfo,
     ) -> Result<(Arc<dyn ExecutionPlan>, ExecutionResult)> {
-        // Note that transaction support is fake, in that we don't currently do
-        // anything and do not provide any transactional semantics.
-        //
-        // We stub out transaction commands since many tools (even BI ones) will
-        // try to open a transaction for some queries.
+        // Once a statement in a transaction block fails, everything up until
+        // the end of the block is rejected.
+        if self.ctx.transaction_status() == TransactionStatus::Failed
+            && !matches!(
+                plan,
+                LogicalPlan::Transaction(TransactionPlan::Commit | TransactionPlan::Abort)
+            )
+        {
+            return Err(ExecError::InFailedTransaction);
+        }
+
         match plan {
             LogicalPlan::Noop => Ok((EMPTY_EXEC_PLAN.clone(), ExecutionResult::EmptyQuery)),
+            LogicalPlan::Transaction(plan) if self.ctx.exec_client().is_none() => {
+                let result = self.execute_transaction_plan(plan).await?;
+                Ok((EMPTY_EXEC_PLAN.clone(), result))
+            }
             LogicalPlan::Transaction(plan) => {
+                // Writes for remote sessions happen on the remote node, and
+                // don't currently provide any transactional semantics.
+                //
+                // We stub out transaction commands since many tools (even BI
+                // ones) will try to open a transaction for some queries.
                 // Push a notice to let the user know about our current
                 // transaction handling.
                 self.ctx.push_notice(Notice{
@@ -631,8 +952,9 @@ impl Session {
                     },
                 ))
             }
+            LogicalPlan::CopyStdio(plan) => self.execute_copy_stdio_plan(plan, op).await,
             LogicalPlan::Datafusion(plan) => {
-                let physical = self.create_physical_plan(plan, op).await?;
+                let physical = self.create_cancelable_physical_plan(plan, op).await?;
                 let stream = self.execute_physical_plan(physical.clone()).await?;
 
                 let stream = ExecutionResult::from_stream(stream).await;
@@ -673,15 +995,98 @@ impl Session {
         }
     }
 
+    /// Start executing a COPY to or from the client.
+    ///
+    /// The returned result needs to be driven by the caller, either by
+    /// sending rows into the sink for `COPY .. FROM STDIN`, or by reading the
+    /// stream for `COPY .. TO STDOUT`.
+    async fn execute_copy_stdio_plan(
+        &mut self,
+        plan: CopyStdioPlan,
+        op: &OperationInfo,
+    ) -> Result<(Arc<dyn ExecutionPlan>, ExecutionResult)> {
+        match plan {
+            CopyStdioPlan::From {
+                provider,
+                schema,
+                column_mapping,
+                column_defaults,
+                constraints,
+                max_errors,
+                options,
+            } => {
+                let (sender, receiver) = mpsc::channel(COPY_STDIN_BUFFERED_BATCHES);
+                let source = Arc::new(CopyStdinExec::new(
+                    schema.clone(),
+                    receiver,
+                    self.canceler.clone(),
+                ));
+                let physical: Arc<dyn ExecutionPlan> = Arc::new(CopyFromExec {
+                    provider,
+                    source: Arc::new(WriteOnlyDataSourceMetricsExecAdapter::new(source)),
+                    column_mapping,
+                    column_defaults,
+                    constraints,
+                    max_errors,
+                });
+
+                let mut stream = self.execute_physical_plan(physical.clone()).await?;
+                // The copy runs in the background while the client is sending
+                // rows.
+                let handle = tokio::spawn(async move {
+                    let mut count = 0;
+                    while let Some(batch) = stream.next().await {
+                        count += get_count_from_batch(&batch?).unwrap_or_default();
+                    }
+                    Ok::<_, DataFusionError>(count)
+                });
+
+                Ok((
+                    physical,
+                    ExecutionResult::CopyIn {
+                        schema,
+                        options,
+                        sink: CopyStdinSink::new(sender, handle),
+                    },
+                ))
+            }
+            CopyStdioPlan::To { source, options } => {
+                let physical = self.create_cancelable_physical_plan(source, op).await?;
+                let stream = self.execute_physical_plan(physical.clone()).await?;
+                Ok((physical, ExecutionResult::CopyOut { options, stream }))
+            }
+        }
+    }
+
     /// Execute a portal.
     ///
     /// This will handle metrics tracking for query executions.
-    // TODO: Handle max rows.
-    pub async fn execute_portal(
-        &mut self,
-        portal_name: &str,
-        _max_rows: i32,
-    ) -> Result<ExecutionResult> {
+    ///
+    /// If the portal was suspended by a previous execute, the remaining results
+    /// are returned instead of executing the portal again. Portals that have
+    /// already returned all of their results return no rows. Callers limiting
+    /// the number of rows sent are responsible for suspending the portal with
+    /// whatever results are left, and for completing the portal once all
+    /// results have been sent.
+    pub async fn execute_portal(&mut self, portal_name: &str) -> Result<ExecutionResult> {
+        self.start_statement();
+
+        match self.ctx.take_portal_results(portal_name) {
+            Some(PortalResults::Suspended(stream)) => return Ok(ExecutionResult::Query { stream }),
+            Some(PortalResults::Exhausted) => {
+                let schema = self
+                    .ctx
+                    .get_portal(portal_name)?
+                    .output_schema()
+                    .cloned()
+                    .unwrap_or_else(Schema::empty);
+                return Ok(ExecutionResult::Query {
+                    stream: Box::pin(EmptyRecordBatchStream::new(Arc::new(schema))),
+                });
+            }
+            None => (),
+        }
+
         let portal = self.ctx.get_portal(portal_name)?;
 
         let plan = match &portal.stmt.plan {
@@ -700,9 +1105,14 @@ impl Session {
             ..Default::default()
         };
 
-        let stream = match self.execute_logical_plan(plan, &op).await {
+        // Cancellation is handled within execution so that transaction control
+        // statements, DDL, and writes are never dropped partway through.
+        let result = self.execute_logical_plan(plan, &op).await;
+
+        let stream = match result {
             Ok((plan, result)) => match result {
                 ExecutionResult::Error(e) => {
+                    self.ctx.fail_transaction();
                     metrics.execution_status = ExecutionStatus::Fail;
                     metrics.error_message = Some(e.to_string());
                     self.ctx.get_metrics_handler().push_metric(metrics);
@@ -744,6 +1154,7 @@ impl Session {
                 }
             },
             Err(e) => {
+                self.ctx.fail_transaction();
                 metrics.execution_status = ExecutionStatus::Fail;
                 metrics.error_message = Some(e.to_string());
 
@@ -824,6 +1235,7 @@ impl Session {
     /// Execute a SQL query.
     /// if the query doesn't contain exactly one statement, an error is returned.
     pub async fn execute_sql(&mut self, query: &str) -> Result<SendableRecordBatchStream> {
+        self.start_statement();
         let plan = self.create_logical_plan(query).await?;
         let plan = plan.try_into_datafusion_plan()?;
         let plan = self